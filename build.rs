@@ -0,0 +1,46 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Зашивает в бинарник коммит и время сборки: `LOCALHOOD_GIT_SHA`, `LOCALHOOD_BUILD_TIMESTAMP`
+fn main() {
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(git_head)
+        .unwrap_or_else(|| "unknown".to_string());
+
+    let build_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    println!("cargo:rustc-env=LOCALHOOD_GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=LOCALHOOD_BUILD_TIMESTAMP={}", build_timestamp);
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+
+    // Пересобираем при смене коммита; без .git (например, в Docker) коммит берётся из GIT_SHA
+    let head = Path::new(".git/HEAD");
+    if head.exists() {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Some(reference) = std::fs::read_to_string(head)
+            .ok()
+            .and_then(|head| head.strip_prefix("ref: ").map(|r| r.trim().to_string()))
+        {
+            if Path::new(".git").join(&reference).exists() {
+                println!("cargo:rerun-if-changed=.git/{}", reference);
+            }
+        }
+    } else {
+        println!("cargo:rerun-if-changed=build.rs");
+    }
+}
+
+fn git_head() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "HEAD"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok().map(|sha| sha.trim().to_string())
+}
@@ -0,0 +1,32 @@
+-- Типы фоновых задач
+CREATE TYPE job_type AS ENUM ('notification_fanout');
+
+-- Статус фоновой задачи
+CREATE TYPE job_status AS ENUM ('pending', 'running', 'completed', 'dead');
+
+-- Очередь фоновых задач
+CREATE TABLE jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    job_type job_type NOT NULL,
+    payload JSONB NOT NULL DEFAULT '{}',
+
+    status job_status NOT NULL DEFAULT 'pending',
+
+    -- Повторы
+    attempts INT NOT NULL DEFAULT 0,
+    max_attempts INT NOT NULL DEFAULT 5,
+    last_error TEXT,
+
+    -- Планирование и блокировка
+    run_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    locked_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_jobs_pending ON jobs(run_at) WHERE status = 'pending';
+CREATE INDEX idx_jobs_status ON jobs(status);
+CREATE INDEX idx_jobs_type ON jobs(job_type);
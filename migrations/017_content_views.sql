@@ -0,0 +1,21 @@
+-- Уникальные просмотры контента (один пользователь — один просмотр в день)
+CREATE TABLE content_views (
+    entity_type VARCHAR(30) NOT NULL,  -- announcement, listing
+    entity_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    view_date DATE NOT NULL DEFAULT CURRENT_DATE,
+
+    -- Сколько раз пользователь открывал контент за день
+    views INT NOT NULL DEFAULT 1,
+
+    PRIMARY KEY (entity_type, entity_id, user_id, view_date)
+);
+
+CREATE INDEX idx_content_views_entity ON content_views(entity_type, entity_id);
+
+-- views_count теперь считает только уникальные просмотры, общее число — отдельно
+ALTER TABLE announcements ADD COLUMN total_views_count INT DEFAULT 0;
+ALTER TABLE marketplace_listings ADD COLUMN total_views_count INT DEFAULT 0;
+
+UPDATE announcements SET total_views_count = views_count;
+UPDATE marketplace_listings SET total_views_count = views_count;
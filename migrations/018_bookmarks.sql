@@ -0,0 +1,18 @@
+-- Тип сохраняемого контента
+CREATE TYPE bookmark_entity_type AS ENUM ('listing', 'announcement', 'document', 'voting');
+
+-- Закладки пользователя (объявления маркетплейса хранятся в listing_favorites)
+CREATE TABLE bookmarks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    entity_type bookmark_entity_type NOT NULL,
+    entity_id UUID NOT NULL,
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(user_id, entity_type, entity_id)
+);
+
+CREATE INDEX idx_bookmarks_user ON bookmarks(user_id);
+CREATE INDEX idx_bookmarks_entity ON bookmarks(entity_type, entity_id);
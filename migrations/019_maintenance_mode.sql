@@ -0,0 +1,4 @@
+-- Режим технического обслуживания API
+INSERT INTO system_settings (key, value, description) VALUES
+('maintenance_mode', '{"enabled": false, "message": null, "starts_at": null, "ends_at": null}', 'Режим технического обслуживания (503 для всех, кроме администраторов)')
+ON CONFLICT (key) DO NOTHING;
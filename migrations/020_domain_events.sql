@@ -0,0 +1,34 @@
+-- Типы доменных событий
+CREATE TYPE domain_event_type AS ENUM (
+    'osi_updated',
+    'council_member_added',
+    'council_member_removed',
+    'worker_added',
+    'worker_updated',
+    'worker_removed',
+    'document_uploaded',
+    'announcement_published'
+);
+
+-- Журнал доменных событий (действия в рамках ЖК/ОСИ)
+CREATE TABLE domain_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    osi_id UUID REFERENCES osi(id) ON DELETE CASCADE,
+
+    -- Кто совершил действие
+    actor_id UUID REFERENCES users(id),
+
+    event_type domain_event_type NOT NULL,
+    entity_type VARCHAR(50),
+    entity_id UUID,
+
+    data JSONB,
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_domain_events_osi ON domain_events(osi_id, created_at);
+CREATE INDEX idx_domain_events_complex ON domain_events(complex_id, created_at);
+CREATE INDEX idx_domain_events_type ON domain_events(event_type);
+CREATE INDEX idx_domain_events_actor ON domain_events(actor_id);
@@ -0,0 +1,87 @@
+-- Категории расходов ОСИ (osi_id NULL — общие категории)
+CREATE TABLE expense_categories (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    osi_id UUID REFERENCES osi(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    slug VARCHAR(100) NOT NULL,
+    sort_order INT DEFAULT 0,
+    is_active BOOLEAN DEFAULT true,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_expense_categories_osi ON expense_categories(osi_id);
+
+INSERT INTO expense_categories (name, slug, sort_order) VALUES
+('Уборка', 'cleaning', 1),
+('Ремонт', 'repair', 2),
+('Коммунальные услуги', 'utilities', 3),
+('Заработная плата', 'salary', 4),
+('Охрана', 'security', 5),
+('Лифт', 'elevator', 6),
+('Благоустройство', 'landscaping', 7),
+('Банковские услуги', 'bank', 8),
+('Прочее', 'other', 9);
+
+-- Статус расхода
+CREATE TYPE expense_status AS ENUM ('pending_approval', 'approved', 'rejected', 'executed', 'cancelled');
+
+-- Расходы ОСИ
+CREATE TABLE osi_expenses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    osi_id UUID NOT NULL REFERENCES osi(id) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES expense_categories(id),
+
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    vendor VARCHAR(200),
+    expense_date DATE NOT NULL DEFAULT CURRENT_DATE,
+
+    -- Подтверждающий документ
+    receipt_url TEXT,
+
+    status expense_status NOT NULL DEFAULT 'approved',
+
+    -- Согласование советом дома
+    required_approvals INT NOT NULL DEFAULT 0,
+
+    created_by UUID NOT NULL REFERENCES users(id),
+    executed_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_osi_expenses_osi ON osi_expenses(osi_id);
+CREATE INDEX idx_osi_expenses_category ON osi_expenses(category_id);
+CREATE INDEX idx_osi_expenses_status ON osi_expenses(status);
+CREATE INDEX idx_osi_expenses_date ON osi_expenses(expense_date);
+
+-- Голоса членов совета по расходам
+CREATE TYPE expense_decision AS ENUM ('approve', 'reject');
+
+CREATE TABLE expense_approvals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    expense_id UUID NOT NULL REFERENCES osi_expenses(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id),
+
+    decision expense_decision NOT NULL,
+    comment TEXT,
+
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+
+    UNIQUE(expense_id, user_id)
+);
+
+CREATE INDEX idx_expense_approvals_expense ON expense_approvals(expense_id);
+
+-- Порог согласования расходов для ОСИ
+ALTER TABLE osi ADD COLUMN expense_approval_threshold DECIMAL(12, 2);
+ALTER TABLE osi ADD COLUMN expense_required_approvals INT NOT NULL DEFAULT 2;
+
+-- Новые типы уведомлений и событий
+ALTER TYPE notification_type ADD VALUE 'finance';
+ALTER TYPE domain_event_type ADD VALUE 'expense_created';
+ALTER TYPE domain_event_type ADD VALUE 'expense_approved';
+ALTER TYPE domain_event_type ADD VALUE 'expense_rejected';
+ALTER TYPE domain_event_type ADD VALUE 'expense_executed';
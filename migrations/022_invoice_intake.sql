@@ -0,0 +1,11 @@
+-- Расходы, созданные из фото счёта, ждут подтверждения председателем
+ALTER TYPE expense_status ADD VALUE 'pending_confirmation' BEFORE 'pending_approval';
+
+-- Сумма распознаётся не всегда — черновик может быть без неё
+ALTER TABLE osi_expenses DROP CONSTRAINT osi_expenses_amount_check;
+ALTER TABLE osi_expenses ADD CONSTRAINT osi_expenses_amount_check CHECK (amount >= 0);
+
+-- Результат распознавания счёта
+ALTER TABLE osi_expenses ADD COLUMN ocr_data JSONB;
+
+CREATE INDEX idx_osi_expenses_vendor ON osi_expenses(osi_id, lower(vendor));
@@ -0,0 +1,30 @@
+-- Наличные платежи, принятые председателем ОСИ
+ALTER TABLE payments ADD COLUMN osi_id UUID REFERENCES osi(id);
+ALTER TABLE payments ADD COLUMN received_by UUID REFERENCES users(id);
+ALTER TABLE payments ADD COLUMN receipt_number VARCHAR(30);
+ALTER TABLE payments ADD COLUMN verification_token VARCHAR(64) UNIQUE;
+ALTER TABLE payments ADD COLUMN comment TEXT;
+
+ALTER TABLE payments ADD CONSTRAINT payments_osi_receipt_unique UNIQUE (osi_id, receipt_number);
+
+CREATE INDEX idx_payments_osi ON payments(osi_id);
+
+-- Сквозная нумерация квитанций в пределах ОСИ и года
+CREATE TABLE receipt_counters (
+    osi_id UUID NOT NULL REFERENCES osi(id) ON DELETE CASCADE,
+    year INT NOT NULL,
+    last_number INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (osi_id, year)
+);
+
+-- Распределение платежа по счетам квартиры
+CREATE TABLE payment_allocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payment_id UUID NOT NULL REFERENCES payments(id) ON DELETE CASCADE,
+    bill_id UUID NOT NULL REFERENCES bills(id),
+    amount DECIMAL(12, 2) NOT NULL CHECK (amount > 0),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_payment_allocations_payment ON payment_allocations(payment_id);
+CREATE INDEX idx_payment_allocations_bill ON payment_allocations(bill_id);
@@ -0,0 +1,50 @@
+-- Правило распределения общих расходов
+CREATE TYPE allocation_rule AS ENUM ('by_area', 'per_unit');
+
+-- Статус общего начисления
+CREATE TYPE shared_charge_status AS ENUM ('scheduled', 'billed', 'cancelled');
+
+-- Общие начисления, распределяемые по квартирам (например, ремонт лифта)
+CREATE TABLE shared_charges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    osi_id UUID NOT NULL REFERENCES osi(id) ON DELETE CASCADE,
+    expense_id UUID REFERENCES osi_expenses(id),
+
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    total_amount DECIMAL(12, 2) NOT NULL CHECK (total_amount > 0),
+    utility_type utility_type NOT NULL DEFAULT 'other',
+
+    rule allocation_rule NOT NULL,
+    -- Ограничение по корпусу (NULL — весь ЖК)
+    building VARCHAR(20),
+    -- Сумма базы распределения (площадь или число квартир)
+    total_basis DECIMAL(14, 2) NOT NULL,
+
+    -- Расчётный период, в счёт которого попадут начисления
+    billing_period DATE NOT NULL,
+    status shared_charge_status NOT NULL DEFAULT 'scheduled',
+    billed_at TIMESTAMPTZ,
+
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_shared_charges_osi ON shared_charges(osi_id);
+
+-- Доля каждой квартиры в общем начислении
+CREATE TABLE shared_charge_allocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    charge_id UUID NOT NULL REFERENCES shared_charges(id) ON DELETE CASCADE,
+    apartment_id UUID NOT NULL REFERENCES apartments(id),
+
+    basis DECIMAL(12, 2) NOT NULL,
+    amount DECIMAL(12, 2) NOT NULL,
+    bill_item_id UUID REFERENCES bill_items(id),
+
+    UNIQUE(charge_id, apartment_id)
+);
+
+CREATE INDEX idx_shared_charge_allocations_apartment ON shared_charge_allocations(apartment_id);
+
+ALTER TYPE job_type ADD VALUE 'shared_charge_billing';
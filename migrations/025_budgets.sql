@@ -0,0 +1,34 @@
+-- Статус годового бюджета ОСИ
+CREATE TYPE budget_status AS ENUM ('draft', 'voting', 'approved', 'rejected');
+
+-- Годовой бюджет ОСИ
+CREATE TABLE osi_budgets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    osi_id UUID NOT NULL REFERENCES osi(id) ON DELETE CASCADE,
+    year INT NOT NULL,
+    title VARCHAR(200) NOT NULL,
+
+    status budget_status NOT NULL DEFAULT 'draft',
+    -- Голосование собственников за утверждение бюджета
+    voting_id UUID REFERENCES votings(id),
+    approved_at TIMESTAMPTZ,
+
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+-- Отклонённый бюджет можно пересоставить, остальные — один на год
+CREATE UNIQUE INDEX idx_osi_budgets_year ON osi_budgets(osi_id, year) WHERE status <> 'rejected';
+CREATE INDEX idx_osi_budgets_voting ON osi_budgets(voting_id);
+
+-- Статьи бюджета
+CREATE TABLE budget_lines (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    budget_id UUID NOT NULL REFERENCES osi_budgets(id) ON DELETE CASCADE,
+    category_id UUID NOT NULL REFERENCES expense_categories(id),
+    planned_amount DECIMAL(12, 2) NOT NULL CHECK (planned_amount >= 0),
+    note TEXT,
+
+    UNIQUE(budget_id, category_id)
+);
@@ -0,0 +1,41 @@
+-- Тип шаблона
+CREATE TYPE template_kind AS ENUM ('voting', 'announcement');
+
+-- Шаблоны голосований и объявлений (complex_id NULL — системные)
+CREATE TABLE content_templates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID REFERENCES complexes(id) ON DELETE CASCADE,
+    kind template_kind NOT NULL,
+
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    -- Варианты ответа для голосований
+    options JSONB,
+    -- Категория для объявлений
+    category announcement_category,
+
+    created_by UUID REFERENCES users(id),
+    is_active BOOLEAN DEFAULT true,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_content_templates_complex ON content_templates(complex_id);
+CREATE INDEX idx_content_templates_kind ON content_templates(kind);
+
+INSERT INTO content_templates (kind, title, body, options, category) VALUES
+('voting', 'Изменение тарифа на содержание {{complex_name}}',
+ 'Предлагается установить тариф на содержание общего имущества {{tariff}} ₸ за м² с {{start_date}}. Обоснование: {{reason}}.',
+ '["За", "Против", "Воздержался"]', NULL),
+('voting', 'Выборы председателя ОСИ {{complex_name}}',
+ 'Проводится голосование по избранию председателя ОСИ сроком на {{term}}. Кандидат: {{candidate}}.',
+ '["За", "Против", "Воздержался"]', NULL),
+('announcement', 'Плановое отключение воды',
+ 'Уважаемые жители {{complex_name}}! {{date}} с {{time_from}} до {{time_to}} будет отключена {{water_type}} вода в связи с {{reason}}.',
+ NULL, 'maintenance'),
+('announcement', 'Общее собрание собственников',
+ 'Уважаемые собственники {{complex_name}}! {{date}} в {{time}} состоится общее собрание по адресу: {{place}}. Повестка: {{agenda}}.',
+ NULL, 'event'),
+('announcement', 'Напоминание об оплате',
+ 'Уважаемые жители {{complex_name}}! Напоминаем, что оплату за {{period}} необходимо внести до {{due_date}}.',
+ NULL, 'financial');
@@ -0,0 +1,19 @@
+-- Выборы председателя как отдельный тип голосования
+ALTER TYPE voting_type ADD VALUE IF NOT EXISTS 'election';
+
+-- Итог выборов
+CREATE TYPE election_outcome AS ENUM ('elected', 'tie', 'no_candidates', 'no_votes');
+
+ALTER TABLE votings
+    ADD COLUMN nomination_ends_at TIMESTAMPTZ,
+    ADD COLUMN winner_option_id UUID REFERENCES voting_options(id) ON DELETE SET NULL,
+    ADD COLUMN election_outcome election_outcome;
+
+-- Кандидаты — это варианты ответа с привязкой к пользователю
+ALTER TABLE voting_options
+    ADD COLUMN candidate_id UUID REFERENCES users(id) ON DELETE CASCADE,
+    ADD COLUMN candidate_bio TEXT,
+    ADD COLUMN candidate_photo_url TEXT;
+
+CREATE UNIQUE INDEX idx_voting_options_candidate
+    ON voting_options(voting_id, candidate_id) WHERE candidate_id IS NOT NULL;
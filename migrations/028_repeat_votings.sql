@@ -0,0 +1,5 @@
+-- Повторное голосование при отсутствии кворума
+ALTER TABLE votings ADD COLUMN repeat_of_id UUID REFERENCES votings(id) ON DELETE SET NULL;
+
+-- У голосования может быть только одно повторное
+CREATE UNIQUE INDEX idx_votings_repeat_of ON votings(repeat_of_id) WHERE repeat_of_id IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Индексы для keyset-пагинации по (created_at, id)
+CREATE INDEX idx_chat_messages_chat_keyset ON chat_messages(chat_id, created_at DESC, id DESC);
+CREATE INDEX idx_barrier_logs_complex_keyset ON barrier_access_logs(complex_id, created_at DESC, id DESC);
+CREATE INDEX idx_notifications_user_keyset ON notifications(user_id, created_at DESC, id DESC);
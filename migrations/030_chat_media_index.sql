@@ -0,0 +1,3 @@
+-- Галерея вложений чата
+CREATE INDEX idx_chat_messages_media ON chat_messages(chat_id, created_at DESC, id DESC)
+    WHERE attachment_url IS NOT NULL AND is_deleted = false;
@@ -0,0 +1,3 @@
+-- Подсчёт непрочитанных: поиск отметки о прочтении по (message_id, user_id) покрыт UNIQUE,
+-- а выборка сообщений чата от других участников — этим индексом
+CREATE INDEX idx_chat_messages_chat_sender ON chat_messages(chat_id, sender_id);
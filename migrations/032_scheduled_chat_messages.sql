@@ -0,0 +1,24 @@
+-- Отложенные сообщения в чатах ЖК
+CREATE TYPE scheduled_message_status AS ENUM ('pending', 'sent', 'cancelled');
+
+CREATE TABLE scheduled_chat_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    chat_id UUID NOT NULL REFERENCES chats(id) ON DELETE CASCADE,
+    sender_id UUID NOT NULL REFERENCES users(id),
+
+    content TEXT NOT NULL,
+    attachment_url TEXT,
+    attachment_type VARCHAR(20),
+
+    send_at TIMESTAMPTZ NOT NULL,
+    status scheduled_message_status NOT NULL DEFAULT 'pending',
+
+    -- Отправленное сообщение
+    message_id UUID REFERENCES chat_messages(id) ON DELETE SET NULL,
+    sent_at TIMESTAMPTZ,
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_scheduled_chat_messages_chat ON scheduled_chat_messages(chat_id);
+CREATE INDEX idx_scheduled_chat_messages_due ON scheduled_chat_messages(send_at) WHERE status = 'pending';
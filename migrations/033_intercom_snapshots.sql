@@ -0,0 +1,9 @@
+-- Архив снимков домофона по квартирам
+ALTER TABLE intercom_calls
+    ADD COLUMN snapshot_delete_requested_at TIMESTAMPTZ,
+    ADD COLUMN snapshot_purged_at TIMESTAMPTZ;
+
+-- Галерея квартиры: новые снимки первыми
+CREATE INDEX idx_intercom_calls_apartment_snapshots
+    ON intercom_calls(apartment_id, created_at DESC, id DESC)
+    WHERE snapshot_url IS NOT NULL;
@@ -0,0 +1,25 @@
+-- Журнал привилегированных действий
+CREATE TABLE audit_logs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+
+    actor_id UUID REFERENCES users(id),
+    complex_id UUID REFERENCES complexes(id),
+
+    action VARCHAR(100) NOT NULL,
+    entity_type VARCHAR(50) NOT NULL,
+    entity_id UUID,
+
+    old_value JSONB,
+    new_value JSONB,
+    -- Изменившиеся поля: {"поле": {"old": ..., "new": ...}}
+    diff JSONB,
+
+    request_id VARCHAR(64),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_logs_actor ON audit_logs(actor_id);
+CREATE INDEX idx_audit_logs_complex ON audit_logs(complex_id, created_at DESC);
+CREATE INDEX idx_audit_logs_entity ON audit_logs(entity_type, entity_id);
+CREATE INDEX idx_audit_logs_created ON audit_logs(created_at DESC);
@@ -0,0 +1,26 @@
+-- Сотрудник ОСИ, у которого есть аккаунт в приложении (по номеру телефона)
+ALTER TABLE osi_workers ADD COLUMN user_id UUID REFERENCES users(id) ON DELETE SET NULL;
+
+UPDATE osi_workers w SET user_id = u.id
+FROM users u
+WHERE u.phone = w.phone AND w.user_id IS NULL;
+
+CREATE INDEX idx_osi_workers_user ON osi_workers(user_id) WHERE user_id IS NOT NULL;
+
+-- Ручные открытия шлагбаума охраной
+CREATE TABLE barrier_manual_openings (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id),
+    barrier_id UUID REFERENCES barriers(id),
+
+    worker_id UUID NOT NULL REFERENCES osi_workers(id),
+    user_id UUID NOT NULL REFERENCES users(id),
+
+    reason TEXT NOT NULL,
+    vehicle_number VARCHAR(20),
+    guest_access_id UUID REFERENCES guest_access(id),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_barrier_manual_openings_complex ON barrier_manual_openings(complex_id, created_at DESC);
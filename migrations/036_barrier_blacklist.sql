@@ -0,0 +1,35 @@
+-- Чёрный список ЖК: автомобили и люди, которым запрещён въезд
+CREATE TABLE barrier_blacklist (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+
+    -- Госномер хранится нормализованным: без пробелов, латиница в верхнем регистре
+    vehicle_number VARCHAR(20),
+    person_name VARCHAR(200),
+    person_phone VARCHAR(20),
+
+    reason TEXT NOT NULL,
+    expires_at TIMESTAMPTZ,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK (vehicle_number IS NOT NULL OR person_phone IS NOT NULL)
+);
+
+CREATE INDEX idx_barrier_blacklist_vehicle ON barrier_blacklist(complex_id, vehicle_number) WHERE is_active;
+CREATE INDEX idx_barrier_blacklist_phone ON barrier_blacklist(complex_id, person_phone) WHERE is_active;
+
+-- Отказы во въезде по чёрному списку
+CREATE TABLE barrier_denials (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id),
+    barrier_id UUID REFERENCES barriers(id),
+    blacklist_id UUID NOT NULL REFERENCES barrier_blacklist(id) ON DELETE CASCADE,
+    guest_access_id UUID REFERENCES guest_access(id),
+    vehicle_number VARCHAR(20),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_barrier_denials_complex ON barrier_denials(complex_id, created_at DESC);
@@ -0,0 +1,52 @@
+-- Права на управление ЖК
+CREATE TYPE app_permission AS ENUM (
+    'manage_osi',
+    'manage_council',
+    'manage_workers',
+    'manage_documents',
+    'manage_finance',
+    'manage_bills',
+    'manage_permissions'
+);
+
+-- Права, которые даёт роль в пределах ЖК
+CREATE TABLE role_permissions (
+    role user_role NOT NULL,
+    permission app_permission NOT NULL,
+    PRIMARY KEY (role, permission)
+);
+
+INSERT INTO role_permissions (role, permission) VALUES
+('chairman', 'manage_osi'),
+('chairman', 'manage_council'),
+('chairman', 'manage_workers'),
+('chairman', 'manage_documents'),
+('chairman', 'manage_finance'),
+('chairman', 'manage_bills'),
+('chairman', 'manage_permissions');
+
+-- Роли пользователя в конкретных ЖК (председатель ОСИ определяется по osi.chairman_id)
+CREATE TABLE complex_roles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    role user_role NOT NULL,
+    granted_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, complex_id, role)
+);
+
+CREATE INDEX idx_complex_roles_complex ON complex_roles(complex_id);
+
+-- Отдельные права пользователя в ЖК
+CREATE TABLE permission_grants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    permission app_permission NOT NULL,
+    granted_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, complex_id, permission)
+);
+
+CREATE INDEX idx_permission_grants_complex ON permission_grants(complex_id);
@@ -0,0 +1,27 @@
+-- Прямые загрузки файлов в хранилище по подписанной ссылке
+CREATE TYPE upload_purpose AS ENUM ('listing_photo', 'maintenance_photo', 'osi_document');
+CREATE TYPE file_upload_status AS ENUM ('pending', 'confirmed');
+
+CREATE TABLE file_uploads (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+
+    purpose upload_purpose NOT NULL,
+    -- Объявление, заявка или ОСИ, к которым прикрепляется файл
+    target_id UUID NOT NULL,
+
+    object_key TEXT NOT NULL UNIQUE,
+    content_type VARCHAR(100) NOT NULL,
+    max_size BIGINT NOT NULL,
+
+    status file_upload_status NOT NULL DEFAULT 'pending',
+    url TEXT,
+    -- Созданная запись: фото объявления, фото заявки или документ ОСИ
+    entity_id UUID,
+
+    expires_at TIMESTAMPTZ NOT NULL,
+    confirmed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_file_uploads_user ON file_uploads(user_id, created_at DESC);
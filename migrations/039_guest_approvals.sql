@@ -0,0 +1,10 @@
+-- Подтверждение гостевых пропусков охраной в ЖК с ограниченным доступом
+ALTER TYPE guest_access_status ADD VALUE 'awaiting_approval' BEFORE 'pending';
+ALTER TYPE guest_access_status ADD VALUE 'declined';
+
+ALTER TABLE complexes ADD COLUMN guest_approval_required BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE guest_access
+    ADD COLUMN reviewed_by UUID REFERENCES users(id),
+    ADD COLUMN reviewed_at TIMESTAMPTZ,
+    ADD COLUMN decline_reason TEXT;
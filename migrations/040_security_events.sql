@@ -0,0 +1,34 @@
+-- Подозрительные события на шлагбауме (лента безопасности)
+CREATE TYPE security_event_type AS ENUM ('pass_overuse', 'entry_without_exit', 'excessive_openings');
+
+CREATE TABLE security_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    event_type security_event_type NOT NULL,
+
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    guest_access_id UUID REFERENCES guest_access(id) ON DELETE SET NULL,
+    vehicle_number VARCHAR(20),
+    details JSONB NOT NULL DEFAULT '{}',
+
+    -- Пропуск приостановлен до проверки председателем
+    pass_suspended BOOLEAN NOT NULL DEFAULT false,
+
+    reviewed_by UUID REFERENCES users(id),
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_security_events_complex ON security_events(complex_id, created_at DESC);
+
+ALTER TABLE guest_access ADD COLUMN suspended_at TIMESTAMPTZ;
+
+ALTER TABLE complexes ADD COLUMN auto_suspend_anomalous_passes BOOLEAN NOT NULL DEFAULT false;
+
+-- Заменяют одноколоночные индексы из 007: правила смотрят проезды пользователя за период
+DROP INDEX idx_barrier_logs_user;
+DROP INDEX idx_barrier_logs_guest;
+CREATE INDEX idx_barrier_logs_user ON barrier_access_logs(user_id, created_at) WHERE user_id IS NOT NULL;
+CREATE INDEX idx_barrier_logs_guest ON barrier_access_logs(guest_access_id) WHERE guest_access_id IS NOT NULL;
+CREATE INDEX idx_barrier_logs_vehicle ON barrier_access_logs(complex_id, vehicle_number, created_at)
+    WHERE vehicle_number IS NOT NULL;
@@ -0,0 +1,10 @@
+-- Уменьшенные копии изображений для мобильных клиентов (200px и 800px)
+ALTER TABLE users ADD COLUMN avatar_thumbnail_url TEXT;
+
+ALTER TABLE complex_photos
+    ADD COLUMN thumbnail_url TEXT,
+    ADD COLUMN medium_url TEXT;
+
+ALTER TABLE listing_photos
+    ADD COLUMN thumbnail_url TEXT,
+    ADD COLUMN medium_url TEXT;
@@ -0,0 +1,21 @@
+-- Учёт стоимости SMS и месячный лимит расходов ОСИ на SMS
+CREATE TYPE sms_status AS ENUM ('sent', 'failed', 'suppressed');
+
+CREATE TABLE sms_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- ЖК, на счёт ОСИ которого относится SMS; NULL — расходы платформы (коды входа)
+    complex_id UUID REFERENCES complexes(id) ON DELETE SET NULL,
+    phone VARCHAR(20) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    segments INT NOT NULL DEFAULT 1,
+    cost DECIMAL(10, 2) NOT NULL DEFAULT 0,
+    status sms_status NOT NULL,
+    provider_message_id VARCHAR(100),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_sms_messages_complex ON sms_messages(complex_id, created_at);
+
+-- NULL — без ограничения
+ALTER TABLE osi ADD COLUMN sms_monthly_cap DECIMAL(12, 2);
@@ -0,0 +1,2 @@
+-- Отложенная отправка SMS, если шлюз недоступен
+ALTER TYPE job_type ADD VALUE 'sms_delivery';
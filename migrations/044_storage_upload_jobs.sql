@@ -0,0 +1,2 @@
+-- Досылка файлов, отложенных на диск, пока хранилище было недоступно
+ALTER TYPE job_type ADD VALUE 'storage_upload';
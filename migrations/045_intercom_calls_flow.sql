@@ -0,0 +1,12 @@
+-- Управление домофоном и входящие вызовы
+ALTER TYPE intercom_call_status ADD VALUE 'ringing';
+
+ALTER TABLE intercoms
+    ADD COLUMN api_url TEXT,
+    ADD COLUMN device_token VARCHAR(64);
+
+CREATE UNIQUE INDEX idx_intercoms_device_token ON intercoms(device_token) WHERE device_token IS NOT NULL;
+
+ALTER TABLE intercom_calls
+    ADD COLUMN answered_at TIMESTAMPTZ,
+    ADD COLUMN ended_at TIMESTAMPTZ;
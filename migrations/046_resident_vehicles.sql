@@ -0,0 +1,16 @@
+-- Автомобили жителей для въезда по распознанному номеру
+CREATE TABLE resident_vehicles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id),
+    user_id UUID NOT NULL REFERENCES users(id),
+
+    -- Номер в нормализованном виде, см. normalize_vehicle_number
+    vehicle_number VARCHAR(20) NOT NULL,
+    description VARCHAR(100),
+
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE (complex_id, vehicle_number)
+);
+
+CREATE INDEX idx_resident_vehicles_user ON resident_vehicles(user_id);
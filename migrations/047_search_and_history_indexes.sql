@@ -0,0 +1,32 @@
+-- Поиск по подстроке (ILIKE '%...%') через триграммы
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_complexes_name_trgm ON complexes USING gin (name gin_trgm_ops);
+CREATE INDEX idx_addresses_street_trgm ON addresses USING gin (street gin_trgm_ops);
+CREATE INDEX idx_addresses_building_trgm ON addresses USING gin (building gin_trgm_ops);
+CREATE INDEX idx_listings_title_trgm ON marketplace_listings USING gin (title gin_trgm_ops);
+CREATE INDEX idx_listings_description_trgm ON marketplace_listings USING gin (description gin_trgm_ops);
+CREATE INDEX idx_users_phone_trgm ON users USING gin (phone gin_trgm_ops);
+CREATE INDEX idx_users_first_name_trgm ON users USING gin (first_name gin_trgm_ops);
+CREATE INDEX idx_users_last_name_trgm ON users USING gin (last_name gin_trgm_ops);
+
+-- Истории и ленты, отсортированные по времени внутри ЖК / ОСИ
+DROP INDEX idx_security_events_complex;
+CREATE INDEX idx_security_events_complex_keyset ON security_events(complex_id, created_at DESC, id DESC);
+CREATE INDEX idx_maintenance_complex_created ON maintenance_requests(complex_id, created_at DESC);
+CREATE INDEX idx_listings_complex_active ON marketplace_listings(complex_id, created_at DESC) WHERE status = 'active';
+CREATE INDEX idx_listings_seller_created ON marketplace_listings(seller_id, created_at DESC);
+CREATE INDEX idx_guest_access_complex_created ON guest_access(complex_id, created_at DESC);
+CREATE INDEX idx_join_requests_complex_created ON join_requests(complex_id, created_at DESC);
+CREATE INDEX idx_intercom_calls_intercom_created ON intercom_calls(intercom_id, created_at DESC);
+CREATE INDEX idx_announcements_complex_feed ON announcements(complex_id, priority DESC, published_at DESC) WHERE is_published;
+CREATE INDEX idx_payments_osi_created ON payments(osi_id, created_at DESC);
+CREATE INDEX idx_shared_charges_osi_created ON shared_charges(osi_id, created_at DESC);
+CREATE INDEX idx_osi_documents_osi_created ON osi_documents(osi_id, created_at DESC);
+CREATE INDEX idx_osi_expenses_osi_date ON osi_expenses(osi_id, expense_date DESC, created_at DESC);
+CREATE INDEX idx_meter_readings_meter_date ON meter_readings(meter_id, reading_date DESC);
+CREATE INDEX idx_bills_apartment_period ON bills(apartment_id, period_end DESC);
+
+-- Списки в админке
+CREATE INDEX idx_users_created ON users(created_at DESC);
+CREATE INDEX idx_complexes_created ON complexes(created_at DESC);
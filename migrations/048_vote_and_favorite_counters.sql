@@ -0,0 +1,80 @@
+-- Итоги голосований хранятся рядом с вариантами и поддерживаются триггером на votes
+ALTER TABLE voting_options
+    ADD COLUMN votes_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN votes_weight DECIMAL(14, 4) NOT NULL DEFAULT 0;
+
+ALTER TABLE votings
+    ADD COLUMN votes_count INT NOT NULL DEFAULT 0,
+    ADD COLUMN votes_weight DECIMAL(14, 4) NOT NULL DEFAULT 0;
+
+UPDATE voting_options o
+SET votes_count = s.votes_count, votes_weight = s.votes_weight
+FROM (
+    SELECT option_id, COUNT(*) AS votes_count, COALESCE(SUM(vote_weight), 0) AS votes_weight
+    FROM votes GROUP BY option_id
+) s
+WHERE s.option_id = o.id;
+
+UPDATE votings v
+SET votes_count = s.votes_count, votes_weight = s.votes_weight
+FROM (
+    SELECT voting_id, COUNT(*) AS votes_count, COALESCE(SUM(vote_weight), 0) AS votes_weight
+    FROM votes GROUP BY voting_id
+) s
+WHERE s.voting_id = v.id;
+
+CREATE FUNCTION votes_update_counters()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE voting_options
+        SET votes_count = votes_count - 1, votes_weight = votes_weight - COALESCE(OLD.vote_weight, 0)
+        WHERE id = OLD.option_id;
+
+        UPDATE votings
+        SET votes_count = votes_count - 1, votes_weight = votes_weight - COALESCE(OLD.vote_weight, 0)
+        WHERE id = OLD.voting_id;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE voting_options
+        SET votes_count = votes_count + 1, votes_weight = votes_weight + COALESCE(NEW.vote_weight, 0)
+        WHERE id = NEW.option_id;
+
+        UPDATE votings
+        SET votes_count = votes_count + 1, votes_weight = votes_weight + COALESCE(NEW.vote_weight, 0)
+        WHERE id = NEW.voting_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER votes_counters
+    AFTER INSERT OR DELETE OR UPDATE OF voting_id, option_id, vote_weight ON votes
+    FOR EACH ROW EXECUTE FUNCTION votes_update_counters();
+
+-- Счётчик избранного объявления
+UPDATE marketplace_listings l
+SET favorites_count = (SELECT COUNT(*) FROM listing_favorites f WHERE f.listing_id = l.id);
+
+ALTER TABLE marketplace_listings
+    ALTER COLUMN favorites_count SET DEFAULT 0,
+    ALTER COLUMN favorites_count SET NOT NULL;
+
+CREATE FUNCTION listing_favorites_update_counter()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        UPDATE marketplace_listings SET favorites_count = favorites_count + 1 WHERE id = NEW.listing_id;
+    ELSE
+        UPDATE marketplace_listings SET favorites_count = favorites_count - 1 WHERE id = OLD.listing_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER listing_favorites_counter
+    AFTER INSERT OR DELETE ON listing_favorites
+    FOR EACH ROW EXECUTE FUNCTION listing_favorites_update_counter();
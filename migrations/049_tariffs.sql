@@ -0,0 +1,25 @@
+-- Тарифы на коммунальные услуги ЖК с периодом действия
+CREATE TYPE tariff_basis AS ENUM ('metered', 'area', 'apartment');
+
+CREATE TABLE tariffs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    utility_type utility_type NOT NULL,
+    -- metered: расход по счётчику × тариф, area: площадь × тариф, apartment: фикс с квартиры
+    basis tariff_basis NOT NULL,
+    rate DECIMAL(10, 4) NOT NULL CHECK (rate >= 0),
+    unit VARCHAR(20) NOT NULL,
+    effective_from DATE NOT NULL,
+    effective_to DATE,
+    created_by UUID REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK (effective_to IS NULL OR effective_to >= effective_from)
+);
+
+CREATE INDEX idx_tariffs_complex ON tariffs(complex_id, utility_type, effective_from DESC);
+
+-- По какому тарифу начислена строка счёта
+ALTER TABLE bill_items ADD COLUMN tariff_id UUID REFERENCES tariffs(id);
+CREATE INDEX idx_bill_items_tariff ON bill_items(tariff_id) WHERE tariff_id IS NOT NULL;
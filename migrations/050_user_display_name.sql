@@ -0,0 +1,5 @@
+-- Отображаемое имя пользователя: «Имя Фамилия», а если имя не заполнено — телефон.
+-- Генерируемый столбец пересчитывается сам при изменении профиля, списки берут его через JOIN
+ALTER TABLE users ADD COLUMN display_name TEXT GENERATED ALWAYS AS (
+    COALESCE(NULLIF(BTRIM(COALESCE(first_name, '') || ' ' || COALESCE(last_name, '')), ''), phone)
+) STORED;
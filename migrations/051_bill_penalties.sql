@@ -0,0 +1,4 @@
+-- Начисление пени по просроченным счетам
+ALTER TABLE bills ADD COLUMN penalty_accrued_until DATE;
+
+CREATE INDEX idx_bills_unpaid_due ON bills(due_date) WHERE status IN ('pending', 'overdue');
@@ -0,0 +1,2 @@
+-- Сгенерированная PDF-квитанция платежа (ключ в хранилище), чтобы не рендерить её повторно
+ALTER TABLE payments ADD COLUMN receipt_pdf_key TEXT;
@@ -0,0 +1,37 @@
+-- Справочник адресов из национального классификатора (КАТО) и адресного регистра
+ALTER TABLE cities ADD COLUMN kato_code VARCHAR(12) UNIQUE;
+
+CREATE TABLE registry_districts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kato_code VARCHAR(12) NOT NULL UNIQUE,
+    city_id VARCHAR(50) NOT NULL REFERENCES cities(id),
+    name VARCHAR(200) NOT NULL,
+    name_kz VARCHAR(200),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_registry_districts_city ON registry_districts(city_id, name);
+
+CREATE TABLE registry_streets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    code VARCHAR(32) NOT NULL UNIQUE,
+    city_id VARCHAR(50) NOT NULL REFERENCES cities(id),
+    district_id UUID REFERENCES registry_districts(id) ON DELETE SET NULL,
+    name VARCHAR(200) NOT NULL,
+    name_kz VARCHAR(200),
+    street_type VARCHAR(50),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_registry_streets_city ON registry_streets(city_id, lower(name));
+CREATE INDEX idx_registry_streets_name_trgm ON registry_streets USING gin (name gin_trgm_ops);
+CREATE INDEX idx_registry_streets_name_kz_trgm ON registry_streets USING gin (name_kz gin_trgm_ops);
+
+-- Адрес на улице из регистра считается подтверждённым, набранный вручную — нет
+ALTER TABLE addresses
+    ADD COLUMN registry_street_id UUID REFERENCES registry_streets(id) ON DELETE SET NULL,
+    ADD COLUMN is_verified BOOLEAN NOT NULL DEFAULT false;
+
+CREATE INDEX idx_addresses_registry_street ON addresses(registry_street_id);
+
+ALTER TYPE job_type ADD VALUE 'address_registry_import';
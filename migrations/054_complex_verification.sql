@@ -0,0 +1,39 @@
+-- Проверка нового ЖК администратором: документы, чек-лист, запрос сведений, отказ
+CREATE TYPE complex_verification_status AS ENUM ('submitted', 'needs_info', 'approved', 'rejected');
+CREATE TYPE verification_evidence_type AS ENUM ('address_proof', 'relation_proof', 'other');
+
+CREATE TABLE complex_verifications (
+    complex_id UUID PRIMARY KEY REFERENCES complexes(id) ON DELETE CASCADE,
+    status complex_verification_status NOT NULL DEFAULT 'submitted',
+    -- Отметки администратора по пунктам чек-листа: {"address_confirmed": true, ...}
+    checklist JSONB NOT NULL DEFAULT '{}',
+    info_request TEXT,
+    rejection_reasons TEXT[] NOT NULL DEFAULT '{}',
+    reviewed_by UUID REFERENCES users(id),
+    reviewed_at TIMESTAMPTZ,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_complex_verifications_status ON complex_verifications(status, submitted_at);
+
+CREATE TABLE complex_verification_evidence (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    evidence_type verification_evidence_type NOT NULL,
+    file_url TEXT NOT NULL,
+    file_name VARCHAR(255),
+    comment TEXT,
+    uploaded_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_complex_verification_evidence_complex ON complex_verification_evidence(complex_id, created_at);
+
+-- Уже подтверждённые ЖК считаются прошедшими проверку
+INSERT INTO complex_verifications (complex_id, status, reviewed_by, reviewed_at, submitted_at)
+SELECT id,
+       CASE WHEN verified_at IS NOT NULL THEN 'approved'::complex_verification_status
+            ELSE 'submitted'::complex_verification_status END,
+       verified_by, verified_at, COALESCE(created_at, NOW())
+FROM complexes;
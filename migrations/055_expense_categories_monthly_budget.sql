@@ -0,0 +1,10 @@
+-- Собственные категории расходов ОСИ не должны повторять друг друга
+CREATE UNIQUE INDEX idx_expense_categories_osi_name
+    ON expense_categories(osi_id, lower(name)) WHERE osi_id IS NOT NULL;
+
+-- Помесячная разбивка статьи бюджета; NULL — план делится на 12 равных частей
+ALTER TABLE budget_lines ADD COLUMN monthly_amounts DECIMAL(12, 2)[]
+    CHECK (monthly_amounts IS NULL OR array_length(monthly_amounts, 1) = 12);
+
+CREATE INDEX idx_payments_apartment_completed ON payments(apartment_id, completed_at)
+    WHERE status = 'completed';
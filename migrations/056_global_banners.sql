@@ -0,0 +1,38 @@
+-- Платформенные баннеры: новые функции, технические работы
+CREATE TYPE banner_severity AS ENUM ('info', 'warning', 'critical');
+
+CREATE TABLE global_banners (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title VARCHAR(200) NOT NULL,
+    message TEXT NOT NULL,
+    link_url TEXT,
+    severity banner_severity NOT NULL DEFAULT 'info',
+
+    -- Пустой список — без ограничения
+    target_cities VARCHAR(50)[] NOT NULL DEFAULT '{}',
+    target_roles user_role[] NOT NULL DEFAULT '{}',
+
+    starts_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ends_at TIMESTAMPTZ,
+    is_dismissible BOOLEAN NOT NULL DEFAULT true,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+
+    -- Разослать уведомление, когда баннер начнёт показываться
+    send_push BOOLEAN NOT NULL DEFAULT false,
+    pushed_at TIMESTAMPTZ,
+
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CHECK (ends_at IS NULL OR ends_at > starts_at)
+);
+
+CREATE INDEX idx_global_banners_active ON global_banners(starts_at, ends_at) WHERE is_active;
+
+CREATE TABLE banner_dismissals (
+    banner_id UUID NOT NULL REFERENCES global_banners(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    dismissed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (banner_id, user_id)
+);
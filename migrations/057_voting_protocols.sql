@@ -0,0 +1,13 @@
+-- Протоколы закрытых голосований: подписываются один раз и дальше отдаются из хранилища
+CREATE TABLE voting_protocols (
+    voting_id UUID PRIMARY KEY REFERENCES votings(id) ON DELETE CASCADE,
+    protocol_number VARCHAR(20) NOT NULL,
+
+    -- Подпись итогов (HMAC-SHA256) и код проверки из протокола
+    signature VARCHAR(64) NOT NULL,
+    verification_token VARCHAR(64) NOT NULL UNIQUE,
+
+    signed_by UUID NOT NULL REFERENCES users(id),
+    signed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    pdf_key TEXT
+);
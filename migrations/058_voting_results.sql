@@ -0,0 +1,9 @@
+-- Итоги голосования фиксируются при закрытии: площадь собственников, кворум и принятое решение
+ALTER TABLE votings
+    ADD COLUMN total_weight DECIMAL(12, 2),
+    ADD COLUMN quorum_reached BOOLEAN,
+    ADD COLUMN passed BOOLEAN,
+    ADD COLUMN closed_at TIMESTAMPTZ;
+
+-- Выборы без кворума не меняют председателя
+ALTER TYPE election_outcome ADD VALUE 'no_quorum';
@@ -0,0 +1,2 @@
+-- Сессии председателя привязываются к устройству входа; ЖК может отключить привязку
+ALTER TABLE complexes ADD COLUMN device_binding_enabled BOOLEAN NOT NULL DEFAULT true;
@@ -0,0 +1,16 @@
+-- Неудачные обращения к админке по IP и временные блокировки подбора
+CREATE TABLE admin_ip_blocks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip_address VARCHAR(45) NOT NULL UNIQUE,
+    failures INT NOT NULL DEFAULT 0,
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_path TEXT,
+    blocked_until TIMESTAMPTZ,
+    block_count INT NOT NULL DEFAULT 0,
+    lifted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    lifted_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_admin_ip_blocks_blocked ON admin_ip_blocks(blocked_until) WHERE blocked_until IS NOT NULL;
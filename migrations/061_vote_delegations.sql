@@ -0,0 +1,23 @@
+-- Доверенности на голосование: собственник передаёт свой вес другому жителю ЖК
+-- на конкретное голосование (voting_id) или на период (valid_from .. valid_until)
+CREATE TABLE vote_delegations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    principal_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    proxy_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    voting_id UUID REFERENCES votings(id) ON DELETE CASCADE,
+    valid_from TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    valid_until TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (principal_id <> proxy_id),
+    CHECK (valid_until IS NULL OR valid_until > valid_from)
+);
+
+CREATE INDEX idx_vote_delegations_principal ON vote_delegations(principal_id, complex_id);
+CREATE INDEX idx_vote_delegations_proxy ON vote_delegations(proxy_id, complex_id);
+
+-- Голос по доверенности: user_id — собственник, proxy_id — кто проголосовал за него
+ALTER TABLE votes
+    ADD COLUMN proxy_id UUID REFERENCES users(id),
+    ADD COLUMN delegation_id UUID REFERENCES vote_delegations(id) ON DELETE SET NULL;
@@ -0,0 +1,27 @@
+-- Юридические документы с версиями и согласия пользователей с ними
+CREATE TYPE legal_document_kind AS ENUM ('terms_of_service', 'personal_data');
+
+CREATE TABLE legal_documents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind legal_document_kind NOT NULL,
+    version VARCHAR(20) NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    content TEXT NOT NULL,
+    -- Новая версия требует повторного согласия с момента публикации
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, version)
+);
+
+CREATE INDEX idx_legal_documents_kind ON legal_documents(kind, published_at DESC);
+
+CREATE TABLE consent_acceptances (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    document_id UUID NOT NULL REFERENCES legal_documents(id),
+    accepted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ip_address VARCHAR(45),
+    user_agent TEXT,
+    UNIQUE (user_id, document_id)
+);
@@ -0,0 +1,23 @@
+-- Члены семьи жителей без собственных аккаунтов: для распознавания домофоном и статистики
+CREATE TYPE family_relation AS ENUM ('spouse', 'child', 'parent', 'sibling', 'other');
+
+CREATE TABLE family_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    apartment_id UUID NOT NULL REFERENCES apartments(id) ON DELETE CASCADE,
+    first_name VARCHAR(100) NOT NULL,
+    last_name VARCHAR(100),
+    relation family_relation NOT NULL DEFAULT 'other',
+    birth_year INT,
+    photo_url TEXT,
+    -- Приглашение превратить профиль в полноценный аккаунт
+    phone VARCHAR(20),
+    invite_token VARCHAR(64) UNIQUE,
+    invite_expires_at TIMESTAMPTZ,
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_family_members_apartment ON family_members(apartment_id);
+CREATE INDEX idx_family_members_user ON family_members(user_id) WHERE user_id IS NOT NULL;
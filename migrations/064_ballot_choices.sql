@@ -0,0 +1,67 @@
+-- Рейтинговое голосование: участник упорядочивает варианты по предпочтению
+ALTER TYPE voting_type ADD VALUE 'ranked';
+
+-- Выбранные в бюллетене варианты; votes.option_id остаётся первым выбором.
+-- Вес бюллетеня копируется, чтобы триггер мог вычесть его и после удаления голоса
+CREATE TABLE vote_choices (
+    vote_id UUID NOT NULL REFERENCES votes(id) ON DELETE CASCADE,
+    option_id UUID NOT NULL REFERENCES voting_options(id) ON DELETE CASCADE,
+    -- Место в рейтинге; при выборе нескольких вариантов у всех 1
+    rank INT NOT NULL DEFAULT 1 CHECK (rank > 0),
+    vote_weight DECIMAL(10, 4) NOT NULL DEFAULT 1,
+    PRIMARY KEY (vote_id, option_id)
+);
+
+CREATE INDEX idx_vote_choices_option ON vote_choices(option_id);
+
+INSERT INTO vote_choices (vote_id, option_id, rank, vote_weight)
+SELECT id, option_id, 1, COALESCE(vote_weight, 1) FROM votes;
+
+-- Итоги голосования считаются по бюллетеням, итоги вариантов — по первым местам выбора
+CREATE OR REPLACE FUNCTION votes_update_counters()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE votings
+        SET votes_count = votes_count - 1, votes_weight = votes_weight - COALESCE(OLD.vote_weight, 0)
+        WHERE id = OLD.voting_id;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE votings
+        SET votes_count = votes_count + 1, votes_weight = votes_weight + COALESCE(NEW.vote_weight, 0)
+        WHERE id = NEW.voting_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER votes_counters ON votes;
+
+CREATE TRIGGER votes_counters
+    AFTER INSERT OR DELETE OR UPDATE OF voting_id, vote_weight ON votes
+    FOR EACH ROW EXECUTE FUNCTION votes_update_counters();
+
+CREATE FUNCTION vote_choices_update_counters()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') AND OLD.rank = 1 THEN
+        UPDATE voting_options
+        SET votes_count = votes_count - 1, votes_weight = votes_weight - OLD.vote_weight
+        WHERE id = OLD.option_id;
+    END IF;
+
+    IF TG_OP IN ('INSERT', 'UPDATE') AND NEW.rank = 1 THEN
+        UPDATE voting_options
+        SET votes_count = votes_count + 1, votes_weight = votes_weight + NEW.vote_weight
+        WHERE id = NEW.option_id;
+    END IF;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER vote_choices_counters
+    AFTER INSERT OR DELETE OR UPDATE OF option_id, rank, vote_weight ON vote_choices
+    FOR EACH ROW EXECUTE FUNCTION vote_choices_update_counters();
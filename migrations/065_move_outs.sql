@@ -0,0 +1,25 @@
+-- Выезд жильца: ОСИ узнаёт заранее, доступы снимаются в день выезда
+CREATE TYPE move_out_status AS ENUM ('scheduled', 'completed', 'cancelled');
+
+CREATE TABLE move_outs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    apartment_id UUID NOT NULL REFERENCES apartments(id) ON DELETE CASCADE,
+    complex_id UUID NOT NULL REFERENCES complexes(id),
+    -- Выезжающий жилец
+    user_id UUID NOT NULL REFERENCES users(id),
+    initiated_by UUID NOT NULL REFERENCES users(id),
+    effective_date DATE NOT NULL,
+    note TEXT,
+    status move_out_status NOT NULL DEFAULT 'scheduled',
+    -- Итоговые показания сданы по всем счётчикам к моменту выезда
+    readings_complete BOOLEAN,
+    completed_at TIMESTAMPTZ,
+    cancelled_by UUID REFERENCES users(id),
+    cancelled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX idx_move_outs_scheduled ON move_outs(apartment_id) WHERE status = 'scheduled';
+CREATE INDEX idx_move_outs_due ON move_outs(effective_date) WHERE status = 'scheduled';
+CREATE INDEX idx_move_outs_complex ON move_outs(complex_id, created_at DESC);
@@ -0,0 +1,57 @@
+-- Опросы жителей: несколько вопросов с выбором, свободным ответом или шкалой
+CREATE TYPE survey_status AS ENUM ('active', 'closed');
+CREATE TYPE survey_question_type AS ENUM ('choice', 'text', 'scale');
+
+CREATE TABLE surveys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id),
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    status survey_status NOT NULL DEFAULT 'active',
+    -- Без даты опрос идёт, пока его не закроют
+    ends_at TIMESTAMPTZ,
+    responses_count INT NOT NULL DEFAULT 0,
+    created_by UUID NOT NULL REFERENCES users(id),
+    closed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_surveys_complex ON surveys(complex_id, created_at DESC);
+
+CREATE TABLE survey_questions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    survey_id UUID NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    question_type survey_question_type NOT NULL,
+    text TEXT NOT NULL,
+    -- Варианты ответа для вопросов с выбором
+    options TEXT[] NOT NULL DEFAULT '{}',
+    allow_multiple BOOLEAN NOT NULL DEFAULT false,
+    scale_min INT NOT NULL DEFAULT 1,
+    scale_max INT NOT NULL DEFAULT 5,
+    is_required BOOLEAN NOT NULL DEFAULT true,
+    UNIQUE (survey_id, position)
+);
+
+CREATE TABLE survey_responses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    survey_id UUID NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id),
+    -- Квартира жителя для разбивки итогов по корпусам
+    apartment_id UUID REFERENCES apartments(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (survey_id, user_id)
+);
+
+CREATE TABLE survey_answers (
+    response_id UUID NOT NULL REFERENCES survey_responses(id) ON DELETE CASCADE,
+    question_id UUID NOT NULL REFERENCES survey_questions(id) ON DELETE CASCADE,
+    -- Номера выбранных вариантов, с нуля
+    option_indexes INT[] NOT NULL DEFAULT '{}',
+    text_value TEXT,
+    scale_value INT,
+    PRIMARY KEY (response_id, question_id)
+);
+
+CREATE INDEX idx_survey_answers_question ON survey_answers(question_id);
@@ -0,0 +1,9 @@
+-- Бюллетень подаётся за квартиру: вес — её площадь, совладельцы не голосуют дважды,
+-- а собственник нескольких квартир может голосовать за каждую по-своему.
+-- Голос без квартиры (голосования для всех жителей) по-прежнему один на пользователя.
+ALTER TABLE votes DROP CONSTRAINT votes_voting_id_user_id_key;
+
+CREATE UNIQUE INDEX idx_votes_apartment ON votes(voting_id, apartment_id)
+    WHERE apartment_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_votes_user_without_apartment ON votes(voting_id, user_id)
+    WHERE apartment_id IS NULL;
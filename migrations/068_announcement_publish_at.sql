@@ -0,0 +1,5 @@
+-- Отложенная публикация: объявление скрыто до publish_at, затем его публикует фоновая задача
+ALTER TABLE announcements ADD COLUMN publish_at TIMESTAMPTZ;
+
+CREATE INDEX idx_announcements_scheduled ON announcements(publish_at)
+    WHERE NOT is_published AND publish_at IS NOT NULL;
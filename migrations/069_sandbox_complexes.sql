@@ -0,0 +1,6 @@
+-- Демо-ЖК для партнёров, интегрирующихся с API: SMS и платежи в них не уходят провайдерам,
+-- а данные можно сбросить к эталонному набору
+ALTER TABLE complexes ADD COLUMN is_sandbox BOOLEAN NOT NULL DEFAULT false;
+
+-- SMS песочницы записываются в журнал, но не отправляются
+ALTER TYPE sms_status ADD VALUE 'sandbox';
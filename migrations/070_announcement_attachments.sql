@@ -0,0 +1,16 @@
+-- Вложения объявлений: фото и документы, например скан решения собрания
+CREATE TABLE announcement_attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    announcement_id UUID NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    file_url TEXT NOT NULL,
+    -- Превью есть только у изображений
+    thumbnail_url TEXT,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    file_size BIGINT NOT NULL,
+    uploaded_by UUID NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_announcement_attachments_announcement
+    ON announcement_attachments(announcement_id, created_at);
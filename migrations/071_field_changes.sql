@@ -0,0 +1,18 @@
+-- Пополевая история изменений ОСИ и ЖК: реквизиты, председатель, статус и настройки.
+-- Одна строка на изменённое поле, чтобы подмену реквизитов было видно и искать
+CREATE TABLE field_changes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    entity_type VARCHAR(50) NOT NULL,
+    entity_id UUID NOT NULL,
+    field VARCHAR(100) NOT NULL,
+    old_value JSONB,
+    new_value JSONB,
+    -- NULL — изменение системой, например по итогам выборов председателя
+    changed_by UUID REFERENCES users(id),
+    request_id VARCHAR(64),
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_field_changes_complex ON field_changes(complex_id, changed_at DESC);
+CREATE INDEX idx_field_changes_entity ON field_changes(entity_type, entity_id, field);
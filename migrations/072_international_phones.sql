@@ -0,0 +1,4 @@
+-- Номера вне зоны +7: код страны хранится отдельно, международные SMS учитываются в журнале
+ALTER TABLE users ADD COLUMN phone_country_code VARCHAR(3) NOT NULL DEFAULT '7';
+
+ALTER TABLE sms_messages ADD COLUMN is_international BOOLEAN NOT NULL DEFAULT false;
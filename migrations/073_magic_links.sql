@@ -0,0 +1,22 @@
+-- Вход в веб-панель по ссылке из письма: только для подтверждённых адресов
+ALTER TABLE users ADD COLUMN email_verified_at TIMESTAMPTZ;
+
+-- Подтверждённый адрес однозначно указывает на пользователя
+CREATE UNIQUE INDEX idx_users_verified_email ON users (LOWER(email))
+    WHERE email_verified_at IS NOT NULL;
+
+CREATE TYPE magic_link_purpose AS ENUM ('login', 'verify_email');
+
+-- Одноразовые ссылки; хранится только хэш токена
+CREATE TABLE magic_links (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    purpose magic_link_purpose NOT NULL,
+    email VARCHAR(255) NOT NULL,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_magic_links_user ON magic_links(user_id, created_at DESC);
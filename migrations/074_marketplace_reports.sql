@@ -0,0 +1,42 @@
+-- Жалобы на объявления маркетплейса и меры модерации
+ALTER TYPE listing_status ADD VALUE 'hidden';
+
+CREATE TYPE listing_report_reason AS ENUM ('spam', 'fraud', 'prohibited', 'offensive', 'other');
+CREATE TYPE listing_report_status AS ENUM ('pending', 'resolved', 'dismissed');
+
+CREATE TABLE listing_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    listing_id UUID NOT NULL REFERENCES marketplace_listings(id) ON DELETE CASCADE,
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason listing_report_reason NOT NULL,
+    comment TEXT,
+    status listing_report_status NOT NULL DEFAULT 'pending',
+    -- Принятая мера: hide_listing, warn_user, ban_user или dismiss
+    resolution VARCHAR(20),
+    moderator_note TEXT,
+    reviewed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Одна открытая жалоба от пользователя на объявление
+CREATE UNIQUE INDEX idx_listing_reports_pending_reporter ON listing_reports(listing_id, reporter_id)
+    WHERE status = 'pending';
+CREATE INDEX idx_listing_reports_queue ON listing_reports(status, created_at);
+
+CREATE TYPE marketplace_sanction_kind AS ENUM ('warning', 'ban');
+
+-- Предупреждения и блокировки продавцов; бан без expires_at — бессрочный
+CREATE TABLE marketplace_sanctions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind marketplace_sanction_kind NOT NULL,
+    report_id UUID REFERENCES listing_reports(id) ON DELETE SET NULL,
+    reason TEXT,
+    issued_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_marketplace_sanctions_user ON marketplace_sanctions(user_id, kind);
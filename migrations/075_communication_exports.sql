@@ -0,0 +1,42 @@
+-- История рассылок ЖК и её выгрузка для председателя
+ALTER TYPE job_type ADD VALUE 'communication_export';
+
+-- Рассылка уведомления всем жителям ЖК; пишется с этой миграции,
+-- более ранние рассылки в выгрузку не попадают
+CREATE TABLE notification_broadcasts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    notification_type notification_type NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    body TEXT,
+    data JSONB,
+    recipients_count INT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notification_broadcasts_complex ON notification_broadcasts(complex_id, created_at);
+
+ALTER TABLE notifications
+    ADD COLUMN broadcast_id UUID REFERENCES notification_broadcasts(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_notifications_broadcast ON notifications(broadcast_id) WHERE broadcast_id IS NOT NULL;
+
+CREATE TYPE export_format AS ENUM ('pdf', 'csv');
+CREATE TYPE export_status AS ENUM ('pending', 'completed', 'failed');
+
+CREATE TABLE communication_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    requested_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    date_from DATE NOT NULL,
+    date_to DATE NOT NULL,
+    format export_format NOT NULL,
+    status export_status NOT NULL DEFAULT 'pending',
+    file_key TEXT,
+    items_count INT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_communication_exports_complex ON communication_exports(complex_id, created_at DESC);
@@ -0,0 +1,43 @@
+-- Переписка по объявлениям идёт в обычных приватных чатах
+ALTER TABLE chats ADD COLUMN listing_id UUID REFERENCES marketplace_listings(id) ON DELETE SET NULL;
+
+-- Один чат покупателя с продавцом на объявление; покупатель — создатель чата
+CREATE UNIQUE INDEX idx_chats_listing_buyer ON chats(listing_id, created_by) WHERE listing_id IS NOT NULL;
+
+-- Переносим накопленные сообщения: пара «объявление — покупатель» становится чатом
+CREATE TEMPORARY TABLE listing_threads ON COMMIT DROP AS
+SELECT DISTINCT m.listing_id, l.complex_id, l.seller_id, l.title,
+       CASE WHEN m.sender_id = l.seller_id THEN m.recipient_id ELSE m.sender_id END AS buyer_id
+FROM listing_messages m
+JOIN marketplace_listings l ON l.id = m.listing_id;
+
+INSERT INTO chats (complex_id, chat_type, name, is_private, created_by, listing_id, created_at, updated_at)
+SELECT t.complex_id, 'private', t.title, true, t.buyer_id, t.listing_id,
+       (SELECT MIN(m.created_at) FROM listing_messages m
+        WHERE m.listing_id = t.listing_id AND t.buyer_id IN (m.sender_id, m.recipient_id)),
+       (SELECT MAX(m.created_at) FROM listing_messages m
+        WHERE m.listing_id = t.listing_id AND t.buyer_id IN (m.sender_id, m.recipient_id))
+FROM listing_threads t
+WHERE t.buyer_id <> t.seller_id;
+
+INSERT INTO chat_members (chat_id, user_id)
+SELECT c.id, c.created_by FROM chats c WHERE c.listing_id IS NOT NULL
+UNION
+SELECT c.id, l.seller_id FROM chats c JOIN marketplace_listings l ON l.id = c.listing_id
+ON CONFLICT (chat_id, user_id) DO NOTHING;
+
+CREATE TEMPORARY TABLE listing_message_map ON COMMIT DROP AS
+SELECT m.id AS listing_message_id, gen_random_uuid() AS chat_message_id, c.id AS chat_id,
+       m.sender_id, m.recipient_id, m.message, m.is_read, m.created_at
+FROM listing_messages m
+JOIN marketplace_listings l ON l.id = m.listing_id
+JOIN chats c ON c.listing_id = m.listing_id
+    AND c.created_by = CASE WHEN m.sender_id = l.seller_id THEN m.recipient_id ELSE m.sender_id END;
+
+INSERT INTO chat_messages (id, chat_id, sender_id, content, created_at)
+SELECT chat_message_id, chat_id, sender_id, message, created_at FROM listing_message_map;
+
+INSERT INTO message_reads (message_id, user_id)
+SELECT chat_message_id, recipient_id FROM listing_message_map WHERE is_read;
+
+DROP TABLE listing_messages;
@@ -0,0 +1,18 @@
+-- Заявки на ЖК и на вступление без движения закрываются автоматически
+ALTER TABLE complex_verifications
+    ADD COLUMN expiry_reminded_at TIMESTAMPTZ,
+    ADD COLUMN expired_at TIMESTAMPTZ;
+
+-- Срок заявки на вступление отсчитывается от последней подачи
+ALTER TABLE join_requests
+    ADD COLUMN submitted_at TIMESTAMPTZ,
+    ADD COLUMN expiry_reminded_at TIMESTAMPTZ,
+    ADD COLUMN expired_at TIMESTAMPTZ;
+
+UPDATE join_requests SET submitted_at = COALESCE(created_at, NOW());
+
+ALTER TABLE join_requests
+    ALTER COLUMN submitted_at SET NOT NULL,
+    ALTER COLUMN submitted_at SET DEFAULT NOW();
+
+CREATE INDEX idx_join_requests_pending_submitted ON join_requests(submitted_at) WHERE status = 'pending';
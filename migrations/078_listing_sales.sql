@@ -0,0 +1,15 @@
+-- Бронь и продажа объявлений маркетплейса, отзыв покупателя о продавце
+ALTER TABLE marketplace_listings
+    ADD COLUMN reserved_for UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN reserved_at TIMESTAMPTZ,
+    ADD COLUMN buyer_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN sold_at TIMESTAMPTZ,
+    -- Покупатель подтвердил, что получил товар
+    ADD COLUMN received_at TIMESTAMPTZ,
+    ADD COLUMN buyer_rating INT CHECK (buyer_rating >= 1 AND buyer_rating <= 5),
+    ADD COLUMN buyer_review TEXT;
+
+CREATE INDEX idx_listings_buyer ON marketplace_listings(buyer_id, sold_at DESC)
+    WHERE buyer_id IS NOT NULL;
+CREATE INDEX idx_listings_seller_rating ON marketplace_listings(seller_id)
+    WHERE buyer_rating IS NOT NULL;
@@ -0,0 +1,9 @@
+-- Полнотекстовый поиск по объявлениям с учётом русской морфологии
+ALTER TABLE marketplace_listings
+    ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+        setweight(to_tsvector('russian', COALESCE(title, '')), 'A') ||
+        setweight(to_tsvector('russian', COALESCE(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX idx_listings_search ON marketplace_listings USING GIN (search_vector);
+CREATE INDEX idx_listings_feed ON marketplace_listings(complex_id, status, created_at DESC);
@@ -0,0 +1,11 @@
+-- Где находился житель, открывая шлагбаум из приложения
+ALTER TABLE barrier_access_logs
+    ADD COLUMN latitude DECIMAL(10, 8),
+    ADD COLUMN longitude DECIMAL(11, 8),
+    -- Расстояние до ЖК по координатам адреса, метров
+    ADD COLUMN distance_m INT,
+    -- Открыто дальше допустимого радиуса; NULL — координаты не переданы или у ЖК их нет
+    ADD COLUMN geo_mismatch BOOLEAN;
+
+CREATE INDEX idx_barrier_logs_geo_mismatch ON barrier_access_logs(complex_id, created_at DESC)
+    WHERE geo_mismatch;
@@ -0,0 +1,15 @@
+-- Коды квартир для входа через панель домофона
+ALTER TYPE intercom_call_status ADD VALUE 'code_opened';
+ALTER TYPE intercom_call_status ADD VALUE 'code_denied';
+
+CREATE TABLE apartment_intercom_codes (
+    apartment_id UUID PRIMARY KEY REFERENCES apartments(id) ON DELETE CASCADE,
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    code VARCHAR(8) NOT NULL,
+    rotated_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    rotated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Неверные коды за последние минуты, для защиты от перебора. Частичный индекс
+-- по новому значению enum в той же миграции создать нельзя
+CREATE INDEX idx_intercom_calls_apartment_status ON intercom_calls(apartment_id, status, created_at);
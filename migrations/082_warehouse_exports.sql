@@ -0,0 +1,40 @@
+-- Регулярные выгрузки данных ЖК в хранилище для аналитики
+ALTER TYPE job_type ADD VALUE 'warehouse_export';
+
+CREATE TYPE warehouse_dataset AS ENUM ('bills', 'payments', 'maintenance', 'access_logs');
+CREATE TYPE warehouse_format AS ENUM ('csv', 'csv_gzip');
+
+CREATE TABLE warehouse_export_configs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    -- Префикс ключей в бакете, без «/» в начале и в конце
+    s3_prefix TEXT NOT NULL,
+    datasets warehouse_dataset[] NOT NULL,
+    format warehouse_format NOT NULL DEFAULT 'csv_gzip',
+    -- Версия набора колонок; входит в ключ файла, чтобы версии не смешивались
+    schema_version INT NOT NULL DEFAULT 1,
+    -- Час UTC, после которого выгружаются данные за прошедшие сутки
+    run_hour INT NOT NULL DEFAULT 2 CHECK (run_hour >= 0 AND run_hour <= 23),
+    is_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (complex_id, s3_prefix)
+);
+
+-- Выгрузка одних суток по настройке; повторный запуск перезаписывает те же файлы
+CREATE TABLE warehouse_export_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    config_id UUID NOT NULL REFERENCES warehouse_export_configs(id) ON DELETE CASCADE,
+    export_date DATE NOT NULL,
+    schema_version INT NOT NULL,
+    status export_status NOT NULL DEFAULT 'pending',
+    files JSONB NOT NULL DEFAULT '[]',
+    rows_count INT,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    UNIQUE (config_id, export_date)
+);
+
+CREATE INDEX idx_warehouse_export_runs_config ON warehouse_export_runs(config_id, export_date DESC);
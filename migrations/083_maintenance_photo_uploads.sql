@@ -0,0 +1,3 @@
+-- Кто загрузил фото к заявке: автор заявки, исполнитель или председатель
+ALTER TABLE maintenance_photos
+    ADD COLUMN uploaded_by UUID REFERENCES users(id) ON DELETE SET NULL;
@@ -0,0 +1,10 @@
+-- Доставка владельцу уведомления о въезде гостя: повторы SMS и замена на push
+ALTER TYPE job_type ADD VALUE 'guest_entry_notification';
+
+CREATE TYPE guest_notification_status AS ENUM ('sent', 'retrying', 'push_sent');
+
+ALTER TABLE guest_access
+    -- Пусто, пока гость не въехал
+    ADD COLUMN owner_notification_status guest_notification_status,
+    ADD COLUMN owner_notification_attempts INT NOT NULL DEFAULT 0,
+    ADD COLUMN owner_notification_error TEXT;
@@ -0,0 +1,50 @@
+-- Неизменяемый журнал движения денег: онлайн- и наличные платежи, исполнение расходов.
+-- Записи связаны цепочкой контрольных сумм; внешних ключей нет, чтобы удаление
+-- пользователя или ЖК не затрагивало журнал
+CREATE TYPE financial_event AS ENUM (
+    'payment_created',
+    'payment_processing',
+    'payment_completed',
+    'payment_failed',
+    'cash_payment_registered',
+    'expense_executed'
+);
+
+CREATE TABLE financial_audit_log (
+    seq BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    id UUID NOT NULL UNIQUE,
+    event financial_event NOT NULL,
+    complex_id UUID,
+    actor_id UUID,
+    request_id TEXT,
+    entity_type VARCHAR(50) NOT NULL,
+    entity_id UUID NOT NULL,
+    amount NUMERIC(14, 2),
+    -- Платёжный провайдер или способ оплаты и идентификатор операции у него
+    provider VARCHAR(50),
+    provider_reference TEXT,
+    details JSONB,
+    -- Сумма предыдущей записи; у первой записи пусто
+    prev_checksum CHAR(64),
+    checksum CHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX idx_financial_audit_complex ON financial_audit_log(complex_id, seq DESC);
+CREATE INDEX idx_financial_audit_entity ON financial_audit_log(entity_id);
+
+CREATE FUNCTION financial_audit_log_append_only() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'financial_audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER financial_audit_log_no_update
+    BEFORE UPDATE OR DELETE ON financial_audit_log
+    FOR EACH ROW EXECUTE FUNCTION financial_audit_log_append_only();
+
+CREATE TRIGGER financial_audit_log_no_truncate
+    BEFORE TRUNCATE ON financial_audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION financial_audit_log_append_only();
+
+REVOKE UPDATE, DELETE, TRUNCATE ON financial_audit_log FROM PUBLIC, CURRENT_USER;
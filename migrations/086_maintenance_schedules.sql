@@ -0,0 +1,44 @@
+-- Плановое обслуживание: повторяющиеся работы (осмотр лифта раз в полгода),
+-- по которым планировщик сам создаёт заявки
+CREATE TYPE maintenance_frequency AS ENUM ('daily', 'weekly', 'monthly', 'yearly');
+
+CREATE TABLE maintenance_schedules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    complex_id UUID NOT NULL REFERENCES complexes(id) ON DELETE CASCADE,
+    created_by UUID NOT NULL REFERENCES users(id),
+
+    -- Шаблон заявки
+    category maintenance_category NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    description TEXT,
+    location VARCHAR(200),
+    priority maintenance_priority NOT NULL DEFAULT 'normal',
+    assigned_to UUID REFERENCES osi_workers(id) ON DELETE SET NULL,
+
+    -- Повторение: каждые interval_count дней/недель/месяцев/лет начиная со starts_on
+    frequency maintenance_frequency NOT NULL,
+    interval_count INT NOT NULL DEFAULT 1 CHECK (interval_count BETWEEN 1 AND 365),
+    starts_on DATE NOT NULL,
+    ends_on DATE,
+    -- Дата ближайших работ; NULL — повторения закончились
+    next_run_on DATE,
+
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    last_request_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_on IS NULL OR ends_on >= starts_on)
+);
+
+CREATE INDEX idx_maintenance_schedules_complex ON maintenance_schedules(complex_id);
+CREATE INDEX idx_maintenance_schedules_due ON maintenance_schedules(next_run_on)
+    WHERE is_active AND next_run_on IS NOT NULL;
+
+-- Заявка, созданная по плану, и дата, на которую работы запланированы
+ALTER TABLE maintenance_requests
+    ADD COLUMN schedule_id UUID REFERENCES maintenance_schedules(id) ON DELETE SET NULL,
+    ADD COLUMN planned_for DATE;
+
+CREATE UNIQUE INDEX idx_maintenance_requests_schedule
+    ON maintenance_requests(schedule_id, planned_for)
+    WHERE schedule_id IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Домовой чат знает свой дом: при разделении ЖК он переезжает вместе с квартирами
+ALTER TABLE chats ADD COLUMN building VARCHAR(20);
+
+CREATE INDEX idx_chats_complex_building ON chats(complex_id, building)
+    WHERE chat_type = 'building';
@@ -0,0 +1,24 @@
+-- Стоимость работ по заявкам: материалы, часы работы и прочие траты
+CREATE TYPE maintenance_cost_kind AS ENUM ('material', 'labor', 'other');
+
+ALTER TABLE maintenance_requests
+    ADD COLUMN estimated_cost DECIMAL(12, 2) CHECK (estimated_cost >= 0),
+    -- Сумма позиций, пересчитывается при каждом изменении
+    ADD COLUMN total_cost DECIMAL(12, 2) NOT NULL DEFAULT 0;
+
+CREATE TABLE maintenance_cost_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    request_id UUID NOT NULL REFERENCES maintenance_requests(id) ON DELETE CASCADE,
+    kind maintenance_cost_kind NOT NULL,
+    description VARCHAR(200) NOT NULL,
+    -- Для работ — часы
+    quantity DECIMAL(10, 2) NOT NULL CHECK (quantity > 0),
+    unit VARCHAR(20),
+    unit_price DECIMAL(12, 2) NOT NULL CHECK (unit_price >= 0),
+    amount DECIMAL(12, 2) NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_maintenance_cost_items_request ON maintenance_cost_items(request_id);
@@ -0,0 +1,11 @@
+-- Каналы доставки по типам уведомлений: можно отключить push о барахолке,
+-- оставив оповещения о безопасности. Нет записи — все каналы включены
+CREATE TABLE notification_settings (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    notification_type notification_type NOT NULL,
+    push BOOLEAN NOT NULL DEFAULT true,
+    sms BOOLEAN NOT NULL DEFAULT true,
+    in_app BOOLEAN NOT NULL DEFAULT true,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, notification_type)
+);
@@ -0,0 +1,34 @@
+-- Живой поток уведомлений: каждый экземпляр API слушает канал и пересылает
+-- события подписанным клиентам, вместо того чтобы клиенты опрашивали счётчик
+CREATE FUNCTION notifications_publish_created()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'notification_events',
+        json_build_object('user_id', NEW.user_id, 'notification_id', NEW.id)::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notifications_created
+    AFTER INSERT ON notifications
+    FOR EACH ROW EXECUTE FUNCTION notifications_publish_created();
+
+-- Прочтение меняет только счётчик: одно событие на пользователя, даже при «прочитать все»
+CREATE FUNCTION notifications_publish_read()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'notification_events',
+        json_build_object('user_id', user_id, 'notification_id', NULL)::text
+    )
+    FROM (SELECT DISTINCT user_id FROM changed) u;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER notifications_read
+    AFTER UPDATE ON notifications
+    REFERENCING NEW TABLE AS changed
+    FOR EACH STATEMENT EXECUTE FUNCTION notifications_publish_read();
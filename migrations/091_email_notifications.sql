@@ -0,0 +1,20 @@
+-- Транзакционные письма о счетах, голосованиях и заявках: канал по подписке,
+-- без записи в настройках письма не отправляются
+ALTER TYPE job_type ADD VALUE 'email_notification';
+
+ALTER TABLE notification_settings ADD COLUMN email BOOLEAN NOT NULL DEFAULT false;
+
+CREATE TYPE email_status AS ENUM ('sent', 'failed');
+
+CREATE TABLE email_messages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) ON DELETE SET NULL,
+    email VARCHAR(255) NOT NULL,
+    template VARCHAR(50) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    status email_status NOT NULL,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_email_messages_user ON email_messages(user_id, created_at);
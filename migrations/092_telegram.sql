@@ -0,0 +1,21 @@
+-- Уведомления через Telegram-бота: привязка чата по коду из deep-link
+ALTER TYPE job_type ADD VALUE 'telegram_notification';
+
+ALTER TABLE notification_settings ADD COLUMN telegram BOOLEAN NOT NULL DEFAULT true;
+
+CREATE TABLE telegram_links (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    chat_id BIGINT NOT NULL UNIQUE,
+    username VARCHAR(64),
+    linked_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Одноразовые коды для команды /start; хранится только хэш
+CREATE TABLE telegram_link_codes (
+    code_hash VARCHAR(64) PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_telegram_link_codes_user ON telegram_link_codes(user_id);
@@ -0,0 +1,2 @@
+-- Через какого провайдера ушло SMS; у неотправленных — последний опробованный
+ALTER TABLE sms_messages ADD COLUMN provider VARCHAR(20);
@@ -0,0 +1,16 @@
+-- Отчёты провайдеров о доставке SMS и повторная отправка неудачных из админки.
+-- status остаётся результатом отправки, по нему считаются расходы ОСИ
+CREATE TYPE sms_delivery_status AS ENUM ('delivered', 'undelivered');
+
+ALTER TABLE sms_messages
+    -- Текст для повторной отправки; коды входа не сохраняются
+    ADD COLUMN text TEXT,
+    -- NULL, пока провайдер не прислал отчёт
+    ADD COLUMN delivery_status sms_delivery_status,
+    ADD COLUMN delivery_error TEXT,
+    ADD COLUMN delivery_updated_at TIMESTAMPTZ,
+    ADD COLUMN resent_at TIMESTAMPTZ;
+
+CREATE INDEX idx_sms_messages_provider_message ON sms_messages(provider, provider_message_id)
+    WHERE provider_message_id IS NOT NULL;
+CREATE INDEX idx_sms_messages_created ON sms_messages(created_at);
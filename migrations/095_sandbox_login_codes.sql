@@ -0,0 +1,3 @@
+-- Свой код входа у каждого демо-ЖК вместо общего для всех: председатель-партнёр
+-- входит по нему без SMS. Код показывается один раз при создании ЖК
+ALTER TABLE complexes ADD COLUMN sandbox_login_code VARCHAR(6);
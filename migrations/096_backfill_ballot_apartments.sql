@@ -0,0 +1,58 @@
+-- Бюллетени активных голосований, поданные до голосования по квартирам: один голос
+-- собственника с весом всех его квартир и квартирой первой из них (или без квартиры).
+-- Раскладываем такой голос по квартирам, чтобы за остальные нельзя было проголосовать
+-- ещё раз, а каждая квартира весила своей площадью. Счётчики пересчитывают триггеры.
+CREATE TEMP TABLE legacy_ballots ON COMMIT DROP AS
+SELECT
+    v.id AS vote_id,
+    a.id AS apartment_id,
+    COALESCE(a.area, 1) AS vote_weight,
+    ROW_NUMBER() OVER (
+        PARTITION BY v.id
+        ORDER BY a.id = v.apartment_id DESC, a.building NULLS FIRST, a.number
+    ) AS n
+FROM votes v
+JOIN votings vt ON vt.id = v.voting_id AND vt.status = 'active'
+JOIN apartments a ON a.complex_id = vt.complex_id AND a.owner_id = v.user_id
+WHERE NOT EXISTS (
+        SELECT 1 FROM votes other
+        WHERE other.voting_id = v.voting_id AND other.user_id = v.user_id AND other.id <> v.id
+    )
+  AND NOT EXISTS (
+        SELECT 1 FROM votes taken
+        WHERE taken.voting_id = v.voting_id AND taken.apartment_id = a.id AND taken.id <> v.id
+    )
+  AND (
+        v.apartment_id IS NULL
+        -- Прежний вес — суммарная площадь всех квартир собственника
+        OR (SELECT COUNT(*) > 1 AND v.vote_weight = COALESCE(SUM(own.area), 1)
+            FROM apartments own
+            WHERE own.complex_id = vt.complex_id AND own.owner_id = v.user_id)
+    );
+
+-- Исходный голос остаётся за первой квартирой
+UPDATE votes v
+SET apartment_id = l.apartment_id, vote_weight = l.vote_weight
+FROM legacy_ballots l
+WHERE v.id = l.vote_id AND l.n = 1;
+
+UPDATE vote_choices c
+SET vote_weight = l.vote_weight
+FROM legacy_ballots l
+WHERE c.vote_id = l.vote_id AND l.n = 1;
+
+-- За остальные квартиры — копии бюллетеня с теми же вариантами
+CREATE TEMP TABLE legacy_ballot_copies ON COMMIT DROP AS
+SELECT l.vote_id AS source_id, gen_random_uuid() AS id, l.apartment_id, l.vote_weight
+FROM legacy_ballots l
+WHERE l.n > 1;
+
+INSERT INTO votes (id, voting_id, option_id, user_id, apartment_id, vote_weight, proxy_id, delegation_id, created_at)
+SELECT c.id, v.voting_id, v.option_id, v.user_id, c.apartment_id, c.vote_weight, v.proxy_id, v.delegation_id, v.created_at
+FROM legacy_ballot_copies c
+JOIN votes v ON v.id = c.source_id;
+
+INSERT INTO vote_choices (vote_id, option_id, rank, vote_weight)
+SELECT c.id, ch.option_id, ch.rank, c.vote_weight
+FROM legacy_ballot_copies c
+JOIN vote_choices ch ON ch.vote_id = c.source_id;
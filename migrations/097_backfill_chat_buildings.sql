@@ -0,0 +1,21 @@
+-- Домовые чаты, созданные до 087, не знают своего дома. Дом берётся из названия
+-- («Дом 5», «Корпус №А»): последнее слово без «№» должно однозначно совпасть с домом
+-- квартир ЖК. В ЖК с единственным домом чат относится к нему
+UPDATE chats c
+SET building = matched.building
+FROM (
+    SELECT ch.id, MIN(a.building) AS building
+    FROM chats ch
+    JOIN (SELECT DISTINCT complex_id, building FROM apartments WHERE building IS NOT NULL) a
+        ON a.complex_id = ch.complex_id
+    WHERE ch.chat_type = 'building' AND ch.building IS NULL
+      AND (
+            LOWER(a.building) = LOWER(BTRIM(ch.name))
+            OR LOWER(a.building) = LOWER(LTRIM(SUBSTRING(ch.name FROM '(\S+)\s*$'), '№'))
+            OR (SELECT COUNT(DISTINCT x.building) FROM apartments x
+                WHERE x.complex_id = ch.complex_id AND x.building IS NOT NULL) = 1
+      )
+    GROUP BY ch.id
+    HAVING COUNT(DISTINCT a.building) = 1
+) matched
+WHERE c.id = matched.id;
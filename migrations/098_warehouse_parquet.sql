@@ -0,0 +1,2 @@
+-- Выгрузка в хранилище в формате Parquet: колонки те же, что в CSV, строками UTF-8
+ALTER TYPE warehouse_format ADD VALUE 'parquet';
@@ -0,0 +1,35 @@
+-- Телефоны работников, добавленных до нормализации, в E.164, как у пользователей
+-- (см. normalize_phone): иначе работник с номером «8 700 …» не привяжется при входе
+WITH parsed AS (
+    SELECT id,
+           regexp_replace(phone, '\D', '', 'g') AS digits,
+           CASE
+               WHEN btrim(phone) LIKE '+%' THEN regexp_replace(phone, '\D', '', 'g')
+               WHEN regexp_replace(phone, '\D', '', 'g') LIKE '00%'
+                   THEN substr(regexp_replace(phone, '\D', '', 'g'), 3)
+               WHEN length(regexp_replace(phone, '\D', '', 'g')) = 11
+                    AND left(regexp_replace(phone, '\D', '', 'g'), 1) IN ('7', '8')
+                   THEN '7' || substr(regexp_replace(phone, '\D', '', 'g'), 2)
+               WHEN length(regexp_replace(phone, '\D', '', 'g')) = 10
+                   THEN '7' || regexp_replace(phone, '\D', '', 'g')
+           END AS e164
+    FROM osi_workers
+    WHERE phone IS NOT NULL AND phone ~ '\d'
+)
+UPDATE osi_workers w
+SET phone = '+' || CASE
+        WHEN p.e164 IS NOT NULL
+             AND length(p.e164) BETWEEN 8 AND 15
+             AND p.e164 NOT LIKE '0%'
+             AND (p.e164 NOT LIKE '7%' OR length(p.e164) = 11)
+            THEN p.e164
+        ELSE p.digits
+    END
+FROM parsed p
+WHERE w.id = p.id;
+
+-- Работники, уже зарегистрированные в приложении, привязываются сразу, не дожидаясь входа
+UPDATE osi_workers w
+SET user_id = u.id
+FROM users u
+WHERE w.user_id IS NULL AND u.phone = w.phone;
@@ -0,0 +1,9 @@
+-- Коды домофона хранятся только хэшем (SHA-256 от «квартира:код»), сам код владелец
+-- видит один раз — при создании или смене
+ALTER TABLE apartment_intercom_codes ADD COLUMN code_hash VARCHAR(64);
+
+UPDATE apartment_intercom_codes
+SET code_hash = encode(sha256(convert_to(apartment_id::text || ':' || code, 'UTF8')), 'hex');
+
+ALTER TABLE apartment_intercom_codes ALTER COLUMN code_hash SET NOT NULL;
+ALTER TABLE apartment_intercom_codes DROP COLUMN code;
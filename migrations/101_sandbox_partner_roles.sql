@@ -0,0 +1,16 @@
+-- Партнёры демо-ЖК получали глобальную роль председателя. Председатель демо-ЖК
+-- определяется через osi.chairman_id, поэтому тем, кто председательствует только
+-- в демо-ЖК, возвращаем роль собственника
+UPDATE users u
+SET role = 'owner', updated_at = NOW()
+WHERE u.role = 'chairman'
+  AND EXISTS (
+      SELECT 1 FROM osi o
+      JOIN complexes c ON c.id = o.complex_id
+      WHERE o.chairman_id = u.id AND c.is_sandbox
+  )
+  AND NOT EXISTS (
+      SELECT 1 FROM osi o
+      JOIN complexes c ON c.id = o.complex_id
+      WHERE o.chairman_id = u.id AND NOT c.is_sandbox
+  );
@@ -6,12 +6,63 @@ use axum::{
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::{AppState, AuthUser};
-use crate::models::{Address, AddressResponse, CreateAddressRequest, SearchAddressQuery};
+use crate::models::{
+    Address, AddressResponse, CreateAddressRequest, DistrictQuery, RegistryDistrict,
+    RegistryStreet, SearchAddressQuery, StreetSearchQuery,
+};
+use crate::services::AddressRegistryService;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", post(create_address))
         .route("/search", get(search_addresses))
+        .route("/districts", get(list_districts))
+        .route("/streets", get(search_streets))
+}
+
+/// Районы города из классификатора
+async fn list_districts(
+    State(state): State<AppState>,
+    Query(query): Query<DistrictQuery>,
+) -> AppResult<Json<Vec<RegistryDistrict>>> {
+    let districts = sqlx::query_as::<_, RegistryDistrict>(
+        "SELECT * FROM registry_districts WHERE city_id = $1 ORDER BY name",
+    )
+    .bind(&query.city)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(districts))
+}
+
+/// Автодополнение улиц по адресному регистру
+async fn search_streets(
+    State(state): State<AppState>,
+    Query(query): Query<StreetSearchQuery>,
+) -> AppResult<Json<Vec<RegistryStreet>>> {
+    let query_text = query.query.trim();
+    if query_text.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
+
+    // Сначала улицы, название которых начинается с введённого текста
+    let streets = sqlx::query_as::<_, RegistryStreet>(
+        r#"
+        SELECT * FROM registry_streets
+        WHERE city_id = $1
+          AND ($3::uuid IS NULL OR district_id = $3)
+          AND (name ILIKE '%' || $2 || '%' OR name_kz ILIKE '%' || $2 || '%')
+        ORDER BY name ILIKE $2 || '%' DESC, name
+        LIMIT 20
+        "#,
+    )
+    .bind(&query.city)
+    .bind(query_text)
+    .bind(query.district_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(streets))
 }
 
 async fn search_addresses(
@@ -20,7 +71,7 @@ async fn search_addresses(
 ) -> AppResult<Json<Vec<AddressResponse>>> {
     let search_pattern = format!("%{}%", query.query);
 
-    let addresses = sqlx::query_as::<_, (uuid::Uuid, String, Option<String>, String, String, String)>(
+    let addresses = sqlx::query_as::<_, (uuid::Uuid, String, Option<String>, String, String, String, bool)>(
         r#"
         SELECT
             a.id,
@@ -28,12 +79,13 @@ async fn search_addresses(
             a.district,
             a.street,
             a.building,
-            c.name as city_name
+            c.name as city_name,
+            a.is_verified
         FROM addresses a
         JOIN cities c ON c.id = a.city_id
         WHERE a.city_id = $1
           AND (a.street ILIKE $2 OR a.building ILIKE $2)
-        ORDER BY a.street, a.building
+        ORDER BY a.is_verified DESC, a.street, a.building
         LIMIT 20
         "#,
     )
@@ -44,7 +96,7 @@ async fn search_addresses(
 
     let response: Vec<AddressResponse> = addresses
         .into_iter()
-        .map(|(id, city_id, district, street, building, city_name)| {
+        .map(|(id, city_id, district, street, building, city_name, is_verified)| {
             AddressResponse {
                 id,
                 city_id: city_id.clone(),
@@ -53,6 +105,7 @@ async fn search_addresses(
                 street: street.clone(),
                 building: building.clone(),
                 full_address: format!("г. {}, {}, {}", city_name, street, building),
+                is_verified,
             }
         })
         .collect();
@@ -77,56 +130,107 @@ async fn create_address(
         .ok_or_else(|| AppError::NotFound("Город не найден".to_string()))?
         .0;
 
+    // Улица из регистра: выбранная в автодополнении или совпавшая по названию
+    let registry_street = match payload.registry_street_id {
+        Some(street_id) => Some(
+            sqlx::query_as::<_, RegistryStreet>(
+                "SELECT * FROM registry_streets WHERE id = $1 AND city_id = $2",
+            )
+            .bind(street_id)
+            .bind(&payload.city_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Улица не найдена в регистре".to_string()))?,
+        ),
+        None => AddressRegistryService::match_street(&state.pool, &payload.city_id, &payload.street).await?,
+    };
+
+    let district = match &registry_street {
+        Some(RegistryStreet { district_id: Some(district_id), .. }) => {
+            sqlx::query_scalar::<_, String>("SELECT name FROM registry_districts WHERE id = $1")
+                .bind(district_id)
+                .fetch_optional(&state.pool)
+                .await?
+                .or_else(|| payload.district.clone())
+        }
+        _ => payload.district.clone(),
+    };
+    let street = registry_street
+        .as_ref()
+        .map(|street| street.name.clone())
+        .unwrap_or_else(|| payload.street.trim().to_string());
+    let building = payload.building.trim().to_string();
+
     // Проверяем, существует ли уже такой адрес
-    let existing: Option<(uuid::Uuid,)> = sqlx::query_as(
+    let existing: Option<Address> = sqlx::query_as(
         r#"
-        SELECT id FROM addresses
-        WHERE city_id = $1 AND street = $2 AND building = $3
+        SELECT * FROM addresses
+        WHERE city_id = $1 AND lower(street) = lower($2) AND lower(building) = lower($3)
+        ORDER BY is_verified DESC
+        LIMIT 1
         "#
     )
     .bind(&payload.city_id)
-    .bind(&payload.street)
-    .bind(&payload.building)
+    .bind(&street)
+    .bind(&building)
     .fetch_optional(&state.pool)
     .await?;
 
-    if let Some((id,)) = existing {
-        return Ok(Json(AddressResponse {
-            id,
-            city_id: payload.city_id.clone(),
-            city_name: Some(city_name.clone()),
-            district: payload.district.clone(),
-            street: payload.street.clone(),
-            building: payload.building.clone(),
-            full_address: format!("г. {}, {}, {}", city_name, payload.street, payload.building),
-        }));
-    }
-
-    // Создаём адрес
-    let address = sqlx::query_as::<_, Address>(
-        r#"
-        INSERT INTO addresses (city_id, district, street, building, postal_code, latitude, longitude)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING *
-        "#,
-    )
-    .bind(&payload.city_id)
-    .bind(&payload.district)
-    .bind(&payload.street)
-    .bind(&payload.building)
-    .bind(&payload.postal_code)
-    .bind(&payload.latitude)
-    .bind(&payload.longitude)
-    .fetch_one(&state.pool)
-    .await?;
+    let address = match existing {
+        // Найденный ранее вручную адрес подтверждается регистром
+        Some(address) if !address.is_verified && registry_street.is_some() => {
+            sqlx::query_as::<_, Address>(
+                r#"
+                UPDATE addresses SET
+                    registry_street_id = $2,
+                    is_verified = true,
+                    street = $3,
+                    district = COALESCE(district, $4)
+                WHERE id = $1
+                RETURNING *
+                "#,
+            )
+            .bind(address.id)
+            .bind(registry_street.as_ref().map(|street| street.id))
+            .bind(&street)
+            .bind(&district)
+            .fetch_one(&state.pool)
+            .await?
+        }
+        Some(address) => address,
+        None => {
+            sqlx::query_as::<_, Address>(
+                r#"
+                INSERT INTO addresses (
+                    city_id, district, street, building, postal_code, latitude, longitude,
+                    registry_street_id, is_verified
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING *
+                "#,
+            )
+            .bind(&payload.city_id)
+            .bind(&district)
+            .bind(&street)
+            .bind(&building)
+            .bind(&payload.postal_code)
+            .bind(payload.latitude)
+            .bind(payload.longitude)
+            .bind(registry_street.as_ref().map(|street| street.id))
+            .bind(registry_street.is_some())
+            .fetch_one(&state.pool)
+            .await?
+        }
+    };
 
     Ok(Json(AddressResponse {
         id: address.id,
-        city_id: address.city_id,
+        city_id: address.city_id.clone(),
         city_name: Some(city_name.clone()),
-        district: address.district,
+        district: address.district.clone(),
         street: address.street.clone(),
         building: address.building.clone(),
-        full_address: format!("г. {}, {}, {}", city_name, address.street, address.building),
+        full_address: address.full_address(&city_name),
+        is_verified: address.is_verified,
     }))
 }
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Json, Router,
 };
 use serde::Deserialize;
@@ -8,16 +8,38 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_admin_or_higher, AppState, AuthUser};
+use crate::middleware::{is_admin_or_higher, AppState, AuthUser, RequestId};
 use crate::models::{
-    ChairmanApplication, ChairmanApplicationStatus, Complex, ComplexStatus, User, UserRole,
+    AddressRegistryImportPayload, AdminIpBlock, LegalDocument, PublishLegalDocumentRequest, BannerSeverity, ChairmanApplication, Complex, ComplexVerification,
+    ComplexRestructureReport, ComplexVerificationResponse, ComplexVerificationStatus, Job, JobType, MaintenanceMode,
+    FinancialAuditEntryResponse, FinancialAuditVerification, FinancialEvent, MergeComplexRequest,
+    MigrationReport, SplitComplexRequest,
+    NewAuditLog, NewFieldChange, Paginated, RejectComplexRequest, RequestVerificationInfoRequest,
+    StartRegistryImportRequest, StartRegistryImportResponse, UpdateMaintenanceModeRequest,
+    UpdateVerificationChecklistRequest, User, UserRole, CreateBannerRequest, GlobalBanner,
+    UpdateBannerRequest, CreateSandboxComplexRequest, CreateWarehouseExportRequest,
+    RunWarehouseExportRequest, SandboxComplexCreated, UpdateWarehouseExportRequest, WarehouseExportConfig,
+    WarehouseExportRun, SmsDeliveryStatus, SmsMessage, SmsStatus,
 };
+use crate::services::{
+    AdminGuardService, AuditService, ComplexRestructureService, ComplexVerificationService, FieldHistoryService,
+    FinancialAuditFilter, FinancialAuditService, JobService, MigrationService, SandboxService, SettingsService, SmsService,
+    WarehouseExportService,
+};
+use crate::services::sms_service::RESENDABLE_SQL;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/dashboard", get(get_dashboard))
         .route("/complexes", get(list_complexes))
+        .route("/complexes/:id/verification", get(get_complex_verification))
+        .route("/complexes/:id/verification/checklist", put(update_verification_checklist))
+        .route("/complexes/:id/request-info", put(request_complex_info))
         .route("/complexes/:id/verify", put(verify_complex))
+        .route("/complexes/:id/reject", put(reject_complex))
+        .route("/complexes/:id/split", post(split_complex))
+        .route("/complexes/:id/merge", post(merge_complex))
+        .route("/sandbox-complexes", post(create_sandbox_complex))
         .route("/users", get(list_users))
         .route("/users/:id/block", put(block_user))
         .route("/users/:id/role", put(change_role))
@@ -25,6 +47,24 @@ pub fn routes() -> Router<AppState> {
         .route("/chairman-applications/:id/approve", put(approve_chairman))
         .route("/chairman-applications/:id/reject", put(reject_chairman))
         .route("/logs", get(get_logs))
+        .route("/jobs/dead", get(list_dead_jobs))
+        .route("/jobs/:id/retry", put(retry_job))
+        .route("/maintenance-mode", get(get_maintenance_mode).put(update_maintenance_mode))
+        .route("/migrations", get(get_migrations))
+        .route("/address-registry/import", post(start_registry_import))
+        .route("/banners", get(list_banners).post(create_banner))
+        .route("/banners/:id", put(update_banner))
+        .route("/banners/:id", delete(delete_banner))
+        .route("/ip-blocks", get(list_ip_blocks))
+        .route("/legal-documents", get(list_legal_documents).post(publish_legal_document))
+        .route("/ip-blocks/:id/lift", put(lift_ip_block))
+        .route("/warehouse-exports", get(list_warehouse_exports).post(create_warehouse_export))
+        .route("/warehouse-exports/:id", put(update_warehouse_export).delete(delete_warehouse_export))
+        .route("/warehouse-exports/:id/runs", get(list_warehouse_export_runs).post(run_warehouse_export))
+        .route("/financial-audit", get(list_financial_audit))
+        .route("/financial-audit/verify", get(verify_financial_audit))
+        .route("/sms", get(list_sms_messages))
+        .route("/sms/:id/resend", post(resend_sms))
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,6 +75,46 @@ struct PaginationQuery {
     query: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct WarehouseExportsQuery {
+    complex_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FinancialAuditQuery {
+    complex_id: Option<Uuid>,
+    entity_id: Option<Uuid>,
+    event: Option<FinancialEvent>,
+    from: Option<chrono::DateTime<chrono::Utc>>,
+    to: Option<chrono::DateTime<chrono::Utc>>,
+    page: Option<i64>,
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SmsMessagesQuery {
+    complex_id: Option<Uuid>,
+    phone: Option<String>,
+    kind: Option<String>,
+    status: Option<SmsStatus>,
+    delivery_status: Option<SmsDeliveryStatus>,
+    /// Только те, что можно отправить повторно
+    #[serde(default)]
+    resendable: bool,
+    page: Option<i64>,
+    limit: Option<i64>,
+}
+
+/// Заявка на роль председателя с заявителем и ЖК
+#[derive(sqlx::FromRow)]
+struct ChairmanApplicationRow {
+    #[sqlx(flatten)]
+    application: ChairmanApplication,
+    user_name: String,
+    user_phone: String,
+    complex_name: String,
+}
+
 fn check_admin(role: &UserRole) -> AppResult<()> {
     if !is_admin_or_higher(role) {
         return Err(AppError::Forbidden);
@@ -42,6 +122,13 @@ fn check_admin(role: &UserRole) -> AppResult<()> {
     Ok(())
 }
 
+fn check_super_admin(role: &UserRole) -> AppResult<()> {
+    if *role != UserRole::SuperAdmin {
+        return Err(AppError::Forbidden);
+    }
+    Ok(())
+}
+
 async fn get_dashboard(
     State(state): State<AppState>,
     auth_user: AuthUser,
@@ -114,13 +201,26 @@ async fn list_complexes(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Query(query): Query<PaginationQuery>,
-) -> AppResult<Json<Vec<Value>>> {
+) -> AppResult<Json<Paginated<Value>>> {
     check_admin(&auth_user.role)?;
 
     let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
+    let page = query.page.unwrap_or(0);
+    let offset = page * limit;
     let search = query.query.as_ref().map(|q| format!("%{}%", q));
 
+    let (total,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM complexes
+        WHERE ($1::varchar IS NULL OR status::text = $1)
+          AND ($2::varchar IS NULL OR name ILIKE $2)
+        "#
+    )
+    .bind(&query.status)
+    .bind(&search)
+    .fetch_one(&state.pool)
+    .await?;
+
     let complexes = sqlx::query_as::<_, Complex>(
         r#"
         SELECT * FROM complexes
@@ -148,25 +248,206 @@ async fn list_complexes(
         })
     }).collect();
 
-    Ok(Json(response))
+    Ok(Json(Paginated::new(response, page, limit, total)))
+}
+
+/// Чек-лист и документы проверки ЖК
+async fn get_complex_verification(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    check_admin(&auth_user.role)?;
+
+    let verification = ComplexVerificationService::load(&state.pool, id).await?;
+    let detail = ComplexVerificationService::detail(&state.pool, verification).await?;
+
+    Ok(Json(detail))
+}
+
+/// Загружает проверку, которая ещё не завершена
+async fn open_verification(
+    state: &AppState,
+    id: Uuid,
+) -> AppResult<ComplexVerification> {
+    let verification = ComplexVerificationService::load(&state.pool, id).await?;
+    if matches!(
+        verification.status,
+        ComplexVerificationStatus::Approved | ComplexVerificationStatus::Rejected
+    ) {
+        return Err(AppError::Conflict("Проверка ЖК уже завершена".to_string()));
+    }
+    Ok(verification)
+}
+
+async fn update_verification_checklist(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateVerificationChecklistRequest>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    check_admin(&auth_user.role)?;
+
+    let verification = open_verification(&state, id).await?;
+
+    let mut checklist = verification.checklist.clone();
+    if !checklist.is_object() {
+        checklist = json!({});
+    }
+    for mark in &payload.items {
+        checklist[mark.item.key()] = json!(mark.checked);
+    }
+
+    let updated = sqlx::query_as::<_, ComplexVerification>(
+        r#"
+        UPDATE complex_verifications
+        SET checklist = $2, reviewed_by = $3, updated_at = NOW()
+        WHERE complex_id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&checklist)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "update_verification_checklist",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: Some(verification.checklist),
+            new_value: Some(checklist),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    let detail = ComplexVerificationService::detail(&state.pool, updated).await?;
+    Ok(Json(detail))
+}
+
+/// Запросить у создателя ЖК недостающие сведения
+async fn request_complex_info(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RequestVerificationInfoRequest>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    check_admin(&auth_user.role)?;
+
+    let message = payload.message.trim();
+    if message.is_empty() {
+        return Err(AppError::Validation("Укажите, какие сведения нужны".to_string()));
+    }
+
+    let verification = open_verification(&state, id).await?;
+
+    let updated = sqlx::query_as::<_, ComplexVerification>(
+        r#"
+        UPDATE complex_verifications
+        SET status = 'needs_info', info_request = $2, reviewed_by = $3, updated_at = NOW()
+        WHERE complex_id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(message)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "request_complex_info",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": verification.status})),
+            new_value: Some(json!({"status": updated.status, "info_request": message})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    ComplexVerificationService::notify_creator(
+        &state.pool,
+        id,
+        "Для проверки ЖК нужны дополнительные сведения",
+        Some(message),
+    )
+    .await?;
+
+    let detail = ComplexVerificationService::detail(&state.pool, updated).await?;
+    Ok(Json(detail))
 }
 
 async fn verify_complex(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Value>> {
     check_admin(&auth_user.role)?;
 
-    sqlx::query(
+    let verification = open_verification(&state, id).await?;
+    let old_status = verification.status;
+    let detail = ComplexVerificationService::detail(&state.pool, verification).await?;
+    ComplexVerificationService::ensure_complete(&detail)?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let old = sqlx::query_as::<_, Complex>("SELECT * FROM complexes WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let complex = sqlx::query_as::<_, Complex>(
         r#"
         UPDATE complexes
         SET status = 'active', verified_at = NOW(), verified_by = $2
         WHERE id = $1
+        RETURNING *
         "#
     )
     .bind(id)
     .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id: id,
+            entity_type: "complex",
+            entity_id: id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!(old),
+            new_value: json!(complex),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    sqlx::query(
+        r#"
+        UPDATE complex_verifications
+        SET status = 'approved', reviewed_by = $2, reviewed_at = NOW(), updated_at = NOW()
+        WHERE complex_id = $1
+        "#,
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
     .execute(&state.pool)
     .await?;
 
@@ -196,20 +477,231 @@ async fn verify_complex(
     // Логируем
     log_admin_action(&state, auth_user.user_id, "verify_complex", "complex", id).await?;
 
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "verify_complex",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": old_status})),
+            new_value: Some(json!({
+                "status": ComplexVerificationStatus::Approved,
+                "evidence": detail.evidence.iter().map(|e| e.id).collect::<Vec<_>>(),
+            })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    ComplexVerificationService::notify_creator(&state.pool, id, "ЖК прошёл проверку", None)
+        .await?;
+
     Ok(Json(json!({"success": true})))
 }
 
+/// Отклонить ЖК с указанием причин
+async fn reject_complex(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RejectComplexRequest>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    check_admin(&auth_user.role)?;
+
+    let reasons: Vec<String> = payload
+        .reasons
+        .iter()
+        .map(|reason| reason.trim().to_string())
+        .filter(|reason| !reason.is_empty())
+        .collect();
+    if reasons.is_empty() {
+        return Err(AppError::Validation("Укажите причину отказа".to_string()));
+    }
+
+    let verification = open_verification(&state, id).await?;
+
+    let updated = sqlx::query_as::<_, ComplexVerification>(
+        r#"
+        UPDATE complex_verifications
+        SET status = 'rejected', rejection_reasons = $2, reviewed_by = $3,
+            reviewed_at = NOW(), updated_at = NOW()
+        WHERE complex_id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(&reasons)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let old = sqlx::query_as::<_, Complex>("SELECT * FROM complexes WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let complex = sqlx::query_as::<_, Complex>(
+        "UPDATE complexes SET status = 'inactive', updated_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id: id,
+            entity_type: "complex",
+            entity_id: id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!(old),
+            new_value: json!(complex),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    log_admin_action(&state, auth_user.user_id, "reject_complex", "complex", id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "reject_complex",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": verification.status})),
+            new_value: Some(json!({"status": updated.status, "reasons": reasons})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    ComplexVerificationService::notify_creator(
+        &state.pool,
+        id,
+        "ЖК не прошёл проверку",
+        Some(&reasons.join("\n")),
+    )
+    .await?;
+
+    let detail = ComplexVerificationService::detail(&state.pool, updated).await?;
+    Ok(Json(detail))
+}
+
+async fn fetch_complex(state: &AppState, id: Uuid) -> AppResult<Complex> {
+    sqlx::query_as::<_, Complex>("SELECT * FROM complexes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ЖК не найден".to_string()))
+}
+
+/// Выделить дома ЖК в новый ЖК вместе с квартирами, счётчиками, счетами и домовыми чатами
+async fn split_complex(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SplitComplexRequest>,
+) -> AppResult<Json<ComplexRestructureReport>> {
+    check_admin(&auth_user.role)?;
+
+    let source = fetch_complex(&state, id).await?;
+    let report = ComplexRestructureService::split(
+        &state.pool,
+        &source,
+        auth_user.user_id,
+        &request_id.0,
+        &payload,
+    )
+    .await?;
+
+    Ok(Json(report))
+}
+
+/// Перенести все квартиры ЖК в другой ЖК; исходный становится неактивным
+async fn merge_complex(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MergeComplexRequest>,
+) -> AppResult<Json<ComplexRestructureReport>> {
+    check_admin(&auth_user.role)?;
+
+    let source = fetch_complex(&state, id).await?;
+    let target = fetch_complex(&state, payload.target_complex_id).await?;
+    let report = ComplexRestructureService::merge(
+        &state.pool,
+        &source,
+        &target,
+        auth_user.user_id,
+        &request_id.0,
+        &payload,
+    )
+    .await?;
+
+    Ok(Json(report))
+}
+
+/// Демо-ЖК для партнёра с эталонными данными
+async fn create_sandbox_complex(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<CreateSandboxComplexRequest>,
+) -> AppResult<Json<SandboxComplexCreated>> {
+    check_admin(&auth_user.role)?;
+
+    let created = SandboxService::create(&state.pool, auth_user.user_id, &payload).await?;
+    let complex = &created.complex;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex.id),
+            action: "create_sandbox_complex",
+            entity_type: "complex",
+            entity_id: Some(complex.id),
+            old_value: None,
+            new_value: Some(json!({"name": complex.name, "chairman_phone": payload.chairman_phone})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(created))
+}
+
 async fn list_users(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Query(query): Query<PaginationQuery>,
-) -> AppResult<Json<Vec<Value>>> {
+) -> AppResult<Json<Paginated<Value>>> {
     check_admin(&auth_user.role)?;
 
     let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
+    let page = query.page.unwrap_or(0);
+    let offset = page * limit;
     let search = query.query.as_ref().map(|q| format!("%{}%", q));
 
+    let (total,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM users WHERE ($1::varchar IS NULL OR phone ILIKE $1 OR first_name ILIKE $1 OR last_name ILIKE $1)"
+    )
+    .bind(&search)
+    .fetch_one(&state.pool)
+    .await?;
+
     let users = sqlx::query_as::<_, User>(
         r#"
         SELECT * FROM users
@@ -237,7 +729,7 @@ async fn list_users(
         })
     }).collect();
 
-    Ok(Json(response))
+    Ok(Json(Paginated::new(response, page, limit, total)))
 }
 
 async fn block_user(
@@ -276,6 +768,7 @@ async fn block_user(
 async fn change_role(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Path(id): Path<Uuid>,
     Json(payload): Json<Value>,
 ) -> AppResult<Json<Value>> {
@@ -301,14 +794,35 @@ async fn change_role(
         _ => return Err(AppError::BadRequest("Неверная роль".to_string())),
     };
 
+    let (old_role,): (UserRole,) = sqlx::query_as("SELECT role FROM users WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Пользователь не найден".to_string()))?;
+
     sqlx::query("UPDATE users SET role = $2, updated_at = NOW() WHERE id = $1")
         .bind(id)
-        .bind(role)
+        .bind(&role)
         .execute(&state.pool)
         .await?;
 
     log_admin_action(&state, auth_user.user_id, "change_role", "user", id).await?;
 
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "change_role",
+            entity_type: "user",
+            entity_id: Some(id),
+            old_value: Some(json!({"role": old_role})),
+            new_value: Some(json!({"role": role})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
     Ok(Json(json!({"success": true})))
 }
 
@@ -322,11 +836,14 @@ async fn list_chairman_applications(
     let limit = query.limit.unwrap_or(50).min(100);
     let offset = query.page.unwrap_or(0) * limit;
 
-    let applications = sqlx::query_as::<_, ChairmanApplication>(
+    let applications = sqlx::query_as::<_, ChairmanApplicationRow>(
         r#"
-        SELECT * FROM chairman_applications
-        WHERE ($1::varchar IS NULL OR status::text = $1)
-        ORDER BY created_at DESC
+        SELECT a.*, u.display_name AS user_name, u.phone AS user_phone, c.name AS complex_name
+        FROM chairman_applications a
+        JOIN users u ON u.id = a.user_id
+        JOIN complexes c ON c.id = a.complex_id
+        WHERE ($1::varchar IS NULL OR a.status::text = $1)
+        ORDER BY a.created_at DESC
         LIMIT $2 OFFSET $3
         "#
     )
@@ -337,28 +854,15 @@ async fn list_chairman_applications(
     .await?;
 
     let mut response = Vec::new();
-    for app in applications {
-        let user: (String, String) = sqlx::query_as(
-            "SELECT COALESCE(first_name || ' ' || last_name, phone), phone FROM users WHERE id = $1"
-        )
-        .bind(app.user_id)
-        .fetch_one(&state.pool)
-        .await?;
-
-        let complex: (String,) = sqlx::query_as(
-            "SELECT name FROM complexes WHERE id = $1"
-        )
-        .bind(app.complex_id)
-        .fetch_one(&state.pool)
-        .await?;
-
+    for row in applications {
+        let app = row.application;
         response.push(json!({
             "id": app.id,
             "user_id": app.user_id,
-            "user_name": user.0,
-            "user_phone": user.1,
+            "user_name": row.user_name,
+            "user_phone": row.user_phone,
             "complex_id": app.complex_id,
-            "complex_name": complex.0,
+            "complex_name": row.complex_name,
             "motivation": app.motivation,
             "document_url": app.document_url,
             "status": format!("{:?}", app.status).to_lowercase(),
@@ -398,14 +902,40 @@ async fn approve_chairman(
     .await?;
 
     // Назначаем председателем
+    let mut tx = state.pool.begin().await?;
+
+    let previous: Option<(Uuid, Option<Uuid>)> =
+        sqlx::query_as("SELECT id, chairman_id FROM osi WHERE complex_id = $1 FOR UPDATE")
+            .bind(app.complex_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
     sqlx::query(
         "UPDATE osi SET chairman_id = $2 WHERE complex_id = $1"
     )
     .bind(app.complex_id)
     .bind(app.user_id)
-    .execute(&state.pool)
+    .execute(&mut *tx)
     .await?;
 
+    if let Some((osi_id, previous_id)) = previous {
+        FieldHistoryService::record(
+            &mut *tx,
+            NewFieldChange {
+                complex_id: app.complex_id,
+                entity_type: "osi",
+                entity_id: osi_id,
+                changed_by: Some(auth_user.user_id),
+                old_value: json!({"chairman_id": previous_id}),
+                new_value: json!({"chairman_id": app.user_id}),
+                request_id: None,
+            },
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
     // Обновляем роль пользователя
     sqlx::query("UPDATE users SET role = 'chairman' WHERE id = $1")
         .bind(app.user_id)
@@ -455,11 +985,12 @@ async fn get_logs(
     let limit = query.limit.unwrap_or(100).min(500);
     let offset = query.page.unwrap_or(0) * limit;
 
-    let logs = sqlx::query_as::<_, (Uuid, Option<Uuid>, String, Option<String>, Option<Uuid>, chrono::DateTime<chrono::Utc>)>(
+    let logs = sqlx::query_as::<_, (Uuid, Option<String>, String, Option<String>, Option<Uuid>, chrono::DateTime<chrono::Utc>)>(
         r#"
-        SELECT id, user_id, action, entity_type, entity_id, created_at
-        FROM admin_logs
-        ORDER BY created_at DESC
+        SELECT l.id, u.display_name, l.action, l.entity_type, l.entity_id, l.created_at
+        FROM admin_logs l
+        LEFT JOIN users u ON u.id = l.user_id
+        ORDER BY l.created_at DESC
         LIMIT $1 OFFSET $2
         "#
     )
@@ -469,19 +1000,7 @@ async fn get_logs(
     .await?;
 
     let mut response = Vec::new();
-    for (id, user_id, action, entity_type, entity_id, created_at) in logs {
-        let user_name = if let Some(uid) = user_id {
-            sqlx::query_as::<_, (String,)>(
-                "SELECT COALESCE(first_name || ' ' || last_name, phone) FROM users WHERE id = $1"
-            )
-            .bind(uid)
-            .fetch_optional(&state.pool)
-            .await?
-            .map(|(n,)| n)
-        } else {
-            None
-        };
-
+    for (id, user_name, action, entity_type, entity_id, created_at) in logs {
         response.push(json!({
             "id": id,
             "user_name": user_name,
@@ -495,6 +1014,628 @@ async fn get_logs(
     Ok(Json(response))
 }
 
+async fn list_dead_jobs(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<PaginationQuery>,
+) -> AppResult<Json<Vec<Job>>> {
+    check_admin(&auth_user.role)?;
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let jobs = sqlx::query_as::<_, Job>(
+        r#"
+        SELECT * FROM jobs
+        WHERE status = 'dead'
+          AND ($1::varchar IS NULL OR job_type::text = $1)
+        ORDER BY updated_at DESC
+        LIMIT $2 OFFSET $3
+        "#
+    )
+    .bind(&query.query)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(jobs))
+}
+
+async fn retry_job(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Job>> {
+    check_admin(&auth_user.role)?;
+
+    let job = JobService::retry(&state.pool, id).await?;
+
+    log_admin_action(&state, auth_user.user_id, "retry_job", "job", id).await?;
+
+    Ok(Json(job))
+}
+
+/// Запустить загрузку справочника адресов; выполняется фоновой задачей
+async fn start_registry_import(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<StartRegistryImportRequest>,
+) -> AppResult<Json<StartRegistryImportResponse>> {
+    check_admin(&auth_user.role)?;
+
+    let source_url = payload
+        .source_url
+        .or_else(|| state.config.address_registry_url.clone())
+        .filter(|url| !url.trim().is_empty())
+        .ok_or_else(|| AppError::BadRequest("Не указан адрес выгрузки регистра".to_string()))?;
+
+    let job_id = JobService::enqueue(
+        &state.pool,
+        JobType::AddressRegistryImport,
+        &AddressRegistryImportPayload { source_url },
+    )
+    .await?;
+
+    log_admin_action(&state, auth_user.user_id, "address_registry_import", "job", job_id).await?;
+
+    Ok(Json(StartRegistryImportResponse { job_id }))
+}
+
+async fn list_banners(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<GlobalBanner>>> {
+    check_super_admin(&auth_user.role)?;
+
+    let banners = sqlx::query_as::<_, GlobalBanner>(
+        "SELECT * FROM global_banners ORDER BY starts_at DESC LIMIT 200",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(banners))
+}
+
+fn validate_banner(
+    title: &str,
+    message: &str,
+    starts_at: chrono::DateTime<chrono::Utc>,
+    ends_at: Option<chrono::DateTime<chrono::Utc>>,
+) -> AppResult<()> {
+    if title.trim().is_empty() || title.chars().count() > 200 {
+        return Err(AppError::Validation("Заголовок от 1 до 200 символов".to_string()));
+    }
+    if message.trim().is_empty() {
+        return Err(AppError::Validation("Текст баннера не может быть пустым".to_string()));
+    }
+    if ends_at.is_some_and(|ends_at| ends_at <= starts_at) {
+        return Err(AppError::Validation("Окончание показа раньше начала".to_string()));
+    }
+    Ok(())
+}
+
+async fn create_banner(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<CreateBannerRequest>,
+) -> AppResult<Json<GlobalBanner>> {
+    check_super_admin(&auth_user.role)?;
+
+    let starts_at = payload.starts_at.unwrap_or_else(chrono::Utc::now);
+    validate_banner(&payload.title, &payload.message, starts_at, payload.ends_at)?;
+
+    let banner = sqlx::query_as::<_, GlobalBanner>(
+        r#"
+        INSERT INTO global_banners (
+            title, message, link_url, severity, target_cities, target_roles,
+            starts_at, ends_at, is_dismissible, send_push, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+    )
+    .bind(payload.title.trim())
+    .bind(payload.message.trim())
+    .bind(&payload.link_url)
+    .bind(payload.severity.unwrap_or(BannerSeverity::Info))
+    .bind(&payload.target_cities)
+    .bind(&payload.target_roles)
+    .bind(starts_at)
+    .bind(payload.ends_at)
+    .bind(payload.is_dismissible.unwrap_or(true))
+    .bind(payload.send_push)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    log_admin_action(&state, auth_user.user_id, "create_banner", "banner", banner.id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "create_banner",
+            entity_type: "banner",
+            entity_id: Some(banner.id),
+            old_value: None,
+            new_value: Some(json!(banner)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(banner))
+}
+
+async fn update_banner(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateBannerRequest>,
+) -> AppResult<Json<GlobalBanner>> {
+    check_super_admin(&auth_user.role)?;
+
+    let old = sqlx::query_as::<_, GlobalBanner>("SELECT * FROM global_banners WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Баннер не найден".to_string()))?;
+
+    let ends_at = payload.ends_at.apply(old.ends_at);
+    validate_banner(
+        payload.title.as_deref().unwrap_or(&old.title),
+        payload.message.as_deref().unwrap_or(&old.message),
+        payload.starts_at.unwrap_or(old.starts_at),
+        ends_at,
+    )?;
+
+    let banner = sqlx::query_as::<_, GlobalBanner>(
+        r#"
+        UPDATE global_banners SET
+            title = COALESCE($2, title),
+            message = COALESCE($3, message),
+            link_url = $4,
+            severity = COALESCE($5, severity),
+            target_cities = COALESCE($6, target_cities),
+            target_roles = COALESCE($7, target_roles),
+            starts_at = COALESCE($8, starts_at),
+            ends_at = $9,
+            is_dismissible = COALESCE($10, is_dismissible),
+            is_active = COALESCE($11, is_active),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(payload.title.as_deref().map(str::trim))
+    .bind(payload.message.as_deref().map(str::trim))
+    .bind(payload.link_url.apply(old.link_url.clone()))
+    .bind(payload.severity)
+    .bind(&payload.target_cities)
+    .bind(&payload.target_roles)
+    .bind(payload.starts_at)
+    .bind(ends_at)
+    .bind(payload.is_dismissible)
+    .bind(payload.is_active)
+    .fetch_one(&state.pool)
+    .await?;
+
+    log_admin_action(&state, auth_user.user_id, "update_banner", "banner", id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "update_banner",
+            entity_type: "banner",
+            entity_id: Some(id),
+            old_value: Some(json!(old)),
+            new_value: Some(json!(banner)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(banner))
+}
+
+async fn delete_banner(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    check_super_admin(&auth_user.role)?;
+
+    let old = sqlx::query_as::<_, GlobalBanner>("DELETE FROM global_banners WHERE id = $1 RETURNING *")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Баннер не найден".to_string()))?;
+
+    log_admin_action(&state, auth_user.user_id, "delete_banner", "banner", id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "delete_banner",
+            entity_type: "banner",
+            entity_id: Some(id),
+            old_value: Some(json!(old)),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Настройки выгрузок данных ЖК для аналитики; `complex_id` — только по одному ЖК
+async fn list_warehouse_exports(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<WarehouseExportsQuery>,
+) -> AppResult<Json<Vec<WarehouseExportConfig>>> {
+    check_super_admin(&auth_user.role)?;
+
+    Ok(Json(WarehouseExportService::list(&state.pool, query.complex_id).await?))
+}
+
+async fn create_warehouse_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<CreateWarehouseExportRequest>,
+) -> AppResult<Json<WarehouseExportConfig>> {
+    check_super_admin(&auth_user.role)?;
+
+    let config = WarehouseExportService::create(&state.pool, auth_user.user_id, &payload).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(config.complex_id),
+            action: "create_warehouse_export",
+            entity_type: "warehouse_export",
+            entity_id: Some(config.id),
+            old_value: None,
+            new_value: Some(json!(config)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(config))
+}
+
+async fn update_warehouse_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateWarehouseExportRequest>,
+) -> AppResult<Json<WarehouseExportConfig>> {
+    check_super_admin(&auth_user.role)?;
+
+    let (old, config) = WarehouseExportService::update(&state.pool, id, &payload).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(config.complex_id),
+            action: "update_warehouse_export",
+            entity_type: "warehouse_export",
+            entity_id: Some(id),
+            old_value: Some(json!(old)),
+            new_value: Some(json!(config)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(config))
+}
+
+async fn delete_warehouse_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    check_super_admin(&auth_user.role)?;
+
+    let old = WarehouseExportService::delete(&state.pool, id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(old.complex_id),
+            action: "delete_warehouse_export",
+            entity_type: "warehouse_export",
+            entity_id: Some(id),
+            old_value: Some(json!(old)),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Последние выгрузки по настройке со списком записанных файлов
+async fn list_warehouse_export_runs(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<WarehouseExportRun>>> {
+    check_super_admin(&auth_user.role)?;
+
+    Ok(Json(WarehouseExportService::runs(&state.pool, id).await?))
+}
+
+/// Выгрузить сутки вне расписания; файлы за эти сутки перезаписываются
+async fn run_warehouse_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RunWarehouseExportRequest>,
+) -> AppResult<Json<WarehouseExportRun>> {
+    check_super_admin(&auth_user.role)?;
+
+    let run = WarehouseExportService::trigger(
+        &state.pool,
+        id,
+        payload.date,
+        auth_user.user_id,
+        &request_id.0,
+    )
+    .await?;
+
+    Ok(Json(run))
+}
+
+/// Финансовый журнал с проверкой контрольной суммы каждой записи
+async fn list_financial_audit(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<FinancialAuditQuery>,
+) -> AppResult<Json<Vec<FinancialAuditEntryResponse>>> {
+    check_super_admin(&auth_user.role)?;
+
+    let limit = query.limit.unwrap_or(100).clamp(1, 500);
+    let offset = query.page.unwrap_or(0).max(0) * limit;
+    let filter = FinancialAuditFilter {
+        complex_id: query.complex_id,
+        entity_id: query.entity_id,
+        event: query.event,
+        from: query.from,
+        to: query.to,
+    };
+
+    let entries = FinancialAuditService::list(&state.pool, &filter, limit, offset).await?;
+
+    Ok(Json(
+        entries
+            .into_iter()
+            .map(|entry| FinancialAuditEntryResponse {
+                checksum_valid: FinancialAuditService::is_valid(&entry),
+                entry,
+            })
+            .collect(),
+    ))
+}
+
+/// Проверить цепочку контрольных сумм всего журнала
+async fn verify_financial_audit(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<FinancialAuditVerification>> {
+    check_super_admin(&auth_user.role)?;
+
+    let verification = FinancialAuditService::verify(&state.pool).await?;
+    if !verification.valid {
+        tracing::error!(
+            "Financial audit chain broken at seq {:?}",
+            verification.first_invalid_seq
+        );
+    }
+
+    Ok(Json(verification))
+}
+
+/// Блокировки IP за подбор доступа к админке; `status=active` — только действующие
+async fn list_ip_blocks(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<PaginationQuery>,
+) -> AppResult<Json<Vec<AdminIpBlock>>> {
+    check_super_admin(&auth_user.role)?;
+
+    let active_only = query.status.as_deref() == Some("active");
+
+    Ok(Json(AdminGuardService::list(&state.pool, active_only).await?))
+}
+
+async fn lift_ip_block(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<AdminIpBlock>> {
+    check_super_admin(&auth_user.role)?;
+
+    let block = AdminGuardService::lift(&state.pool, id, auth_user.user_id).await?;
+
+    log_admin_action(&state, auth_user.user_id, "lift_ip_block", "admin_ip_block", id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "lift_ip_block",
+            entity_type: "admin_ip_block",
+            entity_id: Some(id),
+            old_value: None,
+            new_value: Some(json!({"ip_address": block.ip_address})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(block))
+}
+
+/// Все версии юридических документов, новые сверху
+async fn list_legal_documents(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<LegalDocument>>> {
+    check_admin(&auth_user.role)?;
+
+    let documents = sqlx::query_as::<_, LegalDocument>(
+        "SELECT * FROM legal_documents ORDER BY published_at DESC",
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(documents))
+}
+
+/// Опубликовать новую версию документа; после публикации пользователи должны принять её заново
+async fn publish_legal_document(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<PublishLegalDocumentRequest>,
+) -> AppResult<Json<LegalDocument>> {
+    check_super_admin(&auth_user.role)?;
+
+    let version = payload.version.trim();
+    if version.is_empty() || payload.title.trim().is_empty() || payload.content.trim().is_empty() {
+        return Err(AppError::Validation(
+            "Версия, заголовок и текст документа обязательны".to_string(),
+        ));
+    }
+
+    let exists: Option<(Uuid,)> =
+        sqlx::query_as("SELECT id FROM legal_documents WHERE kind = $1 AND version = $2")
+            .bind(payload.kind)
+            .bind(version)
+            .fetch_optional(&state.pool)
+            .await?;
+    if exists.is_some() {
+        return Err(AppError::Conflict("Такая версия документа уже есть".to_string()));
+    }
+
+    let document = sqlx::query_as::<_, LegalDocument>(
+        r#"
+        INSERT INTO legal_documents (kind, version, title, content, published_at, created_by)
+        VALUES ($1, $2, $3, $4, COALESCE($5, NOW()), $6)
+        RETURNING *
+        "#,
+    )
+    .bind(payload.kind)
+    .bind(version)
+    .bind(payload.title.trim())
+    .bind(&payload.content)
+    .bind(payload.published_at)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    log_admin_action(&state, auth_user.user_id, "publish_legal_document", "legal_document", document.id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "publish_legal_document",
+            entity_type: "legal_document",
+            entity_id: Some(document.id),
+            old_value: None,
+            new_value: Some(json!({"kind": document.kind, "version": document.version})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(document))
+}
+
+/// Применённые миграции в сравнении со встроенными в сборку
+async fn get_migrations(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<MigrationReport>> {
+    check_admin(&auth_user.role)?;
+
+    let report = MigrationService::status(&state.pool).await?;
+    Ok(Json(report))
+}
+
+async fn get_maintenance_mode(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<MaintenanceMode>> {
+    check_admin(&auth_user.role)?;
+
+    let mode = SettingsService::maintenance_mode(&state.pool).await?;
+    Ok(Json(mode))
+}
+
+async fn update_maintenance_mode(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<UpdateMaintenanceModeRequest>,
+) -> AppResult<Json<MaintenanceMode>> {
+    check_admin(&auth_user.role)?;
+
+    if let (Some(starts_at), Some(ends_at)) = (payload.starts_at, payload.ends_at) {
+        if ends_at <= starts_at {
+            return Err(AppError::BadRequest(
+                "Окончание работ должно быть позже начала".to_string(),
+            ));
+        }
+    }
+
+    let mode = MaintenanceMode {
+        enabled: payload.enabled,
+        message: payload.message,
+        starts_at: payload.starts_at,
+        ends_at: payload.ends_at,
+    };
+
+    SettingsService::set_maintenance_mode(&state.pool, &mode, auth_user.user_id).await?;
+
+    sqlx::query(
+        "INSERT INTO admin_logs (user_id, action, entity_type, new_value) VALUES ($1, $2, $3, $4)"
+    )
+    .bind(auth_user.user_id)
+    .bind("update_maintenance_mode")
+    .bind("system_settings")
+    .bind(json!(mode))
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(mode))
+}
+
 async fn log_admin_action(
     state: &AppState,
     user_id: Uuid,
@@ -514,3 +1655,70 @@ async fn log_admin_action(
 
     Ok(())
 }
+
+/// Журнал SMS: отправка, доставка по отчётам провайдеров и повторные отправки
+async fn list_sms_messages(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<SmsMessagesQuery>,
+) -> AppResult<Json<Paginated<SmsMessage>>> {
+    check_admin(&auth_user.role)?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let page = query.page.unwrap_or(0).max(0);
+    let offset = page * limit;
+
+    let filter = format!(
+        r#"
+        WHERE ($1::uuid IS NULL OR m.complex_id = $1)
+          AND ($2::varchar IS NULL OR m.phone = $2)
+          AND ($3::varchar IS NULL OR m.kind = $3)
+          AND ($4::sms_status IS NULL OR m.status = $4)
+          AND ($5::sms_delivery_status IS NULL OR m.delivery_status = $5)
+          AND (NOT $6 OR ({}))
+        "#,
+        RESENDABLE_SQL
+    );
+
+    let (total,): (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM sms_messages m {}", filter))
+        .bind(query.complex_id)
+        .bind(&query.phone)
+        .bind(&query.kind)
+        .bind(query.status)
+        .bind(query.delivery_status)
+        .bind(query.resendable)
+        .fetch_one(&state.pool)
+        .await?;
+
+    let messages = sqlx::query_as::<_, SmsMessage>(&format!(
+        "SELECT m.* FROM sms_messages m {} ORDER BY m.created_at DESC LIMIT $7 OFFSET $8",
+        filter
+    ))
+    .bind(query.complex_id)
+    .bind(&query.phone)
+    .bind(&query.kind)
+    .bind(query.status)
+    .bind(query.delivery_status)
+    .bind(query.resendable)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(Paginated::new(messages, page, limit, total)))
+}
+
+/// Повторно отправить неотправленное или недоставленное SMS
+async fn resend_sms(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    check_admin(&auth_user.role)?;
+
+    SmsService::new(state.config.clone()).resend(&state.pool, id).await?;
+
+    log_admin_action(&state, auth_user.user_id, "resend_sms", "sms_message", id).await?;
+
+    Ok(Json(json!({"success": true})))
+}
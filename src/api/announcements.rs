@@ -1,18 +1,28 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     routing::{delete, get, post, put},
     Json, Router,
 };
+use chrono::Utc;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_chairman_or_higher, AppState, AuthUser};
+use crate::middleware::{get_user_complexes, resolve_complex, AppState, AuthUser, ComplexScope};
 use crate::models::{
-    Announcement, AnnouncementCategory, AnnouncementPriority, AnnouncementResponse,
-    CreateAnnouncementRequest, UpdateAnnouncementRequest,
+    Announcement, AnnouncementAttachment, AnnouncementCategory, AnnouncementPriority,
+    AnnouncementResponse, CreateAnnouncementRequest, MarkAnnouncementsReadRequest, Paginated,
+    Permission, UpdateAnnouncementRequest, ViewEntity,
 };
+use crate::services::file_service::{
+    validate_document_content_type, validate_image_content_type, MAX_DOCUMENT_SIZE,
+    MAX_IMAGE_SIZE,
+};
+use crate::services::{AnnouncementService, FileService, PermissionService, ViewService};
+
+/// Сколько файлов можно приложить к одному объявлению
+const MAX_ANNOUNCEMENT_ATTACHMENTS: i64 = 10;
 
 /// Успешный ответ
 #[derive(serde::Serialize, utoipa::ToSchema)]
@@ -20,6 +30,22 @@ pub struct SuccessResponse {
     pub success: bool,
 }
 
+/// Объявление с именем автора и отметкой о прочтении текущим пользователем
+#[derive(sqlx::FromRow)]
+struct AnnouncementRow {
+    #[sqlx(flatten)]
+    announcement: Announcement,
+    author_name: Option<String>,
+    is_read: bool,
+}
+
+/// Ответ на массовую отметку прочтения
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct MarkReadResponse {
+    pub success: bool,
+    pub count: i64,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_announcements))
@@ -27,31 +53,64 @@ pub fn routes() -> Router<AppState> {
         .route("/:id", get(get_announcement))
         .route("/:id", put(update_announcement))
         .route("/:id", delete(delete_announcement))
+        .route(
+            "/:id/attachments",
+            post(upload_attachments).layer(DefaultBodyLimit::max(MAX_DOCUMENT_SIZE)),
+        )
+        .route("/:id/attachments/:attachment_id", delete(delete_attachment))
         .route("/:id/read", post(mark_as_read))
+        .route("/read", post(mark_many_as_read))
+        .route("/read-all", post(mark_all_as_read))
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
-struct AnnouncementsQuery {
-    category: Option<String>,
-    page: Option<i64>,
-    limit: Option<i64>,
+pub struct AnnouncementsQuery {
+    pub category: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
 }
 
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
+/// Объявление, которое текущий пользователь может менять: автор или правление ОСИ этого ЖК
+async fn editable_announcement(
+    state: &AppState,
+    auth_user: &AuthUser,
+    id: Uuid,
+) -> AppResult<Announcement> {
+    let ann = sqlx::query_as::<_, Announcement>("SELECT * FROM announcements WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
 
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
+    if ann.author_id != auth_user.user_id {
+        PermissionService::require(&state.pool, auth_user, ann.complex_id, Permission::ManageOsi)
+            .await?;
+    }
+
+    Ok(ann)
+}
+
+/// Удалить файлы вложений из хранилища. Записи к этому моменту уже удалены,
+/// поэтому сбой хранилища только пишется в лог
+async fn delete_attachment_files(
+    state: &AppState,
+    attachments: &[AnnouncementAttachment],
+) -> AppResult<()> {
+    let file_service = FileService::new(&state.config).await?;
+    for attachment in attachments {
+        for url in std::iter::once(&attachment.file_url).chain(&attachment.thumbnail_url) {
+            if let Some(key) = file_service.get_key_from_url(url) {
+                if let Err(e) = file_service.delete_file(&key).await {
+                    tracing::warn!(
+                        "Failed to delete announcement attachment {}: {}",
+                        attachment.id,
+                        e
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
 /// Получить список объявлений
@@ -61,74 +120,98 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
     tag = "announcements",
     security(("bearer_auth" = [])),
     params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
         ("category" = Option<String>, Query, description = "Категория"),
         ("page" = Option<i64>, Query, description = "Номер страницы"),
         ("limit" = Option<i64>, Query, description = "Количество записей")
     ),
     responses(
-        (status = 200, description = "Список объявлений", body = Vec<AnnouncementResponse>),
+        (status = 200, description = "Список объявлений", body = PaginatedAnnouncements),
         (status = 401, description = "Не авторизован")
     )
 )]
 pub async fn list_announcements(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Query(query): Query<AnnouncementsQuery>,
-) -> AppResult<Json<Vec<AnnouncementResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+) -> AppResult<Json<Paginated<AnnouncementResponse>>> {
+    let complex_id = complex.complex_id()?;
 
     let limit = query.limit.unwrap_or(20).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
+    let page = query.page.unwrap_or(0);
+    let offset = page * limit;
 
-    let announcements = sqlx::query_as::<_, Announcement>(
+    let (total,): (i64,) = sqlx::query_as(
         r#"
-        SELECT * FROM announcements
+        SELECT COUNT(*) FROM announcements
         WHERE complex_id = $1
           AND is_published = true
           AND (expires_at IS NULL OR expires_at > NOW())
-        ORDER BY priority DESC, published_at DESC
-        LIMIT $2 OFFSET $3
+          AND ($2::varchar IS NULL OR category::text = $2)
         "#,
     )
     .bind(complex_id)
+    .bind(&query.category)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // Имя автора и отметку о прочтении берём тем же запросом
+    let rows = sqlx::query_as::<_, AnnouncementRow>(
+        r#"
+        SELECT a.*,
+               COALESCE(NULLIF(u.display_name, u.phone), 'Администратор') AS author_name,
+               EXISTS (
+                   SELECT 1 FROM announcement_reads r
+                   WHERE r.announcement_id = a.id AND r.user_id = $5
+               ) AS is_read
+        FROM announcements a
+        LEFT JOIN users u ON u.id = a.author_id
+        WHERE a.complex_id = $1
+          AND a.is_published = true
+          AND (a.expires_at IS NULL OR a.expires_at > NOW())
+          AND ($2::varchar IS NULL OR a.category::text = $2)
+        ORDER BY a.priority DESC, a.published_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(complex_id)
+    .bind(&query.category)
     .bind(limit)
     .bind(offset)
+    .bind(auth_user.user_id)
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for ann in announcements {
-        let author_name: Option<(String,)> = sqlx::query_as(
-            "SELECT COALESCE(first_name || ' ' || last_name, 'Администратор') FROM users WHERE id = $1"
-        )
-        .bind(ann.author_id)
-        .fetch_optional(&state.pool)
-        .await?;
-
-        let is_read: Option<(i32,)> = sqlx::query_as(
-            "SELECT 1 FROM announcement_reads WHERE announcement_id = $1 AND user_id = $2",
-        )
-        .bind(ann.id)
-        .bind(auth_user.user_id)
-        .fetch_optional(&state.pool)
-        .await?;
-
-        response.push(AnnouncementResponse {
-            id: ann.id,
-            title: ann.title,
-            content: ann.content,
-            category: ann.category,
-            priority: ann.priority,
-            image_url: ann.image_url,
-            author_name: author_name.map(|(n,)| n),
-            views_count: ann.views_count,
-            is_read: is_read.is_some(),
-            published_at: ann.published_at,
-            created_at: ann.created_at,
-        });
-    }
-
-    Ok(Json(response))
+    let ids: Vec<Uuid> = rows.iter().map(|row| row.announcement.id).collect();
+    let mut attachments = AnnouncementService::attachments(&state.pool, &ids).await?;
+
+    let response = rows
+        .into_iter()
+        .map(|row| {
+            let ann = row.announcement;
+            let view_stats = ann.view_stats_for(auth_user.user_id);
+
+            AnnouncementResponse {
+                id: ann.id,
+                title: ann.title,
+                content: ann.content,
+                category: ann.category,
+                priority: ann.priority,
+                image_url: ann.image_url,
+                author_name: row.author_name,
+                views_count: ann.views_count,
+                view_stats,
+                is_read: row.is_read,
+                published_at: ann.published_at,
+                publish_at: ann.publish_at,
+                attachments: attachments.remove(&ann.id).unwrap_or_default(),
+                created_at: ann.created_at,
+            }
+        })
+        .collect();
+
+    Ok(Json(Paginated::new(response, page, limit, total)))
 }
 
 /// Получить объявление по ID
@@ -149,23 +232,26 @@ pub async fn list_announcements(
 pub async fn get_announcement(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<AnnouncementResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
-
     let ann = sqlx::query_as::<_, Announcement>(
-        "SELECT * FROM announcements WHERE id = $1 AND complex_id = $2",
+        r#"
+        SELECT * FROM announcements
+        WHERE id = $1 AND complex_id = ANY($2) AND (is_published OR author_id = $3)
+        "#,
     )
     .bind(id)
-    .bind(complex_id)
+    .bind(&complex.complex_ids)
+    .bind(auth_user.user_id)
     .fetch_optional(&state.pool)
     .await?
     .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
 
-    sqlx::query("UPDATE announcements SET views_count = views_count + 1 WHERE id = $1")
-        .bind(id)
-        .execute(&state.pool)
-        .await?;
+    // Просмотры автора не учитываем
+    if ann.author_id != auth_user.user_id {
+        ViewService::record(ViewEntity::Announcement, id, auth_user.user_id);
+    }
 
     sqlx::query(
         r#"
@@ -180,12 +266,18 @@ pub async fn get_announcement(
     .await?;
 
     let author_name: Option<(String,)> = sqlx::query_as(
-        "SELECT COALESCE(first_name || ' ' || last_name, 'Администратор') FROM users WHERE id = $1",
+        "SELECT COALESCE(NULLIF(display_name, phone), 'Администратор') FROM users WHERE id = $1",
     )
     .bind(ann.author_id)
     .fetch_optional(&state.pool)
     .await?;
 
+    let view_stats = ann.view_stats_for(auth_user.user_id);
+    let attachments = AnnouncementService::attachments(&state.pool, &[ann.id])
+        .await?
+        .remove(&ann.id)
+        .unwrap_or_default();
+
     Ok(Json(AnnouncementResponse {
         id: ann.id,
         title: ann.title,
@@ -194,9 +286,12 @@ pub async fn get_announcement(
         priority: ann.priority,
         image_url: ann.image_url,
         author_name: author_name.map(|(n,)| n),
-        views_count: ann.views_count + 1,
+        views_count: ann.views_count,
+        view_stats,
         is_read: true,
         published_at: ann.published_at,
+        publish_at: ann.publish_at,
+        attachments,
         created_at: ann.created_at,
     }))
 }
@@ -210,8 +305,9 @@ pub async fn get_announcement(
     request_body = CreateAnnouncementRequest,
     responses(
         (status = 200, description = "Объявление создано", body = AnnouncementResponse),
+        (status = 400, description = "У пользователя несколько ЖК, укажите complex_id"),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Недостаточно прав")
+        (status = 403, description = "Нужно право управления ОСИ")
     )
 )]
 pub async fn create_announcement(
@@ -219,27 +315,19 @@ pub async fn create_announcement(
     auth_user: AuthUser,
     Json(payload): Json<CreateAnnouncementRequest>,
 ) -> AppResult<Json<AnnouncementResponse>> {
-    let complex_id: Option<(Uuid,)> =
-        sqlx::query_as("SELECT complex_id FROM osi WHERE chairman_id = $1")
-            .bind(auth_user.user_id)
-            .fetch_optional(&state.pool)
-            .await?;
+    let complex_id = resolve_complex(&state.pool, auth_user.user_id, payload.complex_id).await?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
 
-    let complex_id = complex_id.map(|(id,)| id).ok_or_else(|| {
-        if is_chairman_or_higher(&auth_user.role) {
-            AppError::BadRequest("complex_id требуется".to_string())
-        } else {
-            AppError::Forbidden
-        }
-    })?;
+    // Время в прошлом — просто публикация сейчас
+    let publish_at = payload.publish_at.filter(|publish_at| *publish_at > Utc::now());
 
     let ann = sqlx::query_as::<_, Announcement>(
         r#"
         INSERT INTO announcements (
             complex_id, title, content, category, priority,
-            image_url, expires_at, author_id, is_published, published_at
+            image_url, expires_at, author_id, is_published, published_at, publish_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, true, NOW())
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9 IS NULL, CASE WHEN $9 IS NULL THEN NOW() END, $9)
         RETURNING *
         "#,
     )
@@ -249,11 +337,18 @@ pub async fn create_announcement(
     .bind(payload.category.unwrap_or(AnnouncementCategory::General))
     .bind(payload.priority.unwrap_or(AnnouncementPriority::Normal))
     .bind(&payload.image_url)
-    .bind(&payload.expires_at)
+    .bind(payload.expires_at)
     .bind(auth_user.user_id)
+    .bind(publish_at)
     .fetch_one(&state.pool)
     .await?;
 
+    if ann.is_published {
+        AnnouncementService::announce(&state.pool, &ann).await?;
+    }
+
+    let view_stats = ann.view_stats_for(auth_user.user_id);
+
     Ok(Json(AnnouncementResponse {
         id: ann.id,
         title: ann.title,
@@ -263,8 +358,11 @@ pub async fn create_announcement(
         image_url: ann.image_url,
         author_name: None,
         views_count: 0,
+        view_stats,
         is_read: true,
         published_at: ann.published_at,
+        publish_at: ann.publish_at,
+        attachments: Vec::new(),
         created_at: ann.created_at,
     }))
 }
@@ -292,15 +390,7 @@ pub async fn update_announcement(
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateAnnouncementRequest>,
 ) -> AppResult<Json<AnnouncementResponse>> {
-    let ann = sqlx::query_as::<_, Announcement>("SELECT * FROM announcements WHERE id = $1")
-        .bind(id)
-        .fetch_optional(&state.pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
-
-    if ann.author_id != auth_user.user_id && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    let ann = editable_announcement(&state, &auth_user, id).await?;
 
     let updated = sqlx::query_as::<_, Announcement>(
         r#"
@@ -309,9 +399,11 @@ pub async fn update_announcement(
             content = COALESCE($3, content),
             category = COALESCE($4, category),
             priority = COALESCE($5, priority),
-            image_url = COALESCE($6, image_url),
+            image_url = $6,
             is_published = COALESCE($7, is_published),
-            expires_at = COALESCE($8, expires_at),
+            published_at = CASE WHEN $7 THEN COALESCE(published_at, NOW()) ELSE published_at END,
+            publish_at = CASE WHEN $7 IS NULL THEN publish_at END,
+            expires_at = $8,
             updated_at = NOW()
         WHERE id = $1
         RETURNING *
@@ -322,12 +414,23 @@ pub async fn update_announcement(
     .bind(&payload.content)
     .bind(&payload.category)
     .bind(&payload.priority)
-    .bind(&payload.image_url)
-    .bind(&payload.is_published)
-    .bind(&payload.expires_at)
+    .bind(payload.image_url.apply(ann.image_url.clone()))
+    .bind(payload.is_published)
+    .bind(payload.expires_at.apply(ann.expires_at))
     .fetch_one(&state.pool)
     .await?;
 
+    // Отложенное объявление, опубликованное вручную, рассылается сразу
+    if updated.is_published && ann.publish_at.is_some() {
+        AnnouncementService::announce(&state.pool, &updated).await?;
+    }
+
+    let view_stats = updated.view_stats_for(auth_user.user_id);
+    let attachments = AnnouncementService::attachments(&state.pool, &[updated.id])
+        .await?
+        .remove(&updated.id)
+        .unwrap_or_default();
+
     Ok(Json(AnnouncementResponse {
         id: updated.id,
         title: updated.title,
@@ -337,8 +440,11 @@ pub async fn update_announcement(
         image_url: updated.image_url,
         author_name: None,
         views_count: updated.views_count,
+        view_stats,
         is_read: true,
         published_at: updated.published_at,
+        publish_at: updated.publish_at,
+        attachments,
         created_at: updated.created_at,
     }))
 }
@@ -364,24 +470,185 @@ pub async fn delete_announcement(
     auth_user: AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Value>> {
-    let ann = sqlx::query_as::<_, Announcement>("SELECT * FROM announcements WHERE id = $1")
-        .bind(id)
-        .fetch_optional(&state.pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+    let ann = editable_announcement(&state, &auth_user, id).await?;
 
-    if ann.author_id != auth_user.user_id && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    let attachments = AnnouncementService::attachments(&state.pool, &[ann.id])
+        .await?
+        .remove(&ann.id)
+        .unwrap_or_default();
 
     sqlx::query("DELETE FROM announcements WHERE id = $1")
         .bind(id)
         .execute(&state.pool)
         .await?;
 
+    delete_attachment_files(&state, &attachments).await?;
+
     Ok(Json(json!({"success": true})))
 }
 
+/// Приложить к объявлению фото или документы, по одному в каждом поле `file`
+#[utoipa::path(
+    post,
+    path = "/api/v1/announcements/{id}/attachments",
+    tag = "announcements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Все вложения объявления", body = Vec<AnnouncementAttachment>),
+        (status = 400, description = "Недопустимый формат, размер или число файлов"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn upload_attachments(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<Vec<AnnouncementAttachment>>> {
+    let ann = editable_announcement(&state, &auth_user, id).await?;
+
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .ok_or_else(|| AppError::BadRequest("Content-Type отсутствует".to_string()))?
+            .to_string();
+
+        let is_image = validate_image_content_type(&content_type);
+        if !is_image && !validate_document_content_type(&content_type) {
+            return Err(AppError::BadRequest(
+                "Недопустимый формат файла".to_string(),
+            ));
+        }
+
+        let file_name = field.file_name().unwrap_or("file").to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        let max_size = if is_image { MAX_IMAGE_SIZE } else { MAX_DOCUMENT_SIZE };
+        if data.len() > max_size {
+            return Err(AppError::BadRequest("Файл слишком большой".to_string()));
+        }
+
+        files.push((file_name, content_type, is_image, data));
+    }
+
+    if files.is_empty() {
+        return Err(AppError::BadRequest("Файл не найден".to_string()));
+    }
+
+    let (existing,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM announcement_attachments WHERE announcement_id = $1")
+            .bind(ann.id)
+            .fetch_one(&state.pool)
+            .await?;
+
+    if existing + files.len() as i64 > MAX_ANNOUNCEMENT_ATTACHMENTS {
+        return Err(AppError::BadRequest(format!(
+            "К объявлению можно приложить не больше {} файлов",
+            MAX_ANNOUNCEMENT_ATTACHMENTS
+        )));
+    }
+
+    let file_service = FileService::new(&state.config)
+        .await?
+        .with_upload_queue(&state.pool);
+
+    for (file_name, content_type, is_image, data) in files {
+        let file_size = data.len() as i64;
+        let (file_url, thumbnail_url) = if is_image {
+            let image = file_service
+                .upload_image("announcements", &file_name, &content_type, data.to_vec())
+                .await?;
+            (image.url, Some(image.thumbnail_url))
+        } else {
+            let url = file_service
+                .upload_file("announcements", &file_name, &content_type, data.to_vec())
+                .await?;
+            (url, None)
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO announcement_attachments (
+                announcement_id, file_url, thumbnail_url, file_name, content_type, file_size, uploaded_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(ann.id)
+        .bind(&file_url)
+        .bind(&thumbnail_url)
+        .bind(&file_name)
+        .bind(&content_type)
+        .bind(file_size)
+        .bind(auth_user.user_id)
+        .execute(&state.pool)
+        .await?;
+    }
+
+    let attachments = AnnouncementService::attachments(&state.pool, &[ann.id])
+        .await?
+        .remove(&ann.id)
+        .unwrap_or_default();
+
+    Ok(Json(attachments))
+}
+
+/// Удалить вложение объявления
+#[utoipa::path(
+    delete,
+    path = "/api/v1/announcements/{id}/attachments/{attachment_id}",
+    tag = "announcements",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления"),
+        ("attachment_id" = Uuid, Path, description = "ID вложения")
+    ),
+    responses(
+        (status = 200, description = "Вложение удалено", body = SuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn delete_attachment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<SuccessResponse>> {
+    let ann = editable_announcement(&state, &auth_user, id).await?;
+
+    let attachment = sqlx::query_as::<_, AnnouncementAttachment>(
+        "DELETE FROM announcement_attachments WHERE id = $1 AND announcement_id = $2 RETURNING *",
+    )
+    .bind(attachment_id)
+    .bind(ann.id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Вложение не найдено".to_string()))?;
+
+    delete_attachment_files(&state, &[attachment]).await?;
+
+    Ok(Json(SuccessResponse { success: true }))
+}
+
 /// Отметить объявление как прочитанное
 #[utoipa::path(
     post,
@@ -415,3 +682,89 @@ pub async fn mark_as_read(
 
     Ok(Json(json!({"success": true})))
 }
+
+/// Отметить несколько объявлений как прочитанные
+#[utoipa::path(
+    post,
+    path = "/api/v1/announcements/read",
+    tag = "announcements",
+    security(("bearer_auth" = [])),
+    request_body = MarkAnnouncementsReadRequest,
+    responses(
+        (status = 200, description = "Объявления отмечены", body = MarkReadResponse),
+        (status = 400, description = "Слишком много объявлений"),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn mark_many_as_read(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<MarkAnnouncementsReadRequest>,
+) -> AppResult<Json<Value>> {
+    if payload.ids.len() > 500 {
+        return Err(AppError::BadRequest("Не более 500 объявлений за раз".to_string()));
+    }
+
+    // Отмечаем только опубликованные объявления из ЖК пользователя
+    let complexes = get_user_complexes(&state.pool, auth_user.user_id).await?;
+    let result = sqlx::query(
+        r#"
+        INSERT INTO announcement_reads (announcement_id, user_id)
+        SELECT a.id, $2
+        FROM announcements a
+        WHERE a.id = ANY($1)
+          AND a.complex_id = ANY($3)
+          AND a.is_published = true
+          AND (a.expires_at IS NULL OR a.expires_at > NOW())
+        ON CONFLICT (announcement_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(&payload.ids)
+    .bind(auth_user.user_id)
+    .bind(&complexes)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "count": result.rows_affected()
+    })))
+}
+
+/// Отметить все объявления как прочитанные
+#[utoipa::path(
+    post,
+    path = "/api/v1/announcements/read-all",
+    tag = "announcements",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Все объявления отмечены", body = MarkReadResponse),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn mark_all_as_read(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Value>> {
+    let complexes = get_user_complexes(&state.pool, auth_user.user_id).await?;
+    let result = sqlx::query(
+        r#"
+        INSERT INTO announcement_reads (announcement_id, user_id)
+        SELECT a.id, $1
+        FROM announcements a
+        WHERE a.complex_id = ANY($2)
+          AND a.is_published = true
+          AND (a.expires_at IS NULL OR a.expires_at > NOW())
+        ON CONFLICT (announcement_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(&complexes)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "count": result.rows_affected()
+    })))
+}
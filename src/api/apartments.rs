@@ -1,16 +1,29 @@
 use axum::{
     extract::{Path, State},
-    routing::{get, put},
+    routing::{get, post, put},
     Json, Router,
 };
+use chrono::{Datelike, Duration, Utc};
 use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_chairman_or_higher, AppState, AuthUser};
+use crate::middleware::{is_admin_or_higher, AppState, AuthUser, RequestId};
 use crate::models::{
-    JoinRequest, JoinRequestResponse, JoinRequestStatus, ReviewJoinRequestRequest, UserRole,
+    AcceptFamilyInvitationRequest, Apartment, CreateFamilyMemberRequest, CreateMoveOutRequest,
+    EmailTemplate, FamilyMember, FamilyRelation, InviteFamilyMemberRequest, JoinRequest, JoinRequestResponse,
+    JoinRequestStatus,
+    MoveOutChecklist, MoveOutStatus, NewAuditLog, Permission, ReviewJoinRequestRequest, UpdateFamilyMemberRequest, User,
+    UserRole,
 };
+use crate::services::{
+    auth_service::parse_allowed_phone,
+    AuditService, ChatService, EmailService, MoveOutService, PaymentService, PermissionService,
+    SmsService,
+};
+
+/// Сколько действует приглашение члена семьи
+const FAMILY_INVITE_TTL_DAYS: i64 = 7;
 
 /// Ответ на рассмотрение заявки
 #[derive(serde::Serialize, utoipa::ToSchema)]
@@ -19,10 +32,31 @@ pub struct ReviewResponse {
     pub message: String,
 }
 
+/// Заявка с именем и телефоном заявителя
+#[derive(sqlx::FromRow)]
+struct JoinRequestRow {
+    #[sqlx(flatten)]
+    request: JoinRequest,
+    user_name: Option<String>,
+    user_phone: Option<String>,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/join-requests", get(get_join_requests))
         .route("/join-requests/:id", put(review_join_request))
+        .route("/join-requests/:id/resubmit", post(resubmit_join_request))
+        .route("/family/accept", post(accept_family_invitation))
+        .route("/:id/family", get(list_family_members).post(add_family_member))
+        .route(
+            "/:id/family/:member_id",
+            put(update_family_member).delete(remove_family_member),
+        )
+        .route("/:id/family/:member_id/invite", post(invite_family_member))
+        .route(
+            "/:id/move-out",
+            get(get_move_out).post(schedule_move_out).delete(cancel_move_out),
+        )
 }
 
 /// Получение заявок на присоединение
@@ -53,17 +87,19 @@ pub async fn get_join_requests(
     .fetch_all(&state.pool)
     .await?;
 
-    if complex_ids.is_empty() && !is_chairman_or_higher(&auth_user.role) {
+    if complex_ids.is_empty() && !is_admin_or_higher(&auth_user.role) {
         return Err(AppError::Forbidden);
     }
 
     let ids: Vec<Uuid> = complex_ids.into_iter().map(|(id,)| id).collect();
 
-    let requests = sqlx::query_as::<_, JoinRequest>(
+    let requests = sqlx::query_as::<_, JoinRequestRow>(
         r#"
-        SELECT * FROM join_requests
-        WHERE complex_id = ANY($1) AND status = 'pending'
-        ORDER BY created_at DESC
+        SELECT r.*, u.display_name AS user_name, u.phone AS user_phone
+        FROM join_requests r
+        LEFT JOIN users u ON u.id = r.user_id
+        WHERE r.complex_id = ANY($1) AND r.status = 'pending'
+        ORDER BY r.submitted_at DESC
         "#,
     )
     .bind(&ids)
@@ -71,21 +107,12 @@ pub async fn get_join_requests(
     .await?;
 
     let mut response = Vec::new();
-    for req in requests {
-        let user_info: Option<(String, String)> = sqlx::query_as(
-            "SELECT COALESCE(first_name || ' ' || last_name, phone), phone FROM users WHERE id = $1"
-        )
-        .bind(req.user_id)
-        .fetch_optional(&state.pool)
-        .await?;
-
-        let (user_name, user_phone) = user_info.unwrap_or(("".to_string(), "".to_string()));
-
+    for JoinRequestRow { request: req, user_name, user_phone } in requests {
         response.push(JoinRequestResponse {
             id: req.id,
             user_id: req.user_id,
-            user_name: Some(user_name),
-            user_phone: Some(user_phone),
+            user_name: Some(user_name.unwrap_or_default()),
+            user_phone: Some(user_phone.unwrap_or_default()),
             complex_id: req.complex_id,
             apartment_number: req.apartment_number,
             building: req.building,
@@ -132,16 +159,8 @@ pub async fn review_join_request(
     .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
 
     // Проверяем права
-    let is_chairman: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM osi WHERE complex_id = $1 AND chairman_id = $2")
-            .bind(request.complex_id)
-            .bind(auth_user.user_id)
-            .fetch_optional(&state.pool)
-            .await?;
-
-    if is_chairman.is_none() && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, request.complex_id, Permission::ManageOsi)
+        .await?;
 
     if payload.approved {
         // Создаём или находим квартиру
@@ -159,6 +178,10 @@ pub async fn review_join_request(
         .fetch_one(&state.pool)
         .await?;
 
+        if let Some(building) = &request.building {
+            ChatService::ensure_building_chat(&state.pool, request.complex_id, building).await?;
+        }
+
         // Привязываем пользователя
         if request.is_owner {
             sqlx::query("UPDATE apartments SET owner_id = $1, updated_at = NOW() WHERE id = $2")
@@ -204,6 +227,21 @@ pub async fn review_join_request(
         .execute(&state.pool)
         .await?;
 
+        let (complex_name,): (String,) = sqlx::query_as("SELECT name FROM complexes WHERE id = $1")
+            .bind(request.complex_id)
+            .fetch_one(&state.pool)
+            .await?;
+        EmailService::enqueue(
+            &state.pool,
+            &[request.user_id],
+            EmailTemplate::JoinRequestApproved {
+                complex_name,
+                building: request.building.clone(),
+                apartment_number: request.apartment_number.clone(),
+            },
+        )
+        .await?;
+
         Ok(Json(json!({
             "success": true,
             "message": "Заявка одобрена"
@@ -229,3 +267,661 @@ pub async fn review_join_request(
         })))
     }
 }
+
+/// Повторно подать заявку, закрытую автоматически за отсутствием рассмотрения
+#[utoipa::path(
+    post,
+    path = "/api/v1/apartments/join-requests/{id}/resubmit",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    responses(
+        (status = 200, description = "Заявка снова на рассмотрении", body = JoinRequest),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Заявка или ЖК не найдены"),
+        (status = 409, description = "Заявка не закрывалась по сроку или уже есть активная")
+    )
+)]
+pub async fn resubmit_join_request(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<JoinRequest>> {
+    let request = sqlx::query_as::<_, JoinRequest>(
+        "SELECT * FROM join_requests WHERE id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
+
+    if request.status != JoinRequestStatus::Rejected || request.expired_at.is_none() {
+        return Err(AppError::Conflict(
+            "Повторно подать можно только заявку, закрытую по сроку".to_string(),
+        ));
+    }
+
+    let complex_exists: Option<(i32,)> =
+        sqlx::query_as("SELECT 1 FROM complexes WHERE id = $1 AND status = 'active'")
+            .bind(request.complex_id)
+            .fetch_optional(&state.pool)
+            .await?;
+    if complex_exists.is_none() {
+        return Err(AppError::NotFound("ЖК не найден".to_string()));
+    }
+
+    let existing_request: Option<(Uuid,)> = sqlx::query_as(
+        "SELECT id FROM join_requests WHERE user_id = $1 AND complex_id = $2 AND status = 'pending'",
+    )
+    .bind(auth_user.user_id)
+    .bind(request.complex_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    if existing_request.is_some() {
+        return Err(AppError::Conflict(
+            "У вас уже есть активная заявка".to_string(),
+        ));
+    }
+
+    let updated = sqlx::query_as::<_, JoinRequest>(
+        r#"
+        UPDATE join_requests
+        SET status = 'pending', rejection_reason = NULL, reviewed_by = NULL, reviewed_at = NULL,
+            expired_at = NULL, expiry_reminded_at = NULL, submitted_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(updated.complex_id),
+            action: "resubmit_join_request",
+            entity_type: "join_request",
+            entity_id: Some(updated.id),
+            old_value: Some(json!({"status": request.status, "expired_at": request.expired_at})),
+            new_value: Some(json!({"status": updated.status})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(updated))
+}
+
+/// Квартира и право управлять её семьёй: владельцу можно всё, жителям и председателю — смотреть
+async fn apartment_access(
+    state: &AppState,
+    apartment_id: Uuid,
+    auth_user: &AuthUser,
+) -> AppResult<(Apartment, bool)> {
+    let apartment = sqlx::query_as::<_, Apartment>("SELECT * FROM apartments WHERE id = $1")
+        .bind(apartment_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+    if apartment.owner_id == Some(auth_user.user_id) {
+        return Ok((apartment, true));
+    }
+
+    let can_view: Option<(i32,)> = sqlx::query_as(
+        r#"
+        SELECT 1 WHERE $3
+        UNION SELECT 1 FROM osi WHERE complex_id = $1 AND chairman_id = $2
+        UNION SELECT 1 FROM family_members WHERE apartment_id = $4 AND user_id = $2
+        "#,
+    )
+    .bind(apartment.complex_id)
+    .bind(auth_user.user_id)
+    .bind(apartment.resident_id == Some(auth_user.user_id))
+    .bind(apartment.id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    match can_view {
+        Some(_) => Ok((apartment, false)),
+        None => Err(AppError::NotFound("Квартира не найдена".to_string())),
+    }
+}
+
+fn validate_family_member(first_name: Option<&str>, birth_year: Option<i32>) -> AppResult<()> {
+    if first_name.is_some_and(|name| name.trim().is_empty()) {
+        return Err(AppError::Validation("Укажите имя".to_string()));
+    }
+    if birth_year.is_some_and(|year| year < 1900 || year > Utc::now().year()) {
+        return Err(AppError::Validation("Неверный год рождения".to_string()));
+    }
+    Ok(())
+}
+
+/// Члены семьи квартиры
+#[utoipa::path(
+    get,
+    path = "/api/v1/apartments/{id}/family",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры")
+    ),
+    responses(
+        (status = 200, description = "Члены семьи", body = Vec<FamilyMember>),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Квартира не найдена")
+    )
+)]
+pub async fn list_family_members(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<FamilyMember>>> {
+    apartment_access(&state, id, &auth_user).await?;
+
+    let members = sqlx::query_as::<_, FamilyMember>(
+        "SELECT * FROM family_members WHERE apartment_id = $1 ORDER BY created_at",
+    )
+    .bind(id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(members))
+}
+
+/// Добавить члена семьи (только владелец квартиры)
+#[utoipa::path(
+    post,
+    path = "/api/v1/apartments/{id}/family",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры")
+    ),
+    request_body = CreateFamilyMemberRequest,
+    responses(
+        (status = 200, description = "Член семьи добавлен", body = FamilyMember),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только владельцу квартиры"),
+        (status = 404, description = "Квартира не найдена"),
+        (status = 422, description = "Неверные данные")
+    )
+)]
+pub async fn add_family_member(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateFamilyMemberRequest>,
+) -> AppResult<Json<FamilyMember>> {
+    let (apartment, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    if !is_owner {
+        return Err(AppError::Forbidden);
+    }
+    validate_family_member(Some(&payload.first_name), payload.birth_year)?;
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        r#"
+        INSERT INTO family_members (apartment_id, first_name, last_name, relation, birth_year, photo_url, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(payload.first_name.trim())
+    .bind(&payload.last_name)
+    .bind(payload.relation.unwrap_or(FamilyRelation::Other))
+    .bind(payload.birth_year)
+    .bind(&payload.photo_url)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(apartment.complex_id),
+            action: "add_family_member",
+            entity_type: "family_member",
+            entity_id: Some(member.id),
+            old_value: None,
+            new_value: Some(json!({"apartment_id": id, "relation": member.relation})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(member))
+}
+
+/// Изменить профиль члена семьи
+#[utoipa::path(
+    put,
+    path = "/api/v1/apartments/{id}/family/{member_id}",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры"),
+        ("member_id" = Uuid, Path, description = "ID члена семьи")
+    ),
+    request_body = UpdateFamilyMemberRequest,
+    responses(
+        (status = 200, description = "Профиль обновлён", body = FamilyMember),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только владельцу квартиры"),
+        (status = 404, description = "Не найдено"),
+        (status = 422, description = "Неверные данные")
+    )
+)]
+pub async fn update_family_member(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateFamilyMemberRequest>,
+) -> AppResult<Json<FamilyMember>> {
+    let (_, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    if !is_owner {
+        return Err(AppError::Forbidden);
+    }
+    validate_family_member(payload.first_name.as_deref(), payload.birth_year.value().copied())?;
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        "SELECT * FROM family_members WHERE id = $1 AND apartment_id = $2",
+    )
+    .bind(member_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Член семьи не найден".to_string()))?;
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        r#"
+        UPDATE family_members SET
+            first_name = COALESCE($2, first_name),
+            last_name = $3,
+            relation = COALESCE($4, relation),
+            birth_year = $5,
+            photo_url = $6,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(member.id)
+    .bind(payload.first_name.as_deref().map(str::trim))
+    .bind(payload.last_name.apply(member.last_name))
+    .bind(payload.relation)
+    .bind(payload.birth_year.apply(member.birth_year))
+    .bind(payload.photo_url.apply(member.photo_url))
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(member))
+}
+
+/// Удалить члена семьи; его аккаунт, если был, теряет доступ к квартире
+#[utoipa::path(
+    delete,
+    path = "/api/v1/apartments/{id}/family/{member_id}",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры"),
+        ("member_id" = Uuid, Path, description = "ID члена семьи")
+    ),
+    responses(
+        (status = 200, description = "Член семьи удалён", body = ReviewResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только владельцу квартиры"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn remove_family_member(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    let (apartment, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    if !is_owner {
+        return Err(AppError::Forbidden);
+    }
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        "DELETE FROM family_members WHERE id = $1 AND apartment_id = $2 RETURNING *",
+    )
+    .bind(member_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Член семьи не найден".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(apartment.complex_id),
+            action: "remove_family_member",
+            entity_type: "family_member",
+            entity_id: Some(member_id),
+            old_value: Some(json!(member)),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Член семьи удалён"
+    })))
+}
+
+/// Пригласить члена семьи завести аккаунт: по SMS приходит ссылка с кодом приглашения
+#[utoipa::path(
+    post,
+    path = "/api/v1/apartments/{id}/family/{member_id}/invite",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры"),
+        ("member_id" = Uuid, Path, description = "ID члена семьи")
+    ),
+    request_body = InviteFamilyMemberRequest,
+    responses(
+        (status = 200, description = "Приглашение отправлено", body = FamilyMember),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только владельцу квартиры"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "У члена семьи уже есть аккаунт"),
+        (status = 422, description = "Неверный номер телефона")
+    )
+)]
+pub async fn invite_family_member(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<InviteFamilyMemberRequest>,
+) -> AppResult<Json<FamilyMember>> {
+    let (apartment, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    if !is_owner {
+        return Err(AppError::Forbidden);
+    }
+
+    let phone = parse_allowed_phone(&payload.phone, &state.config)?.e164;
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        "SELECT * FROM family_members WHERE id = $1 AND apartment_id = $2",
+    )
+    .bind(member_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Член семьи не найден".to_string()))?;
+
+    if member.user_id.is_some() {
+        return Err(AppError::Conflict(
+            "У члена семьи уже есть аккаунт".to_string(),
+        ));
+    }
+
+    let token = PaymentService::generate_verification_token();
+    let member = sqlx::query_as::<_, FamilyMember>(
+        r#"
+        UPDATE family_members
+        SET phone = $2, invite_token = $3, invite_expires_at = $4, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(member.id)
+    .bind(&phone)
+    .bind(&token)
+    .bind(Utc::now() + Duration::days(FAMILY_INVITE_TTL_DAYS))
+    .fetch_one(&state.pool)
+    .await?;
+
+    let link = format!(
+        "{}/family-invite/{}",
+        state.config.public_url.trim_end_matches('/'),
+        token
+    );
+    SmsService::new(state.config.clone())
+        .send_family_invitation(&state.pool, apartment.complex_id, &phone, &link)
+        .await?;
+
+    Ok(Json(member))
+}
+
+/// Принять приглашение: профиль члена семьи привязывается к аккаунту с тем же номером
+#[utoipa::path(
+    post,
+    path = "/api/v1/apartments/family/accept",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    request_body = AcceptFamilyInvitationRequest,
+    responses(
+        (status = 200, description = "Аккаунт привязан к квартире", body = FamilyMember),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Приглашение выдано на другой номер"),
+        (status = 404, description = "Приглашение не найдено или истекло")
+    )
+)]
+pub async fn accept_family_invitation(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<AcceptFamilyInvitationRequest>,
+) -> AppResult<Json<FamilyMember>> {
+    let member = sqlx::query_as::<_, FamilyMember>(
+        r#"
+        SELECT * FROM family_members
+        WHERE invite_token = $1 AND invite_expires_at > NOW() AND user_id IS NULL
+        "#,
+    )
+    .bind(payload.token.trim())
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Приглашение не найдено или истекло".to_string()))?;
+
+    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+        .bind(auth_user.user_id)
+        .fetch_one(&state.pool)
+        .await?;
+    if member.phone.as_deref() != Some(user.phone.as_str()) {
+        return Err(AppError::Forbidden);
+    }
+
+    let member = sqlx::query_as::<_, FamilyMember>(
+        r#"
+        UPDATE family_members
+        SET user_id = $2, invite_token = NULL, invite_expires_at = NULL, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(member.id)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    sqlx::query("UPDATE users SET role = $1 WHERE id = $2 AND role = 'user'")
+        .bind(UserRole::Resident)
+        .bind(auth_user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    let (complex_id,): (Uuid,) = sqlx::query_as("SELECT complex_id FROM apartments WHERE id = $1")
+        .bind(member.apartment_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "accept_family_invitation",
+            entity_type: "family_member",
+            entity_id: Some(member.id),
+            old_value: None,
+            new_value: Some(json!({"apartment_id": member.apartment_id})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(member))
+}
+
+/// Выезд жильца и чек-лист итоговых показаний
+#[utoipa::path(
+    get,
+    path = "/api/v1/apartments/{id}/move-out",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры")
+    ),
+    responses(
+        (status = 200, description = "Назначенный или последний выезд", body = MoveOutChecklist),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Квартира или выезд не найдены")
+    )
+)]
+pub async fn get_move_out(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<MoveOutChecklist>> {
+    apartment_access(&state, id, &auth_user).await?;
+
+    let move_out = MoveOutService::latest(&state.pool, id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Выезд не назначен".to_string()))?;
+
+    Ok(Json(MoveOutService::checklist(&state.pool, move_out).await?))
+}
+
+/// Назначить выезд жильца: председатель получает уведомление, в день выезда доступы снимаются
+#[utoipa::path(
+    post,
+    path = "/api/v1/apartments/{id}/move-out",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры")
+    ),
+    request_body = CreateMoveOutRequest,
+    responses(
+        (status = 200, description = "Выезд назначен", body = MoveOutChecklist),
+        (status = 400, description = "В квартире не указан жилец"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно владельцу или жильцу квартиры"),
+        (status = 404, description = "Квартира не найдена"),
+        (status = 409, description = "Выезд уже назначен"),
+        (status = 422, description = "Дата выезда уже прошла")
+    )
+)]
+pub async fn schedule_move_out(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CreateMoveOutRequest>,
+) -> AppResult<Json<MoveOutChecklist>> {
+    let (apartment, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    if !is_owner && apartment.resident_id != Some(auth_user.user_id) {
+        return Err(AppError::Forbidden);
+    }
+
+    let move_out = MoveOutService::schedule(&state.pool, &apartment, auth_user.user_id, &payload).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(apartment.complex_id),
+            action: "schedule_move_out",
+            entity_type: "move_out",
+            entity_id: Some(move_out.id),
+            old_value: None,
+            new_value: Some(json!({
+                "apartment_id": id,
+                "user_id": move_out.user_id,
+                "effective_date": move_out.effective_date,
+            })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(MoveOutService::checklist(&state.pool, move_out).await?))
+}
+
+/// Отменить назначенный выезд
+#[utoipa::path(
+    delete,
+    path = "/api/v1/apartments/{id}/move-out",
+    tag = "apartments",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID квартиры")
+    ),
+    responses(
+        (status = 200, description = "Выезд отменён", body = ReviewResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав"),
+        (status = 404, description = "Назначенный выезд не найден")
+    )
+)]
+pub async fn cancel_move_out(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let (apartment, is_owner) = apartment_access(&state, id, &auth_user).await?;
+    let is_resident = apartment.resident_id == Some(auth_user.user_id);
+    if !is_owner
+        && !is_resident
+        && !PermissionService::has(&state.pool, &auth_user, apartment.complex_id, Permission::ManageOsi)
+            .await?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let scheduled = MoveOutService::latest(&state.pool, id)
+        .await?
+        .filter(|move_out| move_out.status == MoveOutStatus::Scheduled)
+        .ok_or_else(|| AppError::NotFound("Назначенный выезд не найден".to_string()))?;
+    let move_out = MoveOutService::cancel(&state.pool, scheduled.id, auth_user.user_id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(apartment.complex_id),
+            action: "cancel_move_out",
+            entity_type: "move_out",
+            entity_id: Some(move_out.id),
+            old_value: Some(json!({"effective_date": move_out.effective_date})),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Выезд отменён"
+    })))
+}
@@ -0,0 +1,167 @@
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{is_admin_or_higher, AppState, AuthUser};
+use crate::models::{AuditLogResponse, AuditLogsQuery, FieldChange, FieldHistoryQuery, Permission};
+use crate::services::PermissionService;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/logs", get(list_audit_logs))
+        .route("/field-history", get(list_field_history))
+}
+
+/// Журнал аудита привилегированных действий.
+/// Администраторы видят все записи, председатели — только по своим ЖК.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/logs",
+    tag = "audit",
+    security(("bearer_auth" = [])),
+    params(AuditLogsQuery),
+    responses(
+        (status = 200, description = "Записи журнала аудита", body = Vec<AuditLogResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно администраторам и председателям")
+    )
+)]
+pub async fn list_audit_logs(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<AuditLogsQuery>,
+) -> AppResult<Json<Vec<AuditLogResponse>>> {
+    // Для председателя ограничиваем выборку ЖК, где он председатель ОСИ
+    let allowed_complexes: Option<Vec<Uuid>> = if is_admin_or_higher(&auth_user.role) {
+        None
+    } else {
+        let complexes: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT complex_id FROM osi WHERE chairman_id = $1")
+                .bind(auth_user.user_id)
+                .fetch_all(&state.pool)
+                .await?;
+
+        if complexes.is_empty() {
+            return Err(AppError::Forbidden);
+        }
+
+        Some(complexes.into_iter().map(|(id,)| id).collect())
+    };
+
+    if let (Some(complex_id), Some(allowed)) = (query.complex_id, &allowed_complexes) {
+        if !allowed.contains(&complex_id) {
+            return Err(AppError::Forbidden);
+        }
+    }
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let logs = sqlx::query_as::<_, AuditLogResponse>(
+        r#"
+        SELECT a.id, a.actor_id,
+               u.display_name AS actor_name,
+               a.complex_id, a.action, a.entity_type, a.entity_id,
+               a.old_value, a.new_value, a.diff, a.request_id, a.created_at
+        FROM audit_logs a
+        LEFT JOIN users u ON u.id = a.actor_id
+        WHERE ($1::uuid[] IS NULL OR a.complex_id = ANY($1))
+          AND ($2::uuid IS NULL OR a.complex_id = $2)
+          AND ($3::uuid IS NULL OR a.actor_id = $3)
+          AND ($4::varchar IS NULL OR a.action = $4)
+          AND ($5::varchar IS NULL OR a.entity_type = $5)
+          AND ($6::uuid IS NULL OR a.entity_id = $6)
+          AND ($7::timestamptz IS NULL OR a.created_at >= $7)
+          AND ($8::timestamptz IS NULL OR a.created_at < $8)
+        ORDER BY a.created_at DESC
+        LIMIT $9 OFFSET $10
+        "#,
+    )
+    .bind(&allowed_complexes)
+    .bind(query.complex_id)
+    .bind(query.actor_id)
+    .bind(&query.action)
+    .bind(&query.entity_type)
+    .bind(query.entity_id)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(logs))
+}
+
+/// Пополевая история изменений ОСИ и ЖК: кто, когда, что было и что стало.
+/// Доступна администраторам, председателю и действующим членам совета.
+#[utoipa::path(
+    get,
+    path = "/api/v1/audit/field-history",
+    tag = "audit",
+    security(("bearer_auth" = [])),
+    params(FieldHistoryQuery),
+    responses(
+        (status = 200, description = "Изменения, новые сверху", body = Vec<FieldChange>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно администраторам, председателю и совету дома")
+    )
+)]
+pub async fn list_field_history(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<FieldHistoryQuery>,
+) -> AppResult<Json<Vec<FieldChange>>> {
+    let allowed = PermissionService::has(&state.pool, &auth_user, query.complex_id, Permission::ManageOsi)
+        .await?
+        || sqlx::query_as::<_, (bool,)>(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM council_members cm
+                JOIN osi o ON o.id = cm.osi_id
+                WHERE o.complex_id = $1 AND cm.user_id = $2 AND cm.is_active = true
+                  AND (cm.expires_at IS NULL OR cm.expires_at > NOW())
+            )
+            "#,
+        )
+        .bind(query.complex_id)
+        .bind(auth_user.user_id)
+        .fetch_one(&state.pool)
+        .await?
+        .0;
+
+    if !allowed {
+        return Err(AppError::Forbidden);
+    }
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let changes = sqlx::query_as::<_, FieldChange>(
+        r#"
+        SELECT f.id, f.complex_id, f.entity_type, f.entity_id, f.field,
+               f.old_value, f.new_value, f.changed_by,
+               u.display_name AS changed_by_name, f.changed_at
+        FROM field_changes f
+        LEFT JOIN users u ON u.id = f.changed_by
+        WHERE f.complex_id = $1
+          AND ($2::varchar IS NULL OR f.entity_type = $2)
+          AND ($3::varchar IS NULL OR f.field = $3)
+        ORDER BY f.changed_at DESC, f.field
+        LIMIT $4 OFFSET $5
+        "#,
+    )
+    .bind(query.complex_id)
+    .bind(&query.entity_type)
+    .bind(&query.field)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(changes))
+}
@@ -1,23 +1,28 @@
-use axum::{extract::State, routing::post, Json, Router};
+use axum::{extract::State, http::HeaderMap, routing::post, Json, Router};
 use chrono::{Duration, Utc};
 use serde_json::{json, Value};
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::AppState;
+use crate::middleware::{auth::device_hash, AppState};
 use crate::models::{
-    AuthResponse, RefreshTokenRequest, SendCodeRequest, TokenResponse, UserPublic,
+    AuthResponse, CodeChannel, ConfirmEmailRequest, MagicLinkLoginRequest, MagicLinkPurpose,
+    RefreshTokenRequest, SendCodeRequest, SendMagicLinkRequest, TokenResponse, User, UserPublic,
     VerifyCodeRequest,
 };
 use crate::services::{
-    auth_service::{normalize_phone, validate_kz_phone},
-    AuthService, SmsService,
+    auth_service::{normalize_phone, parse_allowed_phone},
+    AuthService, ConsentService, EmailService, SandboxService, SmsService, WhatsAppService,
 };
+use crate::utils::validate_email;
 
 /// Успешный ответ на отправку SMS-кода
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct SendCodeResponse {
     pub success: bool,
     pub message: String,
+    /// Канал, по которому код фактически ушёл; у ссылки для входа не заполняется
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<CodeChannel>,
 }
 
 /// Успешный ответ на выход
@@ -31,11 +36,14 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/send-code", post(send_code))
         .route("/verify-code", post(verify_code))
+        .route("/magic-link", post(send_magic_link))
+        .route("/magic-link/verify", post(verify_magic_link))
+        .route("/confirm-email", post(confirm_email))
         .route("/refresh", post(refresh_token))
         .route("/logout", post(logout))
 }
 
-/// Отправка SMS-кода для входа
+/// Отправка кода для входа по SMS или в WhatsApp
 #[utoipa::path(
     post,
     path = "/api/v1/auth/send-code",
@@ -43,7 +51,7 @@ pub fn routes() -> Router<AppState> {
     request_body = SendCodeRequest,
     responses(
         (status = 200, description = "Код успешно отправлен", body = SendCodeResponse),
-        (status = 400, description = "Неверный формат номера телефона"),
+        (status = 400, description = "Неверный формат номера или страна не поддерживается"),
         (status = 429, description = "Слишком много запросов")
     )
 )]
@@ -51,13 +59,7 @@ pub async fn send_code(
     State(state): State<AppState>,
     Json(payload): Json<SendCodeRequest>,
 ) -> AppResult<Json<Value>> {
-    let phone = normalize_phone(&payload.phone);
-
-    if !validate_kz_phone(&phone) {
-        return Err(AppError::Validation(
-            "Неверный формат номера телефона".to_string(),
-        ));
-    }
+    let phone = parse_allowed_phone(&payload.phone, &state.config)?.e164;
 
     // Проверяем лимит отправки
     let recent_count: (i64,) = sqlx::query_as(
@@ -74,17 +76,41 @@ pub async fn send_code(
         return Err(AppError::TooManyRequests);
     }
 
+    // Партнёры в демо-ЖК входят по коду, выданному при создании ЖК, SMS не отправляется
+    if let Some(code) = SandboxService::login_code(&state.pool, &phone).await? {
+        AuthService::save_sms_code(&state.pool, &phone, &code).await?;
+        return Ok(Json(json!({
+            "success": true,
+            "message": "Код отправлен",
+            "channel": CodeChannel::Sms
+        })));
+    }
+
     // Генерируем и сохраняем код
     let code = AuthService::generate_sms_code();
     AuthService::save_sms_code(&state.pool, &phone, &code).await?;
 
-    // Отправляем SMS
-    let sms_service = SmsService::new(state.config.clone());
-    sms_service.send_code(&phone, &code).await?;
+    // WhatsApp по выбору пользователя; если не ушло — тот же код по SMS
+    let mut channel = payload.channel;
+    if channel == CodeChannel::Whatsapp {
+        if let Err(e) = WhatsAppService::new(state.config.clone())
+            .send_code(&phone, &code)
+            .await
+        {
+            tracing::warn!("WhatsApp code delivery failed, falling back to SMS: {}", e);
+            channel = CodeChannel::Sms;
+        }
+    }
+
+    if channel == CodeChannel::Sms {
+        let sms_service = SmsService::new(state.config.clone());
+        sms_service.send_code(&state.pool, &phone, &code).await?;
+    }
 
     Ok(Json(json!({
         "success": true,
-        "message": "Код отправлен"
+        "message": "Код отправлен",
+        "channel": channel
     })))
 }
 
@@ -94,15 +120,19 @@ pub async fn send_code(
     path = "/api/v1/auth/verify-code",
     tag = "auth",
     request_body = VerifyCodeRequest,
+    params(
+        ("X-Device-Fingerprint" = Option<String>, Header, description = "Отпечаток устройства; обязателен для председателя и администратора, их сессия привязывается к нему")
+    ),
     responses(
         (status = 200, description = "Успешный вход", body = AuthResponse),
-        (status = 400, description = "Неверный код"),
+        (status = 400, description = "Неверный код или не передан отпечаток устройства"),
         (status = 403, description = "Пользователь заблокирован"),
         (status = 429, description = "Слишком много попыток")
     )
 )]
 pub async fn verify_code(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<VerifyCodeRequest>,
 ) -> AppResult<Json<AuthResponse>> {
     let phone = normalize_phone(&payload.phone);
@@ -146,50 +176,246 @@ pub async fn verify_code(
         }
     };
 
+    let response = start_session(&state, &headers, user, payload.device_info.as_deref(), is_new_user).await?;
+    Ok(Json(response))
+}
+
+/// Устройство, к которому привязывается сессия. Привилегированную сессию без отпечатка
+/// устройства не выдаём: иначе токен работал бы с любого устройства
+async fn session_device(state: &AppState, headers: &HeaderMap, user: &User) -> AppResult<Option<String>> {
+    if !AuthService::device_binding_required(&state.pool, user).await? {
+        return Ok(None);
+    }
+
+    device_hash(headers)
+        .map(Some)
+        .ok_or(AppError::DeviceFingerprintRequired)
+}
+
+/// Выдать пару токенов после успешного входа любым способом
+async fn start_session(
+    state: &AppState,
+    headers: &HeaderMap,
+    user: User,
+    device_info: Option<&str>,
+    is_new_user: bool,
+) -> AppResult<AuthResponse> {
+    let device = session_device(state, headers, &user).await?;
+
     // Обновляем время последнего входа
     AuthService::update_last_login(&state.pool, user.id).await?;
+    // Привязка карточек работника не должна мешать входу
+    if let Err(e) = AuthService::link_worker_accounts(&state.pool, &user).await {
+        tracing::warn!("Failed to link worker accounts for user {}: {}", user.id, e);
+    }
 
     // Генерируем токены
     let auth_service = AuthService::new(state.config.clone());
-    let access_token = auth_service.generate_access_token(&user)?;
-    let refresh_token = auth_service.generate_refresh_token(&user)?;
+    let access_token = auth_service.generate_access_token(&user, device.as_deref())?;
+    let refresh_token = auth_service.generate_refresh_token(&user, device.as_deref())?;
 
     // Сохраняем refresh token
     let token_hash = AuthService::hash_token(&refresh_token);
     let expires_at = Utc::now() + Duration::seconds(state.config.jwt_refresh_expiry);
 
-    AuthService::save_refresh_token(
-        &state.pool,
-        user.id,
-        &token_hash,
-        payload.device_info.as_deref(),
-        None,
-        expires_at,
-    )
-    .await?;
+    AuthService::save_refresh_token(&state.pool, user.id, &token_hash, device_info, None, expires_at)
+        .await?;
+
+    let consent_required = ConsentService::consent_required(&state.pool, user.id).await?;
 
-    Ok(Json(AuthResponse {
+    Ok(AuthResponse {
         access_token,
         refresh_token,
         user: UserPublic::from(user),
         is_new_user,
+        consent_required,
+    })
+}
+
+/// Ссылка для входа на подтверждённый email — альтернатива SMS-коду для веб-панели
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/magic-link",
+    tag = "auth",
+    request_body = SendMagicLinkRequest,
+    responses(
+        (status = 200, description = "Если адрес подтверждён, ссылка отправлена", body = SendCodeResponse),
+        (status = 422, description = "Неверный формат email")
+    )
+)]
+pub async fn send_magic_link(
+    State(state): State<AppState>,
+    Json(payload): Json<SendMagicLinkRequest>,
+) -> AppResult<Json<SendCodeResponse>> {
+    let email = payload.email.trim().to_lowercase();
+    if !validate_email(&email) {
+        return Err(AppError::Validation("Неверный формат email".to_string()));
+    }
+
+    let user = sqlx::query_as::<_, User>(
+        "SELECT * FROM users WHERE LOWER(email) = $1 AND email_verified_at IS NOT NULL",
+    )
+    .bind(&email)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    // Ответ одинаковый при любом исходе, чтобы по нему нельзя было проверить адрес
+    if let Some(user) = user.filter(|user| !user.is_blocked) {
+        if AuthService::recent_magic_links(&state.pool, user.id).await? < 5 {
+            let token = AuthService::create_magic_link(
+                &state.pool,
+                user.id,
+                MagicLinkPurpose::Login,
+                &email,
+                state.config.magic_link_ttl_minutes,
+            )
+            .await?;
+            let link = format!(
+                "{}/auth/magic-link?token={}",
+                state.config.public_url.trim_end_matches('/'),
+                token
+            );
+            // Сбой почтового сервиса не должен отличать подтверждённый адрес от неизвестного
+            if let Err(e) = EmailService::new(state.config.clone())
+                .send_magic_link(&email, &link)
+                .await
+            {
+                tracing::error!("Failed to send magic link to user {}: {}", user.id, e);
+            }
+        } else {
+            tracing::warn!("Magic link limit reached for user {}", user.id);
+        }
+    }
+
+    Ok(Json(SendCodeResponse {
+        success: true,
+        message: "Если адрес подтверждён, мы отправили ссылку для входа".to_string(),
+        channel: None,
     }))
 }
 
+/// Вход по ссылке из письма
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/magic-link/verify",
+    tag = "auth",
+    request_body = MagicLinkLoginRequest,
+    params(
+        ("X-Device-Fingerprint" = Option<String>, Header, description = "Отпечаток устройства; обязателен для председателя и администратора, их сессия привязывается к нему")
+    ),
+    responses(
+        (status = 200, description = "Успешный вход", body = AuthResponse),
+        (status = 400, description = "Ссылка недействительна, устарела или не передан отпечаток устройства"),
+        (status = 403, description = "Пользователь заблокирован")
+    )
+)]
+pub async fn verify_magic_link(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<MagicLinkLoginRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    let invalid = || AppError::BadRequest("Ссылка недействительна или устарела".to_string());
+
+    let link = AuthService::consume_magic_link(&state.pool, &payload.token, MagicLinkPurpose::Login)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let user = AuthService::get_user_by_id(&state.pool, link.user_id).await?;
+    if user.is_blocked {
+        return Err(AppError::Forbidden);
+    }
+    // Адрес сменили или сняли подтверждение после отправки письма
+    let email_still_verified = user.email_verified_at.is_some()
+        && user
+            .email
+            .as_deref()
+            .is_some_and(|email| email.eq_ignore_ascii_case(&link.email));
+    if !email_still_verified {
+        return Err(invalid());
+    }
+
+    let response = start_session(&state, &headers, user, payload.device_info.as_deref(), false).await?;
+    Ok(Json(response))
+}
+
+/// Подтверждение email по ссылке из письма; после него можно входить по email
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/confirm-email",
+    tag = "auth",
+    request_body = ConfirmEmailRequest,
+    responses(
+        (status = 200, description = "Email подтверждён", body = UserPublic),
+        (status = 400, description = "Ссылка недействительна или устарела"),
+        (status = 409, description = "Адрес уже подтверждён другим пользователем")
+    )
+)]
+pub async fn confirm_email(
+    State(state): State<AppState>,
+    Json(payload): Json<ConfirmEmailRequest>,
+) -> AppResult<Json<UserPublic>> {
+    let invalid = || AppError::BadRequest("Ссылка недействительна или устарела".to_string());
+
+    let link =
+        AuthService::consume_magic_link(&state.pool, &payload.token, MagicLinkPurpose::VerifyEmail)
+            .await?
+            .ok_or_else(invalid)?;
+
+    let (taken,): (bool,) = sqlx::query_as(
+        r#"
+        SELECT EXISTS(
+            SELECT 1 FROM users
+            WHERE LOWER(email) = LOWER($1) AND email_verified_at IS NOT NULL AND id <> $2
+        )
+        "#,
+    )
+    .bind(&link.email)
+    .bind(link.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+    if taken {
+        return Err(AppError::Conflict(
+            "Этот адрес уже подтверждён другим пользователем".to_string(),
+        ));
+    }
+
+    // Письмо подтверждает только тот адрес, на который было отправлено
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        UPDATE users
+        SET email_verified_at = COALESCE(email_verified_at, NOW()), updated_at = NOW()
+        WHERE id = $1 AND LOWER(email) = LOWER($2)
+        RETURNING *
+        "#,
+    )
+    .bind(link.user_id)
+    .bind(&link.email)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(invalid)?;
+
+    Ok(Json(UserPublic::from(user)))
+}
+
 /// Обновление пары токенов
 #[utoipa::path(
     post,
     path = "/api/v1/auth/refresh",
     tag = "auth",
     request_body = RefreshTokenRequest,
+    params(
+        ("X-Device-Fingerprint" = Option<String>, Header, description = "Отпечаток устройства, к которому привязана сессия")
+    ),
     responses(
         (status = 200, description = "Токены обновлены", body = TokenResponse),
-        (status = 401, description = "Недействительный токен"),
+        (status = 400, description = "Не передан отпечаток устройства"),
+        (status = 401, description = "Недействительный токен или сессия привязана к другому устройству"),
         (status = 403, description = "Пользователь заблокирован")
     )
 )]
 pub async fn refresh_token(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<RefreshTokenRequest>,
 ) -> AppResult<Json<TokenResponse>> {
     let auth_service = AuthService::new(state.config.clone());
@@ -222,12 +448,26 @@ pub async fn refresh_token(
         return Err(AppError::Forbidden);
     }
 
+    // Смена устройства обрывает все сессии пользователя: нужен повторный вход
+    let current_device = device_hash(&headers);
+    if claims.device.is_some() && claims.device != current_device {
+        sqlx::query("DELETE FROM refresh_tokens WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&state.pool)
+            .await?;
+        tracing::warn!("Device binding mismatch for user {}, sessions revoked", user.id);
+        return Err(AppError::DeviceChanged);
+    }
+
+    // Роль могла измениться с момента входа: привязка следует текущей роли
+    let device = session_device(&state, &headers, &user).await?;
+
     // Удаляем старый refresh token
     AuthService::delete_refresh_token(&state.pool, &token_hash).await?;
 
     // Генерируем новые токены
-    let new_access_token = auth_service.generate_access_token(&user)?;
-    let new_refresh_token = auth_service.generate_refresh_token(&user)?;
+    let new_access_token = auth_service.generate_access_token(&user, device.as_deref())?;
+    let new_refresh_token = auth_service.generate_refresh_token(&user, device.as_deref())?;
 
     // Сохраняем новый refresh token
     let new_token_hash = AuthService::hash_token(&new_refresh_token);
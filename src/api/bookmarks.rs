@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Query, State},
+    routing::{get, post},
+    Json, Router,
+};
+use uuid::Uuid;
+
+use crate::api::marketplace::toggle_listing_favorite;
+use crate::error::{AppError, AppResult};
+use crate::middleware::{get_user_complexes, AppState, AuthUser};
+use crate::models::{
+    BookmarkEntityType, BookmarkResponse, BookmarksQuery, ToggleBookmarkRequest,
+    ToggleBookmarkResponse,
+};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_bookmarks))
+        .route("/", post(toggle_bookmark))
+}
+
+/// Проверяем, что пользователь может видеть сохраняемый объект
+async fn check_entity_access(
+    state: &AppState,
+    entity_type: BookmarkEntityType,
+    entity_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<()> {
+    let sql = match entity_type {
+        BookmarkEntityType::Listing => {
+            r#"
+            SELECT 1 FROM marketplace_listings
+            WHERE id = $1
+              AND complex_id = ANY($2)
+              AND status = 'active'
+            "#
+        }
+        BookmarkEntityType::Announcement => {
+            r#"
+            SELECT 1 FROM announcements
+            WHERE id = $1
+              AND complex_id = ANY($2)
+              AND is_published = true
+              AND (expires_at IS NULL OR expires_at > NOW())
+            "#
+        }
+        BookmarkEntityType::Document => {
+            r#"
+            SELECT 1 FROM osi_documents d
+            JOIN osi o ON o.id = d.osi_id
+            WHERE d.id = $1
+              AND o.complex_id = ANY($2)
+            "#
+        }
+        BookmarkEntityType::Voting => {
+            r#"
+            SELECT 1 FROM votings
+            WHERE id = $1
+              AND complex_id = ANY($2)
+            "#
+        }
+    };
+
+    let complexes = get_user_complexes(&state.pool, user_id).await?;
+    let exists: Option<(i32,)> = sqlx::query_as(sql)
+        .bind(entity_id)
+        .bind(&complexes)
+        .fetch_optional(&state.pool)
+        .await?;
+
+    exists
+        .map(|_| ())
+        .ok_or_else(|| AppError::NotFound("Объект не найден".to_string()))
+}
+
+/// Получить все закладки пользователя
+#[utoipa::path(
+    get,
+    path = "/api/v1/bookmarks",
+    tag = "bookmarks",
+    security(("bearer_auth" = [])),
+    params(BookmarksQuery),
+    responses(
+        (status = 200, description = "Список закладок", body = Vec<BookmarkResponse>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn list_bookmarks(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<BookmarksQuery>,
+) -> AppResult<Json<Vec<BookmarkResponse>>> {
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    // Избранное маркетплейса и остальные закладки в одной ленте;
+    // скрытые и неопубликованные объекты отфильтрованы так же, как в обычных списках
+    let bookmarks = sqlx::query_as::<_, BookmarkResponse>(
+        r#"
+        SELECT * FROM (
+            SELECT 'listing'::bookmark_entity_type AS entity_type, l.id AS entity_id,
+                   l.title::text AS title, f.created_at
+            FROM listing_favorites f
+            JOIN marketplace_listings l ON l.id = f.listing_id
+            WHERE f.user_id = $1 AND l.status = 'active'
+
+            UNION ALL
+
+            SELECT b.entity_type, b.entity_id,
+                   COALESCE(a.title, d.title, v.title)::text AS title, b.created_at
+            FROM bookmarks b
+            LEFT JOIN announcements a ON b.entity_type = 'announcement' AND a.id = b.entity_id
+                AND a.is_published = true
+                AND (a.expires_at IS NULL OR a.expires_at > NOW())
+            LEFT JOIN osi_documents d ON b.entity_type = 'document' AND d.id = b.entity_id
+            LEFT JOIN votings v ON b.entity_type = 'voting' AND v.id = b.entity_id
+            WHERE b.user_id = $1
+              AND COALESCE(a.id, d.id, v.id) IS NOT NULL
+        ) items
+        WHERE ($2::bookmark_entity_type IS NULL OR entity_type = $2)
+        ORDER BY created_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(query.entity_type)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(bookmarks))
+}
+
+/// Добавить/удалить закладку
+#[utoipa::path(
+    post,
+    path = "/api/v1/bookmarks",
+    tag = "bookmarks",
+    security(("bearer_auth" = [])),
+    request_body = ToggleBookmarkRequest,
+    responses(
+        (status = 200, description = "Статус закладки изменён", body = ToggleBookmarkResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Объект не найден")
+    )
+)]
+pub async fn toggle_bookmark(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<ToggleBookmarkRequest>,
+) -> AppResult<Json<ToggleBookmarkResponse>> {
+    check_entity_access(&state, payload.entity_type, payload.entity_id, auth_user.user_id).await?;
+
+    // Объявления маркетплейса сохраняются в избранное
+    if payload.entity_type == BookmarkEntityType::Listing {
+        let is_bookmarked =
+            toggle_listing_favorite(&state, payload.entity_id, auth_user.user_id).await?;
+        return Ok(Json(ToggleBookmarkResponse { is_bookmarked }));
+    }
+
+    let removed = sqlx::query(
+        "DELETE FROM bookmarks WHERE user_id = $1 AND entity_type = $2 AND entity_id = $3",
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.entity_type)
+    .bind(payload.entity_id)
+    .execute(&state.pool)
+    .await?;
+
+    if removed.rows_affected() > 0 {
+        return Ok(Json(ToggleBookmarkResponse {
+            is_bookmarked: false,
+        }));
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO bookmarks (user_id, entity_type, entity_id)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (user_id, entity_type, entity_id) DO NOTHING
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.entity_type)
+    .bind(payload.entity_id)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(ToggleBookmarkResponse {
+        is_bookmarked: true,
+    }))
+}
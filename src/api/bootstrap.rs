@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, State},
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::middleware::{AppState, AuthUser};
+use crate::models::{BootstrapBanner, BootstrapResponse, GlobalBanner};
+use crate::services::{BannerService, ConsentService, SettingsService};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(get_bootstrap))
+        .route("/banners", get(list_banners))
+        .route("/banners/:id/dismiss", post(dismiss_banner))
+}
+
+/// Стартовые данные для мобильного приложения
+#[utoipa::path(
+    get,
+    path = "/api/v1/bootstrap",
+    tag = "bootstrap",
+    responses(
+        (status = 200, description = "Стартовые данные приложения", body = BootstrapResponse)
+    )
+)]
+pub async fn get_bootstrap(
+    State(state): State<AppState>,
+    auth_user: Option<AuthUser>,
+) -> AppResult<Json<BootstrapResponse>> {
+    let now = Utc::now();
+    let mode = SettingsService::maintenance_mode(&state.pool).await?;
+    let maintenance = mode.is_active(now);
+
+    // Баннер показываем во время работ и заранее, если они запланированы
+    let banner = (maintenance || mode.is_upcoming(now)).then(|| BootstrapBanner {
+        kind: "maintenance".to_string(),
+        message: mode.message_or_default(),
+        starts_at: mode.starts_at,
+        ends_at: mode.ends_at,
+    });
+
+    let banners = BannerService::active_for(&state.pool, auth_user.as_ref()).await?;
+    let consent_required = match &auth_user {
+        Some(user) => ConsentService::consent_required(&state.pool, user.user_id).await?,
+        None => false,
+    };
+
+    Ok(Json(BootstrapResponse {
+        api_version: env!("CARGO_PKG_VERSION").to_string(),
+        maintenance,
+        banner,
+        banners,
+        consent_required,
+        server_time: now,
+    }))
+}
+
+/// Платформенные баннеры для текущего пользователя
+#[utoipa::path(
+    get,
+    path = "/api/v1/bootstrap/banners",
+    tag = "bootstrap",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Активные баннеры", body = Vec<GlobalBanner>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn list_banners(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<GlobalBanner>>> {
+    let banners = BannerService::active_for(&state.pool, Some(&auth_user)).await?;
+    Ok(Json(banners))
+}
+
+/// Скрыть баннер
+#[utoipa::path(
+    post,
+    path = "/api/v1/bootstrap/banners/{id}/dismiss",
+    tag = "bootstrap",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID баннера")
+    ),
+    responses(
+        (status = 200, description = "Баннер скрыт"),
+        (status = 400, description = "Баннер нельзя скрыть"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Баннер не найден")
+    )
+)]
+pub async fn dismiss_banner(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    BannerService::dismiss(&state.pool, id, auth_user.user_id).await?;
+    Ok(Json(json!({"success": true})))
+}
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
@@ -9,41 +9,45 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{AppState, AuthUser};
+use crate::middleware::{AppState, AuthUser, ComplexScope};
 use crate::models::{
-    Chat, ChatMessage, ChatMessageResponse, ChatResponse, ChatType, CreatePrivateChatRequest,
-    MessagePreview, MessagesQuery, SendChatMessageRequest, SenderInfo,
+    take_page, Chat, ChatMediaItem, ChatMediaQuery, ChatMessage, ChatMessageResponse,
+    ChatResponse, ChatType, CreatePrivateChatRequest, Cursor, CursorPage, MediaKind,
+    MessagePreview, MessagesQuery, Permission, ScheduleChatMessageRequest, ScheduledChatMessage,
+    SendChatMessageRequest, SenderInfo, UserRole,
 };
+use crate::services::PermissionService;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ChatSuccessResponse {
     pub success: bool,
 }
 
+/// Строка списка чатов с агрегатами
+#[derive(sqlx::FromRow)]
+struct ChatListRow {
+    id: Uuid,
+    chat_type: ChatType,
+    name: Option<String>,
+    listing_id: Option<Uuid>,
+    last_content: Option<String>,
+    last_sender_name: Option<String>,
+    last_created_at: Option<chrono::DateTime<chrono::Utc>>,
+    unread_count: i64,
+    members_count: i64,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_chats))
         .route("/private", post(create_private_chat))
         .route("/:id/messages", get(get_messages))
         .route("/:id/messages", post(send_message))
+        .route("/:id/media", get(get_media))
         .route("/:id/read", post(mark_chat_as_read))
-}
-
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
-
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
+        .route("/:id/scheduled", get(list_scheduled_messages))
+        .route("/:id/scheduled", post(schedule_message))
+        .route("/:id/scheduled/:scheduled_id", delete(cancel_scheduled_message))
 }
 
 /// Получить список чатов пользователя
@@ -52,6 +56,9 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
     path = "/api/chats",
     tag = "Чаты",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     responses(
         (status = 200, description = "Список чатов", body = Vec<ChatResponse>),
         (status = 401, description = "Не авторизован"),
@@ -61,17 +68,40 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
 async fn list_chats(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
 ) -> AppResult<Json<Vec<ChatResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
-    // Получаем чаты пользователя
-    let chats = sqlx::query_as::<_, Chat>(
+    // Чаты пользователя вместе с последним сообщением и счётчиками одним запросом
+    let rows = sqlx::query_as::<_, ChatListRow>(
         r#"
-        SELECT c.* FROM chats c
-        LEFT JOIN chat_members cm ON cm.chat_id = c.id
+        SELECT
+            c.id,
+            c.chat_type,
+            c.name,
+            c.listing_id,
+            lm.content AS last_content,
+            lm.sender_name AS last_sender_name,
+            lm.created_at AS last_created_at,
+            (
+                SELECT COUNT(*) FROM chat_messages m
+                WHERE m.chat_id = c.id AND m.sender_id != $2
+                  AND NOT EXISTS (
+                      SELECT 1 FROM message_reads r WHERE r.message_id = m.id AND r.user_id = $2
+                  )
+            ) AS unread_count,
+            (SELECT COUNT(*) FROM chat_members cm WHERE cm.chat_id = c.id) AS members_count
+        FROM chats c
+        LEFT JOIN LATERAL (
+            SELECT m.content, COALESCE(u.first_name, u.phone) AS sender_name, m.created_at
+            FROM chat_messages m
+            JOIN users u ON u.id = m.sender_id
+            WHERE m.chat_id = c.id AND m.is_deleted = false
+            ORDER BY m.created_at DESC
+            LIMIT 1
+        ) lm ON true
         WHERE (c.complex_id = $1 AND c.chat_type IN ('complex', 'building'))
-           OR cm.user_id = $2
-        GROUP BY c.id
+           OR EXISTS (SELECT 1 FROM chat_members cm WHERE cm.chat_id = c.id AND cm.user_id = $2)
         ORDER BY c.updated_at DESC
         "#,
     )
@@ -80,64 +110,25 @@ async fn list_chats(
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for chat in chats {
-        let last_message: Option<(String, Uuid, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
-            r#"
-            SELECT content, sender_id, created_at
-            FROM chat_messages
-            WHERE chat_id = $1 AND is_deleted = false
-            ORDER BY created_at DESC
-            LIMIT 1
-            "#,
-        )
-        .bind(chat.id)
-        .fetch_optional(&state.pool)
-        .await?;
-
-        let last_message_preview = if let Some((content, sender_id, created_at)) = last_message {
-            let sender_name: (String,) =
-                sqlx::query_as("SELECT COALESCE(first_name, phone) FROM users WHERE id = $1")
-                    .bind(sender_id)
-                    .fetch_one(&state.pool)
-                    .await?;
-
-            Some(MessagePreview {
-                content,
-                sender_name: sender_name.0,
-                created_at,
-            })
-        } else {
-            None
-        };
-
-        let unread_count: (i64,) = sqlx::query_as(
-            r#"
-            SELECT COUNT(*) FROM chat_messages m
-            LEFT JOIN message_reads r ON r.message_id = m.id AND r.user_id = $2
-            WHERE m.chat_id = $1 AND r.id IS NULL AND m.sender_id != $2
-            "#,
-        )
-        .bind(chat.id)
-        .bind(auth_user.user_id)
-        .fetch_one(&state.pool)
-        .await?;
-
-        let members_count: (i64,) =
-            sqlx::query_as("SELECT COUNT(*) FROM chat_members WHERE chat_id = $1")
-                .bind(chat.id)
-                .fetch_one(&state.pool)
-                .await?;
-
-        response.push(ChatResponse {
-            id: chat.id,
-            chat_type: chat.chat_type,
-            name: chat.name,
-            last_message: last_message_preview,
-            unread_count: unread_count.0 as i32,
-            members_count: members_count.0 as i32,
-        });
-    }
+    let response = rows
+        .into_iter()
+        .map(|row| ChatResponse {
+            id: row.id,
+            chat_type: row.chat_type,
+            name: row.name,
+            listing_id: row.listing_id,
+            last_message: match (row.last_content, row.last_created_at) {
+                (Some(content), Some(created_at)) => Some(MessagePreview {
+                    content,
+                    sender_name: row.last_sender_name.unwrap_or_default(),
+                    created_at,
+                }),
+                _ => None,
+            },
+            unread_count: row.unread_count as i32,
+            members_count: row.members_count as i32,
+        })
+        .collect();
 
     Ok(Json(response))
 }
@@ -176,7 +167,7 @@ async fn create_private_chat(
         SELECT c.id FROM chats c
         JOIN chat_members cm1 ON cm1.chat_id = c.id AND cm1.user_id = $1
         JOIN chat_members cm2 ON cm2.chat_id = c.id AND cm2.user_id = $2
-        WHERE c.chat_type = 'private'
+        WHERE c.chat_type = 'private' AND c.listing_id IS NULL
         "#,
     )
     .bind(auth_user.user_id)
@@ -219,6 +210,7 @@ async fn create_private_chat(
         id: chat.id,
         chat_type: chat.chat_type,
         name: chat.name,
+        listing_id: chat.listing_id,
         last_message: None,
         unread_count: 0,
         members_count: 2,
@@ -234,10 +226,11 @@ async fn create_private_chat(
     params(
         ("id" = Uuid, Path, description = "ID чата"),
         ("limit" = Option<i64>, Query, description = "Лимит сообщений"),
-        ("before" = Option<Uuid>, Query, description = "Получить сообщения до указанного ID")
+        ("cursor" = Option<String>, Query, description = "Курсор следующей страницы"),
+        ("before" = Option<Uuid>, Query, description = "Получить сообщения до указанного ID (устарело, используйте cursor)")
     ),
     responses(
-        (status = 200, description = "Список сообщений", body = Vec<ChatMessageResponse>),
+        (status = 200, description = "Список сообщений", body = ChatMessagesPage),
         (status = 401, description = "Не авторизован"),
         (status = 403, description = "Нет доступа к чату"),
         (status = 404, description = "Чат не найден")
@@ -248,44 +241,48 @@ async fn get_messages(
     auth_user: AuthUser,
     Path(chat_id): Path<Uuid>,
     Query(query): Query<MessagesQuery>,
-) -> AppResult<Json<Vec<ChatMessageResponse>>> {
+) -> AppResult<Json<CursorPage<ChatMessageResponse>>> {
     // Проверяем доступ к чату
     let has_access = check_chat_access(&state, chat_id, auth_user.user_id).await?;
     if !has_access {
         return Err(AppError::Forbidden);
     }
 
-    let limit = query.limit.unwrap_or(50).min(100);
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
 
-    let messages = if let Some(before_id) = query.before {
-        sqlx::query_as::<_, ChatMessage>(
-            r#"
-            SELECT * FROM chat_messages
-            WHERE chat_id = $1 AND is_deleted = false AND id < $2
-            ORDER BY created_at DESC
-            LIMIT $3
-            "#,
+    let cursor = match (&query.cursor, query.before) {
+        (Some(cursor), _) => Some(
+            Cursor::decode(cursor).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string()))?,
+        ),
+        (None, Some(before_id)) => sqlx::query_as::<_, (chrono::DateTime<chrono::Utc>, Uuid)>(
+            "SELECT created_at, id FROM chat_messages WHERE id = $1 AND chat_id = $2",
         )
-        .bind(chat_id)
         .bind(before_id)
-        .bind(limit)
-        .fetch_all(&state.pool)
-        .await?
-    } else {
-        sqlx::query_as::<_, ChatMessage>(
-            r#"
-            SELECT * FROM chat_messages
-            WHERE chat_id = $1 AND is_deleted = false
-            ORDER BY created_at DESC
-            LIMIT $2
-            "#,
-        )
         .bind(chat_id)
-        .bind(limit)
-        .fetch_all(&state.pool)
+        .fetch_optional(&state.pool)
         .await?
+        .map(|(created_at, id)| Cursor::new(created_at, id)),
+        (None, None) => None,
     };
 
+    let mut messages = sqlx::query_as::<_, ChatMessage>(
+        r#"
+        SELECT * FROM chat_messages
+        WHERE chat_id = $1 AND is_deleted = false
+          AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $4
+        "#,
+    )
+    .bind(chat_id)
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let next_cursor = take_page(&mut messages, limit, |m| Cursor::new(m.created_at, m.id));
+
     let mut response = Vec::new();
     for msg in messages {
         let sender: (Uuid, Option<String>, Option<String>) = sqlx::query_as(
@@ -319,7 +316,82 @@ async fn get_messages(
     // Помечаем сообщения как прочитанные
     mark_messages_as_read(&state, chat_id, auth_user.user_id).await?;
 
-    Ok(Json(response))
+    Ok(Json(CursorPage::new(response, next_cursor)))
+}
+
+/// Галерея вложений чата
+#[utoipa::path(
+    get,
+    path = "/api/chats/{id}/media",
+    tag = "Чаты",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID чата"),
+        ("kind" = Option<MediaKind>, Query, description = "Тип вложений"),
+        ("cursor" = Option<String>, Query, description = "Курсор следующей страницы"),
+        ("limit" = Option<i64>, Query, description = "Лимит записей")
+    ),
+    responses(
+        (status = 200, description = "Вложения чата", body = ChatMediaPage),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к чату"),
+        (status = 404, description = "Чат не найден")
+    )
+)]
+async fn get_media(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(chat_id): Path<Uuid>,
+    Query(query): Query<ChatMediaQuery>,
+) -> AppResult<Json<CursorPage<ChatMediaItem>>> {
+    let has_access = check_chat_access(&state, chat_id, auth_user.user_id).await?;
+    if !has_access {
+        return Err(AppError::Forbidden);
+    }
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string())))
+        .transpose()?;
+    let types: Option<Vec<&str>> = query.kind.map(|k| k.attachment_types().to_vec());
+
+    let mut messages = sqlx::query_as::<_, ChatMessage>(
+        r#"
+        SELECT * FROM chat_messages
+        WHERE chat_id = $1 AND is_deleted = false AND attachment_url IS NOT NULL
+          AND ($2::text[] IS NULL OR attachment_type = ANY($2))
+          AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(chat_id)
+    .bind(types)
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let next_cursor = take_page(&mut messages, limit, |m| Cursor::new(m.created_at, m.id));
+
+    let items = messages
+        .into_iter()
+        .filter_map(|msg| {
+            Some(ChatMediaItem {
+                message_id: msg.id,
+                sender_id: msg.sender_id,
+                url: msg.attachment_url?,
+                kind: MediaKind::from_attachment_type(msg.attachment_type.as_deref().unwrap_or("")),
+                attachment_type: msg.attachment_type,
+                created_at: msg.created_at,
+            })
+        })
+        .collect();
+
+    Ok(Json(CursorPage::new(items, next_cursor)))
 }
 
 /// Отправить сообщение в чат
@@ -362,7 +434,7 @@ async fn send_message(
     .bind(&payload.content)
     .bind(&payload.attachment_url)
     .bind(&payload.attachment_type)
-    .bind(&payload.reply_to_id)
+    .bind(payload.reply_to_id)
     .fetch_one(&state.pool)
     .await?;
 
@@ -427,6 +499,188 @@ async fn mark_chat_as_read(
     Ok(Json(json!({"success": true})))
 }
 
+/// Запланировать сообщение в чат ЖК или дома
+#[utoipa::path(
+    post,
+    path = "/api/chats/{id}/scheduled",
+    tag = "Чаты",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID чата")
+    ),
+    request_body = ScheduleChatMessageRequest,
+    responses(
+        (status = 200, description = "Сообщение запланировано", body = ScheduledChatMessage),
+        (status = 400, description = "Время отправки уже прошло или чат не общий"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для модераторов чата"),
+        (status = 404, description = "Чат не найден")
+    )
+)]
+async fn schedule_message(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(chat_id): Path<Uuid>,
+    Json(payload): Json<ScheduleChatMessageRequest>,
+) -> AppResult<Json<ScheduledChatMessage>> {
+    check_chat_moderator(&state, chat_id, &auth_user).await?;
+
+    if payload.content.trim().is_empty() {
+        return Err(AppError::BadRequest("Сообщение не может быть пустым".to_string()));
+    }
+
+    if payload.send_at <= chrono::Utc::now() {
+        return Err(AppError::BadRequest(
+            "Время отправки должно быть в будущем".to_string(),
+        ));
+    }
+
+    let scheduled = sqlx::query_as::<_, ScheduledChatMessage>(
+        r#"
+        INSERT INTO scheduled_chat_messages (chat_id, sender_id, content, attachment_url, attachment_type, send_at)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(chat_id)
+    .bind(auth_user.user_id)
+    .bind(&payload.content)
+    .bind(&payload.attachment_url)
+    .bind(&payload.attachment_type)
+    .bind(payload.send_at)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(scheduled))
+}
+
+/// Запланированные, но ещё не отправленные сообщения чата
+#[utoipa::path(
+    get,
+    path = "/api/chats/{id}/scheduled",
+    tag = "Чаты",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID чата")
+    ),
+    responses(
+        (status = 200, description = "Отложенные сообщения", body = Vec<ScheduledChatMessage>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для модераторов чата"),
+        (status = 404, description = "Чат не найден")
+    )
+)]
+async fn list_scheduled_messages(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(chat_id): Path<Uuid>,
+) -> AppResult<Json<Vec<ScheduledChatMessage>>> {
+    check_chat_moderator(&state, chat_id, &auth_user).await?;
+
+    let scheduled = sqlx::query_as::<_, ScheduledChatMessage>(
+        "SELECT * FROM scheduled_chat_messages WHERE chat_id = $1 AND status = 'pending' ORDER BY send_at",
+    )
+    .bind(chat_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(scheduled))
+}
+
+/// Отменить отложенное сообщение до его отправки
+#[utoipa::path(
+    delete,
+    path = "/api/chats/{id}/scheduled/{scheduled_id}",
+    tag = "Чаты",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID чата"),
+        ("scheduled_id" = Uuid, Path, description = "ID отложенного сообщения")
+    ),
+    responses(
+        (status = 200, description = "Сообщение отменено", body = ChatSuccessResponse),
+        (status = 400, description = "Сообщение уже отправлено или отменено"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для модераторов чата"),
+        (status = 404, description = "Сообщение не найдено")
+    )
+)]
+async fn cancel_scheduled_message(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((chat_id, scheduled_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    check_chat_moderator(&state, chat_id, &auth_user).await?;
+
+    let cancelled = sqlx::query(
+        r#"
+        UPDATE scheduled_chat_messages SET status = 'cancelled'
+        WHERE id = $1 AND chat_id = $2 AND status = 'pending'
+        "#,
+    )
+    .bind(scheduled_id)
+    .bind(chat_id)
+    .execute(&state.pool)
+    .await?;
+
+    if cancelled.rows_affected() == 0 {
+        let exists: Option<(i32,)> =
+            sqlx::query_as("SELECT 1 FROM scheduled_chat_messages WHERE id = $1 AND chat_id = $2")
+                .bind(scheduled_id)
+                .bind(chat_id)
+                .fetch_optional(&state.pool)
+                .await?;
+
+        return Err(match exists {
+            Some(_) => AppError::BadRequest("Сообщение уже отправлено или отменено".to_string()),
+            None => AppError::NotFound("Отложенное сообщение не найдено".to_string()),
+        });
+    }
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Модератор общего чата: модератор платформы, админ чата или правление ОСИ этого ЖК
+async fn check_chat_moderator(state: &AppState, chat_id: Uuid, auth_user: &AuthUser) -> AppResult<()> {
+    let chat = sqlx::query_as::<_, Chat>("SELECT * FROM chats WHERE id = $1")
+        .bind(chat_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Чат не найден".to_string()))?;
+
+    if !matches!(chat.chat_type, ChatType::Complex | ChatType::Building) {
+        return Err(AppError::BadRequest(
+            "Отложенные сообщения доступны только в чатах ЖК и дома".to_string(),
+        ));
+    }
+
+    // Модераторы и администраторы платформы управляют любыми чатами
+    if matches!(auth_user.role, UserRole::Moderator | UserRole::Admin | UserRole::SuperAdmin) {
+        return Ok(());
+    }
+
+    // Правление ОСИ — только в чатах своего ЖК
+    if let Some(complex_id) = chat.complex_id {
+        if PermissionService::has(&state.pool, auth_user, complex_id, Permission::ManageOsi).await? {
+            return Ok(());
+        }
+    }
+
+    let is_moderator: Option<(i32,)> = sqlx::query_as(
+        "SELECT 1 FROM chat_members WHERE chat_id = $1 AND user_id = $2 AND is_admin = true",
+    )
+    .bind(chat_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if is_moderator.is_none() {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(())
+}
+
 async fn check_chat_access(state: &AppState, chat_id: Uuid, user_id: Uuid) -> AppResult<bool> {
     let chat = sqlx::query_as::<_, Chat>("SELECT * FROM chats WHERE id = $1")
         .bind(chat_id)
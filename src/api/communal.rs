@@ -1,6 +1,9 @@
 use axum::{
+    body::Bytes,
     extract::{Path, Query, State},
-    routing::{get, post},
+    http::{header, HeaderMap},
+    response::IntoResponse,
+    routing::{get, post, put},
     Json, Router,
 };
 use chrono::NaiveDate;
@@ -9,10 +12,18 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{AppState, AuthUser};
+use crate::middleware::{AppState, AuthUser, ComplexScope, RequestId};
 use crate::models::{
-    Bill, BillItem, BillItemResponse, BillResponse, CreatePaymentRequest, Meter, MeterReading,
-    MeterResponse, PaymentResponse, PaymentStatus, SubmitReadingRequest,
+    Bill, BillItem, BillItemResponse, BillResponse, CreatePaymentRequest, CreateTariffRequest,
+    FinancialContext, FinancialEvent, GenerateTariffBillsRequest, Meter, MeterReading, MeterResponse, NewAuditLog, Paginated,
+    Payment, PaymentMethod, PaymentResponse, PaymentStatus, Permission, ReceiptVerificationResponse,
+    SubmitReadingRequest, Tariff, TariffBillingResult, UpdateTariffRequest, UtilityType,
+};
+use crate::services::payment_service::{KaspiProvider, KaspiWebhookPayload, KASPI_SIGNATURE_HEADER};
+use crate::services::document_service::{ReceiptBill, ReceiptDocument, ReceiptItem};
+use crate::services::{
+    AuditService, BillingService, DocumentService, FileService, PaymentService, PermissionService,
+    SandboxService,
 };
 
 /// Ответ на подачу показаний
@@ -22,15 +33,27 @@ pub struct SubmitReadingResponse {
     pub consumption: Option<rust_decimal::Decimal>,
 }
 
+/// Успешный ответ
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct CommunalSuccessResponse {
+    pub success: bool,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/meters", get(get_meters))
         .route("/meters/readings", post(submit_reading))
         .route("/meters/readings/history", get(get_readings_history))
         .route("/bills", get(get_bills))
+        .route("/bills/generate", post(generate_bills))
         .route("/bills/:id", get(get_bill))
+        .route("/tariffs", get(get_tariffs).post(create_tariff))
+        .route("/tariffs/:id", put(update_tariff).delete(delete_tariff))
         .route("/payments", post(create_payment))
+        .route("/payments/webhook", post(payment_webhook))
         .route("/payments/:id", get(get_payment))
+        .route("/payments/:id/receipt", get(get_payment_receipt))
+        .route("/receipts/:token", get(verify_receipt))
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -255,7 +278,7 @@ pub async fn get_readings_history(
         ("limit" = Option<i64>, Query, description = "Количество записей")
     ),
     responses(
-        (status = 200, description = "Список счетов", body = Vec<BillResponse>),
+        (status = 200, description = "Список счетов", body = PaginatedBills),
         (status = 401, description = "Не авторизован"),
         (status = 403, description = "Нет квартир")
     )
@@ -264,11 +287,26 @@ pub async fn get_bills(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Query(query): Query<BillsQuery>,
-) -> AppResult<Json<Vec<BillResponse>>> {
+) -> AppResult<Json<Paginated<BillResponse>>> {
     let apartment_ids = get_user_apartments(&state, auth_user.user_id).await?;
 
     let limit = query.limit.unwrap_or(20).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
+    let page = query.page.unwrap_or(0);
+    let offset = page * limit;
+
+    let (total,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FROM bills
+        WHERE apartment_id = ANY($1)
+          AND ($2::uuid IS NULL OR apartment_id = $2)
+          AND ($3::varchar IS NULL OR status::text = $3)
+        "#,
+    )
+    .bind(&apartment_ids)
+    .bind(query.apartment_id)
+    .bind(&query.status)
+    .fetch_one(&state.pool)
+    .await?;
 
     let bills = sqlx::query_as::<_, Bill>(
         r#"
@@ -281,7 +319,7 @@ pub async fn get_bills(
         "#,
     )
     .bind(&apartment_ids)
-    .bind(&query.apartment_id)
+    .bind(query.apartment_id)
     .bind(&query.status)
     .bind(limit)
     .bind(offset)
@@ -313,12 +351,14 @@ pub async fn get_bills(
                     unit: i.unit,
                     rate: i.rate,
                     amount: i.amount,
+                    tariff_id: i.tariff_id,
                 })
                 .collect(),
+            tariffs: None,
         });
     }
 
-    Ok(Json(response))
+    Ok(Json(Paginated::new(response, page, limit, total)))
 }
 
 /// Получить счёт по ID
@@ -356,6 +396,10 @@ pub async fn get_bill(
         .fetch_all(&state.pool)
         .await?;
 
+    let tariffs =
+        BillingService::tariffs_for_period(&state.pool, bill.complex_id, bill.period_start, bill.period_end)
+            .await?;
+
     Ok(Json(BillResponse {
         id: bill.id,
         period: format!("{} - {}", bill.period_start, bill.period_end),
@@ -374,8 +418,10 @@ pub async fn get_bill(
                 unit: i.unit,
                 rate: i.rate,
                 amount: i.amount,
+                tariff_id: i.tariff_id,
             })
             .collect(),
+        tariffs: Some(tariffs),
     }))
 }
 
@@ -396,8 +442,13 @@ pub async fn get_bill(
 pub async fn create_payment(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Json(payload): Json<CreatePaymentRequest>,
 ) -> AppResult<Json<PaymentResponse>> {
+    let context = FinancialContext {
+        actor_id: Some(auth_user.user_id),
+        request_id: Some(request_id.0),
+    };
     let apartment_ids = get_user_apartments(&state, auth_user.user_id).await?;
 
     let bill =
@@ -412,6 +463,17 @@ pub async fn create_payment(
         return Err(AppError::BadRequest("Счёт уже оплачен".to_string()));
     }
 
+    // В демо-ЖК платёж через Kaspi проводится сразу, без обращения к провайдеру
+    let sandbox = SandboxService::is_sandbox(&state.pool, bill.complex_id).await?;
+
+    let kaspi = KaspiProvider::new(state.config.clone());
+    if payload.method == PaymentMethod::Kaspi && !sandbox && !kaspi.is_enabled() {
+        return Err(AppError::BadRequest(
+            "Оплата через Kaspi временно недоступна".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
     let payment = sqlx::query_as::<_, crate::models::Payment>(
         r#"
         INSERT INTO payments (bill_id, apartment_id, user_id, amount, method, status)
@@ -425,8 +487,51 @@ pub async fn create_payment(
     .bind(bill.total_amount)
     .bind(&payload.method)
     .bind(PaymentStatus::Pending)
-    .fetch_one(&state.pool)
+    .fetch_one(&mut *tx)
     .await?;
+    PaymentService::audit(&mut tx, &context, FinancialEvent::PaymentCreated, &payment, None)
+        .await?;
+    tx.commit().await?;
+
+    let payment = if payment.method == PaymentMethod::Kaspi && sandbox {
+        let external_id = format!("sandbox-{}", payment.id);
+        PaymentService::complete(&state.pool, &context, payment.id, &external_id)
+            .await?
+            .unwrap_or(payment)
+    } else if payment.method == PaymentMethod::Kaspi {
+        let description = format!(
+            "Оплата коммунальных услуг за {} - {}",
+            bill.period_start, bill.period_end
+        );
+
+        let external = match kaspi.create_payment(&payment, &description).await {
+            Ok(external) => external,
+            Err(e) => {
+                PaymentService::fail(&state.pool, &context, payment.id).await?;
+                return Err(e);
+            }
+        };
+
+        let mut tx = state.pool.begin().await?;
+        let payment = sqlx::query_as::<_, crate::models::Payment>(
+            r#"
+            UPDATE payments SET status = 'processing', external_id = $2, payment_url = $3
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(payment.id)
+        .bind(&external.external_id)
+        .bind(&external.payment_url)
+        .fetch_one(&mut *tx)
+        .await?;
+        PaymentService::audit(&mut tx, &context, FinancialEvent::PaymentProcessing, &payment, None)
+            .await?;
+        tx.commit().await?;
+        payment
+    } else {
+        payment
+    };
 
     Ok(Json(PaymentResponse {
         id: payment.id,
@@ -438,6 +543,86 @@ pub async fn create_payment(
     }))
 }
 
+/// Уведомление Kaspi о статусе платежа
+#[utoipa::path(
+    post,
+    path = "/api/v1/communal/payments/webhook",
+    tag = "communal",
+    params(
+        ("X-Kaspi-Signature" = String, Header, description = "HMAC-SHA256 тела запроса")
+    ),
+    request_body(content = String, content_type = "application/json"),
+    responses(
+        (status = 200, description = "Уведомление обработано", body = CommunalSuccessResponse),
+        (status = 400, description = "Некорректное уведомление"),
+        (status = 401, description = "Неверная подпись")
+    )
+)]
+pub async fn payment_webhook(
+    State(state): State<AppState>,
+    request_id: RequestId,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<Value>> {
+    let kaspi = KaspiProvider::new(state.config.clone());
+
+    let signature = headers
+        .get(KASPI_SIGNATURE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+
+    if !kaspi.verify_signature(&body, signature) {
+        tracing::warn!("Rejected Kaspi webhook with invalid signature");
+        return Err(AppError::Unauthorized);
+    }
+
+    let payload: KaspiWebhookPayload = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Некорректное уведомление: {}", e)))?;
+
+    let payment = sqlx::query_as::<_, crate::models::Payment>(
+        "SELECT * FROM payments WHERE id = $1 AND method = 'kaspi'",
+    )
+    .bind(payload.order_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Платеж не найден".to_string()))?;
+
+    // Уведомление приходит от провайдера, а не от пользователя
+    let context = FinancialContext {
+        actor_id: None,
+        request_id: Some(request_id.0),
+    };
+
+    match payload.status.as_str() {
+        "success" | "completed" => {
+            if payload.amount != payment.amount {
+                tracing::warn!(
+                    "Kaspi amount mismatch for payment {}: expected {}, got {}",
+                    payment.id,
+                    payment.amount,
+                    payload.amount
+                );
+                return Err(AppError::BadRequest("Сумма платежа не совпадает".to_string()));
+            }
+
+            if PaymentService::complete(&state.pool, &context, payment.id, &payload.id)
+                .await?
+                .is_some()
+            {
+                tracing::info!("Kaspi payment {} completed", payment.id);
+            }
+        }
+        "failed" | "declined" | "cancelled" | "expired" => {
+            PaymentService::fail(&state.pool, &context, payment.id).await?;
+        }
+        other => {
+            tracing::debug!("Ignoring Kaspi status {} for payment {}", other, payment.id);
+        }
+    }
+
+    Ok(Json(json!({"success": true})))
+}
+
 /// Получить платёж по ID
 #[utoipa::path(
     get,
@@ -476,3 +661,565 @@ pub async fn get_payment(
         created_at: payment.created_at,
     }))
 }
+
+/// PDF-квитанция об оплате. Генерируется при первом запросе и дальше отдаётся из хранилища
+#[utoipa::path(
+    get,
+    path = "/api/v1/communal/payments/{id}/receipt",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID платежа")
+    ),
+    responses(
+        (status = 200, description = "Квитанция", content_type = "application/pdf"),
+        (status = 400, description = "Платёж не завершён"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Платёж не найден")
+    )
+)]
+pub async fn get_payment_receipt(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    // Квитанцию видят плательщик и жители квартиры
+    let payment = sqlx::query_as::<_, Payment>(
+        r#"
+        SELECT p.* FROM payments p
+        WHERE p.id = $1
+          AND (p.user_id = $2 OR EXISTS (
+              SELECT 1 FROM apartments a
+              WHERE a.id = p.apartment_id AND (a.owner_id = $2 OR a.resident_id = $2)
+          ))
+        "#,
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Платеж не найден".to_string()))?;
+
+    if payment.status != PaymentStatus::Completed {
+        return Err(AppError::BadRequest("Платёж ещё не завершён".to_string()));
+    }
+
+    let receipt_number = payment
+        .receipt_number
+        .clone()
+        .unwrap_or_else(|| payment.id.simple().to_string()[..8].to_uppercase());
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("inline; filename=\"receipt-{}.pdf\"", receipt_number),
+        ),
+    ];
+
+    let file_service = FileService::new(&state.config).await?;
+    if let Some(key) = &payment.receipt_pdf_key {
+        match file_service.get_file(key).await {
+            Ok(data) => return Ok((headers, data)),
+            // Файл пропал из хранилища — сгенерируем заново
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let document = build_receipt_document(&state, &payment, receipt_number).await?;
+    let data = DocumentService::render_receipt(&document)?;
+
+    // Кэш не обязателен: если хранилище недоступно, квитанция всё равно отдаётся
+    let key = format!("receipts/{}.pdf", payment.id);
+    match file_service.put_file(&key, "application/pdf", data.clone()).await {
+        Ok(_) => {
+            sqlx::query("UPDATE payments SET receipt_pdf_key = $2 WHERE id = $1")
+                .bind(payment.id)
+                .bind(&key)
+                .execute(&state.pool)
+                .await?;
+        }
+        Err(e) => tracing::warn!("Receipt for payment {} not cached: {}", payment.id, e),
+    }
+
+    Ok((headers, data))
+}
+
+/// Данные квитанции: квартира, плательщик и погашенные платежом счета
+async fn build_receipt_document(
+    state: &AppState,
+    payment: &Payment,
+    receipt_number: String,
+) -> AppResult<ReceiptDocument> {
+    let (complex_name, address, apartment_number, building, payer_name): (
+        String,
+        Option<String>,
+        String,
+        Option<String>,
+        String,
+    ) = sqlx::query_as(
+        r#"
+        SELECT c.name, get_full_address(ad), a.number, a.building, u.display_name
+        FROM apartments a
+        JOIN complexes c ON c.id = a.complex_id
+        LEFT JOIN addresses ad ON ad.id = c.address_id
+        JOIN users u ON u.id = $2
+        WHERE a.id = $1
+        "#,
+    )
+    .bind(payment.apartment_id)
+    .bind(payment.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let mut allocations: Vec<(Uuid, rust_decimal::Decimal)> = sqlx::query_as(
+        "SELECT bill_id, amount FROM payment_allocations WHERE payment_id = $1 ORDER BY created_at",
+    )
+    .bind(payment.id)
+    .fetch_all(&state.pool)
+    .await?;
+    if allocations.is_empty() {
+        allocations.extend(payment.bill_id.map(|bill_id| (bill_id, payment.amount)));
+    }
+
+    let mut bills = Vec::new();
+    for (bill_id, allocated) in allocations {
+        let bill = sqlx::query_as::<_, Bill>("SELECT * FROM bills WHERE id = $1")
+            .bind(bill_id)
+            .fetch_one(&state.pool)
+            .await?;
+        let items = sqlx::query_as::<_, BillItem>(
+            "SELECT * FROM bill_items WHERE bill_id = $1 ORDER BY created_at",
+        )
+        .bind(bill_id)
+        .fetch_all(&state.pool)
+        .await?;
+
+        bills.push(ReceiptBill {
+            period: format!(
+                "{} – {}",
+                bill.period_start.format("%d.%m.%Y"),
+                bill.period_end.format("%d.%m.%Y")
+            ),
+            items: items
+                .into_iter()
+                .map(|item| ReceiptItem {
+                    description: item
+                        .description
+                        .unwrap_or_else(|| item.utility_type.title().to_string()),
+                    quantity: item.quantity.map(|quantity| match item.unit {
+                        Some(unit) => format!("{} {}", quantity.normalize(), unit),
+                        None => quantity.normalize().to_string(),
+                    }),
+                    amount: item.amount,
+                })
+                .collect(),
+            penalty: bill.penalty,
+            allocated,
+        });
+    }
+
+    Ok(ReceiptDocument {
+        receipt_number,
+        complex_name,
+        address,
+        apartment: match building {
+            Some(building) => format!("{}, корп. {}", apartment_number, building),
+            None => apartment_number,
+        },
+        payer_name,
+        method: payment.method.title().to_string(),
+        paid_at: payment.completed_at.unwrap_or(payment.created_at),
+        amount: payment.amount,
+        bills,
+        verification_url: payment.verification_token.as_ref().map(|token| {
+            format!(
+                "{}/api/v1/communal/receipts/{}",
+                state.config.public_url.trim_end_matches('/'),
+                token
+            )
+        }),
+    })
+}
+
+/// Проверить подлинность квитанции (ссылка из QR-кода, без авторизации)
+#[utoipa::path(
+    get,
+    path = "/api/v1/communal/receipts/{token}",
+    tag = "communal",
+    params(
+        ("token" = String, Path, description = "Код проверки из QR-кода")
+    ),
+    responses(
+        (status = 200, description = "Квитанция действительна", body = ReceiptVerificationResponse),
+        (status = 404, description = "Квитанция не найдена")
+    )
+)]
+pub async fn verify_receipt(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Json<ReceiptVerificationResponse>> {
+    let receipt = sqlx::query_as::<_, ReceiptVerificationResponse>(
+        r#"
+        SELECT p.receipt_number, p.amount, p.method, p.status,
+               c.name AS complex_name, a.number AS apartment_number,
+               p.completed_at AS paid_at
+        FROM payments p
+        JOIN apartments a ON a.id = p.apartment_id
+        JOIN complexes c ON c.id = a.complex_id
+        WHERE p.verification_token = $1 AND p.receipt_number IS NOT NULL
+        "#,
+    )
+    .bind(&token)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Квитанция не найдена".to_string()))?;
+
+    Ok(Json(receipt))
+}
+
+async fn get_tariff(state: &AppState, id: Uuid) -> AppResult<Tariff> {
+    sqlx::query_as::<_, Tariff>("SELECT * FROM tariffs WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Тариф не найден".to_string()))
+}
+
+/// Проверить ставку и период действия тарифа, в том числе пересечение с другими тарифами на ту же услугу
+async fn validate_tariff(
+    state: &AppState,
+    complex_id: Uuid,
+    utility_type: &UtilityType,
+    exclude_id: Option<Uuid>,
+    rate: rust_decimal::Decimal,
+    effective_from: NaiveDate,
+    effective_to: Option<NaiveDate>,
+) -> AppResult<()> {
+    if rate < rust_decimal::Decimal::ZERO {
+        return Err(AppError::BadRequest("Тариф не может быть отрицательным".to_string()));
+    }
+
+    if effective_to.is_some_and(|to| to < effective_from) {
+        return Err(AppError::BadRequest(
+            "Дата окончания действия раньше даты начала".to_string(),
+        ));
+    }
+
+    let overlapping: Option<(NaiveDate,)> = sqlx::query_as(
+        r#"
+        SELECT effective_from FROM tariffs
+        WHERE complex_id = $1
+          AND utility_type = $2
+          AND ($3::uuid IS NULL OR id <> $3)
+          AND daterange(effective_from, effective_to, '[]') && daterange($4, $5, '[]')
+        LIMIT 1
+        "#,
+    )
+    .bind(complex_id)
+    .bind(utility_type)
+    .bind(exclude_id)
+    .bind(effective_from)
+    .bind(effective_to)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some((from,)) = overlapping {
+        return Err(AppError::Conflict(format!(
+            "Период пересекается с тарифом, действующим с {}",
+            from
+        )));
+    }
+
+    Ok(())
+}
+
+/// Тарифы ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/communal/tariffs",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Тарифы ЖК", body = Vec<Tariff>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к ЖК")
+    )
+)]
+pub async fn get_tariffs(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+) -> AppResult<Json<Vec<Tariff>>> {
+    let complex_id = complex.complex_id()?;
+
+    let tariffs = sqlx::query_as::<_, Tariff>(
+        "SELECT * FROM tariffs WHERE complex_id = $1 ORDER BY utility_type, effective_from DESC",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(tariffs))
+}
+
+/// Создать тариф (председатель)
+#[utoipa::path(
+    post,
+    path = "/api/v1/communal/tariffs",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    request_body = CreateTariffRequest,
+    responses(
+        (status = 200, description = "Тариф создан", body = Tariff),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 409, description = "Период пересекается с другим тарифом")
+    )
+)]
+pub async fn create_tariff(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    request_id: RequestId,
+    Json(payload): Json<CreateTariffRequest>,
+) -> AppResult<Json<Tariff>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageBills).await?;
+
+    validate_tariff(
+        &state,
+        complex_id,
+        &payload.utility_type,
+        None,
+        payload.rate,
+        payload.effective_from,
+        payload.effective_to,
+    )
+    .await?;
+
+    let unit = payload
+        .unit
+        .unwrap_or_else(|| payload.basis.default_unit(&payload.utility_type).to_string());
+
+    let tariff = sqlx::query_as::<_, Tariff>(
+        r#"
+        INSERT INTO tariffs (complex_id, utility_type, basis, rate, unit, effective_from, effective_to, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING *
+        "#,
+    )
+    .bind(complex_id)
+    .bind(&payload.utility_type)
+    .bind(payload.basis)
+    .bind(payload.rate)
+    .bind(&unit)
+    .bind(payload.effective_from)
+    .bind(payload.effective_to)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "create_tariff",
+            entity_type: "tariff",
+            entity_id: Some(tariff.id),
+            old_value: None,
+            new_value: Some(json!(tariff)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(tariff))
+}
+
+/// Изменить тариф (председатель)
+#[utoipa::path(
+    put,
+    path = "/api/v1/communal/tariffs/{id}",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID тарифа")
+    ),
+    request_body = UpdateTariffRequest,
+    responses(
+        (status = 200, description = "Тариф обновлён", body = Tariff),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Тариф не найден"),
+        (status = 409, description = "Период пересекается с другим тарифом")
+    )
+)]
+pub async fn update_tariff(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateTariffRequest>,
+) -> AppResult<Json<Tariff>> {
+    let tariff = get_tariff(&state, id).await?;
+    PermissionService::require(&state.pool, &auth_user, tariff.complex_id, Permission::ManageBills)
+        .await?;
+
+    let rate = payload.rate.unwrap_or(tariff.rate);
+    let effective_from = payload.effective_from.unwrap_or(tariff.effective_from);
+    let effective_to = payload.effective_to.apply(tariff.effective_to);
+
+    validate_tariff(
+        &state,
+        tariff.complex_id,
+        &tariff.utility_type,
+        Some(tariff.id),
+        rate,
+        effective_from,
+        effective_to,
+    )
+    .await?;
+
+    let updated = sqlx::query_as::<_, Tariff>(
+        r#"
+        UPDATE tariffs SET
+            rate = $2,
+            unit = COALESCE($3, unit),
+            effective_from = $4,
+            effective_to = $5,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(rate)
+    .bind(&payload.unit)
+    .bind(effective_from)
+    .bind(effective_to)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(tariff.complex_id),
+            action: "update_tariff",
+            entity_type: "tariff",
+            entity_id: Some(id),
+            old_value: Some(json!(tariff)),
+            new_value: Some(json!(updated)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(updated))
+}
+
+/// Удалить тариф (председатель). Тариф, по которому уже начислены счета, удалить нельзя — только закрыть датой окончания
+#[utoipa::path(
+    delete,
+    path = "/api/v1/communal/tariffs/{id}",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID тарифа")
+    ),
+    responses(
+        (status = 200, description = "Тариф удалён", body = CommunalSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Тариф не найден"),
+        (status = 409, description = "По тарифу уже есть начисления")
+    )
+)]
+pub async fn delete_tariff(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let tariff = get_tariff(&state, id).await?;
+    PermissionService::require(&state.pool, &auth_user, tariff.complex_id, Permission::ManageBills)
+        .await?;
+
+    let used: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM bill_items WHERE tariff_id = $1 LIMIT 1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?;
+    if used.is_some() {
+        return Err(AppError::Conflict(
+            "По тарифу уже есть начисления, укажите дату окончания действия".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM tariffs WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(tariff.complex_id),
+            action: "delete_tariff",
+            entity_type: "tariff",
+            entity_id: Some(id),
+            old_value: Some(json!(tariff)),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({ "success": true })))
+}
+
+/// Начислить счета по тарифам за месяц (председатель)
+#[utoipa::path(
+    post,
+    path = "/api/v1/communal/bills/generate",
+    tag = "communal",
+    security(("bearer_auth" = [])),
+    request_body = GenerateTariffBillsRequest,
+    responses(
+        (status = 200, description = "Начисление выполнено", body = TariffBillingResult),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn generate_bills(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    request_id: RequestId,
+    Json(payload): Json<GenerateTariffBillsRequest>,
+) -> AppResult<Json<TariffBillingResult>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageBills).await?;
+
+    let result = BillingService::generate_from_tariffs(&state.pool, complex_id, payload.period).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "generate_tariff_bills",
+            entity_type: "complex",
+            entity_id: Some(complex_id),
+            old_value: None,
+            new_value: Some(json!(result)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(result))
+}
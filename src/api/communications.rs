@@ -0,0 +1,214 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{AppState, AuthUser, RequestId};
+use crate::models::{
+    CommunicationExport, CreateCommunicationExportRequest, ExportStatus, NewAuditLog, Permission,
+};
+use crate::services::{AuditService, CommunicationExportService, FileService, PermissionService};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:id/communication-exports",
+            get(list_exports).post(create_export),
+        )
+        .route("/:id/communication-exports/:export_id", get(get_export))
+        .route(
+            "/:id/communication-exports/:export_id/file",
+            get(download_export),
+        )
+}
+
+/// Историю рассылок выгружает правление ОСИ этого ЖК
+async fn check_manage(state: &AppState, complex_id: Uuid, auth_user: &AuthUser) -> AppResult<()> {
+    PermissionService::require(&state.pool, auth_user, complex_id, Permission::ManageOsi).await
+}
+
+async fn find_export(
+    state: &AppState,
+    complex_id: Uuid,
+    export_id: Uuid,
+) -> AppResult<CommunicationExport> {
+    sqlx::query_as::<_, CommunicationExport>(
+        "SELECT * FROM communication_exports WHERE id = $1 AND complex_id = $2",
+    )
+    .bind(export_id)
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Выгрузка не найдена".to_string()))
+}
+
+/// Заказать выгрузку объявлений, экстренных оповещений и рассылок за период
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/communication-exports",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК")
+    ),
+    request_body = CreateCommunicationExportRequest,
+    responses(
+        (status = 200, description = "Выгрузка поставлена в очередь", body = CommunicationExport),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ в этом ЖК"),
+        (status = 422, description = "Некорректный период")
+    )
+)]
+pub async fn create_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(complex_id): Path<Uuid>,
+    Json(payload): Json<CreateCommunicationExportRequest>,
+) -> AppResult<Json<CommunicationExport>> {
+    check_manage(&state, complex_id, &auth_user).await?;
+
+    let export =
+        CommunicationExportService::request(&state.pool, complex_id, auth_user.user_id, &payload)
+            .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "request_communication_export",
+            entity_type: "communication_export",
+            entity_id: Some(export.id),
+            old_value: None,
+            new_value: Some(json!({
+                "date_from": export.date_from,
+                "date_to": export.date_to,
+                "format": export.format,
+            })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(export))
+}
+
+/// Последние выгрузки истории рассылок ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/complexes/{id}/communication-exports",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК")
+    ),
+    responses(
+        (status = 200, description = "Выгрузки, новые сверху", body = Vec<CommunicationExport>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ в этом ЖК")
+    )
+)]
+pub async fn list_exports(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(complex_id): Path<Uuid>,
+) -> AppResult<Json<Vec<CommunicationExport>>> {
+    check_manage(&state, complex_id, &auth_user).await?;
+
+    let exports = sqlx::query_as::<_, CommunicationExport>(
+        "SELECT * FROM communication_exports WHERE complex_id = $1 ORDER BY created_at DESC LIMIT 50",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(exports))
+}
+
+/// Статус выгрузки
+#[utoipa::path(
+    get,
+    path = "/api/v1/complexes/{id}/communication-exports/{export_id}",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        ("export_id" = Uuid, Path, description = "ID выгрузки")
+    ),
+    responses(
+        (status = 200, description = "Выгрузка", body = CommunicationExport),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ в этом ЖК"),
+        (status = 404, description = "Выгрузка не найдена")
+    )
+)]
+pub async fn get_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((complex_id, export_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<CommunicationExport>> {
+    check_manage(&state, complex_id, &auth_user).await?;
+
+    Ok(Json(find_export(&state, complex_id, export_id).await?))
+}
+
+/// Скачать готовую выгрузку
+#[utoipa::path(
+    get,
+    path = "/api/v1/complexes/{id}/communication-exports/{export_id}/file",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        ("export_id" = Uuid, Path, description = "ID выгрузки")
+    ),
+    responses(
+        (status = 200, description = "PDF или CSV", content_type = "application/octet-stream"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ в этом ЖК"),
+        (status = 404, description = "Выгрузка не найдена"),
+        (status = 409, description = "Выгрузка ещё не готова")
+    )
+)]
+pub async fn download_export(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((complex_id, export_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<impl IntoResponse> {
+    check_manage(&state, complex_id, &auth_user).await?;
+
+    let export = find_export(&state, complex_id, export_id).await?;
+    let key = match (export.status, &export.file_key) {
+        (ExportStatus::Completed, Some(key)) => key,
+        (ExportStatus::Failed, _) => {
+            return Err(AppError::Conflict(
+                "Выгрузку не удалось сформировать, она будет повторена".to_string(),
+            ));
+        }
+        _ => {
+            return Err(AppError::Conflict("Выгрузка ещё формируется".to_string()));
+        }
+    };
+
+    let data = FileService::new(&state.config).await?.get_file(key).await?;
+    let headers = [
+        (header::CONTENT_TYPE, export.format.content_type().to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"communications-{}-{}.{}\"",
+                export.date_from.format("%Y%m%d"),
+                export.date_to.format("%Y%m%d"),
+                export.format.extension()
+            ),
+        ),
+    ];
+    Ok((headers, data))
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Multipart, Path, Query, State},
     routing::{get, post},
     Json, Router,
 };
@@ -7,10 +7,21 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{AppState, AuthUser};
+use crate::middleware::{is_admin_or_higher, AppState, AuthUser, RequestId};
 use crate::models::{
-    Complex, ComplexAmenities, ComplexResponse, ComplexStatus, CreateComplexRequest,
-    JoinComplexRequest, JoinRequestStatus, SearchComplexQuery,
+    Complex, ComplexAmenities, ComplexPhotoUploadResponse, ComplexResponse, ComplexStatus,
+    ComplexVerification, ComplexVerificationEvidence, ComplexVerificationResponse,
+    ComplexVerificationStatus, CreateComplexRequest, JoinComplexRequest, JoinRequestStatus,
+    NewAuditLog, NewFieldChange, Permission, PhotoVariants, SearchComplexQuery,
+    VerificationEvidenceType,
+};
+use crate::services::{
+    file_service::{
+        validate_document_content_type, validate_image_content_type, MAX_DOCUMENT_SIZE,
+        MAX_IMAGE_SIZE,
+    },
+    AuditService, ComplexVerificationService, FieldHistoryService, FileService, PermissionService,
+    SandboxService,
 };
 
 /// Ответ на проверку существования ЖК
@@ -21,6 +32,13 @@ pub struct ComplexExistsResponse {
     pub complex_name: Option<String>,
 }
 
+/// Ответ на сброс демо-ЖК
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct SandboxResetResponse {
+    pub success: bool,
+    pub complex_id: Uuid,
+}
+
 /// Ответ на подачу заявки
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct JoinComplexResponse {
@@ -36,6 +54,11 @@ pub fn routes() -> Router<AppState> {
         .route("/check", get(check_complex_exists))
         .route("/:id", get(get_complex))
         .route("/:id/join", post(join_complex))
+        .route("/:id/photos", post(upload_complex_photo))
+        .route("/:id/verification", get(get_verification))
+        .route("/:id/verification/evidence", post(upload_verification_evidence))
+        .route("/:id/verification/submit", post(resubmit_verification))
+        .route("/:id/sandbox/reset", post(reset_sandbox))
 }
 
 /// Поиск жилых комплексов
@@ -63,6 +86,7 @@ pub async fn search_complexes(
         WHERE ($1::varchar IS NULL OR city_id = $1)
           AND ($2::varchar IS NULL OR name ILIKE $2)
           AND status = 'active'
+          AND NOT is_sandbox
         ORDER BY name
         LIMIT 50
         "#,
@@ -93,8 +117,8 @@ pub async fn search_complexes(
         };
 
         // Получаем фото
-        let photos: Vec<(String,)> = sqlx::query_as(
-            "SELECT url FROM complex_photos WHERE complex_id = $1 ORDER BY sort_order",
+        let photos = sqlx::query_as::<_, PhotoVariants>(
+            "SELECT url, thumbnail_url, medium_url FROM complex_photos WHERE complex_id = $1 ORDER BY sort_order",
         )
         .bind(complex.id)
         .fetch_all(&state.pool)
@@ -120,7 +144,8 @@ pub async fn search_complexes(
                 has_cctv: complex.has_cctv,
             },
             status: complex.status,
-            photos: photos.into_iter().map(|(url,)| url).collect(),
+            photos: photos.iter().map(|p| p.url.clone()).collect(),
+            photo_variants: photos,
         });
     }
 
@@ -167,11 +192,12 @@ pub async fn get_complex(
         None
     };
 
-    let photos: Vec<(String,)> =
-        sqlx::query_as("SELECT url FROM complex_photos WHERE complex_id = $1 ORDER BY sort_order")
-            .bind(complex.id)
-            .fetch_all(&state.pool)
-            .await?;
+    let photos = sqlx::query_as::<_, PhotoVariants>(
+        "SELECT url, thumbnail_url, medium_url FROM complex_photos WHERE complex_id = $1 ORDER BY sort_order",
+    )
+    .bind(complex.id)
+    .fetch_all(&state.pool)
+    .await?;
 
     Ok(Json(ComplexResponse {
         id: complex.id,
@@ -193,7 +219,8 @@ pub async fn get_complex(
             has_cctv: complex.has_cctv,
         },
         status: complex.status,
-        photos: photos.into_iter().map(|(url,)| url).collect(),
+        photos: photos.iter().map(|p| p.url.clone()).collect(),
+        photo_variants: photos,
     }))
 }
 
@@ -282,13 +309,13 @@ pub async fn create_complex(
         "#,
     )
     .bind(&payload.city_id)
-    .bind(&payload.address_id)
+    .bind(payload.address_id)
     .bind(&payload.name)
     .bind(&payload.description)
-    .bind(&payload.buildings_count)
-    .bind(&payload.floors_count)
-    .bind(&payload.apartments_count)
-    .bind(&payload.year_built)
+    .bind(payload.buildings_count)
+    .bind(payload.floors_count)
+    .bind(payload.apartments_count)
+    .bind(payload.year_built)
     .bind(payload.has_parking.unwrap_or(false))
     .bind(payload.has_underground_parking.unwrap_or(false))
     .bind(payload.has_playground.unwrap_or(false))
@@ -301,6 +328,12 @@ pub async fn create_complex(
     .fetch_one(&state.pool)
     .await?;
 
+    // ЖК становится активным только после проверки администратором
+    sqlx::query("INSERT INTO complex_verifications (complex_id) VALUES ($1)")
+        .bind(complex.id)
+        .execute(&state.pool)
+        .await?;
+
     Ok(Json(ComplexResponse {
         id: complex.id,
         city_id: complex.city_id,
@@ -322,6 +355,7 @@ pub async fn create_complex(
         },
         status: complex.status,
         photos: vec![],
+        photo_variants: vec![],
     }))
 }
 
@@ -400,3 +434,433 @@ pub async fn join_complex(
         "message": "Заявка отправлена на рассмотрение"
     })))
 }
+
+/// Загрузка фото ЖК (с превью и облегчённой копией)
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/photos",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID жилого комплекса")
+    ),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Фото загружено", body = ComplexPhotoUploadResponse),
+        (status = 400, description = "Неверный формат файла"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "ЖК не найден")
+    )
+)]
+pub async fn upload_complex_photo(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<ComplexPhotoUploadResponse>> {
+    let exists: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM complexes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?;
+    if exists.is_none() {
+        return Err(AppError::NotFound("ЖК не найден".to_string()));
+    }
+
+    PermissionService::require(&state.pool, &auth_user, id, Permission::ManageOsi).await?;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        if field.name() != Some("photo") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .ok_or_else(|| AppError::BadRequest("Content-Type отсутствует".to_string()))?
+            .to_string();
+
+        if !validate_image_content_type(&content_type) {
+            return Err(AppError::BadRequest(
+                "Недопустимый формат изображения".to_string(),
+            ));
+        }
+
+        let file_name = field.file_name().unwrap_or("photo.jpg").to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        if data.len() > MAX_IMAGE_SIZE {
+            return Err(AppError::BadRequest("Файл слишком большой".to_string()));
+        }
+
+        let image = FileService::new(&state.config)
+            .await?
+            .with_upload_queue(&state.pool)
+            .upload_image("complexes", &file_name, &content_type, data.to_vec())
+            .await?;
+
+        let photo_id: (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO complex_photos (complex_id, url, thumbnail_url, medium_url, is_main, sort_order)
+            SELECT $1, $2, $3, $4, COUNT(*) = 0, COUNT(*)::int
+            FROM complex_photos WHERE complex_id = $1
+            RETURNING id
+            "#,
+        )
+        .bind(id)
+        .bind(&image.url)
+        .bind(&image.thumbnail_url)
+        .bind(&image.medium_url)
+        .fetch_one(&state.pool)
+        .await?;
+
+        return Ok(Json(ComplexPhotoUploadResponse {
+            id: photo_id.0,
+            url: image.url,
+            thumbnail_url: image.thumbnail_url,
+            medium_url: image.medium_url,
+        }));
+    }
+
+    Err(AppError::BadRequest("Файл не найден".to_string()))
+}
+
+/// Проверка ЖК доступна его создателю и администраторам
+async fn creator_verification(
+    state: &AppState,
+    auth_user: &AuthUser,
+    complex_id: Uuid,
+) -> AppResult<ComplexVerification> {
+    let creator: Option<(Option<Uuid>,)> =
+        sqlx::query_as("SELECT created_by FROM complexes WHERE id = $1")
+            .bind(complex_id)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    let (created_by,) = creator.ok_or_else(|| AppError::NotFound("ЖК не найден".to_string()))?;
+    if created_by != Some(auth_user.user_id) && !is_admin_or_higher(&auth_user.role) {
+        return Err(AppError::Forbidden);
+    }
+
+    ComplexVerificationService::load(&state.pool, complex_id).await
+}
+
+/// Ход проверки ЖК: чек-лист, документы, запрос сведений или причины отказа
+#[utoipa::path(
+    get,
+    path = "/api/v1/complexes/{id}/verification",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID жилого комплекса")
+    ),
+    responses(
+        (status = 200, description = "Состояние проверки", body = ComplexVerificationResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "ЖК создан другим пользователем"),
+        (status = 404, description = "ЖК не найден")
+    )
+)]
+pub async fn get_verification(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    let verification = creator_verification(&state, &auth_user, id).await?;
+    let detail = ComplexVerificationService::detail(&state.pool, verification).await?;
+
+    Ok(Json(detail))
+}
+
+/// Загрузка документа для проверки ЖК (поля `evidence_type`, `comment`, `file`)
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/verification/evidence",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID жилого комплекса")
+    ),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Документ загружен", body = ComplexVerificationEvidence),
+        (status = 400, description = "Неверный формат файла"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "ЖК создан другим пользователем"),
+        (status = 404, description = "ЖК не найден"),
+        (status = 409, description = "Проверка уже завершена")
+    )
+)]
+pub async fn upload_verification_evidence(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<ComplexVerificationEvidence>> {
+    let verification = creator_verification(&state, &auth_user, id).await?;
+    if matches!(
+        verification.status,
+        ComplexVerificationStatus::Approved | ComplexVerificationStatus::Rejected
+    ) {
+        return Err(AppError::Conflict("Проверка ЖК уже завершена".to_string()));
+    }
+
+    let mut evidence_type = None;
+    let mut comment = None;
+    let mut upload = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        match field.name() {
+            Some("evidence_type") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+                evidence_type = Some(
+                    serde_json::from_value::<VerificationEvidenceType>(Value::String(value))
+                        .map_err(|_| AppError::BadRequest("Неизвестный тип документа".to_string()))?,
+                );
+            }
+            Some("comment") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+                comment = Some(value).filter(|value| !value.trim().is_empty());
+            }
+            Some("file") => {
+                let content_type = field
+                    .content_type()
+                    .ok_or_else(|| AppError::BadRequest("Content-Type отсутствует".to_string()))?
+                    .to_string();
+
+                if !validate_document_content_type(&content_type) {
+                    return Err(AppError::BadRequest(
+                        "Недопустимый формат документа".to_string(),
+                    ));
+                }
+
+                let file_name = field.file_name().unwrap_or("document.pdf").to_string();
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+                if data.len() > MAX_DOCUMENT_SIZE {
+                    return Err(AppError::BadRequest("Файл слишком большой".to_string()));
+                }
+
+                upload = Some((file_name, content_type, data));
+            }
+            _ => {}
+        }
+    }
+
+    let evidence_type =
+        evidence_type.ok_or_else(|| AppError::BadRequest("Не указан тип документа".to_string()))?;
+    let (file_name, content_type, data) =
+        upload.ok_or_else(|| AppError::BadRequest("Файл не найден".to_string()))?;
+
+    let file_url = FileService::new(&state.config)
+        .await?
+        .with_upload_queue(&state.pool)
+        .upload_file("verification", &file_name, &content_type, data.to_vec())
+        .await?;
+
+    let evidence = sqlx::query_as::<_, ComplexVerificationEvidence>(
+        r#"
+        INSERT INTO complex_verification_evidence (
+            complex_id, evidence_type, file_url, file_name, comment, uploaded_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(evidence_type)
+    .bind(&file_url)
+    .bind(&file_name)
+    .bind(&comment)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "upload_verification_evidence",
+            entity_type: "complex_verification_evidence",
+            entity_id: Some(evidence.id),
+            old_value: None,
+            new_value: Some(json!({"evidence_type": evidence_type, "file_name": file_name})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(evidence))
+}
+
+/// Вернуть ЖК на проверку после того, как запрошенные сведения дополнены,
+/// или подать заново заявку, закрытую за отсутствием движения
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/verification/submit",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID жилого комплекса")
+    ),
+    responses(
+        (status = 200, description = "ЖК снова на проверке", body = ComplexVerificationResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "ЖК создан другим пользователем"),
+        (status = 404, description = "ЖК не найден"),
+        (status = 409, description = "Сведения не запрашивались и заявка не закрывалась по сроку")
+    )
+)]
+pub async fn resubmit_verification(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ComplexVerificationResponse>> {
+    let verification = creator_verification(&state, &auth_user, id).await?;
+    let expired = verification.status == ComplexVerificationStatus::Rejected
+        && verification.expired_at.is_some();
+    if verification.status != ComplexVerificationStatus::NeedsInfo && !expired {
+        return Err(AppError::Conflict(
+            "Администратор не запрашивал дополнительные сведения".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    // Чек-лист и документы сохраняются, заявка просто возвращается в очередь
+    let updated = sqlx::query_as::<_, ComplexVerification>(
+        r#"
+        UPDATE complex_verifications
+        SET status = 'submitted', rejection_reasons = '{}', expired_at = NULL,
+            expiry_reminded_at = NULL, submitted_at = NOW(), updated_at = NOW()
+        WHERE complex_id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if expired {
+        let reopened = sqlx::query(
+            "UPDATE complexes SET status = 'pending', updated_at = NOW() WHERE id = $1 AND status = 'inactive'",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        if reopened.rows_affected() > 0 {
+            FieldHistoryService::record(
+                &mut *tx,
+                NewFieldChange {
+                    complex_id: id,
+                    entity_type: "complex",
+                    entity_id: id,
+                    changed_by: Some(auth_user.user_id),
+                    old_value: json!({"status": ComplexStatus::Inactive}),
+                    new_value: json!({"status": ComplexStatus::Pending}),
+                    request_id: Some(request_id.0.clone()),
+                },
+            )
+            .await?;
+        }
+    }
+
+    AuditService::record(
+        &mut *tx,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "resubmit_complex_verification",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": verification.status, "expired_at": verification.expired_at})),
+            new_value: Some(json!({"status": updated.status})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    let detail = ComplexVerificationService::detail(&state.pool, updated).await?;
+    Ok(Json(detail))
+}
+
+/// Сбросить демо-ЖК к эталонным данным: всё созданное через API удаляется
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/sandbox/reset",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID демо-ЖК")
+    ),
+    responses(
+        (status = 200, description = "Данные демо-ЖК сброшены", body = SandboxResetResponse),
+        (status = 400, description = "ЖК не демонстрационный"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ в этом ЖК"),
+        (status = 404, description = "ЖК не найден")
+    )
+)]
+pub async fn reset_sandbox(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<SandboxResetResponse>> {
+    let complex = sqlx::query_as::<_, Complex>("SELECT * FROM complexes WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ЖК не найден".to_string()))?;
+
+    if !is_admin_or_higher(&auth_user.role) {
+        PermissionService::require(&state.pool, &auth_user, id, Permission::ManageOsi).await?;
+    }
+
+    SandboxService::reset(&state.pool, &complex).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(id),
+            action: "reset_sandbox",
+            entity_type: "complex",
+            entity_id: Some(id),
+            old_value: None,
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(SandboxResetResponse {
+        success: true,
+        complex_id: id,
+    }))
+}
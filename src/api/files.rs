@@ -0,0 +1,421 @@
+use std::time::Duration;
+
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use chrono::Utc;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::api::maintenance::check_photo_uploader;
+use crate::error::{AppError, AppResult};
+use crate::middleware::{AppState, AuthUser};
+use crate::models::{
+    ConfirmUploadRequest, ConfirmUploadResponse, DocumentType, DomainEventType, FileUpload,
+    FileUploadStatus, MaintenanceRequest, NewDomainEvent, Osi, Permission, PresignUploadRequest,
+    PresignUploadResponse, UploadPurpose,
+};
+use crate::services::file_service::{
+    validate_document_content_type, validate_image_content_type, MAX_DOCUMENT_SIZE,
+    MAX_IMAGE_SIZE,
+};
+use crate::services::storage::{content_type_for_key, LocalStorage, StorageBackend};
+use crate::services::{EventService, FileService, PermissionService};
+
+/// Время жизни подписанной ссылки на загрузку
+const PRESIGN_TTL: Duration = Duration::from_secs(15 * 60);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/presign", post(presign_upload))
+        .route("/:id/confirm", post(confirm_upload))
+        .route(
+            "/local/*key",
+            get(download_local_file)
+                .put(upload_local_file)
+                .layer(DefaultBodyLimit::max(MAX_DOCUMENT_SIZE)),
+        )
+}
+
+/// Подпись ссылки на загрузку в локальное хранилище
+#[derive(Debug, Deserialize)]
+pub struct LocalUploadQuery {
+    pub expires: i64,
+    pub signature: String,
+}
+
+/// Локальное хранилище, если оно выбрано в конфигурации
+fn local_storage(state: &AppState) -> AppResult<LocalStorage> {
+    if state.config.storage_backend != "local" {
+        return Err(AppError::NotFound("Файл не найден".to_string()));
+    }
+
+    Ok(LocalStorage::new(&state.config))
+}
+
+/// Проверка, что пользователь может прикреплять файлы к объекту
+async fn check_upload_target(
+    state: &AppState,
+    auth_user: &AuthUser,
+    purpose: UploadPurpose,
+    target_id: Uuid,
+) -> AppResult<()> {
+    match purpose {
+        UploadPurpose::ListingPhoto => {
+            let seller: (Uuid,) =
+                sqlx::query_as("SELECT seller_id FROM marketplace_listings WHERE id = $1")
+                    .bind(target_id)
+                    .fetch_optional(&state.pool)
+                    .await?
+                    .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+
+            if seller.0 != auth_user.user_id {
+                return Err(AppError::Forbidden);
+            }
+        }
+        UploadPurpose::MaintenancePhoto => {
+            let req = sqlx::query_as::<_, MaintenanceRequest>(
+                "SELECT * FROM maintenance_requests WHERE id = $1",
+            )
+            .bind(target_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
+
+            check_photo_uploader(state, auth_user, &req).await?;
+        }
+        UploadPurpose::OsiDocument => {
+            let osi = sqlx::query_as::<_, Osi>("SELECT * FROM osi WHERE id = $1")
+                .bind(target_id)
+                .fetch_optional(&state.pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
+
+            PermissionService::require(
+                &state.pool,
+                auth_user,
+                osi.complex_id,
+                Permission::ManageDocuments,
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Подписанная ссылка для прямой загрузки файла в хранилище
+#[utoipa::path(
+    post,
+    path = "/api/v1/files/presign",
+    tag = "files",
+    security(("bearer_auth" = [])),
+    request_body = PresignUploadRequest,
+    responses(
+        (status = 200, description = "Ссылка для загрузки", body = PresignUploadResponse),
+        (status = 400, description = "Недопустимый тип или размер файла"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к объекту"),
+        (status = 404, description = "Объект не найден")
+    )
+)]
+pub async fn presign_upload(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<PresignUploadRequest>,
+) -> AppResult<Json<PresignUploadResponse>> {
+    let (allowed, max_size) = match payload.purpose {
+        UploadPurpose::OsiDocument => (
+            validate_document_content_type(&payload.content_type),
+            MAX_DOCUMENT_SIZE,
+        ),
+        _ => (
+            validate_image_content_type(&payload.content_type),
+            MAX_IMAGE_SIZE,
+        ),
+    };
+
+    if !allowed {
+        return Err(AppError::BadRequest("Недопустимый тип файла".to_string()));
+    }
+    if payload.size <= 0 || payload.size > max_size as i64 {
+        return Err(AppError::BadRequest(format!(
+            "Размер файла должен быть не больше {} МБ",
+            max_size / 1024 / 1024
+        )));
+    }
+
+    check_upload_target(&state, &auth_user, payload.purpose, payload.target_id).await?;
+
+    let file_service = FileService::new(&state.config).await?;
+    let key = FileService::new_key(payload.purpose.folder(), &payload.file_name);
+    let upload_url = file_service
+        .presigned_upload_url(&key, &payload.content_type, PRESIGN_TTL)
+        .await?;
+    let expires_at = Utc::now() + chrono::Duration::seconds(PRESIGN_TTL.as_secs() as i64);
+
+    let upload_id: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO file_uploads (user_id, purpose, target_id, object_key, content_type, max_size, expires_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(payload.purpose)
+    .bind(payload.target_id)
+    .bind(&key)
+    .bind(&payload.content_type)
+    .bind(payload.size)
+    .bind(expires_at)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(PresignUploadResponse {
+        upload_id: upload_id.0,
+        upload_url,
+        content_type: payload.content_type,
+        expires_at,
+        confirm_url: format!("/api/v1/files/{}/confirm", upload_id.0),
+    }))
+}
+
+/// Подтверждение загрузки: файл прикрепляется к объявлению, заявке или ОСИ
+#[utoipa::path(
+    post,
+    path = "/api/v1/files/{id}/confirm",
+    tag = "files",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID загрузки")
+    ),
+    request_body = ConfirmUploadRequest,
+    responses(
+        (status = 200, description = "Файл прикреплён", body = ConfirmUploadResponse),
+        (status = 400, description = "Файл не загружен, слишком большой или ссылка истекла"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа"),
+        (status = 404, description = "Загрузка не найдена")
+    )
+)]
+pub async fn confirm_upload(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ConfirmUploadRequest>,
+) -> AppResult<Json<ConfirmUploadResponse>> {
+    let upload = sqlx::query_as::<_, FileUpload>(
+        "SELECT * FROM file_uploads WHERE id = $1 AND user_id = $2",
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Загрузка не найдена".to_string()))?;
+
+    if upload.status == FileUploadStatus::Confirmed {
+        return Err(AppError::BadRequest("Загрузка уже подтверждена".to_string()));
+    }
+    if upload.expires_at < Utc::now() {
+        return Err(AppError::BadRequest("Срок действия ссылки истёк".to_string()));
+    }
+
+    let title = match upload.purpose {
+        UploadPurpose::OsiDocument => Some(
+            payload
+                .title
+                .as_deref()
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .ok_or_else(|| AppError::BadRequest("title обязателен".to_string()))?
+                .to_string(),
+        ),
+        _ => None,
+    };
+
+    // Права могли измениться с момента выдачи ссылки
+    check_upload_target(&state, &auth_user, upload.purpose, upload.target_id).await?;
+
+    let file_service = FileService::new(&state.config).await?;
+    let size = file_service
+        .object_size(&upload.object_key)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Файл не загружен".to_string()))?;
+
+    if size > upload.max_size {
+        file_service.delete_file(&upload.object_key).await?;
+        return Err(AppError::BadRequest(
+            "Размер файла превышает заявленный".to_string(),
+        ));
+    }
+
+    let url = file_service.public_url(&upload.object_key);
+
+    // Фото объявлений показываются списками, для них сразу готовим превью
+    let variants = match upload.purpose {
+        UploadPurpose::ListingPhoto => {
+            Some(file_service.process_stored_image(&upload.object_key).await?)
+        }
+        _ => None,
+    };
+
+    let mut tx = state.pool.begin().await?;
+
+    let entity_id: (Uuid,) = match upload.purpose {
+        UploadPurpose::ListingPhoto => {
+            sqlx::query_as(
+                r#"
+                INSERT INTO listing_photos (listing_id, url, thumbnail_url, medium_url, is_main, sort_order)
+                SELECT $1, $2, $3, $4, COUNT(*) = 0, COUNT(*)::int
+                FROM listing_photos WHERE listing_id = $1
+                RETURNING id
+                "#,
+            )
+            .bind(upload.target_id)
+            .bind(&url)
+            .bind(variants.as_ref().map(|v| &v.thumbnail_url))
+            .bind(variants.as_ref().map(|v| &v.medium_url))
+            .fetch_one(&mut *tx)
+            .await?
+        }
+        UploadPurpose::MaintenancePhoto => {
+            sqlx::query_as(
+                "INSERT INTO maintenance_photos (request_id, url, is_before, uploaded_by) VALUES ($1, $2, $3, $4) RETURNING id",
+            )
+            .bind(upload.target_id)
+            .bind(&url)
+            .bind(payload.is_before.unwrap_or(true))
+            .bind(auth_user.user_id)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+        UploadPurpose::OsiDocument => {
+            sqlx::query_as(
+                r#"
+                INSERT INTO osi_documents (osi_id, title, description, document_type, file_url, file_size, uploaded_by)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id
+                "#,
+            )
+            .bind(upload.target_id)
+            .bind(&title)
+            .bind(&payload.description)
+            .bind(payload.document_type.clone().unwrap_or(DocumentType::Other))
+            .bind(&url)
+            .bind(size as i32)
+            .bind(auth_user.user_id)
+            .fetch_one(&mut *tx)
+            .await?
+        }
+    };
+
+    sqlx::query(
+        r#"
+        UPDATE file_uploads SET
+            status = 'confirmed', url = $2, entity_id = $3, confirmed_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(upload.id)
+    .bind(&url)
+    .bind(entity_id.0)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    if upload.purpose == UploadPurpose::OsiDocument {
+        let complex_id: (Uuid,) = sqlx::query_as("SELECT complex_id FROM osi WHERE id = $1")
+            .bind(upload.target_id)
+            .fetch_one(&state.pool)
+            .await?;
+
+        EventService::record(
+            &state.pool,
+            NewDomainEvent {
+                complex_id: complex_id.0,
+                osi_id: Some(upload.target_id),
+                actor_id: auth_user.user_id,
+                event_type: DomainEventType::DocumentUploaded,
+                entity_type: "document",
+                entity_id: Some(entity_id.0),
+                data: Some(json!({"title": title, "document_type": payload.document_type})),
+            },
+        )
+        .await?;
+    }
+
+    Ok(Json(ConfirmUploadResponse {
+        upload_id: upload.id,
+        url,
+        entity_id: entity_id.0,
+    }))
+}
+
+/// Файл из локального хранилища (только при `STORAGE_BACKEND=local`)
+#[utoipa::path(
+    get,
+    path = "/api/v1/files/local/{key}",
+    tag = "files",
+    params(
+        ("key" = String, Path, description = "Ключ файла")
+    ),
+    responses(
+        (status = 200, description = "Содержимое файла"),
+        (status = 404, description = "Файл не найден")
+    )
+)]
+pub async fn download_local_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+) -> AppResult<impl IntoResponse> {
+    let data = local_storage(&state)?.get(&key).await?;
+
+    Ok(([(header::CONTENT_TYPE, content_type_for_key(&key))], data))
+}
+
+/// Загрузка файла в локальное хранилище по подписанной ссылке из `/files/presign`
+#[utoipa::path(
+    put,
+    path = "/api/v1/files/local/{key}",
+    tag = "files",
+    params(
+        ("key" = String, Path, description = "Ключ файла"),
+        ("expires" = i64, Query, description = "Срок действия ссылки (unix time)"),
+        ("signature" = String, Query, description = "Подпись ссылки")
+    ),
+    request_body(content = String, content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Файл сохранён"),
+        (status = 403, description = "Подпись неверна или ссылка истекла"),
+        (status = 404, description = "Локальное хранилище не используется")
+    )
+)]
+pub async fn upload_local_file(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Query(query): Query<LocalUploadQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<StatusCode> {
+    let storage = local_storage(&state)?;
+
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    if !storage.verify_upload(&key, content_type, query.expires, &query.signature) {
+        return Err(AppError::Forbidden);
+    }
+
+    storage.put(&key, content_type, body.to_vec()).await?;
+
+    Ok(StatusCode::OK)
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::State,
+    http::{header, Extensions, HeaderMap},
+    routing::get,
+    Json, Router,
+};
+use serde_json::json;
+
+use crate::error::AppResult;
+use crate::middleware::{admin_guard::client_ip, AppState, AuthUser, RequestId};
+use crate::models::{
+    AcceptConsentsRequest, ConsentAcceptance, ConsentStatusResponse, LegalDocument, NewAuditLog,
+};
+use crate::services::{AuditService, ConsentService};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/documents", get(list_documents))
+        .route("/consents", get(get_consent_status).post(accept_consents))
+}
+
+/// Действующие версии пользовательского соглашения и согласия на обработку данных
+#[utoipa::path(
+    get,
+    path = "/api/v1/legal/documents",
+    tag = "legal",
+    responses(
+        (status = 200, description = "Действующие документы", body = Vec<LegalDocument>)
+    )
+)]
+pub async fn list_documents(State(state): State<AppState>) -> AppResult<Json<Vec<LegalDocument>>> {
+    Ok(Json(ConsentService::current_documents(&state.pool).await?))
+}
+
+/// Какие документы пользователю ещё нужно принять
+#[utoipa::path(
+    get,
+    path = "/api/v1/legal/consents",
+    tag = "legal",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Состояние согласий", body = ConsentStatusResponse),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn get_consent_status(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<ConsentStatusResponse>> {
+    let documents = ConsentService::current_documents(&state.pool).await?;
+    let pending = ConsentService::pending_documents(&state.pool, auth_user.user_id).await?;
+
+    Ok(Json(ConsentStatusResponse {
+        consent_required: !pending.is_empty(),
+        documents,
+        pending,
+    }))
+}
+
+/// Принять действующие версии документов; фиксируются время, версия, IP и клиент
+#[utoipa::path(
+    post,
+    path = "/api/v1/legal/consents",
+    tag = "legal",
+    security(("bearer_auth" = [])),
+    request_body = AcceptConsentsRequest,
+    responses(
+        (status = 200, description = "Согласие записано", body = Vec<ConsentAcceptance>),
+        (status = 400, description = "Документ не является действующей версией"),
+        (status = 401, description = "Не авторизован"),
+        (status = 422, description = "Не указаны документы")
+    )
+)]
+pub async fn accept_consents(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    headers: HeaderMap,
+    extensions: Extensions,
+    Json(payload): Json<AcceptConsentsRequest>,
+) -> AppResult<Json<Vec<ConsentAcceptance>>> {
+    let ip_address = client_ip(&state.config, &headers, &extensions);
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok());
+
+    let accepted = ConsentService::accept(
+        &state.pool,
+        auth_user.user_id,
+        &payload.document_ids,
+        ip_address.as_deref(),
+        user_agent,
+    )
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: None,
+            action: "accept_legal_documents",
+            entity_type: "user",
+            entity_id: Some(auth_user.user_id),
+            old_value: None,
+            new_value: Some(json!({"document_ids": payload.document_ids})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(accepted))
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     routing::{get, post, put},
     Json, Router,
 };
@@ -9,13 +9,27 @@ use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_chairman_or_higher, AppState, AuthUser};
+use crate::middleware::{
+    get_user_complexes, AppState, AuthUser, ComplexScope, RequestId,
+};
 use crate::models::{
-    AddMaintenanceCommentRequest, CreateMaintenanceRequest, MaintenanceComment, MaintenancePhoto,
-    MaintenancePhotoResponse, MaintenancePriority, MaintenanceRequest, MaintenanceRequestResponse,
-    MaintenanceStatus, RateMaintenanceRequest, UpdateMaintenanceStatusRequest,
+    AddMaintenanceCommentRequest, AssignMaintenanceRequest, CreateMaintenanceRequest,
+    CreateMaintenanceScheduleRequest, MaintenanceCostItem, MaintenanceCostItemRequest,
+    MaintenanceCostsResponse, MaintenancePhoto, MaintenancePhotoResponse, MaintenancePriority,
+    MaintenanceRequest, MaintenanceRequestResponse, MaintenanceSchedule, MaintenanceStatus,
+    NewAuditLog, NotificationType, Permission, PlannedMaintenanceResponse, RateMaintenanceRequest,
+    UpdateMaintenanceEstimateRequest, UpdateMaintenanceScheduleRequest,
+    UpdateMaintenanceStatusRequest,
+};
+use crate::services::file_service::{validate_image_content_type, MAX_IMAGE_SIZE};
+use crate::services::{
+    AuditService, FileService, MaintenanceCostService, MaintenanceScheduleService,
+    NotificationService, PermissionService,
 };
 
+/// Сколько фото можно приложить к одной заявке
+const MAX_MAINTENANCE_PHOTOS: i64 = 20;
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MaintenanceSuccessResponse {
     pub success: bool,
@@ -39,11 +53,23 @@ pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/", get(list_requests))
         .route("/", post(create_request))
+        .route("/assigned-to-me", get(list_assigned_to_me))
+        .route("/planned", get(list_planned))
+        .route("/schedules", get(list_schedules).post(create_schedule))
+        .route("/schedules/:id", put(update_schedule).delete(delete_schedule))
         .route("/:id", get(get_request))
         .route("/:id/status", put(update_status))
+        .route("/:id/assign", put(assign_request))
+        .route("/:id/costs", get(get_costs).put(update_estimate))
+        .route("/:id/costs/items", post(add_cost_item))
+        .route("/:id/costs/items/:item_id", put(update_cost_item).delete(delete_cost_item))
         .route("/:id/rate", post(rate_request))
         .route("/:id/comments", get(get_comments))
         .route("/:id/comments", post(add_comment))
+        .route(
+            "/:id/photos",
+            post(upload_photos).layer(DefaultBodyLimit::max(MAX_IMAGE_SIZE * 2)),
+        )
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -54,21 +80,16 @@ struct RequestsQuery {
     limit: Option<i64>,
 }
 
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
+#[derive(Debug, Deserialize)]
+struct AssignedQuery {
+    status: Option<String>,
+    page: Option<i64>,
+    limit: Option<i64>,
+}
 
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
+#[derive(Debug, Deserialize)]
+struct PlannedQuery {
+    days: Option<i64>,
 }
 
 /// Получить список заявок на обслуживание
@@ -78,6 +99,7 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
     tag = "Заявки на обслуживание",
     security(("bearer_auth" = [])),
     params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
         ("status" = Option<String>, Query, description = "Фильтр по статусу"),
         ("category" = Option<String>, Query, description = "Фильтр по категории"),
         ("page" = Option<i64>, Query, description = "Номер страницы"),
@@ -91,10 +113,10 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
 )]
 async fn list_requests(
     State(state): State<AppState>,
-    auth_user: AuthUser,
+    complex: ComplexScope,
     Query(query): Query<RequestsQuery>,
 ) -> AppResult<Json<Vec<MaintenanceRequestResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
     let limit = query.limit.unwrap_or(20).min(100);
     let offset = query.page.unwrap_or(0) * limit;
@@ -179,6 +201,9 @@ async fn build_request_response(
             .collect(),
         comments_count: comments_count.0 as i32,
         rating: req.rating,
+        planned_for: req.planned_for,
+        estimated_cost: req.estimated_cost,
+        total_cost: req.total_cost,
         created_at: req.created_at,
     })
 }
@@ -204,16 +229,8 @@ async fn get_request(
     auth_user: AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<MaintenanceRequestResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
-
-    let req = sqlx::query_as::<_, MaintenanceRequest>(
-        "SELECT * FROM maintenance_requests WHERE id = $1 AND complex_id = $2",
-    )
-    .bind(id)
-    .bind(complex_id)
-    .fetch_optional(&state.pool)
-    .await?
-    .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
+    let req = fetch_request(&state, id).await?;
+    check_request_viewer(&state, &auth_user, &req).await?;
 
     let response = build_request_response(&state, &req).await?;
     Ok(Json(response))
@@ -225,6 +242,9 @@ async fn get_request(
     path = "/api/maintenance",
     tag = "Заявки на обслуживание",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     request_body = CreateMaintenanceRequest,
     responses(
         (status = 200, description = "Заявка создана", body = MaintenanceRequestResponse),
@@ -235,9 +255,10 @@ async fn get_request(
 async fn create_request(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Json(payload): Json<CreateMaintenanceRequest>,
 ) -> AppResult<Json<MaintenanceRequestResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
     let req = sqlx::query_as::<_, MaintenanceRequest>(
         r#"
@@ -250,7 +271,7 @@ async fn create_request(
         "#,
     )
     .bind(complex_id)
-    .bind(&payload.apartment_id)
+    .bind(payload.apartment_id)
     .bind(auth_user.user_id)
     .bind(&payload.category)
     .bind(&payload.title)
@@ -290,6 +311,7 @@ async fn create_request(
 async fn update_status(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Path(id): Path<Uuid>,
     Json(payload): Json<UpdateMaintenanceStatusRequest>,
 ) -> AppResult<Json<MaintenanceRequestResponse>> {
@@ -300,18 +322,20 @@ async fn update_status(
             .await?
             .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
 
-    // Проверяем права (председатель или автор заявки для отмены)
-    let is_chairman: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM osi WHERE complex_id = $1 AND chairman_id = $2")
-            .bind(req.complex_id)
-            .bind(auth_user.user_id)
-            .fetch_optional(&state.pool)
-            .await?;
+    // Проверяем права (правление ОСИ этого ЖК или автор заявки для отмены)
+    let is_manager =
+        PermissionService::has(&state.pool, &auth_user, req.complex_id, Permission::ManageOsi).await?;
 
-    let can_update = is_chairman.is_some()
-        || is_chairman_or_higher(&auth_user.role)
+    // Исполнитель ведёт заявку сам: берёт в работу, ждёт запчасти, завершает
+    let is_assignee_update = matches!(
+        payload.status,
+        MaintenanceStatus::InProgress | MaintenanceStatus::WaitingParts | MaintenanceStatus::Completed
+    ) && is_assignee(&state, &auth_user, &req).await?;
+
+    let can_update = is_manager
         || (req.requester_id == auth_user.user_id
-            && payload.status == MaintenanceStatus::Cancelled);
+            && payload.status == MaintenanceStatus::Cancelled)
+        || is_assignee_update;
 
     if !can_update {
         return Err(AppError::Forbidden);
@@ -341,10 +365,437 @@ async fn update_status(
     .fetch_one(&state.pool)
     .await?;
 
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(updated.complex_id),
+            action: "update_maintenance_status",
+            entity_type: "maintenance_request",
+            entity_id: Some(id),
+            old_value: Some(json!(req)),
+            new_value: Some(json!(updated)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    let response = build_request_response(&state, &updated).await?;
+    Ok(Json(response))
+}
+
+/// Назначить исполнителя заявки из работников ОСИ
+#[utoipa::path(
+    put,
+    path = "/api/v1/maintenance/{id}/assign",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    request_body = AssignMaintenanceRequest,
+    responses(
+        (status = 200, description = "Исполнитель назначен", body = MaintenanceRequestResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только председатель"),
+        (status = 404, description = "Заявка не найдена"),
+        (status = 422, description = "Работник не найден среди сотрудников ОСИ")
+    )
+)]
+async fn assign_request(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<AssignMaintenanceRequest>,
+) -> AppResult<Json<MaintenanceRequestResponse>> {
+    let req =
+        sqlx::query_as::<_, MaintenanceRequest>("SELECT * FROM maintenance_requests WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
+
+    PermissionService::require(&state.pool, &auth_user, req.complex_id, Permission::ManageOsi).await?;
+
+    if matches!(
+        req.status,
+        MaintenanceStatus::Completed | MaintenanceStatus::Cancelled | MaintenanceStatus::Rejected
+    ) {
+        return Err(AppError::BadRequest(
+            "Закрытой заявке нельзя назначить исполнителя".to_string(),
+        ));
+    }
+
+    if let Some(worker_id) = payload.worker_id {
+        MaintenanceScheduleService::check_worker(&state.pool, req.complex_id, worker_id).await?;
+    }
+
+    let updated = sqlx::query_as::<_, MaintenanceRequest>(
+        r#"
+        UPDATE maintenance_requests SET
+            assigned_to = $2,
+            assigned_at = CASE WHEN $2::uuid IS NULL THEN NULL ELSE NOW() END,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(payload.worker_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(updated.complex_id),
+            action: "assign_maintenance_request",
+            entity_type: "maintenance_request",
+            entity_id: Some(id),
+            old_value: Some(json!({ "assigned_to": req.assigned_to })),
+            new_value: Some(json!({ "assigned_to": updated.assigned_to })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    // Работник с аккаунтом узнаёт о новой заявке сразу
+    if let Some(worker_id) = payload.worker_id.filter(|w| req.assigned_to != Some(*w)) {
+        let worker_user: Option<(Option<Uuid>,)> =
+            sqlx::query_as("SELECT user_id FROM osi_workers WHERE id = $1")
+                .bind(worker_id)
+                .fetch_optional(&state.pool)
+                .await?;
+
+        if let Some((Some(user_id),)) = worker_user {
+            NotificationService::notify_users(
+                &state.pool,
+                &[user_id],
+                NotificationType::Maintenance,
+                "Вам назначена заявка",
+                Some(&updated.title),
+                Some(json!({ "maintenance_request_id": updated.id })),
+            )
+            .await?;
+        }
+    }
+
     let response = build_request_response(&state, &updated).await?;
     Ok(Json(response))
 }
 
+/// Заявки, назначенные текущему пользователю как работнику ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/maintenance/assigned-to-me",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("status" = Option<String>, Query, description = "Фильтр по статусу; по умолчанию — незакрытые"),
+        ("page" = Option<i64>, Query, description = "Номер страницы"),
+        ("limit" = Option<i64>, Query, description = "Лимит записей")
+    ),
+    responses(
+        (status = 200, description = "Назначенные заявки", body = Vec<MaintenanceRequestResponse>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+async fn list_assigned_to_me(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<AssignedQuery>,
+) -> AppResult<Json<Vec<MaintenanceRequestResponse>>> {
+    let limit = query.limit.unwrap_or(20).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let requests = sqlx::query_as::<_, MaintenanceRequest>(
+        r#"
+        SELECT r.* FROM maintenance_requests r
+        JOIN osi_workers w ON w.id = r.assigned_to
+        WHERE w.user_id = $1 AND w.is_active
+          AND CASE
+                WHEN $2::varchar IS NULL
+                    THEN r.status NOT IN ('completed', 'rejected', 'cancelled')
+                ELSE r.status::text = $2
+              END
+        ORDER BY
+            CASE r.priority
+                WHEN 'emergency' THEN 1
+                WHEN 'high' THEN 2
+                WHEN 'normal' THEN 3
+                ELSE 4
+            END,
+            r.planned_for NULLS LAST,
+            r.created_at
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(&query.status)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut response = Vec::new();
+    for req in requests {
+        response.push(build_request_response(&state, &req).await?);
+    }
+
+    Ok(Json(response))
+}
+
+async fn fetch_request(state: &AppState, id: Uuid) -> AppResult<MaintenanceRequest> {
+    sqlx::query_as::<_, MaintenanceRequest>("SELECT * FROM maintenance_requests WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))
+}
+
+/// Заявку и её стоимость видят жители ЖК, исполнитель (даже если он не живёт в ЖК) и правление ОСИ этого ЖК
+async fn check_request_viewer(
+    state: &AppState,
+    auth_user: &AuthUser,
+    req: &MaintenanceRequest,
+) -> AppResult<()> {
+    if is_assignee(state, auth_user, req).await?
+        || PermissionService::has(&state.pool, auth_user, req.complex_id, Permission::ManageOsi).await?
+    {
+        return Ok(());
+    }
+
+    let complexes = get_user_complexes(&state.pool, auth_user.user_id).await?;
+    if !complexes.contains(&req.complex_id) {
+        return Err(AppError::NotFound("Заявка не найдена".to_string()));
+    }
+    Ok(())
+}
+
+/// Стоимость ведут исполнитель и председатель
+async fn check_cost_editor(
+    state: &AppState,
+    auth_user: &AuthUser,
+    req: &MaintenanceRequest,
+) -> AppResult<()> {
+    if is_assignee(state, auth_user, req).await? {
+        return Ok(());
+    }
+    PermissionService::require(&state.pool, auth_user, req.complex_id, Permission::ManageOsi).await
+}
+
+async fn record_cost_change(
+    state: &AppState,
+    auth_user: &AuthUser,
+    request_id: &RequestId,
+    req: &MaintenanceRequest,
+    old_value: Option<Value>,
+    new_value: Option<Value>,
+) -> AppResult<()> {
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(req.complex_id),
+            action: "update_maintenance_costs",
+            entity_type: "maintenance_request",
+            entity_id: Some(req.id),
+            old_value,
+            new_value,
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await
+}
+
+/// Стоимость заявки: оценка, итог и позиции материалов и работ
+#[utoipa::path(
+    get,
+    path = "/api/v1/maintenance/{id}/costs",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    responses(
+        (status = 200, description = "Стоимость заявки", body = MaintenanceCostsResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Заявка не найдена")
+    )
+)]
+async fn get_costs(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<MaintenanceCostsResponse>> {
+    let req = fetch_request(&state, id).await?;
+    check_request_viewer(&state, &auth_user, &req).await?;
+
+    Ok(Json(MaintenanceCostService::costs(&state.pool, &req).await?))
+}
+
+/// Оценить стоимость работ
+#[utoipa::path(
+    put,
+    path = "/api/v1/maintenance/{id}/costs",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    request_body = UpdateMaintenanceEstimateRequest,
+    responses(
+        (status = 200, description = "Стоимость заявки", body = MaintenanceCostsResponse),
+        (status = 400, description = "Заявка закрыта"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Не исполнитель и не председатель"),
+        (status = 404, description = "Заявка не найдена"),
+        (status = 422, description = "Отрицательная сумма")
+    )
+)]
+async fn update_estimate(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateMaintenanceEstimateRequest>,
+) -> AppResult<Json<MaintenanceCostsResponse>> {
+    let req = fetch_request(&state, id).await?;
+    check_cost_editor(&state, &auth_user, &req).await?;
+
+    let updated = MaintenanceCostService::set_estimate(&state.pool, &req, payload.estimated_cost).await?;
+
+    record_cost_change(
+        &state,
+        &auth_user,
+        &request_id,
+        &req,
+        Some(json!({ "estimated_cost": req.estimated_cost })),
+        Some(json!({ "estimated_cost": updated.estimated_cost })),
+    )
+    .await?;
+
+    Ok(Json(MaintenanceCostService::costs(&state.pool, &updated).await?))
+}
+
+/// Добавить материал, часы работы или прочую трату
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/{id}/costs/items",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    request_body = MaintenanceCostItemRequest,
+    responses(
+        (status = 200, description = "Позиция добавлена", body = MaintenanceCostItem),
+        (status = 400, description = "Заявка закрыта"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Не исполнитель и не председатель"),
+        (status = 404, description = "Заявка не найдена"),
+        (status = 422, description = "Неверные количество, цена или описание")
+    )
+)]
+async fn add_cost_item(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<MaintenanceCostItemRequest>,
+) -> AppResult<Json<MaintenanceCostItem>> {
+    let req = fetch_request(&state, id).await?;
+    check_cost_editor(&state, &auth_user, &req).await?;
+
+    let item = MaintenanceCostService::add_item(&state.pool, &req, auth_user.user_id, &payload).await?;
+
+    record_cost_change(&state, &auth_user, &request_id, &req, None, Some(json!(item))).await?;
+
+    Ok(Json(item))
+}
+
+/// Изменить позицию стоимости
+#[utoipa::path(
+    put,
+    path = "/api/v1/maintenance/{id}/costs/items/{item_id}",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки"),
+        ("item_id" = Uuid, Path, description = "ID позиции")
+    ),
+    request_body = MaintenanceCostItemRequest,
+    responses(
+        (status = 200, description = "Позиция изменена", body = MaintenanceCostItem),
+        (status = 400, description = "Заявка закрыта"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Не исполнитель и не председатель"),
+        (status = 404, description = "Заявка или позиция не найдена"),
+        (status = 422, description = "Неверные количество, цена или описание")
+    )
+)]
+async fn update_cost_item(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((id, item_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<MaintenanceCostItemRequest>,
+) -> AppResult<Json<MaintenanceCostItem>> {
+    let req = fetch_request(&state, id).await?;
+    check_cost_editor(&state, &auth_user, &req).await?;
+
+    let old: Option<MaintenanceCostItem> = sqlx::query_as(
+        "SELECT * FROM maintenance_cost_items WHERE id = $1 AND request_id = $2",
+    )
+    .bind(item_id)
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let item = MaintenanceCostService::update_item(&state.pool, &req, item_id, &payload).await?;
+
+    record_cost_change(&state, &auth_user, &request_id, &req, Some(json!(old)), Some(json!(item)))
+        .await?;
+
+    Ok(Json(item))
+}
+
+/// Удалить позицию стоимости
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maintenance/{id}/costs/items/{item_id}",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки"),
+        ("item_id" = Uuid, Path, description = "ID позиции")
+    ),
+    responses(
+        (status = 200, description = "Позиция удалена", body = MaintenanceSuccessResponse),
+        (status = 400, description = "Заявка закрыта"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Не исполнитель и не председатель"),
+        (status = 404, description = "Заявка или позиция не найдена")
+    )
+)]
+async fn delete_cost_item(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((id, item_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    let req = fetch_request(&state, id).await?;
+    check_cost_editor(&state, &auth_user, &req).await?;
+
+    let item = MaintenanceCostService::delete_item(&state.pool, &req, item_id).await?;
+
+    record_cost_change(&state, &auth_user, &request_id, &req, Some(json!(item)), None).await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
 /// Оценить выполненную заявку
 #[utoipa::path(
     post,
@@ -425,43 +876,33 @@ async fn get_comments(
     auth_user: AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Vec<Value>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let req = fetch_request(&state, id).await?;
+    check_request_viewer(&state, &auth_user, &req).await?;
 
-    // Проверяем доступ
-    let exists: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM maintenance_requests WHERE id = $1 AND complex_id = $2")
-            .bind(id)
-            .bind(complex_id)
-            .fetch_optional(&state.pool)
-            .await?;
-
-    if exists.is_none() {
-        return Err(AppError::NotFound("Заявка не найдена".to_string()));
-    }
-
-    let comments = sqlx::query_as::<_, MaintenanceComment>(
-        "SELECT * FROM maintenance_comments WHERE request_id = $1 ORDER BY created_at",
+    let comments: Vec<(Uuid, String, String, chrono::DateTime<chrono::Utc>)> = sqlx::query_as(
+        r#"
+        SELECT c.id, c.content, u.display_name, c.created_at
+        FROM maintenance_comments c
+        JOIN users u ON u.id = c.user_id
+        WHERE c.request_id = $1
+        ORDER BY c.created_at
+        "#,
     )
     .bind(id)
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for comment in comments {
-        let author: (String,) = sqlx::query_as(
-            "SELECT COALESCE(first_name || ' ' || last_name, phone) FROM users WHERE id = $1",
-        )
-        .bind(comment.user_id)
-        .fetch_one(&state.pool)
-        .await?;
-
-        response.push(json!({
-            "id": comment.id,
-            "content": comment.content,
-            "author": author.0,
-            "created_at": comment.created_at
-        }));
-    }
+    let response = comments
+        .into_iter()
+        .map(|(id, content, author, created_at)| {
+            json!({
+                "id": id,
+                "content": content,
+                "author": author,
+                "created_at": created_at
+            })
+        })
+        .collect();
 
     Ok(Json(response))
 }
@@ -489,18 +930,8 @@ async fn add_comment(
     Path(id): Path<Uuid>,
     Json(payload): Json<AddMaintenanceCommentRequest>,
 ) -> AppResult<Json<Value>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
-
-    let exists: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM maintenance_requests WHERE id = $1 AND complex_id = $2")
-            .bind(id)
-            .bind(complex_id)
-            .fetch_optional(&state.pool)
-            .await?;
-
-    if exists.is_none() {
-        return Err(AppError::NotFound("Заявка не найдена".to_string()));
-    }
+    let req = fetch_request(&state, id).await?;
+    check_request_viewer(&state, &auth_user, &req).await?;
 
     let comment_id: (Uuid,) = sqlx::query_as(
         r#"
@@ -520,3 +951,390 @@ async fn add_comment(
         "comment_id": comment_id.0
     })))
 }
+
+/// Фото к заявке добавляют её автор, назначенный исполнитель и председатель ЖК
+pub(crate) async fn check_photo_uploader(
+    state: &AppState,
+    auth_user: &AuthUser,
+    req: &MaintenanceRequest,
+) -> AppResult<()> {
+    if req.requester_id == auth_user.user_id || is_assignee(state, auth_user, req).await? {
+        return Ok(());
+    }
+
+    PermissionService::require(&state.pool, auth_user, req.complex_id, Permission::ManageOsi).await
+}
+
+/// Загрузить фото «до» или «после» работ (multipart: поля `file` и `is_before`)
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/{id}/photos",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID заявки")
+    ),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Все фото заявки", body = Vec<MaintenancePhotoResponse>),
+        (status = 400, description = "Недопустимый формат, размер или число фото; заявка закрыта"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Не автор заявки и не исполнитель"),
+        (status = 404, description = "Заявка не найдена")
+    )
+)]
+async fn upload_photos(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<Vec<MaintenancePhotoResponse>>> {
+    let req =
+        sqlx::query_as::<_, MaintenanceRequest>("SELECT * FROM maintenance_requests WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Заявка не найдена".to_string()))?;
+
+    check_photo_uploader(&state, &auth_user, &req).await?;
+
+    let mut is_before = true;
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        match field.name() {
+            Some("is_before") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+                is_before = match value.trim() {
+                    "true" | "1" => true,
+                    "false" | "0" => false,
+                    _ => {
+                        return Err(AppError::BadRequest(
+                            "is_before: ожидается true или false".to_string(),
+                        ))
+                    }
+                };
+            }
+            Some("file") => {
+                let content_type = field
+                    .content_type()
+                    .ok_or_else(|| AppError::BadRequest("Content-Type отсутствует".to_string()))?
+                    .to_string();
+
+                if !validate_image_content_type(&content_type) {
+                    return Err(AppError::BadRequest(
+                        "Недопустимый формат изображения".to_string(),
+                    ));
+                }
+
+                let file_name = field.file_name().unwrap_or("photo.jpg").to_string();
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+                if data.len() > MAX_IMAGE_SIZE {
+                    return Err(AppError::BadRequest("Файл слишком большой".to_string()));
+                }
+
+                files.push((file_name, content_type, data));
+            }
+            _ => {}
+        }
+    }
+
+    if files.is_empty() {
+        return Err(AppError::BadRequest("Файл не найден".to_string()));
+    }
+
+    if matches!(
+        req.status,
+        MaintenanceStatus::Cancelled | MaintenanceStatus::Rejected
+    ) {
+        return Err(AppError::BadRequest(
+            "К закрытой заявке нельзя добавить фото".to_string(),
+        ));
+    }
+    // Фото «после» подтверждают выполненную работу, до начала работ их не бывает
+    if !is_before && req.status == MaintenanceStatus::New {
+        return Err(AppError::BadRequest(
+            "Фото «после» можно добавить, когда работы начаты".to_string(),
+        ));
+    }
+
+    let (existing,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM maintenance_photos WHERE request_id = $1")
+            .bind(req.id)
+            .fetch_one(&state.pool)
+            .await?;
+
+    if existing + files.len() as i64 > MAX_MAINTENANCE_PHOTOS {
+        return Err(AppError::BadRequest(format!(
+            "К заявке можно приложить не больше {} фото",
+            MAX_MAINTENANCE_PHOTOS
+        )));
+    }
+
+    let file_service = FileService::new(&state.config)
+        .await?
+        .with_upload_queue(&state.pool);
+
+    for (file_name, content_type, data) in files {
+        let url = file_service
+            .upload_file("maintenance", &file_name, &content_type, data.to_vec())
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO maintenance_photos (request_id, url, is_before, uploaded_by) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(req.id)
+        .bind(&url)
+        .bind(is_before)
+        .bind(auth_user.user_id)
+        .execute(&state.pool)
+        .await?;
+    }
+
+    let photos = sqlx::query_as::<_, MaintenancePhoto>(
+        "SELECT * FROM maintenance_photos WHERE request_id = $1 ORDER BY is_before DESC, created_at",
+    )
+    .bind(req.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(
+        photos
+            .into_iter()
+            .map(|p| MaintenancePhotoResponse {
+                url: p.url,
+                is_before: p.is_before,
+            })
+            .collect(),
+    ))
+}
+
+/// Пользователь — действующий работник ОСИ, назначенный на заявку
+async fn is_assignee(state: &AppState, auth_user: &AuthUser, req: &MaintenanceRequest) -> AppResult<bool> {
+    let Some(worker_id) = req.assigned_to else {
+        return Ok(false);
+    };
+
+    let worker: Option<(i32,)> =
+        sqlx::query_as("SELECT 1 FROM osi_workers WHERE id = $1 AND user_id = $2 AND is_active")
+            .bind(worker_id)
+            .bind(auth_user.user_id)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    Ok(worker.is_some())
+}
+
+/// Ближайшие плановые работы в ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/maintenance/planned",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
+        ("days" = Option<i64>, Query, description = "На сколько дней вперёд, по умолчанию 90, не больше 365")
+    ),
+    responses(
+        (status = 200, description = "Плановые работы по датам", body = Vec<PlannedMaintenanceResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+async fn list_planned(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+    Query(query): Query<PlannedQuery>,
+) -> AppResult<Json<Vec<PlannedMaintenanceResponse>>> {
+    let complex_id = complex.complex_id()?;
+
+    let planned = MaintenanceScheduleService::planned(
+        &state.pool,
+        complex_id,
+        chrono::Utc::now().date_naive(),
+        query.days.unwrap_or(90),
+    )
+    .await?;
+
+    Ok(Json(planned))
+}
+
+/// Графики плановых работ ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/maintenance/schedules",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Графики работ", body = Vec<MaintenanceSchedule>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только председатель")
+    )
+)]
+async fn list_schedules(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+) -> AppResult<Json<Vec<MaintenanceSchedule>>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    Ok(Json(MaintenanceScheduleService::list(&state.pool, complex_id).await?))
+}
+
+/// Создать график плановых работ
+#[utoipa::path(
+    post,
+    path = "/api/v1/maintenance/schedules",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body = CreateMaintenanceScheduleRequest,
+    responses(
+        (status = 200, description = "График создан", body = MaintenanceSchedule),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только председатель"),
+        (status = 422, description = "Неверные параметры повторения или исполнитель")
+    )
+)]
+async fn create_schedule(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Json(payload): Json<CreateMaintenanceScheduleRequest>,
+) -> AppResult<Json<MaintenanceSchedule>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let schedule =
+        MaintenanceScheduleService::create(&state.pool, complex_id, auth_user.user_id, &payload)
+            .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "create_maintenance_schedule",
+            entity_type: "maintenance_schedule",
+            entity_id: Some(schedule.id),
+            old_value: None,
+            new_value: Some(json!(schedule)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(schedule))
+}
+
+/// Изменить график плановых работ
+#[utoipa::path(
+    put,
+    path = "/api/v1/maintenance/schedules/{id}",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID графика")
+    ),
+    request_body = UpdateMaintenanceScheduleRequest,
+    responses(
+        (status = 200, description = "График обновлён", body = MaintenanceSchedule),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только председатель"),
+        (status = 404, description = "График не найден"),
+        (status = 422, description = "Неверные параметры повторения или исполнитель")
+    )
+)]
+async fn update_schedule(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<UpdateMaintenanceScheduleRequest>,
+) -> AppResult<Json<MaintenanceSchedule>> {
+    let schedule = MaintenanceScheduleService::get(&state.pool, id, &complex.complex_ids).await?;
+    PermissionService::require(&state.pool, &auth_user, schedule.complex_id, Permission::ManageOsi).await?;
+
+    let updated = MaintenanceScheduleService::update(&state.pool, &schedule, payload).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(updated.complex_id),
+            action: "update_maintenance_schedule",
+            entity_type: "maintenance_schedule",
+            entity_id: Some(id),
+            old_value: Some(json!(schedule)),
+            new_value: Some(json!(updated)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(updated))
+}
+
+/// Удалить график плановых работ; созданные заявки остаются
+#[utoipa::path(
+    delete,
+    path = "/api/v1/maintenance/schedules/{id}",
+    tag = "Заявки на обслуживание",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID графика")
+    ),
+    responses(
+        (status = 200, description = "График удалён", body = MaintenanceSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только председатель"),
+        (status = 404, description = "График не найден")
+    )
+)]
+async fn delete_schedule(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<MaintenanceSuccessResponse>> {
+    let schedule = MaintenanceScheduleService::get(&state.pool, id, &complex.complex_ids).await?;
+    PermissionService::require(&state.pool, &auth_user, schedule.complex_id, Permission::ManageOsi).await?;
+
+    MaintenanceScheduleService::delete(&state.pool, id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(schedule.complex_id),
+            action: "delete_maintenance_schedule",
+            entity_type: "maintenance_schedule",
+            entity_id: Some(id),
+            old_value: Some(json!(schedule)),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(MaintenanceSuccessResponse { success: true }))
+}
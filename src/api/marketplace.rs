@@ -7,10 +7,17 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{AppState, AuthUser};
+use crate::middleware::{is_moderator_or_higher, AppState, AuthUser, ComplexScope, RequestId};
 use crate::models::{
-    CategoryResponse, CreateListingRequest, ListingResponse, ListingStatus, ListingsQuery,
-    MarketplaceCategory, MarketplaceListing, SellerInfo, SendMessageRequest, UpdateListingRequest,
+    CategoryResponse, ConfirmReceiptRequest, CreateListingRequest, ListingReport,
+    ListingReportEntry, ListingReportsQuery, ListingResponse, ListingSort, ListingStatus,
+    ListingsQuery,
+    MarketplaceCategory, MarketplaceListing, ModerationAction, NewAuditLog, Paginated,
+    PhotoVariants, ReportListingRequest, ReserveListingRequest, ResolveListingReportRequest,
+    SellListingRequest, SellerInfo, SendMessageRequest, UpdateListingRequest, ViewEntity,
+};
+use crate::services::{
+    AuditService, ChatService, MarketplaceModerationService, MarketplaceSaleService, ViewService,
 };
 
 /// Ответ на toggle favorite
@@ -25,6 +32,22 @@ pub struct SuccessResponse {
     pub success: bool,
 }
 
+/// Объявление из поисковой выдачи с его релевантностью
+#[derive(sqlx::FromRow)]
+struct ListingSearchRow {
+    #[sqlx(flatten)]
+    listing: MarketplaceListing,
+    relevance: Option<f32>,
+}
+
+/// Сообщение продавцу отправлено в чат по объявлению
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct ListingMessageResponse {
+    pub success: bool,
+    pub chat_id: Uuid,
+    pub message_id: Uuid,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/categories", get(get_categories))
@@ -35,25 +58,18 @@ pub fn routes() -> Router<AppState> {
         .route("/listings/:id", delete(delete_listing))
         .route("/listings/:id/favorite", post(toggle_favorite))
         .route("/listings/:id/message", post(send_message))
+        .route("/listings/:id/report", post(report_listing))
+        .route(
+            "/listings/:id/reserve",
+            post(reserve_listing).delete(cancel_reservation),
+        )
+        .route("/listings/:id/sell", post(sell_listing))
+        .route("/listings/:id/confirm-receipt", post(confirm_receipt))
+        .route("/moderation/reports", get(list_reports))
+        .route("/moderation/reports/:id/resolve", post(resolve_report))
         .route("/my-listings", get(my_listings))
         .route("/favorites", get(my_favorites))
-}
-
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
-
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
+        .route("/my-purchases", get(my_purchases))
 }
 
 /// Получить категории маркетплейса
@@ -86,63 +102,114 @@ pub async fn get_categories(
     tag = "marketplace",
     security(("bearer_auth" = [])),
     params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
         ("category" = Option<String>, Query, description = "ID категории"),
         ("query" = Option<String>, Query, description = "Поисковый запрос"),
         ("min_price" = Option<f64>, Query, description = "Минимальная цена"),
         ("max_price" = Option<f64>, Query, description = "Максимальная цена"),
+        ("condition" = Option<String>, Query, description = "Состояние: new, like_new, good, fair"),
+        ("sort" = Option<ListingSort>, Query, description = "Порядок: relevance, newest, price_asc, price_desc"),
         ("page" = Option<i64>, Query, description = "Номер страницы"),
         ("limit" = Option<i64>, Query, description = "Количество записей")
     ),
     responses(
-        (status = 200, description = "Список объявлений", body = Vec<ListingResponse>),
+        (status = 200, description = "Список объявлений", body = PaginatedListings),
         (status = 401, description = "Не авторизован")
     )
 )]
 pub async fn list_listings(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Query(query): Query<ListingsQuery>,
-) -> AppResult<Json<Vec<ListingResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+) -> AppResult<Json<Paginated<ListingResponse>>> {
+    let complex_id = complex.complex_id()?;
 
     let limit = query.limit.unwrap_or(20).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
-    let search_pattern = query.query.as_ref().map(|q| format!("%{}%", q));
-
-    let listings = sqlx::query_as::<_, MarketplaceListing>(
-        r#"
-        SELECT l.* FROM marketplace_listings l
+    let page = query.page.unwrap_or(0);
+    let offset = page * limit;
+    let search = query
+        .query
+        .as_deref()
+        .map(str::trim)
+        .filter(|q| !q.is_empty());
+    // Подстрока ловит то, что не разбирает словарь: бренды, модели, начало слова
+    let search_pattern = search.map(|q| format!("%{}%", q));
+    let category_id = query
+        .category
+        .as_ref()
+        .and_then(|c| Uuid::parse_str(c).ok());
+    let condition = query
+        .condition
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+
+    let filters = r#"
         WHERE l.complex_id = $1
           AND l.status = 'active'
           AND ($2::uuid IS NULL OR l.category_id = $2)
-          AND ($3::varchar IS NULL OR l.title ILIKE $3 OR l.description ILIKE $3)
-          AND ($4::decimal IS NULL OR l.price >= $4)
-          AND ($5::decimal IS NULL OR l.price <= $5)
-        ORDER BY l.created_at DESC
-        LIMIT $6 OFFSET $7
+          AND ($3::text IS NULL
+               OR l.search_vector @@ websearch_to_tsquery('russian', $3)
+               OR l.title ILIKE $4 OR l.description ILIKE $4)
+          AND ($5::decimal IS NULL OR l.price >= $5)
+          AND ($6::decimal IS NULL OR l.price <= $6)
+          AND ($7::varchar IS NULL OR l.condition = $7)
+    "#;
+
+    let (total,): (i64,) = sqlx::query_as(&format!(
+        "SELECT COUNT(*) FROM marketplace_listings l {}",
+        filters
+    ))
+    .bind(complex_id)
+    .bind(category_id)
+    .bind(search)
+    .bind(&search_pattern)
+    .bind(query.min_price)
+    .bind(query.max_price)
+    .bind(condition)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let order_by = match (query.sort.unwrap_or_default(), search) {
+        (ListingSort::Relevance, Some(_)) => "relevance DESC, l.created_at DESC",
+        (ListingSort::Relevance | ListingSort::Newest, _) => "l.created_at DESC",
+        (ListingSort::PriceAsc, _) => "l.price ASC, l.created_at DESC",
+        (ListingSort::PriceDesc, _) => "l.price DESC, l.created_at DESC",
+    };
+    let rows = sqlx::query_as::<_, ListingSearchRow>(&format!(
+        r#"
+        SELECT l.*,
+               CASE WHEN $3::text IS NULL THEN NULL
+                    ELSE ts_rank(l.search_vector, websearch_to_tsquery('russian', $3))
+               END AS relevance
+        FROM marketplace_listings l
+        {}
+        ORDER BY {}
+        LIMIT $8 OFFSET $9
         "#,
-    )
+        filters, order_by
+    ))
     .bind(complex_id)
-    .bind(
-        query
-            .category
-            .as_ref()
-            .and_then(|c| Uuid::parse_str(c).ok()),
-    )
+    .bind(category_id)
+    .bind(search)
     .bind(&search_pattern)
-    .bind(&query.min_price)
-    .bind(&query.max_price)
+    .bind(query.min_price)
+    .bind(query.max_price)
+    .bind(condition)
     .bind(limit)
     .bind(offset)
     .fetch_all(&state.pool)
     .await?;
 
     let mut response = Vec::new();
-    for listing in listings {
-        response.push(build_listing_response(&state, &listing, auth_user.user_id).await?);
+    for row in rows {
+        let mut listing = build_listing_response(&state, &row.listing, auth_user.user_id).await?;
+        listing.relevance = row.relevance;
+        response.push(listing);
     }
 
-    Ok(Json(response))
+    Ok(Json(Paginated::new(response, page, limit, total)))
 }
 
 async fn build_listing_response(
@@ -163,11 +230,23 @@ async fn build_listing_response(
             .fetch_one(&state.pool)
             .await?;
 
-    let photos: Vec<(String,)> =
-        sqlx::query_as("SELECT url FROM listing_photos WHERE listing_id = $1 ORDER BY sort_order")
-            .bind(listing.id)
-            .fetch_all(&state.pool)
-            .await?;
+    let (rating, reviews_count): (Option<f64>, i64) = sqlx::query_as(
+        r#"
+        SELECT AVG(buyer_rating)::float8, COUNT(buyer_rating)
+        FROM marketplace_listings
+        WHERE seller_id = $1 AND buyer_rating IS NOT NULL
+        "#,
+    )
+    .bind(listing.seller_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let photos = sqlx::query_as::<_, PhotoVariants>(
+        "SELECT url, thumbnail_url, medium_url FROM listing_photos WHERE listing_id = $1 ORDER BY sort_order",
+    )
+    .bind(listing.id)
+    .fetch_all(&state.pool)
+    .await?;
 
     let is_favorite: Option<(i32,)> =
         sqlx::query_as("SELECT 1 FROM listing_favorites WHERE listing_id = $1 AND user_id = $2")
@@ -196,11 +275,17 @@ async fn build_listing_response(
             .trim()
             .to_string(),
             avatar_url: seller.3,
+            rating,
+            reviews_count,
         },
-        photos: photos.into_iter().map(|(url,)| url).collect(),
+        photos: photos.iter().map(|p| p.url.clone()).collect(),
+        photo_variants: photos,
         views_count: listing.views_count,
+        view_stats: listing.view_stats_for(user_id),
         favorites_count: listing.favorites_count,
         is_favorite: is_favorite.is_some(),
+        sale: listing.sale_for(user_id),
+        relevance: None,
         created_at: listing.created_at,
     })
 }
@@ -223,19 +308,30 @@ async fn build_listing_response(
 pub async fn get_listing(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<ListingResponse>> {
-    let listing =
-        sqlx::query_as::<_, MarketplaceListing>("SELECT * FROM marketplace_listings WHERE id = $1")
-            .bind(id)
-            .fetch_optional(&state.pool)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+    let listing = sqlx::query_as::<_, MarketplaceListing>(
+        "SELECT * FROM marketplace_listings WHERE id = $1 AND complex_id = ANY($2)",
+    )
+    .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+
+    // Скрытое модератором объявление видят только продавец и модераторы
+    if listing.status == ListingStatus::Hidden
+        && listing.seller_id != auth_user.user_id
+        && !is_moderator_or_higher(&auth_user.role)
+    {
+        return Err(AppError::NotFound("Объявление не найдено".to_string()));
+    }
 
-    sqlx::query("UPDATE marketplace_listings SET views_count = views_count + 1 WHERE id = $1")
-        .bind(id)
-        .execute(&state.pool)
-        .await?;
+    // Просмотры продавца не учитываем
+    if listing.seller_id != auth_user.user_id {
+        ViewService::record(ViewEntity::Listing, id, auth_user.user_id);
+    }
 
     let response = build_listing_response(&state, &listing, auth_user.user_id).await?;
     Ok(Json(response))
@@ -247,18 +343,24 @@ pub async fn get_listing(
     path = "/api/v1/marketplace/listings",
     tag = "marketplace",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     request_body = CreateListingRequest,
     responses(
         (status = 200, description = "Объявление создано", body = ListingResponse),
-        (status = 401, description = "Не авторизован")
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Размещение объявлений заблокировано модератором")
     )
 )]
 pub async fn create_listing(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Json(payload): Json<CreateListingRequest>,
 ) -> AppResult<Json<ListingResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
+    MarketplaceModerationService::ensure_not_banned(&state.pool, auth_user.user_id).await?;
 
     let listing = sqlx::query_as::<_, MarketplaceListing>(
         r#"
@@ -275,7 +377,7 @@ pub async fn create_listing(
     .bind(payload.category_id)
     .bind(&payload.title)
     .bind(&payload.description)
-    .bind(&payload.price)
+    .bind(payload.price)
     .bind(payload.is_negotiable.unwrap_or(false))
     .bind(payload.is_free.unwrap_or(false))
     .bind(&payload.condition)
@@ -300,7 +402,7 @@ pub async fn create_listing(
     responses(
         (status = 200, description = "Объявление обновлено", body = ListingResponse),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет прав"),
+        (status = 403, description = "Нет прав или объявление скрыто модератором"),
         (status = 404, description = "Не найдено")
     )
 )]
@@ -320,31 +422,47 @@ pub async fn update_listing(
     if listing.seller_id != auth_user.user_id {
         return Err(AppError::Forbidden);
     }
+    // Вернуть в ленту скрытое объявление может только модератор
+    if listing.status == ListingStatus::Hidden || payload.status == Some(ListingStatus::Hidden) {
+        return Err(AppError::Forbidden);
+    }
+    // Бронь и продажа оформляются отдельными запросами, проданное объявление не переоткрыть
+    if matches!(payload.status, Some(ListingStatus::Reserved | ListingStatus::Sold)) {
+        return Err(AppError::Validation(
+            "Бронь и продажа отмечаются через /reserve и /sell".to_string(),
+        ));
+    }
+    if listing.status == ListingStatus::Sold && payload.status.is_some() {
+        return Err(AppError::Conflict("Объявление уже продано".to_string()));
+    }
+    MarketplaceModerationService::ensure_not_banned(&state.pool, auth_user.user_id).await?;
 
     let updated = sqlx::query_as::<_, MarketplaceListing>(
         r#"
         UPDATE marketplace_listings SET
             category_id = COALESCE($2, category_id),
             title = COALESCE($3, title),
-            description = COALESCE($4, description),
+            description = $4,
             price = COALESCE($5, price),
             is_negotiable = COALESCE($6, is_negotiable),
             is_free = COALESCE($7, is_free),
-            condition = COALESCE($8, condition),
+            condition = $8,
             status = COALESCE($9, status),
+            reserved_for = CASE WHEN $9 IS NULL THEN reserved_for END,
+            reserved_at = CASE WHEN $9 IS NULL THEN reserved_at END,
             updated_at = NOW()
         WHERE id = $1
         RETURNING *
         "#,
     )
     .bind(id)
-    .bind(&payload.category_id)
+    .bind(payload.category_id)
     .bind(&payload.title)
-    .bind(&payload.description)
-    .bind(&payload.price)
-    .bind(&payload.is_negotiable)
-    .bind(&payload.is_free)
-    .bind(&payload.condition)
+    .bind(payload.description.apply(listing.description))
+    .bind(payload.price)
+    .bind(payload.is_negotiable)
+    .bind(payload.is_free)
+    .bind(payload.condition.apply(listing.condition))
     .bind(&payload.status)
     .fetch_one(&state.pool)
     .await?;
@@ -412,10 +530,22 @@ pub async fn toggle_favorite(
     auth_user: AuthUser,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<Value>> {
+    let is_favorite = toggle_listing_favorite(&state, id, auth_user.user_id).await?;
+
+    Ok(Json(json!({"is_favorite": is_favorite})))
+}
+
+/// Переключить избранное для объявления, возвращает новое состояние.
+/// Счётчик favorites_count обновляет триггер на listing_favorites.
+pub(crate) async fn toggle_listing_favorite(
+    state: &AppState,
+    listing_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<bool> {
     let existing: Option<(Uuid,)> =
         sqlx::query_as("SELECT id FROM listing_favorites WHERE listing_id = $1 AND user_id = $2")
-            .bind(id)
-            .bind(auth_user.user_id)
+            .bind(listing_id)
+            .bind(user_id)
             .fetch_optional(&state.pool)
             .await?;
 
@@ -425,29 +555,17 @@ pub async fn toggle_favorite(
             .execute(&state.pool)
             .await?;
 
+        Ok(false)
+    } else {
         sqlx::query(
-            "UPDATE marketplace_listings SET favorites_count = favorites_count - 1 WHERE id = $1",
+            "INSERT INTO listing_favorites (listing_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
         )
-        .bind(id)
-        .execute(&state.pool)
-        .await?;
-
-        Ok(Json(json!({"is_favorite": false})))
-    } else {
-        sqlx::query("INSERT INTO listing_favorites (listing_id, user_id) VALUES ($1, $2)")
-            .bind(id)
-            .bind(auth_user.user_id)
+            .bind(listing_id)
+            .bind(user_id)
             .execute(&state.pool)
             .await?;
 
-        sqlx::query(
-            "UPDATE marketplace_listings SET favorites_count = favorites_count + 1 WHERE id = $1",
-        )
-        .bind(id)
-        .execute(&state.pool)
-        .await?;
-
-        Ok(Json(json!({"is_favorite": true})))
+        Ok(true)
     }
 }
 
@@ -458,42 +576,197 @@ pub async fn toggle_favorite(
     tag = "marketplace",
     security(("bearer_auth" = [])),
     params(
-        ("id" = Uuid, Path, description = "ID объявления")
+        ("id" = Uuid, Path, description = "ID объявления"),
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
     ),
     request_body = SendMessageRequest,
     responses(
-        (status = 200, description = "Сообщение отправлено", body = SuccessResponse),
+        (status = 200, description = "Сообщение отправлено в чат с продавцом", body = ListingMessageResponse),
+        (status = 400, description = "Сообщение по своему объявлению"),
         (status = 401, description = "Не авторизован"),
-        (status = 404, description = "Объявление не найдено")
+        (status = 403, description = "Маркетплейс заблокирован модератором"),
+        (status = 404, description = "Объявление не найдено"),
+        (status = 422, description = "Пустое сообщение")
     )
 )]
 pub async fn send_message(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Path(id): Path<Uuid>,
     Json(payload): Json<SendMessageRequest>,
-) -> AppResult<Json<Value>> {
-    let listing =
-        sqlx::query_as::<_, MarketplaceListing>("SELECT * FROM marketplace_listings WHERE id = $1")
-            .bind(id)
-            .fetch_optional(&state.pool)
-            .await?
-            .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
-
-    sqlx::query(
-        r#"
-        INSERT INTO listing_messages (listing_id, sender_id, recipient_id, message)
-        VALUES ($1, $2, $3, $4)
-        "#,
+) -> AppResult<Json<ListingMessageResponse>> {
+    let listing = sqlx::query_as::<_, MarketplaceListing>(
+        "SELECT * FROM marketplace_listings WHERE id = $1 AND complex_id = ANY($2)",
     )
     .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .filter(|listing| listing.status != ListingStatus::Hidden)
+    .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+    MarketplaceModerationService::ensure_not_banned(&state.pool, auth_user.user_id).await?;
+
+    if listing.seller_id == auth_user.user_id {
+        return Err(AppError::BadRequest(
+            "Нельзя написать по своему объявлению".to_string(),
+        ));
+    }
+    let content = payload.message.trim();
+    if content.is_empty() {
+        return Err(AppError::Validation("Сообщение не может быть пустым".to_string()));
+    }
+
+    // Переписка продолжается в обычном чате, там же продавец отвечает
+    let chat_id = ChatService::listing_chat(&state.pool, &listing, auth_user.user_id).await?;
+
+    let (message_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO chat_messages (chat_id, sender_id, content) VALUES ($1, $2, $3) RETURNING id",
+    )
+    .bind(chat_id)
     .bind(auth_user.user_id)
-    .bind(listing.seller_id)
-    .bind(&payload.message)
-    .execute(&state.pool)
+    .bind(content)
+    .fetch_one(&state.pool)
     .await?;
 
-    Ok(Json(json!({"success": true})))
+    sqlx::query("UPDATE chats SET updated_at = NOW() WHERE id = $1")
+        .bind(chat_id)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(ListingMessageResponse {
+        success: true,
+        chat_id,
+        message_id,
+    }))
+}
+
+/// Пожаловаться на объявление
+#[utoipa::path(
+    post,
+    path = "/api/v1/marketplace/listings/{id}/report",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    request_body = ReportListingRequest,
+    responses(
+        (status = 200, description = "Жалоба принята", body = ListingReport),
+        (status = 400, description = "Жалоба на своё объявление"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Объявление не найдено"),
+        (status = 409, description = "Жалоба уже на рассмотрении")
+    )
+)]
+pub async fn report_listing(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReportListingRequest>,
+) -> AppResult<Json<ListingReport>> {
+    let listing = sqlx::query_as::<_, MarketplaceListing>(
+        "SELECT * FROM marketplace_listings WHERE id = $1 AND complex_id = ANY($2) AND status <> 'hidden'",
+    )
+    .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+
+    let report =
+        MarketplaceModerationService::report(&state.pool, &listing, auth_user.user_id, &payload)
+            .await?;
+
+    Ok(Json(report))
+}
+
+/// Очередь жалоб на объявления для модераторов
+#[utoipa::path(
+    get,
+    path = "/api/v1/marketplace/moderation/reports",
+    tag = "moderation",
+    security(("bearer_auth" = [])),
+    params(ListingReportsQuery),
+    responses(
+        (status = 200, description = "Жалобы, старые сверху", body = PaginatedListingReports),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для модераторов")
+    )
+)]
+pub async fn list_reports(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<ListingReportsQuery>,
+) -> AppResult<Json<Paginated<ListingReportEntry>>> {
+    if !is_moderator_or_higher(&auth_user.role) {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(Json(MarketplaceModerationService::queue(&state.pool, &query).await?))
+}
+
+/// Рассмотреть жалобу: отклонить, скрыть объявление, предупредить или заблокировать продавца
+#[utoipa::path(
+    post,
+    path = "/api/v1/marketplace/moderation/reports/{id}/resolve",
+    tag = "moderation",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID жалобы")
+    ),
+    request_body = ResolveListingReportRequest,
+    responses(
+        (status = 200, description = "Жалоба рассмотрена", body = ListingReport),
+        (status = 400, description = "Жалоба уже рассмотрена"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для модераторов"),
+        (status = 404, description = "Жалоба не найдена"),
+        (status = 422, description = "Неверный срок блокировки")
+    )
+)]
+pub async fn resolve_report(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ResolveListingReportRequest>,
+) -> AppResult<Json<ListingReport>> {
+    if !is_moderator_or_higher(&auth_user.role) {
+        return Err(AppError::Forbidden);
+    }
+
+    let (report, listing) =
+        MarketplaceModerationService::resolve(&state.pool, id, auth_user.user_id, &payload).await?;
+
+    let action = match payload.action {
+        ModerationAction::Dismiss => "marketplace_dismiss_report",
+        ModerationAction::HideListing => "marketplace_hide_listing",
+        ModerationAction::WarnUser => "marketplace_warn_user",
+        ModerationAction::BanUser => "marketplace_ban_user",
+    };
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(listing.complex_id),
+            action,
+            entity_type: "marketplace_listing",
+            entity_id: Some(listing.id),
+            old_value: Some(json!({"report_id": report.id, "reason": report.reason})),
+            new_value: Some(json!({
+                "seller_id": listing.seller_id,
+                "listing_status": listing.status,
+                "note": report.moderator_note,
+                "ban_days": payload.ban_days,
+            })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(report))
 }
 
 /// Мои объявления
@@ -564,3 +837,165 @@ pub async fn my_favorites(
 
     Ok(Json(response))
 }
+
+/// Забронировать товар для соседа
+#[utoipa::path(
+    post,
+    path = "/api/v1/marketplace/listings/{id}/reserve",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    request_body = ReserveListingRequest,
+    responses(
+        (status = 200, description = "Объявление забронировано", body = ListingResponse),
+        (status = 400, description = "Бронь на себя"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Объявление другого продавца"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "Объявление не активно"),
+        (status = 422, description = "Покупатель не живёт в этом ЖК")
+    )
+)]
+pub async fn reserve_listing(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReserveListingRequest>,
+) -> AppResult<Json<ListingResponse>> {
+    let listing =
+        MarketplaceSaleService::reserve(&state.pool, id, auth_user.user_id, payload.buyer_id)
+            .await?;
+
+    let response = build_listing_response(&state, &listing, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Снять бронь
+#[utoipa::path(
+    delete,
+    path = "/api/v1/marketplace/listings/{id}/reserve",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    responses(
+        (status = 200, description = "Объявление снова в ленте", body = ListingResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Объявление другого продавца"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "Объявление не забронировано")
+    )
+)]
+pub async fn cancel_reservation(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<ListingResponse>> {
+    let listing =
+        MarketplaceSaleService::cancel_reservation(&state.pool, id, auth_user.user_id).await?;
+
+    let response = build_listing_response(&state, &listing, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Отметить продажу соседу
+#[utoipa::path(
+    post,
+    path = "/api/v1/marketplace/listings/{id}/sell",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    request_body = SellListingRequest,
+    responses(
+        (status = 200, description = "Объявление продано", body = ListingResponse),
+        (status = 400, description = "Продажа самому себе"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Объявление другого продавца"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "Объявление не активно"),
+        (status = 422, description = "Покупатель не указан или не живёт в этом ЖК")
+    )
+)]
+pub async fn sell_listing(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SellListingRequest>,
+) -> AppResult<Json<ListingResponse>> {
+    let listing =
+        MarketplaceSaleService::sell(&state.pool, id, auth_user.user_id, payload.buyer_id).await?;
+
+    let response = build_listing_response(&state, &listing, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Подтвердить получение товара и оценить продавца
+#[utoipa::path(
+    post,
+    path = "/api/v1/marketplace/listings/{id}/confirm-receipt",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID объявления")
+    ),
+    request_body = ConfirmReceiptRequest,
+    responses(
+        (status = 200, description = "Получение подтверждено", body = ListingResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Покупка не найдена"),
+        (status = 409, description = "Получение уже подтверждено"),
+        (status = 422, description = "Неверная оценка или отзыв")
+    )
+)]
+pub async fn confirm_receipt(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ConfirmReceiptRequest>,
+) -> AppResult<Json<ListingResponse>> {
+    let listing =
+        MarketplaceSaleService::confirm_receipt(&state.pool, id, auth_user.user_id, &payload)
+            .await?;
+
+    let response = build_listing_response(&state, &listing, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Мои покупки
+#[utoipa::path(
+    get,
+    path = "/api/v1/marketplace/my-purchases",
+    tag = "marketplace",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Купленные товары, новые сверху", body = Vec<ListingResponse>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn my_purchases(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<ListingResponse>>> {
+    let listings = sqlx::query_as::<_, MarketplaceListing>(
+        r#"
+        SELECT * FROM marketplace_listings
+        WHERE buyer_id = $1
+        ORDER BY sold_at DESC
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut response = Vec::new();
+    for listing in listings {
+        response.push(build_listing_response(&state, &listing, auth_user.user_id).await?);
+    }
+
+    Ok(Json(response))
+}
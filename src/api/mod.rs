@@ -2,16 +2,28 @@ pub mod addresses;
 pub mod admin;
 pub mod announcements;
 pub mod apartments;
+pub mod audit;
 pub mod auth;
+pub mod bookmarks;
+pub mod bootstrap;
 pub mod chat;
 pub mod cities;
 pub mod communal;
+pub mod communications;
 pub mod complexes;
+pub mod files;
+pub mod legal;
 pub mod maintenance;
 pub mod marketplace;
 pub mod notifications;
 pub mod osi;
+pub mod osi_finance;
+pub mod permissions;
 pub mod security;
+pub mod sms;
+pub mod surveys;
+pub mod telegram;
+pub mod templates;
 pub mod users;
 pub mod voting;
 
@@ -20,13 +32,19 @@ use axum::Router;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
+        .nest("/bootstrap", bootstrap::routes())
         .nest("/auth", auth::routes())
         .nest("/users", users::routes())
         .nest("/cities", cities::routes())
         .nest("/addresses", addresses::routes())
-        .nest("/complexes", complexes::routes())
+        .nest(
+            "/complexes",
+            complexes::routes()
+                .merge(templates::routes())
+                .merge(communications::routes()),
+        )
         .nest("/apartments", apartments::routes())
-        .nest("/osi", osi::routes())
+        .nest("/osi", osi::routes().merge(osi_finance::routes()))
         .nest("/security", security::routes())
         .nest("/announcements", announcements::routes())
         .nest("/marketplace", marketplace::routes())
@@ -35,5 +53,13 @@ pub fn routes() -> Router<AppState> {
         .nest("/notifications", notifications::routes())
         .nest("/chat", chat::routes())
         .nest("/maintenance", maintenance::routes())
+        .nest("/bookmarks", bookmarks::routes())
         .nest("/admin", admin::routes())
+        .nest("/audit", audit::routes())
+        .nest("/permissions", permissions::routes())
+        .nest("/files", files::routes())
+        .nest("/legal", legal::routes())
+        .nest("/surveys", surveys::routes())
+        .nest("/telegram", telegram::routes())
+        .nest("/sms", sms::routes())
 }
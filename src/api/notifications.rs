@@ -1,8 +1,13 @@
 use axum::{
     extract::{Path, Query, State},
+    response::sse::{Event, KeepAlive, Sse},
     routing::{get, post, put},
     Json, Router,
 };
+use sqlx::PgPool;
+use std::convert::Infallible;
+use tokio::sync::{broadcast::error::RecvError, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use utoipa::ToSchema;
@@ -11,8 +16,11 @@ use uuid::Uuid;
 use crate::error::{AppError, AppResult};
 use crate::middleware::{AppState, AuthUser};
 use crate::models::{
-    Notification, NotificationResponse, NotificationsQuery, RegisterPushTokenRequest,
+    take_page, Cursor, CursorPage, Notification, NotificationResponse, NotificationSetting,
+    NotificationsQuery, RegisterPushTokenRequest, TelegramLinkCode, TelegramLinkStatus,
+    UpdateNotificationSettingsRequest,
 };
+use crate::services::{NotificationService, NotificationStreamService, TelegramService};
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct NotificationSuccessResponse {
@@ -37,6 +45,14 @@ pub fn routes() -> Router<AppState> {
         .route("/read-all", post(mark_all_as_read))
         .route("/push-token", post(register_push_token))
         .route("/unread-count", get(get_unread_count))
+        .route("/settings", get(get_settings).put(update_settings))
+        .route("/stream", get(stream_notifications))
+        .route(
+            "/telegram/link",
+            get(get_telegram_link)
+                .post(create_telegram_link)
+                .delete(delete_telegram_link),
+        )
 }
 
 /// Получить список уведомлений пользователя
@@ -47,11 +63,11 @@ pub fn routes() -> Router<AppState> {
     security(("bearer_auth" = [])),
     params(
         ("limit" = Option<i64>, Query, description = "Лимит записей"),
-        ("page" = Option<i64>, Query, description = "Номер страницы"),
+        ("cursor" = Option<String>, Query, description = "Курсор следующей страницы"),
         ("unread_only" = Option<bool>, Query, description = "Только непрочитанные")
     ),
     responses(
-        (status = 200, description = "Список уведомлений", body = Vec<NotificationResponse>),
+        (status = 200, description = "Список уведомлений", body = NotificationsPage),
         (status = 401, description = "Не авторизован")
     )
 )]
@@ -59,32 +75,40 @@ async fn list_notifications(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Query(query): Query<NotificationsQuery>,
-) -> AppResult<Json<Vec<NotificationResponse>>> {
-    let limit = query.limit.unwrap_or(50).min(100);
-    let offset = query.page.unwrap_or(0) * limit;
+) -> AppResult<Json<CursorPage<NotificationResponse>>> {
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string())))
+        .transpose()?;
 
-    let notifications = sqlx::query_as::<_, Notification>(
+    let mut notifications = sqlx::query_as::<_, Notification>(
         r#"
         SELECT * FROM notifications
         WHERE user_id = $1
           AND ($2::boolean IS NULL OR ($2 = true AND is_read = false))
-        ORDER BY created_at DESC
-        LIMIT $3 OFFSET $4
+          AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5
         "#,
     )
     .bind(auth_user.user_id)
     .bind(query.unread_only)
-    .bind(limit)
-    .bind(offset)
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
     .fetch_all(&state.pool)
     .await?;
 
+    let next_cursor = take_page(&mut notifications, limit, |n| Cursor::new(n.created_at, n.id));
+
     let response: Vec<NotificationResponse> = notifications
         .into_iter()
         .map(NotificationResponse::from)
         .collect();
 
-    Ok(Json(response))
+    Ok(Json(CursorPage::new(response, next_cursor)))
 }
 
 /// Отметить уведомление как прочитанное
@@ -211,3 +235,207 @@ async fn get_unread_count(
 
     Ok(Json(json!({"count": count.0})))
 }
+
+/// Каналы доставки по типам уведомлений
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/settings",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Настройки по всем типам", body = Vec<NotificationSetting>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+async fn get_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Vec<NotificationSetting>>> {
+    let settings = NotificationService::settings(&state.pool, auth_user.user_id).await?;
+    Ok(Json(settings))
+}
+
+/// Включить или отключить push, SMS, email, Telegram и уведомления в приложении по типам
+#[utoipa::path(
+    put,
+    path = "/api/v1/notifications/settings",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    request_body = UpdateNotificationSettingsRequest,
+    responses(
+        (status = 200, description = "Настройки по всем типам", body = Vec<NotificationSetting>),
+        (status = 401, description = "Не авторизован"),
+        (status = 422, description = "Обязательные уведомления нельзя отключить в приложении")
+    )
+)]
+async fn update_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Json(payload): Json<UpdateNotificationSettingsRequest>,
+) -> AppResult<Json<Vec<NotificationSetting>>> {
+    let settings =
+        NotificationService::update_settings(&state.pool, auth_user.user_id, &payload.settings)
+            .await?;
+    Ok(Json(settings))
+}
+
+/// Привязан ли Telegram для уведомлений
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/telegram/link",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Состояние привязки", body = TelegramLinkStatus),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+async fn get_telegram_link(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<TelegramLinkStatus>> {
+    let status = TelegramService::status(&state.pool, auth_user.user_id).await?;
+    Ok(Json(status))
+}
+
+/// Код и deep-link на бота; привязка завершается, когда пользователь нажмёт «Start»
+#[utoipa::path(
+    post,
+    path = "/api/v1/notifications/telegram/link",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Ссылка на бота", body = TelegramLinkCode),
+        (status = 401, description = "Не авторизован"),
+        (status = 503, description = "Telegram-бот не подключён")
+    )
+)]
+async fn create_telegram_link(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<TelegramLinkCode>> {
+    let code = TelegramService::new(state.config.clone())
+        .create_link_code(&state.pool, auth_user.user_id)
+        .await?;
+    Ok(Json(code))
+}
+
+/// Отвязать Telegram
+#[utoipa::path(
+    delete,
+    path = "/api/v1/notifications/telegram/link",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Telegram отвязан", body = NotificationSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Telegram не привязан")
+    )
+)]
+async fn delete_telegram_link(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<Value>> {
+    if !TelegramService::unlink(&state.pool, auth_user.user_id).await? {
+        return Err(AppError::NotFound("Telegram не привязан".to_string()));
+    }
+    Ok(Json(json!({"success": true})))
+}
+
+/// Поток уведомлений (SSE): событие `notification` с новым уведомлением
+/// и `unread_count` при каждом изменении счётчика непрочитанных
+#[utoipa::path(
+    get,
+    path = "/api/v1/notifications/stream",
+    tag = "Уведомления",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Поток событий", body = String, content_type = "text/event-stream"),
+        (status = 401, description = "Не авторизован"),
+        (status = 503, description = "Поток уведомлений недоступен")
+    )
+)]
+async fn stream_notifications(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Sse<ReceiverStream<Result<Event, Infallible>>>> {
+    let mut events = NotificationStreamService::subscribe()?;
+    let (tx, rx) = mpsc::channel(16);
+    let pool = state.pool.clone();
+    let user_id = auth_user.user_id;
+
+    tokio::spawn(async move {
+        // Сразу после подключения — текущий счётчик, дальше только изменения
+        let mut pending = Some(None);
+        loop {
+            if let Some(notification_id) = pending.take() {
+                let batch = match stream_events(&pool, user_id, notification_id).await {
+                    Ok(batch) => batch,
+                    Err(e) => {
+                        tracing::warn!("Failed to build notification event for {}: {}", user_id, e);
+                        Vec::new()
+                    }
+                };
+                for event in batch {
+                    if tx.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = tx.closed() => return,
+                received = events.recv() => match received {
+                    Ok(event) if event.user_id == user_id => pending = Some(event.notification_id),
+                    Ok(_) => {}
+                    // Пропущенные события восполняет актуальный счётчик
+                    Err(RecvError::Lagged(_)) => pending = Some(None),
+                    Err(RecvError::Closed) => return,
+                },
+            }
+        }
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default()))
+}
+
+/// Новое уведомление (если есть) и счётчик непрочитанных
+async fn stream_events(
+    pool: &PgPool,
+    user_id: Uuid,
+    notification_id: Option<Uuid>,
+) -> AppResult<Vec<Event>> {
+    let mut batch = Vec::new();
+
+    if let Some(id) = notification_id {
+        let notification = sqlx::query_as::<_, Notification>(
+            "SELECT * FROM notifications WHERE id = $1 AND user_id = $2",
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(notification) = notification {
+            let event = Event::default()
+                .event("notification")
+                .json_data(NotificationResponse::from(notification))
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            batch.push(event);
+        }
+    }
+
+    let (count,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND is_read = false")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+
+    let event = Event::default()
+        .event("unread_count")
+        .json_data(UnreadCountResponse { count })
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    batch.push(event);
+
+    Ok(batch)
+}
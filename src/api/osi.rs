@@ -1,17 +1,21 @@
 use axum::{
-    extract::{Path, State},
-    routing::{delete, get, post, put},
+    extract::{Path, Query, State},
+    routing::{delete, get, put},
     Json, Router,
 };
 use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_chairman_or_higher, AppState, AuthUser};
+use crate::middleware::{AppState, AuthUser, RequestId};
 use crate::models::{
     AddCouncilMemberRequest, ChairmanInfo, CouncilMember, CouncilMemberResponse,
-    CreateWorkerRequest, Osi, OsiDocument, OsiDocumentResponse, OsiResponse, OsiWorker,
-    UpdateOsiRequest,
+    CreateWorkerRequest, DomainEventResponse, DomainEventType, DomainEventsQuery, InboxItem,
+    InboxItemKind, InboxLink, InboxSection, NewAuditLog, OsiInboxResponse,
+    NewDomainEvent, NewFieldChange, Osi, OsiDocument, Permission, OsiDocumentResponse, OsiResponse, OsiWorker, UpdateOsiRequest,
+};
+use crate::services::{
+    auth_service::normalize_phone, AuditService, EventService, FieldHistoryService, PermissionService,
 };
 
 /// Успешный ответ на добавление члена совета
@@ -45,10 +49,48 @@ pub struct AddDocumentRequest {
     pub document_type: String,
 }
 
+/// Сколько элементов каждого раздела входящих отдавать сразу
+const INBOX_PREVIEW_LIMIT: i64 = 5;
+
+/// За сколько дней до окончания срок члена совета попадает во входящие
+const COUNCIL_TERM_WARNING_DAYS: i32 = 30;
+
+/// Непроверенные показания текущего и прошлого расчётного периода, дней
+const UNVERIFIED_READINGS_WINDOW_DAYS: i32 = 45;
+
+/// Элемент входящих председателя, как его возвращает запрос
+#[derive(sqlx::FromRow)]
+struct InboxRow {
+    kind: String,
+    id: Uuid,
+    title: String,
+    subtitle: Option<String>,
+    date: chrono::DateTime<chrono::Utc>,
+    /// Квартира, пользователь или счётчик — в зависимости от раздела
+    ref_id: Option<Uuid>,
+}
+
 fn default_document_type() -> String {
     "other".to_string()
 }
 
+/// Член совета с именем и телефоном из профиля
+#[derive(sqlx::FromRow)]
+struct CouncilMemberRow {
+    #[sqlx(flatten)]
+    member: CouncilMember,
+    user_name: String,
+    user_phone: String,
+}
+
+/// Документ ОСИ с именем загрузившего
+#[derive(sqlx::FromRow)]
+struct OsiDocumentRow {
+    #[sqlx(flatten)]
+    document: OsiDocument,
+    uploaded_by_name: Option<String>,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         .route("/by-complex/:complex_id", get(get_osi))
@@ -61,6 +103,33 @@ pub fn routes() -> Router<AppState> {
             put(update_worker).delete(remove_worker),
         )
         .route("/:id/documents", get(get_documents).post(add_document))
+        .route("/:id/chairman-actions", get(get_chairman_actions))
+        .route("/:id/inbox", get(get_inbox))
+}
+
+/// Записать действие председателя в журнал событий ОСИ
+async fn record_osi_event(
+    state: &AppState,
+    osi: &Osi,
+    actor_id: Uuid,
+    event_type: DomainEventType,
+    entity_type: &'static str,
+    entity_id: Option<Uuid>,
+    data: Option<Value>,
+) -> AppResult<()> {
+    EventService::record(
+        &state.pool,
+        NewDomainEvent {
+            complex_id: osi.complex_id,
+            osi_id: Some(osi.id),
+            actor_id,
+            event_type,
+            entity_type,
+            entity_id,
+            data,
+        },
+    )
+    .await
 }
 
 /// Получение ОСИ по ID жилого комплекса
@@ -115,6 +184,34 @@ pub async fn get_osi_by_id(
     build_osi_response(&state, osi).await
 }
 
+/// Штат и документы ОСИ видны жителям ЖК и тем, кто ими управляет
+async fn check_osi_access(
+    state: &AppState,
+    osi_id: Uuid,
+    auth_user: &AuthUser,
+    permission: Permission,
+) -> AppResult<()> {
+    let (complex_id,): (Uuid,) = sqlx::query_as("SELECT complex_id FROM osi WHERE id = $1")
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
+
+    if PermissionService::has(&state.pool, auth_user, complex_id, permission).await? {
+        return Ok(());
+    }
+
+    let is_member: Option<(i32,)> = sqlx::query_as(
+        "SELECT 1 FROM apartments WHERE complex_id = $1 AND (owner_id = $2 OR resident_id = $2) LIMIT 1",
+    )
+    .bind(complex_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    is_member.map(|_| ()).ok_or(AppError::Forbidden)
+}
+
 async fn build_osi_response(state: &AppState, osi: Osi) -> AppResult<Json<OsiResponse>> {
     let chairman = if let Some(chairman_id) = osi.chairman_id {
         sqlx::query_as::<_, (Uuid, Option<String>, Option<String>, String)>(
@@ -170,6 +267,7 @@ async fn build_osi_response(state: &AppState, osi: Osi) -> AppResult<Json<OsiRes
 pub async fn update_osi(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Path(osi_id): Path<Uuid>,
     Json(payload): Json<UpdateOsiRequest>,
 ) -> AppResult<Json<OsiResponse>> {
@@ -180,21 +278,22 @@ pub async fn update_osi(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageOsi)
+        .await?;
+
+    let mut tx = state.pool.begin().await?;
 
     let updated = sqlx::query_as::<_, Osi>(
         r#"
         UPDATE osi SET
             name = COALESCE($2, name),
-            bin = COALESCE($3, bin),
-            phone = COALESCE($4, phone),
-            email = COALESCE($5, email),
-            address = COALESCE($6, address),
-            bank_name = COALESCE($7, bank_name),
-            bank_bik = COALESCE($8, bank_bik),
-            bank_account = COALESCE($9, bank_account),
+            bin = $3,
+            phone = $4,
+            email = $5,
+            address = $6,
+            bank_name = $7,
+            bank_bik = $8,
+            bank_account = $9,
             updated_at = NOW()
         WHERE id = $1
         RETURNING *
@@ -202,14 +301,56 @@ pub async fn update_osi(
     )
     .bind(osi_id)
     .bind(&payload.name)
-    .bind(&payload.bin)
-    .bind(&payload.phone)
-    .bind(&payload.email)
-    .bind(&payload.address)
-    .bind(&payload.bank_name)
-    .bind(&payload.bank_bik)
-    .bind(&payload.bank_account)
-    .fetch_one(&state.pool)
+    .bind(payload.bin.apply(osi.bin.clone()))
+    .bind(payload.phone.apply(osi.phone.clone()))
+    .bind(payload.email.apply(osi.email.clone()))
+    .bind(payload.address.apply(osi.address.clone()))
+    .bind(payload.bank_name.apply(osi.bank_name.clone()))
+    .bind(payload.bank_bik.apply(osi.bank_bik.clone()))
+    .bind(payload.bank_account.apply(osi.bank_account.clone()))
+    .fetch_one(&mut *tx)
+    .await?;
+
+    AuditService::record(
+        &mut *tx,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(osi.complex_id),
+            action: "update_osi",
+            entity_type: "osi",
+            entity_id: Some(osi.id),
+            old_value: Some(json!(osi)),
+            new_value: Some(json!(updated)),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id: osi.complex_id,
+            entity_type: "osi",
+            entity_id: osi.id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!(osi),
+            new_value: json!(updated),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::OsiUpdated,
+        "osi",
+        Some(osi.id),
+        None,
+    )
     .await?;
 
     Ok(Json(OsiResponse {
@@ -240,38 +381,32 @@ pub async fn get_council(
     State(state): State<AppState>,
     Path(osi_id): Path<Uuid>,
 ) -> AppResult<Json<Vec<CouncilMemberResponse>>> {
-    let members = sqlx::query_as::<_, CouncilMember>(
-        "SELECT * FROM council_members WHERE osi_id = $1 AND is_active = true ORDER BY position",
+    let members = sqlx::query_as::<_, CouncilMemberRow>(
+        r#"
+        SELECT m.*, u.display_name AS user_name, u.phone AS user_phone
+        FROM council_members m
+        JOIN users u ON u.id = m.user_id
+        WHERE m.osi_id = $1 AND m.is_active = true
+        ORDER BY m.position
+        "#,
     )
     .bind(osi_id)
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for member in members {
-        let user_info: (String, String) = sqlx::query_as(
-            r#"
-            SELECT
-                COALESCE(first_name || ' ' || last_name, phone),
-                phone
-            FROM users WHERE id = $1
-            "#,
-        )
-        .bind(member.user_id)
-        .fetch_one(&state.pool)
-        .await?;
-
-        response.push(CouncilMemberResponse {
-            id: member.id,
-            user_id: member.user_id,
-            user_name: user_info.0,
-            user_phone: user_info.1,
-            position: member.position,
-            responsibilities: member.responsibilities,
-            appointed_at: member.appointed_at,
-            is_active: member.is_active,
-        });
-    }
+    let response = members
+        .into_iter()
+        .map(|row| CouncilMemberResponse {
+            id: row.member.id,
+            user_id: row.member.user_id,
+            user_name: row.user_name,
+            user_phone: row.user_phone,
+            position: row.member.position,
+            responsibilities: row.member.responsibilities,
+            appointed_at: row.member.appointed_at,
+            is_active: row.member.is_active,
+        })
+        .collect();
 
     Ok(Json(response))
 }
@@ -306,9 +441,8 @@ pub async fn add_council_member(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageCouncil)
+        .await?;
 
     let member_id: (Uuid,) = sqlx::query_as(
         r#"
@@ -324,6 +458,17 @@ pub async fn add_council_member(
     .fetch_one(&state.pool)
     .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::CouncilMemberAdded,
+        "council_member",
+        Some(member_id.0),
+        Some(json!({"user_id": payload.user_id, "position": payload.position})),
+    )
+    .await?;
+
     Ok(Json(json!({
         "success": true,
         "member_id": member_id.0
@@ -358,9 +503,8 @@ pub async fn remove_council_member(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageCouncil)
+        .await?;
 
     sqlx::query("UPDATE council_members SET is_active = false WHERE id = $1 AND osi_id = $2")
         .bind(member_id)
@@ -368,6 +512,17 @@ pub async fn remove_council_member(
         .execute(&state.pool)
         .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::CouncilMemberRemoved,
+        "council_member",
+        Some(member_id),
+        None,
+    )
+    .await?;
+
     Ok(Json(json!({"success": true})))
 }
 
@@ -379,14 +534,21 @@ pub async fn remove_council_member(
     params(
         ("id" = Uuid, Path, description = "ID ОСИ")
     ),
+    security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Список работников", body = Vec<OsiWorker>)
+        (status = 200, description = "Список работников", body = Vec<OsiWorker>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к ОСИ"),
+        (status = 404, description = "ОСИ не найдено")
     )
 )]
 pub async fn get_workers(
     State(state): State<AppState>,
+    auth_user: AuthUser,
     Path(osi_id): Path<Uuid>,
 ) -> AppResult<Json<Vec<OsiWorker>>> {
+    check_osi_access(&state, osi_id, &auth_user, Permission::ManageWorkers).await?;
+
     let workers = sqlx::query_as::<_, OsiWorker>(
         "SELECT * FROM osi_workers WHERE osi_id = $1 AND is_active = true ORDER BY last_name",
     )
@@ -426,14 +588,16 @@ pub async fn add_worker(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageWorkers)
+        .await?;
+
+    // Номер в том же виде, что и у пользователей, — по нему работник входит в приложение
+    let phone = payload.phone.as_deref().map(normalize_phone);
 
     let worker = sqlx::query_as::<_, OsiWorker>(
         r#"
-        INSERT INTO osi_workers (osi_id, first_name, last_name, middle_name, phone, role, position_title, salary, hired_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+        INSERT INTO osi_workers (osi_id, first_name, last_name, middle_name, phone, role, position_title, salary, hired_at, user_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, (SELECT id FROM users WHERE phone = $5))
         RETURNING *
         "#
     )
@@ -441,14 +605,29 @@ pub async fn add_worker(
     .bind(&payload.first_name)
     .bind(&payload.last_name)
     .bind(&payload.middle_name)
-    .bind(&payload.phone)
+    .bind(&phone)
     .bind(&payload.role)
     .bind(&payload.position_title)
-    .bind(&payload.salary)
-    .bind(&payload.hired_at)
+    .bind(payload.salary)
+    .bind(payload.hired_at)
     .fetch_one(&state.pool)
     .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::WorkerAdded,
+        "worker",
+        Some(worker.id),
+        Some(json!({
+            "name": format!("{} {}", worker.first_name, worker.last_name),
+            "role": worker.role,
+            "salary": worker.salary
+        })),
+    )
+    .await?;
+
     Ok(Json(worker))
 }
 
@@ -482,9 +661,10 @@ pub async fn update_worker(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageWorkers)
+        .await?;
+
+    let phone = payload.phone.as_deref().map(normalize_phone);
 
     let worker = sqlx::query_as::<_, OsiWorker>(
         r#"
@@ -493,6 +673,7 @@ pub async fn update_worker(
             last_name = $4,
             middle_name = $5,
             phone = $6,
+            user_id = (SELECT id FROM users WHERE phone = $6),
             role = $7,
             position_title = $8,
             salary = $9,
@@ -507,14 +688,29 @@ pub async fn update_worker(
     .bind(&payload.first_name)
     .bind(&payload.last_name)
     .bind(&payload.middle_name)
-    .bind(&payload.phone)
+    .bind(&phone)
     .bind(&payload.role)
     .bind(&payload.position_title)
-    .bind(&payload.salary)
-    .bind(&payload.hired_at)
+    .bind(payload.salary)
+    .bind(payload.hired_at)
     .fetch_one(&state.pool)
     .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::WorkerUpdated,
+        "worker",
+        Some(worker.id),
+        Some(json!({
+            "name": format!("{} {}", worker.first_name, worker.last_name),
+            "role": worker.role,
+            "salary": worker.salary
+        })),
+    )
+    .await?;
+
     Ok(Json(worker))
 }
 
@@ -546,9 +742,8 @@ pub async fn remove_worker(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageWorkers)
+        .await?;
 
     sqlx::query("UPDATE osi_workers SET is_active = false WHERE id = $1 AND osi_id = $2")
         .bind(worker_id)
@@ -556,6 +751,17 @@ pub async fn remove_worker(
         .execute(&state.pool)
         .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::WorkerRemoved,
+        "worker",
+        Some(worker_id),
+        None,
+    )
+    .await?;
+
     Ok(Json(json!({"success": true})))
 }
 
@@ -570,41 +776,44 @@ pub async fn remove_worker(
     ),
     responses(
         (status = 200, description = "Список документов", body = Vec<OsiDocumentResponse>),
-        (status = 401, description = "Не авторизован")
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к ОСИ"),
+        (status = 404, description = "ОСИ не найдено")
     )
 )]
 pub async fn get_documents(
     State(state): State<AppState>,
-    _auth_user: AuthUser,
+    auth_user: AuthUser,
     Path(osi_id): Path<Uuid>,
 ) -> AppResult<Json<Vec<OsiDocumentResponse>>> {
-    let documents = sqlx::query_as::<_, OsiDocument>(
-        "SELECT * FROM osi_documents WHERE osi_id = $1 ORDER BY created_at DESC",
+    check_osi_access(&state, osi_id, &auth_user, Permission::ManageDocuments).await?;
+
+    let documents = sqlx::query_as::<_, OsiDocumentRow>(
+        r#"
+        SELECT d.*, u.display_name AS uploaded_by_name
+        FROM osi_documents d
+        LEFT JOIN users u ON u.id = d.uploaded_by
+        WHERE d.osi_id = $1
+        ORDER BY d.created_at DESC
+        "#,
     )
     .bind(osi_id)
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for doc in documents {
-        let uploader_name: Option<(String,)> = sqlx::query_as(
-            "SELECT COALESCE(first_name || ' ' || last_name, phone) FROM users WHERE id = $1",
-        )
-        .bind(doc.uploaded_by)
-        .fetch_optional(&state.pool)
-        .await?;
-
-        response.push(OsiDocumentResponse {
-            id: doc.id,
-            title: doc.title,
-            description: doc.description,
-            document_type: doc.document_type,
-            file_url: doc.file_url,
-            file_size: doc.file_size,
-            uploaded_by_name: uploader_name.map(|(n,)| n),
-            created_at: doc.created_at,
-        });
-    }
+    let response = documents
+        .into_iter()
+        .map(|row| OsiDocumentResponse {
+            id: row.document.id,
+            title: row.document.title,
+            description: row.document.description,
+            document_type: row.document.document_type,
+            file_url: row.document.file_url,
+            file_size: row.document.file_size,
+            uploaded_by_name: row.uploaded_by_name,
+            created_at: row.document.created_at,
+        })
+        .collect();
 
     Ok(Json(response))
 }
@@ -639,9 +848,8 @@ pub async fn add_document(
         .await?
         .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
 
-    if osi.chairman_id != Some(auth_user.user_id) && !is_chairman_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
-    }
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageDocuments)
+        .await?;
 
     let title = payload["title"]
         .as_str()
@@ -667,8 +875,259 @@ pub async fn add_document(
     .fetch_one(&state.pool)
     .await?;
 
+    record_osi_event(
+        &state,
+        &osi,
+        auth_user.user_id,
+        DomainEventType::DocumentUploaded,
+        "document",
+        Some(doc_id.0),
+        Some(json!({"title": title, "document_type": doc_type})),
+    )
+    .await?;
+
     Ok(Json(json!({
         "success": true,
         "document_id": doc_id.0
     })))
 }
+
+/// Журнал действий председателя (для членов совета)
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/chairman-actions",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        DomainEventsQuery
+    ),
+    responses(
+        (status = 200, description = "Действия председателя", body = Vec<DomainEventResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только членам совета"),
+        (status = 404, description = "ОСИ не найдено")
+    )
+)]
+pub async fn get_chairman_actions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<DomainEventsQuery>,
+) -> AppResult<Json<Vec<DomainEventResponse>>> {
+    let osi = sqlx::query_as::<_, Osi>("SELECT * FROM osi WHERE id = $1")
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
+
+    // Проверяем, что пользователь — действующий член совета
+    let is_council_member: Option<(i32,)> = sqlx::query_as(
+        r#"
+        SELECT 1 FROM council_members
+        WHERE osi_id = $1 AND user_id = $2 AND is_active = true
+          AND (expires_at IS NULL OR expires_at > NOW())
+        "#,
+    )
+    .bind(osi.id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if is_council_member.is_none()
+        && !PermissionService::has(&state.pool, &auth_user, osi.complex_id, Permission::ManageOsi).await?
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let events = sqlx::query_as::<_, DomainEventResponse>(
+        r#"
+        SELECT e.id, e.event_type, e.entity_type, e.entity_id, e.actor_id,
+               u.display_name AS actor_name,
+               e.data, e.created_at
+        FROM domain_events e
+        LEFT JOIN users u ON u.id = e.actor_id
+        WHERE e.osi_id = $1
+          AND ($2::domain_event_type IS NULL OR e.event_type = $2)
+          AND ($3::timestamptz IS NULL OR e.created_at >= $3)
+          AND ($4::timestamptz IS NULL OR e.created_at < $4)
+        ORDER BY e.created_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+    )
+    .bind(osi.id)
+    .bind(query.event_type)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(events))
+}
+
+/// Входящие председателя: всё, что ждёт его действия, со ссылками на экраны приложения
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/inbox",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    responses(
+        (status = 200, description = "Разделы входящих со счётчиками", body = OsiInboxResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для управляющих ОСИ"),
+        (status = 404, description = "ОСИ не найдено")
+    )
+)]
+pub async fn get_inbox(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+) -> AppResult<Json<OsiInboxResponse>> {
+    let osi = sqlx::query_as::<_, Osi>("SELECT * FROM osi WHERE id = $1")
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))?;
+
+    PermissionService::require(&state.pool, &auth_user, osi.complex_id, Permission::ManageOsi)
+        .await?;
+
+    let (join_requests, unassigned, expiring, unverified): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM join_requests
+             WHERE complex_id = $1 AND status = 'pending'),
+            (SELECT COUNT(*) FROM maintenance_requests
+             WHERE complex_id = $1 AND assigned_to IS NULL
+               AND status NOT IN ('completed', 'rejected', 'cancelled')),
+            (SELECT COUNT(*) FROM council_members
+             WHERE osi_id = $2 AND is_active
+               AND expires_at < NOW() + make_interval(days => $3)),
+            (SELECT COUNT(*) FROM meter_readings r
+             JOIN apartments a ON a.id = r.apartment_id
+             WHERE a.complex_id = $1 AND NOT COALESCE(r.is_verified, false)
+               AND r.reading_date >= CURRENT_DATE - $4)
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(osi.id)
+    .bind(COUNCIL_TERM_WARNING_DAYS)
+    .bind(UNVERIFIED_READINGS_WINDOW_DAYS)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // Первые элементы всех разделов одним запросом
+    let rows = sqlx::query_as::<_, InboxRow>(
+        r#"
+        (SELECT 'join_request' AS kind, j.id, COALESCE(u.display_name, u.phone) AS title,
+                concat_ws(', ', j.building, 'кв. ' || j.apartment_number) AS subtitle,
+                j.submitted_at AS date, j.user_id AS ref_id
+         FROM join_requests j
+         JOIN users u ON u.id = j.user_id
+         WHERE j.complex_id = $1 AND j.status = 'pending'
+         ORDER BY j.submitted_at
+         LIMIT $5)
+        UNION ALL
+        (SELECT 'unassigned_maintenance', m.id, m.title, m.location,
+                COALESCE(m.created_at, NOW()), m.apartment_id
+         FROM maintenance_requests m
+         WHERE m.complex_id = $1 AND m.assigned_to IS NULL
+           AND m.status NOT IN ('completed', 'rejected', 'cancelled')
+         ORDER BY
+            CASE m.priority
+                WHEN 'emergency' THEN 1
+                WHEN 'high' THEN 2
+                WHEN 'normal' THEN 3
+                ELSE 4
+            END,
+            m.created_at
+         LIMIT $5)
+        UNION ALL
+        (SELECT 'expiring_council_term', c.id, COALESCE(u.display_name, u.phone), c.position::text,
+                c.expires_at, c.user_id
+         FROM council_members c
+         JOIN users u ON u.id = c.user_id
+         WHERE c.osi_id = $2 AND c.is_active
+           AND c.expires_at < NOW() + make_interval(days => $3)
+         ORDER BY c.expires_at
+         LIMIT $5)
+        UNION ALL
+        (SELECT 'unverified_reading', r.id,
+                concat_ws(', ', a.building, 'кв. ' || a.number), mt.utility_type::text,
+                COALESCE(r.created_at, r.reading_date::timestamptz), r.meter_id
+         FROM meter_readings r
+         JOIN apartments a ON a.id = r.apartment_id
+         JOIN meters mt ON mt.id = r.meter_id
+         WHERE a.complex_id = $1 AND NOT COALESCE(r.is_verified, false)
+           AND r.reading_date >= CURRENT_DATE - $4
+         ORDER BY r.reading_date, r.created_at
+         LIMIT $5)
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(osi.id)
+    .bind(COUNCIL_TERM_WARNING_DAYS)
+    .bind(UNVERIFIED_READINGS_WINDOW_DAYS)
+    .bind(INBOX_PREVIEW_LIMIT)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let sections: Vec<InboxSection> = InboxItemKind::ALL
+        .into_iter()
+        .map(|kind| InboxSection {
+            kind,
+            count: match kind {
+                InboxItemKind::JoinRequest => join_requests,
+                InboxItemKind::UnassignedMaintenance => unassigned,
+                InboxItemKind::ExpiringCouncilTerm => expiring,
+                InboxItemKind::UnverifiedReading => unverified,
+            },
+            items: rows
+                .iter()
+                .filter(|row| row.kind == kind.as_str())
+                .map(|row| InboxItem {
+                    id: row.id,
+                    title: row.title.clone(),
+                    subtitle: row.subtitle.clone().filter(|s| !s.is_empty()),
+                    date: row.date,
+                    link: inbox_link(kind, &osi, row),
+                })
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(OsiInboxResponse {
+        total: sections.iter().map(|section| section.count).sum(),
+        sections,
+    }))
+}
+
+/// Экран приложения, на котором председатель выполнит действие
+fn inbox_link(kind: InboxItemKind, osi: &Osi, row: &InboxRow) -> InboxLink {
+    match kind {
+        InboxItemKind::JoinRequest => InboxLink {
+            screen: "join_request",
+            params: json!({"id": row.id, "complex_id": osi.complex_id, "user_id": row.ref_id}),
+        },
+        InboxItemKind::UnassignedMaintenance => InboxLink {
+            screen: "maintenance_request",
+            params: json!({"id": row.id, "complex_id": osi.complex_id, "apartment_id": row.ref_id}),
+        },
+        InboxItemKind::ExpiringCouncilTerm => InboxLink {
+            screen: "council_member",
+            params: json!({"osi_id": osi.id, "member_id": row.id, "user_id": row.ref_id}),
+        },
+        InboxItemKind::UnverifiedReading => InboxLink {
+            screen: "meter_reading",
+            params: json!({"id": row.id, "complex_id": osi.complex_id, "meter_id": row.ref_id}),
+        },
+    }
+}
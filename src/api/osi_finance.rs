@@ -0,0 +1,2502 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{AppState, AuthUser, RequestId};
+use crate::models::{
+    AllocationRule, Bill, BillStatus, CategoryFinanceSummary, MonthlyReport, MonthlyReportQuery,
+    ReportExpenseCategory, ReportMaintenanceCosts, CreateExpenseCategoryRequest,
+    FinanceSummary, FinanceSummaryQuery, MonthFinanceSummary, UpdateExpenseCategoryRequest, BudgetLineRequest, BudgetLineResponse, BudgetResponse,
+    BudgetStatus, CreateBudgetRequest, NotificationFanoutPayload, OsiBudget, SubmitBudgetRequest,
+    UpdateBudgetLinesRequest, CreateSharedChargeRequest, JobType, SharedCharge,
+    SharedChargeAllocationResponse, SharedChargeBillingPayload, SharedChargeResponse, UtilityType, CashPaymentsQuery, CashReceiptResponse, ConfirmExpenseRequest, CreateExpenseRequest, DomainEventType, ExpenseApprovalResponse,
+    ExpenseCategory, ExpenseDecision, ExpenseDecisionRequest, ExpenseResponse, ExpenseStatus,
+    ExpensesQuery, FinanceSettings, FinancialContext, FinancialEvent, InvoiceIntakeResponse,
+    NewDomainEvent, NewFieldChange, NewFinancialAuditEntry, NotificationType, Osi,
+    OsiExpense, Payment, PaymentAllocation, Permission, RecognizedInvoice, RegisterCashPaymentRequest,
+    SmsKindUsage, SmsUsageQuery, SmsUsageReport,
+};
+use crate::services::barrier_service::generate_qr_code_base64;
+use crate::services::budget_service::BUDGET_VOTING_OPTIONS;
+use crate::services::file_service::{validate_image_content_type, MAX_IMAGE_SIZE};
+use crate::services::ocr_service::parse_invoice;
+use crate::services::shared_charge_service::{next_month_start, split_amount};
+use crate::services::{
+    DocumentService, EventService, FieldHistoryService, FileService, FinancialAuditService,
+    JobService, NotificationService, OcrService, PaymentService, PermissionService,
+};
+
+/// Ключевые слова для подбора категории по тексту счёта
+const CATEGORY_KEYWORDS: &[(&str, &[&str])] = &[
+    ("cleaning", &["уборк", "клининг", "моющ", "вывоз мусора"]),
+    ("repair", &["ремонт", "материал", "сантехн", "краск"]),
+    ("utilities", &["электроэнерг", "водоснаб", "теплоснаб", "газоснаб", "коммунальн"]),
+    ("salary", &["заработн", "зарплат", "оклад"]),
+    ("security", &["охран", "видеонаблюд", "домофон"]),
+    ("elevator", &["лифт"]),
+    ("landscaping", &["озеленен", "благоустр", "газон", "саженц"]),
+    ("bank", &["банк", "комисси"]),
+];
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/:id/expense-categories",
+            get(list_expense_categories).post(create_expense_category),
+        )
+        .route("/:id/expense-categories/:category_id", put(update_expense_category))
+        .route("/:id/finance-summary", get(get_finance_summary))
+        .route("/:id/reports/monthly", get(get_monthly_report))
+        .route("/:id/expenses", get(list_expenses).post(create_expense))
+        .route("/:id/expenses/intake", post(intake_invoice))
+        .route("/:id/expenses/:expense_id", get(get_expense))
+        .route("/:id/expenses/:expense_id/confirm", post(confirm_expense))
+        .route("/:id/expenses/:expense_id/decision", post(decide_expense))
+        .route("/:id/expenses/:expense_id/execute", post(execute_expense))
+        .route("/:id/shared-charges", get(list_shared_charges).post(create_shared_charge))
+        .route("/:id/shared-charges/:charge_id", get(get_shared_charge))
+        .route("/:id/shared-charges/:charge_id/cancel", post(cancel_shared_charge))
+        .route("/:id/budgets", get(list_budgets).post(create_budget))
+        .route("/:id/budgets/:budget_id", get(get_budget))
+        .route("/:id/budgets/:budget_id/lines", put(update_budget_lines))
+        .route("/:id/budgets/:budget_id/submit", post(submit_budget))
+        .route("/:id/cash-payments", get(list_cash_payments).post(register_cash_payment))
+        .route(
+            "/:id/finance-settings",
+            get(get_finance_settings).put(update_finance_settings),
+        )
+        .route("/:id/sms-usage", get(get_sms_usage))
+}
+
+async fn get_osi(state: &AppState, osi_id: Uuid) -> AppResult<Osi> {
+    sqlx::query_as::<_, Osi>("SELECT * FROM osi WHERE id = $1")
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ОСИ не найдено".to_string()))
+}
+
+async fn check_permission(
+    state: &AppState,
+    osi: &Osi,
+    auth_user: &AuthUser,
+    permission: Permission,
+) -> AppResult<()> {
+    PermissionService::require(&state.pool, auth_user, osi.complex_id, permission).await
+}
+
+/// Финансы ОСИ видны всем жителям ЖК
+async fn check_osi_member(state: &AppState, osi: &Osi, auth_user: &AuthUser) -> AppResult<()> {
+    if PermissionService::has(&state.pool, auth_user, osi.complex_id, Permission::ManageFinance).await? {
+        return Ok(());
+    }
+
+    let is_member: Option<(i32,)> = sqlx::query_as(
+        "SELECT 1 FROM apartments WHERE complex_id = $1 AND (owner_id = $2 OR resident_id = $2) LIMIT 1",
+    )
+    .bind(osi.complex_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    is_member.map(|_| ()).ok_or(AppError::Forbidden)
+}
+
+/// Действующие члены совета ОСИ
+async fn active_council_ids(state: &AppState, osi_id: Uuid) -> AppResult<Vec<Uuid>> {
+    let members: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT user_id FROM council_members
+        WHERE osi_id = $1 AND is_active = true
+          AND (expires_at IS NULL OR expires_at > NOW())
+        "#,
+    )
+    .bind(osi_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(members.into_iter().map(|(id,)| id).collect())
+}
+
+async fn get_expense_for_osi(state: &AppState, osi_id: Uuid, expense_id: Uuid) -> AppResult<OsiExpense> {
+    sqlx::query_as::<_, OsiExpense>("SELECT * FROM osi_expenses WHERE id = $1 AND osi_id = $2")
+        .bind(expense_id)
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Расход не найден".to_string()))
+}
+
+async fn build_expense_response(state: &AppState, expense: OsiExpense) -> AppResult<ExpenseResponse> {
+    let category_name: (String,) =
+        sqlx::query_as("SELECT name FROM expense_categories WHERE id = $1")
+            .bind(expense.category_id)
+            .fetch_one(&state.pool)
+            .await?;
+
+    let approvals = sqlx::query_as::<_, ExpenseApprovalResponse>(
+        r#"
+        SELECT ea.id, ea.user_id,
+               u.display_name AS user_name,
+               ea.decision, ea.comment, ea.created_at
+        FROM expense_approvals ea
+        JOIN users u ON u.id = ea.user_id
+        WHERE ea.expense_id = $1
+        ORDER BY ea.created_at
+        "#,
+    )
+    .bind(expense.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let approvals_count = approvals
+        .iter()
+        .filter(|a| a.decision == ExpenseDecision::Approve)
+        .count() as i64;
+
+    Ok(ExpenseResponse {
+        id: expense.id,
+        category_id: expense.category_id,
+        category_name: category_name.0,
+        title: expense.title,
+        description: expense.description,
+        amount: expense.amount,
+        vendor: expense.vendor,
+        expense_date: expense.expense_date,
+        receipt_url: expense.receipt_url,
+        status: expense.status,
+        required_approvals: expense.required_approvals,
+        approvals_count,
+        approvals,
+        executed_at: expense.executed_at,
+        ocr_data: expense.ocr_data,
+        created_at: expense.created_at,
+    })
+}
+
+async fn record_expense_event(
+    state: &AppState,
+    osi: &Osi,
+    actor_id: Uuid,
+    event_type: DomainEventType,
+    expense: &OsiExpense,
+) -> AppResult<()> {
+    EventService::record(
+        &state.pool,
+        NewDomainEvent {
+            complex_id: osi.complex_id,
+            osi_id: Some(osi.id),
+            actor_id,
+            event_type,
+            entity_type: "expense",
+            entity_id: Some(expense.id),
+            data: Some(json!({
+                "title": expense.title,
+                "amount": expense.amount,
+                "status": expense.status
+            })),
+        },
+    )
+    .await
+}
+
+async fn check_category(state: &AppState, osi_id: Uuid, category_id: Uuid) -> AppResult<()> {
+    let category: Option<(Uuid,)> = sqlx::query_as(
+        "SELECT id FROM expense_categories WHERE id = $1 AND (osi_id IS NULL OR osi_id = $2)",
+    )
+    .bind(category_id)
+    .bind(osi_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    category
+        .map(|_| ())
+        .ok_or_else(|| AppError::BadRequest("Категория не найдена".to_string()))
+}
+
+/// Статус нового расхода и кто должен его согласовать
+struct ApprovalPlan {
+    status: ExpenseStatus,
+    required_approvals: i32,
+    council_ids: Vec<Uuid>,
+}
+
+async fn plan_approval(
+    state: &AppState,
+    osi: &Osi,
+    amount: Decimal,
+    created_by: Uuid,
+) -> AppResult<ApprovalPlan> {
+    let needs_approval = osi
+        .expense_approval_threshold
+        .map(|threshold| amount > threshold)
+        .unwrap_or(false);
+
+    if !needs_approval {
+        return Ok(ApprovalPlan {
+            status: ExpenseStatus::Approved,
+            required_approvals: 0,
+            council_ids: Vec::new(),
+        });
+    }
+
+    let mut council_ids = active_council_ids(state, osi.id).await?;
+    council_ids.retain(|id| *id != created_by);
+    if council_ids.is_empty() {
+        return Err(AppError::BadRequest(
+            "Для расходов выше порога необходим совет дома".to_string(),
+        ));
+    }
+
+    Ok(ApprovalPlan {
+        status: ExpenseStatus::PendingApproval,
+        required_approvals: osi.expense_required_approvals.clamp(1, council_ids.len() as i32),
+        council_ids,
+    })
+}
+
+/// Зафиксировать появление расхода и позвать совет на согласование
+async fn announce_expense(
+    state: &AppState,
+    osi: &Osi,
+    actor_id: Uuid,
+    expense: &OsiExpense,
+    council_ids: &[Uuid],
+) -> AppResult<()> {
+    record_expense_event(state, osi, actor_id, DomainEventType::ExpenseCreated, expense).await?;
+
+    if !council_ids.is_empty() {
+        NotificationService::notify_users(
+            &state.pool,
+            council_ids,
+            NotificationType::Finance,
+            "Расход ожидает согласования",
+            Some(&format!("{} — {} ₸", expense.title, expense.amount)),
+            Some(json!({"osi_id": osi.id, "expense_id": expense.id})),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Категория для счёта: как у прошлых расходов этого поставщика, иначе по ключевым словам
+async fn suggest_category(
+    state: &AppState,
+    osi_id: Uuid,
+    vendor: Option<&str>,
+    text: &str,
+) -> AppResult<Uuid> {
+    if let Some(vendor) = vendor {
+        let previous: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT category_id FROM osi_expenses
+            WHERE osi_id = $1 AND lower(vendor) = lower($2) AND status <> 'pending_confirmation'
+            GROUP BY category_id
+            ORDER BY COUNT(*) DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(osi_id)
+        .bind(vendor)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        if let Some((category_id,)) = previous {
+            return Ok(category_id);
+        }
+    }
+
+    let text = text.to_lowercase();
+    let slug = CATEGORY_KEYWORDS
+        .iter()
+        .map(|(slug, words)| (*slug, words.iter().filter(|w| text.contains(*w)).count()))
+        .filter(|(_, hits)| *hits > 0)
+        .max_by_key(|(_, hits)| *hits)
+        .map(|(slug, _)| slug)
+        .unwrap_or("other");
+
+    // Собственная категория ОСИ с тем же кодом важнее общей
+    let (category_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        SELECT id FROM expense_categories
+        WHERE slug = $2 AND (osi_id IS NULL OR osi_id = $1)
+        ORDER BY osi_id NULLS LAST
+        LIMIT 1
+        "#,
+    )
+    .bind(osi_id)
+    .bind(slug)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(category_id)
+}
+
+/// Квитанция наличного платежа с QR-кодом для проверки
+async fn build_cash_receipt(state: &AppState, payment: Payment) -> AppResult<CashReceiptResponse> {
+    let (apartment_number,): (String,) = sqlx::query_as("SELECT number FROM apartments WHERE id = $1")
+        .bind(payment.apartment_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+    let allocations = sqlx::query_as::<_, PaymentAllocation>(
+        "SELECT bill_id, amount FROM payment_allocations WHERE payment_id = $1 ORDER BY created_at",
+    )
+    .bind(payment.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let received_by_name: Option<(String,)> =
+        sqlx::query_as("SELECT display_name FROM users WHERE id = $1")
+            .bind(payment.received_by)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    let allocated: Decimal = allocations.iter().map(|a| a.amount).sum();
+    let verification_url = format!(
+        "{}/api/v1/communal/receipts/{}",
+        state.config.public_url.trim_end_matches('/'),
+        payment.verification_token.as_deref().unwrap_or_default()
+    );
+
+    Ok(CashReceiptResponse {
+        payment_id: payment.id,
+        receipt_number: payment.receipt_number.unwrap_or_default(),
+        apartment_id: payment.apartment_id,
+        apartment_number,
+        amount: payment.amount,
+        allocations,
+        unallocated: payment.amount - allocated,
+        qr_code: generate_qr_code_base64(&verification_url).ok(),
+        verification_url,
+        received_by_name: received_by_name.map(|(name,)| name),
+        comment: payment.comment,
+        created_at: payment.created_at,
+    })
+}
+
+async fn build_shared_charge_response(
+    state: &AppState,
+    charge: SharedCharge,
+) -> AppResult<SharedChargeResponse> {
+    let allocations = sqlx::query_as::<_, SharedChargeAllocationResponse>(
+        r#"
+        SELECT sca.apartment_id, a.number AS apartment_number, a.building,
+               sca.basis, sca.amount, sca.bill_item_id
+        FROM shared_charge_allocations sca
+        JOIN apartments a ON a.id = sca.apartment_id
+        WHERE sca.charge_id = $1
+        ORDER BY a.building NULLS FIRST, a.number
+        "#,
+    )
+    .bind(charge.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(SharedChargeResponse {
+        charge,
+        allocations,
+    })
+}
+
+async fn get_shared_charge_for_osi(
+    state: &AppState,
+    osi_id: Uuid,
+    charge_id: Uuid,
+) -> AppResult<SharedCharge> {
+    sqlx::query_as::<_, SharedCharge>("SELECT * FROM shared_charges WHERE id = $1 AND osi_id = $2")
+        .bind(charge_id)
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Начисление не найдено".to_string()))
+}
+
+async fn get_budget_for_osi(state: &AppState, osi_id: Uuid, budget_id: Uuid) -> AppResult<OsiBudget> {
+    sqlx::query_as::<_, OsiBudget>("SELECT * FROM osi_budgets WHERE id = $1 AND osi_id = $2")
+        .bind(budget_id)
+        .bind(osi_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Бюджет не найден".to_string()))
+}
+
+/// Заменить статьи бюджета
+async fn save_budget_lines(
+    conn: &mut sqlx::PgConnection,
+    osi_id: Uuid,
+    budget_id: Uuid,
+    lines: &[BudgetLineRequest],
+) -> AppResult<()> {
+    if lines.iter().any(|line| line.planned_amount < Decimal::ZERO) {
+        return Err(AppError::BadRequest("Плановая сумма не может быть отрицательной".to_string()));
+    }
+
+    for line in lines {
+        let Some(amounts) = &line.monthly_amounts else {
+            continue;
+        };
+        if amounts.len() != 12 || amounts.iter().any(|amount| *amount < Decimal::ZERO) {
+            return Err(AppError::BadRequest(
+                "Помесячный план — 12 неотрицательных сумм".to_string(),
+            ));
+        }
+        if amounts.iter().copied().sum::<Decimal>() != line.planned_amount {
+            return Err(AppError::BadRequest(
+                "Сумма помесячного плана не совпадает с годовой".to_string(),
+            ));
+        }
+    }
+
+    let category_ids: Vec<Uuid> = lines.iter().map(|line| line.category_id).collect();
+    let (known,): (i64,) = sqlx::query_as(
+        r#"
+        SELECT COUNT(DISTINCT id) FROM expense_categories
+        WHERE id = ANY($1) AND (osi_id IS NULL OR osi_id = $2)
+        "#,
+    )
+    .bind(&category_ids)
+    .bind(osi_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    if known as usize != lines.len() {
+        return Err(AppError::BadRequest(
+            "Категории не найдены или повторяются".to_string(),
+        ));
+    }
+
+    sqlx::query("DELETE FROM budget_lines WHERE budget_id = $1")
+        .bind(budget_id)
+        .execute(&mut *conn)
+        .await?;
+
+    for line in lines {
+        sqlx::query(
+            r#"
+            INSERT INTO budget_lines (budget_id, category_id, planned_amount, note, monthly_amounts)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(budget_id)
+        .bind(line.category_id)
+        .bind(line.planned_amount)
+        .bind(&line.note)
+        .bind(&line.monthly_amounts)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Бюджет с планом и фактом по статьям (факт — согласованные и исполненные расходы года)
+async fn build_budget_response(state: &AppState, budget: OsiBudget) -> AppResult<BudgetResponse> {
+    let lines = sqlx::query_as::<_, BudgetLineResponse>(
+        r#"
+        WITH planned AS (
+            SELECT category_id, planned_amount, note, monthly_amounts
+            FROM budget_lines WHERE budget_id = $1
+        ),
+        actual AS (
+            SELECT category_id, SUM(amount) AS actual_amount
+            FROM osi_expenses
+            WHERE osi_id = $2
+              AND status IN ('approved', 'executed')
+              AND EXTRACT(YEAR FROM expense_date) = $3
+            GROUP BY category_id
+        )
+        SELECT c.id AS category_id, c.name AS category_name,
+               COALESCE(p.planned_amount, 0) AS planned_amount,
+               COALESCE(a.actual_amount, 0) AS actual_amount,
+               p.note, p.monthly_amounts
+        FROM planned p
+        FULL JOIN actual a ON a.category_id = p.category_id
+        JOIN expense_categories c ON c.id = COALESCE(p.category_id, a.category_id)
+        ORDER BY c.sort_order, c.name
+        "#,
+    )
+    .bind(budget.id)
+    .bind(budget.osi_id)
+    .bind(budget.year)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let total_planned = lines.iter().map(|line| line.planned_amount).sum();
+    let total_actual = lines.iter().map(|line| line.actual_amount).sum();
+
+    Ok(BudgetResponse {
+        budget,
+        lines,
+        total_planned,
+        total_actual,
+    })
+}
+
+/// Категории расходов ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/expense-categories",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    responses(
+        (status = 200, description = "Категории расходов", body = Vec<ExpenseCategory>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn list_expense_categories(
+    State(state): State<AppState>,
+    _auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+) -> AppResult<Json<Vec<ExpenseCategory>>> {
+    let categories = sqlx::query_as::<_, ExpenseCategory>(
+        r#"
+        SELECT * FROM expense_categories
+        WHERE (osi_id IS NULL OR osi_id = $1) AND is_active = true
+        ORDER BY sort_order, name
+        "#,
+    )
+    .bind(osi_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(categories))
+}
+
+/// Добавить собственную категорию расходов ОСИ
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expense-categories",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = CreateExpenseCategoryRequest,
+    responses(
+        (status = 200, description = "Категория создана", body = ExpenseCategory),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 409, description = "Категория с таким названием уже есть")
+    )
+)]
+pub async fn create_expense_category(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<CreateExpenseCategoryRequest>,
+) -> AppResult<Json<ExpenseCategory>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.chars().count() > 100 {
+        return Err(AppError::BadRequest("Название категории от 1 до 100 символов".to_string()));
+    }
+
+    // Общая категория с тем же названием уже есть у всех ОСИ
+    let duplicate: Option<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM expense_categories
+        WHERE (osi_id IS NULL OR osi_id = $1) AND lower(name) = lower($2)
+        "#,
+    )
+    .bind(osi.id)
+    .bind(name)
+    .fetch_optional(&state.pool)
+    .await?;
+    if duplicate.is_some() {
+        return Err(AppError::Conflict("Категория с таким названием уже есть".to_string()));
+    }
+
+    let id = Uuid::new_v4();
+    let category = sqlx::query_as::<_, ExpenseCategory>(
+        r#"
+        INSERT INTO expense_categories (id, osi_id, name, slug, sort_order)
+        VALUES (
+            $1, $2, $3, 'custom-' || $1::text,
+            COALESCE($4, (SELECT COALESCE(MAX(sort_order), 0) + 1 FROM expense_categories
+                          WHERE osi_id IS NULL OR osi_id = $2))
+        )
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(osi.id)
+    .bind(name)
+    .bind(payload.sort_order)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(category))
+}
+
+/// Изменить или скрыть собственную категорию расходов
+#[utoipa::path(
+    put,
+    path = "/api/v1/osi/{id}/expense-categories/{category_id}",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("category_id" = Uuid, Path, description = "ID категории")
+    ),
+    request_body = UpdateExpenseCategoryRequest,
+    responses(
+        (status = 200, description = "Категория обновлена", body = ExpenseCategory),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Категория не найдена"),
+        (status = 409, description = "Категория с таким названием уже есть")
+    )
+)]
+pub async fn update_expense_category(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, category_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateExpenseCategoryRequest>,
+) -> AppResult<Json<ExpenseCategory>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let name = payload.name.as_deref().map(str::trim);
+    if name.is_some_and(|name| name.is_empty() || name.chars().count() > 100) {
+        return Err(AppError::BadRequest("Название категории от 1 до 100 символов".to_string()));
+    }
+
+    // Общие категории правит только платформа
+    let category = sqlx::query_as::<_, ExpenseCategory>(
+        r#"
+        UPDATE expense_categories SET
+            name = COALESCE($3, name),
+            sort_order = COALESCE($4, sort_order),
+            is_active = COALESCE($5, is_active)
+        WHERE id = $1 AND osi_id = $2
+        RETURNING *
+        "#,
+    )
+    .bind(category_id)
+    .bind(osi.id)
+    .bind(name)
+    .bind(payload.sort_order)
+    .bind(payload.is_active)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::Conflict("Категория с таким названием уже есть".to_string())
+        }
+        _ => AppError::Database(e),
+    })?
+    .ok_or_else(|| AppError::NotFound("Категория не найдена".to_string()))?;
+
+    Ok(Json(category))
+}
+
+/// Сводка для жителей: поступления, расходы по категориям и исполнение бюджета
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/finance-summary",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        FinanceSummaryQuery
+    ),
+    responses(
+        (status = 200, description = "Финансовая сводка", body = FinanceSummary),
+        (status = 400, description = "Неверный период"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет квартиры в ЖК")
+    )
+)]
+pub async fn get_finance_summary(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<FinanceSummaryQuery>,
+) -> AppResult<Json<FinanceSummary>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let year = query.year.unwrap_or_else(|| chrono::Utc::now().year());
+    let invalid_period = || AppError::BadRequest("Неверный период".to_string());
+    let (period_start, period_end) = match query.month {
+        Some(month) => {
+            let start = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(invalid_period)?;
+            (start, next_month_start(start))
+        }
+        None => (
+            NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(invalid_period)?,
+            NaiveDate::from_ymd_opt(year + 1, 1, 1).ok_or_else(invalid_period)?,
+        ),
+    };
+
+    // Расходы сравниваются с утверждённым собственниками бюджетом
+    let budget_id: Option<Uuid> = sqlx::query_scalar(
+        "SELECT id FROM osi_budgets WHERE osi_id = $1 AND year = $2 AND status = 'approved'",
+    )
+    .bind(osi.id)
+    .bind(year)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let months = sqlx::query_as::<_, MonthFinanceSummary>(
+        r#"
+        WITH months AS (
+            SELECT generate_series(1, 12) AS month
+        ),
+        planned AS (
+            SELECT m.month,
+                   SUM(COALESCE(l.monthly_amounts[m.month], ROUND(l.planned_amount / 12, 2))) AS planned
+            FROM months m
+            CROSS JOIN budget_lines l
+            WHERE l.budget_id = $3
+            GROUP BY m.month
+        ),
+        spent AS (
+            SELECT EXTRACT(MONTH FROM expense_date)::int AS month, SUM(amount) AS spent
+            FROM osi_expenses
+            WHERE osi_id = $1
+              AND status IN ('approved', 'executed')
+              AND expense_date >= make_date($4, 1, 1)
+              AND expense_date < make_date($4 + 1, 1, 1)
+            GROUP BY 1
+        ),
+        collected AS (
+            SELECT EXTRACT(MONTH FROM p.completed_at AT TIME ZONE 'Asia/Almaty')::int AS month,
+                   SUM(p.amount) AS collected
+            FROM payments p
+            JOIN apartments a ON a.id = p.apartment_id
+            WHERE a.complex_id = $2
+              AND p.status = 'completed'
+              AND p.completed_at >= make_date($4, 1, 1)::timestamp AT TIME ZONE 'Asia/Almaty'
+              AND p.completed_at < make_date($4 + 1, 1, 1)::timestamp AT TIME ZONE 'Asia/Almaty'
+            GROUP BY 1
+        )
+        SELECT m.month,
+               COALESCE(c.collected, 0) AS collected,
+               COALESCE(s.spent, 0) AS spent,
+               COALESCE(pl.planned, 0) AS planned
+        FROM months m
+        LEFT JOIN collected c ON c.month = m.month
+        LEFT JOIN spent s ON s.month = m.month
+        LEFT JOIN planned pl ON pl.month = m.month
+        ORDER BY m.month
+        "#,
+    )
+    .bind(osi.id)
+    .bind(osi.complex_id)
+    .bind(budget_id)
+    .bind(year)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let categories = sqlx::query_as::<_, CategoryFinanceSummary>(
+        r#"
+        WITH planned AS (
+            SELECT category_id,
+                   CASE WHEN $5::int IS NULL THEN planned_amount
+                        ELSE COALESCE(monthly_amounts[$5], ROUND(planned_amount / 12, 2))
+                   END AS planned
+            FROM budget_lines
+            WHERE budget_id = $2
+        ),
+        spent AS (
+            SELECT category_id, SUM(amount) AS spent, COUNT(*) AS expenses_count
+            FROM osi_expenses
+            WHERE osi_id = $1
+              AND status IN ('approved', 'executed')
+              AND expense_date >= $3
+              AND expense_date < $4
+            GROUP BY category_id
+        )
+        SELECT c.id AS category_id, c.name AS category_name,
+               COALESCE(p.planned, 0) AS planned,
+               COALESCE(s.spent, 0) AS spent,
+               COALESCE(s.expenses_count, 0) AS expenses_count
+        FROM planned p
+        FULL JOIN spent s ON s.category_id = p.category_id
+        JOIN expense_categories c ON c.id = COALESCE(p.category_id, s.category_id)
+        ORDER BY c.sort_order, c.name
+        "#,
+    )
+    .bind(osi.id)
+    .bind(budget_id)
+    .bind(period_start)
+    .bind(period_end)
+    .bind(query.month.map(|month| month as i32))
+    .fetch_all(&state.pool)
+    .await?;
+
+    let in_period = |month: &&MonthFinanceSummary| {
+        query.month.is_none_or(|selected| month.month == selected as i32)
+    };
+    let collected = months.iter().filter(in_period).map(|m| m.collected).sum();
+    let spent = categories.iter().map(|c| c.spent).sum();
+    let planned = categories.iter().map(|c| c.planned).sum();
+
+    Ok(Json(FinanceSummary {
+        year,
+        month: query.month,
+        period_start,
+        period_end: period_end.pred_opt().unwrap_or(period_end),
+        budget_id,
+        collected,
+        spent,
+        planned,
+        categories,
+        months,
+    }))
+}
+
+/// Ежемесячный отчёт для жителей: доходы и расходы, должники, собираемость (JSON или PDF)
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/reports/monthly",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        MonthlyReportQuery
+    ),
+    responses(
+        (status = 200, description = "Отчёт; с format=pdf — PDF-файл", body = MonthlyReport),
+        (status = 400, description = "Неверный период"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет квартиры в ЖК")
+    )
+)]
+pub async fn get_monthly_report(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<MonthlyReportQuery>,
+) -> AppResult<Response> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let period_start = NaiveDate::parse_from_str(&format!("{}-01", query.period.trim()), "%Y-%m-%d")
+        .map_err(|_| AppError::BadRequest("Период указывается как ГГГГ-ММ".to_string()))?;
+    let next_period = next_month_start(period_start);
+    let period_end = next_period.pred_opt().unwrap_or(period_start);
+
+    // Долги считаются на конец месяца, для текущего месяца — на сегодня
+    let today = chrono::Utc::now().date_naive();
+    let debt_date = period_end.min(today);
+
+    let (billed, billed_paid): (Decimal, Decimal) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(total_amount), 0),
+               COALESCE(SUM(CASE WHEN status = 'paid' THEN total_amount
+                                 ELSE COALESCE(paid_amount, 0) END), 0)
+        FROM bills
+        WHERE complex_id = $1
+          AND status <> 'cancelled'
+          AND period_start >= $2 AND period_start < $3
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(period_start)
+    .bind(next_period)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let (collected,): (Decimal,) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(p.amount), 0)
+        FROM payments p
+        JOIN apartments a ON a.id = p.apartment_id
+        WHERE a.complex_id = $1
+          AND p.status = 'completed'
+          AND p.completed_at >= $2::timestamp AT TIME ZONE 'Asia/Almaty'
+          AND p.completed_at < $3::timestamp AT TIME ZONE 'Asia/Almaty'
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(period_start)
+    .bind(next_period)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let (debtors_count, total_debt): (i64, Decimal) = sqlx::query_as(
+        r#"
+        SELECT COUNT(DISTINCT apartment_id),
+               COALESCE(SUM(total_amount - COALESCE(paid_amount, 0)), 0)
+        FROM bills
+        WHERE complex_id = $1
+          AND status IN ('pending', 'overdue')
+          AND due_date < $2
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(debt_date)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let expenses_by_category = sqlx::query_as::<_, ReportExpenseCategory>(
+        r#"
+        SELECT c.id AS category_id, c.name AS category_name,
+               SUM(e.amount) AS amount, COUNT(*) AS expenses_count
+        FROM osi_expenses e
+        JOIN expense_categories c ON c.id = e.category_id
+        WHERE e.osi_id = $1
+          AND e.status IN ('approved', 'executed')
+          AND e.expense_date >= $2 AND e.expense_date < $3
+        GROUP BY c.id, c.name, c.sort_order
+        ORDER BY c.sort_order, c.name
+        "#,
+    )
+    .bind(osi.id)
+    .bind(period_start)
+    .bind(next_period)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let (sms_spent,): (Decimal,) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(cost), 0) FROM sms_messages
+        WHERE complex_id = $1 AND status = 'sent'
+          AND created_at >= $2::date AND created_at < $3::date
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(period_start)
+    .bind(next_period)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let maintenance = sqlx::query_as::<_, ReportMaintenanceCosts>(
+        r#"
+        SELECT COUNT(DISTINCT r.id) AS requests_count,
+               COALESCE(SUM(i.amount) FILTER (WHERE i.kind = 'material'), 0) AS materials,
+               COALESCE(SUM(i.amount) FILTER (WHERE i.kind = 'labor'), 0) AS labor,
+               COALESCE(SUM(i.quantity) FILTER (WHERE i.kind = 'labor'), 0) AS labor_hours,
+               COALESCE(SUM(i.amount) FILTER (WHERE i.kind = 'other'), 0) AS other,
+               COALESCE(SUM(i.amount), 0) AS total
+        FROM maintenance_requests r
+        JOIN maintenance_cost_items i ON i.request_id = r.id
+        WHERE r.complex_id = $1
+          AND r.status = 'completed'
+          AND r.completed_at >= $2::timestamp AT TIME ZONE 'Asia/Almaty'
+          AND r.completed_at < $3::timestamp AT TIME ZONE 'Asia/Almaty'
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(period_start)
+    .bind(next_period)
+    .fetch_one(&state.pool)
+    .await?;
+
+    let expenses: Decimal = expenses_by_category.iter().map(|c| c.amount).sum();
+    let collection_rate = (billed > Decimal::ZERO)
+        .then(|| (billed_paid * Decimal::from(100) / billed).round_dp(1));
+
+    let report = MonthlyReport {
+        osi_id: osi.id,
+        osi_name: osi.name.clone(),
+        period: period_start.format("%Y-%m").to_string(),
+        period_start,
+        period_end,
+        billed,
+        billed_paid,
+        collection_rate,
+        collected,
+        debtors_count,
+        total_debt,
+        expenses,
+        expenses_by_category,
+        sms_spent,
+        maintenance,
+        balance: collected - expenses - sms_spent,
+        generated_at: chrono::Utc::now(),
+    };
+
+    if query.format.as_deref() != Some("pdf") {
+        return Ok(Json(report).into_response());
+    }
+
+    let data = DocumentService::render_monthly_report(&report)?;
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"report-{}.pdf\"", report.period),
+        ),
+    ];
+
+    Ok((headers, data).into_response())
+}
+
+/// Список расходов ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/expenses",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ExpensesQuery
+    ),
+    responses(
+        (status = 200, description = "Список расходов", body = Vec<ExpenseResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn list_expenses(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<ExpensesQuery>,
+) -> AppResult<Json<Vec<ExpenseResponse>>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let expenses = sqlx::query_as::<_, OsiExpense>(
+        r#"
+        SELECT * FROM osi_expenses
+        WHERE osi_id = $1
+          AND ($2::expense_status IS NULL OR status = $2)
+          AND ($3::uuid IS NULL OR category_id = $3)
+          AND ($4::date IS NULL OR expense_date >= $4)
+          AND ($5::date IS NULL OR expense_date <= $5)
+          AND (status <> 'pending_confirmation' OR $8)
+        ORDER BY expense_date DESC, created_at DESC
+        LIMIT $6 OFFSET $7
+        "#,
+    )
+    .bind(osi.id)
+    .bind(&query.status)
+    .bind(query.category_id)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(limit)
+    .bind(offset)
+    .bind(
+        PermissionService::has(&state.pool, &auth_user, osi.complex_id, Permission::ManageFinance)
+            .await?,
+    )
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut response = Vec::new();
+    for expense in expenses {
+        response.push(build_expense_response(&state, expense).await?);
+    }
+
+    Ok(Json(response))
+}
+
+/// Получить расход с историей согласования
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/expenses/{expense_id}",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("expense_id" = Uuid, Path, description = "ID расхода")
+    ),
+    responses(
+        (status = 200, description = "Расход", body = ExpenseResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn get_expense(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, expense_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<ExpenseResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let expense = get_expense_for_osi(&state, osi.id, expense_id).await?;
+
+    // Неподтверждённые черновики из счетов видит только председатель
+    if expense.status == ExpenseStatus::PendingConfirmation {
+        check_permission(&state, &osi, &auth_user, Permission::ManageFinance)
+            .await
+            .map_err(|_| AppError::NotFound("Расход не найден".to_string()))?;
+    }
+
+    let response = build_expense_response(&state, expense).await?;
+
+    Ok(Json(response))
+}
+
+/// Создать расход (сумма выше порога уходит на согласование совету)
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expenses",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = CreateExpenseRequest,
+    responses(
+        (status = 200, description = "Расход создан", body = ExpenseResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn create_expense(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<CreateExpenseRequest>,
+) -> AppResult<Json<ExpenseResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    if payload.amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Сумма должна быть больше нуля".to_string()));
+    }
+
+    check_category(&state, osi.id, payload.category_id).await?;
+    let plan = plan_approval(&state, &osi, payload.amount, auth_user.user_id).await?;
+
+    let expense = sqlx::query_as::<_, OsiExpense>(
+        r#"
+        INSERT INTO osi_expenses (
+            osi_id, category_id, title, description, amount, vendor,
+            expense_date, receipt_url, status, required_approvals, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, CURRENT_DATE), $8, $9, $10, $11)
+        RETURNING *
+        "#,
+    )
+    .bind(osi.id)
+    .bind(payload.category_id)
+    .bind(&payload.title)
+    .bind(&payload.description)
+    .bind(payload.amount)
+    .bind(&payload.vendor)
+    .bind(payload.expense_date)
+    .bind(&payload.receipt_url)
+    .bind(&plan.status)
+    .bind(plan.required_approvals)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    announce_expense(&state, &osi, auth_user.user_id, &expense, &plan.council_ids).await?;
+
+    let response = build_expense_response(&state, expense).await?;
+    Ok(Json(response))
+}
+
+/// Загрузить фото счёта поставщика: распознать и создать черновик расхода
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expenses/intake",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body(content = String, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Черновик расхода создан", body = InvoiceIntakeResponse),
+        (status = 400, description = "Неверный формат файла"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn intake_invoice(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> AppResult<Json<InvoiceIntakeResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .ok_or_else(|| AppError::BadRequest("Content-Type отсутствует".to_string()))?
+            .to_string();
+
+        if !validate_image_content_type(&content_type) {
+            return Err(AppError::BadRequest(
+                "Недопустимый формат изображения".to_string(),
+            ));
+        }
+
+        let file_name = field.file_name().unwrap_or("invoice.jpg").to_string();
+        let data = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+        if data.len() > MAX_IMAGE_SIZE {
+            return Err(AppError::BadRequest("Файл слишком большой".to_string()));
+        }
+
+        upload = Some((file_name, content_type, data));
+        break;
+    }
+
+    let (file_name, content_type, data) =
+        upload.ok_or_else(|| AppError::BadRequest("Файл не найден".to_string()))?;
+
+    // Ошибка распознавания не мешает сохранить счёт — поля заполнятся вручную
+    let provider = OcrService::provider(&state.config);
+    let text = match provider.recognize(&data, &content_type).await {
+        Ok(text) => text,
+        Err(e) => {
+            tracing::warn!("Invoice OCR via {} failed: {}", provider.name(), e);
+            String::new()
+        }
+    };
+
+    let fields = parse_invoice(&text);
+    let suggested_category_id =
+        suggest_category(&state, osi.id, fields.vendor.as_deref(), &text).await?;
+
+    let file_service = FileService::new(&state.config)
+        .await?
+        .with_upload_queue(&state.pool);
+    let receipt_url = file_service
+        .upload_file("invoices", &file_name, &content_type, data.to_vec())
+        .await?;
+
+    let recognized = RecognizedInvoice {
+        amount: fields.amount,
+        expense_date: fields.date,
+        vendor: fields.vendor,
+        suggested_category_id,
+        text,
+    };
+
+    let title = match &recognized.vendor {
+        Some(vendor) => format!("Счёт {}", vendor),
+        None => "Счёт поставщика".to_string(),
+    };
+
+    let expense = sqlx::query_as::<_, OsiExpense>(
+        r#"
+        INSERT INTO osi_expenses (
+            osi_id, category_id, title, amount, vendor, expense_date,
+            receipt_url, status, required_approvals, created_by, ocr_data
+        )
+        VALUES ($1, $2, $3, $4, $5, COALESCE($6, CURRENT_DATE), $7, 'pending_confirmation', 0, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(osi.id)
+    .bind(suggested_category_id)
+    .bind(&title)
+    .bind(recognized.amount.unwrap_or(Decimal::ZERO))
+    .bind(&recognized.vendor)
+    .bind(recognized.expense_date)
+    .bind(&receipt_url)
+    .bind(auth_user.user_id)
+    .bind(json!({
+        "provider": provider.name(),
+        "amount": recognized.amount,
+        "expense_date": recognized.expense_date,
+        "vendor": recognized.vendor,
+        "suggested_category_id": suggested_category_id
+    }))
+    .fetch_one(&state.pool)
+    .await?;
+
+    let expense = build_expense_response(&state, expense).await?;
+
+    Ok(Json(InvoiceIntakeResponse {
+        expense,
+        recognized,
+    }))
+}
+
+/// Подтвердить расход, созданный из счёта
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expenses/{expense_id}/confirm",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("expense_id" = Uuid, Path, description = "ID расхода")
+    ),
+    request_body = ConfirmExpenseRequest,
+    responses(
+        (status = 200, description = "Расход подтверждён", body = ExpenseResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn confirm_expense(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, expense_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ConfirmExpenseRequest>,
+) -> AppResult<Json<ExpenseResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let expense = get_expense_for_osi(&state, osi.id, expense_id).await?;
+    if expense.status != ExpenseStatus::PendingConfirmation {
+        return Err(AppError::BadRequest("Расход уже подтверждён".to_string()));
+    }
+
+    let amount = payload.amount.unwrap_or(expense.amount);
+    if amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Сумма должна быть больше нуля".to_string()));
+    }
+
+    let category_id = payload.category_id.unwrap_or(expense.category_id);
+    check_category(&state, osi.id, category_id).await?;
+
+    let plan = plan_approval(&state, &osi, amount, expense.created_by).await?;
+
+    let expense = sqlx::query_as::<_, OsiExpense>(
+        r#"
+        UPDATE osi_expenses SET
+            category_id = $3,
+            title = COALESCE($4, title),
+            description = $5,
+            amount = $6,
+            vendor = $7,
+            expense_date = COALESCE($8, expense_date),
+            status = $9,
+            required_approvals = $10,
+            updated_at = NOW()
+        WHERE id = $1 AND osi_id = $2 AND status = 'pending_confirmation'
+        RETURNING *
+        "#,
+    )
+    .bind(expense.id)
+    .bind(osi.id)
+    .bind(category_id)
+    .bind(&payload.title)
+    .bind(payload.description.apply(expense.description.clone()))
+    .bind(amount)
+    .bind(payload.vendor.apply(expense.vendor.clone()))
+    .bind(payload.expense_date)
+    .bind(&plan.status)
+    .bind(plan.required_approvals)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Расход уже подтверждён".to_string()))?;
+
+    announce_expense(&state, &osi, auth_user.user_id, &expense, &plan.council_ids).await?;
+
+    let response = build_expense_response(&state, expense).await?;
+    Ok(Json(response))
+}
+
+/// Согласовать или отклонить расход (член совета)
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expenses/{expense_id}/decision",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("expense_id" = Uuid, Path, description = "ID расхода")
+    ),
+    request_body = ExpenseDecisionRequest,
+    responses(
+        (status = 200, description = "Решение принято", body = ExpenseResponse),
+        (status = 400, description = "Расход не ожидает согласования"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Только для членов совета"),
+        (status = 409, description = "Голос уже учтён")
+    )
+)]
+pub async fn decide_expense(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, expense_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ExpenseDecisionRequest>,
+) -> AppResult<Json<ExpenseResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    let expense = get_expense_for_osi(&state, osi.id, expense_id).await?;
+
+    if expense.status != ExpenseStatus::PendingApproval {
+        return Err(AppError::BadRequest("Расход не ожидает согласования".to_string()));
+    }
+
+    // Автор расхода не может согласовывать его сам
+    let council_ids = active_council_ids(&state, osi.id).await?;
+    if !council_ids.contains(&auth_user.user_id) || expense.created_by == auth_user.user_id {
+        return Err(AppError::Forbidden);
+    }
+
+    let inserted = sqlx::query(
+        r#"
+        INSERT INTO expense_approvals (expense_id, user_id, decision, comment)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (expense_id, user_id) DO NOTHING
+        "#,
+    )
+    .bind(expense.id)
+    .bind(auth_user.user_id)
+    .bind(payload.decision)
+    .bind(&payload.comment)
+    .execute(&state.pool)
+    .await?;
+
+    if inserted.rows_affected() == 0 {
+        return Err(AppError::Conflict("Вы уже проголосовали по этому расходу".to_string()));
+    }
+
+    let (approvals, rejections): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COUNT(*) FILTER (WHERE decision = 'approve'),
+            COUNT(*) FILTER (WHERE decision = 'reject')
+        FROM expense_approvals WHERE expense_id = $1
+        "#,
+    )
+    .bind(expense.id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    // Отклоняем, если набрать нужное число согласований уже невозможно
+    let eligible = council_ids
+        .iter()
+        .filter(|id| **id != expense.created_by)
+        .count() as i64;
+
+    let outcome = if approvals >= expense.required_approvals as i64 {
+        Some((ExpenseStatus::Approved, DomainEventType::ExpenseApproved, "Расход согласован"))
+    } else if eligible - rejections < expense.required_approvals as i64 {
+        Some((ExpenseStatus::Rejected, DomainEventType::ExpenseRejected, "Расход отклонён советом"))
+    } else {
+        None
+    };
+
+    let expense = if let Some((status, event_type, title)) = outcome {
+        let updated = sqlx::query_as::<_, OsiExpense>(
+            r#"
+            UPDATE osi_expenses SET status = $2, updated_at = NOW()
+            WHERE id = $1 AND status = 'pending_approval'
+            RETURNING *
+            "#,
+        )
+        .bind(expense.id)
+        .bind(&status)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        match updated {
+            Some(updated) => {
+                record_expense_event(&state, &osi, auth_user.user_id, event_type, &updated).await?;
+
+                NotificationService::notify_users(
+                    &state.pool,
+                    &[updated.created_by],
+                    NotificationType::Finance,
+                    title,
+                    Some(&format!("{} — {} ₸", updated.title, updated.amount)),
+                    Some(json!({"osi_id": osi.id, "expense_id": updated.id})),
+                )
+                .await?;
+
+                updated
+            }
+            None => get_expense_for_osi(&state, osi.id, expense.id).await?,
+        }
+    } else {
+        expense
+    };
+
+    let response = build_expense_response(&state, expense).await?;
+    Ok(Json(response))
+}
+
+/// Отметить расход исполненным
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/expenses/{expense_id}/execute",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("expense_id" = Uuid, Path, description = "ID расхода")
+    ),
+    responses(
+        (status = 200, description = "Расход исполнен", body = ExpenseResponse),
+        (status = 400, description = "Расход не согласован"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn execute_expense(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((osi_id, expense_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<ExpenseResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let mut tx = state.pool.begin().await?;
+    let expense = sqlx::query_as::<_, OsiExpense>(
+        r#"
+        UPDATE osi_expenses SET status = 'executed', executed_at = NOW(), updated_at = NOW()
+        WHERE id = $1 AND osi_id = $2 AND status = 'approved'
+        RETURNING *
+        "#,
+    )
+    .bind(expense_id)
+    .bind(osi.id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Исполнить можно только согласованный расход".to_string()))?;
+
+    FinancialAuditService::record(
+        &mut tx,
+        &FinancialContext {
+            actor_id: Some(auth_user.user_id),
+            request_id: Some(request_id.0),
+        },
+        NewFinancialAuditEntry {
+            event: FinancialEvent::ExpenseExecuted,
+            complex_id: Some(osi.complex_id),
+            entity_type: "expense",
+            entity_id: expense.id,
+            amount: Some(expense.amount),
+            provider: None,
+            provider_reference: None,
+            details: Some(json!({
+                "osi_id": osi.id,
+                "category_id": expense.category_id,
+                "title": expense.title,
+                "vendor": expense.vendor,
+            })),
+        },
+    )
+    .await?;
+    tx.commit().await?;
+
+    record_expense_event(&state, &osi, auth_user.user_id, DomainEventType::ExpenseExecuted, &expense)
+        .await?;
+
+    let response = build_expense_response(&state, expense).await?;
+    Ok(Json(response))
+}
+
+/// Распределить общий расход по квартирам
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/shared-charges",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = CreateSharedChargeRequest,
+    responses(
+        (status = 200, description = "Начисление распределено", body = SharedChargeResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn create_shared_charge(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<CreateSharedChargeRequest>,
+) -> AppResult<Json<SharedChargeResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageBills).await?;
+
+    if payload.total_amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Сумма должна быть больше нуля".to_string()));
+    }
+
+    if let Some(expense_id) = payload.expense_id {
+        get_expense_for_osi(&state, osi.id, expense_id).await?;
+    }
+
+    let apartments: Vec<(Uuid, Option<Decimal>)> = sqlx::query_as(
+        r#"
+        SELECT id, area FROM apartments
+        WHERE complex_id = $1 AND ($2::varchar IS NULL OR building = $2)
+        ORDER BY building, number
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(&payload.building)
+    .fetch_all(&state.pool)
+    .await?;
+
+    if apartments.is_empty() {
+        return Err(AppError::BadRequest("Нет квартир для распределения".to_string()));
+    }
+
+    let bases: Vec<Decimal> = match payload.rule {
+        AllocationRule::ByArea => {
+            let missing = apartments
+                .iter()
+                .filter(|(_, area)| !area.is_some_and(|a| a > Decimal::ZERO))
+                .count();
+            if missing > 0 {
+                return Err(AppError::BadRequest(format!(
+                    "Не указана площадь у {} квартир",
+                    missing
+                )));
+            }
+            apartments.iter().map(|(_, area)| area.unwrap_or_default()).collect()
+        }
+        AllocationRule::PerUnit => vec![Decimal::ONE; apartments.len()],
+    };
+
+    let amounts = split_amount(payload.total_amount, &bases);
+    let total_basis: Decimal = bases.iter().sum();
+    let billing_period = next_month_start(chrono::Utc::now().date_naive());
+
+    let mut tx = state.pool.begin().await?;
+
+    let charge = sqlx::query_as::<_, SharedCharge>(
+        r#"
+        INSERT INTO shared_charges (
+            osi_id, expense_id, title, description, total_amount, utility_type,
+            rule, building, total_basis, billing_period, created_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+        RETURNING *
+        "#,
+    )
+    .bind(osi.id)
+    .bind(payload.expense_id)
+    .bind(&payload.title)
+    .bind(&payload.description)
+    .bind(payload.total_amount)
+    .bind(payload.utility_type.unwrap_or(UtilityType::Other))
+    .bind(payload.rule)
+    .bind(&payload.building)
+    .bind(total_basis)
+    .bind(billing_period)
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (((apartment_id, _), basis), amount) in apartments.iter().zip(&bases).zip(&amounts) {
+        sqlx::query(
+            "INSERT INTO shared_charge_allocations (charge_id, apartment_id, basis, amount) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(charge.id)
+        .bind(apartment_id)
+        .bind(basis)
+        .bind(amount)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    // Доли попадут в счета, когда начнётся расчётный период
+    let run_at = billing_period
+        .and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc())
+        .unwrap_or_else(chrono::Utc::now);
+    JobService::enqueue_at(
+        &state.pool,
+        JobType::SharedChargeBilling,
+        &SharedChargeBillingPayload {
+            charge_id: charge.id,
+        },
+        run_at,
+    )
+    .await?;
+
+    let response = build_shared_charge_response(&state, charge).await?;
+    Ok(Json(response))
+}
+
+/// Общие начисления ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/shared-charges",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    responses(
+        (status = 200, description = "Общие начисления", body = Vec<SharedCharge>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn list_shared_charges(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+) -> AppResult<Json<Vec<SharedCharge>>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let charges = sqlx::query_as::<_, SharedCharge>(
+        "SELECT * FROM shared_charges WHERE osi_id = $1 ORDER BY created_at DESC",
+    )
+    .bind(osi.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(charges))
+}
+
+/// Общее начисление с расчётом по каждой квартире
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/shared-charges/{charge_id}",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("charge_id" = Uuid, Path, description = "ID начисления")
+    ),
+    responses(
+        (status = 200, description = "Начисление", body = SharedChargeResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn get_shared_charge(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, charge_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<SharedChargeResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let charge = get_shared_charge_for_osi(&state, osi.id, charge_id).await?;
+    let response = build_shared_charge_response(&state, charge).await?;
+
+    Ok(Json(response))
+}
+
+/// Отменить начисление до выставления в счета
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/shared-charges/{charge_id}/cancel",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("charge_id" = Uuid, Path, description = "ID начисления")
+    ),
+    responses(
+        (status = 200, description = "Начисление отменено", body = SharedCharge),
+        (status = 400, description = "Начисление уже выставлено"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn cancel_shared_charge(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, charge_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<SharedCharge>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageBills).await?;
+
+    let charge = sqlx::query_as::<_, SharedCharge>(
+        r#"
+        UPDATE shared_charges SET status = 'cancelled'
+        WHERE id = $1 AND osi_id = $2 AND status = 'scheduled'
+        RETURNING *
+        "#,
+    )
+    .bind(charge_id)
+    .bind(osi.id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::BadRequest("Отменить можно только невыставленное начисление".to_string()))?;
+
+    Ok(Json(charge))
+}
+
+/// Составить годовой бюджет
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/budgets",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = CreateBudgetRequest,
+    responses(
+        (status = 200, description = "Черновик бюджета создан", body = BudgetResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 409, description = "Бюджет на этот год уже есть")
+    )
+)]
+pub async fn create_budget(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<CreateBudgetRequest>,
+) -> AppResult<Json<BudgetResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let existing: Option<(Uuid,)> = sqlx::query_as(
+        "SELECT id FROM osi_budgets WHERE osi_id = $1 AND year = $2 AND status <> 'rejected'",
+    )
+    .bind(osi.id)
+    .bind(payload.year)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if existing.is_some() {
+        return Err(AppError::Conflict("Бюджет на этот год уже составлен".to_string()));
+    }
+
+    let title = payload
+        .title
+        .clone()
+        .unwrap_or_else(|| format!("Бюджет ОСИ на {} год", payload.year));
+
+    let mut tx = state.pool.begin().await?;
+
+    let budget = sqlx::query_as::<_, OsiBudget>(
+        "INSERT INTO osi_budgets (osi_id, year, title, created_by) VALUES ($1, $2, $3, $4) RETURNING *",
+    )
+    .bind(osi.id)
+    .bind(payload.year)
+    .bind(&title)
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    save_budget_lines(&mut tx, osi.id, budget.id, &payload.lines).await?;
+
+    tx.commit().await?;
+
+    let response = build_budget_response(&state, budget).await?;
+    Ok(Json(response))
+}
+
+/// Бюджеты ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/budgets",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    responses(
+        (status = 200, description = "Бюджеты", body = Vec<OsiBudget>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn list_budgets(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+) -> AppResult<Json<Vec<OsiBudget>>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let budgets = sqlx::query_as::<_, OsiBudget>(
+        "SELECT * FROM osi_budgets WHERE osi_id = $1 ORDER BY year DESC, created_at DESC",
+    )
+    .bind(osi.id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(budgets))
+}
+
+/// Бюджет с исполнением по статьям (план/факт)
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/budgets/{budget_id}",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("budget_id" = Uuid, Path, description = "ID бюджета")
+    ),
+    responses(
+        (status = 200, description = "Бюджет", body = BudgetResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn get_budget(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, budget_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<BudgetResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    let budget = get_budget_for_osi(&state, osi.id, budget_id).await?;
+    let response = build_budget_response(&state, budget).await?;
+
+    Ok(Json(response))
+}
+
+/// Изменить статьи черновика бюджета
+#[utoipa::path(
+    put,
+    path = "/api/v1/osi/{id}/budgets/{budget_id}/lines",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("budget_id" = Uuid, Path, description = "ID бюджета")
+    ),
+    request_body = UpdateBudgetLinesRequest,
+    responses(
+        (status = 200, description = "Статьи обновлены", body = BudgetResponse),
+        (status = 400, description = "Бюджет уже вынесен на голосование"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn update_budget_lines(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, budget_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateBudgetLinesRequest>,
+) -> AppResult<Json<BudgetResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let budget = get_budget_for_osi(&state, osi.id, budget_id).await?;
+    if budget.status != BudgetStatus::Draft {
+        return Err(AppError::BadRequest("Изменять можно только черновик бюджета".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+    save_budget_lines(&mut tx, osi.id, budget.id, &payload.lines).await?;
+    sqlx::query("UPDATE osi_budgets SET updated_at = NOW() WHERE id = $1")
+        .bind(budget.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    let response = build_budget_response(&state, budget).await?;
+    Ok(Json(response))
+}
+
+/// Вынести бюджет на голосование собственников
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/budgets/{budget_id}/submit",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        ("budget_id" = Uuid, Path, description = "ID бюджета")
+    ),
+    request_body = SubmitBudgetRequest,
+    responses(
+        (status = 200, description = "Голосование запущено", body = BudgetResponse),
+        (status = 400, description = "Бюджет уже вынесен на голосование"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn submit_budget(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((osi_id, budget_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<SubmitBudgetRequest>,
+) -> AppResult<Json<BudgetResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let budget = get_budget_for_osi(&state, osi.id, budget_id).await?;
+    if budget.status != BudgetStatus::Draft {
+        return Err(AppError::BadRequest("Бюджет уже вынесен на голосование".to_string()));
+    }
+
+    let starts_at = payload.starts_at.unwrap_or_else(chrono::Utc::now);
+    if payload.ends_at <= starts_at {
+        return Err(AppError::BadRequest(
+            "Дата окончания должна быть позже начала".to_string(),
+        ));
+    }
+
+    let summary = build_budget_response(&state, budget.clone()).await?;
+    if summary.lines.iter().all(|line| line.planned_amount == Decimal::ZERO) {
+        return Err(AppError::BadRequest("В бюджете нет статей".to_string()));
+    }
+
+    let description = summary
+        .lines
+        .iter()
+        .filter(|line| line.planned_amount > Decimal::ZERO)
+        .map(|line| format!("{}: {} ₸", line.category_name, line.planned_amount))
+        .chain(std::iter::once(format!("Итого: {} ₸", summary.total_planned)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut tx = state.pool.begin().await?;
+
+    let (voting_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO votings (
+            complex_id, osi_id, title, description, voting_type, status,
+            requires_owner, starts_at, ends_at, created_by
+        )
+        VALUES ($1, $2, $3, $4, 'yes_no', 'active', true, $5, $6, $7)
+        RETURNING id
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(osi.id)
+    .bind(format!("Утверждение: {}", budget.title))
+    .bind(&description)
+    .bind(starts_at)
+    .bind(payload.ends_at)
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    for (i, option_text) in BUDGET_VOTING_OPTIONS.iter().enumerate() {
+        sqlx::query("INSERT INTO voting_options (voting_id, text, sort_order) VALUES ($1, $2, $3)")
+            .bind(voting_id)
+            .bind(option_text)
+            .bind(i as i32)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let budget = sqlx::query_as::<_, OsiBudget>(
+        r#"
+        UPDATE osi_budgets SET status = 'voting', voting_id = $2, updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(budget.id)
+    .bind(voting_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    JobService::enqueue(
+        &state.pool,
+        JobType::NotificationFanout,
+        &NotificationFanoutPayload {
+            complex_id: osi.complex_id,
+            notification_type: NotificationType::Voting,
+            title: format!("Голосование: {}", budget.title),
+            body: None,
+            data: Some(json!({"voting_id": voting_id, "budget_id": budget.id})),
+            exclude_user_id: None,
+        },
+    )
+    .await?;
+
+    let response = build_budget_response(&state, budget).await?;
+    Ok(Json(response))
+}
+
+/// Зарегистрировать оплату наличными
+#[utoipa::path(
+    post,
+    path = "/api/v1/osi/{id}/cash-payments",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = RegisterCashPaymentRequest,
+    responses(
+        (status = 200, description = "Платёж принят, квитанция сформирована", body = CashReceiptResponse),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Квартира или счёт не найдены")
+    )
+)]
+pub async fn register_cash_payment(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<RegisterCashPaymentRequest>,
+) -> AppResult<Json<CashReceiptResponse>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    if payload.amount <= Decimal::ZERO {
+        return Err(AppError::BadRequest("Сумма должна быть больше нуля".to_string()));
+    }
+
+    let (apartment_id, owner_id, resident_id): (Uuid, Option<Uuid>, Option<Uuid>) =
+        sqlx::query_as(
+            "SELECT id, owner_id, resident_id FROM apartments WHERE id = $1 AND complex_id = $2",
+        )
+        .bind(payload.apartment_id)
+        .bind(osi.complex_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+    if let Some(bill_id) = payload.bill_id {
+        let bill = sqlx::query_as::<_, Bill>("SELECT * FROM bills WHERE id = $1 AND apartment_id = $2")
+            .bind(bill_id)
+            .bind(apartment_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Счёт не найден".to_string()))?;
+
+        if matches!(bill.status, BillStatus::Paid | BillStatus::Cancelled) {
+            return Err(AppError::BadRequest("Счёт уже закрыт".to_string()));
+        }
+    }
+
+    // Плательщик — владелец или жилец; если квартира пустая, платёж записываем на председателя
+    let payer_id = owner_id.or(resident_id).unwrap_or(auth_user.user_id);
+
+    let mut tx = state.pool.begin().await?;
+
+    let receipt_number = PaymentService::next_receipt_number(&mut tx, osi.id).await?;
+
+    let payment = sqlx::query_as::<_, Payment>(
+        r#"
+        INSERT INTO payments (
+            bill_id, apartment_id, user_id, amount, method, status, completed_at,
+            osi_id, received_by, receipt_number, verification_token, comment
+        )
+        VALUES ($1, $2, $3, $4, 'cash', 'completed', NOW(), $5, $6, $7, $8, $9)
+        RETURNING *
+        "#,
+    )
+    .bind(payload.bill_id)
+    .bind(apartment_id)
+    .bind(payer_id)
+    .bind(payload.amount)
+    .bind(osi.id)
+    .bind(auth_user.user_id)
+    .bind(&receipt_number)
+    .bind(PaymentService::generate_verification_token())
+    .bind(&payload.comment)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let (allocations, advance) =
+        PaymentService::allocate(&mut tx, payment.id, apartment_id, payload.bill_id, payment.amount)
+            .await?;
+
+    FinancialAuditService::record(
+        &mut tx,
+        &FinancialContext {
+            actor_id: Some(auth_user.user_id),
+            request_id: Some(request_id.0),
+        },
+        NewFinancialAuditEntry {
+            event: FinancialEvent::CashPaymentRegistered,
+            complex_id: Some(osi.complex_id),
+            entity_type: "payment",
+            entity_id: payment.id,
+            amount: Some(payment.amount),
+            provider: Some(payment.method.as_str().to_string()),
+            provider_reference: payment.receipt_number.clone(),
+            details: Some(json!({
+                "osi_id": osi.id,
+                "payer_id": payer_id,
+                "allocations": allocations,
+                "advance": advance,
+            })),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    let recipients: Vec<Uuid> = [owner_id, resident_id]
+        .into_iter()
+        .flatten()
+        .filter(|id| *id != auth_user.user_id)
+        .collect();
+
+    NotificationService::notify_users(
+        &state.pool,
+        &recipients,
+        NotificationType::Finance,
+        "Оплата наличными принята",
+        Some(&format!("Квитанция № {} на сумму {} ₸", receipt_number, payment.amount)),
+        Some(json!({"payment_id": payment.id, "receipt_number": receipt_number})),
+    )
+    .await?;
+
+    let receipt = build_cash_receipt(&state, payment).await?;
+    Ok(Json(receipt))
+}
+
+/// Журнал наличных платежей ОСИ
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/cash-payments",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        CashPaymentsQuery
+    ),
+    responses(
+        (status = 200, description = "Наличные платежи", body = Vec<CashReceiptResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn list_cash_payments(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<CashPaymentsQuery>,
+) -> AppResult<Json<Vec<CashReceiptResponse>>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let limit = query.limit.unwrap_or(50).min(100);
+    let offset = query.page.unwrap_or(0) * limit;
+
+    let payments = sqlx::query_as::<_, Payment>(
+        r#"
+        SELECT * FROM payments
+        WHERE osi_id = $1 AND method = 'cash'
+          AND ($2::uuid IS NULL OR apartment_id = $2)
+          AND ($3::date IS NULL OR created_at >= $3)
+          AND ($4::date IS NULL OR created_at < $4 + 1)
+        ORDER BY created_at DESC
+        LIMIT $5 OFFSET $6
+        "#,
+    )
+    .bind(osi.id)
+    .bind(query.apartment_id)
+    .bind(query.from)
+    .bind(query.to)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let mut response = Vec::new();
+    for payment in payments {
+        response.push(build_cash_receipt(&state, payment).await?);
+    }
+
+    Ok(Json(response))
+}
+
+/// Настройки согласования расходов
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/finance-settings",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    responses(
+        (status = 200, description = "Настройки", body = FinanceSettings),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn get_finance_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+) -> AppResult<Json<FinanceSettings>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_osi_member(&state, &osi, &auth_user).await?;
+
+    Ok(Json(FinanceSettings {
+        expense_approval_threshold: osi.expense_approval_threshold,
+        expense_required_approvals: osi.expense_required_approvals,
+        sms_monthly_cap: osi.sms_monthly_cap,
+    }))
+}
+
+/// Изменить порог согласования расходов
+#[utoipa::path(
+    put,
+    path = "/api/v1/osi/{id}/finance-settings",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ")
+    ),
+    request_body = FinanceSettings,
+    responses(
+        (status = 200, description = "Настройки обновлены", body = FinanceSettings),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn update_finance_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Json(payload): Json<FinanceSettings>,
+) -> AppResult<Json<FinanceSettings>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    if payload.expense_required_approvals < 1 {
+        return Err(AppError::BadRequest(
+            "Требуется хотя бы одно согласование".to_string(),
+        ));
+    }
+
+    if payload.sms_monthly_cap.is_some_and(|cap| cap < Decimal::ZERO) {
+        return Err(AppError::BadRequest(
+            "Лимит на SMS не может быть отрицательным".to_string(),
+        ));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let updated = sqlx::query_as::<_, Osi>(
+        r#"
+        UPDATE osi SET
+            expense_approval_threshold = $2,
+            expense_required_approvals = $3,
+            sms_monthly_cap = $4,
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(osi.id)
+    .bind(payload.expense_approval_threshold)
+    .bind(payload.expense_required_approvals)
+    .bind(payload.sms_monthly_cap)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id: osi.complex_id,
+            entity_type: "osi",
+            entity_id: osi.id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!(osi),
+            new_value: json!(updated),
+            request_id: None,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(payload))
+}
+
+/// Расходы ОСИ на SMS за месяц
+#[utoipa::path(
+    get,
+    path = "/api/v1/osi/{id}/sms-usage",
+    tag = "osi",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ОСИ"),
+        SmsUsageQuery
+    ),
+    responses(
+        (status = 200, description = "Расходы на SMS", body = SmsUsageReport),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn get_sms_usage(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(osi_id): Path<Uuid>,
+    Query(query): Query<SmsUsageQuery>,
+) -> AppResult<Json<SmsUsageReport>> {
+    let osi = get_osi(&state, osi_id).await?;
+    check_permission(&state, &osi, &auth_user, Permission::ManageFinance).await?;
+
+    let day = query.month.unwrap_or_else(|| chrono::Utc::now().date_naive());
+    let month = day.with_day(1).unwrap_or(day);
+
+    let by_kind = sqlx::query_as::<_, SmsKindUsage>(
+        r#"
+        SELECT kind,
+               COUNT(*) FILTER (WHERE status = 'sent') AS sent_count,
+               COUNT(*) FILTER (WHERE status = 'suppressed') AS suppressed_count,
+               COALESCE(SUM(cost) FILTER (WHERE status = 'sent'), 0) AS cost,
+               COUNT(*) FILTER (WHERE status = 'sent' AND is_international) AS international_count,
+               COALESCE(SUM(cost) FILTER (WHERE status = 'sent' AND is_international), 0)
+                   AS international_cost
+        FROM sms_messages
+        WHERE complex_id = $1
+          AND created_at >= $2::date
+          AND created_at < ($2::date + INTERVAL '1 month')
+        GROUP BY kind
+        ORDER BY kind
+        "#,
+    )
+    .bind(osi.complex_id)
+    .bind(month)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let spent: Decimal = by_kind.iter().map(|k| k.cost).sum();
+    let sent_count = by_kind.iter().map(|k| k.sent_count).sum();
+    let suppressed_count = by_kind.iter().map(|k| k.suppressed_count).sum();
+    let international_count = by_kind.iter().map(|k| k.international_count).sum();
+    let international_cost = by_kind.iter().map(|k| k.international_cost).sum();
+
+    Ok(Json(SmsUsageReport {
+        month,
+        spent,
+        cap: osi.sms_monthly_cap,
+        remaining: osi.sms_monthly_cap.map(|cap| (cap - spent).max(Decimal::ZERO)),
+        sent_count,
+        suppressed_count,
+        international_count,
+        international_cost,
+        by_kind,
+    }))
+}
@@ -0,0 +1,353 @@
+use axum::{
+    extract::{Path, State},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{AppState, AuthUser, ComplexScope, RequestId};
+use crate::models::{
+    AssignComplexRoleRequest, ComplexPermissionsResponse, ComplexRole, GrantPermissionRequest,
+    NewAuditLog, Permission, PermissionGrant, UserRole,
+};
+use crate::services::{AuditService, PermissionService};
+
+/// Успешный ответ
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct PermissionSuccessResponse {
+    pub success: bool,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/me", get(get_my_permissions))
+        .route("/complexes/:complex_id", get(get_complex_permissions))
+        .route("/complexes/:complex_id/roles", post(assign_complex_role))
+        .route("/complexes/:complex_id/roles/:role_id", delete(revoke_complex_role))
+        .route("/complexes/:complex_id/grants", post(grant_permission))
+        .route("/complexes/:complex_id/grants/:grant_id", delete(revoke_permission))
+}
+
+async fn check_user_exists(state: &AppState, user_id: Uuid) -> AppResult<()> {
+    let exists: Option<(i32,)> = sqlx::query_as("SELECT 1 FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+    exists
+        .map(|_| ())
+        .ok_or_else(|| AppError::NotFound("Пользователь не найден".to_string()))
+}
+
+/// Мои права в выбранном ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/permissions/me",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Права пользователя в ЖК", body = Vec<Permission>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn get_my_permissions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+) -> AppResult<Json<Vec<Permission>>> {
+    let complex_id = complex.complex_id()?;
+
+    let permissions = PermissionService::permissions(&state.pool, &auth_user, complex_id).await?;
+
+    Ok(Json(permissions))
+}
+
+/// Роли и отдельные права пользователей в ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/permissions/complexes/{complex_id}",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Uuid, Path, description = "ID ЖК")
+    ),
+    responses(
+        (status = 200, description = "Роли и права в ЖК", body = ComplexPermissionsResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет права manage_permissions")
+    )
+)]
+pub async fn get_complex_permissions(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(complex_id): Path<Uuid>,
+) -> AppResult<Json<ComplexPermissionsResponse>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManagePermissions)
+        .await?;
+
+    let roles = sqlx::query_as::<_, ComplexRole>(
+        "SELECT * FROM complex_roles WHERE complex_id = $1 ORDER BY created_at",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let grants = sqlx::query_as::<_, PermissionGrant>(
+        "SELECT * FROM permission_grants WHERE complex_id = $1 ORDER BY created_at",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(ComplexPermissionsResponse { roles, grants }))
+}
+
+/// Назначить пользователю роль в ЖК
+#[utoipa::path(
+    post,
+    path = "/api/v1/permissions/complexes/{complex_id}/roles",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Uuid, Path, description = "ID ЖК")
+    ),
+    request_body = AssignComplexRoleRequest,
+    responses(
+        (status = 200, description = "Роль назначена", body = ComplexRole),
+        (status = 400, description = "Роль нельзя назначить в пределах ЖК"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет права manage_permissions"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 409, description = "Роль уже назначена")
+    )
+)]
+pub async fn assign_complex_role(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(complex_id): Path<Uuid>,
+    Json(payload): Json<AssignComplexRoleRequest>,
+) -> AppResult<Json<ComplexRole>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManagePermissions)
+        .await?;
+
+    // Председатель назначается через ОСИ, администраторы — глобально
+    if matches!(
+        payload.role,
+        UserRole::Chairman | UserRole::Admin | UserRole::SuperAdmin | UserRole::User
+    ) {
+        return Err(AppError::BadRequest(
+            "Эту роль нельзя назначить в пределах ЖК".to_string(),
+        ));
+    }
+
+    check_user_exists(&state, payload.user_id).await?;
+
+    let role = sqlx::query_as::<_, ComplexRole>(
+        r#"
+        INSERT INTO complex_roles (user_id, complex_id, role, granted_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, complex_id, role) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(payload.user_id)
+    .bind(complex_id)
+    .bind(&payload.role)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Роль уже назначена".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "assign_complex_role",
+            entity_type: "user",
+            entity_id: Some(payload.user_id),
+            old_value: None,
+            new_value: Some(json!({"role": role.role})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(role))
+}
+
+/// Снять роль пользователя в ЖК
+#[utoipa::path(
+    delete,
+    path = "/api/v1/permissions/complexes/{complex_id}/roles/{role_id}",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Uuid, Path, description = "ID ЖК"),
+        ("role_id" = Uuid, Path, description = "ID назначения роли")
+    ),
+    responses(
+        (status = 200, description = "Роль снята", body = PermissionSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет права manage_permissions"),
+        (status = 404, description = "Назначение не найдено")
+    )
+)]
+pub async fn revoke_complex_role(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((complex_id, role_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManagePermissions)
+        .await?;
+
+    let role = sqlx::query_as::<_, ComplexRole>(
+        "DELETE FROM complex_roles WHERE id = $1 AND complex_id = $2 RETURNING *",
+    )
+    .bind(role_id)
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Назначение не найдено".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "revoke_complex_role",
+            entity_type: "user",
+            entity_id: Some(role.user_id),
+            old_value: Some(json!({"role": role.role})),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Выдать пользователю отдельное право в ЖК
+#[utoipa::path(
+    post,
+    path = "/api/v1/permissions/complexes/{complex_id}/grants",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Uuid, Path, description = "ID ЖК")
+    ),
+    request_body = GrantPermissionRequest,
+    responses(
+        (status = 200, description = "Право выдано", body = PermissionGrant),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет права manage_permissions"),
+        (status = 404, description = "Пользователь не найден"),
+        (status = 409, description = "Право уже выдано")
+    )
+)]
+pub async fn grant_permission(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(complex_id): Path<Uuid>,
+    Json(payload): Json<GrantPermissionRequest>,
+) -> AppResult<Json<PermissionGrant>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManagePermissions)
+        .await?;
+
+    check_user_exists(&state, payload.user_id).await?;
+
+    let grant = sqlx::query_as::<_, PermissionGrant>(
+        r#"
+        INSERT INTO permission_grants (user_id, complex_id, permission, granted_by)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (user_id, complex_id, permission) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(payload.user_id)
+    .bind(complex_id)
+    .bind(payload.permission)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Право уже выдано".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "grant_permission",
+            entity_type: "user",
+            entity_id: Some(payload.user_id),
+            old_value: None,
+            new_value: Some(json!({"permission": grant.permission})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(grant))
+}
+
+/// Отозвать отдельное право пользователя в ЖК
+#[utoipa::path(
+    delete,
+    path = "/api/v1/permissions/complexes/{complex_id}/grants/{grant_id}",
+    tag = "permissions",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Uuid, Path, description = "ID ЖК"),
+        ("grant_id" = Uuid, Path, description = "ID выданного права")
+    ),
+    responses(
+        (status = 200, description = "Право отозвано", body = PermissionSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет права manage_permissions"),
+        (status = 404, description = "Право не найдено")
+    )
+)]
+pub async fn revoke_permission(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path((complex_id, grant_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManagePermissions)
+        .await?;
+
+    let grant = sqlx::query_as::<_, PermissionGrant>(
+        "DELETE FROM permission_grants WHERE id = $1 AND complex_id = $2 RETURNING *",
+    )
+    .bind(grant_id)
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Право не найдено".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "revoke_permission",
+            entity_type: "user",
+            entity_id: Some(grant.user_id),
+            old_value: Some(json!({"permission": grant.permission})),
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({"success": true})))
+}
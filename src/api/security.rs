@@ -1,5 +1,7 @@
 use axum::{
     extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::IntoResponse,
     routing::{delete, get, post},
     Json, Router,
 };
@@ -8,12 +10,26 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{AppState, AuthUser};
+use crate::middleware::{AppState, AuthUser, ComplexScope, GuardUser, RequestId};
 use crate::models::{
-    BarrierAccessLogResponse, BarrierEntryRequest, Camera, CameraResponse, CameraStreamResponse,
-    CreateGuestAccessRequest, GuestAccessResponse, IntercomCallResponse,
+    take_page, Cursor, CursorPage, CursorQuery,
+    Barrier, BarrierAccessLogResponse, BarrierEntryRequest, BarrierOpenLocation, Camera, CameraResponse, CameraStreamResponse,
+    BarrierDenialResponse, BarrierManualOpening, BlacklistEntry, CreateBlacklistEntryRequest,
+    CreateGuestAccessRequest, DeclineGuestAccessRequest, ExpectedGuestResponse,
+    GuardManualOpenRequest, GuardValidateCodeRequest, GuardValidateCodeResponse, GuestAccess,
+    GuestAccessResponse, GuestAccessStatus, GuestApprovalPolicy, GuestApprovalsQuery,
+    AnomalySettings, DeviceBindingSettings, Intercom, IntercomCall, IntercomCallResponse, IntercomCallStatus,
+    IntercomRingRequest, IntercomSnapshotResponse, NewAuditLog, NewFieldChange,
+    ApartmentIntercomCode, IntercomCodeRequest, IntercomCodeResponse, NotificationType,
+    Permission, ReviewSecurityEventRequest, SecurityEvent, SecurityEventsQuery, StreamTokenQuery,
+    AnprEntryRequest, AnprEntryResponse, CreateResidentVehicleRequest, ResidentVehicle,
 };
-use crate::services::{barrier_service::generate_qr_code_base64, BarrierService, SmsService};
+use crate::services::{
+    anomaly_service::BarrierPassage, barrier_driver, barrier_service::generate_qr_code_base64,
+    stream_service::hls_source, AnomalyService, AuditService, BarrierService, FieldHistoryService,
+    IntercomService, NotificationService, PermissionService, SmsService, StreamService,
+};
+use crate::utils::{geo, normalize_vehicle_number, validate_phone};
 
 /// Успешный ответ
 #[derive(serde::Serialize, utoipa::ToSchema)]
@@ -22,12 +38,70 @@ pub struct SuccessResponse {
     pub message: String,
 }
 
+/// Заголовок, в котором контроллер шлагбаума передаёт свой API-ключ
+pub const BARRIER_KEY_HEADER: &str = "x-barrier-key";
+
+/// Заголовок, в котором устройство домофона передаёт свой токен
+pub const INTERCOM_TOKEN_HEADER: &str = "x-intercom-token";
+
 /// Запрос на открытие домофона
 #[derive(serde::Deserialize, utoipa::ToSchema)]
 pub struct OpenIntercomRequest {
     pub intercom_id: Option<Uuid>,
 }
 
+/// Запрос на открытие шлагбаума
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+pub struct OpenBarrierRequest {
+    pub barrier_id: Option<Uuid>,
+    /// Автомобиль, на котором въезжает житель; без него — единственный зарегистрированный
+    pub vehicle_number: Option<String>,
+    /// Где находится житель, по данным телефона; передаются вместе
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+/// Координаты жителя относительно ЖК при открытии шлагбаума
+struct OpenLocation {
+    latitude: f64,
+    longitude: f64,
+    distance_m: Option<i32>,
+    geo_mismatch: Option<bool>,
+}
+
+/// Гостевой пропуск с пригласившим жителем и его квартирой
+const EXPECTED_GUEST_SELECT: &str = r#"
+    SELECT
+        g.id, g.guest_name, g.guest_phone, g.vehicle_number,
+        u.display_name AS host_name,
+        a.number AS apartment_number,
+        a.building,
+        g.status, g.expires_at, g.entered_at, g.suspended_at, g.created_at
+    FROM guest_access g
+    JOIN users u ON u.id = g.created_by
+    LEFT JOIN LATERAL (
+        SELECT number, building FROM apartments
+        WHERE complex_id = g.complex_id AND (owner_id = g.created_by OR resident_id = g.created_by)
+        ORDER BY resident_id = g.created_by DESC, created_at
+        LIMIT 1
+    ) a ON true
+"#;
+
+/// Строка истории проездов с именами жителя и гостя
+#[derive(sqlx::FromRow)]
+struct BarrierHistoryRow {
+    id: Uuid,
+    action: crate::models::BarrierAction,
+    vehicle_number: Option<String>,
+    user_name: Option<String>,
+    guest_name: Option<String>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    distance_m: Option<i32>,
+    geo_mismatch: Option<bool>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
 pub fn routes() -> Router<AppState> {
     Router::new()
         // Шлагбаум
@@ -35,38 +109,66 @@ pub fn routes() -> Router<AppState> {
         .route("/barrier/guest-access", post(create_guest_access))
         .route("/barrier/guests", get(get_active_guests))
         .route("/barrier/guests/:id", delete(cancel_guest_access))
+        .route(
+            "/barrier/approval-policy",
+            get(get_guest_approval_policy).put(update_guest_approval_policy),
+        )
+        .route("/barrier/approvals", get(list_guest_approvals))
+        .route("/barrier/approvals/:id/approve", post(approve_guest_access))
+        .route("/barrier/approvals/:id/decline", post(decline_guest_access))
         .route("/barrier/history", get(get_barrier_history))
         .route("/barrier/entry", post(process_entry))
+        .route("/barrier/anpr", post(process_anpr_entry))
         .route("/barrier/exit", post(process_exit))
+        // Автомобили жителей
+        .route("/vehicles", get(list_my_vehicles).post(add_vehicle))
+        .route("/vehicles/:id", delete(remove_vehicle))
         // Камеры
         .route("/cameras", get(get_cameras))
         .route("/cameras/:id/stream", get(get_camera_stream))
+        .route("/streams/:camera_id/*path", get(proxy_camera_stream))
         // Домофон
         .route("/intercom/open", post(open_intercom))
         .route("/intercom/calls", get(get_intercom_calls))
+        .route("/intercom/calls/:id/answer", post(answer_intercom_call))
+        .route("/intercom/calls/:id/reject", post(reject_intercom_call))
+        .route("/intercom/calls/:id/open", post(open_intercom_call))
+        .route("/intercom/devices/:intercom_id/ring", post(intercom_ring))
+        .route("/intercom/devices/:intercom_id/code", post(intercom_code))
+        .route(
+            "/intercom/apartments/:apartment_id/code",
+            get(get_apartment_code).post(rotate_apartment_code),
+        )
+        .route(
+            "/intercom/apartments/:apartment_id/code/reset",
+            post(reset_apartment_code),
+        )
+        .route("/intercom/apartments/:apartment_id/snapshots", get(get_apartment_snapshots))
+        .route("/intercom/snapshots/:call_id", delete(delete_snapshot))
+        // Чёрный список
+        .route("/blacklist", get(list_blacklist).post(add_to_blacklist))
+        .route("/blacklist/denials", get(list_blacklist_denials))
+        .route("/blacklist/:id", delete(remove_from_blacklist))
+        // Пост охраны
+        .route("/events", get(list_security_events))
+        .route("/events/:id/review", post(review_security_event))
+        .route(
+            "/anomaly-settings",
+            get(get_anomaly_settings).put(update_anomaly_settings),
+        )
+        .route(
+            "/device-binding-settings",
+            get(get_device_binding_settings).put(update_device_binding_settings),
+        )
+        .route("/guard/validate", post(guard_validate_code))
+        .route("/guard/manual-open", post(guard_manual_open))
+        .route("/guard/expected-guests", get(guard_expected_guests))
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
-struct PaginationQuery {
-    page: Option<i64>,
-    limit: Option<i64>,
-}
-
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
-
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
+pub struct PaginationQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
 }
 
 /// Открыть шлагбаум
@@ -75,26 +177,103 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
     path = "/api/v1/security/barrier/open",
     tag = "security",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body(content = OpenBarrierRequest, description = "barrier_id обязателен, если в ЖК несколько шлагбаумов"),
     responses(
         (status = 200, description = "Шлагбаум открыт", body = SuccessResponse),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет доступа")
+        (status = 403, description = "Нет доступа или житель слишком далеко от ЖК"),
+        (status = 422, description = "Некорректные координаты"),
+        (status = 502, description = "Шлагбаум не ответил")
     )
 )]
 pub async fn open_barrier(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
+    payload: Option<Json<OpenBarrierRequest>>,
 ) -> AppResult<Json<Value>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
+    let payload = payload.map(|Json(p)| p);
+    let barrier_id = payload.as_ref().and_then(|p| p.barrier_id);
+    let vehicle_number = match payload.as_ref().and_then(|p| p.vehicle_number.as_deref()) {
+        Some(number) => Some(normalize_vehicle_number(number)).filter(|n| !n.is_empty()),
+        None => sole_resident_vehicle(&state, complex_id, auth_user.user_id).await?,
+    };
+    let location = match &payload {
+        Some(p) => open_location(&state, complex_id, p.latitude, p.longitude).await?,
+        None => None,
+    };
+
+    if state.config.barrier_geofence_enforce
+        && location.as_ref().is_some_and(|l| l.geo_mismatch == Some(true))
+    {
+        return Err(AppError::EntryDenied(
+            "вы слишком далеко от жилого комплекса".to_string(),
+        ));
+    }
+
+    AnomalyService::check_open_rate(&state.pool, complex_id, auth_user.user_id).await?;
+
+    let barriers = sqlx::query_as::<_, Barrier>(
+        r#"
+        SELECT * FROM barriers
+        WHERE complex_id = $1 AND is_active = true AND ($2::uuid IS NULL OR id = $2)
+        ORDER BY name
+        LIMIT 2
+        "#,
+    )
+    .bind(complex_id)
+    .bind(barrier_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let barrier = match barriers.as_slice() {
+        // Шлагбаумы ЖК не заведены — открытие только фиксируется в журнале
+        [] if barrier_id.is_none() => None,
+        [] => return Err(AppError::NotFound("Шлагбаум не найден".to_string())),
+        [barrier] => Some(barrier),
+        _ => {
+            return Err(AppError::BadRequest(
+                "В ЖК несколько шлагбаумов, укажите barrier_id".to_string(),
+            ))
+        }
+    };
+
+    AnomalyService::inspect_entry(
+        &state.pool,
+        BarrierPassage {
+            complex_id,
+            user_id: Some(auth_user.user_id),
+            guest_access_id: None,
+            vehicle_number: vehicle_number.as_deref(),
+        },
+    )
+    .await?;
+
+    if let Some(barrier) = barrier {
+        barrier_driver::open(barrier).await?;
+    }
 
     sqlx::query(
         r#"
-        INSERT INTO barrier_access_logs (complex_id, user_id, action)
-        VALUES ($1, $2, 'entry')
+        INSERT INTO barrier_access_logs (
+            complex_id, barrier_id, user_id, action, vehicle_number,
+            latitude, longitude, distance_m, geo_mismatch
+        )
+        VALUES ($1, $2, $3, 'entry', $4, $5, $6, $7, $8)
         "#,
     )
     .bind(complex_id)
+    .bind(barrier.map(|b| b.id))
     .bind(auth_user.user_id)
+    .bind(&vehicle_number)
+    .bind(location.as_ref().map(|l| l.latitude))
+    .bind(location.as_ref().map(|l| l.longitude))
+    .bind(location.as_ref().and_then(|l| l.distance_m))
+    .bind(location.as_ref().and_then(|l| l.geo_mismatch))
     .execute(&state.pool)
     .await?;
 
@@ -104,12 +283,77 @@ pub async fn open_barrier(
     })))
 }
 
+/// Номер автомобиля жителя, если в ЖК у него зарегистрирован ровно один
+async fn sole_resident_vehicle(
+    state: &AppState,
+    complex_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<String>> {
+    let vehicles: Vec<(String,)> = sqlx::query_as(
+        "SELECT vehicle_number FROM resident_vehicles WHERE complex_id = $1 AND user_id = $2 LIMIT 2",
+    )
+    .bind(complex_id)
+    .bind(user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(match vehicles.as_slice() {
+        [(number,)] => Some(number.clone()),
+        _ => None,
+    })
+}
+
+/// Проверить переданные координаты и сравнить их с координатами адреса ЖК
+async fn open_location(
+    state: &AppState,
+    complex_id: Uuid,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+) -> AppResult<Option<OpenLocation>> {
+    let (latitude, longitude) = match (latitude, longitude) {
+        (None, None) => return Ok(None),
+        (Some(lat), Some(lon)) if geo::is_valid_coordinate(lat, lon) => (lat, lon),
+        _ => {
+            return Err(AppError::Validation(
+                "Передайте корректные широту и долготу вместе".to_string(),
+            ))
+        }
+    };
+
+    let complex_point: Option<(Option<f64>, Option<f64>)> = sqlx::query_as(
+        r#"
+        SELECT a.latitude::float8, a.longitude::float8
+        FROM complexes c
+        JOIN addresses a ON a.id = c.address_id
+        WHERE c.id = $1
+        "#,
+    )
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let distance_m = match complex_point {
+        Some((Some(lat), Some(lon))) => Some(geo::distance_m(latitude, longitude, lat, lon)),
+        _ => None,
+    };
+
+    Ok(Some(OpenLocation {
+        latitude,
+        longitude,
+        distance_m: distance_m.map(|d| d.round() as i32),
+        geo_mismatch: distance_m.map(|d| d > state.config.barrier_geofence_radius_m),
+    }))
+}
+
 /// Создать гостевой доступ
 #[utoipa::path(
     post,
     path = "/api/v1/security/barrier/guest-access",
     tag = "security",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     request_body = CreateGuestAccessRequest,
     responses(
         (status = 200, description = "Гостевой доступ создан", body = GuestAccessResponse),
@@ -120,9 +364,10 @@ pub async fn open_barrier(
 pub async fn create_guest_access(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Json(payload): Json<CreateGuestAccessRequest>,
 ) -> AppResult<Json<GuestAccessResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
     let duration = payload.duration_minutes.unwrap_or(30).min(240);
 
@@ -164,7 +409,10 @@ pub async fn create_guest_access(
         entered_at: access.entered_at,
         exited_at: access.exited_at,
         status: access.status,
+        decline_reason: access.decline_reason,
         created_at: access.created_at,
+        owner_notification: access.owner_notification_status,
+        owner_notification_attempts: access.owner_notification_attempts,
     }))
 }
 
@@ -174,6 +422,9 @@ pub async fn create_guest_access(
     path = "/api/v1/security/barrier/guests",
     tag = "security",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     responses(
         (status = 200, description = "Список активных гостей", body = Vec<GuestAccessResponse>),
         (status = 401, description = "Не авторизован"),
@@ -182,9 +433,9 @@ pub async fn create_guest_access(
 )]
 pub async fn get_active_guests(
     State(state): State<AppState>,
-    auth_user: AuthUser,
+    complex: ComplexScope,
 ) -> AppResult<Json<Vec<GuestAccessResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
     let guests = BarrierService::get_active_guests(&state.pool, complex_id).await?;
 
@@ -211,18 +462,264 @@ pub async fn get_active_guests(
 pub async fn cancel_guest_access(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    request_id: RequestId,
     Path(access_id): Path<Uuid>,
 ) -> AppResult<Json<Value>> {
     let sms_service = SmsService::new(state.config.clone());
     let barrier_service = BarrierService::new(sms_service);
 
-    barrier_service
+    let cancelled = barrier_service
         .cancel_access(&state.pool, access_id, auth_user.user_id)
         .await?;
 
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(cancelled.complex_id),
+            action: "cancel_guest_access",
+            entity_type: "guest_access",
+            entity_id: Some(access_id),
+            old_value: Some(json!({"status": GuestAccessStatus::Pending})),
+            new_value: Some(json!({"status": cancelled.status})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
     Ok(Json(json!({"success": true})))
 }
 
+/// Политика подтверждения гостевых пропусков в ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/barrier/approval-policy",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Текущая политика", body = GuestApprovalPolicy),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn get_guest_approval_policy(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+) -> AppResult<Json<GuestApprovalPolicy>> {
+    let complex_id = complex.complex_id()?;
+
+    Ok(Json(GuestApprovalPolicy {
+        guest_approval_required: BarrierService::guest_approval_required(&state.pool, complex_id)
+            .await?,
+    }))
+}
+
+/// Включить или выключить подтверждение гостевых пропусков охраной
+#[utoipa::path(
+    put,
+    path = "/api/v1/security/barrier/approval-policy",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body = GuestApprovalPolicy,
+    responses(
+        (status = 200, description = "Политика обновлена", body = GuestApprovalPolicy),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn update_guest_approval_policy(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Json(payload): Json<GuestApprovalPolicy>,
+) -> AppResult<Json<GuestApprovalPolicy>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let previous = BarrierService::guest_approval_required(&state.pool, complex_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query("UPDATE complexes SET guest_approval_required = $2, updated_at = NOW() WHERE id = $1")
+        .bind(complex_id)
+        .bind(payload.guest_approval_required)
+        .execute(&mut *tx)
+        .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id,
+            entity_type: "complex",
+            entity_id: complex_id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!({"guest_approval_required": previous}),
+            new_value: json!({"guest_approval_required": payload.guest_approval_required}),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    AuditService::record(
+        &mut *tx,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "update_guest_approval_policy",
+            entity_type: "complex",
+            entity_id: Some(complex_id),
+            old_value: Some(json!({"guest_approval_required": previous})),
+            new_value: Some(json!({"guest_approval_required": payload.guest_approval_required})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(payload))
+}
+
+/// Пропуска, ожидающие подтверждения
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/barrier/approvals",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(GuestApprovalsQuery),
+    responses(
+        (status = 200, description = "Пропуска на проверке", body = Vec<ExpectedGuestResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно охране и председателю")
+    )
+)]
+pub async fn list_guest_approvals(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<GuestApprovalsQuery>,
+) -> AppResult<Json<Vec<ExpectedGuestResponse>>> {
+    check_security_staff(&state, query.complex_id, &auth_user).await?;
+
+    let guests = sqlx::query_as::<_, ExpectedGuestResponse>(&format!(
+        r#"{}
+        WHERE g.complex_id = $1 AND g.status = 'awaiting_approval' AND g.expires_at > NOW()
+        ORDER BY g.created_at"#,
+        EXPECTED_GUEST_SELECT
+    ))
+    .bind(query.complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(guests))
+}
+
+/// Подтвердить гостевой пропуск
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/barrier/approvals/{id}/approve",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID гостевого доступа")
+    ),
+    responses(
+        (status = 200, description = "Пропуск подтверждён", body = GuestAccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно охране и председателю"),
+        (status = 404, description = "Пропуск не найден или уже рассмотрен")
+    )
+)]
+pub async fn approve_guest_access(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(access_id): Path<Uuid>,
+) -> AppResult<Json<GuestAccessResponse>> {
+    review_guest_access(&state, &auth_user, request_id, access_id, true, None).await
+}
+
+/// Отклонить гостевой пропуск
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/barrier/approvals/{id}/decline",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID гостевого доступа")
+    ),
+    request_body = DeclineGuestAccessRequest,
+    responses(
+        (status = 200, description = "Пропуск отклонён", body = GuestAccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно охране и председателю"),
+        (status = 404, description = "Пропуск не найден или уже рассмотрен")
+    )
+)]
+pub async fn decline_guest_access(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(access_id): Path<Uuid>,
+    Json(payload): Json<DeclineGuestAccessRequest>,
+) -> AppResult<Json<GuestAccessResponse>> {
+    let reason = payload
+        .reason
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+
+    review_guest_access(&state, &auth_user, request_id, access_id, false, reason).await
+}
+
+async fn review_guest_access(
+    state: &AppState,
+    auth_user: &AuthUser,
+    request_id: RequestId,
+    access_id: Uuid,
+    approve: bool,
+    reason: Option<String>,
+) -> AppResult<Json<GuestAccessResponse>> {
+    let access = sqlx::query_as::<_, GuestAccess>("SELECT * FROM guest_access WHERE id = $1")
+        .bind(access_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Гостевой доступ не найден".to_string()))?;
+
+    check_security_staff(state, access.complex_id, auth_user).await?;
+
+    let reviewed = BarrierService::review_guest_access(
+        &state.pool,
+        access_id,
+        auth_user.user_id,
+        approve,
+        reason,
+    )
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(reviewed.complex_id),
+            action: if approve { "approve_guest_access" } else { "decline_guest_access" },
+            entity_type: "guest_access",
+            entity_id: Some(access_id),
+            old_value: Some(json!({"status": access.status})),
+            new_value: Some(json!({"status": reviewed.status, "decline_reason": reviewed.decline_reason})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(GuestAccessResponse::from(reviewed)))
+}
+
 /// Получить историю проездов
 #[utoipa::path(
     get,
@@ -230,11 +727,12 @@ pub async fn cancel_guest_access(
     tag = "security",
     security(("bearer_auth" = [])),
     params(
-        ("page" = Option<i64>, Query, description = "Номер страницы"),
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
+        ("cursor" = Option<String>, Query, description = "Курсор следующей страницы"),
         ("limit" = Option<i64>, Query, description = "Количество записей")
     ),
     responses(
-        (status = 200, description = "История проездов", body = Vec<BarrierAccessLogResponse>),
+        (status = 200, description = "История проездов", body = BarrierHistoryPage),
         (status = 401, description = "Не авторизован"),
         (status = 403, description = "Нет доступа")
     )
@@ -242,82 +740,82 @@ pub async fn cancel_guest_access(
 pub async fn get_barrier_history(
     State(state): State<AppState>,
     auth_user: AuthUser,
-    Query(pagination): Query<PaginationQuery>,
-) -> AppResult<Json<Vec<BarrierAccessLogResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    complex: ComplexScope,
+    Query(pagination): Query<CursorQuery>,
+) -> AppResult<Json<CursorPage<BarrierAccessLogResponse>>> {
+    let complex_id = complex.complex_id()?;
+    // Координаты жителей показываем только председателю
+    let show_location =
+        PermissionService::has(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
 
-    let limit = pagination.limit.unwrap_or(50).min(100);
-    let offset = pagination.page.unwrap_or(0) * limit;
+    let limit = pagination.limit.unwrap_or(50).clamp(1, 100);
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string())))
+        .transpose()?;
 
-    let logs = sqlx::query_as::<
-        _,
-        (
-            Uuid,
-            crate::models::BarrierAction,
-            Option<String>,
-            Option<Uuid>,
-            Option<Uuid>,
-            chrono::DateTime<chrono::Utc>,
-        ),
-    >(
+    // Имена жителей и гостей подтягиваем тем же запросом
+    let mut logs = sqlx::query_as::<_, BarrierHistoryRow>(
         r#"
-        SELECT id, action, vehicle_number, user_id, guest_access_id, created_at
-        FROM barrier_access_logs
-        WHERE complex_id = $1
-        ORDER BY created_at DESC
-        LIMIT $2 OFFSET $3
+        SELECT
+            l.id,
+            l.action,
+            l.vehicle_number,
+            u.display_name AS user_name,
+            g.guest_name,
+            l.latitude::float8 AS latitude,
+            l.longitude::float8 AS longitude,
+            l.distance_m,
+            l.geo_mismatch,
+            l.created_at
+        FROM barrier_access_logs l
+        LEFT JOIN users u ON u.id = l.user_id
+        LEFT JOIN guest_access g ON g.id = l.guest_access_id
+        WHERE l.complex_id = $1
+          AND ($2::timestamptz IS NULL OR (l.created_at, l.id) < ($2, $3))
+        ORDER BY l.created_at DESC, l.id DESC
+        LIMIT $4
         "#,
     )
     .bind(complex_id)
-    .bind(limit)
-    .bind(offset)
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
     .fetch_all(&state.pool)
     .await?;
 
-    let mut response = Vec::new();
-    for (id, action, vehicle_number, user_id, guest_access_id, created_at) in logs {
-        let user_name = if let Some(uid) = user_id {
-            sqlx::query_as::<_, (String,)>(
-                "SELECT COALESCE(first_name || ' ' || last_name, phone) FROM users WHERE id = $1",
-            )
-            .bind(uid)
-            .fetch_optional(&state.pool)
-            .await?
-            .map(|(n,)| n)
-        } else {
-            None
-        };
-
-        let guest_name = if let Some(gid) = guest_access_id {
-            sqlx::query_as::<_, (Option<String>,)>(
-                "SELECT guest_name FROM guest_access WHERE id = $1",
-            )
-            .bind(gid)
-            .fetch_optional(&state.pool)
-            .await?
-            .and_then(|(n,)| n)
-        } else {
-            None
-        };
-
-        response.push(BarrierAccessLogResponse {
-            id,
-            action,
-            vehicle_number,
-            user_name,
-            guest_name,
-            created_at,
-        });
-    }
+    let next_cursor = take_page(&mut logs, limit, |log| Cursor::new(log.created_at, log.id));
 
-    Ok(Json(response))
+    let response = logs
+        .into_iter()
+        .map(|log| BarrierAccessLogResponse {
+            id: log.id,
+            action: log.action,
+            vehicle_number: log.vehicle_number,
+            user_name: log.user_name,
+            guest_name: log.guest_name,
+            location: match (show_location, log.latitude, log.longitude) {
+                (true, Some(latitude), Some(longitude)) => Some(BarrierOpenLocation {
+                    latitude,
+                    longitude,
+                    distance_m: log.distance_m,
+                    geo_mismatch: log.geo_mismatch,
+                }),
+                _ => None,
+            },
+            created_at: log.created_at,
+        })
+        .collect();
+
+    Ok(Json(CursorPage::new(response, next_cursor)))
 }
 
 /// Зарегистрировать въезд по коду
 #[utoipa::path(
     post,
     path = "/api/v1/security/barrier/entry",
-    tag = "security",
+    tag = "devices",
     request_body = BarrierEntryRequest,
     responses(
         (status = 200, description = "Въезд зарегистрирован", body = SuccessResponse),
@@ -350,11 +848,51 @@ pub async fn process_entry(
     })))
 }
 
+/// Въезд по номеру, распознанному камерой шлагбаума
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/barrier/anpr",
+    tag = "devices",
+    security(("barrier_key" = [])),
+    request_body = AnprEntryRequest,
+    responses(
+        (status = 200, description = "Шлагбаум нужно открыть", body = AnprEntryResponse),
+        (status = 401, description = "Неверный ключ шлагбаума"),
+        (status = 403, description = "Въезд запрещён или автомобиль не зарегистрирован")
+    )
+)]
+pub async fn process_anpr_entry(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(payload): Json<AnprEntryRequest>,
+) -> AppResult<Json<AnprEntryResponse>> {
+    let api_key = headers
+        .get(BARRIER_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+
+    let (complex_id,): (Uuid,) = sqlx::query_as(
+        "SELECT complex_id FROM barriers WHERE id = $1 AND api_key = $2 AND is_active = true",
+    )
+    .bind(payload.barrier_id)
+    .bind(api_key)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::Unauthorized)?;
+
+    let barrier_service = BarrierService::new(SmsService::new(state.config.clone()));
+    let decision = barrier_service
+        .process_plate_entry(&state.pool, complex_id, payload.barrier_id, &payload.plate)
+        .await?;
+
+    Ok(Json(decision))
+}
+
 /// Зарегистрировать выезд по коду
 #[utoipa::path(
     post,
     path = "/api/v1/security/barrier/exit",
-    tag = "security",
+    tag = "devices",
     request_body = BarrierEntryRequest,
     responses(
         (status = 200, description = "Выезд зарегистрирован", body = SuccessResponse),
@@ -382,92 +920,262 @@ pub async fn process_exit(
     })))
 }
 
-/// Получить список камер
+/// Мои автомобили для въезда по номеру
 #[utoipa::path(
     get,
-    path = "/api/v1/security/cameras",
+    path = "/api/v1/security/vehicles",
     tag = "security",
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Список камер", body = Vec<CameraResponse>),
-        (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет доступа")
+        (status = 200, description = "Автомобили", body = Vec<ResidentVehicle>),
+        (status = 401, description = "Не авторизован")
     )
 )]
-pub async fn get_cameras(
+pub async fn list_my_vehicles(
     State(state): State<AppState>,
     auth_user: AuthUser,
-) -> AppResult<Json<Vec<CameraResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
-
-    let cameras = sqlx::query_as::<_, Camera>(
-        "SELECT * FROM cameras WHERE complex_id = $1 AND is_active = true ORDER BY name",
+) -> AppResult<Json<Vec<ResidentVehicle>>> {
+    let vehicles = sqlx::query_as::<_, ResidentVehicle>(
+        "SELECT * FROM resident_vehicles WHERE user_id = $1 ORDER BY created_at",
     )
-    .bind(complex_id)
+    .bind(auth_user.user_id)
     .fetch_all(&state.pool)
     .await?;
 
-    let response: Vec<CameraResponse> = cameras
-        .into_iter()
-        .map(|c| CameraResponse {
-            id: c.id,
-            name: c.name,
-            location: c.location,
-            is_active: c.is_active,
-        })
-        .collect();
-
-    Ok(Json(response))
+    Ok(Json(vehicles))
 }
 
-/// Получить URL потока камеры
+/// Добавить автомобиль для въезда по номеру
 #[utoipa::path(
-    get,
-    path = "/api/v1/security/cameras/{id}/stream",
+    post,
+    path = "/api/v1/security/vehicles",
     tag = "security",
     security(("bearer_auth" = [])),
     params(
-        ("id" = Uuid, Path, description = "ID камеры")
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
     ),
+    request_body = CreateResidentVehicleRequest,
     responses(
-        (status = 200, description = "URL потока", body = CameraStreamResponse),
-        (status = 401, description = "Не авторизован"),
-        (status = 404, description = "Камера не найдена")
+        (status = 200, description = "Автомобиль добавлен", body = ResidentVehicle),
+        (status = 400, description = "Неверный номер"),
+        (status = 409, description = "Номер уже зарегистрирован в ЖК")
     )
 )]
-pub async fn get_camera_stream(
+pub async fn add_vehicle(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
+    Json(payload): Json<CreateResidentVehicleRequest>,
+) -> AppResult<Json<ResidentVehicle>> {
+    let complex_id = complex.complex_id()?;
+
+    let vehicle_number = normalize_vehicle_number(&payload.vehicle_number);
+    if vehicle_number.len() < 4 {
+        return Err(AppError::BadRequest("Неверный госномер".to_string()));
+    }
+
+    let vehicle = sqlx::query_as::<_, ResidentVehicle>(
+        r#"
+        INSERT INTO resident_vehicles (complex_id, user_id, vehicle_number, description)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (complex_id, vehicle_number) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(complex_id)
+    .bind(auth_user.user_id)
+    .bind(&vehicle_number)
+    .bind(payload.description.as_deref())
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Номер уже зарегистрирован в ЖК".to_string()))?;
+
+    Ok(Json(vehicle))
+}
+
+/// Удалить свой автомобиль
+#[utoipa::path(
+    delete,
+    path = "/api/v1/security/vehicles/{id}",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID автомобиля")
+    ),
+    responses(
+        (status = 200, description = "Автомобиль удалён", body = SuccessResponse),
+        (status = 404, description = "Автомобиль не найден")
+    )
+)]
+pub async fn remove_vehicle(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(vehicle_id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let result = sqlx::query("DELETE FROM resident_vehicles WHERE id = $1 AND user_id = $2")
+        .bind(vehicle_id)
+        .bind(auth_user.user_id)
+        .execute(&state.pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::NotFound("Автомобиль не найден".to_string()));
+    }
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Автомобиль удалён"
+    })))
+}
+
+/// Получить список камер
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/cameras",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Список камер", body = Vec<CameraResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn get_cameras(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+) -> AppResult<Json<Vec<CameraResponse>>> {
+    let complex_id = complex.complex_id()?;
+
+    let cameras = sqlx::query_as::<_, Camera>(
+        "SELECT * FROM cameras WHERE complex_id = $1 AND is_active = true ORDER BY name",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let response: Vec<CameraResponse> = cameras
+        .into_iter()
+        .map(|c| CameraResponse {
+            id: c.id,
+            name: c.name,
+            location: c.location,
+            is_active: c.is_active,
+        })
+        .collect();
+
+    Ok(Json(response))
+}
+
+/// Получить URL потока камеры
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/cameras/{id}/stream",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID камеры")
+    ),
+    responses(
+        (status = 200, description = "URL потока", body = CameraStreamResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Камера не найдена")
+    )
+)]
+pub async fn get_camera_stream(
+    State(state): State<AppState>,
+    complex: ComplexScope,
     Path(camera_id): Path<Uuid>,
 ) -> AppResult<Json<CameraStreamResponse>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
-
     let camera = sqlx::query_as::<_, Camera>(
-        "SELECT * FROM cameras WHERE id = $1 AND complex_id = $2 AND is_active = true",
+        "SELECT * FROM cameras WHERE id = $1 AND complex_id = ANY($2) AND is_active = true",
     )
     .bind(camera_id)
-    .bind(complex_id)
+    .bind(&complex.complex_ids)
     .fetch_optional(&state.pool)
     .await?
     .ok_or_else(|| AppError::NotFound("Камера не найдена".to_string()))?;
 
-    let stream_url = camera
+    let source = camera
         .stream_url
+        .as_deref()
         .ok_or_else(|| AppError::NotFound("URL потока не настроен".to_string()))?;
 
+    if hls_source(source).is_none() {
+        return Err(AppError::BadRequest(
+            "Камера не поддерживает воспроизведение через HLS".to_string(),
+        ));
+    }
+
+    let streams = StreamService::new(&state.config);
+    let (token, expires) = streams.issue_token(camera.id)?;
+
     Ok(Json(CameraStreamResponse {
         id: camera.id,
         name: camera.name,
-        stream_url,
+        stream_url: streams.playlist_url(camera.id, &token),
+        expires_at: chrono::DateTime::from_timestamp(expires, 0).unwrap_or_else(chrono::Utc::now),
     }))
 }
 
+/// HLS-плейлист или сегмент камеры через прокси бэкенда
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/streams/{camera_id}/{path}",
+    tag = "security",
+    params(
+        ("camera_id" = Uuid, Path, description = "ID камеры"),
+        ("path" = String, Path, description = "index.m3u8 или путь сегмента из плейлиста"),
+        StreamTokenQuery
+    ),
+    responses(
+        (status = 200, description = "Плейлист или сегмент"),
+        (status = 401, description = "Токен недействителен или истёк"),
+        (status = 404, description = "Камера или сегмент не найдены"),
+        (status = 502, description = "Камера недоступна")
+    )
+)]
+pub async fn proxy_camera_stream(
+    State(state): State<AppState>,
+    Path((camera_id, path)): Path<(Uuid, String)>,
+    Query(query): Query<StreamTokenQuery>,
+) -> AppResult<impl IntoResponse> {
+    let streams = StreamService::new(&state.config);
+    streams.verify_token(camera_id, &query.token)?;
+
+    let stream_url: (Option<String>,) =
+        sqlx::query_as("SELECT stream_url FROM cameras WHERE id = $1 AND is_active = true")
+            .bind(camera_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Камера не найдена".to_string()))?;
+
+    let stream_url = stream_url
+        .0
+        .ok_or_else(|| AppError::NotFound("URL потока не настроен".to_string()))?;
+
+    let chunk = streams.fetch(camera_id, &stream_url, &path, &query.token).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, chunk.content_type),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        chunk.body,
+    ))
+}
+
 /// Открыть домофон
 #[utoipa::path(
     post,
     path = "/api/v1/security/intercom/open",
     tag = "security",
     security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
     request_body = OpenIntercomRequest,
     responses(
         (status = 200, description = "Домофон открыт", body = SuccessResponse),
@@ -478,110 +1186,1428 @@ pub async fn get_camera_stream(
 pub async fn open_intercom(
     State(state): State<AppState>,
     auth_user: AuthUser,
-    Json(payload): Json<Value>,
+    complex: ComplexScope,
+    Json(payload): Json<OpenIntercomRequest>,
 ) -> AppResult<Json<Value>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
-    let intercom_id = payload["intercom_id"]
-        .as_str()
-        .and_then(|s| Uuid::parse_str(s).ok());
-
-    if let Some(id) = intercom_id {
-        let exists: Option<(i32,)> = sqlx::query_as(
-            "SELECT 1 FROM intercoms WHERE id = $1 AND complex_id = $2 AND is_active = true",
-        )
-        .bind(id)
-        .bind(complex_id)
-        .fetch_optional(&state.pool)
-        .await?;
+    let intercoms = sqlx::query_as::<_, Intercom>(
+        r#"
+        SELECT * FROM intercoms
+        WHERE complex_id = $1 AND is_active = true AND ($2::uuid IS NULL OR id = $2)
+        ORDER BY name
+        LIMIT 2
+        "#,
+    )
+    .bind(complex_id)
+    .bind(payload.intercom_id)
+    .fetch_all(&state.pool)
+    .await?;
 
-        if exists.is_none() {
-            return Err(AppError::NotFound("Домофон не найден".to_string()));
+    let intercom = match intercoms.as_slice() {
+        [] => return Err(AppError::NotFound("Домофон не найден".to_string())),
+        [intercom] => intercom,
+        _ => {
+            return Err(AppError::BadRequest(
+                "В ЖК несколько домофонов, укажите intercom_id".to_string(),
+            ))
         }
-    }
+    };
+
+    IntercomService::open_door(&state.config, intercom).await?;
+
+    let call_id: (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO intercom_calls (intercom_id, apartment_id, user_id, status, ended_at)
+        VALUES (
+            $1,
+            (SELECT id FROM apartments
+             WHERE complex_id = $2 AND (owner_id = $3 OR resident_id = $3)
+             LIMIT 1),
+            $3,
+            'opened',
+            NOW()
+        )
+        RETURNING id
+        "#,
+    )
+    .bind(intercom.id)
+    .bind(complex_id)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
 
     Ok(Json(json!({
         "success": true,
-        "message": "Домофон открыт"
+        "message": "Домофон открыт",
+        "call_id": call_id.0
     })))
 }
 
-/// Получить историю звонков домофона
+/// Входящий вызов с панели домофона. Вызывается устройством с токеном в `X-Intercom-Token`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/devices/{intercom_id}/ring",
+    tag = "devices",
+    security(("intercom_token" = [])),
+    params(
+        ("intercom_id" = Uuid, Path, description = "ID домофона")
+    ),
+    request_body = IntercomRingRequest,
+    responses(
+        (status = 200, description = "Вызов зарегистрирован", body = IntercomCall),
+        (status = 401, description = "Неверный токен устройства"),
+        (status = 404, description = "Квартира не найдена")
+    )
+)]
+pub async fn intercom_ring(
+    State(state): State<AppState>,
+    Path(intercom_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<IntercomRingRequest>,
+) -> AppResult<Json<IntercomCall>> {
+    let intercom = device_intercom(&state, intercom_id, &headers).await?;
+    let apartment_id = panel_apartment(
+        &state,
+        &intercom,
+        &payload.apartment_number,
+        payload.building.as_deref(),
+    )
+    .await?;
+
+    let call = IntercomService::ring(
+        &state.pool,
+        &intercom,
+        apartment_id,
+        payload.snapshot_url.as_deref(),
+    )
+    .await?;
+
+    Ok(Json(call))
+}
+
+/// Код квартиры, набранный на панели. Вызывается устройством с токеном в `X-Intercom-Token`;
+/// попытка попадает в историю вызовов квартиры
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/devices/{intercom_id}/code",
+    tag = "devices",
+    security(("intercom_token" = [])),
+    params(
+        ("intercom_id" = Uuid, Path, description = "ID домофона")
+    ),
+    request_body = IntercomCodeRequest,
+    responses(
+        (status = 200, description = "Решение для панели", body = IntercomCodeResponse),
+        (status = 401, description = "Неверный токен устройства"),
+        (status = 404, description = "Квартира не найдена")
+    )
+)]
+pub async fn intercom_code(
+    State(state): State<AppState>,
+    Path(intercom_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<IntercomCodeRequest>,
+) -> AppResult<Json<IntercomCodeResponse>> {
+    let intercom = device_intercom(&state, intercom_id, &headers).await?;
+    let apartment_id = panel_apartment(
+        &state,
+        &intercom,
+        &payload.apartment_number,
+        payload.building.as_deref(),
+    )
+    .await?;
+
+    let call = IntercomService::check_code(&state.pool, &intercom, apartment_id, &payload.code).await?;
+
+    Ok(Json(IntercomCodeResponse {
+        valid: call.status == IntercomCallStatus::CodeOpened,
+        call_id: Some(call.id),
+    }))
+}
+
+/// Домофон, от имени которого пришёл запрос устройства
+async fn device_intercom(state: &AppState, intercom_id: Uuid, headers: &HeaderMap) -> AppResult<Intercom> {
+    let token = headers
+        .get(INTERCOM_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+
+    sqlx::query_as::<_, Intercom>(
+        "SELECT * FROM intercoms WHERE id = $1 AND device_token = $2 AND is_active = true",
+    )
+    .bind(intercom_id)
+    .bind(token)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or(AppError::Unauthorized)
+}
+
+/// Квартира по номеру, набранному на панели
+async fn panel_apartment(
+    state: &AppState,
+    intercom: &Intercom,
+    apartment_number: &str,
+    building: Option<&str>,
+) -> AppResult<Uuid> {
+    let apartment: (Uuid,) = sqlx::query_as(
+        r#"
+        SELECT id FROM apartments
+        WHERE complex_id = $1 AND number = $2 AND ($3::text IS NULL OR building = $3)
+        LIMIT 1
+        "#,
+    )
+    .bind(intercom.complex_id)
+    .bind(apartment_number.trim())
+    .bind(building)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+    Ok(apartment.0)
+}
+
+/// Сведения о коде домофона своей квартиры; сам код показывается только при создании или смене
 #[utoipa::path(
     get,
-    path = "/api/v1/security/intercom/calls",
+    path = "/api/v1/security/intercom/apartments/{apartment_id}/code",
     tag = "security",
     security(("bearer_auth" = [])),
     params(
-        ("page" = Option<i64>, Query, description = "Номер страницы"),
-        ("limit" = Option<i64>, Query, description = "Количество записей")
+        ("apartment_id" = Uuid, Path, description = "ID квартиры")
     ),
     responses(
-        (status = 200, description = "История звонков", body = Vec<IntercomCallResponse>),
-        (status = 401, description = "Не авторизован")
+        (status = 200, description = "Когда и кем код создан, без самого кода", body = ApartmentIntercomCode),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Код доступен только владельцу квартиры"),
+        (status = 404, description = "Код ещё не создан")
     )
 )]
-pub async fn get_intercom_calls(
+pub async fn get_apartment_code(
     State(state): State<AppState>,
     auth_user: AuthUser,
-    Query(pagination): Query<PaginationQuery>,
-) -> AppResult<Json<Vec<IntercomCallResponse>>> {
-    let apartment_ids: Vec<(Uuid,)> =
-        sqlx::query_as("SELECT id FROM apartments WHERE owner_id = $1 OR resident_id = $1")
-            .bind(auth_user.user_id)
-            .fetch_all(&state.pool)
-            .await?;
+    Path(apartment_id): Path<Uuid>,
+) -> AppResult<Json<ApartmentIntercomCode>> {
+    check_apartment_owner(&state, apartment_id, auth_user.user_id).await?;
 
-    let ids: Vec<Uuid> = apartment_ids.into_iter().map(|(id,)| id).collect();
+    let code = sqlx::query_as::<_, ApartmentIntercomCode>(
+        "SELECT * FROM apartment_intercom_codes WHERE apartment_id = $1",
+    )
+    .bind(apartment_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Код ещё не создан".to_string()))?;
 
-    if ids.is_empty() {
-        return Ok(Json(vec![]));
-    }
+    Ok(Json(code))
+}
 
-    let limit = pagination.limit.unwrap_or(50).min(100);
-    let offset = pagination.page.unwrap_or(0) * limit;
+/// Создать или сменить код домофона своей квартиры
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/apartments/{apartment_id}/code",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("apartment_id" = Uuid, Path, description = "ID квартиры")
+    ),
+    responses(
+        (status = 200, description = "Новый код квартиры; больше он нигде не показывается", body = ApartmentIntercomCode),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Код меняет только владелец квартиры")
+    )
+)]
+pub async fn rotate_apartment_code(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(apartment_id): Path<Uuid>,
+) -> AppResult<Json<ApartmentIntercomCode>> {
+    check_apartment_owner(&state, apartment_id, auth_user.user_id).await?;
 
-    let calls = sqlx::query_as::<
-        _,
-        (
-            Uuid,
-            Uuid,
-            crate::models::IntercomCallStatus,
-            Option<i32>,
-            Option<String>,
-            chrono::DateTime<chrono::Utc>,
-        ),
-    >(
-        r#"
-        SELECT ic.id, ic.intercom_id, ic.status, ic.duration_seconds, ic.snapshot_url, ic.created_at
-        FROM intercom_calls ic
-        WHERE ic.apartment_id = ANY($1)
-        ORDER BY ic.created_at DESC
-        LIMIT $2 OFFSET $3
-        "#,
+    let code = IntercomService::rotate_code(&state.pool, apartment_id, auth_user.user_id).await?;
+    Ok(Json(code))
+}
+
+/// Сбросить код квартиры председателем, например при смене владельца.
+/// Прежний код перестаёт действовать, новый владелец создаёт сам
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/apartments/{apartment_id}/code/reset",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("apartment_id" = Uuid, Path, description = "ID квартиры")
+    ),
+    responses(
+        (status = 200, description = "Код сброшен", body = SuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю"),
+        (status = 404, description = "Квартира не найдена")
     )
-    .bind(&ids)
-    .bind(limit)
-    .bind(offset)
-    .fetch_all(&state.pool)
-    .await?;
+)]
+pub async fn reset_apartment_code(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(apartment_id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let (complex_id, owner_id): (Uuid, Option<Uuid>) =
+        sqlx::query_as("SELECT complex_id, owner_id FROM apartments WHERE id = $1")
+            .bind(apartment_id)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
 
-    let mut response = Vec::new();
-    for (id, intercom_id, status, duration, snapshot, created_at) in calls {
-        let intercom_name: (String,) = sqlx::query_as("SELECT name FROM intercoms WHERE id = $1")
-            .bind(intercom_id)
-            .fetch_one(&state.pool)
-            .await?;
+    IntercomService::rotate_code(&state.pool, apartment_id, auth_user.user_id).await?;
 
-        response.push(IntercomCallResponse {
-            id,
-            intercom_name: intercom_name.0,
-            status,
-            duration_seconds: duration,
-            snapshot_url: snapshot,
-            created_at,
-        });
+    if let Some(owner_id) = owner_id {
+        NotificationService::notify_users(
+            &state.pool,
+            &[owner_id],
+            NotificationType::Security,
+            "Код домофона сброшен",
+            Some("Председатель сбросил код вашей квартиры. Создайте новый в разделе домофона."),
+            Some(json!({"apartment_id": apartment_id})),
+        )
+        .await?;
     }
 
-    Ok(Json(response))
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "reset_intercom_code",
+            entity_type: "apartment",
+            entity_id: Some(apartment_id),
+            old_value: None,
+            new_value: None,
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Код сброшен, владелец получит уведомление"
+    })))
+}
+
+/// Вызов домофона, адресованный квартире текущего пользователя
+async fn get_own_call(state: &AppState, auth_user: &AuthUser, call_id: Uuid) -> AppResult<IntercomCall> {
+    sqlx::query_as::<_, IntercomCall>(
+        r#"
+        SELECT ic.* FROM intercom_calls ic
+        JOIN apartments a ON a.id = ic.apartment_id
+        WHERE ic.id = $1 AND (a.owner_id = $2 OR a.resident_id = $2)
+        "#,
+    )
+    .bind(call_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Вызов не найден".to_string()))
+}
+
+/// Ответить на вызов домофона
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/calls/{id}/answer",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID вызова")
+    ),
+    responses(
+        (status = 200, description = "Вызов принят", body = IntercomCall),
+        (status = 404, description = "Вызов не найден"),
+        (status = 409, description = "Вызов уже завершён")
+    )
+)]
+pub async fn answer_intercom_call(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(call_id): Path<Uuid>,
+) -> AppResult<Json<IntercomCall>> {
+    let call = get_own_call(&state, &auth_user, call_id).await?;
+
+    let call = IntercomService::transition(
+        &state.pool,
+        call.id,
+        auth_user.user_id,
+        &[IntercomCallStatus::Ringing],
+        IntercomCallStatus::Answered,
+    )
+    .await?;
+
+    Ok(Json(call))
+}
+
+/// Отклонить вызов домофона
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/calls/{id}/reject",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID вызова")
+    ),
+    responses(
+        (status = 200, description = "Вызов отклонён", body = IntercomCall),
+        (status = 404, description = "Вызов не найден"),
+        (status = 409, description = "Вызов уже завершён")
+    )
+)]
+pub async fn reject_intercom_call(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(call_id): Path<Uuid>,
+) -> AppResult<Json<IntercomCall>> {
+    let call = get_own_call(&state, &auth_user, call_id).await?;
+
+    let call = IntercomService::transition(
+        &state.pool,
+        call.id,
+        auth_user.user_id,
+        &[IntercomCallStatus::Ringing, IntercomCallStatus::Answered],
+        IntercomCallStatus::Rejected,
+    )
+    .await?;
+
+    Ok(Json(call))
+}
+
+/// Открыть дверь посетителю во время вызова
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/intercom/calls/{id}/open",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID вызова")
+    ),
+    responses(
+        (status = 200, description = "Дверь открыта", body = IntercomCall),
+        (status = 404, description = "Вызов не найден"),
+        (status = 409, description = "Вызов уже завершён"),
+        (status = 502, description = "Домофон недоступен")
+    )
+)]
+pub async fn open_intercom_call(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(call_id): Path<Uuid>,
+) -> AppResult<Json<IntercomCall>> {
+    let call = get_own_call(&state, &auth_user, call_id).await?;
+
+    if !matches!(call.status, IntercomCallStatus::Ringing | IntercomCallStatus::Answered) {
+        return Err(AppError::Conflict("Вызов уже завершён".to_string()));
+    }
+
+    let intercom = sqlx::query_as::<_, Intercom>("SELECT * FROM intercoms WHERE id = $1")
+        .bind(call.intercom_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+    IntercomService::open_door(&state.config, &intercom).await?;
+
+    let call = IntercomService::transition(
+        &state.pool,
+        call.id,
+        auth_user.user_id,
+        &[IntercomCallStatus::Ringing, IntercomCallStatus::Answered],
+        IntercomCallStatus::Opened,
+    )
+    .await?;
+
+    Ok(Json(call))
+}
+
+/// Получить историю звонков домофона
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/intercom/calls",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("page" = Option<i64>, Query, description = "Номер страницы"),
+        ("limit" = Option<i64>, Query, description = "Количество записей")
+    ),
+    responses(
+        (status = 200, description = "История звонков", body = Vec<IntercomCallResponse>),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn get_intercom_calls(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(pagination): Query<PaginationQuery>,
+) -> AppResult<Json<Vec<IntercomCallResponse>>> {
+    let apartment_ids: Vec<(Uuid,)> =
+        sqlx::query_as("SELECT id FROM apartments WHERE owner_id = $1 OR resident_id = $1")
+            .bind(auth_user.user_id)
+            .fetch_all(&state.pool)
+            .await?;
+
+    let ids: Vec<Uuid> = apartment_ids.into_iter().map(|(id,)| id).collect();
+
+    if ids.is_empty() {
+        return Ok(Json(vec![]));
+    }
+
+    let limit = pagination.limit.unwrap_or(50).min(100);
+    let offset = pagination.page.unwrap_or(0) * limit;
+
+    let calls = sqlx::query_as::<_, IntercomCallResponse>(
+        r#"
+        SELECT ic.id, i.name AS intercom_name, ic.status, ic.duration_seconds, ic.snapshot_url, ic.created_at
+        FROM intercom_calls ic
+        JOIN intercoms i ON i.id = ic.intercom_id
+        WHERE ic.apartment_id = ANY($1)
+        ORDER BY ic.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(&ids)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(calls))
+}
+
+/// Галерея снимков домофона квартиры за срок хранения
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/intercom/apartments/{apartment_id}/snapshots",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("apartment_id" = Uuid, Path, description = "ID квартиры"),
+        ("cursor" = Option<String>, Query, description = "Курсор следующей страницы"),
+        ("limit" = Option<i64>, Query, description = "Количество записей")
+    ),
+    responses(
+        (status = 200, description = "Снимки домофона", body = IntercomSnapshotsPage),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Снимки доступны только жителям квартиры")
+    )
+)]
+pub async fn get_apartment_snapshots(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(apartment_id): Path<Uuid>,
+    Query(pagination): Query<CursorQuery>,
+) -> AppResult<Json<CursorPage<IntercomSnapshotResponse>>> {
+    check_apartment_member(&state, apartment_id, auth_user.user_id).await?;
+
+    let limit = pagination.limit.unwrap_or(50).clamp(1, 100);
+    let cursor = pagination
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string())))
+        .transpose()?;
+
+    let mut snapshots = sqlx::query_as::<_, IntercomSnapshotResponse>(
+        r#"
+        SELECT ic.id AS call_id, i.name AS intercom_name, ic.status, ic.snapshot_url, ic.created_at
+        FROM intercom_calls ic
+        JOIN intercoms i ON i.id = ic.intercom_id
+        WHERE ic.apartment_id = $1
+          AND ic.snapshot_url IS NOT NULL
+          AND ic.snapshot_delete_requested_at IS NULL
+          AND ic.created_at >= NOW() - make_interval(days => $2)
+          AND ($3::timestamptz IS NULL OR (ic.created_at, ic.id) < ($3, $4))
+        ORDER BY ic.created_at DESC, ic.id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(apartment_id)
+    .bind(state.config.intercom_snapshot_retention_days as i32)
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let next_cursor = take_page(&mut snapshots, limit, |s| Cursor::new(s.created_at, s.call_id));
+
+    Ok(Json(CursorPage::new(snapshots, next_cursor)))
+}
+
+/// Запросить удаление снимка. Снимок сразу скрывается из галереи,
+/// файл удаляется фоновой задачей
+#[utoipa::path(
+    delete,
+    path = "/api/v1/security/intercom/snapshots/{call_id}",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("call_id" = Uuid, Path, description = "ID звонка домофона")
+    ),
+    responses(
+        (status = 200, description = "Снимок будет удалён", body = SuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Снимки доступны только жителям квартиры"),
+        (status = 404, description = "Снимок не найден")
+    )
+)]
+pub async fn delete_snapshot(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(call_id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let call: Option<(Option<Uuid>,)> = sqlx::query_as(
+        "SELECT apartment_id FROM intercom_calls WHERE id = $1 AND snapshot_url IS NOT NULL",
+    )
+    .bind(call_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let apartment_id = call
+        .and_then(|(apartment_id,)| apartment_id)
+        .ok_or_else(|| AppError::NotFound("Снимок не найден".to_string()))?;
+
+    check_apartment_member(&state, apartment_id, auth_user.user_id).await?;
+
+    sqlx::query(
+        r#"
+        UPDATE intercom_calls
+        SET snapshot_delete_requested_at = COALESCE(snapshot_delete_requested_at, NOW())
+        WHERE id = $1
+        "#,
+    )
+    .bind(call_id)
+    .execute(&state.pool)
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Снимок будет удалён"
+    })))
+}
+
+/// Код домофона квартиры видит и меняет только её собственник
+async fn check_apartment_owner(state: &AppState, apartment_id: Uuid, user_id: Uuid) -> AppResult<()> {
+    let is_owner: Option<(i32,)> =
+        sqlx::query_as("SELECT 1 FROM apartments WHERE id = $1 AND owner_id = $2")
+            .bind(apartment_id)
+            .bind(user_id)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    is_owner.map(|_| ()).ok_or(AppError::Forbidden)
+}
+
+/// Снимки квартиры видят только её собственник и житель
+async fn check_apartment_member(state: &AppState, apartment_id: Uuid, user_id: Uuid) -> AppResult<()> {
+    let is_member: Option<(i32,)> = sqlx::query_as(
+        "SELECT 1 FROM apartments WHERE id = $1 AND (owner_id = $2 OR resident_id = $2)",
+    )
+    .bind(apartment_id)
+    .bind(user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if is_member.is_none() {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Проверить код доступа или QR гостя на посту охраны
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/guard/validate",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если охранник работает в нескольких")
+    ),
+    request_body = GuardValidateCodeRequest,
+    responses(
+        (status = 200, description = "Результат проверки", body = GuardValidateCodeResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только охране ЖК")
+    )
+)]
+pub async fn guard_validate_code(
+    State(state): State<AppState>,
+    guard: GuardUser,
+    Json(payload): Json<GuardValidateCodeRequest>,
+) -> AppResult<Json<GuardValidateCodeResponse>> {
+    // QR содержит код с префиксом LOCALHOOD:
+    let code = payload.code.trim();
+    let code = code.strip_prefix("LOCALHOOD:").unwrap_or(code);
+
+    let guest = sqlx::query_as::<_, ExpectedGuestResponse>(&format!(
+        "{} WHERE g.complex_id = $1 AND g.access_code = $2",
+        EXPECTED_GUEST_SELECT
+    ))
+    .bind(guard.complex_id)
+    .bind(code)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let Some(guest) = guest else {
+        return Ok(Json(GuardValidateCodeResponse {
+            valid: false,
+            reason: Some("Пропуск не найден".to_string()),
+            guest: None,
+        }));
+    };
+
+    let reason = match guest.status {
+        _ if guest.suspended_at.is_some() => Some("Пропуск приостановлен до проверки председателем"),
+        GuestAccessStatus::AwaitingApproval => Some("Пропуск ещё не подтверждён"),
+        GuestAccessStatus::Pending if guest.expires_at <= chrono::Utc::now() => Some("Пропуск истёк"),
+        GuestAccessStatus::Pending => None,
+        GuestAccessStatus::Active => Some("Гость уже въехал"),
+        GuestAccessStatus::Expired => Some("Пропуск истёк"),
+        GuestAccessStatus::Completed => Some("Пропуск уже использован"),
+        GuestAccessStatus::Cancelled => Some("Пропуск отменён жителем"),
+        GuestAccessStatus::Declined => Some("Пропуск отклонён"),
+    };
+
+    Ok(Json(GuardValidateCodeResponse {
+        valid: reason.is_none(),
+        reason: reason.map(str::to_string),
+        guest: Some(guest),
+    }))
+}
+
+/// Зафиксировать ручное открытие шлагбаума с указанием причины
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/guard/manual-open",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если охранник работает в нескольких")
+    ),
+    request_body = GuardManualOpenRequest,
+    responses(
+        (status = 200, description = "Открытие зафиксировано", body = BarrierManualOpening),
+        (status = 400, description = "Не указана причина"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только охране ЖК"),
+        (status = 502, description = "Шлагбаум не ответил")
+    )
+)]
+pub async fn guard_manual_open(
+    State(state): State<AppState>,
+    guard: GuardUser,
+    request_id: RequestId,
+    Json(payload): Json<GuardManualOpenRequest>,
+) -> AppResult<Json<BarrierManualOpening>> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(AppError::BadRequest("Укажите причину открытия".to_string()));
+    }
+
+    // Въезд по гостевому пропуску проходит те же правила, что и по коду
+    if let Some(access_id) = payload.guest_access_id {
+        let access = sqlx::query_as::<_, GuestAccess>(
+            "SELECT * FROM guest_access WHERE id = $1 AND complex_id = $2",
+        )
+        .bind(access_id)
+        .bind(guard.complex_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Гостевой пропуск не найден".to_string()))?;
+
+        if access.suspended_at.is_some() {
+            return Err(AppError::EntryDenied(
+                "Пропуск приостановлен до проверки председателем".to_string(),
+            ));
+        }
+
+        AnomalyService::inspect_entry(
+            &state.pool,
+            BarrierPassage {
+                complex_id: guard.complex_id,
+                user_id: None,
+                guest_access_id: Some(access.id),
+                vehicle_number: payload
+                    .vehicle_number
+                    .as_deref()
+                    .or(access.vehicle_number.as_deref()),
+            },
+        )
+        .await?;
+    }
+
+    // Без barrier_id охранник открыл вручную на месте — только фиксируем
+    if let Some(barrier_id) = payload.barrier_id {
+        let barrier = sqlx::query_as::<_, Barrier>(
+            "SELECT * FROM barriers WHERE id = $1 AND complex_id = $2",
+        )
+        .bind(barrier_id)
+        .bind(guard.complex_id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Шлагбаум не найден".to_string()))?;
+
+        barrier_driver::open(&barrier).await?;
+    }
+
+    let opening = sqlx::query_as::<_, BarrierManualOpening>(
+        r#"
+        INSERT INTO barrier_manual_openings
+            (complex_id, barrier_id, worker_id, user_id, reason, vehicle_number, guest_access_id)
+        VALUES ($1, $2, $3, $4, $5, $6,
+                (SELECT id FROM guest_access WHERE id = $7 AND complex_id = $1))
+        RETURNING *
+        "#,
+    )
+    .bind(guard.complex_id)
+    .bind(payload.barrier_id)
+    .bind(guard.worker_id)
+    .bind(guard.user_id)
+    .bind(reason)
+    .bind(&payload.vehicle_number)
+    .bind(payload.guest_access_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: guard.user_id,
+            complex_id: Some(guard.complex_id),
+            action: "guard_manual_open",
+            entity_type: "barrier",
+            entity_id: payload.barrier_id,
+            old_value: None,
+            new_value: Some(json!(opening)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(opening))
+}
+
+/// Лента безопасности: подозрительные события на шлагбауме
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/events",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(SecurityEventsQuery),
+    responses(
+        (status = 200, description = "События безопасности", body = SecurityEventsPage),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно охране и председателю")
+    )
+)]
+pub async fn list_security_events(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<SecurityEventsQuery>,
+) -> AppResult<Json<CursorPage<SecurityEvent>>> {
+    check_security_staff(&state, query.complex_id, &auth_user).await?;
+
+    let limit = query.limit.unwrap_or(50).clamp(1, 100);
+    let cursor = query
+        .cursor
+        .as_deref()
+        .map(|c| Cursor::decode(c).ok_or_else(|| AppError::BadRequest("Неверный курсор".to_string())))
+        .transpose()?;
+
+    let mut events = sqlx::query_as::<_, SecurityEvent>(
+        r#"
+        SELECT * FROM security_events
+        WHERE complex_id = $1
+          AND (NOT $2 OR reviewed_at IS NULL)
+          AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4))
+        ORDER BY created_at DESC, id DESC
+        LIMIT $5
+        "#,
+    )
+    .bind(query.complex_id)
+    .bind(query.unreviewed.unwrap_or(false))
+    .bind(cursor.map(|c| c.created_at))
+    .bind(cursor.map(|c| c.id))
+    .bind(limit + 1)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let next_cursor = take_page(&mut events, limit, |e| Cursor::new(e.created_at, e.id));
+
+    Ok(Json(CursorPage::new(events, next_cursor)))
+}
+
+/// Отметить событие проверенным и при необходимости снять приостановку пропуска
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/events/{id}/review",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID события")
+    ),
+    request_body = ReviewSecurityEventRequest,
+    responses(
+        (status = 200, description = "Событие проверено", body = SecurityEvent),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю"),
+        (status = 404, description = "Событие не найдено")
+    )
+)]
+pub async fn review_security_event(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<ReviewSecurityEventRequest>,
+) -> AppResult<Json<SecurityEvent>> {
+    let event = sqlx::query_as::<_, SecurityEvent>("SELECT * FROM security_events WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Событие не найдено".to_string()))?;
+
+    PermissionService::require(&state.pool, &auth_user, event.complex_id, Permission::ManageOsi)
+        .await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    let reviewed = sqlx::query_as::<_, SecurityEvent>(
+        "UPDATE security_events SET reviewed_by = $2, reviewed_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    let lift = payload.lift_suspension && event.guest_access_id.is_some();
+    // Снимаем приостановку только с пропуска ЖК, которому принадлежит событие
+    if lift {
+        sqlx::query("UPDATE guest_access SET suspended_at = NULL WHERE id = $1 AND complex_id = $2")
+            .bind(event.guest_access_id)
+            .bind(event.complex_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(event.complex_id),
+            action: "review_security_event",
+            entity_type: "security_event",
+            entity_id: Some(id),
+            old_value: None,
+            new_value: Some(json!({"event_type": event.event_type, "lift_suspension": lift})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(reviewed))
+}
+
+/// Настройки автоматической приостановки пропусков
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/anomaly-settings",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Текущие настройки", body = AnomalySettings),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn get_anomaly_settings(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+) -> AppResult<Json<AnomalySettings>> {
+    let complex_id = complex.complex_id()?;
+
+    Ok(Json(AnomalySettings {
+        auto_suspend_passes: AnomalyService::auto_suspend_enabled(&state.pool, complex_id).await?,
+    }))
+}
+
+/// Включить или выключить автоматическую приостановку пропусков
+#[utoipa::path(
+    put,
+    path = "/api/v1/security/anomaly-settings",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body = AnomalySettings,
+    responses(
+        (status = 200, description = "Настройки обновлены", body = AnomalySettings),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn update_anomaly_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Json(payload): Json<AnomalySettings>,
+) -> AppResult<Json<AnomalySettings>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let previous = AnomalyService::auto_suspend_enabled(&state.pool, complex_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE complexes SET auto_suspend_anomalous_passes = $2, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(complex_id)
+    .bind(payload.auto_suspend_passes)
+    .execute(&mut *tx)
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id,
+            entity_type: "complex",
+            entity_id: complex_id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!({"auto_suspend_anomalous_passes": previous}),
+            new_value: json!({"auto_suspend_anomalous_passes": payload.auto_suspend_passes}),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    AuditService::record(
+        &mut *tx,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "update_anomaly_settings",
+            entity_type: "complex",
+            entity_id: Some(complex_id),
+            old_value: Some(json!({"auto_suspend_passes": previous})),
+            new_value: Some(json!({"auto_suspend_passes": payload.auto_suspend_passes})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(payload))
+}
+
+/// Привязка сессий председателя к устройству
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/device-binding-settings",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Текущие настройки", body = DeviceBindingSettings),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа")
+    )
+)]
+pub async fn get_device_binding_settings(
+    State(state): State<AppState>,
+    complex: ComplexScope,
+) -> AppResult<Json<DeviceBindingSettings>> {
+    let complex_id = complex.complex_id()?;
+
+    Ok(Json(DeviceBindingSettings {
+        device_binding: device_binding_enabled(&state, complex_id).await?,
+    }))
+}
+
+/// Включить или отключить привязку сессий председателя к устройству.
+/// Изменение применяется со следующего входа
+#[utoipa::path(
+    put,
+    path = "/api/v1/security/device-binding-settings",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body = DeviceBindingSettings,
+    responses(
+        (status = 200, description = "Настройки обновлены", body = DeviceBindingSettings),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn update_device_binding_settings(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Json(payload): Json<DeviceBindingSettings>,
+) -> AppResult<Json<DeviceBindingSettings>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let previous = device_binding_enabled(&state, complex_id).await?;
+
+    let mut tx = state.pool.begin().await?;
+
+    sqlx::query(
+        "UPDATE complexes SET device_binding_enabled = $2, updated_at = NOW() WHERE id = $1",
+    )
+    .bind(complex_id)
+    .bind(payload.device_binding)
+    .execute(&mut *tx)
+    .await?;
+
+    FieldHistoryService::record(
+        &mut *tx,
+        NewFieldChange {
+            complex_id,
+            entity_type: "complex",
+            entity_id: complex_id,
+            changed_by: Some(auth_user.user_id),
+            old_value: json!({"device_binding_enabled": previous}),
+            new_value: json!({"device_binding_enabled": payload.device_binding}),
+            request_id: Some(request_id.0.clone()),
+        },
+    )
+    .await?;
+
+    AuditService::record(
+        &mut *tx,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "update_device_binding_settings",
+            entity_type: "complex",
+            entity_id: Some(complex_id),
+            old_value: Some(json!({"device_binding": previous})),
+            new_value: Some(json!({"device_binding": payload.device_binding})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Json(payload))
+}
+
+/// Гости, приглашённые на сегодня
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/guard/expected-guests",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если охранник работает в нескольких")
+    ),
+    responses(
+        (status = 200, description = "Ожидаемые гости", body = Vec<ExpectedGuestResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только охране ЖК")
+    )
+)]
+pub async fn guard_expected_guests(
+    State(state): State<AppState>,
+    guard: GuardUser,
+) -> AppResult<Json<Vec<ExpectedGuestResponse>>> {
+    let guests = sqlx::query_as::<_, ExpectedGuestResponse>(&format!(
+        r#"{}
+        WHERE g.complex_id = $1
+          AND g.status IN ('pending', 'active')
+          AND g.expires_at >= date_trunc('day', NOW())
+          AND g.created_at < date_trunc('day', NOW()) + INTERVAL '1 day'
+        ORDER BY g.expires_at"#,
+        EXPECTED_GUEST_SELECT
+    ))
+    .bind(guard.complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(guests))
+}
+
+/// Чёрный список ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/blacklist",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    responses(
+        (status = 200, description = "Активные записи чёрного списка", body = Vec<BlacklistEntry>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn list_blacklist(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+) -> AppResult<Json<Vec<BlacklistEntry>>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let entries = sqlx::query_as::<_, BlacklistEntry>(
+        r#"
+        SELECT * FROM barrier_blacklist
+        WHERE complex_id = $1 AND is_active = true
+          AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(entries))
+}
+
+/// Добавить автомобиль или человека в чёрный список ЖК
+#[utoipa::path(
+    post,
+    path = "/api/v1/security/blacklist",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько")
+    ),
+    request_body = CreateBlacklistEntryRequest,
+    responses(
+        (status = 200, description = "Запись добавлена", body = BlacklistEntry),
+        (status = 400, description = "Не указан госномер или телефон"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn add_to_blacklist(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    request_id: RequestId,
+    Json(payload): Json<CreateBlacklistEntryRequest>,
+) -> AppResult<Json<BlacklistEntry>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let vehicle_number = payload
+        .vehicle_number
+        .as_deref()
+        .map(normalize_vehicle_number)
+        .filter(|n| !n.is_empty());
+    let person_phone = payload
+        .person_phone
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+
+    if vehicle_number.is_none() && person_phone.is_none() {
+        return Err(AppError::BadRequest(
+            "Укажите госномер или телефон".to_string(),
+        ));
+    }
+
+    if let Some(phone) = person_phone {
+        if !validate_phone(phone) {
+            return Err(AppError::Validation("Неверный формат телефона".to_string()));
+        }
+    }
+
+    if payload.reason.trim().is_empty() {
+        return Err(AppError::BadRequest("Укажите причину".to_string()));
+    }
+
+    let entry = sqlx::query_as::<_, BlacklistEntry>(
+        r#"
+        INSERT INTO barrier_blacklist (complex_id, vehicle_number, person_name, person_phone, reason, expires_at, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(complex_id)
+    .bind(&vehicle_number)
+    .bind(&payload.person_name)
+    .bind(person_phone)
+    .bind(payload.reason.trim())
+    .bind(payload.expires_at)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "add_to_blacklist",
+            entity_type: "barrier_blacklist",
+            entity_id: Some(entry.id),
+            old_value: None,
+            new_value: Some(json!(entry)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(entry))
+}
+
+/// Убрать запись из чёрного списка
+#[utoipa::path(
+    delete,
+    path = "/api/v1/security/blacklist/{id}",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID записи чёрного списка")
+    ),
+    responses(
+        (status = 200, description = "Запись удалена", body = SuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю"),
+        (status = 404, description = "Запись не найдена")
+    )
+)]
+pub async fn remove_from_blacklist(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let entry = sqlx::query_as::<_, BlacklistEntry>(
+        "SELECT * FROM barrier_blacklist WHERE id = $1 AND is_active = true",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Запись не найдена".to_string()))?;
+
+    PermissionService::require(&state.pool, &auth_user, entry.complex_id, Permission::ManageOsi)
+        .await?;
+
+    sqlx::query("UPDATE barrier_blacklist SET is_active = false WHERE id = $1")
+        .bind(id)
+        .execute(&state.pool)
+        .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(entry.complex_id),
+            action: "remove_from_blacklist",
+            entity_type: "barrier_blacklist",
+            entity_id: Some(id),
+            old_value: Some(json!({"is_active": true})),
+            new_value: Some(json!({"is_active": false})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": "Запись удалена из чёрного списка"
+    })))
+}
+
+/// Журнал отказов во въезде по чёрному списку
+#[utoipa::path(
+    get,
+    path = "/api/v1/security/blacklist/denials",
+    tag = "security",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
+        ("page" = Option<i64>, Query, description = "Номер страницы"),
+        ("limit" = Option<i64>, Query, description = "Количество записей")
+    ),
+    responses(
+        (status = 200, description = "Отказы во въезде", body = Vec<BarrierDenialResponse>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доступно только председателю")
+    )
+)]
+pub async fn list_blacklist_denials(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    Query(pagination): Query<PaginationQuery>,
+) -> AppResult<Json<Vec<BarrierDenialResponse>>> {
+    let complex_id = complex.complex_id()?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let limit = pagination.limit.unwrap_or(50).min(100);
+    let offset = pagination.page.unwrap_or(0) * limit;
+
+    let denials = sqlx::query_as::<_, BarrierDenialResponse>(
+        r#"
+        SELECT d.id, d.barrier_id, d.blacklist_id, d.guest_access_id, d.vehicle_number, b.reason, d.created_at
+        FROM barrier_denials d
+        JOIN barrier_blacklist b ON b.id = d.blacklist_id
+        WHERE d.complex_id = $1
+        ORDER BY d.created_at DESC
+        LIMIT $2 OFFSET $3
+        "#,
+    )
+    .bind(complex_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(denials))
+}
+
+async fn device_binding_enabled(state: &AppState, complex_id: Uuid) -> AppResult<bool> {
+    let enabled: Option<(bool,)> =
+        sqlx::query_as("SELECT device_binding_enabled FROM complexes WHERE id = $1")
+            .bind(complex_id)
+            .fetch_optional(&state.pool)
+            .await?;
+
+    enabled
+        .map(|(enabled,)| enabled)
+        .ok_or_else(|| AppError::NotFound("ЖК не найден".to_string()))
+}
+
+/// Председатель или охранник ЖК
+async fn check_security_staff(state: &AppState, complex_id: Uuid, auth_user: &AuthUser) -> AppResult<()> {
+    if PermissionService::has(&state.pool, auth_user, complex_id, Permission::ManageOsi).await? {
+        return Ok(());
+    }
+
+    let is_guard: Option<(i32,)> = sqlx::query_as(
+        r#"
+        SELECT 1 FROM osi_workers w
+        JOIN osi o ON o.id = w.osi_id
+        WHERE o.complex_id = $1 AND w.user_id = $2 AND w.role = 'guard' AND w.is_active = true
+        "#,
+    )
+    .bind(complex_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    is_guard.map(|_| ()).ok_or(AppError::Forbidden)
 }
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, Query, State},
+    routing::get,
+    Form, Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::AppState;
+use crate::services::{sms_provider, SmsService};
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/status/:provider", get(sms_status_callback).post(sms_status_callback))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SmsCallbackQuery {
+    token: String,
+}
+
+/// Отчёт провайдера о доставке SMS. Провайдеры не подписывают запросы,
+/// поэтому секрет передаётся в адресе, указанном в их кабинете
+#[utoipa::path(
+    post,
+    path = "/api/v1/sms/status/{provider}",
+    tag = "Уведомления",
+    params(
+        ("provider" = String, Path, description = "Провайдер: mobizon или smsc"),
+        ("token" = String, Query, description = "Значение SMS_CALLBACK_SECRET")
+    ),
+    request_body(content = String, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Отчёт принят"),
+        (status = 401, description = "Неверный токен"),
+        (status = 404, description = "Неизвестный провайдер")
+    )
+)]
+pub async fn sms_status_callback(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<SmsCallbackQuery>,
+    Form(params): Form<HashMap<String, String>>,
+) -> AppResult<Json<Value>> {
+    if !SmsService::new(state.config.clone()).verify_callback_secret(&query.token) {
+        tracing::warn!("Rejected SMS status callback with invalid token");
+        return Err(AppError::Unauthorized);
+    }
+
+    let provider = sms_provider::by_name(&state.config, &provider)
+        .ok_or_else(|| AppError::NotFound("SMS-провайдер не найден".to_string()))?;
+
+    // Промежуточные статусы и неизвестные сообщения подтверждаем, чтобы провайдер не повторял отчёт
+    if let Some(report) = provider.parse_status(&params) {
+        if !SmsService::record_delivery(&state.pool, provider.name(), &report).await? {
+            tracing::warn!(
+                "SMS status for unknown {} message {}",
+                provider.name(),
+                report.message_id
+            );
+        }
+    }
+
+    Ok(Json(json!({"ok": true})))
+}
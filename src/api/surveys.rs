@@ -0,0 +1,354 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{get_user_complexes, AppState, AuthUser, RequestId};
+use crate::models::{
+    CreateSurveyRequest, JobType, NewAuditLog, NotificationFanoutPayload, NotificationType,
+    Permission, SubmitSurveyRequest, Survey, SurveyResponse, SurveyResults,
+};
+use crate::services::{AuditService, JobService, PermissionService, SurveyService};
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_surveys).post(create_survey))
+        .route("/:id", get(get_survey))
+        .route("/:id/responses", post(submit_survey))
+        .route("/:id/results", get(get_survey_results))
+        .route("/:id/export", get(export_survey))
+        .route("/:id/close", post(close_survey))
+}
+
+/// Успешный ответ
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct SurveySuccessResponse {
+    pub success: bool,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SurveysQuery {
+    /// ЖК; можно не указывать, если он у пользователя один
+    pub complex_id: Option<Uuid>,
+}
+
+/// Доступ к опросам ЖК: `Some(true)` — управляющий (право ManageOsi), `Some(false)` — житель
+async fn survey_access(state: &AppState, auth_user: &AuthUser, complex_id: Uuid) -> AppResult<Option<bool>> {
+    if PermissionService::has(&state.pool, auth_user, complex_id, Permission::ManageOsi).await? {
+        return Ok(Some(true));
+    }
+    let complexes = get_user_complexes(&state.pool, auth_user.user_id).await?;
+    Ok(complexes.contains(&complex_id).then_some(false))
+}
+
+/// Опрос и права текущего пользователя на него
+async fn find_survey(state: &AppState, auth_user: &AuthUser, id: Uuid) -> AppResult<(Survey, bool)> {
+    let survey = sqlx::query_as::<_, Survey>("SELECT * FROM surveys WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Опрос не найден".to_string()))?;
+
+    let can_manage = survey_access(state, auth_user, survey.complex_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Опрос не найден".to_string()))?;
+
+    Ok((survey, can_manage))
+}
+
+/// Опросы ЖК, новые сверху
+#[utoipa::path(
+    get,
+    path = "/api/v1/surveys",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(SurveysQuery),
+    responses(
+        (status = 200, description = "Опросы", body = Vec<Survey>),
+        (status = 400, description = "У пользователя несколько ЖК, укажите complex_id"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет доступа к ЖК")
+    )
+)]
+pub async fn list_surveys(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Query(query): Query<SurveysQuery>,
+) -> AppResult<Json<Vec<Survey>>> {
+    let complex_id = match query.complex_id {
+        Some(complex_id) => complex_id,
+        None => match get_user_complexes(&state.pool, auth_user.user_id).await?.as_slice() {
+            [only] => *only,
+            _ => {
+                return Err(AppError::BadRequest(
+                    "У вас несколько ЖК, укажите complex_id".to_string(),
+                ))
+            }
+        },
+    };
+    survey_access(&state, &auth_user, complex_id)
+        .await?
+        .ok_or(AppError::Forbidden)?;
+
+    let surveys = sqlx::query_as::<_, Survey>(
+        "SELECT * FROM surveys WHERE complex_id = $1 ORDER BY created_at DESC LIMIT 100",
+    )
+    .bind(complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(surveys))
+}
+
+/// Создать опрос; жители получают уведомление
+#[utoipa::path(
+    post,
+    path = "/api/v1/surveys",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    request_body = CreateSurveyRequest,
+    responses(
+        (status = 200, description = "Опрос создан", body = SurveyResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ"),
+        (status = 422, description = "Неверные вопросы")
+    )
+)]
+pub async fn create_survey(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Json(payload): Json<CreateSurveyRequest>,
+) -> AppResult<Json<SurveyResponse>> {
+    PermissionService::require(&state.pool, &auth_user, payload.complex_id, Permission::ManageOsi).await?;
+
+    let (survey, questions) = SurveyService::create(&state.pool, auth_user.user_id, &payload).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(survey.complex_id),
+            action: "create_survey",
+            entity_type: "survey",
+            entity_id: Some(survey.id),
+            old_value: None,
+            new_value: Some(json!({"title": survey.title, "questions": questions.len()})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    JobService::enqueue(
+        &state.pool,
+        JobType::NotificationFanout,
+        &NotificationFanoutPayload {
+            complex_id: survey.complex_id,
+            notification_type: NotificationType::Voting,
+            title: survey.title.clone(),
+            body: Some("Новый опрос жителей — поделитесь мнением".to_string()),
+            data: Some(json!({"survey_id": survey.id})),
+            exclude_user_id: Some(auth_user.user_id),
+        },
+    )
+    .await?;
+
+    Ok(Json(SurveyResponse {
+        survey,
+        questions,
+        user_responded: false,
+    }))
+}
+
+/// Опрос с вопросами
+#[utoipa::path(
+    get,
+    path = "/api/v1/surveys/{id}",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID опроса")
+    ),
+    responses(
+        (status = 200, description = "Опрос", body = SurveyResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Опрос не найден")
+    )
+)]
+pub async fn get_survey(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<SurveyResponse>> {
+    let (survey, _) = find_survey(&state, &auth_user, id).await?;
+    let questions = SurveyService::questions(&state.pool, id).await?;
+
+    let (user_responded,): (bool,) = sqlx::query_as(
+        "SELECT EXISTS(SELECT 1 FROM survey_responses WHERE survey_id = $1 AND user_id = $2)",
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(SurveyResponse {
+        survey,
+        questions,
+        user_responded,
+    }))
+}
+
+/// Ответить на опрос
+#[utoipa::path(
+    post,
+    path = "/api/v1/surveys/{id}/responses",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID опроса")
+    ),
+    request_body = SubmitSurveyRequest,
+    responses(
+        (status = 200, description = "Ответы приняты", body = SurveySuccessResponse),
+        (status = 400, description = "Опрос завершён"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Опрос не найден"),
+        (status = 409, description = "Вы уже ответили"),
+        (status = 422, description = "Неверные или неполные ответы")
+    )
+)]
+pub async fn submit_survey(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<SubmitSurveyRequest>,
+) -> AppResult<Json<SurveySuccessResponse>> {
+    let (survey, _) = find_survey(&state, &auth_user, id).await?;
+
+    SurveyService::submit(&state.pool, &survey, auth_user.user_id, &payload).await?;
+
+    Ok(Json(SurveySuccessResponse { success: true }))
+}
+
+/// Сводные итоги с разбивкой по корпусам. Жителям — после завершения опроса
+#[utoipa::path(
+    get,
+    path = "/api/v1/surveys/{id}/results",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID опроса")
+    ),
+    responses(
+        (status = 200, description = "Итоги", body = SurveyResults),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Итоги будут доступны после завершения опроса"),
+        (status = 404, description = "Опрос не найден")
+    )
+)]
+pub async fn get_survey_results(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<SurveyResults>> {
+    let (survey, can_manage) = find_survey(&state, &auth_user, id).await?;
+    if !can_manage && survey.is_open() {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(Json(SurveyService::results(&state.pool, &survey).await?))
+}
+
+/// Выгрузка ответов в CSV
+#[utoipa::path(
+    get,
+    path = "/api/v1/surveys/{id}/export",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID опроса")
+    ),
+    responses(
+        (status = 200, description = "CSV, строка на ответившего", content_type = "text/csv"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ"),
+        (status = 404, description = "Опрос не найден")
+    )
+)]
+pub async fn export_survey(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let (survey, can_manage) = find_survey(&state, &auth_user, id).await?;
+    if !can_manage {
+        return Err(AppError::Forbidden);
+    }
+
+    let csv = SurveyService::export_csv(&state.pool, &survey).await?;
+
+    // BOM, чтобы Excel открыл кириллицу в UTF-8
+    let headers = [
+        (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"survey-{}.csv\"", survey.id),
+        ),
+    ];
+    Ok((headers, format!("\u{feff}{}", csv)))
+}
+
+/// Завершить опрос досрочно
+#[utoipa::path(
+    post,
+    path = "/api/v1/surveys/{id}/close",
+    tag = "surveys",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID опроса")
+    ),
+    responses(
+        (status = 200, description = "Опрос завершён", body = Survey),
+        (status = 400, description = "Опрос уже закрыт"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нужно право управления ОСИ"),
+        (status = 404, description = "Опрос не найден")
+    )
+)]
+pub async fn close_survey(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Survey>> {
+    let (survey, can_manage) = find_survey(&state, &auth_user, id).await?;
+    if !can_manage {
+        return Err(AppError::Forbidden);
+    }
+
+    let closed = SurveyService::close(&state.pool, survey.id).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(survey.complex_id),
+            action: "close_survey",
+            entity_type: "survey",
+            entity_id: Some(survey.id),
+            old_value: Some(json!({"status": survey.status})),
+            new_value: Some(json!({"status": closed.status, "responses_count": closed.responses_count})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(closed))
+}
@@ -0,0 +1,54 @@
+use axum::{body::Bytes, extract::State, http::HeaderMap, routing::post, Json, Router};
+use serde_json::{json, Value};
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::AppState;
+use crate::models::TelegramUpdate;
+use crate::services::TelegramService;
+
+/// Заголовок с секретом, который Telegram передаёт в каждом вызове вебхука
+const TELEGRAM_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/webhook", post(telegram_webhook))
+}
+
+/// Обновления Telegram-бота: привязка чата по `/start <код>` и отписка по `/stop`
+#[utoipa::path(
+    post,
+    path = "/api/v1/telegram/webhook",
+    tag = "Уведомления",
+    params(
+        ("X-Telegram-Bot-Api-Secret-Token" = String, Header, description = "Секрет вебхука")
+    ),
+    request_body(content = String, content_type = "application/json"),
+    responses(
+        (status = 200, description = "Обновление обработано"),
+        (status = 400, description = "Некорректное обновление"),
+        (status = 401, description = "Неверный секрет")
+    )
+)]
+pub async fn telegram_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Json<Value>> {
+    let telegram = TelegramService::new(state.config.clone());
+
+    let secret = headers
+        .get(TELEGRAM_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(AppError::Unauthorized)?;
+
+    if !telegram.verify_webhook_secret(secret) {
+        tracing::warn!("Rejected Telegram webhook with invalid secret");
+        return Err(AppError::Unauthorized);
+    }
+
+    let update: TelegramUpdate = serde_json::from_slice(&body)
+        .map_err(|e| AppError::BadRequest(format!("Некорректное обновление: {}", e)))?;
+
+    telegram.handle_update(&state.pool, &update).await?;
+
+    Ok(Json(json!({"ok": true})))
+}
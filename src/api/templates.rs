@@ -0,0 +1,302 @@
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::{AppState, AuthUser};
+use crate::models::{
+    ContentTemplate, CreateTemplateRequest, Permission, RenderTemplateRequest,
+    RenderedTemplateResponse, TemplateKind, TemplatesQuery, UpdateTemplateRequest,
+};
+use crate::services::PermissionService;
+use crate::utils::render_template;
+
+/// Успешный ответ
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct TemplateSuccessResponse {
+    pub success: bool,
+}
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/:id/templates", get(list_templates).post(create_template))
+        .route(
+            "/:id/templates/:template_id",
+            put(update_template).delete(delete_template),
+        )
+        .route("/:id/templates/:template_id/render", post(render))
+}
+
+/// Шаблон, доступный ЖК: системный или собственный
+async fn get_template(
+    state: &AppState,
+    complex_id: Uuid,
+    template_id: Uuid,
+) -> AppResult<ContentTemplate> {
+    sqlx::query_as::<_, ContentTemplate>(
+        "SELECT * FROM content_templates WHERE id = $1 AND (complex_id IS NULL OR complex_id = $2)",
+    )
+    .bind(template_id)
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Шаблон не найден".to_string()))
+}
+
+fn validate_options(kind: TemplateKind, options: Option<&Vec<String>>) -> AppResult<()> {
+    if kind == TemplateKind::Voting && options.map(|o| o.len()).unwrap_or(0) < 2 {
+        return Err(AppError::BadRequest(
+            "Минимум 2 варианта ответа".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Шаблоны голосований и объявлений ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/complexes/{id}/templates",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        TemplatesQuery
+    ),
+    responses(
+        (status = 200, description = "Системные и собственные шаблоны", body = Vec<ContentTemplate>),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn list_templates(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(complex_id): Path<Uuid>,
+    Query(query): Query<TemplatesQuery>,
+) -> AppResult<Json<Vec<ContentTemplate>>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let templates = sqlx::query_as::<_, ContentTemplate>(
+        r#"
+        SELECT * FROM content_templates
+        WHERE (complex_id IS NULL OR complex_id = $1)
+          AND is_active = true
+          AND ($2::template_kind IS NULL OR kind = $2)
+        ORDER BY complex_id NULLS LAST, kind, title
+        "#,
+    )
+    .bind(complex_id)
+    .bind(query.kind)
+    .fetch_all(&state.pool)
+    .await?;
+
+    Ok(Json(templates))
+}
+
+/// Создать шаблон ЖК
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/templates",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК")
+    ),
+    request_body = CreateTemplateRequest,
+    responses(
+        (status = 200, description = "Шаблон создан", body = ContentTemplate),
+        (status = 400, description = "Неверные данные"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав")
+    )
+)]
+pub async fn create_template(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(complex_id): Path<Uuid>,
+    Json(payload): Json<CreateTemplateRequest>,
+) -> AppResult<Json<ContentTemplate>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+    validate_options(payload.kind, payload.options.as_ref())?;
+
+    let template = sqlx::query_as::<_, ContentTemplate>(
+        r#"
+        INSERT INTO content_templates (complex_id, kind, title, body, options, category, created_by)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING *
+        "#,
+    )
+    .bind(complex_id)
+    .bind(payload.kind)
+    .bind(&payload.title)
+    .bind(&payload.body)
+    .bind(payload.options.map(|o| json!(o)))
+    .bind(&payload.category)
+    .bind(auth_user.user_id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(template))
+}
+
+/// Изменить шаблон ЖК
+#[utoipa::path(
+    put,
+    path = "/api/v1/complexes/{id}/templates/{template_id}",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        ("template_id" = Uuid, Path, description = "ID шаблона")
+    ),
+    request_body = UpdateTemplateRequest,
+    responses(
+        (status = 200, description = "Шаблон обновлён", body = ContentTemplate),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Системные шаблоны не редактируются"),
+        (status = 404, description = "Шаблон не найден")
+    )
+)]
+pub async fn update_template(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((complex_id, template_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<UpdateTemplateRequest>,
+) -> AppResult<Json<ContentTemplate>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let template = get_template(&state, complex_id, template_id).await?;
+    if template.complex_id.is_none() {
+        return Err(AppError::Forbidden);
+    }
+
+    if !payload.options.is_absent() {
+        validate_options(template.kind, payload.options.value())?;
+    }
+
+    let template = sqlx::query_as::<_, ContentTemplate>(
+        r#"
+        UPDATE content_templates SET
+            title = COALESCE($2, title),
+            body = COALESCE($3, body),
+            options = $4,
+            category = $5,
+            is_active = COALESCE($6, is_active),
+            updated_at = NOW()
+        WHERE id = $1
+        RETURNING *
+        "#,
+    )
+    .bind(template.id)
+    .bind(&payload.title)
+    .bind(&payload.body)
+    .bind(payload.options.map(|o| json!(o)).apply(template.options))
+    .bind(payload.category.apply(template.category))
+    .bind(payload.is_active)
+    .fetch_one(&state.pool)
+    .await?;
+
+    Ok(Json(template))
+}
+
+/// Удалить шаблон ЖК
+#[utoipa::path(
+    delete,
+    path = "/api/v1/complexes/{id}/templates/{template_id}",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        ("template_id" = Uuid, Path, description = "ID шаблона")
+    ),
+    responses(
+        (status = 200, description = "Шаблон удалён", body = TemplateSuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Системные шаблоны не удаляются"),
+        (status = 404, description = "Шаблон не найден")
+    )
+)]
+pub async fn delete_template(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((complex_id, template_id)): Path<(Uuid, Uuid)>,
+) -> AppResult<Json<Value>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let template = get_template(&state, complex_id, template_id).await?;
+    if template.complex_id.is_none() {
+        return Err(AppError::Forbidden);
+    }
+
+    sqlx::query("DELETE FROM content_templates WHERE id = $1")
+        .bind(template.id)
+        .execute(&state.pool)
+        .await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Заполнить шаблон значениями
+#[utoipa::path(
+    post,
+    path = "/api/v1/complexes/{id}/templates/{template_id}/render",
+    tag = "complexes",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID ЖК"),
+        ("template_id" = Uuid, Path, description = "ID шаблона")
+    ),
+    request_body = RenderTemplateRequest,
+    responses(
+        (status = 200, description = "Готовый текст", body = RenderedTemplateResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Недостаточно прав"),
+        (status = 404, description = "Шаблон не найден")
+    )
+)]
+pub async fn render(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((complex_id, template_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<RenderTemplateRequest>,
+) -> AppResult<Json<RenderedTemplateResponse>> {
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
+
+    let template = get_template(&state, complex_id, template_id).await?;
+
+    let (complex_name,): (String,) = sqlx::query_as("SELECT name FROM complexes WHERE id = $1")
+        .bind(complex_id)
+        .fetch_one(&state.pool)
+        .await?;
+
+    let mut variables = payload.variables;
+    variables
+        .entry("complex_name".to_string())
+        .or_insert(complex_name);
+
+    let (title, mut missing) = render_template(&template.title, &variables);
+    let (body, body_missing) = render_template(&template.body, &variables);
+    for name in body_missing {
+        if !missing.contains(&name) {
+            missing.push(name);
+        }
+    }
+
+    let options: Vec<String> = template
+        .options
+        .and_then(|o| serde_json::from_value(o).ok())
+        .unwrap_or_default();
+
+    Ok(Json(RenderedTemplateResponse {
+        kind: template.kind,
+        title,
+        body,
+        options,
+        category: template.category,
+        missing,
+    }))
+}
@@ -7,17 +7,26 @@ use serde_json::{json, Value};
 
 use crate::error::{AppError, AppResult};
 use crate::middleware::{AppState, AuthUser};
-use crate::models::{ApartmentResponse, UpdateUserRequest, User, UserPublic};
+use crate::models::{ApartmentResponse, MagicLinkPurpose, UpdateUserRequest, User, UserPublic};
 use crate::services::{
     file_service::{validate_image_content_type, MAX_IMAGE_SIZE},
-    AuthService, FileService,
+    AuthService, EmailService, FileService,
 };
+use crate::utils::validate_email;
 
 /// Ответ на загрузку аватара
 #[derive(serde::Serialize, utoipa::ToSchema)]
 pub struct AvatarUploadResponse {
     pub success: bool,
     pub avatar_url: String,
+    pub avatar_thumbnail_url: String,
+}
+
+/// Ответ на отправку письма с подтверждением
+#[derive(serde::Serialize, utoipa::ToSchema)]
+pub struct EmailVerificationResponse {
+    pub success: bool,
+    pub message: String,
 }
 
 pub fn routes() -> Router<AppState> {
@@ -25,6 +34,7 @@ pub fn routes() -> Router<AppState> {
         .route("/me", get(get_me))
         .route("/me", put(update_me))
         .route("/me/avatar", post(upload_avatar))
+        .route("/me/email/verify", post(send_email_verification))
         .route("/me/apartments", get(get_my_apartments))
 }
 
@@ -56,7 +66,8 @@ pub async fn get_me(
     request_body = UpdateUserRequest,
     responses(
         (status = 200, description = "Профиль обновлён", body = UserPublic),
-        (status = 401, description = "Не авторизован")
+        (status = 401, description = "Не авторизован"),
+        (status = 422, description = "Неверный формат email")
     )
 )]
 pub async fn update_me(
@@ -64,30 +75,94 @@ pub async fn update_me(
     auth_user: AuthUser,
     Json(payload): Json<UpdateUserRequest>,
 ) -> AppResult<Json<UserPublic>> {
+    let user = AuthService::get_user_by_id(&state.pool, auth_user.user_id).await?;
+
+    let payload_email = payload.email.map(|email| email.trim().to_string());
+    if payload_email.value().is_some_and(|email| !validate_email(email)) {
+        return Err(AppError::Validation("Неверный формат email".to_string()));
+    }
+
+    // Смена адреса снимает подтверждение и вместе с ним вход по email
     let user = sqlx::query_as::<_, User>(
         r#"
         UPDATE users
         SET
-            first_name = COALESCE($2, first_name),
-            last_name = COALESCE($3, last_name),
-            middle_name = COALESCE($4, middle_name),
-            email = COALESCE($5, email),
+            first_name = $2,
+            last_name = $3,
+            middle_name = $4,
+            email = $5,
+            email_verified_at = CASE
+                WHEN LOWER(email) IS NOT DISTINCT FROM LOWER($5) THEN email_verified_at
+            END,
             updated_at = NOW()
         WHERE id = $1
         RETURNING *
         "#,
     )
     .bind(auth_user.user_id)
-    .bind(&payload.first_name)
-    .bind(&payload.last_name)
-    .bind(&payload.middle_name)
-    .bind(&payload.email)
+    .bind(payload.first_name.apply(user.first_name))
+    .bind(payload.last_name.apply(user.last_name))
+    .bind(payload.middle_name.apply(user.middle_name))
+    .bind(payload_email.apply(user.email))
     .fetch_one(&state.pool)
     .await?;
 
     Ok(Json(UserPublic::from(user)))
 }
 
+/// Отправить на email из профиля ссылку для подтверждения адреса
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/me/email/verify",
+    tag = "users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Письмо отправлено", body = EmailVerificationResponse),
+        (status = 400, description = "В профиле не указан email"),
+        (status = 401, description = "Не авторизован"),
+        (status = 409, description = "Email уже подтверждён"),
+        (status = 429, description = "Слишком много запросов")
+    )
+)]
+pub async fn send_email_verification(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<EmailVerificationResponse>> {
+    let user = AuthService::get_user_by_id(&state.pool, auth_user.user_id).await?;
+
+    let email = user
+        .email
+        .ok_or_else(|| AppError::BadRequest("Укажите email в профиле".to_string()))?;
+    if user.email_verified_at.is_some() {
+        return Err(AppError::Conflict("Email уже подтверждён".to_string()));
+    }
+    if AuthService::recent_magic_links(&state.pool, user.id).await? >= 5 {
+        return Err(AppError::TooManyRequests);
+    }
+
+    let token = AuthService::create_magic_link(
+        &state.pool,
+        user.id,
+        MagicLinkPurpose::VerifyEmail,
+        &email,
+        state.config.magic_link_ttl_minutes,
+    )
+    .await?;
+    let link = format!(
+        "{}/confirm-email?token={}",
+        state.config.public_url.trim_end_matches('/'),
+        token
+    );
+    EmailService::new(state.config.clone())
+        .send_email_confirmation(&email, &link)
+        .await?;
+
+    Ok(Json(EmailVerificationResponse {
+        success: true,
+        message: format!("Письмо с подтверждением отправлено на {}", email),
+    }))
+}
+
 /// Загрузка аватара пользователя
 #[utoipa::path(
     post,
@@ -106,7 +181,9 @@ pub async fn upload_avatar(
     auth_user: AuthUser,
     mut multipart: Multipart,
 ) -> AppResult<Json<Value>> {
-    let file_service = FileService::new(&state.config).await?;
+    let file_service = FileService::new(&state.config)
+        .await?
+        .with_upload_queue(&state.pool);
 
     while let Some(field) = multipart
         .next_field()
@@ -138,20 +215,24 @@ pub async fn upload_avatar(
                 return Err(AppError::BadRequest("Файл слишком большой".to_string()));
             }
 
-            let url = file_service
-                .upload_file("avatars", &file_name, &content_type, data.to_vec())
+            let image = file_service
+                .upload_image("avatars", &file_name, &content_type, data.to_vec())
                 .await?;
 
             // Обновляем аватар пользователя
-            sqlx::query("UPDATE users SET avatar_url = $1, updated_at = NOW() WHERE id = $2")
-                .bind(&url)
-                .bind(auth_user.user_id)
-                .execute(&state.pool)
-                .await?;
+            sqlx::query(
+                "UPDATE users SET avatar_url = $1, avatar_thumbnail_url = $2, updated_at = NOW() WHERE id = $3",
+            )
+            .bind(&image.url)
+            .bind(&image.thumbnail_url)
+            .bind(auth_user.user_id)
+            .execute(&state.pool)
+            .await?;
 
             return Ok(Json(json!({
                 "success": true,
-                "avatar_url": url
+                "avatar_url": image.url,
+                "avatar_thumbnail_url": image.thumbnail_url
             })));
         }
     }
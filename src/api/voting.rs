@@ -1,6 +1,8 @@
 use axum::{
     extract::{Path, Query, State},
-    routing::{get, post},
+    http::header,
+    response::IntoResponse,
+    routing::{delete, get, post},
     Json, Router,
 };
 use rust_decimal::Decimal;
@@ -9,10 +11,19 @@ use serde_json::{json, Value};
 use uuid::Uuid;
 
 use crate::error::{AppError, AppResult};
-use crate::middleware::{is_chairman_or_higher, is_owner_or_higher, AppState, AuthUser};
+use crate::middleware::{
+    is_owner_or_higher, resolve_complex, AppState, AuthUser, ComplexScope, RequestId,
+};
 use crate::models::{
-    CastVoteRequest, CreateVotingRequest, Voting, VotingOption, VotingOptionResponse,
-    VotingResponse, VotingStatus, VotingType,
+    CandidateProfile, CastVoteRequest, CreateVoteDelegationRequest, CreateVotingRequest,
+    NewAuditLog, NotificationType, Permission, VoteDelegation, VoteDelegationsResponse,
+    RegisterCandidateRequest, RepeatVotingRequest, Voting, VotingOptionResponse, VotingProtocol,
+    VotingApartment, VotingProtocolVerification, VotingResponse, VotingStatus, VotingType,
+};
+use crate::services::document_service::ProtocolSignature;
+use crate::services::{
+    AuditService, DocumentService, FileService,
+    NotificationService, PaymentService, PermissionService, VotingService,
 };
 
 /// Успешный ответ
@@ -34,7 +45,16 @@ pub fn routes() -> Router<AppState> {
         .route("/", post(create_voting))
         .route("/:id", get(get_voting))
         .route("/:id/vote", post(cast_vote))
+        .route("/:id/apartments", get(list_voting_apartments))
+        .route("/:id/apartments/:apartment_id/vote", post(cast_apartment_vote))
+        .route("/:id/candidates", post(register_candidate))
+        .route("/:id/activate", post(activate_voting))
         .route("/:id/close", post(close_voting))
+        .route("/:id/repeat", post(repeat_voting))
+        .route("/:id/protocol", get(get_voting_protocol))
+        .route("/protocols/:token", get(verify_voting_protocol))
+        .route("/delegations", get(list_delegations).post(create_delegation))
+        .route("/delegations/:id", delete(revoke_delegation))
 }
 
 #[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
@@ -44,23 +64,6 @@ pub struct VotingsQuery {
     pub limit: Option<i64>,
 }
 
-async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
-    let complex: Option<(Uuid,)> = sqlx::query_as(
-        r#"
-        SELECT DISTINCT c.id
-        FROM complexes c
-        JOIN apartments a ON a.complex_id = c.id
-        WHERE a.owner_id = $1 OR a.resident_id = $1
-        LIMIT 1
-        "#,
-    )
-    .bind(user_id)
-    .fetch_optional(&state.pool)
-    .await?;
-
-    complex.map(|(id,)| id).ok_or_else(|| AppError::Forbidden)
-}
-
 /// Получить список голосований
 #[utoipa::path(
     get,
@@ -68,6 +71,7 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
     tag = "voting",
     security(("bearer_auth" = [])),
     params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК, если у пользователя их несколько"),
         ("status" = Option<String>, Query, description = "Статус (draft, active, closed)"),
         ("page" = Option<i64>, Query, description = "Номер страницы"),
         ("limit" = Option<i64>, Query, description = "Количество записей")
@@ -80,9 +84,10 @@ async fn get_user_complex(state: &AppState, user_id: Uuid) -> AppResult<Uuid> {
 pub async fn list_votings(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Query(query): Query<VotingsQuery>,
 ) -> AppResult<Json<Vec<VotingResponse>>> {
-    let complex_id = get_user_complex(&state, auth_user.user_id).await?;
+    let complex_id = complex.complex_id()?;
 
     let limit = query.limit.unwrap_or(20).min(100);
     let offset = query.page.unwrap_or(0) * limit;
@@ -111,51 +116,69 @@ pub async fn list_votings(
     Ok(Json(response))
 }
 
+/// Вариант голосования вместе с признаками, общими для всего голосования
+#[derive(sqlx::FromRow)]
+struct VotingDetailRow {
+    user_voted: bool,
+    repeat_voting_id: Option<Uuid>,
+    option_id: Option<Uuid>,
+    text: Option<String>,
+    votes_count: Option<i32>,
+    votes_weight: Option<Decimal>,
+    candidate_id: Option<Uuid>,
+    candidate_bio: Option<String>,
+    candidate_photo_url: Option<String>,
+}
+
 async fn build_voting_response(
     state: &AppState,
     voting: &Voting,
     user_id: Uuid,
 ) -> AppResult<VotingResponse> {
-    let options = sqlx::query_as::<_, VotingOption>(
-        "SELECT * FROM voting_options WHERE voting_id = $1 ORDER BY sort_order",
+    // Итоги берутся из счётчиков; строка без варианта остаётся, если вариантов ещё нет
+    let rows = sqlx::query_as::<_, VotingDetailRow>(
+        r#"
+        SELECT
+            EXISTS(SELECT 1 FROM votes WHERE voting_id = $1 AND user_id = $2) AS user_voted,
+            (SELECT id FROM votings WHERE repeat_of_id = $1 LIMIT 1) AS repeat_voting_id,
+            o.id AS option_id, o.text, o.votes_count, o.votes_weight,
+            o.candidate_id, o.candidate_bio, o.candidate_photo_url
+        FROM (SELECT 1) AS detail
+        LEFT JOIN voting_options o ON o.voting_id = $1
+        ORDER BY o.sort_order
+        "#,
     )
     .bind(voting.id)
+    .bind(user_id)
     .fetch_all(&state.pool)
     .await?;
 
-    let total_votes: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM votes WHERE voting_id = $1")
-        .bind(voting.id)
-        .fetch_one(&state.pool)
-        .await?;
-
-    let total_weight: (Decimal,) =
-        sqlx::query_as("SELECT COALESCE(SUM(vote_weight), 0) FROM votes WHERE voting_id = $1")
-            .bind(voting.id)
-            .fetch_one(&state.pool)
-            .await?;
+    let user_voted = rows.first().is_some_and(|row| row.user_voted);
+    let repeat_voting_id = rows.first().and_then(|row| row.repeat_voting_id);
 
-    let user_voted: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM votes WHERE voting_id = $1 AND user_id = $2")
-            .bind(voting.id)
-            .bind(user_id)
-            .fetch_optional(&state.pool)
-            .await?;
+    // Рейтинговое голосование показывает последний тур; несколько вариантов — долю бюллетеней
+    let ranked = if voting.voting_type == VotingType::Ranked {
+        Some(VotingService::ranked_tally(&mut *state.pool.acquire().await?, voting).await?)
+    } else {
+        None
+    };
+    let base_weight = ranked
+        .as_ref()
+        .map_or(voting.votes_weight, |tally| tally.continuing_weight);
 
     let mut option_responses = Vec::new();
-    for opt in options {
-        let votes_count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM votes WHERE option_id = $1")
-            .bind(opt.id)
-            .fetch_one(&state.pool)
-            .await?;
-
-        let votes_weight: (Decimal,) =
-            sqlx::query_as("SELECT COALESCE(SUM(vote_weight), 0) FROM votes WHERE option_id = $1")
-                .bind(opt.id)
-                .fetch_one(&state.pool)
-                .await?;
+    for row in rows {
+        let Some(id) = row.option_id else {
+            continue;
+        };
+        let result = ranked
+            .as_ref()
+            .and_then(|tally| tally.options.iter().find(|option| option.option_id == id));
+        let votes_count = result.map_or(row.votes_count.unwrap_or_default(), |r| r.votes_count);
+        let votes_weight = result.map_or(row.votes_weight.unwrap_or_default(), |r| r.votes_weight);
 
-        let percentage = if total_weight.0 > Decimal::ZERO {
-            (votes_weight.0 / total_weight.0 * Decimal::from(100))
+        let percentage = if base_weight > Decimal::ZERO {
+            (votes_weight / base_weight * Decimal::from(100))
                 .to_string()
                 .parse::<f64>()
                 .unwrap_or(0.0)
@@ -164,11 +187,18 @@ async fn build_voting_response(
         };
 
         option_responses.push(VotingOptionResponse {
-            id: opt.id,
-            text: opt.text,
-            votes_count: votes_count.0 as i32,
-            votes_weight: votes_weight.0,
+            id,
+            text: row.text.unwrap_or_default(),
+            votes_count,
+            votes_weight,
             percentage,
+            is_winner: voting.winner_option_id == Some(id),
+            candidate: row.candidate_id.map(|user_id| CandidateProfile {
+                user_id,
+                bio: row.candidate_bio,
+                photo_url: row.candidate_photo_url,
+            }),
+            eliminated_in_round: result.and_then(|r| r.eliminated_in_round),
         });
     }
 
@@ -183,10 +213,19 @@ async fn build_voting_response(
         starts_at: voting.starts_at,
         ends_at: voting.ends_at,
         options: option_responses,
-        total_votes: total_votes.0 as i32,
-        total_weight: total_weight.0,
-        user_voted: user_voted.is_some(),
+        total_votes: voting.votes_count,
+        total_weight: voting.votes_weight,
+        eligible_weight: voting.total_weight,
+        quorum_reached: voting.quorum_reached,
+        passed: voting.passed,
+        closed_at: voting.closed_at,
+        user_voted,
         created_at: voting.created_at,
+        nomination_ends_at: voting.nomination_ends_at,
+        winner_option_id: voting.winner_option_id,
+        election_outcome: voting.election_outcome,
+        repeat_of_id: voting.repeat_of_id,
+        repeat_voting_id,
     })
 }
 
@@ -208,13 +247,17 @@ async fn build_voting_response(
 pub async fn get_voting(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Path(id): Path<Uuid>,
 ) -> AppResult<Json<VotingResponse>> {
-    let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
-        .bind(id)
-        .fetch_optional(&state.pool)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+    let voting = sqlx::query_as::<_, Voting>(
+        "SELECT * FROM votings WHERE id = $1 AND complex_id = ANY($2)",
+    )
+    .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
 
     let response = build_voting_response(&state, &voting, auth_user.user_id).await?;
     Ok(Json(response))
@@ -229,9 +272,9 @@ pub async fn get_voting(
     request_body = CreateVotingRequest,
     responses(
         (status = 200, description = "Голосование создано", body = VotingResponse),
-        (status = 400, description = "Минимум 2 варианта ответа"),
+        (status = 400, description = "Минимум 2 варианта ответа или не указан complex_id"),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет прав")
+        (status = 403, description = "Нужно право управления ОСИ")
     )
 )]
 pub async fn create_voting(
@@ -239,21 +282,18 @@ pub async fn create_voting(
     auth_user: AuthUser,
     Json(payload): Json<CreateVotingRequest>,
 ) -> AppResult<Json<VotingResponse>> {
-    let complex_id: Option<(Uuid,)> =
-        sqlx::query_as("SELECT complex_id FROM osi WHERE chairman_id = $1")
-            .bind(auth_user.user_id)
-            .fetch_optional(&state.pool)
-            .await?;
+    let complex_id = resolve_complex(&state.pool, auth_user.user_id, payload.complex_id).await?;
+    PermissionService::require(&state.pool, &auth_user, complex_id, Permission::ManageOsi).await?;
 
-    let complex_id = complex_id.map(|(id,)| id).ok_or_else(|| {
-        if is_chairman_or_higher(&auth_user.role) {
-            AppError::BadRequest("complex_id требуется".to_string())
-        } else {
-            AppError::Forbidden
-        }
-    })?;
+    let voting_type = payload
+        .voting_type
+        .clone()
+        .unwrap_or(VotingType::SingleChoice);
+    let is_election = voting_type == VotingType::Election;
 
-    if payload.options.len() < 2 {
+    if is_election {
+        validate_election(&state, complex_id, &payload).await?;
+    } else if payload.options.len() < 2 {
         return Err(AppError::BadRequest(
             "Минимум 2 варианта ответа".to_string(),
         ));
@@ -263,31 +303,28 @@ pub async fn create_voting(
         r#"
         INSERT INTO votings (
             complex_id, title, description, voting_type, status,
-            requires_owner, quorum_percent, starts_at, ends_at, created_by
+            requires_owner, quorum_percent, starts_at, ends_at, created_by, nomination_ends_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
         RETURNING *
         "#,
     )
     .bind(complex_id)
     .bind(&payload.title)
     .bind(&payload.description)
-    .bind(
-        payload
-            .voting_type
-            .clone()
-            .unwrap_or(VotingType::SingleChoice),
-    )
+    .bind(&voting_type)
     .bind(VotingStatus::Draft)
-    .bind(payload.requires_owner.unwrap_or(true))
+    .bind(is_election || payload.requires_owner.unwrap_or(true))
     .bind(payload.quorum_percent.unwrap_or(51))
     .bind(payload.starts_at)
     .bind(payload.ends_at)
     .bind(auth_user.user_id)
+    .bind(payload.nomination_ends_at.filter(|_| is_election))
     .fetch_one(&state.pool)
     .await?;
 
-    for (i, option_text) in payload.options.iter().enumerate() {
+    let options = if is_election { &[][..] } else { &payload.options[..] };
+    for (i, option_text) in options.iter().enumerate() {
         sqlx::query("INSERT INTO voting_options (voting_id, text, sort_order) VALUES ($1, $2, $3)")
             .bind(voting.id)
             .bind(option_text)
@@ -300,106 +337,243 @@ pub async fn create_voting(
     Ok(Json(response))
 }
 
-/// Проголосовать
+/// Выборы: окно выдвижения до начала голосования и не более одних выборов одновременно
+async fn validate_election(
+    state: &AppState,
+    complex_id: Uuid,
+    payload: &CreateVotingRequest,
+) -> AppResult<()> {
+    let nomination_ends_at = payload.nomination_ends_at.ok_or_else(|| {
+        AppError::BadRequest("Для выборов укажите окончание выдвижения кандидатов".to_string())
+    })?;
+
+    if nomination_ends_at <= chrono::Utc::now() || nomination_ends_at > payload.starts_at {
+        return Err(AppError::BadRequest(
+            "Выдвижение должно завершиться до начала голосования".to_string(),
+        ));
+    }
+
+    let running: Option<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM votings
+        WHERE complex_id = $1 AND voting_type = 'election' AND status IN ('draft', 'active')
+        LIMIT 1
+        "#,
+    )
+    .bind(complex_id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if running.is_some() {
+        return Err(AppError::Conflict(
+            "В ЖК уже проводятся выборы председателя".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Варианты бюллетеня по форме голосования: один вариант, несколько или рейтинг без повторов
+async fn ballot_choices(
+    state: &AppState,
+    voting: &Voting,
+    payload: &CastVoteRequest,
+) -> AppResult<Vec<Uuid>> {
+    let choices = match payload.option_id {
+        Some(option_id) if payload.option_ids.is_empty() => vec![option_id],
+        Some(_) => {
+            return Err(AppError::Validation(
+                "Укажите либо option_id, либо option_ids".to_string(),
+            ))
+        }
+        None => payload.option_ids.clone(),
+    };
+
+    if choices.is_empty() {
+        return Err(AppError::Validation("Не выбран вариант ответа".to_string()));
+    }
+    let mut unique = choices.clone();
+    unique.sort();
+    unique.dedup();
+    if unique.len() != choices.len() {
+        return Err(AppError::Validation("Варианты не должны повторяться".to_string()));
+    }
+
+    let single = matches!(
+        voting.voting_type,
+        VotingType::SingleChoice | VotingType::YesNo | VotingType::Election
+    );
+    if single && choices.len() > 1 {
+        return Err(AppError::Validation(
+            "В этом голосовании выбирается один вариант".to_string(),
+        ));
+    }
+
+    let (known_options,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM voting_options WHERE id = ANY($1) AND voting_id = $2",
+    )
+    .bind(&choices)
+    .bind(voting.id)
+    .fetch_one(&state.pool)
+    .await?;
+
+    if known_options != choices.len() as i64 {
+        return Err(AppError::BadRequest("Неверный вариант ответа".to_string()));
+    }
+
+    Ok(choices)
+}
+
+/// Выдвинуть свою кандидатуру на выборах председателя
 #[utoipa::path(
     post,
-    path = "/api/v1/voting/{id}/vote",
+    path = "/api/v1/voting/{id}/candidates",
     tag = "voting",
     security(("bearer_auth" = [])),
     params(
         ("id" = Uuid, Path, description = "ID голосования")
     ),
-    request_body = CastVoteRequest,
+    request_body = RegisterCandidateRequest,
     responses(
-        (status = 200, description = "Голос принят", body = VoteResponse),
-        (status = 400, description = "Голосование не активно"),
+        (status = 200, description = "Кандидат зарегистрирован", body = VotingResponse),
+        (status = 400, description = "Выдвижение закрыто или это не выборы"),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет прав голосовать"),
+        (status = 403, description = "Выдвигаться могут только собственники ЖК"),
         (status = 404, description = "Голосование не найдено"),
-        (status = 409, description = "Вы уже голосовали")
+        (status = 409, description = "Вы уже зарегистрированы")
     )
 )]
-pub async fn cast_vote(
+pub async fn register_candidate(
     State(state): State<AppState>,
     auth_user: AuthUser,
     Path(id): Path<Uuid>,
-    Json(payload): Json<CastVoteRequest>,
-) -> AppResult<Json<Value>> {
+    Json(payload): Json<RegisterCandidateRequest>,
+) -> AppResult<Json<VotingResponse>> {
     let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
         .bind(id)
         .fetch_optional(&state.pool)
         .await?
         .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
 
-    if voting.status != VotingStatus::Active {
-        return Err(AppError::BadRequest("Голосование не активно".to_string()));
-    }
-
-    let now = chrono::Utc::now();
-    if now < voting.starts_at || now > voting.ends_at {
-        return Err(AppError::BadRequest(
-            "Голосование не в активном периоде".to_string(),
-        ));
+    if voting.voting_type != VotingType::Election {
+        return Err(AppError::BadRequest("Голосование не является выборами".to_string()));
     }
 
-    if voting.requires_owner && !is_owner_or_higher(&auth_user.role) {
-        return Err(AppError::Forbidden);
+    let nomination_open = matches!(voting.status, VotingStatus::Draft | VotingStatus::Active)
+        && voting
+            .nomination_ends_at
+            .is_some_and(|ends_at| chrono::Utc::now() < ends_at);
+    if !nomination_open {
+        return Err(AppError::BadRequest("Выдвижение кандидатов завершено".to_string()));
     }
 
-    let existing_vote: Option<(Uuid,)> =
-        sqlx::query_as("SELECT id FROM votes WHERE voting_id = $1 AND user_id = $2")
-            .bind(id)
+    let is_owner: Option<(i32,)> =
+        sqlx::query_as("SELECT 1 FROM apartments WHERE complex_id = $1 AND owner_id = $2 LIMIT 1")
+            .bind(voting.complex_id)
             .bind(auth_user.user_id)
             .fetch_optional(&state.pool)
             .await?;
 
-    if existing_vote.is_some() {
-        return Err(AppError::Conflict("Вы уже голосовали".to_string()));
+    if is_owner.is_none() {
+        return Err(AppError::Forbidden);
     }
 
-    let option_exists: Option<(i32,)> =
-        sqlx::query_as("SELECT 1 FROM voting_options WHERE id = $1 AND voting_id = $2")
-            .bind(payload.option_id)
-            .bind(id)
-            .fetch_optional(&state.pool)
+    let (first_name, last_name, phone): (Option<String>, Option<String>, String) =
+        sqlx::query_as("SELECT first_name, last_name, phone FROM users WHERE id = $1")
+            .bind(auth_user.user_id)
+            .fetch_one(&state.pool)
             .await?;
 
-    if option_exists.is_none() {
-        return Err(AppError::BadRequest("Неверный вариант ответа".to_string()));
-    }
+    let name = [last_name, first_name]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let name = if name.is_empty() { phone } else { name };
 
-    let vote_weight: (Decimal,) = sqlx::query_as(
+    let inserted = sqlx::query(
         r#"
-        SELECT COALESCE(SUM(area), 1)
-        FROM apartments
-        WHERE complex_id = $1 AND owner_id = $2
+        INSERT INTO voting_options (voting_id, text, sort_order, candidate_id, candidate_bio, candidate_photo_url)
+        VALUES ($1, $2, (SELECT COUNT(*) FROM voting_options WHERE voting_id = $1), $3, $4, $5)
+        ON CONFLICT (voting_id, candidate_id) WHERE candidate_id IS NOT NULL DO NOTHING
         "#,
     )
-    .bind(voting.complex_id)
+    .bind(id)
+    .bind(&name)
     .bind(auth_user.user_id)
-    .fetch_one(&state.pool)
+    .bind(&payload.bio)
+    .bind(&payload.photo_url)
+    .execute(&state.pool)
     .await?;
 
-    let apartment_id: Option<(Uuid,)> =
-        sqlx::query_as("SELECT id FROM apartments WHERE complex_id = $1 AND owner_id = $2 LIMIT 1")
-            .bind(voting.complex_id)
-            .bind(auth_user.user_id)
-            .fetch_optional(&state.pool)
-            .await?;
+    if inserted.rows_affected() == 0 {
+        return Err(AppError::Conflict("Вы уже зарегистрированы кандидатом".to_string()));
+    }
 
-    sqlx::query(
-        r#"
-        INSERT INTO votes (voting_id, option_id, user_id, apartment_id, vote_weight)
-        VALUES ($1, $2, $3, $4, $5)
-        "#,
+    let response = build_voting_response(&state, &voting, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Проголосовать: бюллетень подаётся за каждую свою квартиру, за которую ещё не голосовали
+#[utoipa::path(
+    post,
+    path = "/api/v1/voting/{id}/vote",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования")
+    ),
+    request_body = CastVoteRequest,
+    responses(
+        (status = 200, description = "Голос принят", body = VoteResponse),
+        (status = 400, description = "Голосование не активно"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав голосовать или нет действующей доверенности"),
+        (status = 404, description = "Голосование не найдено"),
+        (status = 409, description = "Вы или собственник уже голосовали за все квартиры"),
+        (status = 422, description = "Выбор не соответствует форме голосования")
     )
-    .bind(id)
-    .bind(payload.option_id)
-    .bind(auth_user.user_id)
-    .bind(apartment_id.map(|(id,)| id))
-    .bind(vote_weight.0)
-    .execute(&state.pool)
-    .await?;
+)]
+pub async fn cast_vote(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<CastVoteRequest>,
+) -> AppResult<Json<Value>> {
+    let voting = open_voting(&state, id).await?;
+    let (voter_id, delegation) = ballot_voter(&state, &voting, &auth_user, &payload).await?;
+    let choices = ballot_choices(&state, &voting, &payload).await?;
+
+    // Бюллетень за каждую квартиру собственника, за которую ещё не голосовали;
+    // у жителя без квартир — один бюллетень с единичным весом
+    let apartments = VotingService::voter_apartments(&state.pool, &voting, voter_id).await?;
+    let ballots: Vec<(Option<Uuid>, Decimal)> = if apartments.is_empty() {
+        let existing_vote: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM votes WHERE voting_id = $1 AND user_id = $2 AND apartment_id IS NULL",
+        )
+        .bind(id)
+        .bind(voter_id)
+        .fetch_optional(&state.pool)
+        .await?;
+
+        if existing_vote.is_some() {
+            return Err(already_voted(&delegation));
+        }
+        vec![(None, Decimal::ONE)]
+    } else {
+        let pending: Vec<_> = apartments
+            .into_iter()
+            .filter(|apartment| !apartment.voted)
+            .map(|apartment| (Some(apartment.apartment_id), apartment.vote_weight))
+            .collect();
+
+        if pending.is_empty() {
+            return Err(already_voted(&delegation));
+        }
+        pending
+    };
+
+    insert_ballots(&state, &voting, voter_id, delegation.as_ref(), &choices, &ballots).await?;
 
     Ok(Json(json!({
         "success": true,
@@ -407,41 +581,941 @@ pub async fn cast_vote(
     })))
 }
 
-/// Закрыть голосование
+/// Квартиры собственника в голосовании и поданные за них бюллетени
 #[utoipa::path(
-    post,
-    path = "/api/v1/voting/{id}/close",
+    get,
+    path = "/api/v1/voting/{id}/apartments",
     tag = "voting",
     security(("bearer_auth" = [])),
     params(
         ("id" = Uuid, Path, description = "ID голосования")
     ),
     responses(
-        (status = 200, description = "Голосование закрыто", body = SuccessResponse),
+        (status = 200, description = "Квартиры текущего пользователя", body = Vec<VotingApartment>),
         (status = 401, description = "Не авторизован"),
-        (status = 403, description = "Нет прав"),
-        (status = 404, description = "Не найдено")
+        (status = 404, description = "Голосование не найдено")
     )
 )]
-pub async fn close_voting(
+pub async fn list_voting_apartments(
     State(state): State<AppState>,
     auth_user: AuthUser,
+    complex: ComplexScope,
     Path(id): Path<Uuid>,
+) -> AppResult<Json<Vec<VotingApartment>>> {
+    let voting = sqlx::query_as::<_, Voting>(
+        "SELECT * FROM votings WHERE id = $1 AND complex_id = ANY($2)",
+    )
+    .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+    let apartments = VotingService::voter_apartments(&state.pool, &voting, auth_user.user_id).await?;
+    Ok(Json(apartments))
+}
+
+/// Проголосовать за одну квартиру, если у собственника их несколько
+#[utoipa::path(
+    post,
+    path = "/api/v1/voting/{id}/apartments/{apartment_id}/vote",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования"),
+        ("apartment_id" = Uuid, Path, description = "ID квартиры собственника")
+    ),
+    request_body = CastVoteRequest,
+    responses(
+        (status = 200, description = "Голос принят", body = VoteResponse),
+        (status = 400, description = "Голосование не активно"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав голосовать или нет действующей доверенности"),
+        (status = 404, description = "Голосование или квартира собственника не найдены"),
+        (status = 409, description = "За квартиру уже проголосовали"),
+        (status = 422, description = "Выбор не соответствует форме голосования")
+    )
+)]
+pub async fn cast_apartment_vote(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path((id, apartment_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<CastVoteRequest>,
 ) -> AppResult<Json<Value>> {
+    let voting = open_voting(&state, id).await?;
+    let (voter_id, delegation) = ballot_voter(&state, &voting, &auth_user, &payload).await?;
+    let choices = ballot_choices(&state, &voting, &payload).await?;
+
+    let apartment = VotingService::voter_apartments(&state.pool, &voting, voter_id)
+        .await?
+        .into_iter()
+        .find(|apartment| apartment.apartment_id == apartment_id)
+        .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+    if apartment.voted {
+        return Err(AppError::Conflict(format!(
+            "За квартиру {} уже проголосовали",
+            apartment.number
+        )));
+    }
+
+    insert_ballots(
+        &state,
+        &voting,
+        voter_id,
+        delegation.as_ref(),
+        &choices,
+        &[(Some(apartment.apartment_id), apartment.vote_weight)],
+    )
+    .await?;
+
+    Ok(Json(json!({
+        "success": true,
+        "message": format!("Голос за квартиру {} принят", apartment.number)
+    })))
+}
+
+/// Голосованием управляет его автор или правление ОСИ этого ЖК
+async fn can_manage_voting(state: &AppState, auth_user: &AuthUser, voting: &Voting) -> AppResult<bool> {
+    if voting.created_by == auth_user.user_id {
+        return Ok(true);
+    }
+
+    PermissionService::has(&state.pool, auth_user, voting.complex_id, Permission::ManageOsi).await
+}
+
+/// Голосование, принимающее бюллетени прямо сейчас
+async fn open_voting(state: &AppState, id: Uuid) -> AppResult<Voting> {
     let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
         .bind(id)
         .fetch_optional(&state.pool)
         .await?
         .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
 
-    if voting.created_by != auth_user.user_id && !is_chairman_or_higher(&auth_user.role) {
+    if voting.status != VotingStatus::Active {
+        return Err(AppError::BadRequest("Голосование не активно".to_string()));
+    }
+
+    let now = chrono::Utc::now();
+    if now < voting.starts_at || now > voting.ends_at {
+        return Err(AppError::BadRequest(
+            "Голосование не в активном периоде".to_string(),
+        ));
+    }
+
+    Ok(voting)
+}
+
+/// Чей голос подаётся: свой или собственника по доверенности
+async fn ballot_voter(
+    state: &AppState,
+    voting: &Voting,
+    auth_user: &AuthUser,
+    payload: &CastVoteRequest,
+) -> AppResult<(Uuid, Option<VoteDelegation>)> {
+    // Голос по доверенности засчитывается собственнику, доверенное лицо фиксируется отдельно
+    let delegation = match payload.on_behalf_of {
+        Some(principal_id) if principal_id != auth_user.user_id => Some(
+            VotingService::active_delegation(&state.pool, voting, principal_id, auth_user.user_id)
+                .await?
+                .ok_or(AppError::Forbidden)?,
+        ),
+        _ => None,
+    };
+    let voter_id = delegation
+        .as_ref()
+        .map_or(auth_user.user_id, |delegation| delegation.principal_id);
+
+    if delegation.is_none() && voting.requires_owner && !is_owner_or_higher(&auth_user.role) {
         return Err(AppError::Forbidden);
     }
 
-    sqlx::query("UPDATE votings SET status = 'closed', updated_at = NOW() WHERE id = $1")
-        .bind(id)
-        .execute(&state.pool)
+    Ok((voter_id, delegation))
+}
+
+fn already_voted(delegation: &Option<VoteDelegation>) -> AppError {
+    AppError::Conflict(if delegation.is_some() {
+        "Собственник уже проголосовал".to_string()
+    } else {
+        "Вы уже голосовали".to_string()
+    })
+}
+
+/// Записать бюллетени с выбранными вариантами одной транзакцией: по одному на квартиру
+async fn insert_ballots(
+    state: &AppState,
+    voting: &Voting,
+    voter_id: Uuid,
+    delegation: Option<&VoteDelegation>,
+    choices: &[Uuid],
+    ballots: &[(Option<Uuid>, Decimal)],
+) -> AppResult<()> {
+    let mut tx = state.pool.begin().await?;
+
+    for (apartment_id, vote_weight) in ballots {
+        // Параллельный бюллетень за ту же квартиру отсекается уникальным индексом
+        let vote_id: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO votes (voting_id, option_id, user_id, apartment_id, vote_weight, proxy_id, delegation_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(voting.id)
+        .bind(choices[0])
+        .bind(voter_id)
+        .bind(apartment_id)
+        .bind(vote_weight)
+        .bind(delegation.map(|delegation| delegation.proxy_id))
+        .bind(delegation.map(|delegation| delegation.id))
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((vote_id,)) = vote_id else {
+            return Err(AppError::Conflict("Бюллетень уже подан".to_string()));
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO vote_choices (vote_id, option_id, rank, vote_weight)
+            SELECT $1, c.option_id, CASE WHEN $3 THEN c.position::INT ELSE 1 END, $4
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS c(option_id, position)
+            "#,
+        )
+        .bind(vote_id)
+        .bind(choices)
+        .bind(voting.voting_type == VotingType::Ranked)
+        .bind(vote_weight)
+        .execute(&mut *tx)
         .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Запустить голосование досрочно, не дожидаясь начала по расписанию
+#[utoipa::path(
+    post,
+    path = "/api/v1/voting/{id}/activate",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования")
+    ),
+    responses(
+        (status = 200, description = "Голосование открыто, собственники уведомлены", body = VotingResponse),
+        (status = 400, description = "Голосование не черновик, срок истёк или идёт выдвижение"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав"),
+        (status = 404, description = "Не найдено")
+    )
+)]
+pub async fn activate_voting(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<VotingResponse>> {
+    let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+    if !can_manage_voting(&state, &auth_user, &voting).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    let now = chrono::Utc::now();
+    if voting.ends_at <= now {
+        return Err(AppError::BadRequest("Срок голосования истёк".to_string()));
+    }
+    if voting.nomination_ends_at.is_some_and(|ends_at| ends_at > now) {
+        return Err(AppError::BadRequest(
+            "Выдвижение кандидатов ещё не завершено".to_string(),
+        ));
+    }
+
+    let activated = VotingService::activate(&state.pool, &voting)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Голосование уже запущено или завершено".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(voting.complex_id),
+            action: "activate_voting",
+            entity_type: "voting",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": voting.status, "starts_at": voting.starts_at})),
+            new_value: Some(json!({"status": activated.status, "starts_at": activated.starts_at})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    let response = build_voting_response(&state, &activated, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Закрыть голосование
+#[utoipa::path(
+    post,
+    path = "/api/v1/voting/{id}/close",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования")
+    ),
+    responses(
+        (status = 200, description = "Голосование закрыто, итоги зафиксированы", body = SuccessResponse),
+        (status = 400, description = "Голосование не активно"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "Голосование уже закрыто другим запросом")
+    )
+)]
+pub async fn close_voting(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+    if !can_manage_voting(&state, &auth_user, &voting).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    if voting.status != VotingStatus::Active {
+        return Err(AppError::BadRequest("Голосование не активно".to_string()));
+    }
+
+    let closed = VotingService::finish(&state.pool, &voting).await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(voting.complex_id),
+            action: "close_voting",
+            entity_type: "voting",
+            entity_id: Some(id),
+            old_value: Some(json!({"status": voting.status})),
+            new_value: Some(json!({
+                "status": closed.status,
+                "quorum_reached": closed.quorum_reached,
+                "passed": closed.passed,
+                "winner_option_id": closed.winner_option_id,
+            })),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    Ok(Json(json!({"success": true})))
+}
+
+/// Назначить повторное голосование со сниженным кворумом
+#[utoipa::path(
+    post,
+    path = "/api/v1/voting/{id}/repeat",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования, не набравшего кворум")
+    ),
+    request_body = RepeatVotingRequest,
+    responses(
+        (status = 200, description = "Повторное голосование создано", body = VotingResponse),
+        (status = 400, description = "Голосование не закрыто, кворум набран или неверные параметры"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Нет прав"),
+        (status = 404, description = "Не найдено"),
+        (status = 409, description = "Повторное голосование уже назначено")
+    )
+)]
+pub async fn repeat_voting(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+    Json(payload): Json<RepeatVotingRequest>,
+) -> AppResult<Json<VotingResponse>> {
+    let original = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&state.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+    if !can_manage_voting(&state, &auth_user, &original).await? {
+        return Err(AppError::Forbidden);
+    }
+
+    if original.status != VotingStatus::Closed {
+        return Err(AppError::BadRequest("Голосование ещё не закрыто".to_string()));
+    }
+
+    let quorum_reached = match original.quorum_reached {
+        Some(reached) => reached,
+        None => VotingService::turnout(&mut *state.pool.acquire().await?, &original)
+            .await?
+            .quorum_reached(original.quorum_percent),
+    };
+    if quorum_reached {
+        return Err(AppError::BadRequest(
+            "Кворум набран, повторное голосование не требуется".to_string(),
+        ));
+    }
+
+    if payload.quorum_percent <= 0 || payload.quorum_percent >= original.quorum_percent {
+        return Err(AppError::BadRequest(
+            "Кворум повторного голосования должен быть ниже исходного".to_string(),
+        ));
+    }
+
+    if payload.ends_at <= payload.starts_at || payload.ends_at <= chrono::Utc::now() {
+        return Err(AppError::BadRequest("Неверный период голосования".to_string()));
+    }
+
+    let mut tx = state.pool.begin().await?;
+
+    let voting = sqlx::query_as::<_, Voting>(
+        r#"
+        INSERT INTO votings (
+            complex_id, osi_id, title, description, voting_type, status,
+            requires_owner, quorum_percent, starts_at, ends_at, created_by, repeat_of_id
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        ON CONFLICT (repeat_of_id) WHERE repeat_of_id IS NOT NULL DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(original.complex_id)
+    .bind(original.osi_id)
+    .bind(payload.title.as_deref().unwrap_or(&original.title))
+    .bind(payload.description.as_ref().or(original.description.as_ref()))
+    .bind(&original.voting_type)
+    .bind(VotingStatus::Draft)
+    .bind(original.requires_owner)
+    .bind(payload.quorum_percent)
+    .bind(payload.starts_at)
+    .bind(payload.ends_at)
+    .bind(auth_user.user_id)
+    .bind(original.id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("Повторное голосование уже назначено".to_string()))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO voting_options (voting_id, text, sort_order, candidate_id, candidate_bio, candidate_photo_url)
+        SELECT $2, text, sort_order, candidate_id, candidate_bio, candidate_photo_url
+        FROM voting_options WHERE voting_id = $1
+        "#,
+    )
+    .bind(original.id)
+    .bind(voting.id)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        "INSERT INTO voting_documents (voting_id, title, file_url) SELECT $2, title, file_url FROM voting_documents WHERE voting_id = $1",
+    )
+    .bind(original.id)
+    .bind(voting.id)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let owner_ids: Vec<(Uuid,)> = sqlx::query_as(
+        "SELECT DISTINCT owner_id FROM apartments WHERE complex_id = $1 AND owner_id IS NOT NULL",
+    )
+    .bind(voting.complex_id)
+    .fetch_all(&state.pool)
+    .await?;
+    let owner_ids: Vec<Uuid> = owner_ids.into_iter().map(|(id,)| id).collect();
+
+    let body = format!(
+        "Голосование «{}» не набрало кворум. Повторное голосование с {} по {}, кворум {}%",
+        original.title,
+        voting.starts_at.format("%d.%m.%Y"),
+        voting.ends_at.format("%d.%m.%Y"),
+        voting.quorum_percent
+    );
+
+    NotificationService::notify_users(
+        &state.pool,
+        &owner_ids,
+        NotificationType::Voting,
+        &voting.title,
+        Some(&body),
+        Some(json!({"voting_id": voting.id, "repeat_of_id": original.id})),
+    )
+    .await?;
+
+    let response = build_voting_response(&state, &voting, auth_user.user_id).await?;
+    Ok(Json(response))
+}
+
+/// Протокол закрытого голосования в PDF. Первым его формирует и подписывает инициатор или председатель,
+/// дальше протокол доступен жителям ЖК
+#[utoipa::path(
+    get,
+    path = "/api/v1/votings/{id}/protocol",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID голосования")
+    ),
+    responses(
+        (status = 200, description = "Протокол", content_type = "application/pdf"),
+        (status = 400, description = "Голосование ещё не закрыто"),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Голосование не найдено или протокол ещё не подписан")
+    )
+)]
+pub async fn get_voting_protocol(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    complex: ComplexScope,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<impl IntoResponse> {
+    let voting = sqlx::query_as::<_, Voting>(
+        "SELECT * FROM votings WHERE id = $1 AND complex_id = ANY($2)",
+    )
+    .bind(id)
+    .bind(&complex.complex_ids)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+    if voting.status != VotingStatus::Closed {
+        return Err(AppError::BadRequest(
+            "Протокол формируется после закрытия голосования".to_string(),
+        ));
+    }
+
+    let existing = sqlx::query_as::<_, VotingProtocol>(
+        "SELECT * FROM voting_protocols WHERE voting_id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let file_service = FileService::new(&state.config).await?;
+    let (protocol, results) = match existing {
+        Some(protocol) => {
+            if let Some(key) = &protocol.pdf_key {
+                match file_service.get_file(key).await {
+                    Ok(data) => return Ok(protocol_response(&protocol, data)),
+                    // Файл пропал из хранилища — сгенерируем заново
+                    Err(AppError::NotFound(_)) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            let results = VotingService::protocol_results(&state.pool, &voting).await?;
+            (protocol, results)
+        }
+        None => {
+            if !can_manage_voting(&state, &auth_user, &voting).await? {
+                return Err(AppError::NotFound("Протокол ещё не подписан".to_string()));
+            }
+
+            let results = VotingService::protocol_results(&state.pool, &voting).await?;
+            let signature = VotingService::sign_protocol(&state.config.jwt_secret, voting.id, &results);
+            let protocol = sign_voting_protocol(&state, &voting, auth_user.user_id, signature).await?;
+
+            AuditService::record(
+                &state.pool,
+                NewAuditLog {
+                    actor_id: auth_user.user_id,
+                    complex_id: Some(voting.complex_id),
+                    action: "sign_voting_protocol",
+                    entity_type: "voting",
+                    entity_id: Some(voting.id),
+                    old_value: None,
+                    new_value: Some(json!({
+                        "protocol_number": protocol.protocol_number,
+                        "signature": protocol.signature,
+                    })),
+                    request_id: Some(request_id.0),
+                },
+            )
+            .await?;
+
+            (protocol, results)
+        }
+    };
+
+    let (signed_by,): (String,) = sqlx::query_as("SELECT display_name FROM users WHERE id = $1")
+        .bind(protocol.signed_by)
+        .fetch_one(&state.pool)
+        .await?;
+
+    let signature = ProtocolSignature {
+        protocol_number: protocol.protocol_number.clone(),
+        signed_by,
+        signed_at: protocol.signed_at,
+        signature: protocol.signature.clone(),
+        verification_url: format!(
+            "{}/api/v1/votings/protocols/{}",
+            state.config.public_url.trim_end_matches('/'),
+            protocol.verification_token
+        ),
+    };
+    let data = DocumentService::render_voting_protocol(&results, &signature)?;
+
+    // Кэш не обязателен: если хранилище недоступно, протокол всё равно отдаётся
+    let key = format!("protocols/{}.pdf", voting.id);
+    match file_service.put_file(&key, "application/pdf", data.clone()).await {
+        Ok(_) => {
+            sqlx::query("UPDATE voting_protocols SET pdf_key = $2 WHERE voting_id = $1")
+                .bind(voting.id)
+                .bind(&key)
+                .execute(&state.pool)
+                .await?;
+        }
+        Err(e) => tracing::warn!("Protocol for voting {} not cached: {}", voting.id, e),
+    }
+
+    Ok(protocol_response(&protocol, data))
+}
+
+/// Номер протокола — порядковый в пределах ЖК за год, например `2026/3`
+async fn sign_voting_protocol(
+    state: &AppState,
+    voting: &Voting,
+    signed_by: Uuid,
+    signature: String,
+) -> AppResult<VotingProtocol> {
+    let inserted = sqlx::query_as::<_, VotingProtocol>(
+        r#"
+        INSERT INTO voting_protocols (voting_id, protocol_number, signature, verification_token, signed_by)
+        SELECT $1,
+               EXTRACT(YEAR FROM NOW())::int || '/' || (COUNT(*) + 1),
+               $3, $4, $5
+        FROM voting_protocols p
+        JOIN votings v ON v.id = p.voting_id
+        WHERE v.complex_id = $2 AND date_trunc('year', p.signed_at) = date_trunc('year', NOW())
+        ON CONFLICT (voting_id) DO NOTHING
+        RETURNING *
+        "#,
+    )
+    .bind(voting.id)
+    .bind(voting.complex_id)
+    .bind(&signature)
+    .bind(PaymentService::generate_verification_token())
+    .bind(signed_by)
+    .fetch_optional(&state.pool)
+    .await?;
+
+    if let Some(protocol) = inserted {
+        return Ok(protocol);
+    }
+
+    // Протокол одновременно подписал кто-то другой
+    sqlx::query_as::<_, VotingProtocol>("SELECT * FROM voting_protocols WHERE voting_id = $1")
+        .bind(voting.id)
+        .fetch_one(&state.pool)
+        .await
+        .map_err(Into::into)
+}
+
+fn protocol_response(protocol: &VotingProtocol, data: Vec<u8>) -> impl IntoResponse {
+    let headers = [
+        (header::CONTENT_TYPE, "application/pdf".to_string()),
+        (
+            header::CONTENT_DISPOSITION,
+            format!(
+                "inline; filename=\"protocol-{}.pdf\"",
+                protocol.protocol_number.replace('/', "-")
+            ),
+        ),
+    ];
+    (headers, data)
+}
+
+/// Проверить подлинность протокола (ссылка из документа, без авторизации)
+#[utoipa::path(
+    get,
+    path = "/api/v1/votings/protocols/{token}",
+    tag = "voting",
+    params(
+        ("token" = String, Path, description = "Код проверки из протокола")
+    ),
+    responses(
+        (status = 200, description = "Протокол действителен", body = VotingProtocolVerification),
+        (status = 404, description = "Протокол не найден")
+    )
+)]
+pub async fn verify_voting_protocol(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> AppResult<Json<VotingProtocolVerification>> {
+    let protocol = sqlx::query_as::<_, VotingProtocolVerification>(
+        r#"
+        SELECT p.protocol_number, v.title AS voting_title, c.name AS complex_name,
+               u.display_name AS signed_by_name, p.signed_at, p.signature
+        FROM voting_protocols p
+        JOIN votings v ON v.id = p.voting_id
+        JOIN complexes c ON c.id = v.complex_id
+        JOIN users u ON u.id = p.signed_by
+        WHERE p.verification_token = $1
+        "#,
+    )
+    .bind(&token)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Протокол не найден".to_string()))?;
+
+    Ok(Json(protocol))
+}
+
+/// Выдать доверенность на голосование соседу
+#[utoipa::path(
+    post,
+    path = "/api/v1/votings/delegations",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("complex_id" = Option<Uuid>, Query, description = "ID ЖК для доверенности на период, если у пользователя их несколько")
+    ),
+    request_body = CreateVoteDelegationRequest,
+    responses(
+        (status = 200, description = "Доверенность выдана", body = VoteDelegation),
+        (status = 400, description = "Доверенное лицо не из этого ЖК или голосование завершено"),
+        (status = 401, description = "Не авторизован"),
+        (status = 403, description = "Доверенность может выдать только собственник"),
+        (status = 404, description = "Голосование не найдено"),
+        (status = 409, description = "На это голосование или период уже есть доверенность"),
+        (status = 422, description = "Неверный период")
+    )
+)]
+pub async fn create_delegation(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    complex: ComplexScope,
+    Json(payload): Json<CreateVoteDelegationRequest>,
+) -> AppResult<Json<VoteDelegation>> {
+    let complex_id = match payload.voting_id {
+        Some(voting_id) => {
+            let voting = sqlx::query_as::<_, Voting>(
+                "SELECT * FROM votings WHERE id = $1 AND complex_id = ANY($2)",
+            )
+            .bind(voting_id)
+            .bind(&complex.complex_ids)
+            .fetch_optional(&state.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Голосование не найдено".to_string()))?;
+
+            if !matches!(voting.status, VotingStatus::Draft | VotingStatus::Active) {
+                return Err(AppError::BadRequest("Голосование уже завершено".to_string()));
+            }
+            voting.complex_id
+        }
+        None => complex.complex_id()?,
+    };
+
+    if payload.proxy_id == auth_user.user_id {
+        return Err(AppError::BadRequest(
+            "Нельзя выдать доверенность самому себе".to_string(),
+        ));
+    }
+
+    let is_owner: Option<(i32,)> =
+        sqlx::query_as("SELECT 1 FROM apartments WHERE complex_id = $1 AND owner_id = $2 LIMIT 1")
+            .bind(complex_id)
+            .bind(auth_user.user_id)
+            .fetch_optional(&state.pool)
+            .await?;
+    if is_owner.is_none() {
+        return Err(AppError::Forbidden);
+    }
+
+    let proxy_lives_here: Option<(i32,)> = sqlx::query_as(
+        "SELECT 1 FROM apartments WHERE complex_id = $1 AND (owner_id = $2 OR resident_id = $2) LIMIT 1",
+    )
+    .bind(complex_id)
+    .bind(payload.proxy_id)
+    .fetch_optional(&state.pool)
+    .await?;
+    if proxy_lives_here.is_none() {
+        return Err(AppError::BadRequest(
+            "Доверенное лицо не проживает в этом ЖК".to_string(),
+        ));
+    }
+
+    let valid_from = payload.valid_from.unwrap_or_else(chrono::Utc::now);
+    if payload.valid_until.is_some_and(|until| until <= valid_from) {
+        return Err(AppError::Validation(
+            "Окончание доверенности должно быть позже начала".to_string(),
+        ));
+    }
+
+    let overlapping: Option<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM vote_delegations
+        WHERE principal_id = $1 AND complex_id = $2 AND revoked_at IS NULL
+          AND CASE
+              WHEN $3::uuid IS NOT NULL THEN voting_id = $3
+              ELSE voting_id IS NULL
+                   AND (valid_until IS NULL OR valid_until > $4)
+                   AND ($5::timestamptz IS NULL OR valid_from < $5)
+          END
+        LIMIT 1
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .bind(complex_id)
+    .bind(payload.voting_id)
+    .bind(valid_from)
+    .bind(payload.valid_until)
+    .fetch_optional(&state.pool)
+    .await?;
+    if overlapping.is_some() {
+        return Err(AppError::Conflict(
+            "На это голосование или период уже выдана доверенность".to_string(),
+        ));
+    }
+
+    let delegation = sqlx::query_as::<_, VoteDelegation>(
+        r#"
+        INSERT INTO vote_delegations (complex_id, principal_id, proxy_id, voting_id, valid_from, valid_until)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING *
+        "#,
+    )
+    .bind(complex_id)
+    .bind(auth_user.user_id)
+    .bind(payload.proxy_id)
+    .bind(payload.voting_id)
+    .bind(valid_from)
+    .bind(payload.valid_until)
+    .fetch_one(&state.pool)
+    .await?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(complex_id),
+            action: "create_vote_delegation",
+            entity_type: "vote_delegation",
+            entity_id: Some(delegation.id),
+            old_value: None,
+            new_value: Some(json!(delegation)),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
+
+    NotificationService::notify_users(
+        &state.pool,
+        &[payload.proxy_id],
+        NotificationType::Voting,
+        "Вам выдана доверенность на голосование",
+        Some("Сосед доверил вам голосовать от его имени"),
+        Some(json!({"delegation_id": delegation.id, "voting_id": delegation.voting_id})),
+    )
+    .await?;
+
+    Ok(Json(delegation))
+}
+
+/// Действующие доверенности: выданные пользователем и полученные им
+#[utoipa::path(
+    get,
+    path = "/api/v1/votings/delegations",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Доверенности", body = VoteDelegationsResponse),
+        (status = 401, description = "Не авторизован")
+    )
+)]
+pub async fn list_delegations(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+) -> AppResult<Json<VoteDelegationsResponse>> {
+    let delegations = sqlx::query_as::<_, VoteDelegation>(
+        r#"
+        SELECT * FROM vote_delegations
+        WHERE (principal_id = $1 OR proxy_id = $1)
+          AND revoked_at IS NULL
+          AND (valid_until IS NULL OR valid_until > NOW())
+        ORDER BY created_at DESC
+        "#,
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&state.pool)
+    .await?;
+
+    let (given, received) = delegations
+        .into_iter()
+        .partition(|delegation| delegation.principal_id == auth_user.user_id);
+
+    Ok(Json(VoteDelegationsResponse { given, received }))
+}
+
+/// Отозвать доверенность; уже поданные по ней голоса сохраняются
+#[utoipa::path(
+    delete,
+    path = "/api/v1/votings/delegations/{id}",
+    tag = "voting",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "ID доверенности")
+    ),
+    responses(
+        (status = 200, description = "Доверенность отозвана", body = SuccessResponse),
+        (status = 401, description = "Не авторизован"),
+        (status = 404, description = "Доверенность не найдена")
+    )
+)]
+pub async fn revoke_delegation(
+    State(state): State<AppState>,
+    auth_user: AuthUser,
+    request_id: RequestId,
+    Path(id): Path<Uuid>,
+) -> AppResult<Json<Value>> {
+    let delegation = sqlx::query_as::<_, VoteDelegation>(
+        r#"
+        UPDATE vote_delegations SET revoked_at = NOW()
+        WHERE id = $1 AND principal_id = $2 AND revoked_at IS NULL
+        RETURNING *
+        "#,
+    )
+    .bind(id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.pool)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Доверенность не найдена".to_string()))?;
+
+    AuditService::record(
+        &state.pool,
+        NewAuditLog {
+            actor_id: auth_user.user_id,
+            complex_id: Some(delegation.complex_id),
+            action: "revoke_vote_delegation",
+            entity_type: "vote_delegation",
+            entity_id: Some(id),
+            old_value: None,
+            new_value: Some(json!({"proxy_id": delegation.proxy_id})),
+            request_id: Some(request_id.0),
+        },
+    )
+    .await?;
 
     Ok(Json(json!({"success": true})))
 }
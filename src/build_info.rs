@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use utoipa::ToSchema;
+
+/// Миграции, встроенные в бинарник при компиляции
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Коммит, из которого собран бинарник
+pub const GIT_SHA: &str = env!("LOCALHOOD_GIT_SHA");
+
+const BUILD_TIMESTAMP: &str = env!("LOCALHOOD_BUILD_TIMESTAMP");
+
+/// Сведения о запущенной сборке
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    pub build_time: Option<DateTime<Utc>>,
+    /// Последняя миграция, встроенная в сборку
+    pub migration_version: Option<i64>,
+}
+
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: VERSION,
+        git_sha: GIT_SHA,
+        build_time: BUILD_TIMESTAMP
+            .parse()
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0)),
+        migration_version: latest_migration(),
+    }
+}
+
+/// Номер последней встроенной миграции
+pub fn latest_migration() -> Option<i64> {
+    MIGRATOR.iter().map(|m| m.version).max()
+}
@@ -1,21 +1,117 @@
+use rust_decimal::Decimal;
 use std::env;
 
+/// Проверка ответов API на данные чужих ЖК (для отладки и стейджинга)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TenantAuditMode {
+    Off,
+    /// Нарушение только пишется в лог
+    Log,
+    /// Ответ с чужими данными заменяется ошибкой
+    Enforce,
+}
+
+impl TenantAuditMode {
+    fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "log" => Self::Log,
+            "enforce" => Self::Enforce,
+            _ => Self::Off,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub host: String,
     pub port: u16,
     pub database_url: String,
+    pub auto_migrate: bool,
+    pub db_statement_timeout_ms: u64,
+    pub slow_query_threshold_ms: u64,
+    pub tenant_audit_mode: TenantAuditMode,
+    /// Брать IP клиента из X-Forwarded-For / X-Real-IP (только за доверенным прокси)
+    pub trust_proxy_headers: bool,
+    /// Сколько доверенных прокси дописывают X-Forwarded-For: IP клиента — столько-й адрес справа
+    pub trusted_proxy_hops: usize,
+    /// Сколько 401/403 от одного IP в админке за окно приводят к блокировке
+    pub admin_lockout_threshold: i32,
+    pub admin_lockout_minutes: i64,
     pub jwt_secret: String,
     pub jwt_access_expiry: i64,
     pub jwt_refresh_expiry: i64,
+    /// Ключ API Mobizon
     pub sms_api_key: String,
     pub sms_sender: String,
+    /// Порядок SMS-провайдеров: при сбое отправка переходит к следующему
+    pub sms_providers: Vec<String>,
+    pub smsc_login: String,
+    pub smsc_password: String,
+    /// Токен в адресе обратного вызова о доставке SMS; пустой — отчёты не принимаются
+    pub sms_callback_secret: String,
     pub sms_enabled: bool,
+    pub sms_segment_price: Decimal,
+    /// Отправитель для номеров вне зоны +7: буквенные имена за рубежом регистрируются отдельно
+    pub sms_international_sender: String,
+    pub sms_international_segment_price: Decimal,
+    /// Коды входа через WhatsApp Business Cloud API: шаблон категории authentication
+    /// с параметром кода в тексте и кнопке копирования
+    pub whatsapp_enabled: bool,
+    pub whatsapp_api_url: String,
+    pub whatsapp_phone_number_id: String,
+    pub whatsapp_access_token: String,
+    pub whatsapp_code_template: String,
+    pub whatsapp_template_language: String,
+    /// Коды стран без `+`, номера которых принимаются при входе и приглашении
+    pub phone_allowed_country_codes: Vec<String>,
+    /// HTTP API транзакционных писем: POST JSON `{from, to, subject, text}` с Bearer-ключом
+    pub email_enabled: bool,
+    pub email_api_url: String,
+    pub email_api_key: String,
+    pub email_from: String,
+    /// Срок жизни ссылки для входа и подтверждения email, минуты
+    pub magic_link_ttl_minutes: i64,
+    /// Бот для уведомлений в Telegram; вебхук проверяется по секрету из `setWebhook`
+    pub telegram_enabled: bool,
+    pub telegram_api_url: String,
+    pub telegram_bot_token: String,
+    pub telegram_bot_username: String,
+    pub telegram_webhook_secret: String,
     pub minio_endpoint: String,
     pub minio_access_key: String,
     pub minio_secret_key: String,
     pub minio_bucket: String,
     pub minio_public_url: Option<String>,
+    pub storage_backend: String,
+    pub local_storage_path: String,
+    pub upload_spool_path: String,
+    pub job_worker_concurrency: usize,
+    pub job_poll_interval_secs: u64,
+    pub scheduler_enabled: bool,
+    /// Через сколько дней без движения закрываются заявки на ЖК и на вступление
+    pub pending_expiry_days: i64,
+    /// За сколько дней до закрытия заявки напомнить заявителю
+    pub pending_expiry_reminder_days: i64,
+    /// Радиус вокруг ЖК, в котором житель считается рядом со шлагбаумом, метров
+    pub barrier_geofence_radius_m: f64,
+    /// Не открывать шлагбаум дальше радиуса, а не только отмечать это в журнале
+    pub barrier_geofence_enforce: bool,
+    pub intercom_snapshot_retention_days: i64,
+    pub intercom_sip_gateway_url: Option<String>,
+    pub ocr_api_url: Option<String>,
+    pub ocr_api_key: String,
+    /// Выгрузка КАТО и адресного регистра для загрузки справочника адресов
+    pub address_registry_url: Option<String>,
+    pub public_url: String,
+    pub kaspi_enabled: bool,
+    pub kaspi_api_url: String,
+    pub kaspi_merchant_id: String,
+    pub kaspi_api_key: String,
+    pub kaspi_webhook_secret: String,
+    /// Токен, с которым Prometheus забирает `/metrics`; пустой — метрики только администраторам
+    pub metrics_token: String,
+    /// Пеня за каждый день просрочки, доля от неоплаченной суммы счёта
+    pub penalty_daily_rate: Decimal,
 }
 
 impl Config {
@@ -27,6 +123,36 @@ impl Config {
                 .parse()
                 .unwrap_or(8080),
             database_url: env::var("DATABASE_URL")?,
+            auto_migrate: env::var("AUTO_MIGRATE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            db_statement_timeout_ms: env::var("DB_STATEMENT_TIMEOUT_MS")
+                .unwrap_or_else(|_| "15000".to_string())
+                .parse()
+                .unwrap_or(15000),
+            slow_query_threshold_ms: env::var("SLOW_QUERY_THRESHOLD_MS")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500),
+            tenant_audit_mode: env::var("TENANT_AUDIT_MODE")
+                .map(|v| TenantAuditMode::parse(&v))
+                .unwrap_or(TenantAuditMode::Off),
+            trust_proxy_headers: env::var("TRUST_PROXY_HEADERS")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            trusted_proxy_hops: env::var("TRUSTED_PROXY_HOPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|hops| *hops > 0)
+                .unwrap_or(1),
+            admin_lockout_threshold: env::var("ADMIN_LOCKOUT_THRESHOLD")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .unwrap_or(10),
+            admin_lockout_minutes: env::var("ADMIN_LOCKOUT_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
             jwt_secret: env::var("JWT_SECRET")?,
             jwt_access_expiry: env::var("JWT_ACCESS_EXPIRY")
                 .unwrap_or_else(|_| "900".to_string())
@@ -38,10 +164,68 @@ impl Config {
                 .unwrap_or(2592000),
             sms_api_key: env::var("SMS_API_KEY").unwrap_or_default(),
             sms_sender: env::var("SMS_SENDER").unwrap_or_else(|_| "LocalHood".to_string()),
+            sms_providers: env::var("SMS_PROVIDERS")
+                .unwrap_or_else(|_| "mobizon".to_string())
+                .split(',')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect(),
+            smsc_login: env::var("SMSC_LOGIN").unwrap_or_default(),
+            smsc_password: env::var("SMSC_PASSWORD").unwrap_or_default(),
+            sms_callback_secret: env::var("SMS_CALLBACK_SECRET").unwrap_or_default(),
             sms_enabled: env::var("SMS_ENABLED")
                 .unwrap_or_else(|_| "false".to_string())
                 .parse()
                 .unwrap_or(false),
+            sms_segment_price: env::var("SMS_SEGMENT_PRICE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(Decimal::from(12)),
+            sms_international_sender: env::var("SMS_INTERNATIONAL_SENDER")
+                .unwrap_or_else(|_| "LocalHood".to_string()),
+            sms_international_segment_price: env::var("SMS_INTERNATIONAL_SEGMENT_PRICE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(Decimal::from(60)),
+            whatsapp_enabled: env::var("WHATSAPP_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            whatsapp_api_url: env::var("WHATSAPP_API_URL")
+                .unwrap_or_else(|_| "https://graph.facebook.com/v19.0".to_string()),
+            whatsapp_phone_number_id: env::var("WHATSAPP_PHONE_NUMBER_ID").unwrap_or_default(),
+            whatsapp_access_token: env::var("WHATSAPP_ACCESS_TOKEN").unwrap_or_default(),
+            whatsapp_code_template: env::var("WHATSAPP_CODE_TEMPLATE")
+                .unwrap_or_else(|_| "localhood_auth_code".to_string()),
+            whatsapp_template_language: env::var("WHATSAPP_TEMPLATE_LANGUAGE")
+                .unwrap_or_else(|_| "ru".to_string()),
+            phone_allowed_country_codes: env::var("ALLOWED_PHONE_COUNTRY_CODES")
+                .unwrap_or_else(|_| "7".to_string())
+                .split(',')
+                .map(|code| code.trim().trim_start_matches('+').to_string())
+                .filter(|code| !code.is_empty())
+                .collect(),
+            email_enabled: env::var("EMAIL_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            email_api_url: env::var("EMAIL_API_URL").unwrap_or_default(),
+            email_api_key: env::var("EMAIL_API_KEY").unwrap_or_default(),
+            email_from: env::var("EMAIL_FROM")
+                .unwrap_or_else(|_| "LocalHood <noreply@localhood.kz>".to_string()),
+            magic_link_ttl_minutes: env::var("MAGIC_LINK_TTL_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .unwrap_or(15),
+            telegram_enabled: env::var("TELEGRAM_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            telegram_api_url: env::var("TELEGRAM_API_URL")
+                .unwrap_or_else(|_| "https://api.telegram.org".to_string()),
+            telegram_bot_token: env::var("TELEGRAM_BOT_TOKEN").unwrap_or_default(),
+            telegram_bot_username: env::var("TELEGRAM_BOT_USERNAME").unwrap_or_default(),
+            telegram_webhook_secret: env::var("TELEGRAM_WEBHOOK_SECRET").unwrap_or_default(),
             minio_endpoint: env::var("MINIO_ENDPOINT")
                 .unwrap_or_else(|_| "http://localhost:9000".to_string()),
             minio_access_key: env::var("MINIO_ACCESS_KEY")
@@ -51,6 +235,96 @@ impl Config {
             minio_bucket: env::var("MINIO_BUCKET")
                 .unwrap_or_else(|_| "localhood".to_string()),
             minio_public_url: env::var("MINIO_PUBLIC_URL").ok(),
+            storage_backend: env::var("STORAGE_BACKEND").unwrap_or_else(|_| "s3".to_string()),
+            local_storage_path: env::var("LOCAL_STORAGE_PATH")
+                .unwrap_or_else(|_| "./storage".to_string()),
+            upload_spool_path: env::var("UPLOAD_SPOOL_PATH")
+                .unwrap_or_else(|_| "./upload-spool".to_string()),
+            job_worker_concurrency: env::var("JOB_WORKER_CONCURRENCY")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .unwrap_or(4),
+            job_poll_interval_secs: env::var("JOB_POLL_INTERVAL_SECS")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .unwrap_or(5),
+            scheduler_enabled: env::var("SCHEDULER_ENABLED")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true),
+            pending_expiry_days: env::var("PENDING_EXPIRY_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            pending_expiry_reminder_days: env::var("PENDING_EXPIRY_REMINDER_DAYS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()
+                .unwrap_or(3),
+            barrier_geofence_radius_m: env::var("BARRIER_GEOFENCE_RADIUS_M")
+                .unwrap_or_else(|_| "500".to_string())
+                .parse()
+                .unwrap_or(500.0),
+            barrier_geofence_enforce: env::var("BARRIER_GEOFENCE_ENFORCE")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            intercom_snapshot_retention_days: env::var("INTERCOM_SNAPSHOT_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .unwrap_or(30),
+            intercom_sip_gateway_url: env::var("INTERCOM_SIP_GATEWAY_URL").ok(),
+            ocr_api_url: env::var("OCR_API_URL").ok(),
+            ocr_api_key: env::var("OCR_API_KEY").unwrap_or_default(),
+            address_registry_url: env::var("ADDRESS_REGISTRY_URL").ok(),
+            public_url: env::var("PUBLIC_URL")
+                .unwrap_or_else(|_| "http://localhost:8080".to_string()),
+            kaspi_enabled: env::var("KASPI_ENABLED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .unwrap_or(false),
+            kaspi_api_url: env::var("KASPI_API_URL")
+                .unwrap_or_else(|_| "https://pay.kaspi.kz/api/v1".to_string()),
+            kaspi_merchant_id: env::var("KASPI_MERCHANT_ID").unwrap_or_default(),
+            kaspi_api_key: env::var("KASPI_API_KEY").unwrap_or_default(),
+            kaspi_webhook_secret: env::var("KASPI_WEBHOOK_SECRET").unwrap_or_default(),
+            metrics_token: env::var("METRICS_TOKEN").unwrap_or_default(),
+            penalty_daily_rate: env::var("PENALTY_DAILY_RATE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(Decimal::new(5, 4)),
         })
     }
+
+    /// Включённые интеграции и подсистемы; показываются только администраторам
+    pub fn enabled_modules(&self) -> Vec<String> {
+        let mut modules = vec![format!("storage:{}", self.storage_backend)];
+
+        if self.sms_enabled {
+            modules.push(format!("sms:{}", self.sms_providers.join(",")));
+        }
+        if self.whatsapp_enabled {
+            modules.push("whatsapp".to_string());
+        }
+        if self.email_enabled {
+            modules.push("email".to_string());
+        }
+        if self.telegram_enabled {
+            modules.push("telegram".to_string());
+        }
+        if self.kaspi_enabled {
+            modules.push("kaspi".to_string());
+        }
+        if self.ocr_api_url.is_some() {
+            modules.push("ocr".to_string());
+        }
+        if self.intercom_sip_gateway_url.is_some() {
+            modules.push("intercom_sip".to_string());
+        }
+        if self.scheduler_enabled {
+            modules.push("scheduler".to_string());
+        }
+        if self.tenant_audit_mode != TenantAuditMode::Off {
+            modules.push(format!("tenant_audit:{:?}", self.tenant_audit_mode).to_lowercase());
+        }
+
+        modules
+    }
 }
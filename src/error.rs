@@ -6,6 +6,9 @@ use axum::{
 use serde_json::json;
 use thiserror::Error;
 
+/// Код PostgreSQL, с которым запрос прерывается по statement_timeout
+const QUERY_CANCELED: &str = "57014";
+
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Не авторизован")]
@@ -41,9 +44,33 @@ pub enum AppError {
     #[error("Ошибка SMS: {0}")]
     Sms(String),
 
+    #[error("Ошибка отправки письма: {0}")]
+    Email(String),
+
+    #[error("Ошибка платёжного провайдера: {0}")]
+    Payment(String),
+
+    #[error("Ошибка распознавания: {0}")]
+    Ocr(String),
+
     #[error("Ошибка файла: {0}")]
     File(String),
 
+    #[error("Сервис {0} временно недоступен")]
+    ServiceUnavailable(String),
+
+    #[error("Хранилище файлов недоступно: {0}")]
+    StorageUnavailable(String),
+
+    #[error("Ошибка домофона: {0}")]
+    Intercom(String),
+
+    #[error("Ошибка шлагбаума: {0}")]
+    Barrier(String),
+
+    #[error("Ошибка видеопотока: {0}")]
+    Stream(String),
+
     #[error("Код подтверждения истёк")]
     CodeExpired,
 
@@ -52,6 +79,18 @@ pub enum AppError {
 
     #[error("Превышено количество попыток")]
     TooManyAttempts,
+
+    #[error("Сессия привязана к другому устройству, войдите заново")]
+    DeviceChanged,
+
+    #[error("Для входа нужен отпечаток устройства в заголовке X-Device-Fingerprint")]
+    DeviceFingerprintRequired,
+
+    #[error("Нужно принять новую версию пользовательского соглашения")]
+    ConsentRequired,
+
+    #[error("Въезд запрещён: {0}")]
+    EntryDenied(String),
 }
 
 impl IntoResponse for AppError {
@@ -72,6 +111,16 @@ impl IntoResponse for AppError {
                 "TOO_MANY_REQUESTS",
                 self.to_string(),
             ),
+            AppError::Database(sqlx::Error::Database(e))
+                if e.code().as_deref() == Some(QUERY_CANCELED) =>
+            {
+                tracing::error!("Statement timeout: {}", e);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "QUERY_TIMEOUT",
+                    "База данных не ответила вовремя, попробуйте позже".to_string(),
+                )
+            }
             AppError::Database(e) => {
                 tracing::error!("Database error: {:?}", e);
                 (
@@ -94,7 +143,26 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::Sms(msg) => (StatusCode::SERVICE_UNAVAILABLE, "SMS_ERROR", msg.clone()),
+            AppError::Email(msg) => (StatusCode::SERVICE_UNAVAILABLE, "EMAIL_ERROR", msg.clone()),
+            AppError::Payment(msg) => (StatusCode::BAD_GATEWAY, "PAYMENT_ERROR", msg.clone()),
+            AppError::Ocr(msg) => (StatusCode::SERVICE_UNAVAILABLE, "OCR_ERROR", msg.clone()),
             AppError::File(msg) => (StatusCode::BAD_REQUEST, "FILE_ERROR", msg.clone()),
+            AppError::ServiceUnavailable(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "SERVICE_UNAVAILABLE",
+                self.to_string(),
+            ),
+            AppError::StorageUnavailable(msg) => {
+                tracing::error!("Storage unavailable: {}", msg);
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "STORAGE_UNAVAILABLE",
+                    "Хранилище файлов временно недоступно, попробуйте позже".to_string(),
+                )
+            }
+            AppError::Intercom(msg) => (StatusCode::BAD_GATEWAY, "INTERCOM_ERROR", msg.clone()),
+            AppError::Barrier(msg) => (StatusCode::BAD_GATEWAY, "BARRIER_ERROR", msg.clone()),
+            AppError::Stream(msg) => (StatusCode::BAD_GATEWAY, "STREAM_ERROR", msg.clone()),
             AppError::CodeExpired => (StatusCode::BAD_REQUEST, "CODE_EXPIRED", self.to_string()),
             AppError::InvalidCode => (StatusCode::BAD_REQUEST, "INVALID_CODE", self.to_string()),
             AppError::TooManyAttempts => (
@@ -102,6 +170,18 @@ impl IntoResponse for AppError {
                 "TOO_MANY_ATTEMPTS",
                 self.to_string(),
             ),
+            AppError::DeviceChanged => {
+                (StatusCode::UNAUTHORIZED, "DEVICE_CHANGED", self.to_string())
+            }
+            AppError::DeviceFingerprintRequired => (
+                StatusCode::BAD_REQUEST,
+                "DEVICE_FINGERPRINT_REQUIRED",
+                self.to_string(),
+            ),
+            AppError::ConsentRequired => {
+                (StatusCode::FORBIDDEN, "CONSENT_REQUIRED", self.to_string())
+            }
+            AppError::EntryDenied(_) => (StatusCode::FORBIDDEN, "ENTRY_DENIED", self.to_string()),
         };
 
         let body = Json(json!({
@@ -1,4 +1,5 @@
 pub mod api;
+pub mod build_info;
 pub mod config;
 pub mod error;
 pub mod middleware;
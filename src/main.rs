@@ -1,39 +1,59 @@
 use axum::{
-    http::{header, Method},
+    extract::{Query, State},
+    http::{header, HeaderMap, HeaderName, Method, StatusCode},
     middleware as axum_middleware,
+    response::{IntoResponse, Response},
     routing::get,
     Json, Router,
 };
 use serde_json::json;
-use sqlx::postgres::PgPoolOptions;
+use sha2::{Digest, Sha256};
+use log::LevelFilter;
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgPoolOptions};
+use sqlx::{ConnectOptions, Connection};
 use std::net::SocketAddr;
+use std::time::Duration;
 use tower_http::{
     cors::{Any, CorsLayer},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+use utoipa_swagger_ui::{SwaggerUi, Url};
 
 use localhood_backend::{
     api,
+    build_info::{build_info, MIGRATOR},
     config::Config,
-    middleware::{auth_middleware, AppState},
-    ApiDoc,
+    error::AppError,
+    middleware::{
+        admin_guard_middleware, auth_middleware, consent_middleware, is_admin_or_higher,
+        maintenance_middleware, request_id_middleware, tenant_audit_middleware, AppState, AuthUser,
+        COMPLEX_ID_HEADER, DEVICE_FINGERPRINT_HEADER, REQUEST_ID_HEADER,
+    },
+    services::{
+        query_metrics, resilience, AnnouncementService, BannerService, BarrierService, BillingService, ChatService, FileService, IntercomService, JobService,
+        MaintenanceScheduleService, MigrationService, MoveOutService, NotificationStreamService, PendingExpiryService, SchedulerService, ViewService,
+        VotingService, WarehouseExportService,
+    },
+    openapi::{openapi_for, ApiAudience, OpenApiQuery},
 };
 
+/// Сколько ждём ответа хранилища в health-check
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Загружаем .env файл
     dotenvy::dotenv().ok();
 
-    // Инициализируем логирование
+    // Инициализируем логирование; метрики запросов к базе собираются независимо от уровня логов
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "localhood_backend=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                "localhood_backend=debug,tower_http=debug,sqlx::query=warn".into()
+            }),
+        ))
+        .with(query_metrics::layer())
         .init();
 
     // Загружаем конфигурацию
@@ -43,21 +63,86 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Connecting to database...");
 
     // Подключаемся к базе данных
+    // Все запросы попадают в события sqlx::query ради метрик, медленные — ещё и в лог
+    let connect_options = config
+        .database_url
+        .parse::<PgConnectOptions>()
+        .expect("Invalid DATABASE_URL")
+        .options([("statement_timeout", config.db_statement_timeout_ms)])
+        .log_statements(LevelFilter::Trace)
+        .log_slow_statements(
+            LevelFilter::Warn,
+            Duration::from_millis(config.slow_query_threshold_ms),
+        );
+
     let pool = PgPoolOptions::new()
         .max_connections(10)
-        .connect(&config.database_url)
+        .connect_with(connect_options)
         .await
         .expect("Failed to connect to database");
 
     tracing::info!("Connected to database successfully");
 
-    // Запускаем миграции
-    tracing::info!("Running database migrations...");
-    sqlx::migrate!("./migrations")
-        .run(&pool)
-        .await
-        .expect("Failed to run migrations");
-    tracing::info!("Migrations completed");
+    if config.auto_migrate {
+        // Запускаем миграции
+        tracing::info!("Running database migrations...");
+        // Отдельное соединение без statement_timeout: миграции бывают долгими
+        let mut connection = PgConnection::connect(&config.database_url)
+            .await
+            .expect("Failed to connect to database");
+        MIGRATOR
+            .run(&mut connection)
+            .await
+            .expect("Failed to run migrations");
+        tracing::info!("Migrations completed");
+    } else {
+        // В продакшене миграции применяются отдельным шагом выкладки: с расхождением не стартуем
+        let report = MigrationService::status(&pool).await?;
+        if report.drift {
+            tracing::error!(
+                "Database schema does not match this build: {} pending, applied {:?}, embedded {:?}",
+                report.pending,
+                report.applied_version,
+                report.embedded_version
+            );
+            anyhow::bail!("Database migrations are out of sync and AUTO_MIGRATE is disabled");
+        }
+        tracing::info!("Database schema is up to date");
+    }
+
+    // Проверяем хранилище файлов; без него API работает, но загрузки откладываются или недоступны
+    match FileService::new(&config).await?.check().await {
+        Ok(()) => tracing::info!("Storage backend '{}' is available", config.storage_backend),
+        Err(e) => tracing::error!(
+            "Storage backend '{}' is unavailable, file uploads will be degraded: {}",
+            config.storage_backend,
+            e
+        ),
+    }
+
+    // Запускаем воркеры фоновых задач
+    JobService::new(pool.clone(), config.clone()).start();
+
+    // Запускаем батчер просмотров
+    ViewService::start(pool.clone());
+
+    // Слушаем изменения уведомлений для живых потоков клиентов
+    NotificationStreamService::start(pool.clone());
+
+    // Запускаем периодические задачи обслуживания
+    let mut scheduler = SchedulerService::new(pool.clone(), config.clone());
+    AnnouncementService::register_jobs(&mut scheduler);
+    BannerService::register_jobs(&mut scheduler);
+    BarrierService::register_jobs(&mut scheduler);
+    BillingService::register_jobs(&mut scheduler);
+    ChatService::register_jobs(&mut scheduler);
+    IntercomService::register_jobs(&mut scheduler);
+    MaintenanceScheduleService::register_jobs(&mut scheduler);
+    MoveOutService::register_jobs(&mut scheduler);
+    PendingExpiryService::register_jobs(&mut scheduler);
+    VotingService::register_jobs(&mut scheduler);
+    WarehouseExportService::register_jobs(&mut scheduler);
+    scheduler.start();
 
     // Создаём состояние приложения
     let state = AppState {
@@ -76,19 +161,51 @@ async fn main() -> anyhow::Result<()> {
             Method::PATCH,
             Method::OPTIONS,
         ])
-        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::ACCEPT]);
+        .allow_headers([
+            header::AUTHORIZATION,
+            header::CONTENT_TYPE,
+            header::ACCEPT,
+            HeaderName::from_static(COMPLEX_ID_HEADER),
+            HeaderName::from_static(DEVICE_FINGERPRINT_HEADER),
+            HeaderName::from_static(REQUEST_ID_HEADER),
+        ])
+        .expose_headers([HeaderName::from_static(REQUEST_ID_HEADER)]);
 
     // Создаём роутер
     let app = Router::new()
         .route("/", get(root))
         .route("/health", get(health_check))
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+        .route("/ready", get(readiness_check))
+        .route("/metrics", get(metrics))
+        .route("/api-docs/openapi.json", get(openapi_json))
+        .merge(SwaggerUi::new("/swagger-ui").config(utoipa_swagger_ui::Config::new([
+            Url::new("Председатель", "/api-docs/openapi.json?audience=chairman"),
+            Url::new("Оборудование", "/api-docs/openapi.json?audience=device"),
+            Url::new("Полный", "/api-docs/openapi.json?audience=admin"),
+        ])))
         .nest("/api/v1", api::routes())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            tenant_audit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            consent_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            maintenance_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            admin_guard_middleware,
+        ))
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
         ))
-        .layer(TraceLayer::new_for_http())
+        .layer(TraceLayer::new_for_http().make_span_with(query_metrics::request_span))
+        .layer(axum_middleware::from_fn(request_id_middleware))
         .layer(cors)
         .with_state(state);
 
@@ -97,22 +214,118 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Server running on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
 
-async fn root() -> Json<serde_json::Value> {
-    Json(json!({
+/// Сведения о сборке; включённые модули видны только администраторам
+async fn root(State(state): State<AppState>, auth_user: Option<AuthUser>) -> Json<serde_json::Value> {
+    let mut body = json!({
         "name": "LocalHood API",
-        "version": "1.0.0",
-        "description": "Backend API for LocalHood - residential complex management platform"
-    }))
+        "description": env!("CARGO_PKG_DESCRIPTION"),
+        "build": build_info(),
+    });
+
+    if auth_user.is_some_and(|user| is_admin_or_higher(&user.role)) {
+        body["modules"] = json!(state.config.enabled_modules());
+    }
+
+    Json(body)
+}
+
+/// Документ OpenAPI; `audience=chairman|device|admin` оставляет только операции этой аудитории.
+/// По умолчанию отдаём документ для председателей, полный — только администраторам
+async fn openapi_json(Query(query): Query<OpenApiQuery>, auth_user: Option<AuthUser>) -> Response {
+    let audience = query.audience.unwrap_or(ApiAudience::Chairman);
+
+    if audience == ApiAudience::Admin {
+        match auth_user {
+            Some(user) if is_admin_or_higher(&user.role) => {}
+            Some(_) => return AppError::Forbidden.into_response(),
+            None => return AppError::Unauthorized.into_response(),
+        }
+    }
+
+    Json(openapi_for(audience)).into_response()
 }
 
-async fn health_check() -> Json<serde_json::Value> {
+/// Метрики Prometheus: по токену `METRICS_TOKEN` или администратору
+async fn metrics(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    auth_user: Option<AuthUser>,
+) -> Response {
+    let token = &state.config.metrics_token;
+    let scraper = !token.is_empty()
+        && headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|bearer| Sha256::digest(bearer.as_bytes()) == Sha256::digest(token.as_bytes()));
+
+    match auth_user {
+        _ if scraper => {}
+        Some(user) if is_admin_or_higher(&user.role) => {}
+        Some(_) => return AppError::Forbidden.into_response(),
+        None => return AppError::Unauthorized.into_response(),
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        resilience::render_metrics() + &query_metrics::render_metrics(),
+    )
+        .into_response()
+}
+
+/// Готовность принимать трафик: база доступна и схема совпадает со сборкой
+async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
+    match MigrationService::status(&state.pool).await {
+        Ok(report) if !report.drift => (
+            StatusCode::OK,
+            Json(json!({"status": "ready", "migration_version": report.applied_version})),
+        ),
+        Ok(report) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({
+                "status": "migration_drift",
+                "pending": report.pending,
+                "applied_version": report.applied_version,
+                "embedded_version": report.embedded_version,
+            })),
+        ),
+        Err(e) => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"status": "database_unavailable", "error": e.to_string()})),
+        ),
+    }
+}
+
+async fn health_check(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let storage = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, async {
+        FileService::new(&state.config).await?.check().await
+    })
+    .await
+    {
+        Ok(Ok(())) => json!({"status": "ok"}),
+        Ok(Err(e)) => json!({"status": "unavailable", "error": e.to_string()}),
+        Err(_) => json!({"status": "unavailable", "error": "timeout"}),
+    };
+
+    let status = if storage["status"] == "ok" { "ok" } else { "degraded" };
+
+    let build = build_info();
+
     Json(json!({
-        "status": "ok",
+        "status": status,
+        "storage": storage,
+        "version": build.version,
+        "git_sha": build.git_sha,
+        "migration_version": build.migration_version,
         "timestamp": chrono::Utc::now().to_rfc3339()
     }))
 }
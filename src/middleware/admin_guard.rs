@@ -0,0 +1,150 @@
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, FromRequestParts, State},
+    http::{header, Extensions, HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde_json::json;
+
+use super::auth::{is_admin_or_higher, AppState, AuthUser};
+use crate::config::Config;
+use crate::services::AdminGuardService;
+
+const ADMIN_PREFIX: &str = "/api/v1/admin";
+
+// Middleware защиты админки от подбора: блокирует IP после серии 401/403.
+// 403 администратора (например, на действие только для супер-админа) не считается
+pub async fn admin_guard_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    if !path.starts_with(ADMIN_PREFIX) {
+        return next.run(request).await;
+    }
+    let Some(ip) = client_ip(&state.config, request.headers(), request.extensions()) else {
+        return next.run(request).await;
+    };
+
+    match AdminGuardService::blocked_until(&state.pool, &ip).await {
+        Ok(Some(blocked_until)) => return blocked_response(blocked_until),
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to check admin block for {}: {}", ip, e),
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let is_admin = AuthUser::from_request_parts(&mut parts, &state)
+        .await
+        .is_ok_and(|user| is_admin_or_higher(&user.role));
+    let request = Request::from_parts(parts, body);
+
+    let response = next.run(request).await;
+    if counts_as_failure(response.status(), is_admin) {
+        if let Err(e) =
+            AdminGuardService::record_failure(&state.pool, &state.config, &ip, &path).await
+        {
+            tracing::error!("Failed to record admin failure for {}: {}", ip, e);
+        }
+    }
+
+    response
+}
+
+/// Ответ похож на подбор доступа: 401 или 403 не администратору
+fn counts_as_failure(status: StatusCode, is_admin: bool) -> bool {
+    status == StatusCode::UNAUTHORIZED || (status == StatusCode::FORBIDDEN && !is_admin)
+}
+
+/// IP клиента: из заголовков доверенного прокси или адрес соединения
+pub(crate) fn client_ip(config: &Config, headers: &HeaderMap, extensions: &Extensions) -> Option<String> {
+    if config.trust_proxy_headers {
+        let forwarded = headers
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| forwarded_client(value, config.trusted_proxy_hops))
+            .or_else(|| {
+                headers
+                    .get("x-real-ip")
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+            });
+        if let Some(ip) = forwarded {
+            return Some(ip.to_string());
+        }
+    }
+
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+}
+
+/// Адрес из X-Forwarded-For, дописанный внешним доверенным прокси. Левее него
+/// стоят значения, которые клиент мог прислать сам
+fn forwarded_client(value: &str, hops: usize) -> Option<&str> {
+    value
+        .rsplit(',')
+        .map(str::trim)
+        .nth(hops.saturating_sub(1))
+        .filter(|ip| !ip.is_empty())
+}
+
+fn blocked_response(blocked_until: chrono::DateTime<Utc>) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(json!({
+            "success": false,
+            "error": {
+                "code": "ADMIN_IP_BLOCKED",
+                "message": "Доступ к админ-панели с этого адреса временно заблокирован",
+                "blocked_until": blocked_until
+            }
+        })),
+    )
+        .into_response();
+
+    let retry_after = (blocked_until - Utc::now()).num_seconds().max(0);
+    if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+        response.headers_mut().insert(header::RETRY_AFTER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forwarded_client_ignores_spoofed_entries() {
+        // Клиент прислал свой X-Forwarded-For, nginx дописал реальный адрес
+        assert_eq!(forwarded_client("1.1.1.1, 203.0.113.7", 1), Some("203.0.113.7"));
+        assert_eq!(forwarded_client("203.0.113.7", 1), Some("203.0.113.7"));
+    }
+
+    #[test]
+    fn forwarded_client_skips_inner_proxies() {
+        // CDN дописал клиента, nginx — адрес CDN
+        assert_eq!(
+            forwarded_client("1.1.1.1, 203.0.113.7, 198.51.100.2", 2),
+            Some("203.0.113.7")
+        );
+        assert_eq!(forwarded_client("198.51.100.2", 2), None);
+        assert_eq!(forwarded_client("1.1.1.1, ", 1), None);
+    }
+
+    #[test]
+    fn admin_forbidden_not_counted() {
+        assert!(counts_as_failure(StatusCode::UNAUTHORIZED, false));
+        assert!(counts_as_failure(StatusCode::UNAUTHORIZED, true));
+        assert!(counts_as_failure(StatusCode::FORBIDDEN, false));
+        assert!(!counts_as_failure(StatusCode::FORBIDDEN, true));
+        assert!(!counts_as_failure(StatusCode::OK, false));
+    }
+}
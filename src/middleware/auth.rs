@@ -11,9 +11,13 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::error::AppError;
 use crate::models::UserRole;
 use crate::services::AuthService;
 
+/// Заголовок с отпечатком устройства клиента
+pub const DEVICE_FINGERPRINT_HEADER: &str = "x-device-fingerprint";
+
 #[derive(Clone, Debug)]
 pub struct AuthUser {
     pub user_id: Uuid,
@@ -26,18 +30,17 @@ pub struct AppState {
     pub config: Config,
 }
 
-// Вспомогательные функции для проверки ролей
-pub fn is_chairman_or_higher(role: &UserRole) -> bool {
-    matches!(
-        role,
-        UserRole::Chairman | UserRole::Admin | UserRole::SuperAdmin
-    )
-}
-
+// Вспомогательные функции для проверки ролей.
+// Председатель — роль в конкретном ЖК, её проверяет `PermissionService`
 pub fn is_admin_or_higher(role: &UserRole) -> bool {
     matches!(role, UserRole::Admin | UserRole::SuperAdmin)
 }
 
+/// Модератор контента платформы или администратор
+pub fn is_moderator_or_higher(role: &UserRole) -> bool {
+    matches!(role, UserRole::Moderator | UserRole::Admin | UserRole::SuperAdmin)
+}
+
 pub fn is_owner_or_higher(role: &UserRole) -> bool {
     matches!(
         role,
@@ -53,7 +56,7 @@ pub fn is_resident_or_higher(role: &UserRole) -> bool {
     !matches!(role, UserRole::User)
 }
 
-fn parse_role(role_str: &str) -> UserRole {
+pub(crate) fn parse_role(role_str: &str) -> UserRole {
     match role_str {
         "user" => UserRole::User,
         "resident" => UserRole::Resident,
@@ -67,6 +70,15 @@ fn parse_role(role_str: &str) -> UserRole {
     }
 }
 
+/// Хэш отпечатка устройства из заголовка запроса
+pub(crate) fn device_hash(headers: &axum::http::HeaderMap) -> Option<String> {
+    headers
+        .get(DEVICE_FINGERPRINT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.trim().is_empty())
+        .map(AuthService::device_hash)
+}
+
 // Middleware для добавления AppState в extensions
 pub async fn auth_middleware(
     State(state): State<AppState>,
@@ -138,6 +150,13 @@ where
                 .into_response());
         }
 
+        // Сессия привилегированной роли действует только с устройства, где выполнен вход
+        if let Some(bound) = &claims.device {
+            if device_hash(&parts.headers).as_ref() != Some(bound) {
+                return Err(AppError::DeviceChanged.into_response());
+            }
+        }
+
         // Парсим user_id
         let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
             (
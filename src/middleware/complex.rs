@@ -0,0 +1,141 @@
+use axum::{
+    extract::Query,
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::auth::{AppState, AuthUser};
+use crate::error::{AppError, AppResult};
+
+/// Заголовок для выбора ЖК, альтернатива параметру `?complex_id=`
+pub const COMPLEX_ID_HEADER: &str = "x-complex-id";
+
+/// ЖК, в контексте которого выполняется запрос.
+/// Если у пользователя несколько ЖК, нужный выбирается через `?complex_id=` или `X-Complex-Id`.
+#[derive(Clone, Debug)]
+pub struct ComplexScope {
+    /// ЖК, явно выбранный в запросе
+    pub selected: Option<Uuid>,
+    /// Все ЖК пользователя: по квартирам, председательству в ОСИ и ролям в ЖК
+    pub complex_ids: Vec<Uuid>,
+}
+
+impl ComplexScope {
+    /// Единственный ЖК запроса: выбранный явно или единственный у пользователя
+    pub fn complex_id(&self) -> AppResult<Uuid> {
+        match (self.selected, self.complex_ids.as_slice()) {
+            (Some(id), _) => Ok(id),
+            (None, [only]) => Ok(*only),
+            _ => Err(AppError::BadRequest(
+                "У вас несколько ЖК, укажите complex_id".to_string(),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ComplexSelector {
+    complex_id: Option<Uuid>,
+}
+
+/// Все ЖК пользователя: где у него есть квартира в собственности, он проживает,
+/// состоит в семье жителя, председательствует в ОСИ или имеет роль в ЖК
+pub async fn get_user_complexes(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<Uuid>> {
+    let complexes: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT complex_id
+        FROM (
+            SELECT a.complex_id, a.created_at
+            FROM apartments a
+            WHERE a.owner_id = $1 OR a.resident_id = $1
+               OR EXISTS (
+                   SELECT 1 FROM family_members f
+                   WHERE f.apartment_id = a.id AND f.user_id = $1
+               )
+            UNION ALL
+            SELECT o.complex_id, o.created_at FROM osi o WHERE o.chairman_id = $1
+            UNION ALL
+            SELECT r.complex_id, r.created_at FROM complex_roles r WHERE r.user_id = $1
+        ) c
+        GROUP BY complex_id
+        ORDER BY MIN(created_at)
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(complexes.into_iter().map(|(id,)| id).collect())
+}
+
+/// ЖК действия: указанный явно или единственный у пользователя.
+/// Для тех, кто в ЖК не состоит (администраторы), ЖК нужно указать
+pub async fn resolve_complex(pool: &PgPool, user_id: Uuid, requested: Option<Uuid>) -> AppResult<Uuid> {
+    if let Some(complex_id) = requested {
+        return Ok(complex_id);
+    }
+
+    match get_user_complexes(pool, user_id).await?.as_slice() {
+        [only] => Ok(*only),
+        [] => Err(AppError::BadRequest("Укажите complex_id".to_string())),
+        _ => Err(AppError::BadRequest(
+            "У вас несколько ЖК, укажите complex_id".to_string(),
+        )),
+    }
+}
+
+pub(crate) fn requested_complex(parts: &Parts) -> AppResult<Option<Uuid>> {
+    if let Some(value) = parts.headers.get(COMPLEX_ID_HEADER) {
+        let id = value
+            .to_str()
+            .ok()
+            .and_then(|v| Uuid::parse_str(v.trim()).ok())
+            .ok_or_else(|| AppError::BadRequest("Неверный заголовок X-Complex-Id".to_string()))?;
+        return Ok(Some(id));
+    }
+
+    let Query(selector) = Query::<ComplexSelector>::try_from_uri(&parts.uri)
+        .map_err(|_| AppError::BadRequest("Неверный complex_id".to_string()))?;
+
+    Ok(selector.complex_id)
+}
+
+#[axum::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for ComplexScope
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+
+        let app_state = parts
+            .extensions
+            .get::<AppState>()
+            .cloned()
+            .ok_or_else(|| AppError::Internal("AppState не найден".to_string()).into_response())?;
+
+        let requested = requested_complex(parts).map_err(IntoResponse::into_response)?;
+
+        let complex_ids = get_user_complexes(&app_state.pool, auth_user.user_id)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        let allowed = match requested {
+            Some(id) => complex_ids.contains(&id),
+            None => !complex_ids.is_empty(),
+        };
+        if !allowed {
+            return Err(AppError::Forbidden.into_response());
+        }
+
+        Ok(ComplexScope {
+            selected: requested,
+            complex_ids,
+        })
+    }
+}
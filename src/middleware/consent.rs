@@ -0,0 +1,98 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::auth::{AppState, AuthUser};
+use crate::error::AppError;
+use crate::services::ConsentService;
+
+/// Разделы с персональными данными, закрытые до принятия действующих документов
+const CONSENT_REQUIRED_PREFIXES: &[&str] = &[
+    "/api/v1/apartments",
+    "/api/v1/osi",
+    "/api/v1/security",
+    "/api/v1/announcements",
+    "/api/v1/marketplace",
+    "/api/v1/votings",
+    "/api/v1/communal",
+    "/api/v1/chat",
+    "/api/v1/maintenance",
+    "/api/v1/files",
+    "/api/v1/surveys",
+];
+
+/// Разделы ЖК (`/api/v1/complexes/{id}/...`) с переписками и текстами жителей.
+/// Поиск и карточка ЖК нужны для вступления и доступны без согласия
+const CONSENT_REQUIRED_COMPLEX_SECTIONS: &[&str] = &["communication-exports", "templates"];
+
+fn requires_consent(path: &str) -> bool {
+    if CONSENT_REQUIRED_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return true;
+    }
+
+    path.strip_prefix("/api/v1/complexes/")
+        .and_then(|rest| rest.split('/').nth(1))
+        .is_some_and(|section| CONSENT_REQUIRED_COMPLEX_SECTIONS.contains(&section))
+}
+
+// Middleware согласий: без принятой версии соглашения разделы с данными недоступны
+pub async fn consent_middleware(
+    State(state): State<AppState>,
+    auth_user: Option<AuthUser>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let Some(auth_user) = auth_user else {
+        return next.run(request).await;
+    };
+    if !requires_consent(path) {
+        return next.run(request).await;
+    }
+
+    match ConsentService::consent_required(&state.pool, auth_user.user_id).await {
+        Ok(true) => AppError::ConsentRequired.into_response(),
+        Ok(false) => next.run(request).await,
+        // Без проверки согласия данные не отдаём
+        Err(e) => {
+            tracing::error!("Failed to check consent of {}: {}", auth_user.user_id, e);
+            AppError::ServiceUnavailable("согласий".to_string()).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complex_exports_and_templates_require_consent() {
+        let id = "0b6c1f4e-2a7d-4f7e-9a51-3c2d8e9f0a11";
+        assert!(requires_consent(&format!("/api/v1/complexes/{}/communication-exports", id)));
+        assert!(requires_consent(&format!(
+            "/api/v1/complexes/{}/communication-exports/{}",
+            id, id
+        )));
+        assert!(requires_consent(&format!("/api/v1/complexes/{}/templates", id)));
+        assert!(requires_consent("/api/v1/chat"));
+    }
+
+    #[test]
+    fn complex_lookup_stays_open() {
+        assert!(!requires_consent("/api/v1/complexes/search"));
+        assert!(!requires_consent(
+            "/api/v1/complexes/0b6c1f4e-2a7d-4f7e-9a51-3c2d8e9f0a11"
+        ));
+        assert!(!requires_consent(
+            "/api/v1/complexes/0b6c1f4e-2a7d-4f7e-9a51-3c2d8e9f0a11/join"
+        ));
+        assert!(!requires_consent("/api/v1/legal/documents"));
+    }
+}
@@ -0,0 +1,71 @@
+use axum::{
+    http::request::Parts,
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use super::auth::{AppState, AuthUser};
+use super::complex::requested_complex;
+use crate::error::AppError;
+
+/// Охранник ЖК: активный сотрудник ОСИ с ролью guard, привязанный к аккаунту.
+/// Если охранник работает в нескольких ЖК, нужный выбирается так же, как в `ComplexScope`.
+#[derive(Clone, Debug)]
+pub struct GuardUser {
+    pub user_id: Uuid,
+    pub worker_id: Uuid,
+    pub complex_id: Uuid,
+}
+
+#[axum::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for GuardUser
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthUser::from_request_parts(parts, state).await?;
+
+        let app_state = parts
+            .extensions
+            .get::<AppState>()
+            .cloned()
+            .ok_or_else(|| AppError::Internal("AppState не найден".to_string()).into_response())?;
+
+        let requested = requested_complex(parts).map_err(IntoResponse::into_response)?;
+
+        let posts: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT w.id, o.complex_id
+            FROM osi_workers w
+            JOIN osi o ON o.id = w.osi_id
+            WHERE w.user_id = $1 AND w.role = 'guard' AND w.is_active = true
+            "#,
+        )
+        .bind(auth_user.user_id)
+        .fetch_all(&app_state.pool)
+        .await
+        .map_err(|e| AppError::from(e).into_response())?;
+
+        let post = match (requested, posts.as_slice()) {
+            (Some(id), _) => posts.iter().find(|(_, complex_id)| *complex_id == id),
+            (None, [only]) => Some(only),
+            (None, []) => None,
+            (None, _) => {
+                return Err(AppError::BadRequest(
+                    "Вы охраняете несколько ЖК, укажите complex_id".to_string(),
+                )
+                .into_response())
+            }
+        };
+
+        let (worker_id, complex_id) = *post.ok_or_else(|| AppError::Forbidden.into_response())?;
+
+        Ok(GuardUser {
+            user_id: auth_user.user_id,
+            worker_id,
+            complex_id,
+        })
+    }
+}
@@ -0,0 +1,90 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, header::AUTHORIZATION, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::Utc;
+use serde_json::json;
+
+use super::auth::{is_admin_or_higher, parse_role, AppState};
+use crate::services::{AuthService, SettingsService};
+
+/// Пути, доступные во время технических работ
+const MAINTENANCE_ALLOWED_PREFIXES: &[&str] = &[
+    "/api/v1/admin",
+    "/api/v1/auth",
+    "/api/v1/bootstrap",
+    "/api/v1/communal/payments/webhook",
+    "/api/v1/telegram/webhook",
+    "/api/v1/sms/status",
+];
+
+// Middleware режима обслуживания: 503 для всех, кроме администраторов
+pub async fn maintenance_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !path.starts_with("/api/v1")
+        || MAINTENANCE_ALLOWED_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        return next.run(request).await;
+    }
+
+    let mode = match SettingsService::maintenance_mode(&state.pool).await {
+        Ok(mode) => mode,
+        Err(e) => {
+            tracing::error!("Failed to load maintenance mode: {}", e);
+            return next.run(request).await;
+        }
+    };
+
+    let now = Utc::now();
+    if !mode.is_active(now) || is_admin_request(&state, &request) {
+        return next.run(request).await;
+    }
+
+    let mut response = (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(json!({
+            "success": false,
+            "error": {
+                "code": "MAINTENANCE",
+                "message": mode.message_or_default(),
+                "eta": mode.ends_at
+            }
+        })),
+    )
+        .into_response();
+
+    if let Some(ends_at) = mode.ends_at {
+        let retry_after = (ends_at - now).num_seconds().max(0);
+        if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+    }
+
+    response
+}
+
+fn is_admin_request(state: &AppState, request: &Request<Body>) -> bool {
+    let Some(token) = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+
+    AuthService::new(state.config.clone())
+        .verify_token(token)
+        .map(|claims| claims.token_type == "access" && is_admin_or_higher(&parse_role(&claims.role)))
+        .unwrap_or(false)
+}
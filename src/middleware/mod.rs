@@ -1,6 +1,20 @@
+pub mod admin_guard;
 pub mod auth;
+pub mod complex;
+pub mod consent;
+pub mod guard;
+pub mod maintenance;
+pub mod request_id;
+pub mod tenant_audit;
 
+pub use admin_guard::admin_guard_middleware;
 pub use auth::{
-    auth_middleware, is_admin_or_higher, is_chairman_or_higher, is_owner_or_higher,
-    is_resident_or_higher, AppState, AuthUser,
+    auth_middleware, is_admin_or_higher, is_moderator_or_higher, is_owner_or_higher,
+    is_resident_or_higher, AppState, AuthUser, DEVICE_FINGERPRINT_HEADER,
 };
+pub use complex::{get_user_complexes, resolve_complex, ComplexScope, COMPLEX_ID_HEADER};
+pub use consent::consent_middleware;
+pub use guard::GuardUser;
+pub use maintenance::maintenance_middleware;
+pub use request_id::{request_id_middleware, RequestId, REQUEST_ID_HEADER};
+pub use tenant_audit::tenant_audit_middleware;
@@ -0,0 +1,52 @@
+use axum::{
+    body::Body,
+    http::{request::Parts, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::convert::Infallible;
+use uuid::Uuid;
+
+/// Заголовок с идентификатором запроса
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Идентификатор текущего запроса: из `X-Request-Id` клиента или сгенерированный
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+/// Проставляет идентификатор запроса в extensions и в заголовок ответа
+pub async fn request_id_middleware(mut request: Request<Body>, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty() && v.len() <= 64)
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[axum::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .unwrap_or_else(|| RequestId(Uuid::new_v4().to_string())))
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{header, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::auth::{is_admin_or_higher, AppState, AuthUser};
+use crate::config::TenantAuditMode;
+use crate::error::{AppError, AppResult};
+
+/// Поля ответа, ссылающиеся на данные ЖК, и запрос, возвращающий ЖК каждой записи
+const SCOPED_ENTITIES: &[(&str, &str)] = &[
+    ("complex_id", "SELECT id, id FROM complexes WHERE id = ANY($1)"),
+    ("apartment_id", "SELECT id, complex_id FROM apartments WHERE id = ANY($1)"),
+    ("osi_id", "SELECT id, complex_id FROM osi WHERE id = ANY($1)"),
+    ("bill_id", "SELECT id, complex_id FROM bills WHERE id = ANY($1)"),
+    ("voting_id", "SELECT id, complex_id FROM votings WHERE id = ANY($1)"),
+    ("announcement_id", "SELECT id, complex_id FROM announcements WHERE id = ANY($1)"),
+    ("listing_id", "SELECT id, complex_id FROM marketplace_listings WHERE id = ANY($1)"),
+    ("maintenance_request_id", "SELECT id, complex_id FROM maintenance_requests WHERE id = ANY($1)"),
+    ("barrier_id", "SELECT id, complex_id FROM barriers WHERE id = ANY($1)"),
+    ("intercom_id", "SELECT id, complex_id FROM intercoms WHERE id = ANY($1)"),
+    ("survey_id", "SELECT id, complex_id FROM surveys WHERE id = ANY($1)"),
+    (
+        "chat_id",
+        "SELECT id, complex_id FROM chats WHERE id = ANY($1) AND complex_id IS NOT NULL",
+    ),
+];
+
+/// Разделы API и сущность, которую описывает поле `id` в их ответах
+const ROUTE_ENTITIES: &[(&str, &str)] = &[
+    ("/api/v1/announcements", "announcement_id"),
+    ("/api/v1/votings", "voting_id"),
+    ("/api/v1/marketplace/listings", "listing_id"),
+    ("/api/v1/communal/bills", "bill_id"),
+    ("/api/v1/maintenance", "maintenance_request_id"),
+    ("/api/v1/chat", "chat_id"),
+    ("/api/v1/osi", "osi_id"),
+    ("/api/v1/surveys", "survey_id"),
+];
+
+/// Публичные карточки, открытые любому пользователю; `*` — один сегмент пути
+const TENANT_AUDIT_PUBLIC_ROUTES: &[&str] = &[
+    "/api/v1/osi/by-complex/*",
+    "/api/v1/osi/*",
+    "/api/v1/osi/*/council",
+    "/api/v1/votings/protocols/*",
+];
+
+/// Разделы, где пользователь законно видит чужие ЖК: поиск, справочники, вход, админка
+/// и модерация маркетплейса
+const TENANT_AUDIT_EXEMPT_PREFIXES: &[&str] = &[
+    "/api/v1/admin",
+    "/api/v1/marketplace/moderation",
+    "/api/v1/auth",
+    "/api/v1/bootstrap",
+    "/api/v1/cities",
+    "/api/v1/addresses",
+    "/api/v1/complexes",
+    "/api/v1/communal/receipts",
+];
+
+// Middleware аудита изоляции: ищет в JSON-ответе записи ЖК, к которым у пользователя нет доступа
+pub async fn tenant_audit_middleware(
+    State(state): State<AppState>,
+    auth_user: Option<AuthUser>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let mode = state.config.tenant_audit_mode;
+    let path = request.uri().path().to_string();
+    let Some(auth_user) = auth_user else {
+        return next.run(request).await;
+    };
+    if mode == TenantAuditMode::Off
+        || is_admin_or_higher(&auth_user.role)
+        || TENANT_AUDIT_EXEMPT_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+        || TENANT_AUDIT_PUBLIC_ROUTES
+            .iter()
+            .any(|route| matches_route(route, &path))
+    {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !response.status().is_success() || !is_json {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Tenant audit failed to read response of {}: {}", path, e);
+            return AppError::Internal("Не удалось прочитать ответ".to_string()).into_response();
+        }
+    };
+
+    let Ok(json) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    match find_foreign_entities(&state.pool, auth_user.user_id, &path, &json).await {
+        Ok(violations) if !violations.is_empty() => {
+            for (field, id, complex_id) in &violations {
+                tracing::error!(
+                    "Tenant isolation violation on {}: user {} received {} {} of complex {}",
+                    path,
+                    auth_user.user_id,
+                    field,
+                    id,
+                    complex_id
+                );
+            }
+            if mode == TenantAuditMode::Enforce {
+                return AppError::Internal(format!("Утечка данных чужого ЖК в {}", path))
+                    .into_response();
+            }
+        }
+        Ok(_) => {}
+        Err(e) => tracing::error!("Tenant audit failed on {}: {}", path, e),
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Все ЖК, к которым пользователь причастен: квартира, семья, роль, права, совет, заявки
+async fn user_complexes(pool: &PgPool, user_id: Uuid) -> AppResult<HashSet<Uuid>> {
+    let complexes: Vec<(Uuid,)> = sqlx::query_as(
+        r#"
+        SELECT complex_id FROM apartments WHERE owner_id = $1 OR resident_id = $1
+        UNION SELECT a.complex_id FROM family_members f
+              JOIN apartments a ON a.id = f.apartment_id
+              WHERE f.user_id = $1
+        UNION SELECT complex_id FROM complex_roles WHERE user_id = $1
+        UNION SELECT complex_id FROM permission_grants WHERE user_id = $1
+        UNION SELECT complex_id FROM join_requests WHERE user_id = $1
+        UNION SELECT complex_id FROM chairman_applications WHERE user_id = $1
+        UNION SELECT complex_id FROM osi WHERE chairman_id = $1
+        UNION SELECT o.complex_id FROM council_members cm
+              JOIN osi o ON o.id = cm.osi_id
+              WHERE cm.user_id = $1
+        UNION SELECT o.complex_id FROM osi_workers w
+              JOIN osi o ON o.id = w.osi_id
+              WHERE w.user_id = $1 AND w.is_active
+        UNION SELECT id FROM complexes WHERE created_by = $1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(complexes.into_iter().map(|(id,)| id).collect())
+}
+
+/// Записи из ответа, принадлежащие чужим ЖК: (поле, ID записи, ЖК)
+async fn find_foreign_entities(
+    pool: &PgPool,
+    user_id: Uuid,
+    path: &str,
+    json: &Value,
+) -> AppResult<Vec<(&'static str, Uuid, Uuid)>> {
+    let route_entity = ROUTE_ENTITIES
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map(|(_, field)| *field);
+
+    let mut found: HashMap<&'static str, HashSet<Uuid>> = HashMap::new();
+    collect_scoped_ids(json, route_entity, &mut found);
+    if found.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let allowed = user_complexes(pool, user_id).await?;
+    let mut violations = Vec::new();
+
+    for (field, query) in SCOPED_ENTITIES {
+        let Some(ids) = found.get(field) else {
+            continue;
+        };
+
+        let owners: Vec<(Uuid, Uuid)> = sqlx::query_as(query)
+            .bind(ids.iter().copied().collect::<Vec<_>>())
+            .fetch_all(pool)
+            .await?;
+
+        violations.extend(
+            owners
+                .into_iter()
+                .filter(|(_, complex_id)| !allowed.contains(complex_id))
+                .map(|(id, complex_id)| (*field, id, complex_id)),
+        );
+    }
+
+    Ok(violations)
+}
+
+/// ID из ответа по полям реестра; `id` относится к сущности раздела.
+/// Чужие `id` (категорий, фото) просто не найдутся в таблице сущности.
+fn collect_scoped_ids(
+    value: &Value,
+    route_entity: Option<&'static str>,
+    found: &mut HashMap<&'static str, HashSet<Uuid>>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let field = if key == "id" {
+                    route_entity
+                } else {
+                    SCOPED_ENTITIES
+                        .iter()
+                        .find(|(field, _)| field == key)
+                        .map(|(field, _)| *field)
+                };
+                let id = value.as_str().and_then(|v| Uuid::parse_str(v).ok());
+                if let (Some(field), Some(id)) = (field, id) {
+                    found.entry(field).or_default().insert(id);
+                }
+                collect_scoped_ids(value, route_entity, found);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_scoped_ids(item, route_entity, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn matches_route(route: &str, path: &str) -> bool {
+    let route = route.split('/');
+    let path = path.trim_end_matches('/').split('/');
+    route.clone().count() == path.clone().count()
+        && route.zip(path).all(|(expected, actual)| expected == "*" || expected == actual)
+}
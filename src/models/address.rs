@@ -16,6 +16,8 @@ pub struct Address {
     pub latitude: Option<Decimal>,
     pub longitude: Option<Decimal>,
     pub created_at: DateTime<Utc>,
+    pub registry_street_id: Option<Uuid>,
+    pub is_verified: bool,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -27,6 +29,8 @@ pub struct AddressResponse {
     pub street: String,
     pub building: String,
     pub full_address: String,
+    /// Улица взята из национального адресного регистра
+    pub is_verified: bool,
 }
 
 impl Address {
@@ -39,6 +43,8 @@ impl Address {
 pub struct CreateAddressRequest {
     pub city_id: String,
     pub district: Option<String>,
+    /// Улица из автодополнения; без неё улица ищется в регистре по точному названию
+    pub registry_street_id: Option<Uuid>,
     pub street: String,
     pub building: String,
     pub postal_code: Option<String>,
@@ -51,3 +57,79 @@ pub struct SearchAddressQuery {
     pub city: String,
     pub query: String,
 }
+
+/// Район города из классификатора КАТО
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RegistryDistrict {
+    pub id: Uuid,
+    pub kato_code: String,
+    pub city_id: String,
+    pub name: String,
+    pub name_kz: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Улица из национального адресного регистра
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RegistryStreet {
+    pub id: Uuid,
+    pub code: String,
+    pub city_id: String,
+    pub district_id: Option<Uuid>,
+    pub name: String,
+    pub name_kz: Option<String>,
+    pub street_type: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DistrictQuery {
+    pub city: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StreetSearchQuery {
+    pub city: String,
+    pub query: String,
+    pub district_id: Option<Uuid>,
+}
+
+/// Запись выгрузки классификатора: город, район или улица
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegistryRecord {
+    pub kind: RegistryRecordKind,
+    pub code: String,
+    pub parent_code: Option<String>,
+    pub name_ru: String,
+    pub name_kz: Option<String>,
+    #[serde(rename = "type")]
+    pub street_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryRecordKind {
+    City,
+    District,
+    Street,
+}
+
+/// Итог загрузки классификатора
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct RegistryImportResult {
+    pub cities: u64,
+    pub districts: u64,
+    pub streets: u64,
+    pub skipped: u64,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct StartRegistryImportRequest {
+    /// Адрес выгрузки; по умолчанию ADDRESS_REGISTRY_URL
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StartRegistryImportResponse {
+    pub job_id: Uuid,
+}
@@ -4,9 +4,14 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+use crate::utils::Patch;
+
+use super::ViewStats;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "announcement_category", rename_all = "snake_case")]
 pub enum AnnouncementCategory {
+    #[default]
     General,
     Maintenance,
     Emergency,
@@ -15,27 +20,16 @@ pub enum AnnouncementCategory {
     Voting,
 }
 
-impl Default for AnnouncementCategory {
-    fn default() -> Self {
-        Self::General
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "announcement_priority", rename_all = "snake_case")]
 pub enum AnnouncementPriority {
     Low,
+    #[default]
     Normal,
     High,
     Urgent,
 }
 
-impl Default for AnnouncementPriority {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Announcement {
     pub id: Uuid,
@@ -50,8 +44,36 @@ pub struct Announcement {
     pub expires_at: Option<DateTime<Utc>>,
     pub author_id: Uuid,
     pub views_count: i32,
+    pub total_views_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Запланированное время публикации; сбрасывается, когда объявление выходит
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+impl Announcement {
+    /// Статистика просмотров для автора объявления
+    pub fn view_stats_for(&self, user_id: Uuid) -> Option<ViewStats> {
+        (self.author_id == user_id).then_some(ViewStats {
+            unique_views: self.views_count,
+            total_views: self.total_views_count,
+        })
+    }
+}
+
+/// Фото или документ, приложенный к объявлению
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct AnnouncementAttachment {
+    pub id: Uuid,
+    pub announcement_id: Uuid,
+    pub file_url: String,
+    /// Превью, только для изображений
+    pub thumbnail_url: Option<String>,
+    pub file_name: String,
+    pub content_type: String,
+    pub file_size: i64,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -64,30 +86,49 @@ pub struct AnnouncementResponse {
     pub image_url: Option<String>,
     pub author_name: Option<String>,
     pub views_count: i32,
+    pub view_stats: Option<ViewStats>,
     pub is_read: bool,
     pub published_at: Option<DateTime<Utc>>,
+    /// Когда выйдет отложенное объявление; видно только автору до публикации
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Фото и документы в порядке загрузки
+    pub attachments: Vec<AnnouncementAttachment>,
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateAnnouncementRequest {
+    /// ЖК; можно не указывать, если он у пользователя один
+    pub complex_id: Option<Uuid>,
     pub title: String,
     pub content: String,
     pub category: Option<AnnouncementCategory>,
     pub priority: Option<AnnouncementPriority>,
     pub image_url: Option<String>,
     pub expires_at: Option<DateTime<Utc>>,
+    /// Опубликовать позже; до этого объявление видно только автору
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateAnnouncementRequest {
     pub title: Option<String>,
     pub content: Option<String>,
     pub category: Option<AnnouncementCategory>,
     pub priority: Option<AnnouncementPriority>,
-    pub image_url: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub image_url: Patch<String>,
     pub is_published: Option<bool>,
-    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[schema(value_type = Option<DateTime<Utc>>)]
+    pub expires_at: Patch<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MarkAnnouncementsReadRequest {
+    pub ids: Vec<Uuid>,
 }
 
 #[derive(Debug, Clone, FromRow)]
@@ -1,10 +1,14 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::Patch;
+
+use super::UtilityType;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Apartment {
     pub id: Uuid,
@@ -64,6 +68,10 @@ pub struct JoinRequest {
     pub reviewed_at: Option<DateTime<Utc>>,
     pub rejection_reason: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Последняя подача заявки, от неё отсчитывается срок
+    pub submitted_at: DateTime<Utc>,
+    /// Когда заявка закрыта автоматически за отсутствием движения
+    pub expired_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -86,3 +94,124 @@ pub struct ReviewJoinRequestRequest {
     pub approved: bool,
     pub rejection_reason: Option<String>,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "family_relation", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FamilyRelation {
+    Spouse,
+    Child,
+    Parent,
+    Sibling,
+    Other,
+}
+
+/// Член семьи жителя; аккаунт (`user_id`) появляется после принятия приглашения
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FamilyMember {
+    pub id: Uuid,
+    pub apartment_id: Uuid,
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub relation: FamilyRelation,
+    pub birth_year: Option<i32>,
+    pub photo_url: Option<String>,
+    pub phone: Option<String>,
+    #[serde(skip_serializing)]
+    pub invite_token: Option<String>,
+    pub invite_expires_at: Option<DateTime<Utc>>,
+    pub user_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateFamilyMemberRequest {
+    pub first_name: String,
+    pub last_name: Option<String>,
+    pub relation: Option<FamilyRelation>,
+    pub birth_year: Option<i32>,
+    pub photo_url: Option<String>,
+}
+
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateFamilyMemberRequest {
+    pub first_name: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub last_name: Patch<String>,
+    pub relation: Option<FamilyRelation>,
+    #[serde(default)]
+    #[schema(value_type = Option<i32>)]
+    pub birth_year: Patch<i32>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub photo_url: Patch<String>,
+}
+
+/// Пригласить члена семьи завести аккаунт по номеру телефона
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct InviteFamilyMemberRequest {
+    pub phone: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptFamilyInvitationRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "move_out_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MoveOutStatus {
+    Scheduled,
+    Completed,
+    Cancelled,
+}
+
+/// Выезд жильца из квартиры
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MoveOut {
+    pub id: Uuid,
+    pub apartment_id: Uuid,
+    pub complex_id: Uuid,
+    pub user_id: Uuid,
+    pub initiated_by: Uuid,
+    pub effective_date: NaiveDate,
+    pub note: Option<String>,
+    pub status: MoveOutStatus,
+    pub readings_complete: Option<bool>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub cancelled_by: Option<Uuid>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateMoveOutRequest {
+    /// День выезда: в этот день снимаются доступы и пропуска
+    pub effective_date: NaiveDate,
+    pub note: Option<String>,
+}
+
+/// Счётчик квартиры и итоговое показание к выезду
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct MoveOutMeter {
+    pub meter_id: Uuid,
+    pub utility_type: UtilityType,
+    pub serial_number: Option<String>,
+    pub reading_id: Option<Uuid>,
+    pub value: Option<Decimal>,
+    pub reading_date: Option<NaiveDate>,
+}
+
+/// Чек-лист выезда: показания по всем счётчикам
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MoveOutChecklist {
+    pub move_out: MoveOut,
+    pub meters: Vec<MoveOutMeter>,
+    pub readings_complete: bool,
+}
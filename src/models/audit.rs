@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Новая запись журнала аудита
+#[derive(Debug, Clone)]
+pub struct NewAuditLog {
+    pub actor_id: Uuid,
+    pub complex_id: Option<Uuid>,
+    pub action: &'static str,
+    pub entity_type: &'static str,
+    pub entity_id: Option<Uuid>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub complex_id: Option<Uuid>,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: Option<Uuid>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub diff: Option<Value>,
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct AuditLogsQuery {
+    pub complex_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub action: Option<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Изменение записи ОСИ или ЖК для пополевой истории
+#[derive(Debug, Clone)]
+pub struct NewFieldChange {
+    pub complex_id: Uuid,
+    pub entity_type: &'static str,
+    pub entity_id: Uuid,
+    /// `None` — изменение системой
+    pub changed_by: Option<Uuid>,
+    /// Запись или её часть до и после изменения; сравниваются поля верхнего уровня
+    pub old_value: Value,
+    pub new_value: Value,
+    pub request_id: Option<String>,
+}
+
+/// Изменение одного поля ОСИ или ЖК
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FieldChange {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub field: String,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub changed_by: Option<Uuid>,
+    pub changed_by_name: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct FieldHistoryQuery {
+    pub complex_id: Uuid,
+    /// `osi` или `complex`
+    pub entity_type: Option<String>,
+    pub field: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Операция с деньгами в финансовом журнале
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "financial_event", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum FinancialEvent {
+    PaymentCreated,
+    PaymentProcessing,
+    PaymentCompleted,
+    PaymentFailed,
+    CashPaymentRegistered,
+    ExpenseExecuted,
+}
+
+impl FinancialEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinancialEvent::PaymentCreated => "payment_created",
+            FinancialEvent::PaymentProcessing => "payment_processing",
+            FinancialEvent::PaymentCompleted => "payment_completed",
+            FinancialEvent::PaymentFailed => "payment_failed",
+            FinancialEvent::CashPaymentRegistered => "cash_payment_registered",
+            FinancialEvent::ExpenseExecuted => "expense_executed",
+        }
+    }
+}
+
+/// Кто и в каком запросе двигает деньги; у уведомлений провайдера актора нет
+#[derive(Debug, Clone, Default)]
+pub struct FinancialContext {
+    pub actor_id: Option<Uuid>,
+    pub request_id: Option<String>,
+}
+
+/// Новая запись финансового журнала
+#[derive(Debug, Clone)]
+pub struct NewFinancialAuditEntry {
+    pub event: FinancialEvent,
+    pub complex_id: Option<Uuid>,
+    pub entity_type: &'static str,
+    pub entity_id: Uuid,
+    pub amount: Option<Decimal>,
+    pub provider: Option<String>,
+    pub provider_reference: Option<String>,
+    pub details: Option<Value>,
+}
+
+/// Запись финансового журнала; `checksum` считается по полям записи и сумме предыдущей
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FinancialAuditEntry {
+    pub seq: i64,
+    pub id: Uuid,
+    pub event: FinancialEvent,
+    pub complex_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub request_id: Option<String>,
+    pub entity_type: String,
+    pub entity_id: Uuid,
+    pub amount: Option<Decimal>,
+    pub provider: Option<String>,
+    pub provider_reference: Option<String>,
+    pub details: Option<Value>,
+    pub prev_checksum: Option<String>,
+    pub checksum: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Запись журнала с результатом проверки её контрольной суммы
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FinancialAuditEntryResponse {
+    #[serde(flatten)]
+    pub entry: FinancialAuditEntry,
+    pub checksum_valid: bool,
+}
+
+/// Итог проверки всей цепочки журнала
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FinancialAuditVerification {
+    pub checked: i64,
+    pub valid: bool,
+    /// Первая запись, на которой цепочка нарушена
+    pub first_invalid_seq: Option<i64>,
+}
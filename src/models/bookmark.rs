@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "bookmark_entity_type", rename_all = "snake_case")]
+pub enum BookmarkEntityType {
+    Listing,
+    Announcement,
+    Document,
+    Voting,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ToggleBookmarkRequest {
+    pub entity_type: BookmarkEntityType,
+    pub entity_id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ToggleBookmarkResponse {
+    pub is_bookmarked: bool,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct BookmarkResponse {
+    pub entity_type: BookmarkEntityType,
+    pub entity_id: Uuid,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct BookmarksQuery {
+    pub entity_type: Option<BookmarkEntityType>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
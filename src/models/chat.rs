@@ -13,6 +13,14 @@ pub enum ChatType {
     Support,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "scheduled_message_status", rename_all = "snake_case")]
+pub enum ScheduledMessageStatus {
+    Pending,
+    Sent,
+    Cancelled,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Chat {
     pub id: Uuid,
@@ -23,6 +31,8 @@ pub struct Chat {
     pub created_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Объявление маркетплейса, по которому идёт переписка
+    pub listing_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -57,6 +67,8 @@ pub struct ChatResponse {
     pub id: Uuid,
     pub chat_type: ChatType,
     pub name: Option<String>,
+    /// Объявление маркетплейса, по которому идёт переписка
+    pub listing_id: Option<Uuid>,
     pub last_message: Option<MessagePreview>,
     pub unread_count: i32,
     pub members_count: i32,
@@ -102,8 +114,83 @@ pub struct CreatePrivateChatRequest {
     pub user_id: Uuid,
 }
 
+/// Тип вложения в галерее чата
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum MediaKind {
+    Image,
+    Video,
+    Document,
+    Voice,
+}
+
+impl MediaKind {
+    /// Значения `attachment_type`, относящиеся к типу
+    pub fn attachment_types(&self) -> &'static [&'static str] {
+        match self {
+            MediaKind::Image => &["image"],
+            MediaKind::Video => &["video"],
+            MediaKind::Document => &["file", "document"],
+            MediaKind::Voice => &["voice"],
+        }
+    }
+
+    pub fn from_attachment_type(attachment_type: &str) -> Self {
+        match attachment_type {
+            "image" => MediaKind::Image,
+            "video" => MediaKind::Video,
+            "voice" => MediaKind::Voice,
+            _ => MediaKind::Document,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChatMediaItem {
+    pub message_id: Uuid,
+    pub sender_id: Uuid,
+    pub url: String,
+    pub kind: MediaKind,
+    pub attachment_type: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChatMediaQuery {
+    pub kind: Option<MediaKind>,
+    /// Курсор из `next_cursor` предыдущей страницы
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct MessagesQuery {
+    /// Курсор из `next_cursor` предыдущей страницы
+    pub cursor: Option<String>,
+    /// Устаревший вариант курсора: ID последнего полученного сообщения
     pub before: Option<Uuid>,
     pub limit: Option<i64>,
 }
+
+/// Сообщение, которое будет отправлено в чат в назначенное время
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ScheduledChatMessage {
+    pub id: Uuid,
+    pub chat_id: Uuid,
+    pub sender_id: Uuid,
+    pub content: String,
+    pub attachment_url: Option<String>,
+    pub attachment_type: Option<String>,
+    pub send_at: DateTime<Utc>,
+    pub status: ScheduledMessageStatus,
+    pub message_id: Option<Uuid>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ScheduleChatMessageRequest {
+    pub content: String,
+    pub attachment_url: Option<String>,
+    pub attachment_type: Option<String>,
+    pub send_at: DateTime<Utc>,
+}
@@ -5,6 +5,8 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::Patch;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
 #[sqlx(type_name = "utility_type", rename_all = "snake_case")]
 pub enum UtilityType {
@@ -22,6 +24,26 @@ pub enum UtilityType {
     Other,
 }
 
+impl UtilityType {
+    /// Название услуги для квитанций
+    pub fn title(&self) -> &'static str {
+        match self {
+            UtilityType::Electricity => "Электроэнергия",
+            UtilityType::ColdWater => "Холодная вода",
+            UtilityType::HotWater => "Горячая вода",
+            UtilityType::Heating => "Отопление",
+            UtilityType::Gas => "Газ",
+            UtilityType::Maintenance => "Содержание дома",
+            UtilityType::Garbage => "Вывоз мусора",
+            UtilityType::Elevator => "Лифт",
+            UtilityType::Intercom => "Домофон",
+            UtilityType::Parking => "Парковка",
+            UtilityType::Security => "Охрана",
+            UtilityType::Other => "Прочее",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Meter {
     pub id: Uuid,
@@ -68,21 +90,16 @@ pub struct SubmitReadingRequest {
     pub photo_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "bill_status", rename_all = "snake_case")]
 pub enum BillStatus {
+    #[default]
     Pending,
     Paid,
     Overdue,
     Cancelled,
 }
 
-impl Default for BillStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Bill {
     pub id: Uuid,
@@ -99,6 +116,8 @@ pub struct Bill {
     pub paid_at: Option<DateTime<Utc>>,
     pub paid_amount: Option<Decimal>,
     pub created_at: DateTime<Utc>,
+    /// По какой день включительно начислена пеня
+    pub penalty_accrued_until: Option<NaiveDate>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -112,6 +131,7 @@ pub struct BillItem {
     pub rate: Option<Decimal>,
     pub amount: Decimal,
     pub created_at: DateTime<Utc>,
+    pub tariff_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -125,6 +145,9 @@ pub struct BillResponse {
     pub status: BillStatus,
     pub due_date: NaiveDate,
     pub items: Vec<BillItemResponse>,
+    /// Тарифы, действовавшие в расчётном периоде (только в детальном просмотре)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tariffs: Option<Vec<Tariff>>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -135,6 +158,7 @@ pub struct BillItemResponse {
     pub unit: Option<String>,
     pub rate: Option<Decimal>,
     pub amount: Decimal,
+    pub tariff_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
@@ -157,6 +181,29 @@ pub enum PaymentMethod {
     Cash,
 }
 
+impl PaymentMethod {
+    /// Название для квитанций
+    pub fn title(&self) -> &'static str {
+        match self {
+            PaymentMethod::Card => "Банковская карта",
+            PaymentMethod::Kaspi => "Kaspi",
+            PaymentMethod::Halyk => "Halyk",
+            PaymentMethod::BankTransfer => "Банковский перевод",
+            PaymentMethod::Cash => "Наличные",
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentMethod::Card => "card",
+            PaymentMethod::Kaspi => "kaspi",
+            PaymentMethod::Halyk => "halyk",
+            PaymentMethod::BankTransfer => "bank_transfer",
+            PaymentMethod::Cash => "cash",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Payment {
     pub id: Uuid,
@@ -170,6 +217,13 @@ pub struct Payment {
     pub payment_url: Option<String>,
     pub completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub osi_id: Option<Uuid>,
+    pub received_by: Option<Uuid>,
+    pub receipt_number: Option<String>,
+    pub verification_token: Option<String>,
+    pub comment: Option<String>,
+    /// Ключ сгенерированной PDF-квитанции в хранилище
+    pub receipt_pdf_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -187,3 +241,137 @@ pub struct PaymentResponse {
     pub payment_url: Option<String>,
     pub created_at: DateTime<Utc>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct PaymentAllocation {
+    pub bill_id: Uuid,
+    pub amount: Decimal,
+}
+
+/// Регистрация наличного платежа председателем
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterCashPaymentRequest {
+    pub apartment_id: Uuid,
+    pub bill_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CashReceiptResponse {
+    pub payment_id: Uuid,
+    pub receipt_number: String,
+    pub apartment_id: Uuid,
+    pub apartment_number: String,
+    pub amount: Decimal,
+    pub allocations: Vec<PaymentAllocation>,
+    /// Сумма сверх задолженности (аванс)
+    pub unallocated: Decimal,
+    pub verification_url: String,
+    pub qr_code: Option<String>,
+    pub received_by_name: Option<String>,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct CashPaymentsQuery {
+    pub apartment_id: Option<Uuid>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Результат проверки квитанции по QR-коду
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ReceiptVerificationResponse {
+    pub receipt_number: String,
+    pub amount: Decimal,
+    pub method: PaymentMethod,
+    pub status: PaymentStatus,
+    pub complex_name: String,
+    pub apartment_number: String,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+/// Как считается начисление по тарифу
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "tariff_basis", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TariffBasis {
+    /// Расход по счётчику за период × тариф
+    Metered,
+    /// Площадь квартиры × тариф
+    Area,
+    /// Фиксированная сумма с квартиры
+    Apartment,
+}
+
+impl TariffBasis {
+    /// Единица измерения по умолчанию
+    pub fn default_unit(self, utility_type: &UtilityType) -> &'static str {
+        match self {
+            TariffBasis::Area => "м²",
+            TariffBasis::Apartment => "кв.",
+            TariffBasis::Metered => match utility_type {
+                UtilityType::Electricity => "кВт·ч",
+                UtilityType::Heating => "Гкал",
+                _ => "м³",
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Tariff {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub utility_type: UtilityType,
+    pub basis: TariffBasis,
+    pub rate: Decimal,
+    pub unit: String,
+    pub effective_from: NaiveDate,
+    /// Последний день действия; пусто — действует бессрочно
+    pub effective_to: Option<NaiveDate>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTariffRequest {
+    pub utility_type: UtilityType,
+    pub basis: TariffBasis,
+    pub rate: Decimal,
+    pub unit: Option<String>,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+}
+
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTariffRequest {
+    pub rate: Option<Decimal>,
+    pub unit: Option<String>,
+    pub effective_from: Option<NaiveDate>,
+    #[serde(default)]
+    #[schema(value_type = Option<NaiveDate>)]
+    pub effective_to: Patch<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GenerateTariffBillsRequest {
+    /// Любой день расчётного месяца
+    pub period: NaiveDate,
+}
+
+/// Итог начисления по тарифам за период
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TariffBillingResult {
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    pub bills: i64,
+    pub items: i64,
+    pub total_amount: Decimal,
+}
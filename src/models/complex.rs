@@ -4,20 +4,17 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+use super::PhotoVariants;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "complex_status", rename_all = "snake_case")]
 pub enum ComplexStatus {
+    #[default]
     Pending,
     Active,
     Inactive,
 }
 
-impl Default for ComplexStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Complex {
     pub id: Uuid,
@@ -42,6 +39,8 @@ pub struct Complex {
     pub created_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Демо-ЖК для партнёров: SMS и платежи не уходят провайдерам
+    pub is_sandbox: bool,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
@@ -68,6 +67,8 @@ pub struct ComplexResponse {
     pub amenities: ComplexAmenities,
     pub status: ComplexStatus,
     pub photos: Vec<String>,
+    /// Те же фото с превью и облегчёнными копиями
+    pub photo_variants: Vec<PhotoVariants>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -113,3 +114,207 @@ pub struct JoinComplexRequest {
     pub building: Option<String>,
     pub is_owner: bool,
 }
+
+/// Этап проверки ЖК администратором
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "complex_verification_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ComplexVerificationStatus {
+    Submitted,
+    NeedsInfo,
+    Approved,
+    Rejected,
+}
+
+/// Документ, подтверждающий ЖК
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "verification_evidence_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationEvidenceType {
+    /// Подтверждение адреса: техпаспорт, выписка из регистра
+    AddressProof,
+    /// Связь создателя с домом: право собственности, протокол собрания
+    RelationProof,
+    Other,
+}
+
+impl VerificationEvidenceType {
+    /// Без этих документов ЖК не подтверждается
+    pub const REQUIRED: [VerificationEvidenceType; 2] = [Self::AddressProof, Self::RelationProof];
+}
+
+/// Пункт чек-листа проверки
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationCheckItem {
+    AddressConfirmed,
+    AddressProofValid,
+    CreatorRelationConfirmed,
+    NotDuplicate,
+    DetailsPlausible,
+}
+
+impl VerificationCheckItem {
+    pub const ALL: [VerificationCheckItem; 5] = [
+        Self::AddressConfirmed,
+        Self::AddressProofValid,
+        Self::CreatorRelationConfirmed,
+        Self::NotDuplicate,
+        Self::DetailsPlausible,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::AddressConfirmed => "address_confirmed",
+            Self::AddressProofValid => "address_proof_valid",
+            Self::CreatorRelationConfirmed => "creator_relation_confirmed",
+            Self::NotDuplicate => "not_duplicate",
+            Self::DetailsPlausible => "details_plausible",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::AddressConfirmed => "Адрес существует и совпадает с регистром",
+            Self::AddressProofValid => "Документ на адрес читается и действителен",
+            Self::CreatorRelationConfirmed => "Создатель связан с домом",
+            Self::NotDuplicate => "ЖК не дублирует уже зарегистрированный",
+            Self::DetailsPlausible => "Характеристики ЖК правдоподобны",
+        }
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ComplexVerification {
+    pub complex_id: Uuid,
+    pub status: ComplexVerificationStatus,
+    pub checklist: serde_json::Value,
+    pub info_request: Option<String>,
+    pub rejection_reasons: Vec<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub submitted_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Когда заявка закрыта автоматически за отсутствием движения
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+impl ComplexVerification {
+    pub fn is_checked(&self, item: VerificationCheckItem) -> bool {
+        self.checklist
+            .get(item.key())
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ComplexVerificationEvidence {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub evidence_type: VerificationEvidenceType,
+    pub file_url: String,
+    pub file_name: Option<String>,
+    pub comment: Option<String>,
+    pub uploaded_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VerificationChecklistEntry {
+    pub item: VerificationCheckItem,
+    pub title: String,
+    pub checked: bool,
+}
+
+/// Состояние проверки ЖК для создателя и администратора
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComplexVerificationResponse {
+    pub complex_id: Uuid,
+    pub status: ComplexVerificationStatus,
+    pub checklist: Vec<VerificationChecklistEntry>,
+    /// Обязательные документы, которые ещё не загружены
+    pub missing_evidence: Vec<VerificationEvidenceType>,
+    /// Что администратор просит дополнить
+    pub info_request: Option<String>,
+    pub rejection_reasons: Vec<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub submitted_at: DateTime<Utc>,
+    /// Заявка закрыта за отсутствием движения; её можно подать повторно
+    pub expired_at: Option<DateTime<Utc>>,
+    pub evidence: Vec<ComplexVerificationEvidence>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ChecklistMark {
+    pub item: VerificationCheckItem,
+    pub checked: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateVerificationChecklistRequest {
+    pub items: Vec<ChecklistMark>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RequestVerificationInfoRequest {
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RejectComplexRequest {
+    pub reasons: Vec<String>,
+}
+
+/// Демо-ЖК для партнёра: председателем становится пользователь с указанным номером
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSandboxComplexRequest {
+    pub name: String,
+    pub city_id: String,
+    pub chairman_phone: String,
+}
+
+/// Созданный демо-ЖК и код входа председателя; код больше нигде не возвращается
+#[derive(Debug, Serialize)]
+pub struct SandboxComplexCreated {
+    #[serde(flatten)]
+    pub complex: Complex,
+    pub login_code: String,
+}
+
+/// Выделить дома ЖК в новый ЖК (например, когда дома переходят в отдельное ОСИ)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SplitComplexRequest {
+    pub name: String,
+    pub buildings: Vec<String>,
+    /// Только посчитать, что переедет, ничего не меняя
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Перенести все квартиры ЖК в другой ЖК, исходный становится неактивным
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MergeComplexRequest {
+    pub target_complex_id: Uuid,
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Что переезжает при разделении или слиянии ЖК
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ComplexRestructureReport {
+    pub source_complex_id: Uuid,
+    /// При пробном запуске разделения новый ЖК не создаётся
+    pub target_complex_id: Option<Uuid>,
+    pub dry_run: bool,
+    pub buildings: Vec<String>,
+    pub apartments: i64,
+    pub meters: i64,
+    pub meter_readings: i64,
+    pub bills: i64,
+    pub payments: i64,
+    pub maintenance_requests: i64,
+    pub chats: i64,
+    /// Квартиры, номера которых уже заняты в целевом ЖК
+    pub conflicts: Vec<String>,
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "domain_event_type", rename_all = "snake_case")]
+pub enum DomainEventType {
+    OsiUpdated,
+    CouncilMemberAdded,
+    CouncilMemberRemoved,
+    WorkerAdded,
+    WorkerUpdated,
+    WorkerRemoved,
+    DocumentUploaded,
+    AnnouncementPublished,
+    ExpenseCreated,
+    ExpenseApproved,
+    ExpenseRejected,
+    ExpenseExecuted,
+}
+
+/// Новое событие для записи в журнал
+#[derive(Debug, Clone)]
+pub struct NewDomainEvent {
+    pub complex_id: Uuid,
+    pub osi_id: Option<Uuid>,
+    pub actor_id: Uuid,
+    pub event_type: DomainEventType,
+    pub entity_type: &'static str,
+    pub entity_id: Option<Uuid>,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct DomainEventResponse {
+    pub id: Uuid,
+    pub event_type: DomainEventType,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub actor_name: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct DomainEventsQuery {
+    pub event_type: Option<DomainEventType>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
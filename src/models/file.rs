@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::DocumentType;
+
+/// Куда будет прикреплён загруженный файл
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "upload_purpose", rename_all = "snake_case")]
+pub enum UploadPurpose {
+    ListingPhoto,
+    MaintenancePhoto,
+    OsiDocument,
+}
+
+impl UploadPurpose {
+    /// Папка в хранилище
+    pub fn folder(&self) -> &'static str {
+        match self {
+            UploadPurpose::ListingPhoto => "listings",
+            UploadPurpose::MaintenancePhoto => "maintenance",
+            UploadPurpose::OsiDocument => "documents",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "file_upload_status", rename_all = "snake_case")]
+pub enum FileUploadStatus {
+    Pending,
+    Confirmed,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct FileUpload {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: UploadPurpose,
+    pub target_id: Uuid,
+    pub object_key: String,
+    pub content_type: String,
+    pub max_size: i64,
+    pub status: FileUploadStatus,
+    pub url: Option<String>,
+    pub entity_id: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PresignUploadRequest {
+    pub purpose: UploadPurpose,
+    pub target_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    /// Размер файла в байтах
+    pub size: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PresignUploadResponse {
+    pub upload_id: Uuid,
+    /// Ссылка для загрузки методом PUT с заголовком Content-Type
+    pub upload_url: String,
+    pub content_type: String,
+    pub expires_at: DateTime<Utc>,
+    /// Куда сообщить об окончании загрузки
+    pub confirm_url: String,
+}
+
+/// Данные для записи, которая создаётся после загрузки
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ConfirmUploadRequest {
+    /// Название документа ОСИ
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub document_type: Option<DocumentType>,
+    /// Фото заявки до начала работ
+    pub is_before: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfirmUploadResponse {
+    pub upload_id: Uuid,
+    pub url: String,
+    pub entity_id: Uuid,
+}
+
+/// Фото с уменьшенными копиями; у старых фото копий может не быть
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PhotoVariants {
+    pub url: String,
+    /// Превью 200px
+    pub thumbnail_url: Option<String>,
+    /// Копия 800px
+    pub medium_url: Option<String>,
+}
+
+/// Ответ на загрузку фото ЖК
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComplexPhotoUploadResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub thumbnail_url: String,
+    pub medium_url: String,
+}
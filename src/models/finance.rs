@@ -0,0 +1,478 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::utils::Patch;
+
+use super::UtilityType;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ExpenseCategory {
+    pub id: Uuid,
+    pub osi_id: Option<Uuid>,
+    pub name: String,
+    pub slug: String,
+    pub sort_order: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "expense_status", rename_all = "snake_case")]
+pub enum ExpenseStatus {
+    PendingConfirmation,
+    PendingApproval,
+    Approved,
+    Rejected,
+    Executed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "expense_decision", rename_all = "snake_case")]
+pub enum ExpenseDecision {
+    Approve,
+    Reject,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OsiExpense {
+    pub id: Uuid,
+    pub osi_id: Uuid,
+    pub category_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub vendor: Option<String>,
+    pub expense_date: NaiveDate,
+    pub receipt_url: Option<String>,
+    pub status: ExpenseStatus,
+    pub required_approvals: i32,
+    pub created_by: Uuid,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub ocr_data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ExpenseApprovalResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_name: Option<String>,
+    pub decision: ExpenseDecision,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ExpenseResponse {
+    pub id: Uuid,
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub vendor: Option<String>,
+    pub expense_date: NaiveDate,
+    pub receipt_url: Option<String>,
+    pub status: ExpenseStatus,
+    pub required_approvals: i32,
+    pub approvals_count: i64,
+    pub approvals: Vec<ExpenseApprovalResponse>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub ocr_data: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateExpenseRequest {
+    pub category_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub vendor: Option<String>,
+    pub expense_date: Option<NaiveDate>,
+    pub receipt_url: Option<String>,
+}
+
+/// Подтверждение расхода, созданного из счёта (незаполненные поля берутся из распознавания)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmExpenseRequest {
+    pub category_id: Option<Uuid>,
+    pub title: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub description: Patch<String>,
+    pub amount: Option<Decimal>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub vendor: Patch<String>,
+    pub expense_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecognizedInvoice {
+    pub amount: Option<Decimal>,
+    pub expense_date: Option<NaiveDate>,
+    pub vendor: Option<String>,
+    pub suggested_category_id: Uuid,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InvoiceIntakeResponse {
+    pub expense: ExpenseResponse,
+    pub recognized: RecognizedInvoice,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ExpenseDecisionRequest {
+    pub decision: ExpenseDecision,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct ExpensesQuery {
+    pub status: Option<ExpenseStatus>,
+    pub category_id: Option<Uuid>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct FinanceSettings {
+    pub expense_approval_threshold: Option<Decimal>,
+    pub expense_required_approvals: i32,
+    /// Месячный лимит расходов на SMS; после него уведомления уходят только push
+    #[serde(default)]
+    pub sms_monthly_cap: Option<Decimal>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SmsUsageQuery {
+    /// Любая дата месяца; по умолчанию текущий месяц
+    pub month: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct SmsKindUsage {
+    pub kind: String,
+    pub sent_count: i64,
+    pub suppressed_count: i64,
+    pub cost: Decimal,
+    /// Отправлено на номера вне зоны +7 и их стоимость — тариф выше внутреннего
+    pub international_count: i64,
+    pub international_cost: Decimal,
+}
+
+/// Расходы ОСИ на SMS за месяц
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SmsUsageReport {
+    pub month: NaiveDate,
+    pub spent: Decimal,
+    pub cap: Option<Decimal>,
+    pub remaining: Option<Decimal>,
+    pub sent_count: i64,
+    pub suppressed_count: i64,
+    pub international_count: i64,
+    pub international_cost: Decimal,
+    pub by_kind: Vec<SmsKindUsage>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "sms_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SmsStatus {
+    Sent,
+    Failed,
+    /// Не отправлено: исчерпан лимит ОСИ
+    Suppressed,
+    /// Демо-ЖК: только запись в журнале
+    Sandbox,
+}
+
+/// Итог доставки по отчёту провайдера
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "sms_delivery_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SmsDeliveryStatus {
+    Delivered,
+    Undelivered,
+}
+
+/// Запись журнала SMS
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SmsMessage {
+    pub id: Uuid,
+    pub complex_id: Option<Uuid>,
+    pub phone: String,
+    pub kind: String,
+    pub text: Option<String>,
+    pub segments: i32,
+    pub cost: Decimal,
+    pub status: SmsStatus,
+    pub provider: Option<String>,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+    pub delivery_status: Option<SmsDeliveryStatus>,
+    pub delivery_error: Option<String>,
+    pub delivery_updated_at: Option<DateTime<Utc>>,
+    pub is_international: bool,
+    /// Когда администратор отправил SMS повторно
+    pub resent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "allocation_rule", rename_all = "snake_case")]
+pub enum AllocationRule {
+    /// Пропорционально площади квартиры
+    ByArea,
+    /// Поровну на каждую квартиру
+    PerUnit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "shared_charge_status", rename_all = "snake_case")]
+pub enum SharedChargeStatus {
+    Scheduled,
+    Billed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SharedCharge {
+    pub id: Uuid,
+    pub osi_id: Uuid,
+    pub expense_id: Option<Uuid>,
+    pub title: String,
+    pub description: Option<String>,
+    pub total_amount: Decimal,
+    pub utility_type: UtilityType,
+    pub rule: AllocationRule,
+    pub building: Option<String>,
+    pub total_basis: Decimal,
+    pub billing_period: NaiveDate,
+    pub status: SharedChargeStatus,
+    pub billed_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SharedChargeAllocationResponse {
+    pub apartment_id: Uuid,
+    pub apartment_number: String,
+    pub building: Option<String>,
+    pub basis: Decimal,
+    pub amount: Decimal,
+    pub bill_item_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SharedChargeResponse {
+    pub charge: SharedCharge,
+    pub allocations: Vec<SharedChargeAllocationResponse>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSharedChargeRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub total_amount: Decimal,
+    pub rule: AllocationRule,
+    pub utility_type: Option<UtilityType>,
+    pub building: Option<String>,
+    pub expense_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "budget_status", rename_all = "snake_case")]
+pub enum BudgetStatus {
+    Draft,
+    Voting,
+    Approved,
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OsiBudget {
+    pub id: Uuid,
+    pub osi_id: Uuid,
+    pub year: i32,
+    pub title: String,
+    pub status: BudgetStatus,
+    pub voting_id: Option<Uuid>,
+    pub approved_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Статья бюджета: план и фактическое исполнение
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct BudgetLineResponse {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub planned_amount: Decimal,
+    pub actual_amount: Decimal,
+    pub note: Option<String>,
+    /// План по месяцам; если не задан, годовой план делится поровну
+    pub monthly_amounts: Option<Vec<Decimal>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BudgetResponse {
+    pub budget: OsiBudget,
+    pub lines: Vec<BudgetLineResponse>,
+    pub total_planned: Decimal,
+    pub total_actual: Decimal,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BudgetLineRequest {
+    pub category_id: Uuid,
+    pub planned_amount: Decimal,
+    pub note: Option<String>,
+    /// 12 сумм по месяцам, в сумме равных годовому плану
+    pub monthly_amounts: Option<Vec<Decimal>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBudgetRequest {
+    pub year: i32,
+    pub title: Option<String>,
+    pub lines: Vec<BudgetLineRequest>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateBudgetLinesRequest {
+    pub lines: Vec<BudgetLineRequest>,
+}
+
+/// Вынести бюджет на голосование собственников
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SubmitBudgetRequest {
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateExpenseCategoryRequest {
+    pub name: String,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateExpenseCategoryRequest {
+    pub name: Option<String>,
+    pub sort_order: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct FinanceSummaryQuery {
+    /// По умолчанию текущий год
+    pub year: Option<i32>,
+    /// Месяц 1–12; без него разбивка по категориям за весь год
+    pub month: Option<u32>,
+}
+
+/// Поступления, расходы и план за месяц
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct MonthFinanceSummary {
+    pub month: i32,
+    pub collected: Decimal,
+    pub spent: Decimal,
+    pub planned: Decimal,
+}
+
+/// Расходы по категории в сравнении с бюджетом
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct CategoryFinanceSummary {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub planned: Decimal,
+    pub spent: Decimal,
+    pub expenses_count: i64,
+}
+
+/// Отчёт ОСИ для жителей: куда потрачены собранные деньги
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FinanceSummary {
+    pub year: i32,
+    pub month: Option<u32>,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    /// Утверждённый бюджет года, с которым сравниваются расходы
+    pub budget_id: Option<Uuid>,
+    pub collected: Decimal,
+    pub spent: Decimal,
+    pub planned: Decimal,
+    pub categories: Vec<CategoryFinanceSummary>,
+    pub months: Vec<MonthFinanceSummary>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct MonthlyReportQuery {
+    /// Месяц в формате ГГГГ-ММ
+    pub period: String,
+    /// `json` (по умолчанию) или `pdf`
+    pub format: Option<String>,
+}
+
+/// Расходы месяца по категории
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ReportExpenseCategory {
+    pub category_id: Uuid,
+    pub category_name: String,
+    pub amount: Decimal,
+    pub expenses_count: i64,
+}
+
+/// Стоимость заявок на обслуживание, выполненных за месяц
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ReportMaintenanceCosts {
+    pub requests_count: i64,
+    pub materials: Decimal,
+    pub labor: Decimal,
+    pub labor_hours: Decimal,
+    pub other: Decimal,
+    pub total: Decimal,
+}
+
+/// Ежемесячный отчёт ОСИ для жителей
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MonthlyReport {
+    pub osi_id: Uuid,
+    pub osi_name: String,
+    pub period: String,
+    pub period_start: NaiveDate,
+    pub period_end: NaiveDate,
+    /// Начислено по счетам за месяц
+    pub billed: Decimal,
+    /// Оплачено по этим счетам
+    pub billed_paid: Decimal,
+    /// Доля оплаченного от начисленного, %
+    pub collection_rate: Option<Decimal>,
+    /// Все поступления за месяц, включая погашение старых долгов
+    pub collected: Decimal,
+    /// Квартиры с просроченными счетами на конец месяца
+    pub debtors_count: i64,
+    pub total_debt: Decimal,
+    pub expenses: Decimal,
+    pub expenses_by_category: Vec<ReportExpenseCategory>,
+    /// Расходы на SMS-рассылки
+    pub sms_spent: Decimal,
+    /// Ремонт по заявкам — справочно: оплата работ проходит через расходы ОСИ
+    pub maintenance: ReportMaintenanceCosts,
+    /// Поступления минус расходы и SMS
+    pub balance: Decimal,
+    pub generated_at: DateTime<Utc>,
+}
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{EmailTemplate, NotificationType};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "job_type", rename_all = "snake_case")]
+pub enum JobType {
+    NotificationFanout,
+    SharedChargeBilling,
+    SmsDelivery,
+    StorageUpload,
+    AddressRegistryImport,
+    CommunicationExport,
+    WarehouseExport,
+    GuestEntryNotification,
+    EmailNotification,
+    TelegramNotification,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "job_status", rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Completed,
+    Dead,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Job {
+    pub id: Uuid,
+    pub job_type: JobType,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub last_error: Option<String>,
+    pub run_at: DateTime<Utc>,
+    pub locked_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Рассылка уведомления всем жителям ЖК
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationFanoutPayload {
+    pub complex_id: Uuid,
+    pub notification_type: NotificationType,
+    pub title: String,
+    pub body: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub exclude_user_id: Option<Uuid>,
+}
+
+/// Выставить общее начисление в счета квартир
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedChargeBillingPayload {
+    pub charge_id: Uuid,
+}
+
+/// Повторная отправка SMS, не ушедшего из-за сбоя шлюза
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmsDeliveryPayload {
+    pub complex_id: Option<Uuid>,
+    pub phone: String,
+    pub kind: String,
+    pub text: String,
+}
+
+/// Досылка в хранилище файла, отложенного на диск сервера
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageUploadPayload {
+    pub key: String,
+    pub content_type: String,
+}
+
+/// Загрузка справочника адресов из выгрузки КАТО и адресного регистра
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressRegistryImportPayload {
+    pub source_url: String,
+}
+
+/// Сформировать выгрузку истории рассылок ЖК
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunicationExportPayload {
+    pub export_id: Uuid,
+}
+
+/// Выгрузить данные ЖК за сутки в хранилище для аналитики
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WarehouseExportPayload {
+    pub run_id: Uuid,
+}
+
+/// Повторить SMS владельцу о въезде гостя
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuestEntryNotificationPayload {
+    pub guest_access_id: Uuid,
+}
+
+/// Письмо по шаблону пользователям, подписанным на этот тип уведомлений
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailNotificationPayload {
+    pub user_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub template: EmailTemplate,
+}
+
+/// Сообщение от бота пользователям с привязанным Telegram
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramNotificationPayload {
+    pub user_ids: Vec<Uuid>,
+    pub text: String,
+}
@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "legal_document_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum LegalDocumentKind {
+    /// Пользовательское соглашение
+    TermsOfService,
+    /// Согласие на сбор и обработку персональных данных
+    PersonalData,
+}
+
+/// Версия юридического документа
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct LegalDocument {
+    pub id: Uuid,
+    pub kind: LegalDocumentKind,
+    pub version: String,
+    pub title: String,
+    pub content: String,
+    pub published_at: DateTime<Utc>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Опубликовать новую версию документа (админка)
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PublishLegalDocumentRequest {
+    pub kind: LegalDocumentKind,
+    pub version: String,
+    pub title: String,
+    pub content: String,
+    /// Отложенная публикация; по умолчанию сразу
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Принять действующие версии документов
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AcceptConsentsRequest {
+    pub document_ids: Vec<Uuid>,
+}
+
+/// Согласие пользователя с версией документа
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ConsentAcceptance {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub document_id: Uuid,
+    pub accepted_at: DateTime<Utc>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// Состояние согласий: действующие документы и ещё не принятые
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConsentStatusResponse {
+    pub consent_required: bool,
+    pub documents: Vec<LegalDocument>,
+    pub pending: Vec<Uuid>,
+}
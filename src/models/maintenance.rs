@@ -1,9 +1,13 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::recurrence::RecurrenceStep;
+use crate::utils::Patch;
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
 #[sqlx(type_name = "maintenance_category", rename_all = "snake_case")]
 pub enum MaintenanceCategory {
@@ -20,9 +24,10 @@ pub enum MaintenanceCategory {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "maintenance_status", rename_all = "snake_case")]
 pub enum MaintenanceStatus {
+    #[default]
     New,
     InProgress,
     WaitingParts,
@@ -31,27 +36,16 @@ pub enum MaintenanceStatus {
     Cancelled,
 }
 
-impl Default for MaintenanceStatus {
-    fn default() -> Self {
-        Self::New
-    }
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "maintenance_priority", rename_all = "snake_case")]
 pub enum MaintenancePriority {
     Low,
+    #[default]
     Normal,
     High,
     Emergency,
 }
 
-impl Default for MaintenancePriority {
-    fn default() -> Self {
-        Self::Normal
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct MaintenanceRequest {
     pub id: Uuid,
@@ -72,6 +66,10 @@ pub struct MaintenanceRequest {
     pub rating_comment: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub schedule_id: Option<Uuid>,
+    pub planned_for: Option<NaiveDate>,
+    pub estimated_cost: Option<Decimal>,
+    pub total_cost: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -81,6 +79,7 @@ pub struct MaintenancePhoto {
     pub url: String,
     pub is_before: bool,
     pub created_at: DateTime<Utc>,
+    pub uploaded_by: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -105,6 +104,11 @@ pub struct MaintenanceRequestResponse {
     pub photos: Vec<MaintenancePhotoResponse>,
     pub comments_count: i32,
     pub rating: Option<i32>,
+    /// Дата плановых работ, если заявка создана по графику
+    pub planned_for: Option<NaiveDate>,
+    pub estimated_cost: Option<Decimal>,
+    /// Фактическая стоимость — сумма позиций материалов и работ
+    pub total_cost: Decimal,
     pub created_at: DateTime<Utc>,
 }
 
@@ -130,6 +134,64 @@ pub struct UpdateMaintenanceStatusRequest {
     pub completion_notes: Option<String>,
 }
 
+/// Назначить исполнителя; `null` снимает назначение
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignMaintenanceRequest {
+    pub worker_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "maintenance_cost_kind", rename_all = "snake_case")]
+pub enum MaintenanceCostKind {
+    Material,
+    Labor,
+    Other,
+}
+
+/// Позиция стоимости заявки: материал, часы работы или прочая трата
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MaintenanceCostItem {
+    pub id: Uuid,
+    pub request_id: Uuid,
+    pub kind: MaintenanceCostKind,
+    pub description: String,
+    /// Для работ — количество часов
+    pub quantity: Decimal,
+    pub unit: Option<String>,
+    pub unit_price: Decimal,
+    pub amount: Decimal,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MaintenanceCostItemRequest {
+    pub kind: MaintenanceCostKind,
+    pub description: String,
+    pub quantity: Decimal,
+    pub unit: Option<String>,
+    pub unit_price: Decimal,
+}
+
+/// Оценка стоимости до начала работ; `null` снимает оценку
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceEstimateRequest {
+    pub estimated_cost: Option<Decimal>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MaintenanceCostsResponse {
+    pub request_id: Uuid,
+    pub estimated_cost: Option<Decimal>,
+    pub total_cost: Decimal,
+    pub materials: Decimal,
+    pub labor: Decimal,
+    pub labor_hours: Decimal,
+    pub other: Decimal,
+    pub items: Vec<MaintenanceCostItem>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct RateMaintenanceRequest {
     pub rating: i32,
@@ -140,3 +202,100 @@ pub struct RateMaintenanceRequest {
 pub struct AddMaintenanceCommentRequest {
     pub content: String,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "maintenance_frequency", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl MaintenanceFrequency {
+    /// Шаг повторения для «каждые `interval` дней/недель/месяцев/лет»
+    pub fn step(&self, interval: u32) -> RecurrenceStep {
+        match self {
+            MaintenanceFrequency::Daily => RecurrenceStep::Days(interval),
+            MaintenanceFrequency::Weekly => RecurrenceStep::Days(interval * 7),
+            MaintenanceFrequency::Monthly => RecurrenceStep::Months(interval),
+            MaintenanceFrequency::Yearly => RecurrenceStep::Months(interval * 12),
+        }
+    }
+}
+
+/// График плановых работ, по которому создаются заявки
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MaintenanceSchedule {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub created_by: Uuid,
+    pub category: MaintenanceCategory,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub priority: MaintenancePriority,
+    pub assigned_to: Option<Uuid>,
+    pub frequency: MaintenanceFrequency,
+    pub interval_count: i32,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>,
+    /// Дата ближайших работ; пусто, если повторения закончились
+    pub next_run_on: Option<NaiveDate>,
+    pub is_active: bool,
+    pub last_request_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateMaintenanceScheduleRequest {
+    pub category: MaintenanceCategory,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub priority: Option<MaintenancePriority>,
+    /// Исполнитель из сотрудников ОСИ
+    pub assigned_to: Option<Uuid>,
+    pub frequency: MaintenanceFrequency,
+    /// Каждые N периодов, по умолчанию 1
+    pub interval_count: Option<i32>,
+    pub starts_on: NaiveDate,
+    pub ends_on: Option<NaiveDate>,
+}
+
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceScheduleRequest {
+    pub category: Option<MaintenanceCategory>,
+    pub title: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub description: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub location: Patch<String>,
+    pub priority: Option<MaintenancePriority>,
+    #[serde(default)]
+    #[schema(value_type = Option<Uuid>)]
+    pub assigned_to: Patch<Uuid>,
+    pub frequency: Option<MaintenanceFrequency>,
+    pub interval_count: Option<i32>,
+    pub starts_on: Option<NaiveDate>,
+    #[serde(default)]
+    #[schema(value_type = Option<NaiveDate>)]
+    pub ends_on: Patch<NaiveDate>,
+    pub is_active: Option<bool>,
+}
+
+/// Плановые работы, которые ждут жителей
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PlannedMaintenanceResponse {
+    pub schedule_id: Uuid,
+    pub category: MaintenanceCategory,
+    pub title: String,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub planned_for: NaiveDate,
+}
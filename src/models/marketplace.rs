@@ -5,6 +5,10 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::Patch;
+
+use super::{PhotoVariants, ViewStats};
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct MarketplaceCategory {
     pub id: Uuid,
@@ -41,20 +45,17 @@ impl From<MarketplaceCategory> for CategoryResponse {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "listing_status", rename_all = "snake_case")]
 pub enum ListingStatus {
     Draft,
+    #[default]
     Active,
     Sold,
     Reserved,
     Archived,
-}
-
-impl Default for ListingStatus {
-    fn default() -> Self {
-        Self::Active
-    }
+    /// Скрыто модератором по жалобе; продавец не может вернуть его в ленту
+    Hidden,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -71,9 +72,57 @@ pub struct MarketplaceListing {
     pub condition: Option<String>,
     pub status: ListingStatus,
     pub views_count: i32,
+    pub total_views_count: i32,
     pub favorites_count: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub reserved_for: Option<Uuid>,
+    pub reserved_at: Option<DateTime<Utc>>,
+    pub buyer_id: Option<Uuid>,
+    pub sold_at: Option<DateTime<Utc>>,
+    pub received_at: Option<DateTime<Utc>>,
+    pub buyer_rating: Option<i32>,
+    pub buyer_review: Option<String>,
+}
+
+impl MarketplaceListing {
+    /// Статистика просмотров для продавца
+    pub fn view_stats_for(&self, user_id: Uuid) -> Option<ViewStats> {
+        (self.seller_id == user_id).then_some(ViewStats {
+            unique_views: self.views_count,
+            total_views: self.total_views_count,
+        })
+    }
+
+    /// Бронь и продажа видны только продавцу и самому покупателю
+    pub fn sale_for(&self, user_id: Uuid) -> Option<ListingSale> {
+        let involved = self.seller_id == user_id
+            || self.reserved_for == Some(user_id)
+            || self.buyer_id == Some(user_id);
+        let has_sale = self.reserved_for.is_some() || self.buyer_id.is_some();
+        (involved && has_sale).then(|| ListingSale {
+            reserved_for: self.reserved_for,
+            reserved_at: self.reserved_at,
+            buyer_id: self.buyer_id,
+            sold_at: self.sold_at,
+            received_at: self.received_at,
+            buyer_rating: self.buyer_rating,
+            buyer_review: self.buyer_review.clone(),
+        })
+    }
+}
+
+/// Бронь и продажа объявления
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ListingSale {
+    pub reserved_for: Option<Uuid>,
+    pub reserved_at: Option<DateTime<Utc>>,
+    pub buyer_id: Option<Uuid>,
+    pub sold_at: Option<DateTime<Utc>>,
+    /// Покупатель подтвердил получение
+    pub received_at: Option<DateTime<Utc>>,
+    pub buyer_rating: Option<i32>,
+    pub buyer_review: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -99,9 +148,16 @@ pub struct ListingResponse {
     pub category: CategoryResponse,
     pub seller: SellerInfo,
     pub photos: Vec<String>,
+    /// Те же фото с превью и облегчёнными копиями
+    pub photo_variants: Vec<PhotoVariants>,
     pub views_count: i32,
+    pub view_stats: Option<ViewStats>,
     pub favorites_count: i32,
     pub is_favorite: bool,
+    /// Только для продавца и покупателя
+    pub sale: Option<ListingSale>,
+    /// Релевантность поисковому запросу, только в выдаче поиска
+    pub relevance: Option<f32>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -110,6 +166,9 @@ pub struct SellerInfo {
     pub id: Uuid,
     pub name: String,
     pub avatar_url: Option<String>,
+    /// Средняя оценка от покупателей, подтвердивших получение
+    pub rating: Option<f64>,
+    pub reviews_count: i64,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -123,18 +182,35 @@ pub struct CreateListingRequest {
     pub condition: Option<String>,
 }
 
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateListingRequest {
     pub category_id: Option<Uuid>,
     pub title: Option<String>,
-    pub description: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub description: Patch<String>,
     pub price: Option<Decimal>,
     pub is_negotiable: Option<bool>,
     pub is_free: Option<bool>,
-    pub condition: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub condition: Patch<String>,
     pub status: Option<ListingStatus>,
 }
 
+/// Порядок выдачи объявлений
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingSort {
+    /// По релевантности запросу; без запроса — как `newest`
+    #[default]
+    Relevance,
+    Newest,
+    PriceAsc,
+    PriceDesc,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct ListingsQuery {
     pub category: Option<String>,
@@ -142,6 +218,7 @@ pub struct ListingsQuery {
     pub min_price: Option<Decimal>,
     pub max_price: Option<Decimal>,
     pub condition: Option<String>,
+    pub sort: Option<ListingSort>,
     pub page: Option<i64>,
     pub limit: Option<i64>,
 }
@@ -165,7 +242,137 @@ pub struct ListingMessage {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReserveListingRequest {
+    /// Сосед, для которого продавец придерживает товар
+    pub buyer_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SellListingRequest {
+    /// По умолчанию — тот, для кого объявление забронировано
+    pub buyer_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmReceiptRequest {
+    /// Оценка продавцу от 1 до 5
+    pub rating: Option<i32>,
+    pub review: Option<String>,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct SendMessageRequest {
     pub message: String,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "listing_report_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ListingReportReason {
+    Spam,
+    /// Мошенничество
+    Fraud,
+    /// Запрещённый товар
+    Prohibited,
+    Offensive,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "listing_report_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ListingReportStatus {
+    Pending,
+    Resolved,
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ListingReport {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub complex_id: Uuid,
+    pub reporter_id: Uuid,
+    pub reason: ListingReportReason,
+    pub comment: Option<String>,
+    pub status: ListingReportStatus,
+    pub resolution: Option<String>,
+    pub moderator_note: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ReportListingRequest {
+    pub reason: ListingReportReason,
+    pub comment: Option<String>,
+}
+
+/// Жалоба в очереди модерации вместе с объявлением
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct ListingReportEntry {
+    pub id: Uuid,
+    pub listing_id: Uuid,
+    pub complex_id: Uuid,
+    pub reporter_id: Uuid,
+    pub reporter_name: String,
+    pub reason: ListingReportReason,
+    pub comment: Option<String>,
+    pub status: ListingReportStatus,
+    pub resolution: Option<String>,
+    pub moderator_note: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub listing_title: String,
+    pub listing_status: ListingStatus,
+    pub seller_id: Uuid,
+    pub seller_name: String,
+    /// Открытых жалоб на это объявление
+    pub pending_reports: i64,
+    /// Предупреждений продавцу за всё время
+    pub seller_warnings: i64,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ListingReportsQuery {
+    /// По умолчанию — открытые жалобы
+    pub status: Option<ListingReportStatus>,
+    pub complex_id: Option<Uuid>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Мера по жалобе
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// Отклонить жалобу
+    Dismiss,
+    HideListing,
+    /// Предупредить продавца, объявление остаётся
+    WarnUser,
+    /// Запретить продавцу маркетплейс и скрыть все его объявления
+    BanUser,
+}
+
+impl ModerationAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationAction::Dismiss => "dismiss",
+            ModerationAction::HideListing => "hide_listing",
+            ModerationAction::WarnUser => "warn_user",
+            ModerationAction::BanUser => "ban_user",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ResolveListingReportRequest {
+    pub action: ModerationAction,
+    /// Комментарий модератора; продавец видит его в уведомлении
+    pub note: Option<String>,
+    /// Срок блокировки для `ban_user`; без него — бессрочно
+    pub ban_days: Option<i64>,
+}
@@ -1,29 +1,55 @@
 pub mod address;
 pub mod announcement;
 pub mod apartment;
+pub mod audit;
+pub mod bookmark;
 pub mod chat;
 pub mod city;
 pub mod communal;
 pub mod complex;
+pub mod event;
+pub mod file;
+pub mod finance;
+pub mod job;
+pub mod legal;
 pub mod maintenance;
 pub mod marketplace;
 pub mod notification;
 pub mod osi;
+pub mod pagination;
+pub mod permission;
 pub mod security;
+pub mod survey;
+pub mod system;
+pub mod template;
 pub mod user;
+pub mod view;
 pub mod voting;
 
 pub use address::*;
 pub use announcement::*;
 pub use apartment::*;
+pub use audit::*;
+pub use bookmark::*;
 pub use chat::*;
 pub use city::*;
 pub use communal::*;
 pub use complex::*;
+pub use event::*;
+pub use file::*;
+pub use finance::*;
+pub use job::*;
+pub use legal::*;
 pub use maintenance::*;
 pub use marketplace::*;
 pub use notification::*;
 pub use osi::*;
+pub use pagination::*;
+pub use permission::*;
 pub use security::*;
+pub use survey::*;
+pub use system::*;
+pub use template::*;
 pub use user::*;
+pub use view::*;
 pub use voting::*;
@@ -1,4 +1,5 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
@@ -17,6 +18,164 @@ pub enum NotificationType {
     Chat,
     Marketplace,
     System,
+    Finance,
+}
+
+impl NotificationType {
+    pub const ALL: [NotificationType; 11] = [
+        NotificationType::Announcement,
+        NotificationType::Voting,
+        NotificationType::Bill,
+        NotificationType::Payment,
+        NotificationType::GuestAccess,
+        NotificationType::Maintenance,
+        NotificationType::Security,
+        NotificationType::Chat,
+        NotificationType::Marketplace,
+        NotificationType::System,
+        NotificationType::Finance,
+    ];
+
+    /// Уведомления о безопасности и системные нельзя отключить в приложении
+    pub fn is_mandatory(&self) -> bool {
+        matches!(self, NotificationType::Security | NotificationType::System)
+    }
+}
+
+/// Канал доставки уведомления
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationChannel {
+    Push,
+    Sms,
+    InApp,
+    Email,
+    Telegram,
+}
+
+impl NotificationChannel {
+    /// Колонка в `notification_settings`
+    pub fn column(&self) -> &'static str {
+        match self {
+            NotificationChannel::Push => "push",
+            NotificationChannel::Sms => "sms",
+            NotificationChannel::InApp => "in_app",
+            NotificationChannel::Email => "email",
+            NotificationChannel::Telegram => "telegram",
+        }
+    }
+
+    /// Письма отправляются только по подписке, остальные каналы включены, пока их не отключили
+    pub fn enabled_by_default(&self) -> bool {
+        !matches!(self, NotificationChannel::Email)
+    }
+}
+
+/// Каналы доставки для одного типа уведомлений; без записи в базе всё, кроме email, включено
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct NotificationSetting {
+    pub notification_type: NotificationType,
+    pub push: bool,
+    pub sms: bool,
+    pub in_app: bool,
+    pub email: bool,
+    pub telegram: bool,
+}
+
+/// Изменение настроек типа; не указанные каналы не меняются
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NotificationSettingUpdate {
+    pub notification_type: NotificationType,
+    pub push: Option<bool>,
+    pub sms: Option<bool>,
+    pub in_app: Option<bool>,
+    pub email: Option<bool>,
+    pub telegram: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateNotificationSettingsRequest {
+    pub settings: Vec<NotificationSettingUpdate>,
+}
+
+/// Транзакционное письмо с данными для подстановки в шаблон
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "template", rename_all = "snake_case")]
+pub enum EmailTemplate {
+    BillIssued {
+        bill_id: Uuid,
+        period_start: NaiveDate,
+        total_amount: Decimal,
+        due_date: NaiveDate,
+    },
+    VotingOpened {
+        voting_id: Uuid,
+        title: String,
+        ends_at: DateTime<Utc>,
+    },
+    JoinRequestApproved {
+        complex_name: String,
+        building: Option<String>,
+        apartment_number: String,
+    },
+}
+
+impl EmailTemplate {
+    /// Имя шаблона в журнале писем
+    pub fn name(&self) -> &'static str {
+        match self {
+            EmailTemplate::BillIssued { .. } => "bill_issued",
+            EmailTemplate::VotingOpened { .. } => "voting_opened",
+            EmailTemplate::JoinRequestApproved { .. } => "join_request_approved",
+        }
+    }
+
+    /// Тип уведомления, по настройкам которого проверяется подписка на письмо
+    pub fn notification_type(&self) -> NotificationType {
+        match self {
+            EmailTemplate::BillIssued { .. } => NotificationType::Bill,
+            EmailTemplate::VotingOpened { .. } => NotificationType::Voting,
+            EmailTemplate::JoinRequestApproved { .. } => NotificationType::System,
+        }
+    }
+}
+
+/// Код привязки Telegram: пользователь открывает ссылку, бот получает `/start <code>`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TelegramLinkCode {
+    pub code: String,
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TelegramLinkStatus {
+    pub linked: bool,
+    pub username: Option<String>,
+    pub linked_at: Option<DateTime<Utc>>,
+}
+
+/// Обновление из вебхука Telegram; нужны только текстовые сообщения
+#[derive(Debug, Deserialize)]
+pub struct TelegramUpdate {
+    pub update_id: i64,
+    pub message: Option<TelegramMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramMessage {
+    pub chat: TelegramChat,
+    pub from: Option<TelegramUser>,
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramChat {
+    pub id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TelegramUser {
+    pub username: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -80,6 +239,68 @@ pub struct RegisterPushTokenRequest {
 pub struct NotificationsQuery {
     pub unread_only: Option<bool>,
     pub notification_type: Option<String>,
-    pub page: Option<i64>,
+    /// Курсор из `next_cursor` предыдущей страницы
+    pub cursor: Option<String>,
     pub limit: Option<i64>,
 }
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "export_format", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Pdf,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "application/pdf",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "export_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// Выгрузка истории объявлений и рассылок ЖК за период
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct CommunicationExport {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub requested_by: Uuid,
+    pub date_from: NaiveDate,
+    pub date_to: NaiveDate,
+    pub format: ExportFormat,
+    pub status: ExportStatus,
+    #[serde(skip_serializing)]
+    pub file_key: Option<String>,
+    /// Сколько записей попало в отчёт
+    pub items_count: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateCommunicationExportRequest {
+    /// Первый день периода включительно
+    pub date_from: NaiveDate,
+    /// Последний день периода включительно
+    pub date_to: NaiveDate,
+    pub format: ExportFormat,
+}
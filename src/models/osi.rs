@@ -5,6 +5,8 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::utils::Patch;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Osi {
     pub id: Uuid,
@@ -18,6 +20,9 @@ pub struct Osi {
     pub bank_name: Option<String>,
     pub bank_bik: Option<String>,
     pub bank_account: Option<String>,
+    pub expense_approval_threshold: Option<Decimal>,
+    pub expense_required_approvals: i32,
+    pub sms_monthly_cap: Option<Decimal>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -41,16 +46,31 @@ pub struct ChairmanInfo {
     pub phone: String,
 }
 
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateOsiRequest {
     pub name: Option<String>,
-    pub bin: Option<String>,
-    pub phone: Option<String>,
-    pub email: Option<String>,
-    pub address: Option<String>,
-    pub bank_name: Option<String>,
-    pub bank_bik: Option<String>,
-    pub bank_account: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub bin: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub phone: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub email: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub address: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub bank_name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub bank_bik: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub bank_account: Patch<String>,
 }
 
 // Совет дома
@@ -113,6 +133,8 @@ pub enum WorkerRole {
 pub struct OsiWorker {
     pub id: Uuid,
     pub osi_id: Uuid,
+    /// Аккаунт сотрудника в приложении, если он зарегистрирован
+    pub user_id: Option<Uuid>,
     pub first_name: String,
     pub last_name: String,
     pub middle_name: Option<String>,
@@ -197,3 +219,63 @@ pub struct ChairmanApplication {
     pub rejection_reason: Option<String>,
     pub created_at: DateTime<Utc>,
 }
+
+/// Раздел входящих председателя
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxItemKind {
+    JoinRequest,
+    UnassignedMaintenance,
+    ExpiringCouncilTerm,
+    UnverifiedReading,
+}
+
+impl InboxItemKind {
+    pub const ALL: [InboxItemKind; 4] = [
+        InboxItemKind::JoinRequest,
+        InboxItemKind::UnassignedMaintenance,
+        InboxItemKind::ExpiringCouncilTerm,
+        InboxItemKind::UnverifiedReading,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InboxItemKind::JoinRequest => "join_request",
+            InboxItemKind::UnassignedMaintenance => "unassigned_maintenance",
+            InboxItemKind::ExpiringCouncilTerm => "expiring_council_term",
+            InboxItemKind::UnverifiedReading => "unverified_reading",
+        }
+    }
+}
+
+/// Экран мобильного приложения, куда ведёт элемент, и его параметры
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboxLink {
+    pub screen: &'static str,
+    pub params: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboxItem {
+    pub id: Uuid,
+    pub title: String,
+    pub subtitle: Option<String>,
+    /// Когда подано, а для сроков совета — когда истекает
+    pub date: DateTime<Utc>,
+    pub link: InboxLink,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InboxSection {
+    pub kind: InboxItemKind,
+    pub count: i64,
+    /// Первые несколько элементов; остальные — на экране раздела
+    pub items: Vec<InboxItem>,
+}
+
+/// Всё, что ждёт действия председателя
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OsiInboxResponse {
+    pub total: i64,
+    pub sections: Vec<InboxSection>,
+}
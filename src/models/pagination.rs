@@ -0,0 +1,111 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::{
+    AnnouncementResponse, BarrierAccessLogResponse, BillResponse, ChatMediaItem,
+    ChatMessageResponse, IntercomSnapshotResponse, ListingReportEntry, ListingResponse,
+    NotificationResponse, SecurityEvent,
+};
+
+/// Страница списка с общим количеством записей
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    PaginatedAnnouncements = Paginated<AnnouncementResponse>,
+    PaginatedBills = Paginated<BillResponse>,
+    PaginatedListings = Paginated<ListingResponse>,
+    PaginatedListingReports = Paginated<ListingReportEntry>
+)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    pub page: i64,
+    pub limit: i64,
+    /// Всего записей по фильтру
+    pub total: i64,
+    pub has_more: bool,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(items: Vec<T>, page: i64, limit: i64, total: i64) -> Self {
+        let has_more = (page + 1) * limit < total;
+        Self {
+            items,
+            page,
+            limit,
+            total,
+            has_more,
+        }
+    }
+}
+
+/// Параметры keyset-пагинации
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct CursorQuery {
+    /// Курсор из `next_cursor` предыдущей страницы
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// Позиция в списке, отсортированном по (created_at, id) по убыванию
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn new(created_at: DateTime<Utc>, id: Uuid) -> Self {
+        Self { created_at, id }
+    }
+
+    /// Непрозрачная строка для клиента
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", self.created_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(value).ok()?).ok()?;
+        let (micros, id) = raw.split_once('|')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::parse_str(id).ok()?,
+        })
+    }
+}
+
+/// Страница keyset-пагинации; `next_cursor` пуст, если записей больше нет
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    ChatMessagesPage = CursorPage<ChatMessageResponse>,
+    ChatMediaPage = CursorPage<ChatMediaItem>,
+    BarrierHistoryPage = CursorPage<BarrierAccessLogResponse>,
+    IntercomSnapshotsPage = CursorPage<IntercomSnapshotResponse>,
+    NotificationsPage = CursorPage<NotificationResponse>,
+    SecurityEventsPage = CursorPage<SecurityEvent>
+)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> CursorPage<T> {
+    pub fn new(items: Vec<T>, next_cursor: Option<Cursor>) -> Self {
+        Self {
+            items,
+            next_cursor: next_cursor.map(|c| c.encode()),
+        }
+    }
+}
+
+/// Выборка делается с `limit + 1`: лишняя запись отрезается и означает, что есть продолжение.
+/// Возвращает курсор на следующую страницу.
+pub fn take_page<R>(rows: &mut Vec<R>, limit: i64, cursor_of: impl Fn(&R) -> Cursor) -> Option<Cursor> {
+    if rows.len() as i64 <= limit {
+        return None;
+    }
+
+    rows.truncate(limit.max(0) as usize);
+    rows.last().map(cursor_of)
+}
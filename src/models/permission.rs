@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use super::UserRole;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "app_permission", rename_all = "snake_case")]
+pub enum Permission {
+    ManageOsi,
+    ManageCouncil,
+    ManageWorkers,
+    ManageDocuments,
+    ManageFinance,
+    ManageBills,
+    ManagePermissions,
+}
+
+/// Роль пользователя в конкретном ЖК
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ComplexRole {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub complex_id: Uuid,
+    pub role: UserRole,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Отдельное право пользователя в ЖК
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct PermissionGrant {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub complex_id: Uuid,
+    pub permission: Permission,
+    pub granted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ComplexPermissionsResponse {
+    pub roles: Vec<ComplexRole>,
+    pub grants: Vec<PermissionGrant>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignComplexRoleRequest {
+    pub user_id: Uuid,
+    pub role: UserRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GrantPermissionRequest {
+    pub user_id: Uuid,
+    pub permission: Permission,
+}
@@ -1,24 +1,36 @@
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
 // Статус гостевого доступа
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "guest_access_status", rename_all = "snake_case")]
 pub enum GuestAccessStatus {
+    /// Ждёт подтверждения охраной или председателем
+    AwaitingApproval,
+    #[default]
     Pending,
     Active,
     Expired,
     Completed,
     Cancelled,
+    Declined,
 }
 
-impl Default for GuestAccessStatus {
-    fn default() -> Self {
-        Self::Pending
-    }
+/// Как владелец пропуска узнал о въезде гостя
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "guest_notification_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum GuestNotificationStatus {
+    /// Ушло SMS
+    Sent,
+    /// SMS не ушло, ждёт повтора
+    Retrying,
+    /// Вместо SMS отправлен push: SMS выключены, исчерпан лимит ОСИ или все попытки неудачны
+    PushSent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -39,7 +51,14 @@ pub struct GuestAccess {
     pub owner_notified: bool,
     pub chairman_notified: bool,
     pub overstay_notified: bool,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub decline_reason: Option<String>,
+    pub suspended_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
+    pub owner_notification_status: Option<GuestNotificationStatus>,
+    pub owner_notification_attempts: i32,
+    pub owner_notification_error: Option<String>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -55,7 +74,12 @@ pub struct GuestAccessResponse {
     pub entered_at: Option<DateTime<Utc>>,
     pub exited_at: Option<DateTime<Utc>>,
     pub status: GuestAccessStatus,
+    pub decline_reason: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Уведомление владельца о въезде; пусто, пока гость не въехал
+    pub owner_notification: Option<GuestNotificationStatus>,
+    /// Сколько раз пытались отправить SMS
+    pub owner_notification_attempts: i32,
 }
 
 impl From<GuestAccess> for GuestAccessResponse {
@@ -72,7 +96,10 @@ impl From<GuestAccess> for GuestAccessResponse {
             entered_at: ga.entered_at,
             exited_at: ga.exited_at,
             status: ga.status,
+            decline_reason: ga.decline_reason,
             created_at: ga.created_at,
+            owner_notification: ga.owner_notification_status,
+            owner_notification_attempts: ga.owner_notification_attempts,
         }
     }
 }
@@ -117,9 +144,24 @@ pub struct BarrierAccessLog {
     pub guest_access_id: Option<Uuid>,
     pub action: BarrierAction,
     pub vehicle_number: Option<String>,
+    pub latitude: Option<Decimal>,
+    pub longitude: Option<Decimal>,
+    pub distance_m: Option<i32>,
+    pub geo_mismatch: Option<bool>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Где житель открыл шлагбаум; видит только председатель
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BarrierOpenLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Расстояние до ЖК, метров; нет, если у адреса ЖК нет координат
+    pub distance_m: Option<i32>,
+    /// Открыто дальше допустимого радиуса
+    pub geo_mismatch: Option<bool>,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct BarrierAccessLogResponse {
     pub id: Uuid,
@@ -127,6 +169,7 @@ pub struct BarrierAccessLogResponse {
     pub vehicle_number: Option<String>,
     pub user_name: Option<String>,
     pub guest_name: Option<String>,
+    pub location: Option<BarrierOpenLocation>,
     pub created_at: DateTime<Utc>,
 }
 
@@ -137,6 +180,48 @@ pub struct BarrierEntryRequest {
     pub barrier_id: Option<Uuid>,
 }
 
+/// Автомобиль жителя, по номеру которого шлагбаум открывается автоматически
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ResidentVehicle {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub user_id: Uuid,
+    pub vehicle_number: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateResidentVehicleRequest {
+    pub vehicle_number: String,
+    pub description: Option<String>,
+}
+
+/// Номер, распознанный камерой шлагбаума
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AnprEntryRequest {
+    pub barrier_id: Uuid,
+    pub plate: String,
+}
+
+/// Чей автомобиль распознан
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnprMatch {
+    Resident,
+    Guest,
+}
+
+/// Решение по распознанному номеру: открыть шлагбаум и кому
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AnprEntryResponse {
+    pub open: bool,
+    pub vehicle_number: String,
+    pub matched: AnprMatch,
+    pub user_id: Option<Uuid>,
+    pub guest_access_id: Option<Uuid>,
+}
+
 // Камеры
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Camera {
@@ -165,7 +250,15 @@ pub struct CameraResponse {
 pub struct CameraStreamResponse {
     pub id: Uuid,
     pub name: String,
+    /// Адрес HLS-плейлиста на бэкенде с токеном воспроизведения
     pub stream_url: String,
+    /// Когда истекает токен; после этого ссылку нужно запросить заново
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct StreamTokenQuery {
+    pub token: String,
 }
 
 // Домофоны
@@ -178,6 +271,12 @@ pub struct Intercom {
     pub device_type: Option<String>,
     pub device_id: Option<String>,
     pub sip_address: Option<String>,
+    /// HTTP-адрес устройства; может содержать учётные данные
+    #[serde(skip_serializing)]
+    pub api_url: Option<String>,
+    /// Токен, которым устройство подписывает входящие вызовы
+    #[serde(skip_serializing)]
+    pub device_token: Option<String>,
     pub is_active: bool,
     pub created_at: DateTime<Utc>,
 }
@@ -185,10 +284,22 @@ pub struct Intercom {
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
 #[sqlx(type_name = "intercom_call_status", rename_all = "snake_case")]
 pub enum IntercomCallStatus {
+    /// Вызов идёт, житель ещё не ответил
+    Ringing,
     Missed,
     Answered,
     Opened,
     Rejected,
+    /// Дверь открыта кодом квартиры на панели
+    CodeOpened,
+    /// На панели набран неверный код квартиры
+    CodeDenied,
+}
+
+impl sqlx::postgres::PgHasArrayType for IntercomCallStatus {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_intercom_call_status")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -200,10 +311,21 @@ pub struct IntercomCall {
     pub status: IntercomCallStatus,
     pub duration_seconds: Option<i32>,
     pub snapshot_url: Option<String>,
+    pub answered_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+/// Входящий вызов от устройства домофона
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IntercomRingRequest {
+    /// Номер квартиры, набранный на панели
+    pub apartment_number: String,
+    pub building: Option<String>,
+    pub snapshot_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
 pub struct IntercomCallResponse {
     pub id: Uuid,
     pub intercom_name: String,
@@ -212,3 +334,203 @@ pub struct IntercomCallResponse {
     pub snapshot_url: Option<String>,
     pub created_at: DateTime<Utc>,
 }
+
+/// Код квартиры для входа через панель домофона
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ApartmentIntercomCode {
+    pub apartment_id: Uuid,
+    pub complex_id: Uuid,
+    /// Сам код — только в ответе на создание или смену: хранится лишь его хэш
+    #[sqlx(default)]
+    pub code: Option<String>,
+    pub rotated_by: Option<Uuid>,
+    pub rotated_at: DateTime<Utc>,
+}
+
+/// Код, набранный на панели домофона
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct IntercomCodeRequest {
+    pub apartment_number: String,
+    pub building: Option<String>,
+    pub code: String,
+}
+
+/// Решение для панели: открывать дверь или нет
+#[derive(Debug, Serialize, ToSchema)]
+pub struct IntercomCodeResponse {
+    pub valid: bool,
+    /// Запись в истории вызовов квартиры
+    pub call_id: Option<Uuid>,
+}
+
+/// Снимок с домофона в галерее квартиры
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct IntercomSnapshotResponse {
+    pub call_id: Uuid,
+    pub intercom_name: String,
+    pub status: IntercomCallStatus,
+    pub snapshot_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Код доступа или содержимое QR, предъявленные на посту охраны
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GuardValidateCodeRequest {
+    pub code: String,
+}
+
+/// Результат проверки кода охраной
+#[derive(Debug, Serialize, ToSchema)]
+pub struct GuardValidateCodeResponse {
+    pub valid: bool,
+    /// Почему пропуск недействителен
+    pub reason: Option<String>,
+    pub guest: Option<ExpectedGuestResponse>,
+}
+
+/// Ожидаемый гость с квартирой пригласившего
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct ExpectedGuestResponse {
+    pub id: Uuid,
+    pub guest_name: Option<String>,
+    pub guest_phone: Option<String>,
+    pub vehicle_number: Option<String>,
+    pub host_name: Option<String>,
+    pub apartment_number: Option<String>,
+    pub building: Option<String>,
+    pub status: GuestAccessStatus,
+    pub expires_at: DateTime<Utc>,
+    pub entered_at: Option<DateTime<Utc>>,
+    /// Пропуск приостановлен из-за подозрительной активности
+    pub suspended_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Нужно ли подтверждать гостевые пропуска в ЖК
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GuestApprovalPolicy {
+    pub guest_approval_required: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct GuestApprovalsQuery {
+    pub complex_id: Uuid,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct DeclineGuestAccessRequest {
+    pub reason: Option<String>,
+}
+
+/// Ручное открытие шлагбаума охраной
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct GuardManualOpenRequest {
+    pub reason: String,
+    pub barrier_id: Option<Uuid>,
+    pub vehicle_number: Option<String>,
+    pub guest_access_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct BarrierManualOpening {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub barrier_id: Option<Uuid>,
+    pub worker_id: Uuid,
+    pub user_id: Uuid,
+    pub reason: String,
+    pub vehicle_number: Option<String>,
+    pub guest_access_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+// Чёрный список
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BlacklistEntry {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub vehicle_number: Option<String>,
+    pub person_name: Option<String>,
+    pub person_phone: Option<String>,
+    pub reason: String,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBlacklistEntryRequest {
+    pub vehicle_number: Option<String>,
+    pub person_name: Option<String>,
+    pub person_phone: Option<String>,
+    pub reason: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct BarrierDenialResponse {
+    pub id: Uuid,
+    pub barrier_id: Option<Uuid>,
+    pub blacklist_id: Uuid,
+    pub guest_access_id: Option<Uuid>,
+    pub vehicle_number: Option<String>,
+    pub reason: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// Лента безопасности
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "security_event_type", rename_all = "snake_case")]
+pub enum SecurityEventType {
+    /// По одному пропуску слишком много въездов
+    PassOveruse,
+    /// Повторный въезд автомобиля без выезда
+    EntryWithoutExit,
+    /// Житель слишком часто открывает шлагбаум
+    ExcessiveOpenings,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct SecurityEvent {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub event_type: SecurityEventType,
+    pub user_id: Option<Uuid>,
+    pub guest_access_id: Option<Uuid>,
+    pub vehicle_number: Option<String>,
+    pub details: serde_json::Value,
+    pub pass_suspended: bool,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SecurityEventsQuery {
+    pub complex_id: Uuid,
+    /// Только непросмотренные
+    pub unreviewed: Option<bool>,
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ReviewSecurityEventRequest {
+    /// Снять приостановку с пропуска
+    pub lift_suspension: bool,
+}
+
+/// Автоматическая приостановка пропусков при подозрительной активности
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AnomalySettings {
+    pub auto_suspend_passes: bool,
+}
+
+/// Привязка сессий председателя к устройству входа
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeviceBindingSettings {
+    pub device_binding: bool,
+}
@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "survey_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SurveyStatus {
+    Active,
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "survey_question_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum SurveyQuestionType {
+    /// Выбор из вариантов, один или несколько
+    Choice,
+    /// Свободный ответ
+    Text,
+    /// Оценка по шкале
+    Scale,
+}
+
+/// Опрос жителей ЖК
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Survey {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: SurveyStatus,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub responses_count: i32,
+    pub created_by: Uuid,
+    pub closed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Survey {
+    /// Принимает ли опрос ответы
+    pub fn is_open(&self) -> bool {
+        self.status == SurveyStatus::Active && self.ends_at.is_none_or(|ends_at| ends_at > Utc::now())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SurveyQuestion {
+    pub id: Uuid,
+    pub survey_id: Uuid,
+    pub position: i32,
+    pub question_type: SurveyQuestionType,
+    pub text: String,
+    pub options: Vec<String>,
+    pub allow_multiple: bool,
+    pub scale_min: i32,
+    pub scale_max: i32,
+    pub is_required: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSurveyQuestion {
+    pub question_type: SurveyQuestionType,
+    pub text: String,
+    /// Варианты для вопроса с выбором
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub allow_multiple: bool,
+    /// Границы шкалы, по умолчанию 1–5
+    pub scale_min: Option<i32>,
+    pub scale_max: Option<i32>,
+    pub is_required: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateSurveyRequest {
+    pub complex_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub questions: Vec<CreateSurveyQuestion>,
+}
+
+/// Опрос с вопросами и отметкой, отвечал ли текущий пользователь
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyResponse {
+    pub survey: Survey,
+    pub questions: Vec<SurveyQuestion>,
+    pub user_responded: bool,
+}
+
+/// Ответ на один вопрос: заполняется поле, соответствующее типу вопроса
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SurveyAnswerInput {
+    pub question_id: Uuid,
+    /// Номера выбранных вариантов, с нуля
+    #[serde(default)]
+    pub option_indexes: Vec<i32>,
+    pub text: Option<String>,
+    pub scale_value: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SubmitSurveyRequest {
+    pub answers: Vec<SurveyAnswerInput>,
+}
+
+/// Сколько раз выбран вариант
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyOptionResult {
+    pub index: i32,
+    pub text: String,
+    pub count: i64,
+    /// Доля ответивших на вопрос
+    pub percentage: f64,
+}
+
+/// Сколько раз поставлена оценка
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyScaleResult {
+    pub value: i32,
+    pub count: i64,
+}
+
+/// Итоги вопроса по одному корпусу
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyBuildingResult {
+    /// Корпус; `null` — ответившие без квартиры или корпуса
+    pub building: Option<String>,
+    pub answers_count: i64,
+    /// Выборы по вариантам в порядке вопроса
+    pub option_counts: Vec<i64>,
+    pub scale_average: Option<f64>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyQuestionResult {
+    pub question_id: Uuid,
+    pub text: String,
+    pub question_type: SurveyQuestionType,
+    pub answers_count: i64,
+    pub options: Vec<SurveyOptionResult>,
+    pub scale: Vec<SurveyScaleResult>,
+    pub scale_average: Option<f64>,
+    /// Свободные ответы, новые сверху
+    pub text_answers: Vec<String>,
+    pub buildings: Vec<SurveyBuildingResult>,
+}
+
+/// Сводные итоги опроса для графиков
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SurveyResults {
+    pub survey_id: Uuid,
+    pub responses_count: i32,
+    pub questions: Vec<SurveyQuestionResult>,
+}
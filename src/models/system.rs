@@ -0,0 +1,309 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::utils::Patch;
+
+use super::{ExportStatus, UserRole};
+
+/// Режим технического обслуживания (хранится в system_settings)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceMode {
+    /// Включён вручную администратором
+    #[serde(default)]
+    pub enabled: bool,
+    pub message: Option<String>,
+    /// Плановое окно работ
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+impl MaintenanceMode {
+    /// Действует ли режим обслуживания сейчас
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        if self.enabled {
+            return true;
+        }
+
+        match (self.starts_at, self.ends_at) {
+            (Some(starts_at), Some(ends_at)) => starts_at <= now && now < ends_at,
+            (Some(starts_at), None) => starts_at <= now,
+            _ => false,
+        }
+    }
+
+    /// Запланированы ли работы в будущем
+    pub fn is_upcoming(&self, now: DateTime<Utc>) -> bool {
+        !self.is_active(now) && self.starts_at.map(|s| s > now).unwrap_or(false)
+    }
+
+    pub fn message_or_default(&self) -> String {
+        self.message
+            .clone()
+            .unwrap_or_else(|| "Ведутся технические работы. Попробуйте позже.".to_string())
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMaintenanceModeRequest {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Баннер для отображения в приложении
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BootstrapBanner {
+    pub kind: String,
+    pub message: String,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BootstrapResponse {
+    pub api_version: String,
+    pub maintenance: bool,
+    pub banner: Option<BootstrapBanner>,
+    /// Платформенные баннеры; без авторизации — только адресованные всем
+    pub banners: Vec<GlobalBanner>,
+    /// Опубликована новая версия соглашения, которую пользователь ещё не принял
+    pub consent_required: bool,
+    pub server_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "banner_severity", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BannerSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Платформенный баннер от администрации сервиса
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct GlobalBanner {
+    pub id: Uuid,
+    pub title: String,
+    pub message: String,
+    pub link_url: Option<String>,
+    pub severity: BannerSeverity,
+    pub target_cities: Vec<String>,
+    pub target_roles: Vec<UserRole>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub is_dismissible: bool,
+    pub is_active: bool,
+    pub send_push: bool,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateBannerRequest {
+    pub title: String,
+    pub message: String,
+    pub link_url: Option<String>,
+    pub severity: Option<BannerSeverity>,
+    #[serde(default)]
+    pub target_cities: Vec<String>,
+    #[serde(default)]
+    pub target_roles: Vec<UserRole>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub is_dismissible: Option<bool>,
+    #[serde(default)]
+    pub send_push: bool,
+}
+
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateBannerRequest {
+    pub title: Option<String>,
+    pub message: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub link_url: Patch<String>,
+    pub severity: Option<BannerSeverity>,
+    pub target_cities: Option<Vec<String>>,
+    pub target_roles: Option<Vec<UserRole>>,
+    pub starts_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    #[schema(value_type = Option<DateTime<Utc>>)]
+    pub ends_at: Patch<DateTime<Utc>>,
+    pub is_dismissible: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+/// Счётчик неудачных обращений к админке с одного IP и его блокировка
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct AdminIpBlock {
+    pub id: Uuid,
+    pub ip_address: String,
+    pub failures: i32,
+    pub window_started_at: DateTime<Utc>,
+    pub last_path: Option<String>,
+    pub blocked_until: Option<DateTime<Utc>>,
+    pub block_count: i32,
+    pub lifted_by: Option<Uuid>,
+    pub lifted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Состояние миграции относительно встроенных в сборку
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    Applied,
+    /// Встроена в сборку, но ещё не применена
+    Pending,
+    /// Применена, но файл миграции с тех пор изменился
+    ChecksumMismatch,
+    /// Применение завершилось ошибкой
+    Failed,
+    /// Применена в базе, но отсутствует в сборке
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub state: MigrationState,
+    pub installed_on: Option<DateTime<Utc>>,
+}
+
+/// Сверка применённых миграций со встроенными
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MigrationReport {
+    /// Последняя миграция, встроенная в сборку
+    pub embedded_version: Option<i64>,
+    /// Последняя успешно применённая миграция
+    pub applied_version: Option<i64>,
+    pub pending: usize,
+    /// Есть ли расхождение, при котором сервис не готов принимать трафик
+    pub drift: bool,
+    pub migrations: Vec<MigrationStatus>,
+}
+
+/// Набор данных ЖК в выгрузке для аналитики
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "warehouse_dataset", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WarehouseDataset {
+    Bills,
+    Payments,
+    Maintenance,
+    AccessLogs,
+}
+
+impl sqlx::postgres::PgHasArrayType for WarehouseDataset {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_warehouse_dataset")
+    }
+}
+
+impl WarehouseDataset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WarehouseDataset::Bills => "bills",
+            WarehouseDataset::Payments => "payments",
+            WarehouseDataset::Maintenance => "maintenance",
+            WarehouseDataset::AccessLogs => "access_logs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "warehouse_format", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum WarehouseFormat {
+    Csv,
+    CsvGzip,
+    /// Все колонки — необязательные строки UTF-8, сжатие GZIP
+    Parquet,
+}
+
+impl WarehouseFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            WarehouseFormat::Csv => "csv",
+            WarehouseFormat::CsvGzip => "csv.gz",
+            WarehouseFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            WarehouseFormat::Csv => "text/csv; charset=utf-8",
+            WarehouseFormat::CsvGzip => "application/gzip",
+            WarehouseFormat::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+/// Настройка ежедневной выгрузки данных ЖК в хранилище
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WarehouseExportConfig {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub s3_prefix: String,
+    pub datasets: Vec<WarehouseDataset>,
+    pub format: WarehouseFormat,
+    pub schema_version: i32,
+    pub run_hour: i32,
+    pub is_enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateWarehouseExportRequest {
+    pub complex_id: Uuid,
+    pub s3_prefix: String,
+    pub datasets: Vec<WarehouseDataset>,
+    pub format: Option<WarehouseFormat>,
+    /// По умолчанию — текущая версия схемы
+    pub schema_version: Option<i32>,
+    pub run_hour: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateWarehouseExportRequest {
+    pub s3_prefix: Option<String>,
+    pub datasets: Option<Vec<WarehouseDataset>>,
+    pub format: Option<WarehouseFormat>,
+    pub schema_version: Option<i32>,
+    pub run_hour: Option<i32>,
+    pub is_enabled: Option<bool>,
+}
+
+/// Выгрузка одних суток (UTC) по настройке
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct WarehouseExportRun {
+    pub id: Uuid,
+    pub config_id: Uuid,
+    pub export_date: NaiveDate,
+    pub schema_version: i32,
+    pub status: ExportStatus,
+    /// Записанные файлы: набор данных, ключ и число строк
+    pub files: serde_json::Value,
+    pub rows_count: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Выгрузить (или перевыгрузить) указанные сутки
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RunWarehouseExportRequest {
+    pub date: NaiveDate,
+}
@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::utils::Patch;
+
+use super::AnnouncementCategory;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "template_kind", rename_all = "snake_case")]
+pub enum TemplateKind {
+    Voting,
+    Announcement,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ContentTemplate {
+    pub id: Uuid,
+    /// NULL — системный шаблон
+    pub complex_id: Option<Uuid>,
+    pub kind: TemplateKind,
+    pub title: String,
+    pub body: String,
+    pub options: Option<serde_json::Value>,
+    pub category: Option<AnnouncementCategory>,
+    pub created_by: Option<Uuid>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTemplateRequest {
+    pub kind: TemplateKind,
+    pub title: String,
+    pub body: String,
+    pub options: Option<Vec<String>>,
+    pub category: Option<AnnouncementCategory>,
+}
+
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateTemplateRequest {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<String>>)]
+    pub options: Patch<Vec<String>>,
+    #[serde(default)]
+    #[schema(value_type = Option<AnnouncementCategory>)]
+    pub category: Patch<AnnouncementCategory>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct TemplatesQuery {
+    pub kind: Option<TemplateKind>,
+}
+
+/// Значения плейсхолдеров; `complex_name` подставляется автоматически
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct RenderTemplateRequest {
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RenderedTemplateResponse {
+    pub kind: TemplateKind,
+    pub title: String,
+    pub body: String,
+    pub options: Vec<String>,
+    pub category: Option<AnnouncementCategory>,
+    /// Плейсхолдеры, для которых не передано значение
+    pub missing: Vec<String>,
+}
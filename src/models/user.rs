@@ -4,9 +4,12 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+use crate::utils::Patch;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "user_role", rename_all = "snake_case")]
 pub enum UserRole {
+    #[default]
     User,
     Resident,
     Owner,
@@ -17,9 +20,9 @@ pub enum UserRole {
     SuperAdmin,
 }
 
-impl Default for UserRole {
-    fn default() -> Self {
-        Self::User
+impl sqlx::postgres::PgHasArrayType for UserRole {
+    fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+        sqlx::postgres::PgTypeInfo::with_name("_user_role")
     }
 }
 
@@ -27,11 +30,18 @@ impl Default for UserRole {
 pub struct User {
     pub id: Uuid,
     pub phone: String,
+    /// Код страны номера без `+`
+    pub phone_country_code: String,
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub middle_name: Option<String>,
+    /// «Имя Фамилия» или телефон, если имя не заполнено
+    pub display_name: String,
     pub email: Option<String>,
+    /// Адрес подтверждён по ссылке из письма; только с ним доступен вход по email
+    pub email_verified_at: Option<DateTime<Utc>>,
     pub avatar_url: Option<String>,
+    pub avatar_thumbnail_url: Option<String>,
     pub role: UserRole,
     pub is_verified: bool,
     pub is_blocked: bool,
@@ -49,6 +59,8 @@ pub struct UserPublic {
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub avatar_url: Option<String>,
+    /// Превью аватара 200px
+    pub avatar_thumbnail_url: Option<String>,
     pub role: UserRole,
     pub is_verified: bool,
 }
@@ -61,6 +73,7 @@ impl From<User> for UserPublic {
             first_name: user.first_name,
             last_name: user.last_name,
             avatar_url: user.avatar_url,
+            avatar_thumbnail_url: user.avatar_thumbnail_url,
             role: user.role,
             is_verified: user.is_verified,
         }
@@ -86,6 +99,26 @@ pub struct RefreshToken {
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq)]
+#[sqlx(type_name = "magic_link_purpose", rename_all = "snake_case")]
+pub enum MagicLinkPurpose {
+    Login,
+    VerifyEmail,
+}
+
+/// Одноразовая ссылка из письма
+#[derive(Debug, Clone, FromRow)]
+pub struct MagicLink {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: MagicLinkPurpose,
+    pub email: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, FromRow)]
 pub struct SmsCode {
     pub id: Uuid,
@@ -97,10 +130,22 @@ pub struct SmsCode {
     pub created_at: DateTime<Utc>,
 }
 
+/// Канал доставки кода входа
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeChannel {
+    #[default]
+    Sms,
+    Whatsapp,
+}
+
 // DTOs
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct SendCodeRequest {
     pub phone: String,
+    /// WhatsApp по желанию пользователя; если не доставлено — код уходит по SMS
+    #[serde(default)]
+    pub channel: CodeChannel,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -110,12 +155,32 @@ pub struct VerifyCodeRequest {
     pub device_info: Option<String>,
 }
 
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SendMagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MagicLinkLoginRequest {
+    /// Токен из ссылки в письме
+    pub token: String,
+    pub device_info: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ConfirmEmailRequest {
+    /// Токен из ссылки в письме
+    pub token: String,
+}
+
 #[derive(Debug, Serialize, ToSchema)]
 pub struct AuthResponse {
     pub access_token: String,
     pub refresh_token: String,
     pub user: UserPublic,
     pub is_new_user: bool,
+    /// Нужно принять действующие версии документов, иначе разделы с данными недоступны
+    pub consent_required: bool,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -129,10 +194,19 @@ pub struct TokenResponse {
     pub refresh_token: String,
 }
 
+/// Поля `Patch`: не переданы — без изменений, `null` — очистить
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateUserRequest {
-    pub first_name: Option<String>,
-    pub last_name: Option<String>,
-    pub middle_name: Option<String>,
-    pub email: Option<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub first_name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub last_name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub middle_name: Patch<String>,
+    #[serde(default)]
+    #[schema(value_type = Option<String>)]
+    pub email: Patch<String>,
 }
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+pub enum ViewEntity {
+    Announcement,
+    Listing,
+}
+
+impl ViewEntity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewEntity::Announcement => "announcement",
+            ViewEntity::Listing => "listing",
+        }
+    }
+}
+
+/// Статистика просмотров, доступна только автору
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ViewStats {
+    pub unique_views: i32,
+    pub total_views: i32,
+}
@@ -5,35 +5,53 @@ use sqlx::FromRow;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "voting_type", rename_all = "snake_case")]
 pub enum VotingType {
+    #[default]
     SingleChoice,
     MultipleChoice,
     YesNo,
+    /// Выборы председателя: кандидаты регистрируются сами до начала голосования
+    Election,
+    /// Рейтинговое: варианты упорядочиваются, итоги — мгновенный второй тур
+    Ranked,
 }
 
-impl Default for VotingType {
-    fn default() -> Self {
-        Self::SingleChoice
+impl VotingType {
+    /// Форма голосования для протокола
+    pub fn title(&self) -> &'static str {
+        match self {
+            VotingType::SingleChoice => "Выбор одного варианта",
+            VotingType::MultipleChoice => "Выбор нескольких вариантов",
+            VotingType::YesNo => "За / против",
+            VotingType::Election => "Выборы председателя",
+            VotingType::Ranked => "Рейтинговое голосование",
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema)]
+#[sqlx(type_name = "election_outcome", rename_all = "snake_case")]
+pub enum ElectionOutcome {
+    Elected,
+    /// Несколько кандидатов набрали одинаковый вес — нужны повторные выборы
+    Tie,
+    NoCandidates,
+    NoVotes,
+    NoQuorum,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Eq, ToSchema, Default)]
 #[sqlx(type_name = "voting_status", rename_all = "snake_case")]
 pub enum VotingStatus {
+    #[default]
     Draft,
     Active,
     Closed,
     Cancelled,
 }
 
-impl Default for VotingStatus {
-    fn default() -> Self {
-        Self::Draft
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Voting {
     pub id: Uuid,
@@ -50,6 +68,21 @@ pub struct Voting {
     pub created_by: Uuid,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Окончание выдвижения кандидатов (только для выборов)
+    pub nomination_ends_at: Option<DateTime<Utc>>,
+    pub winner_option_id: Option<Uuid>,
+    pub election_outcome: Option<ElectionOutcome>,
+    /// Исходное голосование, если это повторное
+    pub repeat_of_id: Option<Uuid>,
+    /// Итоги, поддерживаемые триггером на votes
+    pub votes_count: i32,
+    pub votes_weight: Decimal,
+    /// Площадь квартир собственников на момент закрытия
+    pub total_weight: Option<Decimal>,
+    pub quorum_reached: Option<bool>,
+    /// Решение принято: кворум есть и вариант набрал больше половины поданного веса
+    pub passed: Option<bool>,
+    pub closed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -59,6 +92,11 @@ pub struct VotingOption {
     pub text: String,
     pub sort_order: i32,
     pub created_at: DateTime<Utc>,
+    pub candidate_id: Option<Uuid>,
+    pub candidate_bio: Option<String>,
+    pub candidate_photo_url: Option<String>,
+    pub votes_count: i32,
+    pub votes_weight: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -70,6 +108,9 @@ pub struct Vote {
     pub apartment_id: Option<Uuid>,
     pub vote_weight: Decimal,
     pub created_at: DateTime<Utc>,
+    /// Проголосовавший по доверенности за собственника `user_id`
+    pub proxy_id: Option<Uuid>,
+    pub delegation_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -86,8 +127,20 @@ pub struct VotingResponse {
     pub options: Vec<VotingOptionResponse>,
     pub total_votes: i32,
     pub total_weight: Decimal,
+    /// Площадь собственников ЖК, от которой считается кворум (после закрытия)
+    pub eligible_weight: Option<Decimal>,
+    pub quorum_reached: Option<bool>,
+    pub passed: Option<bool>,
+    pub closed_at: Option<DateTime<Utc>>,
     pub user_voted: bool,
     pub created_at: DateTime<Utc>,
+    pub nomination_ends_at: Option<DateTime<Utc>>,
+    pub winner_option_id: Option<Uuid>,
+    pub election_outcome: Option<ElectionOutcome>,
+    /// Исходное голосование, не набравшее кворум
+    pub repeat_of_id: Option<Uuid>,
+    /// Повторное голосование, назначенное вместо этого
+    pub repeat_voting_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -97,10 +150,25 @@ pub struct VotingOptionResponse {
     pub votes_count: i32,
     pub votes_weight: Decimal,
     pub percentage: f64,
+    /// Вариант победил по итогам закрытого голосования
+    pub is_winner: bool,
+    /// Профиль кандидата (для выборов)
+    pub candidate: Option<CandidateProfile>,
+    /// Тур, в котором вариант выбыл (для рейтингового голосования)
+    pub eliminated_in_round: Option<i32>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CandidateProfile {
+    pub user_id: Uuid,
+    pub bio: Option<String>,
+    pub photo_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateVotingRequest {
+    /// ЖК; можно не указывать, если он у пользователя один
+    pub complex_id: Option<Uuid>,
     pub title: String,
     pub description: Option<String>,
     pub voting_type: Option<VotingType>,
@@ -108,12 +176,81 @@ pub struct CreateVotingRequest {
     pub quorum_percent: Option<i32>,
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
+    /// Для выборов варианты не передаются — кандидаты регистрируются сами
+    #[serde(default)]
     pub options: Vec<String>,
+    /// Окончание выдвижения кандидатов, обязательно для выборов
+    pub nomination_ends_at: Option<DateTime<Utc>>,
+}
+
+/// Параметры повторного голосования; не указанные поля берутся из исходного
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RepeatVotingRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Сниженный кворум, меньше исходного
+    pub quorum_percent: i32,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RegisterCandidateRequest {
+    pub bio: Option<String>,
+    pub photo_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct CastVoteRequest {
-    pub option_id: Uuid,
+    /// Единственный выбранный вариант
+    pub option_id: Option<Uuid>,
+    /// Несколько вариантов; для рейтингового голосования — в порядке предпочтения
+    #[serde(default)]
+    pub option_ids: Vec<Uuid>,
+    /// Собственник, за которого голосует доверенное лицо
+    pub on_behalf_of: Option<Uuid>,
+}
+
+/// Квартира собственника в голосовании: бюллетень подаётся за каждую отдельно
+#[derive(Debug, Clone, Serialize, FromRow, ToSchema)]
+pub struct VotingApartment {
+    pub apartment_id: Uuid,
+    pub number: String,
+    pub building: Option<String>,
+    /// Вес бюллетеня — площадь квартиры
+    pub vote_weight: Decimal,
+    pub voted: bool,
+}
+
+/// Доверенность собственника на голосование
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct VoteDelegation {
+    pub id: Uuid,
+    pub complex_id: Uuid,
+    pub principal_id: Uuid,
+    pub proxy_id: Uuid,
+    /// Конкретное голосование; без него доверенность действует на период
+    pub voting_id: Option<Uuid>,
+    pub valid_from: DateTime<Utc>,
+    pub valid_until: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Выдать доверенность: на голосование (`voting_id`) или на период
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateVoteDelegationRequest {
+    pub proxy_id: Uuid,
+    pub voting_id: Option<Uuid>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub valid_until: Option<DateTime<Utc>>,
+}
+
+/// Доверенности пользователя: выданные им и полученные от соседей
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VoteDelegationsResponse {
+    pub given: Vec<VoteDelegation>,
+    pub received: Vec<VoteDelegation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
@@ -124,3 +261,27 @@ pub struct VotingDocument {
     pub file_url: String,
     pub created_at: DateTime<Utc>,
 }
+
+/// Подписанный протокол закрытого голосования
+#[derive(Debug, Clone, FromRow)]
+pub struct VotingProtocol {
+    pub voting_id: Uuid,
+    pub protocol_number: String,
+    pub signature: String,
+    pub verification_token: String,
+    pub signed_by: Uuid,
+    pub signed_at: DateTime<Utc>,
+    pub pdf_key: Option<String>,
+}
+
+/// Сведения о протоколе для проверки по коду из документа
+#[derive(Debug, Serialize, FromRow, ToSchema)]
+pub struct VotingProtocolVerification {
+    pub protocol_number: String,
+    pub voting_title: String,
+    pub complex_name: String,
+    pub signed_by_name: String,
+    pub signed_at: DateTime<Utc>,
+    /// Совпадает с подписью, напечатанной в протоколе
+    pub signature: String,
+}
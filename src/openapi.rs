@@ -1,3 +1,8 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde_json::Value;
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
 use utoipa::OpenApi;
 
 #[derive(OpenApi)]
@@ -28,7 +33,16 @@ use utoipa::OpenApi;
         (name = "communal", description = "Коммунальные услуги: счётчики, счета, оплата"),
         (name = "Чаты", description = "Чаты и сообщения между соседями"),
         (name = "Уведомления", description = "Уведомления пользователя"),
-        (name = "Заявки на обслуживание", description = "Заявки на ремонт и обслуживание")
+        (name = "Заявки на обслуживание", description = "Заявки на ремонт и обслуживание"),
+        (name = "bookmarks", description = "Закладки: объявления, документы, голосования"),
+        (name = "bootstrap", description = "Стартовые данные приложения"),
+        (name = "audit", description = "Журнал аудита привилегированных действий"),
+        (name = "permissions", description = "Роли и права пользователей в ЖК"),
+        (name = "files", description = "Прямая загрузка файлов в хранилище"),
+        (name = "legal", description = "Пользовательское соглашение и согласия на обработку данных"),
+        (name = "surveys", description = "Опросы жителей ЖК"),
+        (name = "devices", description = "Вызовы от оборудования: шлагбаумы и панели домофонов"),
+        (name = "moderation", description = "Модерация контента для модераторов платформы")
     ),
     paths(
         // Auth
@@ -36,12 +50,16 @@ use utoipa::OpenApi;
         crate::api::auth::verify_code,
         crate::api::auth::refresh_token,
         crate::api::auth::logout,
+        crate::api::auth::send_magic_link,
+        crate::api::auth::verify_magic_link,
+        crate::api::auth::confirm_email,
         // Cities
         crate::api::cities::list_cities,
         // Users
         crate::api::users::get_me,
         crate::api::users::update_me,
         crate::api::users::upload_avatar,
+        crate::api::users::send_email_verification,
         crate::api::users::get_my_apartments,
         // Complexes
         crate::api::complexes::search_complexes,
@@ -49,9 +67,33 @@ use utoipa::OpenApi;
         crate::api::complexes::check_complex_exists,
         crate::api::complexes::create_complex,
         crate::api::complexes::join_complex,
+        crate::api::complexes::upload_complex_photo,
+        crate::api::complexes::get_verification,
+        crate::api::complexes::upload_verification_evidence,
+        crate::api::complexes::resubmit_verification,
+        crate::api::complexes::reset_sandbox,
+        crate::api::templates::list_templates,
+        crate::api::templates::create_template,
+        crate::api::templates::update_template,
+        crate::api::templates::delete_template,
+        crate::api::templates::render,
+        crate::api::communications::create_export,
+        crate::api::communications::list_exports,
+        crate::api::communications::get_export,
+        crate::api::communications::download_export,
         // Apartments
         crate::api::apartments::get_join_requests,
         crate::api::apartments::review_join_request,
+        crate::api::apartments::resubmit_join_request,
+        crate::api::apartments::list_family_members,
+        crate::api::apartments::add_family_member,
+        crate::api::apartments::update_family_member,
+        crate::api::apartments::remove_family_member,
+        crate::api::apartments::invite_family_member,
+        crate::api::apartments::accept_family_invitation,
+        crate::api::apartments::get_move_out,
+        crate::api::apartments::schedule_move_out,
+        crate::api::apartments::cancel_move_out,
         // OSI
         crate::api::osi::get_osi,
         crate::api::osi::get_osi_by_id,
@@ -65,25 +107,91 @@ use utoipa::OpenApi;
         crate::api::osi::remove_worker,
         crate::api::osi::get_documents,
         crate::api::osi::add_document,
+        crate::api::osi::get_chairman_actions,
+        crate::api::osi::get_inbox,
+        // OSI finance
+        crate::api::osi_finance::list_expense_categories,
+        crate::api::osi_finance::create_expense_category,
+        crate::api::osi_finance::update_expense_category,
+        crate::api::osi_finance::get_finance_summary,
+        crate::api::osi_finance::get_monthly_report,
+        crate::api::osi_finance::list_expenses,
+        crate::api::osi_finance::get_expense,
+        crate::api::osi_finance::create_expense,
+        crate::api::osi_finance::intake_invoice,
+        crate::api::osi_finance::confirm_expense,
+        crate::api::osi_finance::decide_expense,
+        crate::api::osi_finance::execute_expense,
+        crate::api::osi_finance::create_shared_charge,
+        crate::api::osi_finance::list_shared_charges,
+        crate::api::osi_finance::get_shared_charge,
+        crate::api::osi_finance::cancel_shared_charge,
+        crate::api::osi_finance::create_budget,
+        crate::api::osi_finance::list_budgets,
+        crate::api::osi_finance::get_budget,
+        crate::api::osi_finance::update_budget_lines,
+        crate::api::osi_finance::submit_budget,
+        crate::api::osi_finance::register_cash_payment,
+        crate::api::osi_finance::list_cash_payments,
+        crate::api::osi_finance::get_finance_settings,
+        crate::api::osi_finance::update_finance_settings,
+        crate::api::osi_finance::get_sms_usage,
         // Security
         crate::api::security::open_barrier,
         crate::api::security::create_guest_access,
         crate::api::security::get_active_guests,
         crate::api::security::cancel_guest_access,
+        crate::api::security::get_guest_approval_policy,
+        crate::api::security::update_guest_approval_policy,
+        crate::api::security::list_guest_approvals,
+        crate::api::security::approve_guest_access,
+        crate::api::security::decline_guest_access,
+        crate::api::security::list_security_events,
+        crate::api::security::review_security_event,
+        crate::api::security::get_anomaly_settings,
+        crate::api::security::update_anomaly_settings,
+        crate::api::security::get_device_binding_settings,
+        crate::api::security::update_device_binding_settings,
         crate::api::security::get_barrier_history,
         crate::api::security::process_entry,
         crate::api::security::process_exit,
         crate::api::security::get_cameras,
+        crate::api::security::process_anpr_entry,
+        crate::api::security::list_my_vehicles,
+        crate::api::security::add_vehicle,
+        crate::api::security::remove_vehicle,
         crate::api::security::get_camera_stream,
+        crate::api::security::proxy_camera_stream,
         crate::api::security::open_intercom,
         crate::api::security::get_intercom_calls,
+        crate::api::security::intercom_ring,
+        crate::api::security::intercom_code,
+        crate::api::security::get_apartment_code,
+        crate::api::security::rotate_apartment_code,
+        crate::api::security::reset_apartment_code,
+        crate::api::security::answer_intercom_call,
+        crate::api::security::reject_intercom_call,
+        crate::api::security::open_intercom_call,
+        crate::api::security::get_apartment_snapshots,
+        crate::api::security::delete_snapshot,
+        crate::api::security::list_blacklist,
+        crate::api::security::add_to_blacklist,
+        crate::api::security::remove_from_blacklist,
+        crate::api::security::list_blacklist_denials,
+        crate::api::security::guard_validate_code,
+        crate::api::security::guard_manual_open,
+        crate::api::security::guard_expected_guests,
         // Announcements
         crate::api::announcements::list_announcements,
         crate::api::announcements::get_announcement,
         crate::api::announcements::create_announcement,
         crate::api::announcements::update_announcement,
         crate::api::announcements::delete_announcement,
+        crate::api::announcements::upload_attachments,
+        crate::api::announcements::delete_attachment,
         crate::api::announcements::mark_as_read,
+        crate::api::announcements::mark_many_as_read,
+        crate::api::announcements::mark_all_as_read,
         // Marketplace
         crate::api::marketplace::get_categories,
         crate::api::marketplace::list_listings,
@@ -95,12 +203,30 @@ use utoipa::OpenApi;
         crate::api::marketplace::send_message,
         crate::api::marketplace::my_listings,
         crate::api::marketplace::my_favorites,
+        crate::api::marketplace::reserve_listing,
+        crate::api::marketplace::cancel_reservation,
+        crate::api::marketplace::sell_listing,
+        crate::api::marketplace::confirm_receipt,
+        crate::api::marketplace::my_purchases,
+        crate::api::marketplace::report_listing,
+        crate::api::marketplace::list_reports,
+        crate::api::marketplace::resolve_report,
         // Voting
         crate::api::voting::list_votings,
         crate::api::voting::get_voting,
         crate::api::voting::create_voting,
         crate::api::voting::cast_vote,
+        crate::api::voting::list_voting_apartments,
+        crate::api::voting::cast_apartment_vote,
+        crate::api::voting::register_candidate,
+        crate::api::voting::activate_voting,
         crate::api::voting::close_voting,
+        crate::api::voting::repeat_voting,
+        crate::api::voting::get_voting_protocol,
+        crate::api::voting::verify_voting_protocol,
+        crate::api::voting::create_delegation,
+        crate::api::voting::list_delegations,
+        crate::api::voting::revoke_delegation,
         // Communal
         crate::api::communal::get_meters,
         crate::api::communal::submit_reading,
@@ -109,35 +235,106 @@ use utoipa::OpenApi;
         crate::api::communal::get_bill,
         crate::api::communal::create_payment,
         crate::api::communal::get_payment,
+        crate::api::communal::get_payment_receipt,
+        crate::api::communal::payment_webhook,
+        crate::api::communal::verify_receipt,
+        crate::api::communal::get_tariffs,
+        crate::api::communal::create_tariff,
+        crate::api::communal::update_tariff,
+        crate::api::communal::delete_tariff,
+        crate::api::communal::generate_bills,
         // Chat
         crate::api::chat::list_chats,
         crate::api::chat::create_private_chat,
         crate::api::chat::get_messages,
+        crate::api::chat::get_media,
         crate::api::chat::send_message,
         crate::api::chat::mark_chat_as_read,
+        crate::api::chat::schedule_message,
+        crate::api::chat::list_scheduled_messages,
+        crate::api::chat::cancel_scheduled_message,
         // Notifications
         crate::api::notifications::list_notifications,
         crate::api::notifications::mark_as_read,
         crate::api::notifications::mark_all_as_read,
         crate::api::notifications::register_push_token,
         crate::api::notifications::get_unread_count,
+        crate::api::notifications::get_settings,
+        crate::api::notifications::update_settings,
+        crate::api::notifications::stream_notifications,
+        crate::api::notifications::get_telegram_link,
+        crate::api::notifications::create_telegram_link,
+        crate::api::notifications::delete_telegram_link,
+        crate::api::telegram::telegram_webhook,
+        crate::api::sms::sms_status_callback,
         // Maintenance
         crate::api::maintenance::list_requests,
         crate::api::maintenance::get_request,
         crate::api::maintenance::create_request,
         crate::api::maintenance::update_status,
+        crate::api::maintenance::assign_request,
+        crate::api::maintenance::list_assigned_to_me,
+        crate::api::maintenance::get_costs,
+        crate::api::maintenance::update_estimate,
+        crate::api::maintenance::add_cost_item,
+        crate::api::maintenance::update_cost_item,
+        crate::api::maintenance::delete_cost_item,
         crate::api::maintenance::rate_request,
         crate::api::maintenance::get_comments,
         crate::api::maintenance::add_comment,
+        crate::api::maintenance::upload_photos,
+        crate::api::maintenance::list_planned,
+        crate::api::maintenance::list_schedules,
+        crate::api::maintenance::create_schedule,
+        crate::api::maintenance::update_schedule,
+        crate::api::maintenance::delete_schedule,
+        // Bookmarks
+        crate::api::bookmarks::list_bookmarks,
+        crate::api::bookmarks::toggle_bookmark,
+        // Bootstrap
+        crate::api::bootstrap::get_bootstrap,
+        crate::api::bootstrap::list_banners,
+        crate::api::bootstrap::dismiss_banner,
+        // Audit
+        crate::api::audit::list_audit_logs,
+        crate::api::audit::list_field_history,
+        // Permissions
+        crate::api::permissions::get_my_permissions,
+        crate::api::permissions::get_complex_permissions,
+        crate::api::permissions::assign_complex_role,
+        crate::api::permissions::revoke_complex_role,
+        crate::api::permissions::grant_permission,
+        crate::api::permissions::revoke_permission,
+        // Files
+        crate::api::files::presign_upload,
+        crate::api::files::confirm_upload,
+        crate::api::files::download_local_file,
+        crate::api::files::upload_local_file,
+        // Legal
+        crate::api::legal::list_documents,
+        crate::api::legal::get_consent_status,
+        crate::api::legal::accept_consents,
+        // Surveys
+        crate::api::surveys::list_surveys,
+        crate::api::surveys::create_survey,
+        crate::api::surveys::get_survey,
+        crate::api::surveys::submit_survey,
+        crate::api::surveys::get_survey_results,
+        crate::api::surveys::export_survey,
+        crate::api::surveys::close_survey,
     ),
     components(
         schemas(
             // Auth
             crate::models::SendCodeRequest,
+            crate::models::CodeChannel,
             crate::models::VerifyCodeRequest,
             crate::models::AuthResponse,
             crate::models::RefreshTokenRequest,
             crate::models::TokenResponse,
+            crate::models::SendMagicLinkRequest,
+            crate::models::MagicLinkLoginRequest,
+            crate::models::ConfirmEmailRequest,
             crate::models::UserPublic,
             crate::models::UserRole,
             crate::models::UpdateUserRequest,
@@ -145,6 +342,7 @@ use utoipa::OpenApi;
             crate::api::auth::LogoutResponse,
             // Users
             crate::api::users::AvatarUploadResponse,
+            crate::api::users::EmailVerificationResponse,
             // Cities
             crate::models::CityResponse,
             // Complexes
@@ -156,12 +354,38 @@ use utoipa::OpenApi;
             crate::models::JoinComplexRequest,
             crate::api::complexes::ComplexExistsResponse,
             crate::api::complexes::JoinComplexResponse,
+            crate::api::complexes::SandboxResetResponse,
+            crate::models::ComplexVerificationStatus,
+            crate::models::VerificationEvidenceType,
+            crate::models::VerificationCheckItem,
+            crate::models::VerificationChecklistEntry,
+            crate::models::ComplexVerificationEvidence,
+            crate::models::ComplexVerificationResponse,
+            crate::models::TemplateKind,
+            crate::models::ContentTemplate,
+            crate::models::CreateTemplateRequest,
+            crate::models::UpdateTemplateRequest,
+            crate::models::RenderTemplateRequest,
+            crate::models::RenderedTemplateResponse,
+            crate::api::templates::TemplateSuccessResponse,
             // Apartments
             crate::models::ApartmentResponse,
             crate::models::JoinRequestStatus,
+            crate::models::JoinRequest,
             crate::models::JoinRequestResponse,
             crate::models::ReviewJoinRequestRequest,
             crate::api::apartments::ReviewResponse,
+            crate::models::FamilyRelation,
+            crate::models::FamilyMember,
+            crate::models::CreateFamilyMemberRequest,
+            crate::models::UpdateFamilyMemberRequest,
+            crate::models::InviteFamilyMemberRequest,
+            crate::models::AcceptFamilyInvitationRequest,
+            crate::models::MoveOutStatus,
+            crate::models::MoveOut,
+            crate::models::CreateMoveOutRequest,
+            crate::models::MoveOutMeter,
+            crate::models::MoveOutChecklist,
             // OSI
             crate::models::OsiResponse,
             crate::models::ChairmanInfo,
@@ -174,30 +398,117 @@ use utoipa::OpenApi;
             crate::models::CreateWorkerRequest,
             crate::models::DocumentType,
             crate::models::OsiDocumentResponse,
+            crate::models::OsiInboxResponse,
+            crate::models::InboxSection,
+            crate::models::InboxItem,
+            crate::models::InboxItemKind,
+            crate::models::InboxLink,
             crate::api::osi::AddCouncilMemberResponse,
             crate::api::osi::SuccessResponse,
             crate::api::osi::AddDocumentResponse,
             crate::api::osi::AddDocumentRequest,
+            crate::models::DomainEventType,
+            crate::models::DomainEventResponse,
+            crate::models::DomainEventsQuery,
+            // OSI finance
+            crate::models::ExpenseCategory,
+            crate::models::CreateExpenseCategoryRequest,
+            crate::models::UpdateExpenseCategoryRequest,
+            crate::models::FinanceSummary,
+            crate::models::MonthFinanceSummary,
+            crate::models::CategoryFinanceSummary,
+            crate::models::MonthlyReport,
+            crate::models::ReportExpenseCategory,
+            crate::models::ReportMaintenanceCosts,
+            crate::models::ExpenseStatus,
+            crate::models::ExpenseDecision,
+            crate::models::ExpenseResponse,
+            crate::models::ExpenseApprovalResponse,
+            crate::models::CreateExpenseRequest,
+            crate::models::ExpenseDecisionRequest,
+            crate::models::ConfirmExpenseRequest,
+            crate::models::RecognizedInvoice,
+            crate::models::InvoiceIntakeResponse,
+            crate::models::ExpensesQuery,
+            crate::models::FinanceSettings,
+            crate::models::SmsUsageQuery,
+            crate::models::SmsKindUsage,
+            crate::models::SmsUsageReport,
+            crate::models::AllocationRule,
+            crate::models::SharedChargeStatus,
+            crate::models::SharedCharge,
+            crate::models::SharedChargeAllocationResponse,
+            crate::models::SharedChargeResponse,
+            crate::models::CreateSharedChargeRequest,
+            crate::models::BudgetStatus,
+            crate::models::OsiBudget,
+            crate::models::BudgetLineResponse,
+            crate::models::BudgetResponse,
+            crate::models::BudgetLineRequest,
+            crate::models::CreateBudgetRequest,
+            crate::models::UpdateBudgetLinesRequest,
+            crate::models::SubmitBudgetRequest,
+            crate::models::PaymentAllocation,
+            crate::models::RegisterCashPaymentRequest,
+            crate::models::CashReceiptResponse,
+            crate::models::CashPaymentsQuery,
+            crate::models::ReceiptVerificationResponse,
             // Security
             crate::models::GuestAccessStatus,
+            crate::models::GuestNotificationStatus,
             crate::models::GuestAccessResponse,
             crate::models::CreateGuestAccessRequest,
             crate::models::BarrierAction,
             crate::models::BarrierAccessLogResponse,
+            crate::models::BarrierOpenLocation,
             crate::models::BarrierEntryRequest,
             crate::models::CameraResponse,
+            crate::models::ResidentVehicle,
+            crate::models::CreateResidentVehicleRequest,
+            crate::models::AnprEntryRequest,
+            crate::models::AnprMatch,
+            crate::models::AnprEntryResponse,
             crate::models::CameraStreamResponse,
+            crate::models::StreamTokenQuery,
             crate::models::IntercomCallStatus,
             crate::models::IntercomCallResponse,
+            crate::models::IntercomCall,
+            crate::models::IntercomRingRequest,
+            crate::models::ApartmentIntercomCode,
+            crate::models::IntercomCodeRequest,
+            crate::models::IntercomCodeResponse,
+            crate::models::IntercomSnapshotResponse,
+            crate::models::GuardValidateCodeRequest,
+            crate::models::GuardValidateCodeResponse,
+            crate::models::ExpectedGuestResponse,
+            crate::models::GuardManualOpenRequest,
+            crate::models::GuestApprovalPolicy,
+            crate::models::GuestApprovalsQuery,
+            crate::models::DeclineGuestAccessRequest,
+            crate::models::SecurityEventType,
+            crate::models::SecurityEvent,
+            crate::models::SecurityEventsQuery,
+            crate::models::ReviewSecurityEventRequest,
+            crate::models::AnomalySettings,
+            crate::models::DeviceBindingSettings,
+            crate::models::BarrierManualOpening,
+            crate::models::BlacklistEntry,
+            crate::models::CreateBlacklistEntryRequest,
+            crate::models::BarrierDenialResponse,
             crate::api::security::SuccessResponse,
             crate::api::security::OpenIntercomRequest,
+            crate::api::security::OpenBarrierRequest,
             // Announcements
             crate::models::AnnouncementCategory,
             crate::models::AnnouncementPriority,
             crate::models::AnnouncementResponse,
+            crate::models::AnnouncementAttachment,
+            crate::models::ViewStats,
             crate::models::CreateAnnouncementRequest,
             crate::models::UpdateAnnouncementRequest,
+            crate::models::MarkAnnouncementsReadRequest,
             crate::api::announcements::SuccessResponse,
+            crate::api::announcements::MarkReadResponse,
             // Marketplace
             crate::models::CategoryResponse,
             crate::models::ListingResponse,
@@ -206,16 +517,48 @@ use utoipa::OpenApi;
             crate::models::CreateListingRequest,
             crate::models::UpdateListingRequest,
             crate::models::ListingsQuery,
+            crate::models::ListingSort,
             crate::models::SendMessageRequest,
+            crate::models::ListingSale,
+            crate::models::ReserveListingRequest,
+            crate::models::SellListingRequest,
+            crate::models::ConfirmReceiptRequest,
+            crate::models::ListingReport,
+            crate::models::ListingReportReason,
+            crate::models::ListingReportStatus,
+            crate::models::ListingReportEntry,
+            crate::models::ReportListingRequest,
+            crate::models::ModerationAction,
+            crate::models::ResolveListingReportRequest,
             crate::api::marketplace::FavoriteResponse,
             crate::api::marketplace::SuccessResponse,
+            crate::api::marketplace::ListingMessageResponse,
             // Voting
+            crate::models::PaginatedAnnouncements,
+            crate::models::PaginatedBills,
+            crate::models::PaginatedListings,
+            crate::models::PaginatedListingReports,
+            crate::models::CursorQuery,
+            crate::models::ChatMessagesPage,
+            crate::models::BarrierHistoryPage,
+            crate::models::IntercomSnapshotsPage,
+            crate::models::NotificationsPage,
+            crate::models::SecurityEventsPage,
             crate::models::VotingType,
             crate::models::VotingStatus,
             crate::models::VotingResponse,
             crate::models::VotingOptionResponse,
             crate::models::CreateVotingRequest,
+            crate::models::ElectionOutcome,
+            crate::models::CandidateProfile,
+            crate::models::RegisterCandidateRequest,
+            crate::models::RepeatVotingRequest,
             crate::models::CastVoteRequest,
+            crate::models::VotingApartment,
+            crate::models::VotingProtocolVerification,
+            crate::models::VoteDelegation,
+            crate::models::CreateVoteDelegationRequest,
+            crate::models::VoteDelegationsResponse,
             crate::api::voting::SuccessResponse,
             crate::api::voting::VoteResponse,
             crate::api::voting::VotingsQuery,
@@ -226,12 +569,19 @@ use utoipa::OpenApi;
             crate::models::BillResponse,
             crate::models::BillItemResponse,
             crate::models::BillStatus,
+            crate::models::Tariff,
+            crate::models::TariffBasis,
+            crate::models::CreateTariffRequest,
+            crate::models::UpdateTariffRequest,
+            crate::models::GenerateTariffBillsRequest,
+            crate::models::TariffBillingResult,
             crate::models::CreatePaymentRequest,
             crate::models::PaymentResponse,
             crate::models::PaymentStatus,
             crate::models::PaymentMethod,
             crate::api::communal::SubmitReadingResponse,
             crate::api::communal::BillsQuery,
+            crate::api::communal::CommunalSuccessResponse,
             // Chat
             crate::models::ChatResponse,
             crate::models::ChatType,
@@ -241,15 +591,31 @@ use utoipa::OpenApi;
             crate::models::CreatePrivateChatRequest,
             crate::models::SendChatMessageRequest,
             crate::models::MessagesQuery,
+            crate::models::MediaKind,
+            crate::models::ChatMediaItem,
+            crate::models::ScheduledMessageStatus,
+            crate::models::ScheduledChatMessage,
+            crate::models::ScheduleChatMessageRequest,
+            crate::models::ChatMediaQuery,
+            crate::models::ChatMediaPage,
             crate::api::chat::ChatSuccessResponse,
             // Notifications
             crate::models::NotificationResponse,
             crate::models::NotificationType,
             crate::models::NotificationsQuery,
             crate::models::RegisterPushTokenRequest,
+            crate::models::NotificationSetting,
+            crate::models::NotificationSettingUpdate,
+            crate::models::UpdateNotificationSettingsRequest,
+            crate::models::TelegramLinkCode,
+            crate::models::TelegramLinkStatus,
             crate::api::notifications::NotificationSuccessResponse,
             crate::api::notifications::MarkAllReadResponse,
             crate::api::notifications::UnreadCountResponse,
+            crate::models::CommunicationExport,
+            crate::models::CreateCommunicationExportRequest,
+            crate::models::ExportFormat,
+            crate::models::ExportStatus,
             // Maintenance
             crate::models::MaintenanceRequestResponse,
             crate::models::MaintenancePhotoResponse,
@@ -258,11 +624,77 @@ use utoipa::OpenApi;
             crate::models::MaintenanceStatus,
             crate::models::CreateMaintenanceRequest,
             crate::models::UpdateMaintenanceStatusRequest,
+            crate::models::AssignMaintenanceRequest,
+            crate::models::MaintenanceCostKind,
+            crate::models::MaintenanceCostItem,
+            crate::models::MaintenanceCostItemRequest,
+            crate::models::UpdateMaintenanceEstimateRequest,
+            crate::models::MaintenanceCostsResponse,
             crate::models::RateMaintenanceRequest,
             crate::models::AddMaintenanceCommentRequest,
+            crate::models::MaintenanceFrequency,
+            crate::models::MaintenanceSchedule,
+            crate::models::CreateMaintenanceScheduleRequest,
+            crate::models::UpdateMaintenanceScheduleRequest,
+            crate::models::PlannedMaintenanceResponse,
             crate::api::maintenance::MaintenanceSuccessResponse,
             crate::api::maintenance::CommentCreatedResponse,
             crate::api::maintenance::CommentResponse,
+            // Bookmarks
+            crate::models::BookmarkEntityType,
+            crate::models::BookmarkResponse,
+            crate::models::BookmarksQuery,
+            crate::models::ToggleBookmarkRequest,
+            crate::models::ToggleBookmarkResponse,
+            // Bootstrap
+            crate::models::BootstrapResponse,
+            crate::models::GlobalBanner,
+            crate::models::BannerSeverity,
+            crate::models::BootstrapBanner,
+            // Audit
+            crate::models::AuditLogResponse,
+            crate::models::AuditLogsQuery,
+            crate::models::FieldChange,
+            crate::models::FieldHistoryQuery,
+            // Permissions
+            crate::models::Permission,
+            crate::models::ComplexRole,
+            crate::models::PermissionGrant,
+            crate::models::ComplexPermissionsResponse,
+            crate::models::AssignComplexRoleRequest,
+            crate::models::GrantPermissionRequest,
+            crate::api::permissions::PermissionSuccessResponse,
+            // Files
+            crate::models::UploadPurpose,
+            crate::models::FileUploadStatus,
+            crate::models::PresignUploadRequest,
+            crate::models::PresignUploadResponse,
+            crate::models::ConfirmUploadRequest,
+            crate::models::ConfirmUploadResponse,
+            crate::models::PhotoVariants,
+            crate::models::ComplexPhotoUploadResponse,
+            // Legal
+            crate::models::LegalDocumentKind,
+            crate::models::LegalDocument,
+            crate::models::AcceptConsentsRequest,
+            crate::models::ConsentAcceptance,
+            crate::models::ConsentStatusResponse,
+            // Surveys
+            crate::models::SurveyStatus,
+            crate::models::SurveyQuestionType,
+            crate::models::Survey,
+            crate::models::SurveyQuestion,
+            crate::models::CreateSurveyQuestion,
+            crate::models::CreateSurveyRequest,
+            crate::models::SurveyResponse,
+            crate::models::SurveyAnswerInput,
+            crate::models::SubmitSurveyRequest,
+            crate::models::SurveyOptionResult,
+            crate::models::SurveyScaleResult,
+            crate::models::SurveyBuildingResult,
+            crate::models::SurveyQuestionResult,
+            crate::models::SurveyResults,
+            crate::api::surveys::SurveySuccessResponse,
         )
     ),
     modifiers(&SecurityAddon)
@@ -276,12 +708,152 @@ impl utoipa::Modify for SecurityAddon {
         if let Some(components) = openapi.components.as_mut() {
             components.add_security_scheme(
                 "bearer_auth",
-                utoipa::openapi::security::SecurityScheme::Http(
-                    utoipa::openapi::security::Http::new(
-                        utoipa::openapi::security::HttpAuthScheme::Bearer,
-                    ),
-                ),
+                SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+            );
+            components.add_security_scheme(
+                "barrier_key",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Barrier-Key"))),
+            );
+            components.add_security_scheme(
+                "intercom_token",
+                SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Intercom-Token"))),
             );
         }
     }
 }
+
+/// Операции, относящиеся к оборудованию: по тегу или по схеме авторизации устройства
+const DEVICE_TAGS: &[&str] = &["devices"];
+const DEVICE_SECURITY_SCHEMES: &[&str] = &["barrier_key", "intercom_token"];
+/// Служебные разделы платформы, которые председателю не показываются
+const STAFF_TAGS: &[&str] = &["moderation"];
+
+const HTTP_METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Для кого собирается документ OpenAPI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiAudience {
+    /// Председатели и жители: без оборудования и модерации
+    Chairman,
+    /// Производители оборудования: только вызовы от устройств
+    Device,
+    /// Весь документ
+    Admin,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenApiQuery {
+    pub audience: Option<ApiAudience>,
+}
+
+impl ApiAudience {
+    fn includes(self, operation: &Value) -> bool {
+        let has_tag = |tags: &[&str]| {
+            operation["tags"]
+                .as_array()
+                .is_some_and(|t| t.iter().any(|tag| tag.as_str().is_some_and(|tag| tags.contains(&tag))))
+        };
+        let is_device = has_tag(DEVICE_TAGS)
+            || security_schemes(operation)
+                .iter()
+                .any(|scheme| DEVICE_SECURITY_SCHEMES.contains(&scheme.as_str()));
+
+        match self {
+            ApiAudience::Admin => true,
+            ApiAudience::Device => is_device,
+            ApiAudience::Chairman => !is_device && !has_tag(STAFF_TAGS),
+        }
+    }
+}
+
+/// Документ OpenAPI для аудитории. Операции отбираются по тегам и схемам авторизации,
+/// в компонентах остаются только схемы, на которые ссылаются оставшиеся операции
+pub fn openapi_for(audience: ApiAudience) -> Value {
+    let mut doc = serde_json::to_value(ApiDoc::openapi()).expect("OpenAPI document is valid JSON");
+    if audience == ApiAudience::Admin {
+        return doc;
+    }
+
+    if let Some(paths) = doc["paths"].as_object_mut() {
+        for item in paths.values_mut() {
+            if let Some(item) = item.as_object_mut() {
+                item.retain(|key, operation| {
+                    !HTTP_METHODS.contains(&key.as_str()) || audience.includes(operation)
+                });
+            }
+        }
+        paths.retain(|_, item| {
+            item.as_object()
+                .is_some_and(|item| item.keys().any(|key| HTTP_METHODS.contains(&key.as_str())))
+        });
+    }
+
+    let operations: Vec<Value> = doc["paths"]
+        .as_object()
+        .into_iter()
+        .flat_map(|paths| paths.values())
+        .filter_map(Value::as_object)
+        .flat_map(|item| {
+            item.iter()
+                .filter(|(key, _)| HTTP_METHODS.contains(&key.as_str()))
+                .map(|(_, operation)| operation.clone())
+        })
+        .collect();
+
+    let used_tags: HashSet<&str> = operations
+        .iter()
+        .filter_map(|operation| operation["tags"].as_array())
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    if let Some(tags) = doc["tags"].as_array_mut() {
+        tags.retain(|tag| tag["name"].as_str().is_some_and(|name| used_tags.contains(name)));
+    }
+
+    let used_schemes: HashSet<String> = operations.iter().flat_map(security_schemes).collect();
+    if let Some(schemes) = doc["components"]["securitySchemes"].as_object_mut() {
+        schemes.retain(|name, _| used_schemes.contains(name));
+    }
+
+    // Схемы, достижимые по $ref из операций, включая вложенные
+    let mut used_schemas = HashSet::new();
+    let mut pending = Vec::new();
+    schema_refs(&doc["paths"], &mut pending);
+    while let Some(name) = pending.pop() {
+        if used_schemas.insert(name.clone()) {
+            schema_refs(&doc["components"]["schemas"][&name], &mut pending);
+        }
+    }
+    if let Some(schemas) = doc["components"]["schemas"].as_object_mut() {
+        schemas.retain(|name, _| used_schemas.contains(name));
+    }
+
+    doc
+}
+
+/// Имена схем авторизации из требований операции
+fn security_schemes(operation: &Value) -> Vec<String> {
+    operation["security"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_object)
+        .flat_map(|requirement| requirement.keys().cloned())
+        .collect()
+}
+
+fn schema_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value.as_str().and_then(|r| r.strip_prefix("#/components/schemas/")) {
+                    Some(name) if key == "$ref" => refs.push(name.to_string()),
+                    _ => schema_refs(value, refs),
+                }
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|item| schema_refs(item, refs)),
+        _ => {}
+    }
+}
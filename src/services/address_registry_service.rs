@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{RegistryImportResult, RegistryRecord, RegistryRecordKind, RegistryStreet};
+
+/// Справочник адресов из национального классификатора
+pub struct AddressRegistryService;
+
+impl AddressRegistryService {
+    /// Загрузить выгрузку классификатора: города, районы и улицы обновляются по своим кодам
+    pub async fn import(pool: &PgPool, source_url: &str) -> AppResult<RegistryImportResult> {
+        let response = reqwest::get(source_url)
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("адресный регистр ({})", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::ServiceUnavailable(format!(
+                "адресный регистр ({})",
+                response.status()
+            )));
+        }
+
+        let mut records: Vec<RegistryRecord> = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Некорректная выгрузка регистра: {}", e)))?;
+
+        // Родитель всегда загружается раньше потомков
+        records.sort_by_key(|record| match record.kind {
+            RegistryRecordKind::City => 0,
+            RegistryRecordKind::District => 1,
+            RegistryRecordKind::Street => 2,
+        });
+
+        let mut tx = pool.begin().await?;
+        let mut result = RegistryImportResult::default();
+
+        let mut cities: HashMap<String, String> =
+            sqlx::query_as::<_, (String, String)>(
+                "SELECT kato_code, id FROM cities WHERE kato_code IS NOT NULL",
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut districts: HashMap<String, (Uuid, String)> =
+            sqlx::query_as::<_, (String, Uuid, String)>(
+                "SELECT kato_code, id, city_id FROM registry_districts",
+            )
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(code, id, city_id)| (code, (id, city_id)))
+            .collect();
+
+        for record in records {
+            match record.kind {
+                RegistryRecordKind::City => {
+                    // Города из начального справочника сопоставляются по названию
+                    let (city_id,): (String,) = sqlx::query_as(
+                        r#"
+                        WITH matched AS (
+                            UPDATE cities SET
+                                kato_code = $1,
+                                name_kz = COALESCE($3, name_kz)
+                            WHERE id = (
+                                SELECT id FROM cities
+                                WHERE kato_code = $1
+                                   OR (kato_code IS NULL AND lower(name) = lower($2))
+                                ORDER BY kato_code IS NULL
+                                LIMIT 1
+                            )
+                            RETURNING id
+                        ), inserted AS (
+                            INSERT INTO cities (id, name, name_kz, kato_code)
+                            SELECT $1, $2, $3, $1
+                            WHERE NOT EXISTS (SELECT 1 FROM matched)
+                            RETURNING id
+                        )
+                        SELECT id FROM matched
+                        UNION ALL
+                        SELECT id FROM inserted
+                        "#,
+                    )
+                    .bind(&record.code)
+                    .bind(&record.name_ru)
+                    .bind(&record.name_kz)
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+                    cities.insert(record.code, city_id);
+                    result.cities += 1;
+                }
+                RegistryRecordKind::District => {
+                    let Some(city_id) = record
+                        .parent_code
+                        .as_ref()
+                        .and_then(|code| cities.get(code))
+                        .cloned()
+                    else {
+                        result.skipped += 1;
+                        continue;
+                    };
+
+                    let (id,): (Uuid,) = sqlx::query_as(
+                        r#"
+                        INSERT INTO registry_districts (kato_code, city_id, name, name_kz)
+                        VALUES ($1, $2, $3, $4)
+                        ON CONFLICT (kato_code) DO UPDATE SET
+                            city_id = EXCLUDED.city_id,
+                            name = EXCLUDED.name,
+                            name_kz = EXCLUDED.name_kz,
+                            updated_at = NOW()
+                        RETURNING id
+                        "#,
+                    )
+                    .bind(&record.code)
+                    .bind(&city_id)
+                    .bind(&record.name_ru)
+                    .bind(&record.name_kz)
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+                    districts.insert(record.code, (id, city_id));
+                    result.districts += 1;
+                }
+                RegistryRecordKind::Street => {
+                    // Улица может числиться за районом или сразу за городом
+                    let parent = record.parent_code.as_ref().and_then(|code| {
+                        districts
+                            .get(code)
+                            .map(|(id, city_id)| (Some(*id), city_id.clone()))
+                            .or_else(|| cities.get(code).map(|city_id| (None, city_id.clone())))
+                    });
+                    let Some((district_id, city_id)) = parent else {
+                        result.skipped += 1;
+                        continue;
+                    };
+
+                    sqlx::query(
+                        r#"
+                        INSERT INTO registry_streets (code, city_id, district_id, name, name_kz, street_type)
+                        VALUES ($1, $2, $3, $4, $5, $6)
+                        ON CONFLICT (code) DO UPDATE SET
+                            city_id = EXCLUDED.city_id,
+                            district_id = EXCLUDED.district_id,
+                            name = EXCLUDED.name,
+                            name_kz = EXCLUDED.name_kz,
+                            street_type = EXCLUDED.street_type,
+                            updated_at = NOW()
+                        "#,
+                    )
+                    .bind(&record.code)
+                    .bind(&city_id)
+                    .bind(district_id)
+                    .bind(&record.name_ru)
+                    .bind(&record.name_kz)
+                    .bind(&record.street_type)
+                    .execute(&mut *tx)
+                    .await?;
+
+                    result.streets += 1;
+                }
+            }
+        }
+
+        // Введённые вручную адреса привязываются к регистру, если название улицы однозначно
+        let linked = sqlx::query(
+            r#"
+            UPDATE addresses a SET registry_street_id = s.id, is_verified = true
+            FROM registry_streets s
+            WHERE a.registry_street_id IS NULL
+              AND s.city_id = a.city_id
+              AND lower(s.name) = lower(a.street)
+              AND NOT EXISTS (
+                  SELECT 1 FROM registry_streets other
+                  WHERE other.city_id = s.city_id
+                    AND lower(other.name) = lower(s.name)
+                    AND other.id <> s.id
+              )
+            "#,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            "Address registry imported: {} cities, {} districts, {} streets, {} skipped, {} addresses verified",
+            result.cities,
+            result.districts,
+            result.streets,
+            result.skipped,
+            linked.rows_affected()
+        );
+
+        Ok(result)
+    }
+
+    /// Улица регистра с таким названием, если она в городе одна
+    pub async fn match_street(
+        pool: &PgPool,
+        city_id: &str,
+        name: &str,
+    ) -> AppResult<Option<RegistryStreet>> {
+        let mut streets = sqlx::query_as::<_, RegistryStreet>(
+            "SELECT * FROM registry_streets WHERE city_id = $1 AND lower(name) = lower($2) LIMIT 2",
+        )
+        .bind(city_id)
+        .bind(name.trim())
+        .fetch_all(pool)
+        .await?;
+
+        Ok(if streets.len() == 1 { streets.pop() } else { None })
+    }
+}
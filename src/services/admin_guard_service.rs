@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{AdminIpBlock, NotificationType, UserRole};
+use crate::services::NotificationService;
+
+/// Окно, в котором считаются неудачные обращения к админке
+const ADMIN_FAILURE_WINDOW_SECS: f64 = 600.0;
+
+/// Защита админки от подбора: счётчики 401/403 по IP и временные блокировки
+pub struct AdminGuardService;
+
+impl AdminGuardService {
+    /// До какого момента IP заблокирован, если блокировка ещё действует
+    pub async fn blocked_until(pool: &PgPool, ip: &str) -> AppResult<Option<DateTime<Utc>>> {
+        let blocked: Option<(DateTime<Utc>,)> = sqlx::query_as(
+            "SELECT blocked_until FROM admin_ip_blocks WHERE ip_address = $1 AND blocked_until > NOW()",
+        )
+        .bind(ip)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(blocked.map(|(until,)| until))
+    }
+
+    /// Учесть неудачное обращение; при превышении порога заблокировать IP и оповестить суперадминов
+    pub async fn record_failure(pool: &PgPool, config: &Config, ip: &str, path: &str) -> AppResult<()> {
+        let entry = sqlx::query_as::<_, AdminIpBlock>(
+            r#"
+            INSERT INTO admin_ip_blocks (ip_address, failures, last_path)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (ip_address) DO UPDATE SET
+                failures = CASE
+                    WHEN admin_ip_blocks.window_started_at < NOW() - make_interval(secs => $3)
+                    THEN 1 ELSE admin_ip_blocks.failures + 1
+                END,
+                window_started_at = CASE
+                    WHEN admin_ip_blocks.window_started_at < NOW() - make_interval(secs => $3)
+                    THEN NOW() ELSE admin_ip_blocks.window_started_at
+                END,
+                last_path = EXCLUDED.last_path,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(ip)
+        .bind(path)
+        .bind(ADMIN_FAILURE_WINDOW_SECS)
+        .fetch_one(pool)
+        .await?;
+
+        if entry.failures < config.admin_lockout_threshold {
+            return Ok(());
+        }
+
+        // Условие на blocked_until не даёт параллельным запросам заблокировать IP дважды
+        let blocked = sqlx::query_as::<_, AdminIpBlock>(
+            r#"
+            UPDATE admin_ip_blocks SET
+                blocked_until = NOW() + make_interval(mins => $2),
+                block_count = block_count + 1,
+                failures = 0,
+                window_started_at = NOW(),
+                lifted_by = NULL,
+                lifted_at = NULL,
+                updated_at = NOW()
+            WHERE id = $1 AND (blocked_until IS NULL OR blocked_until <= NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(entry.id)
+        .bind(config.admin_lockout_minutes as i32)
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some(blocked) = blocked {
+            tracing::warn!(
+                "Admin endpoints blocked for {} after {} failures, last path {}",
+                blocked.ip_address,
+                entry.failures,
+                path
+            );
+            Self::notify_super_admins(pool, &blocked, entry.failures).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Блокировки: действующие или все, новые сверху
+    pub async fn list(pool: &PgPool, active_only: bool) -> AppResult<Vec<AdminIpBlock>> {
+        let blocks = sqlx::query_as::<_, AdminIpBlock>(
+            r#"
+            SELECT * FROM admin_ip_blocks
+            WHERE block_count > 0 AND (NOT $1 OR blocked_until > NOW())
+            ORDER BY updated_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(active_only)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(blocks)
+    }
+
+    /// Досрочно снять блокировку и обнулить счётчик
+    pub async fn lift(pool: &PgPool, id: Uuid, lifted_by: Uuid) -> AppResult<AdminIpBlock> {
+        sqlx::query_as::<_, AdminIpBlock>(
+            r#"
+            UPDATE admin_ip_blocks SET
+                blocked_until = NULL,
+                failures = 0,
+                window_started_at = NOW(),
+                lifted_by = $2,
+                lifted_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND blocked_until > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(lifted_by)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Действующая блокировка не найдена".to_string()))
+    }
+
+    async fn notify_super_admins(pool: &PgPool, block: &AdminIpBlock, failures: i32) -> AppResult<()> {
+        let super_admins: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM users WHERE role = $1 AND NOT is_blocked")
+                .bind(UserRole::SuperAdmin)
+                .fetch_all(pool)
+                .await?;
+        let user_ids: Vec<Uuid> = super_admins.into_iter().map(|(id,)| id).collect();
+
+        NotificationService::notify_users(
+            pool,
+            &user_ids,
+            NotificationType::Security,
+            "Подбор доступа к админке",
+            Some(&format!(
+                "IP {} заблокирован после {} отказов в доступе к админ-панели",
+                block.ip_address, failures
+            )),
+            Some(json!({
+                "admin_ip_block_id": block.id,
+                "ip_address": block.ip_address,
+                "blocked_until": block.blocked_until,
+                "last_path": block.last_path,
+            })),
+        )
+        .await
+    }
+}
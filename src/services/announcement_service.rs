@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::models::{
+    Announcement, AnnouncementAttachment, DomainEventType, JobType, NewDomainEvent,
+    NotificationFanoutPayload, NotificationType,
+};
+use crate::services::{EventService, JobService, SchedulerService};
+
+/// Как часто проверять объявления, которым пора выйти
+const ANNOUNCEMENT_PUBLISH_INTERVAL_SECS: u64 = 60;
+
+/// Публикация объявлений (сразу или по расписанию) и их вложения
+pub struct AnnouncementService;
+
+impl AnnouncementService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "announcement_publish",
+            std::time::Duration::from_secs(ANNOUNCEMENT_PUBLISH_INTERVAL_SECS),
+            |pool, _config| async move {
+                let published = AnnouncementService::publish_due(&pool).await?;
+                if published > 0 {
+                    tracing::info!("Published {} scheduled announcements", published);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Событие в ленте ОСИ и рассылка жителям о вышедшем объявлении
+    pub async fn announce(pool: &PgPool, announcement: &Announcement) -> AppResult<()> {
+        let osi_id: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM osi WHERE complex_id = $1")
+            .bind(announcement.complex_id)
+            .fetch_optional(pool)
+            .await?;
+
+        EventService::record(
+            pool,
+            NewDomainEvent {
+                complex_id: announcement.complex_id,
+                osi_id: osi_id.map(|(id,)| id),
+                actor_id: announcement.author_id,
+                event_type: DomainEventType::AnnouncementPublished,
+                entity_type: "announcement",
+                entity_id: Some(announcement.id),
+                data: Some(json!({"title": announcement.title, "category": announcement.category})),
+            },
+        )
+        .await?;
+
+        // Рассылаем уведомления жителям в фоне
+        JobService::enqueue(
+            pool,
+            JobType::NotificationFanout,
+            &NotificationFanoutPayload {
+                complex_id: announcement.complex_id,
+                notification_type: NotificationType::Announcement,
+                title: announcement.title.clone(),
+                body: None,
+                data: Some(json!({"announcement_id": announcement.id})),
+                exclude_user_id: Some(announcement.author_id),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Опубликовать объявления, время которых наступило
+    pub async fn publish_due(pool: &PgPool) -> AppResult<usize> {
+        let published = sqlx::query_as::<_, Announcement>(
+            r#"
+            UPDATE announcements SET
+                is_published = true,
+                published_at = NOW(),
+                publish_at = NULL,
+                updated_at = NOW()
+            WHERE NOT is_published AND publish_at <= NOW()
+            RETURNING *
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for announcement in &published {
+            Self::announce(pool, announcement).await?;
+        }
+
+        Ok(published.len())
+    }
+
+    /// Вложения объявлений по ID объявления, в порядке загрузки
+    pub async fn attachments(
+        pool: &PgPool,
+        announcement_ids: &[Uuid],
+    ) -> AppResult<HashMap<Uuid, Vec<AnnouncementAttachment>>> {
+        let attachments = sqlx::query_as::<_, AnnouncementAttachment>(
+            r#"
+            SELECT * FROM announcement_attachments
+            WHERE announcement_id = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(announcement_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let mut grouped: HashMap<Uuid, Vec<AnnouncementAttachment>> = HashMap::new();
+        for attachment in attachments {
+            grouped.entry(attachment.announcement_id).or_default().push(attachment);
+        }
+        Ok(grouped)
+    }
+}
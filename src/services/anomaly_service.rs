@@ -0,0 +1,276 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{BarrierAction, NotificationType, SecurityEvent, SecurityEventType};
+use crate::services::NotificationService;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// Сколько въездов по одному пропуску считается злоупотреблением. Пропуск
+/// одноразовый, поэтому лишние въезды — это ручные открытия охраной по нему
+const PASS_ENTRY_LIMIT: i64 = 3;
+
+/// Сколько открытий шлагбаума жителем за сутки считается подозрительным
+const RESIDENT_DAILY_OPEN_LIMIT: i64 = 50;
+
+/// Жёсткий лимит открытий шлагбаума жителем в минуту
+const RESIDENT_OPENS_PER_MINUTE: i64 = 5;
+
+/// Проезд через шлагбаум, который нужно проверить правилами до открытия
+pub struct BarrierPassage<'a> {
+    pub complex_id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub guest_access_id: Option<Uuid>,
+    pub vehicle_number: Option<&'a str>,
+}
+
+pub struct AnomalyService;
+
+impl AnomalyService {
+    /// Ограничить частоту открытий шлагбаума жителем
+    pub async fn check_open_rate(pool: &PgPool, complex_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let (count,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM barrier_access_logs
+            WHERE complex_id = $1 AND user_id = $2 AND created_at > NOW() - INTERVAL '1 minute'
+            "#,
+        )
+        .bind(complex_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        if count >= RESIDENT_OPENS_PER_MINUTE {
+            return Err(AppError::TooManyRequests);
+        }
+
+        Ok(())
+    }
+
+    /// Проверить въезд правилами до открытия шлагбаума и занести нарушения в ленту
+    /// безопасности. Если пропуск при этом приостановлен, въезд запрещается
+    pub async fn inspect_entry(pool: &PgPool, passage: BarrierPassage<'_>) -> AppResult<()> {
+        let mut suspended = false;
+
+        if let Some(access_id) = passage.guest_access_id {
+            let (previous,): (i64,) = sqlx::query_as(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM barrier_access_logs WHERE guest_access_id = $1 AND action = 'entry')
+                    + (SELECT COUNT(*) FROM barrier_manual_openings WHERE guest_access_id = $1)
+                "#,
+            )
+            .bind(access_id)
+            .fetch_one(pool)
+            .await?;
+
+            let flagged: Option<(i32,)> = sqlx::query_as(
+                "SELECT 1 FROM security_events WHERE event_type = 'pass_overuse' AND guest_access_id = $1",
+            )
+            .bind(access_id)
+            .fetch_optional(pool)
+            .await?;
+
+            if pass_overused(previous) && flagged.is_none() {
+                let event = Self::flag(
+                    pool,
+                    &passage,
+                    SecurityEventType::PassOveruse,
+                    json!({"entries": previous + 1, "limit": PASS_ENTRY_LIMIT}),
+                )
+                .await?;
+                suspended |= event.pass_suspended;
+            }
+        }
+
+        if let Some(vehicle_number) = passage.vehicle_number {
+            let previous: Option<(BarrierAction,)> = sqlx::query_as(
+                r#"
+                SELECT action FROM barrier_access_logs
+                WHERE complex_id = $1 AND vehicle_number = $2
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+            )
+            .bind(passage.complex_id)
+            .bind(vehicle_number)
+            .fetch_optional(pool)
+            .await?;
+
+            if entered_without_exit(previous.map(|(action,)| action)) {
+                let event =
+                    Self::flag(pool, &passage, SecurityEventType::EntryWithoutExit, json!({})).await?;
+                suspended |= event.pass_suspended;
+            }
+        }
+
+        if let Some(user_id) = passage.user_id {
+            let (previous,): (i64,) = sqlx::query_as(
+                r#"
+                SELECT COUNT(*) FROM barrier_access_logs
+                WHERE user_id = $1 AND created_at >= date_trunc('day', NOW())
+                "#,
+            )
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+
+            if opens_excessive(previous) {
+                let flagged_today: Option<(i32,)> = sqlx::query_as(
+                    r#"
+                    SELECT 1 FROM security_events
+                    WHERE event_type = 'excessive_openings' AND user_id = $1
+                      AND created_at >= date_trunc('day', NOW())
+                    "#,
+                )
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+
+                if flagged_today.is_none() {
+                    Self::flag(
+                        pool,
+                        &passage,
+                        SecurityEventType::ExcessiveOpenings,
+                        json!({"opens_today": previous + 1, "limit": RESIDENT_DAILY_OPEN_LIMIT}),
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        if suspended {
+            return Err(AppError::EntryDenied(
+                "Пропуск приостановлен до проверки председателем".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Включена ли в ЖК автоматическая приостановка пропусков
+    pub async fn auto_suspend_enabled(pool: &PgPool, complex_id: Uuid) -> AppResult<bool> {
+        let enabled: Option<(bool,)> =
+            sqlx::query_as("SELECT auto_suspend_anomalous_passes FROM complexes WHERE id = $1")
+                .bind(complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(enabled.is_some_and(|(e,)| e))
+    }
+
+    /// Записать событие, при необходимости приостановить пропуск и уведомить председателя
+    async fn flag(
+        pool: &PgPool,
+        passage: &BarrierPassage<'_>,
+        event_type: SecurityEventType,
+        details: Value,
+    ) -> AppResult<SecurityEvent> {
+        let suspend = passage.guest_access_id.is_some()
+            && Self::auto_suspend_enabled(pool, passage.complex_id).await?;
+
+        if suspend {
+            sqlx::query("UPDATE guest_access SET suspended_at = NOW() WHERE id = $1 AND suspended_at IS NULL")
+                .bind(passage.guest_access_id)
+                .execute(pool)
+                .await?;
+        }
+
+        let event = sqlx::query_as::<_, SecurityEvent>(
+            r#"
+            INSERT INTO security_events
+                (complex_id, event_type, user_id, guest_access_id, vehicle_number, details, pass_suspended)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(passage.complex_id)
+        .bind(event_type)
+        .bind(passage.user_id)
+        .bind(passage.guest_access_id)
+        .bind(passage.vehicle_number)
+        .bind(details)
+        .bind(suspend)
+        .fetch_one(pool)
+        .await?;
+
+        tracing::warn!(
+            "Barrier anomaly {:?} in complex {} (event {})",
+            event_type,
+            passage.complex_id,
+            event.id
+        );
+
+        let chairman: Option<(Option<Uuid>,)> =
+            sqlx::query_as("SELECT chairman_id FROM osi WHERE complex_id = $1")
+                .bind(passage.complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        if let Some(chairman_id) = chairman.and_then(|(id,)| id) {
+            let body = match event_type {
+                SecurityEventType::PassOveruse => "По гостевому пропуску слишком много въездов",
+                SecurityEventType::EntryWithoutExit => "Повторный въезд автомобиля без выезда",
+                SecurityEventType::ExcessiveOpenings => "Житель слишком часто открывает шлагбаум",
+            };
+            let body = if suspend {
+                format!("{}. Пропуск приостановлен до проверки", body)
+            } else {
+                body.to_string()
+            };
+
+            NotificationService::notify_users(
+                pool,
+                &[chairman_id],
+                NotificationType::Security,
+                "Подозрительная активность на шлагбауме",
+                Some(&body),
+                Some(json!({"security_event_id": event.id})),
+            )
+            .await?;
+        }
+
+        Ok(event)
+    }
+}
+
+/// Этот въезд по пропуску превышает лимит
+fn pass_overused(previous_entries: i64) -> bool {
+    previous_entries + 1 >= PASS_ENTRY_LIMIT
+}
+
+/// Автомобиль въезжает, а последним по нему записан въезд, а не выезд
+fn entered_without_exit(previous: Option<BarrierAction>) -> bool {
+    previous == Some(BarrierAction::Entry)
+}
+
+/// Это открытие доводит число открытий жителем за сутки до порога
+fn opens_excessive(previous_opens: i64) -> bool {
+    previous_opens + 1 >= RESIDENT_DAILY_OPEN_LIMIT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_overuse_counts_current_entry() {
+        assert!(!pass_overused(0));
+        assert!(!pass_overused(PASS_ENTRY_LIMIT - 2));
+        assert!(pass_overused(PASS_ENTRY_LIMIT - 1));
+        assert!(pass_overused(PASS_ENTRY_LIMIT + 5));
+    }
+
+    #[test]
+    fn entry_without_exit_only_after_entry() {
+        assert!(entered_without_exit(Some(BarrierAction::Entry)));
+        assert!(!entered_without_exit(Some(BarrierAction::Exit)));
+        assert!(!entered_without_exit(None));
+    }
+
+    #[test]
+    fn excessive_openings_counts_current_open() {
+        assert!(!opens_excessive(0));
+        assert!(!opens_excessive(RESIDENT_DAILY_OPEN_LIMIT - 2));
+        assert!(opens_excessive(RESIDENT_DAILY_OPEN_LIMIT - 1));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::error::AppResult;
+use crate::models::NewAuditLog;
+use crate::utils::json_diff;
+use sqlx::PgExecutor;
+
+pub struct AuditService;
+
+impl AuditService {
+    /// Записать привилегированное действие в журнал аудита вместе с диффом изменений.
+    /// Внутри транзакции действия запись фиксируется вместе с ним
+    pub async fn record<'e>(db: impl PgExecutor<'e>, entry: NewAuditLog) -> AppResult<()> {
+        let diff = match (&entry.old_value, &entry.new_value) {
+            (Some(old), Some(new)) => Some(json_diff(old, new)),
+            _ => None,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO audit_logs (actor_id, complex_id, action, entity_type, entity_id, old_value, new_value, diff, request_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(entry.actor_id)
+        .bind(entry.complex_id)
+        .bind(entry.action)
+        .bind(entry.entity_type)
+        .bind(entry.entity_id)
+        .bind(entry.old_value)
+        .bind(entry.new_value)
+        .bind(diff)
+        .bind(entry.request_id)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+}
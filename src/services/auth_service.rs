@@ -1,9 +1,12 @@
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use crate::models::{User, UserRole};
+use crate::models::{MagicLink, MagicLinkPurpose, Permission, User, UserRole};
+use crate::services::PermissionService;
+use crate::utils::{parse_phone, PhoneNumber};
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use uuid::Uuid;
 
@@ -14,6 +17,9 @@ pub struct Claims {
     pub exp: i64,
     pub iat: i64,
     pub token_type: String,
+    /// Хэш отпечатка устройства, к которому привязана сессия привилегированной роли
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
 }
 
 pub struct AuthService {
@@ -25,7 +31,7 @@ impl AuthService {
         Self { config }
     }
 
-    pub fn generate_access_token(&self, user: &User) -> AppResult<String> {
+    pub fn generate_access_token(&self, user: &User, device: Option<&str>) -> AppResult<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.config.jwt_access_expiry);
 
@@ -35,6 +41,7 @@ impl AuthService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "access".to_string(),
+            device: device.map(str::to_string),
         };
 
         encode(
@@ -45,7 +52,7 @@ impl AuthService {
         .map_err(AppError::from)
     }
 
-    pub fn generate_refresh_token(&self, user: &User) -> AppResult<String> {
+    pub fn generate_refresh_token(&self, user: &User, device: Option<&str>) -> AppResult<String> {
         let now = Utc::now();
         let exp = now + Duration::seconds(self.config.jwt_refresh_expiry);
 
@@ -55,6 +62,7 @@ impl AuthService {
             exp: exp.timestamp(),
             iat: now.timestamp(),
             token_type: "refresh".to_string(),
+            device: device.map(str::to_string),
         };
 
         encode(
@@ -104,14 +112,19 @@ impl AuthService {
     }
 
     pub async fn create_user(pool: &PgPool, phone: &str) -> AppResult<User> {
+        let country_code = parse_phone(phone)
+            .map(|parsed| parsed.country_code)
+            .unwrap_or_else(|| "7".to_string());
+
         let user = sqlx::query_as::<_, User>(
             r#"
-            INSERT INTO users (phone, role, is_verified)
-            VALUES ($1, $2, false)
+            INSERT INTO users (phone, phone_country_code, role, is_verified)
+            VALUES ($1, $2, $3, false)
             RETURNING *
             "#,
         )
         .bind(phone)
+        .bind(country_code)
         .bind(UserRole::User)
         .fetch_one(pool)
         .await?;
@@ -158,6 +171,74 @@ impl AuthService {
         Ok(result.is_some())
     }
 
+    /// Выпустить одноразовую ссылку для письма; в базе остаётся только хэш токена
+    pub async fn create_magic_link(
+        pool: &PgPool,
+        user_id: Uuid,
+        purpose: MagicLinkPurpose,
+        email: &str,
+        ttl_minutes: i64,
+    ) -> AppResult<String> {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+
+        sqlx::query(
+            r#"
+            INSERT INTO magic_links (user_id, purpose, email, token_hash, expires_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose)
+        .bind(email)
+        .bind(Self::magic_link_hash(&token))
+        .bind(Utc::now() + Duration::minutes(ttl_minutes))
+        .execute(pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    /// Погасить ссылку: `None`, если она неизвестна, просрочена или уже использована
+    pub async fn consume_magic_link(
+        pool: &PgPool,
+        token: &str,
+        purpose: MagicLinkPurpose,
+    ) -> AppResult<Option<MagicLink>> {
+        let link = sqlx::query_as::<_, MagicLink>(
+            r#"
+            UPDATE magic_links
+            SET used_at = NOW()
+            WHERE token_hash = $1
+              AND purpose = $2
+              AND used_at IS NULL
+              AND expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(Self::magic_link_hash(token.trim()))
+        .bind(purpose)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(link)
+    }
+
+    /// Сколько ссылок выпущено пользователю за последний час
+    pub async fn recent_magic_links(pool: &PgPool, user_id: Uuid) -> AppResult<i64> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM magic_links WHERE user_id = $1 AND created_at > NOW() - INTERVAL '1 hour'",
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    fn magic_link_hash(token: &str) -> String {
+        hex::encode(Sha256::digest(token.as_bytes()))
+    }
+
     pub async fn save_refresh_token(
         pool: &PgPool,
         user_id: Uuid,
@@ -201,6 +282,44 @@ impl AuthService {
         Ok(())
     }
 
+    /// Привязать к пользователю карточки работников ОСИ с его номером,
+    /// чтобы работник видел назначенные ему заявки
+    pub async fn link_worker_accounts(pool: &PgPool, user: &User) -> AppResult<()> {
+        sqlx::query("UPDATE osi_workers SET user_id = $1 WHERE phone = $2 AND user_id IS NULL")
+            .bind(user.id)
+            .bind(&user.phone)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Хэш отпечатка устройства для привязки сессии
+    pub fn device_hash(fingerprint: &str) -> String {
+        hex::encode(Sha256::digest(fingerprint.trim().as_bytes()))
+    }
+
+    /// Нужно ли привязывать сессию к устройству: админам всегда, управляющим ОСИ —
+    /// если хотя бы один из ЖК, где у них есть это право, не отключил привязку
+    pub async fn device_binding_required(pool: &PgPool, user: &User) -> AppResult<bool> {
+        if matches!(user.role, UserRole::Admin | UserRole::SuperAdmin) {
+            return Ok(true);
+        }
+
+        let complexes = PermissionService::complexes_with(pool, user.id, Permission::ManageOsi).await?;
+        if complexes.is_empty() {
+            return Ok(false);
+        }
+
+        let (required,): (Option<bool>,) =
+            sqlx::query_as("SELECT bool_or(device_binding_enabled) FROM complexes WHERE id = ANY($1)")
+                .bind(&complexes)
+                .fetch_one(pool)
+                .await?;
+
+        Ok(required.unwrap_or(false))
+    }
+
     pub fn hash_token(token: &str) -> String {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
@@ -211,20 +330,27 @@ impl AuthService {
 }
 
 pub fn normalize_phone(phone: &str) -> String {
-    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
-
-    if digits.starts_with("8") && digits.len() == 11 {
-        format!("+7{}", &digits[1..])
-    } else if digits.starts_with("7") && digits.len() == 11 {
-        format!("+{}", digits)
-    } else if digits.len() == 10 {
-        format!("+7{}", digits)
-    } else {
-        format!("+{}", digits)
+    match parse_phone(phone) {
+        Some(parsed) => parsed.e164,
+        None => {
+            let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+            format!("+{}", digits)
+        }
     }
 }
 
-pub fn validate_kz_phone(phone: &str) -> bool {
-    let normalized = normalize_phone(phone);
-    normalized.starts_with("+7") && normalized.len() == 12
+/// Номер в E.164 из страны, разрешённой в `ALLOWED_PHONE_COUNTRY_CODES`
+pub fn parse_allowed_phone(phone: &str, config: &Config) -> AppResult<PhoneNumber> {
+    let parsed = parse_phone(phone).ok_or_else(|| {
+        AppError::Validation("Неверный формат номера телефона".to_string())
+    })?;
+
+    if !config.phone_allowed_country_codes.contains(&parsed.country_code) {
+        return Err(AppError::Validation(format!(
+            "Номера с кодом +{} не поддерживаются",
+            parsed.country_code
+        )));
+    }
+
+    Ok(parsed)
 }
@@ -0,0 +1,129 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::AuthUser;
+use crate::models::{GlobalBanner, NotificationType};
+use crate::services::SchedulerService;
+
+/// Как часто проверять баннеры, которым пора разослать уведомление
+const BANNER_PUSH_INTERVAL_SECS: u64 = 60;
+
+/// Платформенные баннеры для всех пользователей
+pub struct BannerService;
+
+impl BannerService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "banner_push",
+            std::time::Duration::from_secs(BANNER_PUSH_INTERVAL_SECS),
+            |pool, _config| async move { BannerService::push_started(&pool).await },
+        );
+    }
+
+    /// Баннеры, которые сейчас видит пользователь; анонимному — только адресованные всем
+    pub async fn active_for(pool: &PgPool, user: Option<&AuthUser>) -> AppResult<Vec<GlobalBanner>> {
+        let banners = sqlx::query_as::<_, GlobalBanner>(
+            r#"
+            SELECT b.* FROM global_banners b
+            WHERE b.is_active
+              AND b.starts_at <= NOW()
+              AND (b.ends_at IS NULL OR b.ends_at > NOW())
+              AND (cardinality(b.target_roles) = 0 OR $2 = ANY(b.target_roles))
+              AND (cardinality(b.target_cities) = 0 OR EXISTS (
+                  SELECT 1 FROM apartments a
+                  JOIN complexes c ON c.id = a.complex_id
+                  WHERE (a.owner_id = $1 OR a.resident_id = $1)
+                    AND c.city_id = ANY(b.target_cities)
+              ))
+              AND NOT EXISTS (
+                  SELECT 1 FROM banner_dismissals d
+                  WHERE d.banner_id = b.id AND d.user_id = $1
+              )
+            ORDER BY b.severity DESC, b.starts_at DESC
+            "#,
+        )
+        .bind(user.map(|user| user.user_id))
+        .bind(user.map(|user| user.role.clone()))
+        .fetch_all(pool)
+        .await?;
+
+        Ok(banners)
+    }
+
+    /// Скрыть баннер для пользователя
+    pub async fn dismiss(pool: &PgPool, banner_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let dismissible: Option<(bool,)> =
+            sqlx::query_as("SELECT is_dismissible FROM global_banners WHERE id = $1")
+                .bind(banner_id)
+                .fetch_optional(pool)
+                .await?;
+
+        match dismissible {
+            None => return Err(AppError::NotFound("Баннер не найден".to_string())),
+            Some((false,)) => {
+                return Err(AppError::BadRequest("Этот баннер нельзя скрыть".to_string()))
+            }
+            Some((true,)) => {}
+        }
+
+        sqlx::query(
+            "INSERT INTO banner_dismissals (banner_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(banner_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Разослать уведомления по начавшим показываться баннерам
+    pub async fn push_started(pool: &PgPool) -> AppResult<()> {
+        let banners = sqlx::query_as::<_, GlobalBanner>(
+            r#"
+            UPDATE global_banners SET pushed_at = NOW()
+            WHERE send_push AND pushed_at IS NULL AND is_active
+              AND starts_at <= NOW()
+              AND (ends_at IS NULL OR ends_at > NOW())
+            RETURNING *
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for banner in banners {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO notifications (user_id, notification_type, title, body, data)
+                SELECT u.id, $1, $2, $3, $4
+                FROM users u
+                WHERE NOT u.is_blocked
+                  AND (cardinality($5::user_role[]) = 0 OR u.role = ANY($5))
+                  AND (cardinality($6::varchar[]) = 0 OR EXISTS (
+                      SELECT 1 FROM apartments a
+                      JOIN complexes c ON c.id = a.complex_id
+                      WHERE (a.owner_id = u.id OR a.resident_id = u.id)
+                        AND c.city_id = ANY($6)
+                  ))
+                "#,
+            )
+            .bind(NotificationType::System)
+            .bind(&banner.title)
+            .bind(&banner.message)
+            .bind(serde_json::json!({ "banner_id": banner.id, "link_url": banner.link_url }))
+            .bind(&banner.target_roles)
+            .bind(&banner.target_cities)
+            .execute(pool)
+            .await?;
+
+            tracing::info!(
+                "Banner {} pushed to {} users",
+                banner.id,
+                result.rows_affected()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use crate::error::{AppError, AppResult};
+use crate::models::Barrier;
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS};
+use serde_json::json;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Сколько ждём ответа контроллера шлагбаума
+const DEVICE_TIMEOUT: Duration = Duration::from_secs(5);
+
+const DEFAULT_HTTP_PORT: i32 = 80;
+const DEFAULT_MQTT_PORT: i32 = 1883;
+
+/// Управление контроллером шлагбаума
+#[axum::async_trait]
+pub trait BarrierDriver: Send + Sync {
+    /// Открыть шлагбаум; ошибка означает, что команда до устройства не дошла
+    async fn open(&self, barrier: &Barrier) -> AppResult<()>;
+}
+
+/// Драйвер по `device_type` шлагбаума; без типа устройства открытие только фиксируется
+pub fn driver_for(barrier: &Barrier) -> AppResult<Box<dyn BarrierDriver>> {
+    match barrier.device_type.as_deref() {
+        None => Ok(Box::new(LogOnlyDriver)),
+        Some("http") | Some("relay") => Ok(Box::new(HttpRelayDriver)),
+        Some("mqtt") => Ok(Box::new(MqttDriver)),
+        Some(_) => Err(AppError::BadRequest(
+            "Шлагбаум не поддерживает удалённое открытие".to_string(),
+        )),
+    }
+}
+
+/// Открыть шлагбаум подходящим драйвером
+pub async fn open(barrier: &Barrier) -> AppResult<()> {
+    driver_for(barrier)?.open(barrier).await?;
+    tracing::info!("Barrier {} opened", barrier.id);
+    Ok(())
+}
+
+fn device_address(barrier: &Barrier, default_port: i32) -> AppResult<(&str, u16)> {
+    let host = barrier
+        .device_ip
+        .as_deref()
+        .ok_or_else(|| AppError::Barrier("У шлагбаума не указан адрес устройства".to_string()))?;
+    let port = u16::try_from(barrier.device_port.unwrap_or(default_port))
+        .map_err(|_| AppError::Barrier("Неверный порт устройства".to_string()))?;
+
+    Ok((host, port))
+}
+
+/// Шлагбаум без подключённого контроллера: открытие только пишется в журнал
+pub struct LogOnlyDriver;
+
+#[axum::async_trait]
+impl BarrierDriver for LogOnlyDriver {
+    async fn open(&self, barrier: &Barrier) -> AppResult<()> {
+        tracing::info!("Barrier {} has no device, opening is only logged", barrier.id);
+        Ok(())
+    }
+}
+
+/// HTTP-реле: `POST http://<device_ip>:<device_port>/open` с ключом шлагбаума
+pub struct HttpRelayDriver;
+
+#[axum::async_trait]
+impl BarrierDriver for HttpRelayDriver {
+    async fn open(&self, barrier: &Barrier) -> AppResult<()> {
+        let (host, port) = device_address(barrier, DEFAULT_HTTP_PORT)?;
+        let client = reqwest::Client::builder()
+            .timeout(DEVICE_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Barrier(e.to_string()))?;
+
+        let mut request = client.post(format!("http://{}:{}/open", host, port));
+        if let Some(api_key) = &barrier.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        // Адрес устройства остаётся в логе и не уходит клиенту
+        let response = request.send().await.map_err(|e| {
+            tracing::warn!("Barrier {} relay request failed: {}", barrier.id, e);
+            AppError::Barrier(format!("Шлагбаум {} недоступен", barrier.name))
+        })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Barrier(format!(
+                "Шлагбаум {} вернул {}",
+                barrier.name,
+                response.status()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// MQTT: брокер на `device_ip:device_port`, команда в `localhood/barriers/<id>/open`.
+/// Успехом считается подтверждение контроллера в `localhood/barriers/<id>/opened`
+/// с тем же `command_id`: PubAck означает лишь, что команду принял брокер
+pub struct MqttDriver;
+
+#[axum::async_trait]
+impl BarrierDriver for MqttDriver {
+    async fn open(&self, barrier: &Barrier) -> AppResult<()> {
+        let (host, port) = device_address(barrier, DEFAULT_MQTT_PORT)?;
+
+        let mut options = MqttOptions::new(format!("localhood-{}", Uuid::new_v4()), host, port);
+        options.set_keep_alive(DEVICE_TIMEOUT);
+        if let Some(api_key) = &barrier.api_key {
+            options.set_credentials(barrier.id.to_string(), api_key);
+        }
+
+        let (client, mut eventloop) = AsyncClient::new(options, 4);
+        let ack_topic = format!("localhood/barriers/{}/opened", barrier.id);
+        let command_id = Uuid::new_v4();
+        let command = json!({
+            "command": "open",
+            "command_id": command_id,
+            "issued_at": chrono::Utc::now(),
+        });
+        let queued = async {
+            client.subscribe(&ack_topic, QoS::AtLeastOnce).await?;
+            client
+                .publish(
+                    format!("localhood/barriers/{}/open", barrier.id),
+                    QoS::AtLeastOnce,
+                    false,
+                    command.to_string(),
+                )
+                .await
+        };
+        queued.await.map_err(|e| {
+            tracing::warn!("Barrier {} MQTT command not queued: {}", barrier.id, e);
+            AppError::Barrier(format!("Шлагбаум {} недоступен", barrier.name))
+        })?;
+
+        let confirmed = tokio::time::timeout(DEVICE_TIMEOUT, async {
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::Publish(publish)))
+                        if publish.topic == ack_topic && is_open_ack(&publish.payload, command_id) =>
+                    {
+                        return Ok(())
+                    }
+                    Ok(_) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+        })
+        .await;
+        let _ = client.try_disconnect();
+
+        match confirmed {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => {
+                tracing::warn!("Barrier {} MQTT connection failed: {}", barrier.id, e);
+                Err(AppError::Barrier(format!("Шлагбаум {} недоступен", barrier.name)))
+            }
+            Err(_) => Err(AppError::Barrier(format!(
+                "Шлагбаум {} не подтвердил открытие",
+                barrier.name
+            ))),
+        }
+    }
+}
+
+/// Подтверждение контроллера относится к отправленной команде
+fn is_open_ack(payload: &[u8], command_id: Uuid) -> bool {
+    serde_json::from_slice::<serde_json::Value>(payload)
+        .ok()
+        .and_then(|ack| ack.get("command_id")?.as_str()?.parse::<Uuid>().ok())
+        == Some(command_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn barrier(device_type: Option<&str>, device_ip: Option<&str>, device_port: Option<i32>) -> Barrier {
+        Barrier {
+            id: Uuid::new_v4(),
+            complex_id: Uuid::new_v4(),
+            name: "Въезд".to_string(),
+            location: None,
+            device_type: device_type.map(str::to_string),
+            device_ip: device_ip.map(str::to_string),
+            device_port,
+            api_key: None,
+            is_active: true,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn no_device_type_only_logs() {
+        let barrier = barrier(None, None, None);
+        assert!(driver_for(&barrier).is_ok());
+        assert!(open(&barrier).await.is_ok());
+    }
+
+    #[test]
+    fn known_and_unknown_device_types() {
+        for device_type in ["http", "relay", "mqtt"] {
+            assert!(driver_for(&barrier(Some(device_type), None, None)).is_ok());
+        }
+        assert!(matches!(
+            driver_for(&barrier(Some("zigbee"), None, None)),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn device_address_defaults_and_validates_port() {
+        let relay = barrier(Some("http"), Some("10.0.0.5"), None);
+        assert_eq!(device_address(&relay, DEFAULT_HTTP_PORT).unwrap(), ("10.0.0.5", 80));
+
+        assert!(device_address(&barrier(Some("http"), None, None), DEFAULT_HTTP_PORT).is_err());
+        assert!(device_address(&barrier(Some("http"), Some("10.0.0.5"), Some(70000)), DEFAULT_HTTP_PORT).is_err());
+    }
+
+    #[tokio::test]
+    async fn relay_error_hides_device_address() {
+        // Порт 1 на loopback закрыт: соединение отклоняется сразу
+        let relay = barrier(Some("http"), Some("127.0.0.1"), Some(1));
+        let Err(AppError::Barrier(message)) = open(&relay).await else {
+            panic!("expected barrier error");
+        };
+        assert!(!message.contains("127.0.0.1"), "{}", message);
+    }
+
+    #[test]
+    fn open_ack_matches_command() {
+        let command_id = Uuid::new_v4();
+        let ack = json!({"command_id": command_id, "status": "opened"}).to_string();
+        assert!(is_open_ack(ack.as_bytes(), command_id));
+
+        let other = json!({"command_id": Uuid::new_v4()}).to_string();
+        assert!(!is_open_ack(other.as_bytes(), command_id));
+        assert!(!is_open_ack(b"opened", command_id));
+    }
+}
@@ -1,10 +1,31 @@
 use crate::error::{AppError, AppResult};
-use crate::models::{BarrierAction, GuestAccess, GuestAccessStatus};
-use crate::services::{AuthService, SmsService};
+use crate::models::{
+    AnprEntryResponse, AnprMatch, BarrierAction, BlacklistEntry, GuestAccess, GuestAccessStatus,
+    GuestEntryNotificationPayload, GuestNotificationStatus, JobType, NotificationChannel,
+    NotificationType,
+};
+use crate::services::anomaly_service::BarrierPassage;
+use crate::services::sms_service::SmsDelivery;
+use crate::services::job_service::retry_delay;
+use crate::services::{
+    AnomalyService, AuthService, JobService, NotificationService, SchedulerService, SmsService,
+    TelegramService,
+};
+use crate::utils::normalize_vehicle_number;
+use serde_json::json;
 use chrono::{Duration, Utc};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+/// Как часто проверяем гостей, превысивших время визита
+const OVERSTAY_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Как часто переводим неиспользованные пропуска в expired
+const EXPIRE_ACCESS_INTERVAL_SECS: u64 = 300;
+
+/// Сколько раз пробуем отправить SMS о въезде гостя, прежде чем перейти на push
+const GUEST_ENTRY_SMS_ATTEMPTS: i32 = 3;
+
 pub struct BarrierService {
     sms_service: SmsService,
 }
@@ -14,6 +35,34 @@ impl BarrierService {
         Self { sms_service }
     }
 
+    /// Зарегистрировать периодические задачи по гостевым пропускам
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "barrier_check_overstays",
+            std::time::Duration::from_secs(OVERSTAY_CHECK_INTERVAL_SECS),
+            |pool, config| async move {
+                BarrierService::new(SmsService::new(config))
+                    .check_overstays(&pool)
+                    .await
+            },
+        );
+
+        scheduler.register(
+            "barrier_expire_old_access",
+            std::time::Duration::from_secs(EXPIRE_ACCESS_INTERVAL_SECS),
+            |pool, config| async move {
+                let expired = BarrierService::new(SmsService::new(config))
+                    .expire_old_access(&pool)
+                    .await?;
+                if expired > 0 {
+                    tracing::info!("Expired {} unused guest passes", expired);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_guest_access(
         &self,
         pool: &PgPool,
@@ -27,6 +76,12 @@ impl BarrierService {
         let access_code = AuthService::generate_access_code();
         let expires_at = Utc::now() + Duration::minutes(duration_minutes as i64);
 
+        let status = if Self::guest_approval_required(pool, complex_id).await? {
+            GuestAccessStatus::AwaitingApproval
+        } else {
+            GuestAccessStatus::Pending
+        };
+
         let guest_access = sqlx::query_as::<_, GuestAccess>(
             r#"
             INSERT INTO guest_access
@@ -44,13 +99,101 @@ impl BarrierService {
         .bind(&access_code)
         .bind(duration_minutes)
         .bind(expires_at)
-        .bind(GuestAccessStatus::Pending)
+        .bind(&status)
         .fetch_one(pool)
         .await?;
 
+        if status == GuestAccessStatus::AwaitingApproval {
+            let mut approver_ids = Self::guard_user_ids(pool, complex_id).await?;
+            let chairman: Option<(Option<Uuid>,)> =
+                sqlx::query_as("SELECT chairman_id FROM osi WHERE complex_id = $1")
+                    .bind(complex_id)
+                    .fetch_optional(pool)
+                    .await?;
+            approver_ids.extend(chairman.and_then(|(id,)| id));
+
+            let guest = guest_access.guest_name.as_deref().unwrap_or("Гость");
+            let body = match &guest_access.vehicle_number {
+                Some(vehicle) => format!("{}, авто {}", guest, vehicle),
+                None => guest.to_string(),
+            };
+
+            NotificationService::notify_users(
+                pool,
+                &approver_ids,
+                NotificationType::Security,
+                "Пропуск ждёт подтверждения",
+                Some(&body),
+                Some(json!({"guest_access_id": guest_access.id})),
+            )
+            .await?;
+        }
+
         Ok(guest_access)
     }
 
+    /// Требует ли ЖК подтверждения каждого гостевого пропуска
+    pub async fn guest_approval_required(pool: &PgPool, complex_id: Uuid) -> AppResult<bool> {
+        let required: Option<(bool,)> =
+            sqlx::query_as("SELECT guest_approval_required FROM complexes WHERE id = $1")
+                .bind(complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(required.is_some_and(|(r,)| r))
+    }
+
+    /// Подтвердить или отклонить пропуск, ожидающий проверки, и уведомить пригласившего жителя.
+    /// При подтверждении срок действия отсчитывается заново.
+    pub async fn review_guest_access(
+        pool: &PgPool,
+        access_id: Uuid,
+        reviewer_id: Uuid,
+        approve: bool,
+        decline_reason: Option<String>,
+    ) -> AppResult<GuestAccess> {
+        let updated = sqlx::query_as::<_, GuestAccess>(
+            r#"
+            UPDATE guest_access SET
+                status = $3,
+                expires_at = CASE WHEN $4 THEN NOW() + (duration_minutes || ' minutes')::interval ELSE expires_at END,
+                decline_reason = $5,
+                reviewed_by = $2,
+                reviewed_at = NOW()
+            WHERE id = $1 AND status = 'awaiting_approval'
+            RETURNING *
+            "#,
+        )
+        .bind(access_id)
+        .bind(reviewer_id)
+        .bind(if approve { GuestAccessStatus::Pending } else { GuestAccessStatus::Declined })
+        .bind(approve)
+        .bind(if approve { None } else { decline_reason })
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Пропуск не найден или уже рассмотрен".to_string()))?;
+
+        let guest = updated.guest_name.as_deref().unwrap_or("Гость");
+        let (title, body) = if approve {
+            ("Пропуск подтверждён", format!("{} может проехать по пропуску", guest))
+        } else {
+            let reason = updated.decline_reason.as_deref().unwrap_or("причина не указана");
+            ("Пропуск отклонён", format!("{}: {}", guest, reason))
+        };
+
+        NotificationService::notify_users(
+            pool,
+            &[updated.created_by],
+            NotificationType::Security,
+            title,
+            Some(&body),
+            Some(json!({"guest_access_id": updated.id, "status": updated.status})),
+        )
+        .await?;
+
+        Ok(updated)
+    }
+
     pub async fn process_entry(
         &self,
         pool: &PgPool,
@@ -72,6 +215,36 @@ impl BarrierService {
         .await?
         .ok_or_else(|| AppError::NotFound("Код доступа не найден или истёк".to_string()))?;
 
+        if guest_access.suspended_at.is_some() {
+            return Err(AppError::EntryDenied(
+                "Пропуск приостановлен до проверки председателем".to_string(),
+            ));
+        }
+
+        let vehicle_number = vehicle_number.or(guest_access.vehicle_number.as_deref());
+
+        self.ensure_not_blacklisted(
+            pool,
+            guest_access.complex_id,
+            vehicle_number,
+            guest_access.guest_phone.as_deref(),
+            barrier_id,
+            Some(guest_access.id),
+        )
+        .await?;
+
+        // Правила проверяются до открытия: приостановленный ими пропуск не пропускает
+        AnomalyService::inspect_entry(
+            pool,
+            BarrierPassage {
+                complex_id: guest_access.complex_id,
+                user_id: None,
+                guest_access_id: Some(guest_access.id),
+                vehicle_number,
+            },
+        )
+        .await?;
+
         // Обновить статус
         let updated = sqlx::query_as::<_, GuestAccess>(
             r#"
@@ -97,29 +270,238 @@ impl BarrierService {
         .bind(barrier_id)
         .bind(guest_access.id)
         .bind(BarrierAction::Entry)
-        .bind(vehicle_number.or(guest_access.vehicle_number.as_deref()))
+        .bind(vehicle_number)
         .execute(pool)
         .await?;
 
-        // Уведомить владельца
-        if let Some(owner_phone) = self.get_owner_phone(pool, guest_access.created_by).await? {
-            let guest_name = updated.guest_name.clone().unwrap_or_else(|| "Гость".to_string());
-            let time = Utc::now().format("%H:%M").to_string();
-
-            if let Err(e) = self.sms_service.send_guest_entry_notification(&owner_phone, &guest_name, &time).await {
-                tracing::error!("Failed to send entry notification: {}", e);
-            }
-
-            // Отметить, что владелец уведомлён
-            sqlx::query("UPDATE guest_access SET owner_notified = true WHERE id = $1")
-                .bind(updated.id)
-                .execute(pool)
+        // Уведомить владельца; неудачное SMS уходит в очередь повторов
+        let updated = match self.deliver_entry_notification(pool, &updated).await? {
+            (GuestNotificationStatus::Retrying, access) => {
+                JobService::enqueue_at(
+                    pool,
+                    JobType::GuestEntryNotification,
+                    &GuestEntryNotificationPayload {
+                        guest_access_id: access.id,
+                    },
+                    Utc::now() + retry_delay(access.owner_notification_attempts),
+                )
                 .await?;
+                access
+            }
+            (_, access) => access,
+        };
+
+        // Telegram дополняет SMS и не должен мешать ни ему, ни въезду
+        let telegram = TelegramService::enqueue(
+            pool,
+            &[updated.created_by],
+            NotificationType::Security,
+            format!(
+                "{} въехал в {}",
+                updated.guest_name.as_deref().unwrap_or("Гость"),
+                updated.entered_at.unwrap_or_else(Utc::now).format("%H:%M")
+            ),
+        )
+        .await;
+        if let Err(e) = telegram {
+            tracing::warn!("Telegram entry notice for pass {} not queued: {}", updated.id, e);
         }
 
         Ok(updated)
     }
 
+    /// Въезд по номеру, распознанному камерой шлагбаума: сначала автомобили жителей,
+    /// затем действующие гостевые пропуска с этим номером
+    pub async fn process_plate_entry(
+        &self,
+        pool: &PgPool,
+        complex_id: Uuid,
+        barrier_id: Uuid,
+        plate: &str,
+    ) -> AppResult<AnprEntryResponse> {
+        let vehicle_number = normalize_vehicle_number(plate);
+        if vehicle_number.is_empty() {
+            return Err(AppError::BadRequest("Номер не распознан".to_string()));
+        }
+
+        let resident: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT user_id FROM resident_vehicles WHERE complex_id = $1 AND vehicle_number = $2",
+        )
+        .bind(complex_id)
+        .bind(&vehicle_number)
+        .fetch_optional(pool)
+        .await?;
+
+        if let Some((user_id,)) = resident {
+            self.ensure_not_blacklisted(
+                pool,
+                complex_id,
+                Some(&vehicle_number),
+                None,
+                Some(barrier_id),
+                None,
+            )
+            .await?;
+
+            AnomalyService::inspect_entry(
+                pool,
+                BarrierPassage {
+                    complex_id,
+                    user_id: Some(user_id),
+                    guest_access_id: None,
+                    vehicle_number: Some(&vehicle_number),
+                },
+            )
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO barrier_access_logs (complex_id, barrier_id, user_id, action, vehicle_number)
+                VALUES ($1, $2, $3, 'entry', $4)
+                "#,
+            )
+            .bind(complex_id)
+            .bind(barrier_id)
+            .bind(user_id)
+            .bind(&vehicle_number)
+            .execute(pool)
+            .await?;
+
+            return Ok(AnprEntryResponse {
+                open: true,
+                vehicle_number,
+                matched: AnprMatch::Resident,
+                user_id: Some(user_id),
+                guest_access_id: None,
+            });
+        }
+
+        // Номера в пропусках вводят жители как попало, поэтому сравниваем нормализованные
+        let pending: Vec<(String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT access_code, vehicle_number FROM guest_access
+            WHERE complex_id = $1 AND status = 'pending' AND expires_at > NOW()
+              AND vehicle_number IS NOT NULL
+            ORDER BY expires_at
+            "#,
+        )
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?;
+
+        let access_code = pending
+            .into_iter()
+            .find(|(_, number)| {
+                number
+                    .as_deref()
+                    .is_some_and(|n| normalize_vehicle_number(n) == vehicle_number)
+            })
+            .map(|(code, _)| code);
+
+        let Some(access_code) = access_code else {
+            self.ensure_not_blacklisted(
+                pool,
+                complex_id,
+                Some(&vehicle_number),
+                None,
+                Some(barrier_id),
+                None,
+            )
+            .await?;
+            return Err(AppError::EntryDenied(format!(
+                "автомобиль {} не зарегистрирован",
+                vehicle_number
+            )));
+        };
+
+        let access = self
+            .process_entry(pool, &access_code, Some(&vehicle_number), Some(barrier_id))
+            .await?;
+
+        Ok(AnprEntryResponse {
+            open: true,
+            vehicle_number,
+            matched: AnprMatch::Guest,
+            user_id: None,
+            guest_access_id: Some(access.id),
+        })
+    }
+
+    /// Проверить госномер и телефон по чёрному списку ЖК перед открытием.
+    /// При совпадении фиксирует отказ, уведомляет охрану и возвращает `EntryDenied`.
+    pub async fn ensure_not_blacklisted(
+        &self,
+        pool: &PgPool,
+        complex_id: Uuid,
+        vehicle_number: Option<&str>,
+        phone: Option<&str>,
+        barrier_id: Option<Uuid>,
+        guest_access_id: Option<Uuid>,
+    ) -> AppResult<()> {
+        let vehicle_number = vehicle_number.map(normalize_vehicle_number);
+
+        if vehicle_number.is_none() && phone.is_none() {
+            return Ok(());
+        }
+
+        let entry = sqlx::query_as::<_, BlacklistEntry>(
+            r#"
+            SELECT * FROM barrier_blacklist
+            WHERE complex_id = $1 AND is_active = true
+              AND (expires_at IS NULL OR expires_at > NOW())
+              AND (vehicle_number = $2 OR person_phone = $3)
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(complex_id)
+        .bind(&vehicle_number)
+        .bind(phone)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(entry) = entry else {
+            return Ok(());
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO barrier_denials (complex_id, barrier_id, blacklist_id, guest_access_id, vehicle_number)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(complex_id)
+        .bind(barrier_id)
+        .bind(entry.id)
+        .bind(guest_access_id)
+        .bind(&vehicle_number)
+        .execute(pool)
+        .await?;
+
+        let guard_ids = Self::guard_user_ids(pool, complex_id).await?;
+
+        let subject = entry
+            .vehicle_number
+            .clone()
+            .or_else(|| entry.person_name.clone())
+            .unwrap_or_else(|| "Посетитель".to_string());
+        let body = format!("{} в чёрном списке: {}", subject, entry.reason);
+
+        NotificationService::notify_users(
+            pool,
+            &guard_ids,
+            NotificationType::Security,
+            "Отказ во въезде",
+            Some(&body),
+            Some(json!({"blacklist_id": entry.id, "barrier_id": barrier_id, "guest_access_id": guest_access_id})),
+        )
+        .await?;
+
+        tracing::info!("Entry denied by blacklist entry {} in complex {}", entry.id, complex_id);
+
+        Err(AppError::EntryDenied("въезд в ЖК ограничен".to_string()))
+    }
+
     pub async fn process_exit(
         &self,
         pool: &PgPool,
@@ -169,24 +551,20 @@ impl BarrierService {
         Ok(updated)
     }
 
-    pub async fn cancel_access(&self, pool: &PgPool, access_id: Uuid, user_id: Uuid) -> AppResult<()> {
-        let result = sqlx::query(
+    pub async fn cancel_access(&self, pool: &PgPool, access_id: Uuid, user_id: Uuid) -> AppResult<GuestAccess> {
+        sqlx::query_as::<_, GuestAccess>(
             r#"
             UPDATE guest_access
             SET status = 'cancelled'
-            WHERE id = $1 AND created_by = $2 AND status = 'pending'
+            WHERE id = $1 AND created_by = $2 AND status IN ('awaiting_approval', 'pending')
+            RETURNING *
             "#,
         )
         .bind(access_id)
         .bind(user_id)
-        .execute(pool)
-        .await?;
-
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("Гостевой доступ не найден".to_string()));
-        }
-
-        Ok(())
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Гостевой доступ не найден".to_string()))
     }
 
     pub async fn check_overstays(&self, pool: &PgPool) -> AppResult<()> {
@@ -206,12 +584,40 @@ impl BarrierService {
             if let Some(owner_phone) = self.get_owner_phone(pool, access.created_by).await? {
                 let guest_name = access.guest_name.clone().unwrap_or_else(|| "Гость".to_string());
 
-                if let Err(e) = self.sms_service.send_overstay_notification(
-                    &owner_phone,
-                    &guest_name,
-                    access.duration_minutes
-                ).await {
-                    tracing::error!("Failed to send overstay notification: {}", e);
+                // Без SMS (отключены в настройках или исчерпан лимит ОСИ) владелец узнаёт из приложения
+                let sms = if Self::owner_accepts_sms(pool, access.created_by).await? {
+                    Some(
+                        self.sms_service
+                            .send_overstay_notification(
+                                pool,
+                                access.complex_id,
+                                &owner_phone,
+                                &guest_name,
+                                access.duration_minutes,
+                            )
+                            .await,
+                    )
+                } else {
+                    None
+                };
+
+                match sms {
+                    Some(Ok(SmsDelivery::OverBudget)) | None => {
+                        NotificationService::notify_users(
+                            pool,
+                            &[access.created_by],
+                            NotificationType::Security,
+                            "Гость не выехал",
+                            Some(&format!(
+                                "{} не выехал. Прошло {} мин.",
+                                guest_name, access.duration_minutes
+                            )),
+                            Some(json!({"guest_access_id": access.id})),
+                        )
+                        .await?;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => tracing::error!("Failed to send overstay notification: {}", e),
                 }
 
                 sqlx::query("UPDATE guest_access SET overstay_notified = true WHERE id = $1")
@@ -224,12 +630,115 @@ impl BarrierService {
         Ok(())
     }
 
+    /// Повтор SMS о въезде из очереди задач. Ошибка возвращается, чтобы задача
+    /// повторилась; после последней попытки владелец получает push
+    pub async fn retry_entry_notification(&self, pool: &PgPool, access_id: Uuid) -> AppResult<()> {
+        let access = sqlx::query_as::<_, GuestAccess>("SELECT * FROM guest_access WHERE id = $1")
+            .bind(access_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Гостевой доступ не найден".to_string()))?;
+
+        if access.owner_notification_status != Some(GuestNotificationStatus::Retrying) {
+            return Ok(());
+        }
+
+        match self.deliver_entry_notification(pool, &access).await? {
+            (GuestNotificationStatus::Retrying, access) => Err(AppError::Sms(
+                access
+                    .owner_notification_error
+                    .unwrap_or_else(|| "SMS не отправлено".to_string()),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Одна попытка уведомить владельца о въезде гостя. Если SMS не отправить
+    /// (выключены в ОСИ или у владельца, исчерпан лимит ОСИ, нет телефона или попытки кончились),
+    /// уходит push. Возвращает итог и пропуск с записанным статусом
+    async fn deliver_entry_notification(
+        &self,
+        pool: &PgPool,
+        access: &GuestAccess,
+    ) -> AppResult<(GuestNotificationStatus, GuestAccess)> {
+        let guest_name = access.guest_name.clone().unwrap_or_else(|| "Гость".to_string());
+        let time = access.entered_at.unwrap_or_else(Utc::now).format("%H:%M").to_string();
+        let attempts = access.owner_notification_attempts + 1;
+
+        let owner_phone = if Self::owner_accepts_sms(pool, access.created_by).await? {
+            self.get_owner_phone(pool, access.created_by).await?
+        } else {
+            None
+        };
+        let sms = match owner_phone {
+            Some(owner_phone) => Some(
+                self.sms_service
+                    .send_guest_entry_notification(
+                        pool,
+                        access.complex_id,
+                        &owner_phone,
+                        &guest_name,
+                        &time,
+                    )
+                    .await,
+            ),
+            None => None,
+        };
+
+        let (status, attempts, error) = match sms {
+            Some(Ok(SmsDelivery::Sent)) => (GuestNotificationStatus::Sent, attempts, None),
+            Some(Err(e)) if attempts < GUEST_ENTRY_SMS_ATTEMPTS => {
+                tracing::warn!("Entry notification SMS failed (attempt {}): {}", attempts, e);
+                (GuestNotificationStatus::Retrying, attempts, Some(e.to_string()))
+            }
+            sms => {
+                let (attempts, error) = match sms {
+                    Some(Err(e)) => {
+                        tracing::error!("Entry notification SMS failed, falling back to push: {}", e);
+                        (attempts, Some(e.to_string()))
+                    }
+                    _ => (access.owner_notification_attempts, None),
+                };
+                NotificationService::notify_users(
+                    pool,
+                    &[access.created_by],
+                    NotificationType::Security,
+                    "Гость въехал",
+                    Some(&format!("{} въехал в {}", guest_name, time)),
+                    Some(json!({"guest_access_id": access.id})),
+                )
+                .await?;
+                (GuestNotificationStatus::PushSent, attempts, error)
+            }
+        };
+
+        let updated = sqlx::query_as::<_, GuestAccess>(
+            r#"
+            UPDATE guest_access
+            SET owner_notification_status = $2,
+                owner_notification_attempts = $3,
+                owner_notification_error = $4,
+                owner_notified = $2 <> 'retrying'
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(access.id)
+        .bind(status)
+        .bind(attempts)
+        .bind(error)
+        .fetch_one(pool)
+        .await?;
+
+        Ok((status, updated))
+    }
+
     pub async fn expire_old_access(&self, pool: &PgPool) -> AppResult<i64> {
         let result = sqlx::query(
             r#"
             UPDATE guest_access
             SET status = 'expired'
-            WHERE status = 'pending' AND expires_at < NOW()
+            WHERE status IN ('awaiting_approval', 'pending') AND expires_at < NOW()
             "#,
         )
         .execute(pool)
@@ -238,6 +747,35 @@ impl BarrierService {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Аккаунты охранников ЖК
+    async fn guard_user_ids(pool: &PgPool, complex_id: Uuid) -> AppResult<Vec<Uuid>> {
+        let guard_ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT w.user_id FROM osi_workers w
+            JOIN osi o ON o.id = w.osi_id
+            WHERE o.complex_id = $1 AND w.role = 'guard' AND w.is_active = true AND w.user_id IS NOT NULL
+            "#,
+        )
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(guard_ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Владелец не отключил SMS об охране в настройках уведомлений
+    async fn owner_accepts_sms(pool: &PgPool, user_id: Uuid) -> AppResult<bool> {
+        let recipients = NotificationService::recipients(
+            pool,
+            &[user_id],
+            NotificationType::Security,
+            NotificationChannel::Sms,
+        )
+        .await?;
+
+        Ok(!recipients.is_empty())
+    }
+
     async fn get_owner_phone(&self, pool: &PgPool, user_id: Uuid) -> AppResult<Option<String>> {
         let result = sqlx::query_as::<_, (String,)>(
             "SELECT phone FROM users WHERE id = $1"
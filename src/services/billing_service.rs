@@ -0,0 +1,383 @@
+use crate::error::AppResult;
+use crate::models::{EmailTemplate, NotificationType, Tariff, TariffBillingResult};
+use crate::services::shared_charge_service::{month_end, next_month_start};
+use crate::services::{EmailService, NotificationService, SchedulerService, TelegramService};
+use chrono::{Datelike, NaiveDate, Utc};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashSet;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Срок оплаты счёта — до этого числа месяца, следующего за расчётным
+const BILL_DUE_DAY: u32 = 25;
+
+/// Как часто проверяем просрочку и начисляем пеню; пеня считается по дням, повторный запуск за день ничего не меняет
+const PENALTY_INTERVAL_SECS: u64 = 3600;
+
+pub struct BillingService;
+
+impl BillingService {
+    /// Зарегистрировать периодические задачи начислений
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "bill_penalties",
+            Duration::from_secs(PENALTY_INTERVAL_SECS),
+            |pool, config| async move {
+                let today = Utc::now().date_naive();
+                let overdue = BillingService::mark_overdue(&pool, today, config.penalty_daily_rate).await?;
+                let accrued = BillingService::accrue_penalties(&pool, today, config.penalty_daily_rate).await?;
+                if overdue > 0 || accrued > 0 {
+                    tracing::info!("Marked {} bills overdue, accrued penalty on {}", overdue, accrued);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Перевести неоплаченные счета с истёкшим сроком в просроченные и уведомить жителей
+    pub async fn mark_overdue(pool: &PgPool, today: NaiveDate, daily_rate: Decimal) -> AppResult<u64> {
+        let overdue: Vec<(Uuid, Uuid, NaiveDate, NaiveDate, Decimal)> = sqlx::query_as(
+            r#"
+            UPDATE bills SET status = 'overdue'
+            WHERE status = 'pending' AND due_date < $1
+            RETURNING id, apartment_id, period_start, due_date, total_amount - COALESCE(paid_amount, 0)
+            "#,
+        )
+        .bind(today)
+        .fetch_all(pool)
+        .await?;
+
+        let percent = (daily_rate * Decimal::ONE_HUNDRED).normalize();
+        for (bill_id, apartment_id, period_start, due_date, unpaid) in &overdue {
+            let recipients: Vec<(Uuid,)> = sqlx::query_as(
+                r#"
+                SELECT u FROM apartments a, UNNEST(ARRAY[a.owner_id, a.resident_id]) AS u
+                WHERE a.id = $1 AND u IS NOT NULL
+                "#,
+            )
+            .bind(apartment_id)
+            .fetch_all(pool)
+            .await?;
+            let user_ids: Vec<Uuid> = recipients.into_iter().map(|(id,)| id).collect();
+
+            let body = format!(
+                "Счёт за {} не оплачен до {}, к оплате {} ₸. Начисляется пеня {}% за каждый день просрочки.",
+                period_start.format("%m.%Y"),
+                due_date.format("%d.%m.%Y"),
+                unpaid,
+                percent
+            );
+            NotificationService::notify_users(
+                pool,
+                &user_ids,
+                NotificationType::Bill,
+                "Счёт просрочен",
+                Some(&body),
+                Some(json!({"bill_id": bill_id})),
+            )
+            .await?;
+            // Счёт уже просрочен: сбой Telegram не должен оставить без уведомлений остальные
+            if let Err(e) = TelegramService::enqueue(pool, &user_ids, NotificationType::Bill, body).await {
+                tracing::warn!("Telegram overdue notice for bill {} not queued: {}", bill_id, e);
+            }
+        }
+
+        Ok(overdue.len() as u64)
+    }
+
+    /// Начислить пеню по просроченным счетам за дни с прошлого начисления.
+    /// База — неоплаченная часть счёта без учёта уже начисленной пени.
+    pub async fn accrue_penalties(pool: &PgPool, today: NaiveDate, daily_rate: Decimal) -> AppResult<u64> {
+        if daily_rate <= Decimal::ZERO {
+            return Ok(0);
+        }
+
+        let result = sqlx::query(
+            r#"
+            WITH accrual AS (
+                SELECT id,
+                       ROUND(
+                           GREATEST(amount + COALESCE(debt, 0) - COALESCE(paid_amount, 0), 0)
+                           * $2 * ($1 - COALESCE(penalty_accrued_until, due_date)),
+                           2
+                       ) AS added
+                FROM bills
+                WHERE status = 'overdue' AND COALESCE(penalty_accrued_until, due_date) < $1
+            )
+            UPDATE bills b SET
+                penalty = COALESCE(b.penalty, 0) + accrual.added,
+                total_amount = b.total_amount + accrual.added,
+                penalty_accrued_until = $1
+            FROM accrual
+            WHERE b.id = accrual.id
+            "#,
+        )
+        .bind(today)
+        .bind(daily_rate)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Неоплаченный счёт квартиры за период; если его нет — создаётся пустой
+    pub async fn pending_bill(
+        conn: &mut PgConnection,
+        apartment_id: Uuid,
+        complex_id: Uuid,
+        period_start: NaiveDate,
+    ) -> AppResult<Uuid> {
+        let existing: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT id FROM bills
+            WHERE apartment_id = $1 AND period_start = $2 AND status IN ('pending', 'overdue')
+            ORDER BY created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(apartment_id)
+        .bind(period_start)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        if let Some((id,)) = existing {
+            return Ok(id);
+        }
+
+        let period_end = month_end(period_start);
+        let due_date = next_month_start(period_start)
+            .with_day(BILL_DUE_DAY)
+            .unwrap_or(period_end);
+
+        let (id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO bills (apartment_id, complex_id, period_start, period_end, amount, total_amount, due_date)
+            VALUES ($1, $2, $3, $4, 0, 0, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(apartment_id)
+        .bind(complex_id)
+        .bind(period_start)
+        .bind(period_end)
+        .bind(due_date)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(id)
+    }
+
+    /// Тарифы ЖК, действовавшие хотя бы один день периода
+    pub async fn tariffs_for_period(
+        pool: &PgPool,
+        complex_id: Uuid,
+        period_start: NaiveDate,
+        period_end: NaiveDate,
+    ) -> AppResult<Vec<Tariff>> {
+        let tariffs = sqlx::query_as::<_, Tariff>(
+            r#"
+            SELECT * FROM tariffs
+            WHERE complex_id = $1
+              AND effective_from <= $3
+              AND (effective_to IS NULL OR effective_to >= $2)
+            ORDER BY utility_type, effective_from
+            "#,
+        )
+        .bind(complex_id)
+        .bind(period_start)
+        .bind(period_end)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tariffs)
+    }
+
+    /// Начислить по тарифам за месяц, в который попадает `period`.
+    /// По каждой услуге берётся тариф, вступивший в силу последним; уже начисленные строки повторно не создаются.
+    pub async fn generate_from_tariffs(
+        pool: &PgPool,
+        complex_id: Uuid,
+        period: NaiveDate,
+    ) -> AppResult<TariffBillingResult> {
+        let period_start = period.with_day(1).unwrap_or(period);
+        let period_end = month_end(period_start);
+
+        let mut tx = pool.begin().await?;
+
+        let tariffs = sqlx::query_as::<_, Tariff>(
+            r#"
+            SELECT DISTINCT ON (utility_type) * FROM tariffs
+            WHERE complex_id = $1
+              AND effective_from <= $3
+              AND (effective_to IS NULL OR effective_to >= $2)
+            ORDER BY utility_type, effective_from DESC
+            "#,
+        )
+        .bind(complex_id)
+        .bind(period_start)
+        .bind(period_end)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut bills = HashSet::new();
+        let mut items = 0;
+        let mut total_amount = Decimal::ZERO;
+
+        for tariff in tariffs {
+            // База начисления по квартирам, где эта услуга за период ещё не начислена по тарифу
+            let quantities: Vec<(Uuid, Option<Decimal>)> = sqlx::query_as(
+                r#"
+                SELECT a.id,
+                       CASE $5::tariff_basis
+                           WHEN 'area' THEN a.area
+                           WHEN 'apartment' THEN 1
+                           ELSE (
+                               SELECT SUM(r.consumption)
+                               FROM meter_readings r
+                               JOIN meters m ON m.id = r.meter_id
+                               WHERE m.apartment_id = a.id
+                                 AND m.utility_type = $2
+                                 AND r.reading_date BETWEEN $3 AND $4
+                           )
+                       END
+                FROM apartments a
+                WHERE a.complex_id = $1
+                  AND NOT EXISTS (
+                      SELECT 1 FROM bill_items bi
+                      JOIN bills b ON b.id = bi.bill_id
+                      WHERE b.apartment_id = a.id
+                        AND b.period_start = $3
+                        AND bi.utility_type = $2
+                        AND bi.tariff_id IS NOT NULL
+                  )
+                ORDER BY a.building, a.number
+                "#,
+            )
+            .bind(complex_id)
+            .bind(&tariff.utility_type)
+            .bind(period_start)
+            .bind(period_end)
+            .bind(tariff.basis)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            for (apartment_id, quantity) in quantities {
+                let Some(quantity) = quantity.filter(|q| *q > Decimal::ZERO) else {
+                    continue;
+                };
+                let amount = (quantity * tariff.rate)
+                    .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+                if amount <= Decimal::ZERO {
+                    continue;
+                }
+
+                let bill_id = Self::pending_bill(&mut tx, apartment_id, complex_id, period_start).await?;
+
+                sqlx::query(
+                    r#"
+                    INSERT INTO bill_items (bill_id, utility_type, quantity, unit, rate, amount, tariff_id)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7)
+                    "#,
+                )
+                .bind(bill_id)
+                .bind(&tariff.utility_type)
+                .bind(quantity)
+                .bind(&tariff.unit)
+                .bind(tariff.rate)
+                .bind(amount)
+                .bind(tariff.id)
+                .execute(&mut *tx)
+                .await?;
+
+                sqlx::query(
+                    "UPDATE bills SET amount = amount + $2, total_amount = total_amount + $2 WHERE id = $1",
+                )
+                .bind(bill_id)
+                .bind(amount)
+                .execute(&mut *tx)
+                .await?;
+
+                bills.insert(bill_id);
+                items += 1;
+                total_amount += amount;
+            }
+        }
+
+        tx.commit().await?;
+
+        // Счета уже выставлены: ошибка рассылки не должна превращать начисление в ошибку
+        let bill_ids: Vec<Uuid> = bills.iter().copied().collect();
+        if let Err(e) = Self::notify_issued(pool, &bill_ids).await {
+            tracing::warn!("Bill notifications for complex {} not queued: {}", complex_id, e);
+        }
+
+        tracing::info!(
+            "Tariff billing for complex {} period {}: {} items, {}",
+            complex_id,
+            period_start,
+            items,
+            total_amount
+        );
+
+        Ok(TariffBillingResult {
+            period_start,
+            period_end,
+            bills: bills.len() as i64,
+            items,
+            total_amount,
+        })
+    }
+
+    /// Письма и сообщения в Telegram о выставленных счетах собственнику и жильцу квартиры
+    async fn notify_issued(pool: &PgPool, bill_ids: &[Uuid]) -> AppResult<()> {
+        let bills: Vec<(Uuid, NaiveDate, Decimal, NaiveDate, Vec<Uuid>)> = sqlx::query_as(
+            r#"
+            SELECT b.id, b.period_start, b.total_amount, b.due_date,
+                   ARRAY_REMOVE(ARRAY[a.owner_id, a.resident_id], NULL)
+            FROM bills b
+            JOIN apartments a ON a.id = b.apartment_id
+            WHERE b.id = ANY($1)
+            "#,
+        )
+        .bind(bill_ids)
+        .fetch_all(pool)
+        .await?;
+
+        for (bill_id, period_start, total_amount, due_date, user_ids) in bills {
+            let telegram = TelegramService::enqueue(
+                pool,
+                &user_ids,
+                NotificationType::Bill,
+                format!(
+                    "Выставлен счёт за {} на сумму {} ₸. Оплатить до {}",
+                    period_start.format("%m.%Y"),
+                    total_amount,
+                    due_date.format("%d.%m.%Y")
+                ),
+            )
+            .await;
+            if let Err(e) = telegram {
+                tracing::warn!("Telegram notice for bill {} not queued: {}", bill_id, e);
+            }
+
+            let email = EmailService::enqueue(
+                pool,
+                &user_ids,
+                EmailTemplate::BillIssued {
+                    bill_id,
+                    period_start,
+                    total_amount,
+                    due_date,
+                },
+            )
+            .await;
+            if let Err(e) = email {
+                tracing::warn!("Email for bill {} not queued: {}", bill_id, e);
+            }
+        }
+
+        Ok(())
+    }
+}
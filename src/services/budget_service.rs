@@ -0,0 +1,66 @@
+use crate::error::AppResult;
+use crate::models::BudgetStatus;
+use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Варианты голосования за бюджет: первый — «За», второй — «Против»
+pub const BUDGET_VOTING_OPTIONS: [&str; 2] = ["За", "Против"];
+
+pub struct BudgetService;
+
+impl BudgetService {
+    /// Подвести итог голосования, если оно было за утверждение бюджета
+    pub async fn resolve_voting(conn: &mut PgConnection, voting_id: Uuid) -> AppResult<()> {
+        let budget: Option<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM osi_budgets WHERE voting_id = $1 AND status = 'voting'")
+                .bind(voting_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        let Some((budget_id,)) = budget else {
+            return Ok(());
+        };
+
+        let (for_weight, against_weight, quorum_reached): (Decimal, Decimal, Option<bool>) =
+            sqlx::query_as(
+                r#"
+                SELECT
+                    COALESCE(SUM(v.vote_weight) FILTER (WHERE o.sort_order = 0), 0),
+                    COALESCE(SUM(v.vote_weight) FILTER (WHERE o.sort_order = 1), 0),
+                    (SELECT quorum_reached FROM votings WHERE id = $1)
+                FROM voting_options o
+                LEFT JOIN votes v ON v.option_id = o.id
+                WHERE o.voting_id = $1
+                "#,
+            )
+            .bind(voting_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        // Без кворума бюджет не утверждается
+        let status = if quorum_reached != Some(false) && for_weight > against_weight {
+            BudgetStatus::Approved
+        } else {
+            BudgetStatus::Rejected
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE osi_budgets
+            SET status = $2,
+                approved_at = CASE WHEN $2 = 'approved'::budget_status THEN NOW() END,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(budget_id)
+        .bind(&status)
+        .execute(&mut *conn)
+        .await?;
+
+        tracing::info!("Budget {} resolved as {:?} by voting {}", budget_id, status, voting_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::error::AppResult;
+use crate::models::{MarketplaceListing, ScheduledChatMessage};
+use crate::services::SchedulerService;
+use sqlx::{PgExecutor, PgPool};
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Как часто отправляем наступившие отложенные сообщения
+const SCHEDULED_DISPATCH_INTERVAL_SECS: u64 = 30;
+
+/// Сколько отложенных сообщений отправляем за один проход
+const SCHEDULED_DISPATCH_BATCH: i64 = 100;
+
+pub struct ChatService;
+
+impl ChatService {
+    /// Домовой чат: создаётся, когда в доме ЖК появляется первый житель
+    pub async fn ensure_building_chat<'e>(
+        db: impl PgExecutor<'e>,
+        complex_id: Uuid,
+        building: &str,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO chats (complex_id, chat_type, name, building)
+            SELECT $1, 'building', 'Чат дома ' || $2, $2
+            WHERE NOT EXISTS (
+                SELECT 1 FROM chats WHERE complex_id = $1 AND chat_type = 'building' AND building = $2
+            )
+            "#,
+        )
+        .bind(complex_id)
+        .bind(building)
+        .execute(db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Зарегистрировать периодические задачи чатов
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "chat_dispatch_scheduled",
+            Duration::from_secs(SCHEDULED_DISPATCH_INTERVAL_SECS),
+            |pool, _config| async move {
+                let sent = ChatService::dispatch_scheduled(&pool).await?;
+                if sent > 0 {
+                    tracing::info!("Dispatched {} scheduled chat messages", sent);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Отправить отложенные сообщения, время которых наступило
+    pub async fn dispatch_scheduled(pool: &PgPool) -> AppResult<usize> {
+        let mut tx = pool.begin().await?;
+
+        let due = sqlx::query_as::<_, ScheduledChatMessage>(
+            r#"
+            SELECT * FROM scheduled_chat_messages
+            WHERE status = 'pending' AND send_at <= NOW()
+            ORDER BY send_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(SCHEDULED_DISPATCH_BATCH)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for scheduled in &due {
+            let (message_id,): (uuid::Uuid,) = sqlx::query_as(
+                r#"
+                INSERT INTO chat_messages (chat_id, sender_id, content, attachment_url, attachment_type)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id
+                "#,
+            )
+            .bind(scheduled.chat_id)
+            .bind(scheduled.sender_id)
+            .bind(&scheduled.content)
+            .bind(&scheduled.attachment_url)
+            .bind(&scheduled.attachment_type)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE scheduled_chat_messages SET status = 'sent', message_id = $2, sent_at = NOW() WHERE id = $1",
+            )
+            .bind(scheduled.id)
+            .bind(message_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("UPDATE chats SET updated_at = NOW() WHERE id = $1")
+                .bind(scheduled.chat_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(due.len())
+    }
+
+    /// Приватный чат покупателя с продавцом по объявлению: существующий или новый
+    pub async fn listing_chat(
+        pool: &PgPool,
+        listing: &MarketplaceListing,
+        buyer_id: Uuid,
+    ) -> AppResult<Uuid> {
+        let mut tx = pool.begin().await?;
+
+        let created: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO chats (complex_id, chat_type, name, is_private, created_by, listing_id)
+            VALUES ($1, 'private', $2, true, $3, $4)
+            ON CONFLICT (listing_id, created_by) WHERE listing_id IS NOT NULL DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(listing.complex_id)
+        .bind(&listing.title)
+        .bind(buyer_id)
+        .bind(listing.id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let chat_id = match created {
+            Some((chat_id,)) => {
+                sqlx::query("INSERT INTO chat_members (chat_id, user_id) VALUES ($1, $2), ($1, $3)")
+                    .bind(chat_id)
+                    .bind(buyer_id)
+                    .bind(listing.seller_id)
+                    .execute(&mut *tx)
+                    .await?;
+                chat_id
+            }
+            None => {
+                let (chat_id,): (Uuid,) = sqlx::query_as(
+                    "SELECT id FROM chats WHERE listing_id = $1 AND created_by = $2",
+                )
+                .bind(listing.id)
+                .bind(buyer_id)
+                .fetch_one(&mut *tx)
+                .await?;
+                chat_id
+            }
+        };
+
+        tx.commit().await?;
+
+        Ok(chat_id)
+    }
+}
@@ -0,0 +1,357 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CommunicationExport, CommunicationExportPayload, CreateCommunicationExportRequest,
+    ExportFormat, ExportStatus, JobType,
+};
+use crate::services::document_service::{
+    CommunicationItem, CommunicationReport, LOCAL_OFFSET_SECS,
+};
+use crate::services::{DocumentService, FileService, JobService};
+use crate::utils::csv_row;
+
+/// Самый длинный период одной выгрузки, дней
+const MAX_EXPORT_DAYS: i64 = 366;
+
+#[derive(FromRow)]
+struct CommunicationRow {
+    sent_at: DateTime<Utc>,
+    kind: String,
+    title: String,
+    author: Option<String>,
+    recipients: Option<i64>,
+    opened: Option<i64>,
+    confirmed: Option<i64>,
+    views: Option<i64>,
+}
+
+/// Выгрузка истории объявлений, экстренных оповещений и рассылок ЖК
+pub struct CommunicationExportService;
+
+impl CommunicationExportService {
+    /// Поставить выгрузку в очередь; файл формирует фоновая задача
+    pub async fn request(
+        pool: &PgPool,
+        complex_id: Uuid,
+        requested_by: Uuid,
+        payload: &CreateCommunicationExportRequest,
+    ) -> AppResult<CommunicationExport> {
+        if payload.date_from > payload.date_to {
+            return Err(AppError::Validation(
+                "Начало периода позже его окончания".to_string(),
+            ));
+        }
+        if (payload.date_to - payload.date_from).num_days() >= MAX_EXPORT_DAYS {
+            return Err(AppError::Validation(format!(
+                "Период выгрузки — не больше {} дней",
+                MAX_EXPORT_DAYS
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let export = sqlx::query_as::<_, CommunicationExport>(
+            r#"
+            INSERT INTO communication_exports (complex_id, requested_by, date_from, date_to, format)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(complex_id)
+        .bind(requested_by)
+        .bind(payload.date_from)
+        .bind(payload.date_to)
+        .bind(payload.format)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        JobService::enqueue(
+            &mut *tx,
+            JobType::CommunicationExport,
+            &CommunicationExportPayload {
+                export_id: export.id,
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(export)
+    }
+
+    /// Сформировать файл выгрузки. Ошибка записывается в выгрузку,
+    /// а задача уходит на повтор
+    pub async fn generate(
+        pool: &PgPool,
+        file_service: &FileService,
+        export_id: Uuid,
+    ) -> AppResult<()> {
+        let export = sqlx::query_as::<_, CommunicationExport>(
+            "SELECT * FROM communication_exports WHERE id = $1",
+        )
+        .bind(export_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Выгрузка не найдена".to_string()))?;
+
+        if export.status == ExportStatus::Completed {
+            return Ok(());
+        }
+
+        match Self::build(pool, file_service, &export).await {
+            Ok((key, items_count)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE communication_exports
+                    SET status = 'completed', file_key = $2, items_count = $3, error = NULL,
+                        completed_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(export.id)
+                .bind(&key)
+                .bind(items_count)
+                .execute(pool)
+                .await?;
+                Ok(())
+            }
+            Err(e) => {
+                sqlx::query(
+                    "UPDATE communication_exports SET status = 'failed', error = $2 WHERE id = $1",
+                )
+                .bind(export.id)
+                .bind(e.to_string())
+                .execute(pool)
+                .await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn build(
+        pool: &PgPool,
+        file_service: &FileService,
+        export: &CommunicationExport,
+    ) -> AppResult<(String, i32)> {
+        let (complex_name,): (String,) = sqlx::query_as("SELECT name FROM complexes WHERE id = $1")
+            .bind(export.complex_id)
+            .fetch_one(pool)
+            .await?;
+
+        let report = CommunicationReport {
+            complex_name,
+            date_from: export.date_from,
+            date_to: export.date_to,
+            items: Self::items(pool, export).await?,
+            generated_at: Utc::now(),
+        };
+
+        let data = match export.format {
+            ExportFormat::Pdf => DocumentService::render_communication_report(&report)?,
+            // BOM, чтобы Excel открыл кириллицу в UTF-8
+            ExportFormat::Csv => format!("\u{feff}{}", communication_csv(&report)).into_bytes(),
+        };
+        let key = format!(
+            "exports/communications/{}/{}.{}",
+            export.complex_id,
+            export.id,
+            export.format.extension()
+        );
+        file_service
+            .put_file(&key, export.format.content_type(), data)
+            .await?;
+
+        Ok((key, report.items.len() as i32))
+    }
+
+    /// Объявления со статистикой их рассылки и рассылки без объявления, по времени
+    async fn items(
+        pool: &PgPool,
+        export: &CommunicationExport,
+    ) -> AppResult<Vec<CommunicationItem>> {
+        let (from, to) = local_period(export.date_from, export.date_to);
+        let rows = sqlx::query_as::<_, CommunicationRow>(
+            r#"
+            WITH broadcasts AS (
+                SELECT b.id, b.title, b.data, b.recipients_count, b.created_at,
+                       COUNT(n.id) FILTER (WHERE n.is_read) AS opened
+                FROM notification_broadcasts b
+                LEFT JOIN notifications n ON n.broadcast_id = b.id
+                WHERE b.complex_id = $1
+                GROUP BY b.id
+            )
+            SELECT a.published_at AS sent_at,
+                   CASE WHEN a.category = 'emergency' THEN 'emergency' ELSE 'announcement' END AS kind,
+                   a.title, u.display_name AS author,
+                   s.recipients, s.opened,
+                   (SELECT COUNT(*) FROM announcement_reads r WHERE r.announcement_id = a.id) AS confirmed,
+                   a.views_count::bigint AS views
+            FROM announcements a
+            JOIN users u ON u.id = a.author_id
+            LEFT JOIN LATERAL (
+                SELECT SUM(b.recipients_count)::bigint AS recipients, SUM(b.opened)::bigint AS opened
+                FROM broadcasts b
+                WHERE b.data->>'announcement_id' = a.id::text
+            ) s ON true
+            WHERE a.complex_id = $1
+              AND a.is_published
+              AND a.published_at >= $2
+              AND a.published_at < $3
+            UNION ALL
+            SELECT b.created_at, 'broadcast', b.title, NULL,
+                   b.recipients_count::bigint, b.opened, NULL, NULL
+            FROM broadcasts b
+            WHERE NOT COALESCE(b.data ? 'announcement_id', false)
+              AND b.created_at >= $2
+              AND b.created_at < $3
+            ORDER BY sent_at
+            "#,
+        )
+        .bind(export.complex_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| CommunicationItem {
+                sent_at: row.sent_at,
+                kind: kind_label(&row.kind).to_string(),
+                title: row.title,
+                author: row.author,
+                recipients: row.recipients,
+                opened: row.opened,
+                confirmed: row.confirmed,
+                views: row.views,
+            })
+            .collect())
+    }
+}
+
+/// Границы периода в UTC: сутки считаются по местному времени, как и даты в отчёте
+fn local_period(date_from: NaiveDate, date_to: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let midnight = |date: NaiveDate| {
+        date.and_time(NaiveTime::MIN).and_utc() - Duration::seconds(LOCAL_OFFSET_SECS)
+    };
+    (midnight(date_from), midnight(date_to + Duration::days(1)))
+}
+
+fn kind_label(kind: &str) -> &'static str {
+    match kind {
+        "emergency" => "Экстренное",
+        "announcement" => "Объявление",
+        _ => "Рассылка",
+    }
+}
+
+fn communication_csv(report: &CommunicationReport) -> String {
+    let mut csv = csv_row(&[
+        "Дата",
+        "Тип",
+        "Заголовок",
+        "Автор",
+        "Получателей",
+        "Открыли",
+        "Отметили прочитанным",
+        "Просмотры",
+    ]);
+    let count = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+    for item in &report.items {
+        let sent_at = item.sent_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        csv.push_str(&csv_row(&[
+            sent_at.format("%d.%m.%Y %H:%M").to_string(),
+            item.kind.clone(),
+            item.title.clone(),
+            item.author.clone().unwrap_or_default(),
+            count(item.recipients),
+            count(item.opened),
+            count(item.confirmed),
+            count(item.views),
+        ]));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn item(sent_at: DateTime<Utc>, kind: &str, title: &str) -> CommunicationItem {
+        CommunicationItem {
+            sent_at,
+            kind: kind.to_string(),
+            title: title.to_string(),
+            author: None,
+            recipients: Some(120),
+            opened: Some(45),
+            confirmed: None,
+            views: None,
+        }
+    }
+
+    fn report(items: Vec<CommunicationItem>) -> CommunicationReport {
+        CommunicationReport {
+            complex_name: "Алатау".to_string(),
+            date_from: NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            date_to: NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+            items,
+            generated_at: Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap(),
+        }
+    }
+
+    fn page_count(pdf: &[u8]) -> usize {
+        let text = String::from_utf8_lossy(pdf);
+        let count = text.split("/Count ").nth(1).unwrap();
+        count[..count.find(' ').unwrap()].parse().unwrap()
+    }
+
+    #[test]
+    fn period_follows_local_days() {
+        let (from, to) = local_period(
+            NaiveDate::from_ymd_opt(2026, 3, 1).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 3, 31).unwrap(),
+        );
+        assert_eq!(from, Utc.with_ymd_and_hms(2026, 2, 28, 19, 0, 0).unwrap());
+        assert_eq!(to, Utc.with_ymd_and_hms(2026, 3, 31, 19, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn csv_uses_local_time_and_blank_counters() {
+        let csv = communication_csv(&report(vec![item(
+            Utc.with_ymd_and_hms(2026, 3, 1, 20, 30, 0).unwrap(),
+            "Объявление",
+            "Отключение воды, 2 подъезд",
+        )]));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Дата,Тип,Заголовок"));
+        assert_eq!(
+            lines[1],
+            "02.03.2026 01:30,Объявление,\"Отключение воды, 2 подъезд\",,120,45,,"
+        );
+    }
+
+    #[test]
+    fn pdf_renders_empty_and_long_reports() {
+        let empty = DocumentService::render_communication_report(&report(Vec::new())).unwrap();
+        assert!(empty.starts_with(b"%PDF"));
+        assert_eq!(page_count(&empty), 1);
+
+        let items = (0..120)
+            .map(|i| {
+                item(
+                    Utc.with_ymd_and_hms(2026, 3, 1, 6, 0, 0).unwrap() + Duration::hours(i),
+                    "Рассылка",
+                    &format!("Рассылка №{}", i),
+                )
+            })
+            .collect();
+        let long = DocumentService::render_communication_report(&report(items)).unwrap();
+        assert!(page_count(&long) > 1);
+    }
+}
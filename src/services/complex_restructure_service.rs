@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use serde_json::json;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Complex, ComplexRestructureReport, MergeComplexRequest, NewAuditLog, NewFieldChange,
+    SplitComplexRequest,
+};
+use crate::services::{AuditService, FieldHistoryService};
+
+/// Таблицы, где у записей квартиры продублирован ЖК: при переезде квартиры он меняется вместе с ней
+const APARTMENT_SCOPED_TABLES: &[&str] = &[
+    "bills",
+    "maintenance_requests",
+    "join_requests",
+    "move_outs",
+    "apartment_intercom_codes",
+];
+
+/// Разделение и слияние ЖК. Настройки уровня ЖК (тарифы, шлагбаумы, камеры,
+/// графики работ) остаются у исходного ЖК
+pub struct ComplexRestructureService;
+
+impl ComplexRestructureService {
+    /// Выделить дома в новый ЖК; при `dry_run` транзакция откатывается
+    pub async fn split(
+        pool: &PgPool,
+        source: &Complex,
+        created_by: Uuid,
+        request_id: &str,
+        payload: &SplitComplexRequest,
+    ) -> AppResult<ComplexRestructureReport> {
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Укажите название нового ЖК".to_string()));
+        }
+
+        let buildings: Vec<String> = payload
+            .buildings
+            .iter()
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        if buildings.is_empty() {
+            return Err(AppError::Validation("Укажите дома для выделения".to_string()));
+        }
+
+        let present: Vec<(Option<String>,)> =
+            sqlx::query_as("SELECT DISTINCT building FROM apartments WHERE complex_id = $1")
+                .bind(source.id)
+                .fetch_all(pool)
+                .await?;
+        let present: BTreeSet<Option<String>> = present.into_iter().map(|(b,)| b).collect();
+
+        let missing: Vec<&str> = buildings
+            .iter()
+            .filter(|b| !present.contains(&Some(b.to_string())))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            return Err(AppError::Validation(format!(
+                "В ЖК нет домов: {}",
+                missing.join(", ")
+            )));
+        }
+        if present.len() == buildings.len() {
+            return Err(AppError::Validation(
+                "Нельзя выделить все дома ЖК — в исходном не останется квартир".to_string(),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let (target_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO complexes (
+                city_id, address_id, name, description, status, verified_at, verified_by,
+                is_sandbox, guest_approval_required, device_binding_enabled,
+                auto_suspend_anomalous_passes, created_by
+            )
+            SELECT city_id, address_id, $2, description, status, verified_at, verified_by,
+                   is_sandbox, guest_approval_required, device_binding_enabled,
+                   auto_suspend_anomalous_passes, $3
+            FROM complexes WHERE id = $1
+            RETURNING id
+            "#,
+        )
+        .bind(source.id)
+        .bind(name)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO chats (complex_id, chat_type, name)
+            VALUES ($1, 'complex', 'Общий чат ' || $2)
+            "#,
+        )
+        .bind(target_id)
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+
+        let mut report =
+            Self::move_apartments(&mut tx, source.id, target_id, Some(&buildings)).await?;
+        report.buildings = buildings;
+        report.dry_run = payload.dry_run;
+
+        Self::refresh_counts(&mut tx, source.id).await?;
+        Self::refresh_counts(&mut tx, target_id).await?;
+
+        if payload.dry_run {
+            tx.rollback().await?;
+            return Ok(report);
+        }
+
+        report.target_complex_id = Some(target_id);
+        Self::record_changes(&mut tx, source, created_by, request_id).await?;
+        AuditService::record(
+            &mut *tx,
+            NewAuditLog {
+                actor_id: created_by,
+                complex_id: Some(source.id),
+                action: "split_complex",
+                entity_type: "complex",
+                entity_id: Some(target_id),
+                old_value: Some(json!({"name": source.name})),
+                new_value: Some(json!(report)),
+                request_id: Some(request_id.to_string()),
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// Перенести все квартиры в другой ЖК; при совпадении номеров квартир слияние не выполняется
+    pub async fn merge(
+        pool: &PgPool,
+        source: &Complex,
+        target: &Complex,
+        actor_id: Uuid,
+        request_id: &str,
+        payload: &MergeComplexRequest,
+    ) -> AppResult<ComplexRestructureReport> {
+        if source.id == target.id {
+            return Err(AppError::Validation(
+                "ЖК нельзя объединить с самим собой".to_string(),
+            ));
+        }
+
+        let conflicts: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT COALESCE(s.building || ', кв. ', 'кв. ') || s.number
+            FROM apartments s
+            JOIN apartments t ON t.complex_id = $2
+                AND t.building IS NOT DISTINCT FROM s.building
+                AND t.number = s.number
+            WHERE s.complex_id = $1
+            ORDER BY s.building, s.number
+            "#,
+        )
+        .bind(source.id)
+        .bind(target.id)
+        .fetch_all(pool)
+        .await?;
+        let conflicts: Vec<String> = conflicts.into_iter().map(|(c,)| c).collect();
+
+        if !conflicts.is_empty() && !payload.dry_run {
+            return Err(AppError::Conflict(format!(
+                "Номера квартир уже заняты в целевом ЖК: {}",
+                conflicts.join("; ")
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let buildings: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT building FROM apartments
+            WHERE complex_id = $1 AND building IS NOT NULL
+            ORDER BY building
+            "#,
+        )
+        .bind(source.id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Пробный запуск с конфликтами только считает: перенос упал бы на уникальности номеров
+        let mut report = if conflicts.is_empty() {
+            Self::move_apartments(&mut tx, source.id, target.id, None).await?
+        } else {
+            Self::count_apartment_data(&mut tx, source.id).await?
+        };
+        report.target_complex_id = Some(target.id);
+        report.buildings = buildings.into_iter().map(|(b,)| b).collect();
+        report.dry_run = payload.dry_run;
+        report.conflicts = conflicts;
+
+        sqlx::query("UPDATE complexes SET status = 'inactive', updated_at = NOW() WHERE id = $1")
+            .bind(source.id)
+            .execute(&mut *tx)
+            .await?;
+
+        Self::refresh_counts(&mut tx, source.id).await?;
+        Self::refresh_counts(&mut tx, target.id).await?;
+
+        if payload.dry_run {
+            tx.rollback().await?;
+            return Ok(report);
+        }
+
+        Self::record_changes(&mut tx, source, actor_id, request_id).await?;
+        Self::record_changes(&mut tx, target, actor_id, request_id).await?;
+        AuditService::record(
+            &mut *tx,
+            NewAuditLog {
+                actor_id,
+                complex_id: Some(target.id),
+                action: "merge_complex",
+                entity_type: "complex",
+                entity_id: Some(source.id),
+                old_value: Some(json!({"name": source.name, "status": source.status})),
+                new_value: Some(json!(report)),
+                request_id: Some(request_id.to_string()),
+            },
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    /// Перенести квартиры (всех или только указанных домов) вместе с их данными и домовыми чатами
+    async fn move_apartments(
+        tx: &mut Transaction<'_, Postgres>,
+        from: Uuid,
+        to: Uuid,
+        buildings: Option<&[String]>,
+    ) -> AppResult<ComplexRestructureReport> {
+        let apartment_ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            UPDATE apartments SET complex_id = $2, updated_at = NOW()
+            WHERE complex_id = $1 AND ($3::text[] IS NULL OR building = ANY($3))
+            RETURNING id
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(buildings)
+        .fetch_all(&mut **tx)
+        .await?;
+        let apartment_ids: Vec<Uuid> = apartment_ids.into_iter().map(|(id,)| id).collect();
+
+        let mut report = Self::count_by_apartments(tx, from, &apartment_ids).await?;
+
+        for table in APARTMENT_SCOPED_TABLES {
+            sqlx::query(&format!(
+                "UPDATE {} SET complex_id = $2 WHERE apartment_id = ANY($1)",
+                table
+            ))
+            .bind(&apartment_ids)
+            .bind(to)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        let chats = sqlx::query(
+            r#"
+            UPDATE chats SET complex_id = $2, updated_at = NOW()
+            WHERE complex_id = $1 AND chat_type = 'building'
+              AND ($3::text[] IS NULL OR building = ANY($3))
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(buildings)
+        .execute(&mut **tx)
+        .await?;
+        report.chats = chats.rows_affected() as i64;
+
+        Ok(report)
+    }
+
+    /// Данные всех квартир ЖК без переноса
+    async fn count_apartment_data(
+        tx: &mut Transaction<'_, Postgres>,
+        complex_id: Uuid,
+    ) -> AppResult<ComplexRestructureReport> {
+        let apartment_ids: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT id FROM apartments WHERE complex_id = $1")
+                .bind(complex_id)
+                .fetch_all(&mut **tx)
+                .await?;
+        let apartment_ids: Vec<Uuid> = apartment_ids.into_iter().map(|(id,)| id).collect();
+
+        let mut report = Self::count_by_apartments(tx, complex_id, &apartment_ids).await?;
+
+        let (chats,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM chats WHERE complex_id = $1 AND chat_type = 'building'",
+        )
+        .bind(complex_id)
+        .fetch_one(&mut **tx)
+        .await?;
+        report.chats = chats;
+
+        Ok(report)
+    }
+
+    async fn count_by_apartments(
+        tx: &mut Transaction<'_, Postgres>,
+        source_id: Uuid,
+        apartment_ids: &[Uuid],
+    ) -> AppResult<ComplexRestructureReport> {
+        let (meters, meter_readings, bills, payments, maintenance_requests): (i64, i64, i64, i64, i64) =
+            sqlx::query_as(
+                r#"
+                SELECT
+                    (SELECT COUNT(*) FROM meters WHERE apartment_id = ANY($1)),
+                    (SELECT COUNT(*) FROM meter_readings WHERE apartment_id = ANY($1)),
+                    (SELECT COUNT(*) FROM bills WHERE apartment_id = ANY($1)),
+                    (SELECT COUNT(*) FROM payments WHERE apartment_id = ANY($1)),
+                    (SELECT COUNT(*) FROM maintenance_requests WHERE apartment_id = ANY($1))
+                "#,
+            )
+            .bind(apartment_ids)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(ComplexRestructureReport {
+            source_complex_id: source_id,
+            target_complex_id: None,
+            dry_run: false,
+            buildings: Vec::new(),
+            apartments: apartment_ids.len() as i64,
+            meters,
+            meter_readings,
+            bills,
+            payments,
+            maintenance_requests,
+            chats: 0,
+            conflicts: Vec::new(),
+        })
+    }
+
+    /// Записать в историю изменившиеся поля ЖК: статус, число домов и квартир
+    async fn record_changes(
+        tx: &mut Transaction<'_, Postgres>,
+        before: &Complex,
+        actor_id: Uuid,
+        request_id: &str,
+    ) -> AppResult<()> {
+        let after = sqlx::query_as::<_, Complex>("SELECT * FROM complexes WHERE id = $1")
+            .bind(before.id)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        FieldHistoryService::record(
+            &mut **tx,
+            NewFieldChange {
+                complex_id: before.id,
+                entity_type: "complex",
+                entity_id: before.id,
+                changed_by: Some(actor_id),
+                old_value: json!(before),
+                new_value: json!(after),
+                request_id: Some(request_id.to_string()),
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Пересчитать число домов и квартир в карточке ЖК
+    async fn refresh_counts(tx: &mut Transaction<'_, Postgres>, complex_id: Uuid) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE complexes SET
+                buildings_count = GREATEST(
+                    (SELECT COUNT(DISTINCT building) FROM apartments WHERE complex_id = $1), 1
+                ),
+                apartments_count = (SELECT COUNT(*) FROM apartments WHERE complex_id = $1),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(complex_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
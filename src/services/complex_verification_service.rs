@@ -0,0 +1,116 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    ComplexVerification, ComplexVerificationEvidence, ComplexVerificationResponse,
+    NotificationType, VerificationCheckItem, VerificationChecklistEntry, VerificationEvidenceType,
+};
+use crate::services::NotificationService;
+
+/// Проверка новых ЖК администраторами
+pub struct ComplexVerificationService;
+
+impl ComplexVerificationService {
+    pub async fn load(pool: &PgPool, complex_id: Uuid) -> AppResult<ComplexVerification> {
+        sqlx::query_as::<_, ComplexVerification>(
+            "SELECT * FROM complex_verifications WHERE complex_id = $1",
+        )
+        .bind(complex_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("ЖК не найден".to_string()))
+    }
+
+    /// Чек-лист, документы и недостающие обязательные документы
+    pub async fn detail(
+        pool: &PgPool,
+        verification: ComplexVerification,
+    ) -> AppResult<ComplexVerificationResponse> {
+        let evidence = sqlx::query_as::<_, ComplexVerificationEvidence>(
+            "SELECT * FROM complex_verification_evidence WHERE complex_id = $1 ORDER BY created_at",
+        )
+        .bind(verification.complex_id)
+        .fetch_all(pool)
+        .await?;
+
+        let missing_evidence = VerificationEvidenceType::REQUIRED
+            .into_iter()
+            .filter(|required| !evidence.iter().any(|e| e.evidence_type == *required))
+            .collect();
+
+        let checklist = VerificationCheckItem::ALL
+            .into_iter()
+            .map(|item| VerificationChecklistEntry {
+                item,
+                title: item.title().to_string(),
+                checked: verification.is_checked(item),
+            })
+            .collect();
+
+        Ok(ComplexVerificationResponse {
+            complex_id: verification.complex_id,
+            status: verification.status,
+            checklist,
+            missing_evidence,
+            info_request: verification.info_request,
+            rejection_reasons: verification.rejection_reasons,
+            reviewed_at: verification.reviewed_at,
+            submitted_at: verification.submitted_at,
+            expired_at: verification.expired_at,
+            evidence,
+        })
+    }
+
+    /// ЖК можно подтвердить, только когда чек-лист пройден и документы загружены
+    pub fn ensure_complete(detail: &ComplexVerificationResponse) -> AppResult<()> {
+        let unchecked: Vec<&str> = detail
+            .checklist
+            .iter()
+            .filter(|entry| !entry.checked)
+            .map(|entry| entry.title.as_str())
+            .collect();
+        if !unchecked.is_empty() {
+            return Err(AppError::Validation(format!(
+                "Не отмечены пункты проверки: {}",
+                unchecked.join("; ")
+            )));
+        }
+
+        if !detail.missing_evidence.is_empty() {
+            return Err(AppError::Validation(
+                "Не загружены обязательные документы".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Сообщить создателю ЖК о ходе проверки
+    pub async fn notify_creator(
+        pool: &PgPool,
+        complex_id: Uuid,
+        title: &str,
+        body: Option<&str>,
+    ) -> AppResult<()> {
+        let creator: Option<(Option<Uuid>,)> =
+            sqlx::query_as("SELECT created_by FROM complexes WHERE id = $1")
+                .bind(complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        let Some((Some(creator_id),)) = creator else {
+            return Ok(());
+        };
+
+        NotificationService::notify_users(
+            pool,
+            &[creator_id],
+            NotificationType::System,
+            title,
+            body,
+            Some(serde_json::json!({ "complex_id": complex_id })),
+        )
+        .await
+    }
+}
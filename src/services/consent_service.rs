@@ -0,0 +1,164 @@
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{ConsentAcceptance, LegalDocument};
+
+/// Как долго держим список действующих версий, прежде чем перечитать из базы
+const CURRENT_DOCUMENTS_TTL: Duration = Duration::from_secs(10);
+
+/// Как долго помним, что пользователь принял действующие версии
+const ACCEPTED_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Сколько пользователей держим в кэше, прежде чем вычистить устаревшие записи
+const ACCEPTED_CACHE_CAPACITY: usize = 10_000;
+
+/// Версии документов и когда они загружены или проверены
+type CachedVersions = (Instant, Vec<Uuid>);
+
+static CURRENT_DOCUMENTS: Lazy<RwLock<Option<CachedVersions>>> = Lazy::new(|| RwLock::new(None));
+
+/// Пользователь → версии, которые он принял целиком, и когда это проверено
+static ACCEPTED: Lazy<RwLock<HashMap<Uuid, CachedVersions>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Версии юридических документов и согласия пользователей с ними
+pub struct ConsentService;
+
+impl ConsentService {
+    /// Действующие версии: последняя опубликованная по каждому виду документа
+    pub async fn current_documents(pool: &PgPool) -> AppResult<Vec<LegalDocument>> {
+        let documents = sqlx::query_as::<_, LegalDocument>(
+            r#"
+            SELECT DISTINCT ON (kind) * FROM legal_documents
+            WHERE published_at <= NOW()
+            ORDER BY kind, published_at DESC
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Действующие версии, которые пользователь ещё не принял
+    pub async fn pending_documents(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<Uuid>> {
+        let pending: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT d.id FROM (
+                SELECT DISTINCT ON (kind) id FROM legal_documents
+                WHERE published_at <= NOW()
+                ORDER BY kind, published_at DESC
+            ) d
+            WHERE NOT EXISTS (
+                SELECT 1 FROM consent_acceptances a
+                WHERE a.document_id = d.id AND a.user_id = $1
+            )
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(pending.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Нужно ли пользователю принять новую версию документов (вызывается на каждый
+    /// запрос: принятые версии кэшируются по пользователю до смены действующих)
+    pub async fn consent_required(pool: &PgPool, user_id: Uuid) -> AppResult<bool> {
+        let current = Self::current_document_ids(pool).await?;
+        if let Ok(cache) = ACCEPTED.read() {
+            if let Some((checked_at, accepted)) = cache.get(&user_id) {
+                if checked_at.elapsed() < ACCEPTED_CACHE_TTL && *accepted == current {
+                    return Ok(false);
+                }
+            }
+        }
+
+        if !Self::pending_documents(pool, user_id).await?.is_empty() {
+            return Ok(true);
+        }
+
+        if let Ok(mut cache) = ACCEPTED.write() {
+            if cache.len() >= ACCEPTED_CACHE_CAPACITY {
+                cache.retain(|_, (checked_at, _)| checked_at.elapsed() < ACCEPTED_CACHE_TTL);
+            }
+            cache.insert(user_id, (Instant::now(), current));
+        }
+
+        Ok(false)
+    }
+
+    /// Идентификаторы действующих версий по порядку (с кэшированием)
+    async fn current_document_ids(pool: &PgPool) -> AppResult<Vec<Uuid>> {
+        if let Ok(cache) = CURRENT_DOCUMENTS.read() {
+            if let Some((loaded_at, ids)) = cache.as_ref() {
+                if loaded_at.elapsed() < CURRENT_DOCUMENTS_TTL {
+                    return Ok(ids.clone());
+                }
+            }
+        }
+
+        let mut ids: Vec<Uuid> = Self::current_documents(pool)
+            .await?
+            .into_iter()
+            .map(|document| document.id)
+            .collect();
+        ids.sort();
+
+        if let Ok(mut cache) = CURRENT_DOCUMENTS.write() {
+            *cache = Some((Instant::now(), ids.clone()));
+        }
+
+        Ok(ids)
+    }
+
+    /// Записать согласие с действующими версиями; устаревшие версии не принимаются
+    pub async fn accept(
+        pool: &PgPool,
+        user_id: Uuid,
+        document_ids: &[Uuid],
+        ip_address: Option<&str>,
+        user_agent: Option<&str>,
+    ) -> AppResult<Vec<ConsentAcceptance>> {
+        let mut document_ids = document_ids.to_vec();
+        document_ids.sort();
+        document_ids.dedup();
+        if document_ids.is_empty() {
+            return Err(AppError::Validation("Не указаны документы".to_string()));
+        }
+
+        let current: Vec<Uuid> = Self::current_documents(pool)
+            .await?
+            .into_iter()
+            .map(|document| document.id)
+            .collect();
+        if let Some(outdated) = document_ids.iter().find(|id| !current.contains(id)) {
+            return Err(AppError::BadRequest(format!(
+                "Документ {} не является действующей версией",
+                outdated
+            )));
+        }
+
+        let accepted = sqlx::query_as::<_, ConsentAcceptance>(
+            r#"
+            INSERT INTO consent_acceptances (user_id, document_id, ip_address, user_agent)
+            SELECT $1, d, $3, $4 FROM UNNEST($2::uuid[]) AS d
+            ON CONFLICT (user_id, document_id) DO UPDATE SET user_id = EXCLUDED.user_id
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&document_ids)
+        .bind(ip_address)
+        .bind(user_agent)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(accepted)
+    }
+}
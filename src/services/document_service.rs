@@ -0,0 +1,848 @@
+use crate::error::{AppError, AppResult};
+use crate::models::MonthlyReport;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use flate2::{write::ZlibEncoder, Compression};
+use once_cell::sync::Lazy;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use ttf_parser::{Face, GlyphId};
+
+/// Шрифт с кириллицей; встраивается в каждый документ целиком
+static FONT_DATA: &[u8] = include_bytes!("../../assets/fonts/DejaVuSans.ttf");
+const FONT_NAME: &str = "DejaVuSans";
+
+static FONT: Lazy<Face<'static>> =
+    Lazy::new(|| Face::parse(FONT_DATA, 0).expect("Bundled font must be a valid TrueType file"));
+
+/// Страница A4 в пунктах
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+
+/// Время в документах — по Казахстану (UTC+5)
+pub const LOCAL_OFFSET_SECS: i64 = 5 * 3600;
+
+/// Данные квитанции об оплате
+pub struct ReceiptDocument {
+    pub receipt_number: String,
+    pub complex_name: String,
+    pub address: Option<String>,
+    pub apartment: String,
+    pub payer_name: String,
+    pub method: String,
+    pub paid_at: DateTime<Utc>,
+    pub amount: Decimal,
+    pub bills: Vec<ReceiptBill>,
+    pub verification_url: Option<String>,
+}
+
+/// Счёт, который погашен платежом
+pub struct ReceiptBill {
+    pub period: String,
+    pub items: Vec<ReceiptItem>,
+    pub penalty: Decimal,
+    /// Сколько из платежа пришлось на этот счёт
+    pub allocated: Decimal,
+}
+
+pub struct ReceiptItem {
+    pub description: String,
+    pub quantity: Option<String>,
+    pub amount: Decimal,
+}
+
+/// Итоги закрытого голосования для протокола; именно они подписываются
+pub struct ProtocolResults {
+    pub osi_name: Option<String>,
+    pub osi_bin: Option<String>,
+    pub osi_address: Option<String>,
+    pub complex_name: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub voting_type: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub options: Vec<ProtocolOption>,
+    pub voted_weight: Decimal,
+    pub total_weight: Decimal,
+    pub quorum_percent: i32,
+    pub quorum_reached: bool,
+    pub participants: Vec<ProtocolParticipant>,
+}
+
+pub struct ProtocolOption {
+    pub text: String,
+    pub votes_count: i32,
+    pub votes_weight: Decimal,
+}
+
+/// Проголосовавший собственник и его квартиры
+pub struct ProtocolParticipant {
+    pub apartments: String,
+    pub area: Option<Decimal>,
+    pub owner: String,
+    pub choice: String,
+    pub weight: Decimal,
+}
+
+/// Реквизиты подписи протокола
+pub struct ProtocolSignature {
+    pub protocol_number: String,
+    pub signed_by: String,
+    pub signed_at: DateTime<Utc>,
+    pub signature: String,
+    pub verification_url: String,
+}
+
+/// История рассылок ЖК за период
+pub struct CommunicationReport {
+    pub complex_name: String,
+    pub date_from: NaiveDate,
+    pub date_to: NaiveDate,
+    pub items: Vec<CommunicationItem>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Объявление, экстренное оповещение или рассылка со статистикой доставки
+pub struct CommunicationItem {
+    pub sent_at: DateTime<Utc>,
+    pub kind: String,
+    pub title: String,
+    pub author: Option<String>,
+    /// Скольким жителям ушло уведомление
+    pub recipients: Option<i64>,
+    /// Сколько открыли уведомление
+    pub opened: Option<i64>,
+    /// Сколько отметили объявление прочитанным
+    pub confirmed: Option<i64>,
+    pub views: Option<i64>,
+}
+
+pub struct DocumentService;
+
+impl DocumentService {
+    /// Квитанция об оплате в PDF
+    pub fn render_receipt(receipt: &ReceiptDocument) -> AppResult<Vec<u8>> {
+        let mut pdf = PdfBuilder::new();
+        let right = PAGE_WIDTH - MARGIN;
+        let mut y = PAGE_HEIGHT - MARGIN;
+
+        pdf.text(MARGIN, y, 16.0, &format!("Квитанция об оплате № {}", receipt.receipt_number));
+        y -= 28.0;
+
+        let paid_at = receipt.paid_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        let mut header = vec![("ЖК", receipt.complex_name.clone())];
+        if let Some(address) = &receipt.address {
+            header.push(("Адрес", address.clone()));
+        }
+        header.push(("Квартира", receipt.apartment.clone()));
+        header.push(("Плательщик", receipt.payer_name.clone()));
+        header.push(("Дата оплаты", paid_at.format("%d.%m.%Y %H:%M").to_string()));
+        header.push(("Способ оплаты", receipt.method.clone()));
+
+        for (label, value) in header {
+            pdf.text(MARGIN, y, 10.0, &format!("{}:", label));
+            pdf.text(MARGIN + 100.0, y, 10.0, &value);
+            y -= 16.0;
+        }
+
+        for bill in &receipt.bills {
+            y -= 12.0;
+            y = pdf.ensure_space(y, 60.0);
+            pdf.text(MARGIN, y, 12.0, &format!("Счёт за {}", bill.period));
+            y -= 6.0;
+            pdf.line(MARGIN, y, right, y);
+            y -= 14.0;
+
+            for item in &bill.items {
+                y = pdf.ensure_space(y, 16.0);
+                pdf.text(MARGIN, y, 10.0, &item.description);
+                if let Some(quantity) = &item.quantity {
+                    pdf.text_right(right - 110.0, y, 10.0, quantity);
+                }
+                pdf.text_right(right, y, 10.0, &money(item.amount));
+                y -= 16.0;
+            }
+
+            if bill.penalty > Decimal::ZERO {
+                y = pdf.ensure_space(y, 16.0);
+                pdf.text(MARGIN, y, 10.0, "Пеня");
+                pdf.text_right(right, y, 10.0, &money(bill.penalty));
+                y -= 16.0;
+            }
+
+            y = pdf.ensure_space(y, 16.0);
+            pdf.text(MARGIN, y, 10.0, "Оплачено по счёту");
+            pdf.text_right(right, y, 10.0, &money(bill.allocated));
+            y -= 16.0;
+        }
+
+        y -= 8.0;
+        y = pdf.ensure_space(y, 60.0);
+        pdf.line(MARGIN, y, right, y);
+        y -= 20.0;
+        pdf.text(MARGIN, y, 14.0, "Итого оплачено");
+        pdf.text_right(right, y, 14.0, &money(receipt.amount));
+
+        if let Some(url) = &receipt.verification_url {
+            y -= 30.0;
+            y = pdf.ensure_space(y, 16.0);
+            pdf.text(MARGIN, y, 9.0, &format!("Проверить квитанцию: {}", url));
+        }
+
+        pdf.finish()
+    }
+
+    /// Ежемесячный финансовый отчёт ОСИ для размещения на стенде
+    pub fn render_monthly_report(report: &MonthlyReport) -> AppResult<Vec<u8>> {
+        let mut pdf = PdfBuilder::new();
+        let right = PAGE_WIDTH - MARGIN;
+        let mut y = PAGE_HEIGHT - MARGIN;
+
+        pdf.text(MARGIN, y, 16.0, &format!("Финансовый отчёт за {}", report.period));
+        y -= 22.0;
+        pdf.text(MARGIN, y, 11.0, &report.osi_name);
+        y -= 14.0;
+        pdf.text(
+            MARGIN,
+            y,
+            9.0,
+            &format!(
+                "Период: {} – {}",
+                report.period_start.format("%d.%m.%Y"),
+                report.period_end.format("%d.%m.%Y")
+            ),
+        );
+        y -= 24.0;
+
+        let collection_rate = report
+            .collection_rate
+            .map(|rate| format!("{} %", rate.round_dp(1)))
+            .unwrap_or_else(|| "—".to_string());
+        let summary = [
+            ("Начислено за месяц", money(report.billed)),
+            ("Оплачено по начислениям месяца", money(report.billed_paid)),
+            ("Собираемость", collection_rate),
+            ("Всего поступило", money(report.collected)),
+            ("Квартир с просрочкой", report.debtors_count.to_string()),
+            ("Сумма просроченной задолженности", money(report.total_debt)),
+        ];
+
+        pdf.text(MARGIN, y, 12.0, "Доходы");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+        for (label, value) in summary {
+            pdf.text(MARGIN, y, 10.0, label);
+            pdf.text_right(right, y, 10.0, &value);
+            y -= 16.0;
+        }
+
+        y -= 12.0;
+        pdf.text(MARGIN, y, 12.0, "Расходы");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+
+        if report.expenses_by_category.is_empty() {
+            pdf.text(MARGIN, y, 10.0, "Расходов в этом месяце не было");
+            y -= 16.0;
+        }
+        for category in &report.expenses_by_category {
+            y = pdf.ensure_space(y, 16.0);
+            pdf.text(MARGIN, y, 10.0, &category.category_name);
+            pdf.text_right(right - 110.0, y, 10.0, &format!("{} шт.", category.expenses_count));
+            pdf.text_right(right, y, 10.0, &money(category.amount));
+            y -= 16.0;
+        }
+        if report.sms_spent > Decimal::ZERO {
+            y = pdf.ensure_space(y, 16.0);
+            pdf.text(MARGIN, y, 10.0, "SMS-рассылки");
+            pdf.text_right(right, y, 10.0, &money(report.sms_spent));
+            y -= 16.0;
+        }
+
+        y -= 8.0;
+        y = pdf.ensure_space(y, 60.0);
+        pdf.line(MARGIN, y, right, y);
+        y -= 20.0;
+        pdf.text(MARGIN, y, 12.0, "Итого расходов");
+        pdf.text_right(right, y, 12.0, &money(report.expenses + report.sms_spent));
+        y -= 20.0;
+        pdf.text(MARGIN, y, 14.0, "Остаток месяца");
+        pdf.text_right(right, y, 14.0, &money(report.balance));
+
+        let maintenance = &report.maintenance;
+        if maintenance.requests_count > 0 {
+            y -= 32.0;
+            y = pdf.ensure_space(y, 100.0);
+            pdf.text(MARGIN, y, 12.0, "Ремонт по заявкам (справочно)");
+            y -= 6.0;
+            pdf.line(MARGIN, y, right, y);
+            y -= 14.0;
+
+            let rows = [
+                ("Выполнено заявок", maintenance.requests_count.to_string()),
+                ("Материалы", money(maintenance.materials)),
+                (
+                    "Работы",
+                    format!("{} ч, {}", maintenance.labor_hours.normalize(), money(maintenance.labor)),
+                ),
+                ("Прочее", money(maintenance.other)),
+                ("Итого по заявкам", money(maintenance.total)),
+            ];
+            for (label, value) in rows {
+                pdf.text(MARGIN, y, 10.0, label);
+                pdf.text_right(right, y, 10.0, &value);
+                y -= 16.0;
+            }
+        }
+
+        let generated_at = report.generated_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        y -= 30.0;
+        y = pdf.ensure_space(y, 16.0);
+        pdf.text(
+            MARGIN,
+            y,
+            9.0,
+            &format!("Сформировано {}", generated_at.format("%d.%m.%Y %H:%M")),
+        );
+
+        pdf.finish()
+    }
+
+    /// Протокол закрытого голосования с итогами, кворумом и списком участников
+    pub fn render_voting_protocol(
+        results: &ProtocolResults,
+        signature: &ProtocolSignature,
+    ) -> AppResult<Vec<u8>> {
+        let mut pdf = PdfBuilder::new();
+        let right = PAGE_WIDTH - MARGIN;
+        let width = right - MARGIN;
+        let mut y = PAGE_HEIGHT - MARGIN;
+
+        pdf.text(
+            MARGIN,
+            y,
+            16.0,
+            &format!("Протокол голосования № {}", signature.protocol_number),
+        );
+        y -= 22.0;
+
+        if let Some(osi_name) = &results.osi_name {
+            pdf.text(MARGIN, y, 11.0, osi_name);
+            y -= 14.0;
+        }
+        let mut requisites = Vec::new();
+        if let Some(bin) = &results.osi_bin {
+            requisites.push(format!("БИН {}", bin));
+        }
+        requisites.push(format!("ЖК «{}»", results.complex_name));
+        if let Some(address) = &results.osi_address {
+            requisites.push(address.clone());
+        }
+        for line in wrap_text(&requisites.join(", "), 9.0, width) {
+            pdf.text(MARGIN, y, 9.0, &line);
+            y -= 12.0;
+        }
+        y -= 12.0;
+
+        let starts_at = results.starts_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        let ends_at = results.ends_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        let header = [
+            ("Вопрос", results.title.clone()),
+            ("Форма", results.voting_type.clone()),
+            (
+                "Период",
+                format!(
+                    "{} – {}",
+                    starts_at.format("%d.%m.%Y %H:%M"),
+                    ends_at.format("%d.%m.%Y %H:%M")
+                ),
+            ),
+        ];
+        for (label, value) in header {
+            let lines = wrap_text(&value, 10.0, width - 100.0);
+            y = pdf.ensure_space(y, 16.0 * lines.len() as f32);
+            pdf.text(MARGIN, y, 10.0, &format!("{}:", label));
+            for line in lines {
+                pdf.text(MARGIN + 100.0, y, 10.0, &line);
+                y -= 16.0;
+            }
+        }
+        if let Some(description) = &results.description {
+            y -= 4.0;
+            for line in wrap_text(description, 9.0, width) {
+                y = pdf.ensure_space(y, 12.0);
+                pdf.text(MARGIN, y, 9.0, &line);
+                y -= 12.0;
+            }
+        }
+
+        y -= 12.0;
+        y = pdf.ensure_space(y, 80.0);
+        pdf.text(MARGIN, y, 12.0, "Кворум");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+        let turnout = if results.total_weight > Decimal::ZERO {
+            format!(
+                "{} %",
+                (results.voted_weight / results.total_weight * Decimal::from(100)).round_dp(2)
+            )
+        } else {
+            "—".to_string()
+        };
+        let quorum = [
+            ("Площадь собственников, м²", results.total_weight.round_dp(2).to_string()),
+            ("Приняли участие, м²", results.voted_weight.round_dp(2).to_string()),
+            ("Явка", turnout),
+            ("Необходимый кворум", format!("{} %", results.quorum_percent)),
+            (
+                "Кворум",
+                if results.quorum_reached { "имеется" } else { "отсутствует" }.to_string(),
+            ),
+        ];
+        for (label, value) in quorum {
+            pdf.text(MARGIN, y, 10.0, label);
+            pdf.text_right(right, y, 10.0, &value);
+            y -= 16.0;
+        }
+
+        y -= 12.0;
+        y = pdf.ensure_space(y, 40.0);
+        pdf.text(MARGIN, y, 12.0, "Итоги");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+        for option in &results.options {
+            let share = if results.voted_weight > Decimal::ZERO {
+                (option.votes_weight / results.voted_weight * Decimal::from(100)).round_dp(2)
+            } else {
+                Decimal::ZERO
+            };
+            let lines = wrap_text(&option.text, 10.0, width - 220.0);
+            y = pdf.ensure_space(y, 16.0 * lines.len() as f32);
+            pdf.text_right(right - 150.0, y, 10.0, &format!("{} гол.", option.votes_count));
+            pdf.text_right(right - 60.0, y, 10.0, &format!("{} м²", option.votes_weight.round_dp(2)));
+            pdf.text_right(right, y, 10.0, &format!("{} %", share));
+            for line in lines {
+                pdf.text(MARGIN, y, 10.0, &line);
+                y -= 16.0;
+            }
+        }
+
+        y -= 12.0;
+        y = pdf.ensure_space(y, 40.0);
+        pdf.text(MARGIN, y, 12.0, "Участники голосования");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+        if results.participants.is_empty() {
+            pdf.text(MARGIN, y, 10.0, "Никто не проголосовал");
+            y -= 16.0;
+        }
+        for participant in &results.participants {
+            y = pdf.ensure_space(y, 14.0);
+            let area = participant
+                .area
+                .map(|area| format!("{} м²", area.round_dp(2)))
+                .unwrap_or_else(|| "—".to_string());
+            pdf.text(MARGIN, y, 8.0, &fit_text(&participant.apartments, 8.0, 70.0));
+            pdf.text_right(MARGIN + 125.0, y, 8.0, &area);
+            pdf.text(MARGIN + 135.0, y, 8.0, &fit_text(&participant.owner, 8.0, 140.0));
+            pdf.text(MARGIN + 285.0, y, 8.0, &fit_text(&participant.choice, 8.0, 150.0));
+            pdf.text_right(right, y, 8.0, &participant.weight.round_dp(2).to_string());
+            y -= 14.0;
+        }
+
+        let signed_at = signature.signed_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        y -= 20.0;
+        y = pdf.ensure_space(y, 70.0);
+        pdf.line(MARGIN, y, right, y);
+        y -= 18.0;
+        pdf.text(
+            MARGIN,
+            y,
+            10.0,
+            &format!(
+                "Подписал: {}, {}",
+                signature.signed_by,
+                signed_at.format("%d.%m.%Y %H:%M")
+            ),
+        );
+        y -= 14.0;
+        pdf.text(MARGIN, y, 8.0, &format!("Подпись: {}", signature.signature));
+        y -= 12.0;
+        pdf.text(
+            MARGIN,
+            y,
+            8.0,
+            &format!("Проверить протокол: {}", signature.verification_url),
+        );
+
+        pdf.finish()
+    }
+
+    /// Хронологический отчёт об объявлениях и рассылках ЖК
+    pub fn render_communication_report(report: &CommunicationReport) -> AppResult<Vec<u8>> {
+        let mut pdf = PdfBuilder::new();
+        let right = PAGE_WIDTH - MARGIN;
+        let mut y = PAGE_HEIGHT - MARGIN;
+
+        pdf.text(MARGIN, y, 16.0, "История объявлений и рассылок");
+        y -= 22.0;
+        pdf.text(MARGIN, y, 11.0, &format!("ЖК «{}»", report.complex_name));
+        y -= 14.0;
+        pdf.text(
+            MARGIN,
+            y,
+            9.0,
+            &format!(
+                "Период: {} – {}",
+                report.date_from.format("%d.%m.%Y"),
+                report.date_to.format("%d.%m.%Y")
+            ),
+        );
+        y -= 24.0;
+
+        let mut kinds: Vec<(&str, usize)> = Vec::new();
+        for item in &report.items {
+            match kinds.iter_mut().find(|(kind, _)| *kind == item.kind) {
+                Some((_, count)) => *count += 1,
+                None => kinds.push((&item.kind, 1)),
+            }
+        }
+        pdf.text(MARGIN, y, 12.0, "Итого");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 14.0;
+        for (kind, count) in &kinds {
+            pdf.text(MARGIN, y, 10.0, kind);
+            pdf.text_right(right, y, 10.0, &count.to_string());
+            y -= 16.0;
+        }
+        pdf.text(MARGIN, y, 10.0, "Всего записей");
+        pdf.text_right(right, y, 10.0, &report.items.len().to_string());
+        y -= 28.0;
+
+        pdf.text(MARGIN, y, 12.0, "Хронология");
+        y -= 6.0;
+        pdf.line(MARGIN, y, right, y);
+        y -= 12.0;
+        let counters = [
+            (right - 135.0, "Получ."),
+            (right - 90.0, "Откр."),
+            (right - 45.0, "Прочит."),
+            (right, "Просм."),
+        ];
+        pdf.text(MARGIN, y, 7.0, "Дата");
+        pdf.text(MARGIN + 70.0, y, 7.0, "Тип");
+        pdf.text(MARGIN + 130.0, y, 7.0, "Заголовок");
+        for (x, label) in counters {
+            pdf.text_right(x, y, 7.0, label);
+        }
+        y -= 14.0;
+
+        if report.items.is_empty() {
+            pdf.text(MARGIN, y, 10.0, "За период ничего не публиковалось");
+            y -= 16.0;
+        }
+        let count = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_else(|| "—".to_string());
+        for item in &report.items {
+            let sent_at = item.sent_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+            let mut lines = wrap_text(&item.title, 8.0, 185.0);
+            if let Some(author) = &item.author {
+                lines.push(fit_text(author, 8.0, 185.0));
+            }
+            y = pdf.ensure_space(y, 12.0 * lines.len() as f32 + 2.0);
+            pdf.text(MARGIN, y, 8.0, &sent_at.format("%d.%m.%Y %H:%M").to_string());
+            pdf.text(MARGIN + 70.0, y, 8.0, &fit_text(&item.kind, 8.0, 55.0));
+            let values = [item.recipients, item.opened, item.confirmed, item.views];
+            for ((x, _), value) in counters.iter().zip(values) {
+                pdf.text_right(*x, y, 8.0, &count(value));
+            }
+            for line in lines {
+                pdf.text(MARGIN + 130.0, y, 8.0, &line);
+                y -= 12.0;
+            }
+            y -= 2.0;
+        }
+
+        let generated_at = report.generated_at.naive_utc() + Duration::seconds(LOCAL_OFFSET_SECS);
+        y -= 16.0;
+        y = pdf.ensure_space(y, 40.0);
+        for line in wrap_text(
+            "Получ. — скольким жителям отправлено уведомление, Откр. — сколько его открыли, \
+             Прочит. — сколько отметили объявление прочитанным, Просм. — просмотры объявления.",
+            8.0,
+            right - MARGIN,
+        ) {
+            pdf.text(MARGIN, y, 8.0, &line);
+            y -= 11.0;
+        }
+        y -= 6.0;
+        pdf.text(
+            MARGIN,
+            y,
+            9.0,
+            &format!("Сформировано {}", generated_at.format("%d.%m.%Y %H:%M")),
+        );
+
+        pdf.finish()
+    }
+}
+
+/// Разбить текст на строки не шире `width`
+fn wrap_text(text: &str, size: f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if !line.is_empty() && text_width(&candidate, size) > width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Обрезать текст до ширины колонки
+fn fit_text(text: &str, size: f32, width: f32) -> String {
+    if text_width(text, size) <= width {
+        return text.to_string();
+    }
+
+    let mut fitted = String::new();
+    for ch in text.chars() {
+        if text_width(&format!("{}{}…", fitted, ch), size) > width {
+            break;
+        }
+        fitted.push(ch);
+    }
+    format!("{}…", fitted)
+}
+
+fn money(amount: Decimal) -> String {
+    format!("{} ₸", amount.round_dp(2))
+}
+
+fn glyph(ch: char) -> GlyphId {
+    FONT.glyph_index(ch)
+        .or_else(|| FONT.glyph_index('?'))
+        .unwrap_or(GlyphId(0))
+}
+
+/// Ширина глифа в тысячных долях кегля, как её ждёт PDF
+fn glyph_width(id: GlyphId) -> f32 {
+    let advance = FONT.glyph_hor_advance(id).unwrap_or(0) as f32;
+    advance * 1000.0 / FONT.units_per_em() as f32
+}
+
+fn text_width(text: &str, size: f32) -> f32 {
+    text.chars().map(|ch| glyph_width(glyph(ch))).sum::<f32>() * size / 1000.0
+}
+
+/// Минимальный генератор PDF: текст одним встроенным шрифтом (Identity-H) и линии
+struct PdfBuilder {
+    pages: Vec<String>,
+    current: String,
+    /// Использованные глифы и символы, которые они изображают (для копирования текста)
+    glyphs: BTreeMap<u16, char>,
+}
+
+impl PdfBuilder {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    fn text(&mut self, x: f32, y: f32, size: f32, text: &str) {
+        let mut hex = String::with_capacity(text.len() * 4);
+        for ch in text.chars() {
+            let id = glyph(ch);
+            self.glyphs.entry(id.0).or_insert(ch);
+            let _ = write!(hex, "{:04X}", id.0);
+        }
+
+        let _ = writeln!(
+            self.current,
+            "BT /F1 {} Tf {:.2} {:.2} Td <{}> Tj ET",
+            size, x, y, hex
+        );
+    }
+
+    fn text_right(&mut self, right: f32, y: f32, size: f32, text: &str) {
+        self.text(right - text_width(text, size), y, size, text);
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        let _ = writeln!(self.current, "0.5 w {:.2} {:.2} m {:.2} {:.2} l S", x1, y1, x2, y2);
+    }
+
+    /// Перенести вывод на новую страницу, если до нижнего поля осталось меньше `needed`
+    fn ensure_space(&mut self, y: f32, needed: f32) -> f32 {
+        if y - needed >= MARGIN {
+            return y;
+        }
+
+        self.pages.push(std::mem::take(&mut self.current));
+        PAGE_HEIGHT - MARGIN
+    }
+
+    fn finish(mut self) -> AppResult<Vec<u8>> {
+        self.pages.push(std::mem::take(&mut self.current));
+
+        // 1 — каталог, 2 — дерево страниц, 3–7 — шрифт, дальше по два объекта на страницу
+        const FIRST_PAGE_OBJECT: usize = 8;
+        let page_refs: Vec<String> = (0..self.pages.len())
+            .map(|i| format!("{} 0 R", FIRST_PAGE_OBJECT + i * 2))
+            .collect();
+
+        let scale = 1000.0 / FONT.units_per_em() as f32;
+        let bbox = FONT.global_bounding_box();
+        let widths: String = self
+            .glyphs
+            .keys()
+            .map(|&id| format!("{} [{:.0}]", id, glyph_width(GlyphId(id))))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                page_refs.join(" "),
+                self.pages.len()
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H \
+                 /DescendantFonts [4 0 R] /ToUnicode 7 0 R >>",
+                FONT_NAME
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} \
+                 /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> \
+                 /FontDescriptor 5 0 R /CIDToGIDMap /Identity /W [{}] >>",
+                FONT_NAME, widths
+            )
+            .into_bytes(),
+            format!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [{:.0} {:.0} {:.0} {:.0}] \
+                 /ItalicAngle 0 /Ascent {:.0} /Descent {:.0} /CapHeight {:.0} /StemV 80 /FontFile2 6 0 R >>",
+                FONT_NAME,
+                bbox.x_min as f32 * scale,
+                bbox.y_min as f32 * scale,
+                bbox.x_max as f32 * scale,
+                bbox.y_max as f32 * scale,
+                FONT.ascender() as f32 * scale,
+                FONT.descender() as f32 * scale,
+                FONT.capital_height().unwrap_or(FONT.ascender()) as f32 * scale,
+            )
+            .into_bytes(),
+            stream(&format!("/Length1 {}", FONT_DATA.len()), FONT_DATA)?,
+            stream("", to_unicode_cmap(&self.glyphs).as_bytes())?,
+        ];
+
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    FIRST_PAGE_OBJECT + i * 2 + 1
+                )
+                .into_bytes(),
+            );
+            objects.push(stream("", content.as_bytes())?);
+        }
+
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        let mut trailer = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(trailer, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            trailer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        out.extend_from_slice(trailer.as_bytes());
+
+        Ok(out)
+    }
+}
+
+/// Сжатый поток PDF с дополнительными ключами словаря
+fn stream(extra: &str, data: &[u8]) -> AppResult<Vec<u8>> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let compressed = encoder.finish().map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut out = format!(
+        "<< /Length {} /Filter /FlateDecode {} >>\nstream\n",
+        compressed.len(),
+        extra
+    )
+    .into_bytes();
+    out.extend_from_slice(&compressed);
+    out.extend_from_slice(b"\nendstream");
+    Ok(out)
+}
+
+/// Таблица глиф → Unicode, чтобы текст из PDF можно было выделить и скопировать
+fn to_unicode_cmap(glyphs: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+
+    let entries: Vec<_> = glyphs.iter().collect();
+    // В одном блоке bfchar допускается не больше 100 записей
+    for chunk in entries.chunks(100) {
+        let _ = writeln!(cmap, "{} beginbfchar", chunk.len());
+        for (id, ch) in chunk {
+            let mut utf16 = [0u16; 2];
+            let code: String = ch
+                .encode_utf16(&mut utf16)
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            let _ = writeln!(cmap, "<{:04X}> <{}>", id, code);
+        }
+        cmap.push_str("endbfchar\n");
+    }
+
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
@@ -0,0 +1,167 @@
+use crate::error::AppResult;
+use crate::models::{ElectionOutcome, NewFieldChange, NotificationType, Voting, VotingType};
+use crate::services::{FieldHistoryService, NotificationService};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+pub struct ElectionService;
+
+impl ElectionService {
+    /// Подвести итог выборов: определить победителя и передать ему полномочия председателя.
+    /// Возвращает итог, о котором нужно сообщить собственникам, или `None`, если это не выборы
+    pub async fn resolve_voting(conn: &mut PgConnection, voting_id: Uuid) -> AppResult<Option<ElectionOutcome>> {
+        let voting = sqlx::query_as::<_, Voting>("SELECT * FROM votings WHERE id = $1")
+            .bind(voting_id)
+            .fetch_one(&mut *conn)
+            .await?;
+
+        if voting.voting_type != VotingType::Election || voting.election_outcome.is_some() {
+            return Ok(None);
+        }
+
+        let results: Vec<(Uuid, Option<Uuid>, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT id, candidate_id, votes_weight
+            FROM voting_options
+            WHERE voting_id = $1
+            ORDER BY votes_weight DESC
+            "#,
+        )
+        .bind(voting_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let (outcome, winner) = if voting.quorum_reached == Some(false) {
+            (ElectionOutcome::NoQuorum, None)
+        } else {
+            determine_winner(&results)
+        };
+
+        sqlx::query(
+            "UPDATE votings SET election_outcome = $2, winner_option_id = $3, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(voting_id)
+        .bind(outcome)
+        .bind(winner.map(|(option_id, _)| option_id))
+        .execute(&mut *conn)
+        .await?;
+
+        if let Some((_, candidate_id)) = winner {
+            Self::transfer_chairmanship(&mut *conn, voting.complex_id, candidate_id).await?;
+        }
+
+        tracing::info!("Election {} resolved as {:?}", voting_id, outcome);
+
+        Ok(Some(outcome))
+    }
+
+    /// Назначить избранного председателем ОСИ, прежнему вернуть роль собственника
+    async fn transfer_chairmanship(conn: &mut PgConnection, complex_id: Uuid, user_id: Uuid) -> AppResult<()> {
+        let previous: Option<(Uuid, Option<Uuid>)> =
+            sqlx::query_as("SELECT id, chairman_id FROM osi WHERE complex_id = $1 FOR UPDATE")
+                .bind(complex_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        let Some((osi_id, previous_id)) = previous else {
+            tracing::warn!("Election winner {} has no OSI in complex {}", user_id, complex_id);
+            return Ok(());
+        };
+
+        sqlx::query("UPDATE osi SET chairman_id = $2, updated_at = NOW() WHERE complex_id = $1")
+            .bind(complex_id)
+            .bind(user_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            "UPDATE users SET role = 'chairman' WHERE id = $1 AND role IN ('user', 'resident', 'owner', 'council')",
+        )
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+        if let Some(previous_id) = previous_id.filter(|id| *id != user_id) {
+            sqlx::query(
+                r#"
+                UPDATE users SET role = 'owner'
+                WHERE id = $1 AND role = 'chairman'
+                  AND NOT EXISTS (SELECT 1 FROM osi WHERE chairman_id = $1)
+                "#,
+            )
+            .bind(previous_id)
+            .execute(&mut *conn)
+            .await?;
+        }
+
+        FieldHistoryService::record(
+            &mut *conn,
+            NewFieldChange {
+                complex_id,
+                entity_type: "osi",
+                entity_id: osi_id,
+                changed_by: None,
+                old_value: json!({"chairman_id": previous_id}),
+                new_value: json!({"chairman_id": user_id}),
+                request_id: None,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Сообщить собственникам итог выборов
+    pub async fn announce_result(pool: &PgPool, voting: &Voting, outcome: ElectionOutcome) -> AppResult<()> {
+        let owner_ids: Vec<(Uuid,)> = sqlx::query_as(
+            "SELECT DISTINCT owner_id FROM apartments WHERE complex_id = $1 AND owner_id IS NOT NULL",
+        )
+        .bind(voting.complex_id)
+        .fetch_all(pool)
+        .await?;
+        let owner_ids: Vec<Uuid> = owner_ids.into_iter().map(|(id,)| id).collect();
+
+        let body = match outcome {
+            ElectionOutcome::Elected => "Выборы завершены, председатель избран",
+            ElectionOutcome::Tie => "Кандидаты набрали равное число голосов, будут назначены повторные выборы",
+            ElectionOutcome::NoCandidates => "Выборы не состоялись: кандидаты не выдвинуты",
+            ElectionOutcome::NoVotes => "Выборы не состоялись: голосов не подано",
+            ElectionOutcome::NoQuorum => "Выборы не состоялись: кворум не набран",
+        };
+
+        NotificationService::notify_users(
+            pool,
+            &owner_ids,
+            NotificationType::Voting,
+            &voting.title,
+            Some(body),
+            Some(json!({"voting_id": voting.id, "outcome": outcome})),
+        )
+        .await
+    }
+}
+
+/// Победитель — единственный кандидат с наибольшим весом голосов.
+/// Результаты должны быть отсортированы по весу по убыванию.
+fn determine_winner(results: &[(Uuid, Option<Uuid>, Decimal)]) -> (ElectionOutcome, Option<(Uuid, Uuid)>) {
+    let candidates: Vec<_> = results
+        .iter()
+        .filter_map(|(option_id, candidate_id, weight)| Some((*option_id, (*candidate_id)?, *weight)))
+        .collect();
+
+    let Some(&(option_id, candidate_id, top)) = candidates.first() else {
+        return (ElectionOutcome::NoCandidates, None);
+    };
+
+    if top <= Decimal::ZERO {
+        return (ElectionOutcome::NoVotes, None);
+    }
+
+    if candidates.get(1).is_some_and(|(_, _, weight)| *weight == top) {
+        return (ElectionOutcome::Tie, None);
+    }
+
+    (ElectionOutcome::Elected, Some((option_id, candidate_id)))
+}
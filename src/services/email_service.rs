@@ -0,0 +1,210 @@
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{EmailNotificationPayload, EmailTemplate, JobType, NotificationChannel};
+use crate::services::{JobService, NotificationService};
+
+/// Подпись транзакционных писем
+const EMAIL_FOOTER: &str =
+    "Отключить письма можно в настройках уведомлений приложения LocalHood.";
+
+pub struct EmailService {
+    config: Config,
+    client: reqwest::Client,
+}
+
+impl EmailService {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Ссылка для входа в веб-панель без SMS-кода
+    pub async fn send_magic_link(&self, to: &str, link: &str) -> AppResult<()> {
+        if !self.config.email_enabled {
+            tracing::info!("Email disabled. Magic link for {}: {}", to, link);
+            return Ok(());
+        }
+
+        let text = format!(
+            "Для входа в LocalHood перейдите по ссылке: {}\n\nСсылка действует {} мин. \
+             Если вы не запрашивали вход, просто проигнорируйте это письмо.",
+            link, self.config.magic_link_ttl_minutes
+        );
+        self.send(to, "Вход в LocalHood", &text).await
+    }
+
+    /// Ссылка для подтверждения адреса, после которого по нему можно входить
+    pub async fn send_email_confirmation(&self, to: &str, link: &str) -> AppResult<()> {
+        if !self.config.email_enabled {
+            tracing::info!("Email disabled. Confirmation link for {}: {}", to, link);
+            return Ok(());
+        }
+
+        let text = format!(
+            "Подтвердите адрес почты для входа в LocalHood: {}\n\nСсылка действует {} мин.",
+            link, self.config.magic_link_ttl_minutes
+        );
+        self.send(to, "Подтверждение email в LocalHood", &text).await
+    }
+
+    /// Поставить письмо в очередь для подписанных на него пользователей
+    pub async fn enqueue(pool: &PgPool, user_ids: &[Uuid], template: EmailTemplate) -> AppResult<()> {
+        let user_ids = NotificationService::recipients(
+            pool,
+            user_ids,
+            template.notification_type(),
+            NotificationChannel::Email,
+        )
+        .await?;
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        JobService::enqueue(
+            pool,
+            JobType::EmailNotification,
+            &EmailNotificationPayload { user_ids, template },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Разослать письмо из очереди на подтверждённые адреса и записать результат в журнал.
+    /// Ошибка возвращается, только если не ушло ни одно письмо, — повтор не задвоит доставленные
+    pub async fn deliver_queued(&self, pool: &PgPool, payload: &EmailNotificationPayload) -> AppResult<()> {
+        if !self.config.email_enabled {
+            return Ok(());
+        }
+
+        let recipients: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT id, email FROM users
+            WHERE id = ANY($1) AND email IS NOT NULL AND email_verified_at IS NOT NULL
+            "#,
+        )
+        .bind(&payload.user_ids)
+        .fetch_all(pool)
+        .await?;
+
+        let (subject, text) = render(&payload.template);
+        let mut last_error = None;
+        let mut sent = 0;
+
+        for (user_id, email) in &recipients {
+            let (status, error) = match self.send(email, &subject, &text).await {
+                Ok(()) => {
+                    sent += 1;
+                    ("sent", None)
+                }
+                Err(e) => {
+                    let error = e.to_string();
+                    last_error = Some(e);
+                    ("failed", Some(error))
+                }
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO email_messages (user_id, email, template, subject, status, error)
+                VALUES ($1, $2, $3, $4, $5::email_status, $6)
+                "#,
+            )
+            .bind(user_id)
+            .bind(email)
+            .bind(payload.template.name())
+            .bind(&subject)
+            .bind(status)
+            .bind(error)
+            .execute(pool)
+            .await?;
+        }
+
+        match last_error {
+            Some(e) if sent == 0 => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    async fn send(&self, to: &str, subject: &str, text: &str) -> AppResult<()> {
+        let response = self
+            .client
+            .post(&self.config.email_api_url)
+            .bearer_auth(&self.config.email_api_key)
+            .json(&json!({
+                "from": self.config.email_from,
+                "to": to,
+                "subject": subject,
+                "text": text,
+            }))
+            .send()
+            .await
+            .map_err(|e| AppError::Email(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!("Email API error: {} - {}", status, body);
+            return Err(AppError::Email(format!("Email API error: {}", status)));
+        }
+
+        tracing::info!("Email \"{}\" sent to {}", subject, to);
+        Ok(())
+    }
+}
+
+/// Тема и текст письма по шаблону
+fn render(template: &EmailTemplate) -> (String, String) {
+    let (subject, text) = match template {
+        EmailTemplate::BillIssued {
+            period_start,
+            total_amount,
+            due_date,
+            ..
+        } => (
+            format!("Счёт за {}", period_start.format("%m.%Y")),
+            format!(
+                "Выставлен счёт за {} на сумму {} ₸. Оплатить его нужно до {}.\n\n\
+                 Состав начислений и оплата — в приложении LocalHood.",
+                period_start.format("%m.%Y"),
+                total_amount,
+                due_date.format("%d.%m.%Y")
+            ),
+        ),
+        EmailTemplate::VotingOpened { title, ends_at, .. } => (
+            format!("Открыто голосование: {}", title),
+            format!(
+                "В вашем ЖК открыто голосование «{}». Проголосовать можно в приложении \
+                 LocalHood до {}.",
+                title,
+                ends_at.format("%d.%m.%Y %H:%M")
+            ),
+        ),
+        EmailTemplate::JoinRequestApproved {
+            complex_name,
+            building,
+            apartment_number,
+        } => {
+            let apartment = match building {
+                Some(building) => format!("{}, кв. {}", building, apartment_number),
+                None => format!("кв. {}", apartment_number),
+            };
+            (
+                format!("Заявка в {} одобрена", complex_name),
+                format!(
+                    "Ваша заявка на присоединение к квартире ({}) в {} одобрена. \
+                     Счета, голосования и чат ЖК теперь доступны в приложении LocalHood.",
+                    apartment, complex_name
+                ),
+            )
+        }
+    };
+
+    (subject, format!("{}\n\n{}", text, EMAIL_FOOTER))
+}
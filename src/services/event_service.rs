@@ -0,0 +1,28 @@
+use crate::error::AppResult;
+use crate::models::NewDomainEvent;
+use sqlx::PgPool;
+
+pub struct EventService;
+
+impl EventService {
+    /// Записать доменное событие в журнал
+    pub async fn record(pool: &PgPool, event: NewDomainEvent) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO domain_events (complex_id, osi_id, actor_id, event_type, entity_type, entity_id, data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(event.complex_id)
+        .bind(event.osi_id)
+        .bind(event.actor_id)
+        .bind(event.event_type)
+        .bind(event.entity_type)
+        .bind(event.entity_id)
+        .bind(event.data)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
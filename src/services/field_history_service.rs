@@ -0,0 +1,60 @@
+use serde_json::Value;
+use sqlx::PgExecutor;
+
+use crate::error::AppResult;
+use crate::models::NewFieldChange;
+use crate::utils::json_diff;
+
+/// Служебные поля, изменение которых историей не считается
+const IGNORED_FIELDS: &[&str] = &["created_at", "updated_at"];
+
+/// Пополевая история изменений ОСИ и ЖК
+pub struct FieldHistoryService;
+
+impl FieldHistoryService {
+    /// Записать поля, которые отличаются в `old_value` и `new_value`; возвращает их число.
+    /// Вызывается в транзакции самого изменения, чтобы история не разошлась с записью
+    pub async fn record<'e>(db: impl PgExecutor<'e>, change: NewFieldChange) -> AppResult<usize> {
+        let Value::Object(diff) = json_diff(&change.old_value, &change.new_value) else {
+            return Ok(0);
+        };
+
+        let mut fields = Vec::new();
+        let mut old_values = Vec::new();
+        let mut new_values = Vec::new();
+        for (field, values) in diff {
+            if IGNORED_FIELDS.contains(&field.as_str()) {
+                continue;
+            }
+            fields.push(field);
+            old_values.push(values["old"].clone());
+            new_values.push(values["new"].clone());
+        }
+
+        if fields.is_empty() {
+            return Ok(0);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO field_changes (
+                complex_id, entity_type, entity_id, field, old_value, new_value, changed_by, request_id
+            )
+            SELECT $1, $2, $3, f.field, NULLIF(f.old_value, 'null'), NULLIF(f.new_value, 'null'), $7, $8
+            FROM UNNEST($4::text[], $5::jsonb[], $6::jsonb[]) AS f(field, old_value, new_value)
+            "#,
+        )
+        .bind(change.complex_id)
+        .bind(change.entity_type)
+        .bind(change.entity_id)
+        .bind(&fields)
+        .bind(&old_values)
+        .bind(&new_values)
+        .bind(change.changed_by)
+        .bind(change.request_id)
+        .execute(db)
+        .await?;
+
+        Ok(fields.len())
+    }
+}
@@ -1,44 +1,84 @@
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use aws_config::BehaviorVersion;
-use aws_sdk_s3::config::{Credentials, Region};
-use aws_sdk_s3::primitives::ByteStream;
-use aws_sdk_s3::Client;
+use crate::models::{JobType, StorageUploadPayload};
+use crate::services::storage::{self, LocalStorage, StorageBackend};
+use crate::services::JobService;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage};
+use sqlx::PgPool;
+use std::time::Duration;
 use uuid::Uuid;
 
+/// Превью для списков и аватаров
+pub const THUMBNAIL_SIZE: u32 = 200;
+/// Вариант для просмотра на телефоне
+pub const MEDIUM_SIZE: u32 = 800;
+/// Качество JPEG для уменьшенных вариантов
+const VARIANT_JPEG_QUALITY: u8 = 80;
+
+/// Загруженное изображение с уменьшенными копиями
+#[derive(Debug, Clone)]
+pub struct UploadedImage {
+    pub url: String,
+    pub thumbnail_url: String,
+    pub medium_url: String,
+}
+
+/// Куда откладывать файлы, если хранилище недоступно
+struct UploadQueue {
+    pool: PgPool,
+    spool: LocalStorage,
+}
+
 pub struct FileService {
-    client: Client,
-    bucket: String,
-    public_url: Option<String>,
+    backend: Box<dyn StorageBackend>,
+    config: Config,
+    upload_queue: Option<UploadQueue>,
 }
 
 impl FileService {
+    /// Хранилище выбирается по `STORAGE_BACKEND`: S3/MinIO или локальный диск
     pub async fn new(config: &Config) -> AppResult<Self> {
-        let credentials = Credentials::new(
-            &config.minio_access_key,
-            &config.minio_secret_key,
-            None,
-            None,
-            "localhood",
-        );
-
-        let s3_config = aws_sdk_s3::Config::builder()
-            .behavior_version(BehaviorVersion::latest())
-            .credentials_provider(credentials)
-            .region(Region::new("us-east-1"))
-            .endpoint_url(&config.minio_endpoint)
-            .force_path_style(true)
-            .build();
-
-        let client = Client::from_conf(s3_config);
-
         Ok(Self {
-            client,
-            bucket: config.minio_bucket.clone(),
-            public_url: config.minio_public_url.clone(),
+            backend: storage::from_config(config),
+            config: config.clone(),
+            upload_queue: None,
         })
     }
 
+    /// При недоступном хранилище сохранять файлы на диск и досылать их фоновой задачей.
+    /// Подходит для загрузок, где клиенту достаточно ссылки, а не самого файла.
+    pub fn with_upload_queue(mut self, pool: &PgPool) -> Self {
+        if !self.backend.is_local() {
+            self.upload_queue = Some(UploadQueue {
+                pool: pool.clone(),
+                spool: LocalStorage::with_root(&self.config, &self.config.upload_spool_path),
+            });
+        }
+        self
+    }
+
+    /// Проверить доступность хранилища
+    pub async fn check(&self) -> AppResult<()> {
+        self.backend.check().await
+    }
+
+    /// Дослать в хранилище отложенный файл
+    pub async fn flush_spooled(&self, payload: &StorageUploadPayload) -> AppResult<()> {
+        let spool = LocalStorage::with_root(&self.config, &self.config.upload_spool_path);
+        let data = match spool.get(&payload.key).await {
+            Ok(data) => data,
+            // Файл уже дослан предыдущей попыткой
+            Err(AppError::NotFound(_)) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+
+        self.backend.put(&payload.key, &payload.content_type, data).await?;
+        spool.delete(&payload.key).await?;
+
+        tracing::info!("Spooled upload {} delivered to storage", payload.key);
+        Ok(())
+    }
+
     pub async fn upload_file(
         &self,
         folder: &str,
@@ -46,53 +86,155 @@ impl FileService {
         content_type: &str,
         data: Vec<u8>,
     ) -> AppResult<String> {
+        let key = Self::new_key(folder, file_name);
+        self.put_object(&key, content_type, data).await?;
+
+        Ok(self.public_url(&key))
+    }
+
+    /// Загрузить изображение вместе с превью и облегчённой копией для мобильных клиентов
+    pub async fn upload_image(
+        &self,
+        folder: &str,
+        file_name: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> AppResult<UploadedImage> {
+        let key = Self::new_key(folder, file_name);
+        let (thumbnail_url, medium_url) = self.store_variants(&key, data.clone()).await?;
+        self.put_object(&key, content_type, data).await?;
+
+        Ok(UploadedImage {
+            url: self.public_url(&key),
+            thumbnail_url,
+            medium_url,
+        })
+    }
+
+    /// Сделать уменьшенные копии для уже загруженного объекта (например, по подписанной ссылке)
+    pub async fn process_stored_image(&self, key: &str) -> AppResult<UploadedImage> {
+        let data = self.backend.get(key).await?;
+        let (thumbnail_url, medium_url) = self.store_variants(key, data).await?;
+
+        Ok(UploadedImage {
+            url: self.public_url(key),
+            thumbnail_url,
+            medium_url,
+        })
+    }
+
+    /// Ключи вариантов строятся от ключа оригинала: `<ключ>_200.jpg`, `<ключ>_800.jpg`
+    async fn store_variants(&self, key: &str, data: Vec<u8>) -> AppResult<(String, String)> {
+        let (thumbnail, medium) = tokio::task::spawn_blocking(move || {
+            let image = image::load_from_memory(&data).map_err(|_| {
+                AppError::BadRequest("Не удалось обработать изображение".to_string())
+            })?;
+            Ok::<_, AppError>((
+                encode_variant(&image, THUMBNAIL_SIZE)?,
+                encode_variant(&image, MEDIUM_SIZE)?,
+            ))
+        })
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))??;
+
+        let stem = key.rsplit_once('.').map_or(key, |(stem, _)| stem);
+        let thumbnail_key = format!("{}_{}.jpg", stem, THUMBNAIL_SIZE);
+        let medium_key = format!("{}_{}.jpg", stem, MEDIUM_SIZE);
+
+        self.put_object(&thumbnail_key, "image/jpeg", thumbnail).await?;
+        self.put_object(&medium_key, "image/jpeg", medium).await?;
+
+        Ok((self.public_url(&thumbnail_key), self.public_url(&medium_key)))
+    }
+
+    async fn put_object(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        let Some(queue) = &self.upload_queue else {
+            return self.backend.put(key, content_type, data).await;
+        };
+
+        match self.backend.put(key, content_type, data.clone()).await {
+            Err(AppError::StorageUnavailable(reason)) => {
+                queue.spool.put(key, content_type, data).await?;
+                let payload = StorageUploadPayload {
+                    key: key.to_string(),
+                    content_type: content_type.to_string(),
+                };
+                JobService::enqueue(&queue.pool, JobType::StorageUpload, &payload).await?;
+
+                tracing::warn!("Storage unavailable ({}), upload {} queued", reason, key);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Сохранить объект под заданным ключом, например сгенерированный документ
+    pub async fn put_file(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<String> {
+        self.backend.put(key, content_type, data).await?;
+        Ok(self.public_url(key))
+    }
+
+    /// Содержимое объекта из хранилища
+    pub async fn get_file(&self, key: &str) -> AppResult<Vec<u8>> {
+        self.backend.get(key).await
+    }
+
+    /// Новый уникальный ключ объекта в папке с расширением исходного файла
+    pub fn new_key(folder: &str, file_name: &str) -> String {
         let extension = file_name
             .rsplit('.')
             .next()
             .unwrap_or("bin");
 
-        let key = format!("{}/{}.{}", folder, Uuid::new_v4(), extension);
-
-        self.client
-            .put_object()
-            .bucket(&self.bucket)
-            .key(&key)
-            .body(ByteStream::from(data))
-            .content_type(content_type)
-            .send()
-            .await
-            .map_err(|e| AppError::File(e.to_string()))?;
-
-        let url = match &self.public_url {
-            Some(base_url) => format!("{}/{}/{}", base_url, self.bucket, key),
-            None => format!("/{}/{}", self.bucket, key),
-        };
+        format!("{}/{}.{}", folder, Uuid::new_v4(), extension)
+    }
 
-        Ok(url)
+    /// Публичная ссылка на объект
+    pub fn public_url(&self, key: &str) -> String {
+        self.backend.public_url(key)
     }
 
-    pub async fn delete_file(&self, key: &str) -> AppResult<()> {
-        self.client
-            .delete_object()
-            .bucket(&self.bucket)
-            .key(key)
-            .send()
-            .await
-            .map_err(|e| AppError::File(e.to_string()))?;
+    /// Подписанная ссылка для загрузки объекта напрямую в хранилище методом PUT
+    pub async fn presigned_upload_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> AppResult<String> {
+        self.backend.presigned_put_url(key, content_type, expires_in).await
+    }
 
-        Ok(())
+    /// Размер загруженного объекта; `None`, если объекта нет
+    pub async fn object_size(&self, key: &str) -> AppResult<Option<i64>> {
+        self.backend.size(key).await
+    }
+
+    pub async fn delete_file(&self, key: &str) -> AppResult<()> {
+        self.backend.delete(key).await
     }
 
     pub fn get_key_from_url(&self, url: &str) -> Option<String> {
-        let prefix = format!("/{}/", self.bucket);
-        if let Some(pos) = url.find(&prefix) {
-            Some(url[pos + prefix.len()..].to_string())
-        } else {
-            None
-        }
+        self.backend.key_from_url(url)
     }
 }
 
+/// Уменьшить изображение, чтобы большая сторона не превышала `size`, и сжать в JPEG.
+/// Маленькие изображения не увеличиваются, только пережимаются.
+fn encode_variant(image: &DynamicImage, size: u32) -> AppResult<Vec<u8>> {
+    let resized = if image.width() > size || image.height() > size {
+        image.resize(size, size, FilterType::Triangle)
+    } else {
+        image.clone()
+    };
+
+    let mut buffer = Vec::new();
+    JpegEncoder::new_with_quality(&mut buffer, VARIANT_JPEG_QUALITY)
+        .encode_image(&resized.to_rgb8())
+        .map_err(|e| AppError::File(e.to_string()))?;
+
+    Ok(buffer)
+}
+
 pub fn validate_image_content_type(content_type: &str) -> bool {
     matches!(
         content_type,
@@ -0,0 +1,344 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    FinancialAuditEntry, FinancialAuditVerification, FinancialContext, FinancialEvent,
+    NewFinancialAuditEntry,
+};
+
+/// Ключ advisory-блокировки: записи журнала добавляются строго по одной,
+/// чтобы цепочка сумм не ветвилась
+const FINANCIAL_AUDIT_LOCK: i64 = 0x4649_4E41_5544_4954;
+
+/// Сколько записей проверять за один запрос к базе
+const VERIFY_BATCH: i64 = 1000;
+
+#[derive(Debug, Default)]
+pub struct FinancialAuditFilter {
+    pub complex_id: Option<Uuid>,
+    pub entity_id: Option<Uuid>,
+    pub event: Option<FinancialEvent>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Неизменяемый журнал операций с деньгами
+pub struct FinancialAuditService;
+
+impl FinancialAuditService {
+    /// Добавить запись в транзакции операции: без фиксации операции не будет и записи
+    pub async fn record(
+        tx: &mut Transaction<'_, Postgres>,
+        context: &FinancialContext,
+        entry: NewFinancialAuditEntry,
+    ) -> AppResult<()> {
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(FINANCIAL_AUDIT_LOCK)
+            .execute(&mut **tx)
+            .await?;
+
+        let prev: Option<(String,)> =
+            sqlx::query_as("SELECT checksum FROM financial_audit_log ORDER BY seq DESC LIMIT 1")
+                .fetch_optional(&mut **tx)
+                .await?;
+
+        // Время с точностью базы, чтобы сумма совпала при проверке
+        let now = Utc::now();
+        let created_at = DateTime::from_timestamp_micros(now.timestamp_micros()).unwrap_or(now);
+
+        let mut row = FinancialAuditEntry {
+            seq: 0,
+            id: Uuid::new_v4(),
+            event: entry.event,
+            complex_id: entry.complex_id,
+            actor_id: context.actor_id,
+            request_id: context.request_id.clone(),
+            entity_type: entry.entity_type.to_string(),
+            entity_id: entry.entity_id,
+            amount: entry.amount.map(|amount| amount.round_dp(2)),
+            provider: entry.provider,
+            provider_reference: entry.provider_reference,
+            details: entry.details,
+            prev_checksum: prev.map(|(checksum,)| checksum),
+            checksum: String::new(),
+            created_at,
+        };
+        row.checksum = checksum(&row);
+
+        sqlx::query(
+            r#"
+            INSERT INTO financial_audit_log (
+                id, event, complex_id, actor_id, request_id, entity_type, entity_id, amount,
+                provider, provider_reference, details, prev_checksum, checksum, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            "#,
+        )
+        .bind(row.id)
+        .bind(row.event)
+        .bind(row.complex_id)
+        .bind(row.actor_id)
+        .bind(&row.request_id)
+        .bind(&row.entity_type)
+        .bind(row.entity_id)
+        .bind(row.amount)
+        .bind(&row.provider)
+        .bind(&row.provider_reference)
+        .bind(&row.details)
+        .bind(&row.prev_checksum)
+        .bind(&row.checksum)
+        .bind(row.created_at)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn list(
+        pool: &PgPool,
+        filter: &FinancialAuditFilter,
+        limit: i64,
+        offset: i64,
+    ) -> AppResult<Vec<FinancialAuditEntry>> {
+        Ok(sqlx::query_as::<_, FinancialAuditEntry>(
+            r#"
+            SELECT * FROM financial_audit_log
+            WHERE ($1::uuid IS NULL OR complex_id = $1)
+              AND ($2::uuid IS NULL OR entity_id = $2)
+              AND ($3::financial_event IS NULL OR event = $3)
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+            ORDER BY seq DESC
+            LIMIT $6 OFFSET $7
+            "#,
+        )
+        .bind(filter.complex_id)
+        .bind(filter.entity_id)
+        .bind(filter.event)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Совпадает ли сумма записи с её содержимым
+    pub fn is_valid(entry: &FinancialAuditEntry) -> bool {
+        checksum(entry) == entry.checksum
+    }
+
+    /// Пройти всю цепочку: сумма каждой записи верна и ссылается на предыдущую
+    pub async fn verify(pool: &PgPool) -> AppResult<FinancialAuditVerification> {
+        let mut chain = ChainCheck::default();
+        let mut last_seq = 0;
+
+        loop {
+            let batch = sqlx::query_as::<_, FinancialAuditEntry>(
+                "SELECT * FROM financial_audit_log WHERE seq > $1 ORDER BY seq LIMIT $2",
+            )
+            .bind(last_seq)
+            .bind(VERIFY_BATCH)
+            .fetch_all(pool)
+            .await?;
+
+            if batch.is_empty() {
+                break;
+            }
+
+            for entry in batch {
+                last_seq = entry.seq;
+                if !chain.push(entry) {
+                    return Ok(chain.result());
+                }
+            }
+        }
+
+        Ok(chain.result())
+    }
+
+    /// ЖК квартиры, к которой относится платёж
+    pub async fn apartment_complex(
+        tx: &mut Transaction<'_, Postgres>,
+        apartment_id: Uuid,
+    ) -> AppResult<Uuid> {
+        let (complex_id,): (Uuid,) = sqlx::query_as("SELECT complex_id FROM apartments WHERE id = $1")
+            .bind(apartment_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+        Ok(complex_id)
+    }
+}
+
+/// Проверка цепочки записей по порядку `seq`
+#[derive(Default)]
+struct ChainCheck {
+    checked: i64,
+    prev: Option<String>,
+    first_invalid_seq: Option<i64>,
+}
+
+impl ChainCheck {
+    /// Проверить следующую запись; `false` — цепочка нарушена на ней
+    fn push(&mut self, entry: FinancialAuditEntry) -> bool {
+        self.checked += 1;
+        if entry.prev_checksum != self.prev || !FinancialAuditService::is_valid(&entry) {
+            self.first_invalid_seq = Some(entry.seq);
+            return false;
+        }
+        self.prev = Some(entry.checksum);
+        true
+    }
+
+    fn result(&self) -> FinancialAuditVerification {
+        FinancialAuditVerification {
+            checked: self.checked,
+            valid: self.first_invalid_seq.is_none(),
+            first_invalid_seq: self.first_invalid_seq,
+        }
+    }
+}
+
+/// SHA-256 от полей записи построчно, начиная с суммы предыдущей записи
+fn checksum(entry: &FinancialAuditEntry) -> String {
+    let opt = |value: Option<String>| value.unwrap_or_default();
+    let fields = [
+        opt(entry.prev_checksum.clone()),
+        entry.id.to_string(),
+        entry.event.as_str().to_string(),
+        opt(entry.complex_id.map(|id| id.to_string())),
+        opt(entry.actor_id.map(|id| id.to_string())),
+        opt(entry.request_id.clone()),
+        entry.entity_type.clone(),
+        entry.entity_id.to_string(),
+        opt(entry.amount.map(|amount| format!("{:.2}", amount))),
+        opt(entry.provider.clone()),
+        opt(entry.provider_reference.clone()),
+        opt(entry.details.as_ref().map(|details| details.to_string())),
+        entry.created_at.timestamp_micros().to_string(),
+    ];
+
+    hex::encode(Sha256::digest(fields.join("\n").as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use serde_json::json;
+
+    /// Цепочка из `amounts.len()` корректных записей
+    fn chain(amounts: &[i64]) -> Vec<FinancialAuditEntry> {
+        let mut prev = None;
+        amounts
+            .iter()
+            .enumerate()
+            .map(|(i, amount)| {
+                let mut entry = FinancialAuditEntry {
+                    seq: i as i64 + 1,
+                    id: Uuid::new_v4(),
+                    event: FinancialEvent::PaymentCompleted,
+                    complex_id: Some(Uuid::new_v4()),
+                    actor_id: None,
+                    request_id: Some("req-1".to_string()),
+                    entity_type: "payment".to_string(),
+                    entity_id: Uuid::new_v4(),
+                    amount: Some(Decimal::new(*amount, 2)),
+                    provider: Some("kaspi".to_string()),
+                    provider_reference: None,
+                    details: Some(json!({"bill_id": i})),
+                    prev_checksum: prev.clone(),
+                    checksum: String::new(),
+                    created_at: Utc::now(),
+                };
+                entry.checksum = checksum(&entry);
+                prev = Some(entry.checksum.clone());
+                entry
+            })
+            .collect()
+    }
+
+    fn verify(entries: Vec<FinancialAuditEntry>) -> FinancialAuditVerification {
+        let mut check = ChainCheck::default();
+        for entry in entries {
+            if !check.push(entry) {
+                break;
+            }
+        }
+        check.result()
+    }
+
+    #[test]
+    fn checksum_is_stable_and_covers_fields() {
+        let entry = chain(&[150000]).remove(0);
+        assert_eq!(checksum(&entry), entry.checksum);
+        assert_eq!(entry.checksum.len(), 64);
+
+        let mut changed = entry.clone();
+        changed.amount = Some(Decimal::new(150001, 2));
+        assert_ne!(checksum(&changed), entry.checksum);
+
+        let mut changed = entry.clone();
+        changed.details = None;
+        assert_ne!(checksum(&changed), entry.checksum);
+    }
+
+    #[test]
+    fn amount_scale_does_not_change_checksum() {
+        let mut entry = chain(&[150000]).remove(0);
+        entry.amount = Some(Decimal::new(15000000, 4));
+        assert!(FinancialAuditService::is_valid(&entry));
+    }
+
+    #[test]
+    fn intact_chain_is_valid() {
+        let result = verify(chain(&[100, 200, 300]));
+        assert!(result.valid);
+        assert_eq!(result.checked, 3);
+        assert_eq!(result.first_invalid_seq, None);
+    }
+
+    #[test]
+    fn empty_chain_is_valid() {
+        let result = verify(Vec::new());
+        assert!(result.valid);
+        assert_eq!(result.checked, 0);
+    }
+
+    #[test]
+    fn tampered_entry_breaks_chain() {
+        let mut entries = chain(&[100, 200, 300]);
+        entries[1].amount = Some(Decimal::new(1, 2));
+
+        let result = verify(entries);
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_seq, Some(2));
+        assert_eq!(result.checked, 2);
+    }
+
+    #[test]
+    fn deleted_entry_breaks_chain() {
+        let mut entries = chain(&[100, 200, 300]);
+        entries.remove(1);
+
+        let result = verify(entries);
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_seq, Some(3));
+    }
+
+    #[test]
+    fn recomputed_checksum_does_not_hide_tampering() {
+        let mut entries = chain(&[100, 200, 300]);
+        entries[0].amount = Some(Decimal::new(1, 2));
+        entries[0].checksum = checksum(&entries[0]);
+
+        let result = verify(entries);
+        assert!(!result.valid);
+        assert_eq!(result.first_invalid_seq, Some(2));
+    }
+}
@@ -0,0 +1,390 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    ApartmentIntercomCode, Intercom, IntercomCall, IntercomCallStatus, NotificationType,
+};
+use crate::services::{AuthService, FileService, NotificationService, SchedulerService};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Как часто удаляем устаревшие и запрошенные к удалению снимки
+const SNAPSHOT_PURGE_INTERVAL_SECS: u64 = 3600;
+
+/// Сколько снимков удаляем за один проход
+const SNAPSHOT_PURGE_BATCH: i64 = 500;
+
+/// Через сколько неотвеченный вызов считается пропущенным
+const RING_TIMEOUT_SECS: i32 = 60;
+
+/// Как часто закрываем неотвеченные вызовы
+const MISSED_CALLS_INTERVAL_SECS: u64 = 30;
+
+/// Таймаут запроса к устройству
+const DEVICE_TIMEOUT_SECS: u64 = 5;
+
+/// Сколько неверных кодов квартиры подряд допускаем, прежде чем перестать их проверять
+const CODE_MAX_FAILURES: i64 = 5;
+
+/// За какой срок считаем неверные коды, минут
+const CODE_FAILURE_WINDOW_MINUTES: i32 = 15;
+
+pub struct IntercomService;
+
+impl IntercomService {
+    /// Зарегистрировать периодические задачи домофона
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "intercom_purge_snapshots",
+            Duration::from_secs(SNAPSHOT_PURGE_INTERVAL_SECS),
+            |pool, config| async move {
+                let purged = IntercomService::purge_snapshots(&pool, &config).await?;
+                if purged > 0 {
+                    tracing::info!("Purged {} intercom snapshots", purged);
+                }
+                Ok(())
+            },
+        );
+
+        scheduler.register(
+            "intercom_missed_calls",
+            Duration::from_secs(MISSED_CALLS_INTERVAL_SECS),
+            |pool, _config| async move {
+                let missed = IntercomService::close_unanswered_calls(&pool).await?;
+                if missed > 0 {
+                    tracing::info!("Marked {} intercom calls as missed", missed);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Открыть дверь на устройстве. Способ зависит от `device_type`:
+    /// `http` — POST на адрес устройства, `beward` — CGI-команда панели,
+    /// `sip` — команда SIP-шлюзу, который отправляет DTMF в вызов.
+    pub async fn open_door(config: &Config, intercom: &Intercom) -> AppResult<()> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(DEVICE_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| AppError::Intercom(e.to_string()))?;
+
+        let request = match intercom.device_type.as_deref() {
+            Some("http") => client.post(Self::api_url(intercom)?),
+            Some("beward") => client.get(format!(
+                "{}/cgi-bin/intercom_cgi?action=maindoor",
+                Self::api_url(intercom)?.trim_end_matches('/')
+            )),
+            Some("sip") => {
+                let gateway = config.intercom_sip_gateway_url.as_deref().ok_or_else(|| {
+                    AppError::Intercom("SIP-шлюз не настроен".to_string())
+                })?;
+                let sip_address = intercom.sip_address.as_deref().ok_or_else(|| {
+                    AppError::Intercom("У домофона не указан SIP-адрес".to_string())
+                })?;
+
+                client
+                    .post(format!("{}/open", gateway.trim_end_matches('/')))
+                    .json(&json!({"sip_address": sip_address, "device_id": intercom.device_id}))
+            }
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Домофон не поддерживает удалённое открытие".to_string(),
+                ))
+            }
+        };
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| {
+                // Адрес устройства и шлюза может содержать учётные данные — клиенту его не отдаём
+                tracing::warn!("Intercom {} request failed: {}", intercom.id, e.without_url());
+                AppError::Intercom(format!("Домофон {} недоступен", intercom.name))
+            })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Intercom(format!(
+                "Домофон {} вернул {}",
+                intercom.name,
+                response.status()
+            )));
+        }
+
+        tracing::info!("Intercom {} opened", intercom.id);
+        Ok(())
+    }
+
+    fn api_url(intercom: &Intercom) -> AppResult<&str> {
+        intercom
+            .api_url
+            .as_deref()
+            .ok_or_else(|| AppError::Intercom("У домофона не указан адрес API".to_string()))
+    }
+
+    /// Зарегистрировать вызов с панели и позвонить жителям квартиры
+    pub async fn ring(
+        pool: &PgPool,
+        intercom: &Intercom,
+        apartment_id: Uuid,
+        snapshot_url: Option<&str>,
+    ) -> AppResult<IntercomCall> {
+        let call = sqlx::query_as::<_, IntercomCall>(
+            r#"
+            INSERT INTO intercom_calls (intercom_id, apartment_id, status, snapshot_url)
+            VALUES ($1, $2, 'ringing', $3)
+            RETURNING *
+            "#,
+        )
+        .bind(intercom.id)
+        .bind(apartment_id)
+        .bind(snapshot_url)
+        .fetch_one(pool)
+        .await?;
+
+        let residents: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT u FROM apartments a, UNNEST(ARRAY[a.owner_id, a.resident_id]) AS u
+            WHERE a.id = $1 AND u IS NOT NULL
+            "#,
+        )
+        .bind(apartment_id)
+        .fetch_all(pool)
+        .await?;
+
+        let user_ids: Vec<Uuid> = residents.into_iter().map(|(id,)| id).collect();
+        NotificationService::notify_users(
+            pool,
+            &user_ids,
+            NotificationType::Security,
+            "Звонок в домофон",
+            Some(&intercom.name),
+            Some(json!({
+                "intercom_call_id": call.id,
+                "intercom_id": intercom.id,
+                "snapshot_url": snapshot_url,
+            })),
+        )
+        .await?;
+
+        Ok(call)
+    }
+
+    /// Выдать квартире новый код домофона; прежний сразу перестаёт действовать,
+    /// а счётчик неверных попыток начинается заново
+    pub async fn rotate_code(
+        pool: &PgPool,
+        apartment_id: Uuid,
+        rotated_by: Uuid,
+    ) -> AppResult<ApartmentIntercomCode> {
+        let code = AuthService::generate_access_code();
+        let mut rotated = sqlx::query_as::<_, ApartmentIntercomCode>(
+            r#"
+            INSERT INTO apartment_intercom_codes (apartment_id, complex_id, code_hash, rotated_by)
+            SELECT id, complex_id, $2, $3 FROM apartments WHERE id = $1
+            ON CONFLICT (apartment_id) DO UPDATE
+                SET code_hash = EXCLUDED.code_hash, rotated_by = EXCLUDED.rotated_by, rotated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(apartment_id)
+        .bind(code_hash(apartment_id, &code))
+        .bind(rotated_by)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Квартира не найдена".to_string()))?;
+
+        rotated.code = Some(code);
+        Ok(rotated)
+    }
+
+    /// Проверить код, набранный на панели, и записать попытку в историю вызовов квартиры.
+    /// После серии неверных кодов попытки отклоняются без проверки, пока владелец
+    /// не сменит код или не истечёт окно подсчёта
+    pub async fn check_code(
+        pool: &PgPool,
+        intercom: &Intercom,
+        apartment_id: Uuid,
+        code: &str,
+    ) -> AppResult<IntercomCall> {
+        // Попытки по квартире идут по одной: строка кода блокируется до записи результата,
+        // иначе параллельные запросы панели увидят одно и то же число неудач и обойдут лимит
+        let mut tx = pool.begin().await?;
+
+        let stored: Option<(String,)> = sqlx::query_as(
+            "SELECT code_hash FROM apartment_intercom_codes WHERE apartment_id = $1 FOR UPDATE",
+        )
+        .bind(apartment_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (failures,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM intercom_calls
+            WHERE apartment_id = $1 AND status = 'code_denied'
+              AND created_at > NOW() - make_interval(mins => $2)
+              AND created_at > GREATEST(
+                  (SELECT MAX(created_at) FROM intercom_calls
+                   WHERE apartment_id = $1 AND status = 'code_opened'),
+                  (SELECT rotated_at FROM apartment_intercom_codes WHERE apartment_id = $1),
+                  '-infinity'
+              )
+            "#,
+        )
+        .bind(apartment_id)
+        .bind(CODE_FAILURE_WINDOW_MINUTES)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let valid = failures < CODE_MAX_FAILURES
+            && stored.is_some_and(|(stored,)| stored == code_hash(apartment_id, code.trim()));
+
+        let status = if valid {
+            IntercomCallStatus::CodeOpened
+        } else {
+            IntercomCallStatus::CodeDenied
+        };
+        let call = sqlx::query_as::<_, IntercomCall>(
+            r#"
+            INSERT INTO intercom_calls (intercom_id, apartment_id, status, ended_at)
+            VALUES ($1, $2, $3, NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(intercom.id)
+        .bind(apartment_id)
+        .bind(status)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        // О начавшемся переборе жители узнают один раз, на пороге
+        if !valid && failures + 1 == CODE_MAX_FAILURES {
+            let residents: Vec<(Uuid,)> = sqlx::query_as(
+                r#"
+                SELECT u FROM apartments a, UNNEST(ARRAY[a.owner_id, a.resident_id]) AS u
+                WHERE a.id = $1 AND u IS NOT NULL
+                "#,
+            )
+            .bind(apartment_id)
+            .fetch_all(pool)
+            .await?;
+
+            let user_ids: Vec<Uuid> = residents.into_iter().map(|(id,)| id).collect();
+            NotificationService::notify_users(
+                pool,
+                &user_ids,
+                NotificationType::Security,
+                "Кто-то подбирает код вашей квартиры",
+                Some(&format!(
+                    "{}: код временно не принимается. Смените код в приложении — новый заработает сразу.",
+                    intercom.name
+                )),
+                Some(json!({"intercom_id": intercom.id, "apartment_id": apartment_id})),
+            )
+            .await?;
+        }
+
+        Ok(call)
+    }
+
+    /// Перевести вызов в новый статус, если он ещё в одном из ожидаемых
+    pub async fn transition(
+        pool: &PgPool,
+        call_id: Uuid,
+        user_id: Uuid,
+        from: &[IntercomCallStatus],
+        to: IntercomCallStatus,
+    ) -> AppResult<IntercomCall> {
+        let finished = to != IntercomCallStatus::Answered;
+
+        sqlx::query_as::<_, IntercomCall>(
+            r#"
+            UPDATE intercom_calls SET
+                status = $4,
+                user_id = $3,
+                answered_at = CASE WHEN $4 = 'answered' THEN NOW() ELSE answered_at END,
+                ended_at = CASE WHEN $5 THEN NOW() ELSE ended_at END,
+                duration_seconds = CASE
+                    WHEN $5 AND answered_at IS NOT NULL
+                    THEN EXTRACT(EPOCH FROM NOW() - answered_at)::int
+                    ELSE duration_seconds
+                END
+            WHERE id = $1 AND status = ANY($2)
+            RETURNING *
+            "#,
+        )
+        .bind(call_id)
+        .bind(from)
+        .bind(user_id)
+        .bind(to)
+        .bind(finished)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Вызов уже завершён".to_string()))
+    }
+
+    /// Отметить пропущенными вызовы, на которые не ответили вовремя
+    pub async fn close_unanswered_calls(pool: &PgPool) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE intercom_calls SET status = 'missed', ended_at = NOW()
+            WHERE status = 'ringing' AND created_at < NOW() - make_interval(secs => $1)
+            "#,
+        )
+        .bind(RING_TIMEOUT_SECS)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Удалить снимки старше срока хранения и те, что жители попросили удалить
+    pub async fn purge_snapshots(pool: &PgPool, config: &Config) -> AppResult<usize> {
+        let snapshots: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            SELECT id, snapshot_url FROM intercom_calls
+            WHERE snapshot_url IS NOT NULL
+              AND (snapshot_delete_requested_at IS NOT NULL
+                   OR created_at < NOW() - make_interval(days => $1))
+            LIMIT $2
+            "#,
+        )
+        .bind(config.intercom_snapshot_retention_days as i32)
+        .bind(SNAPSHOT_PURGE_BATCH)
+        .fetch_all(pool)
+        .await?;
+
+        if snapshots.is_empty() {
+            return Ok(0);
+        }
+
+        let file_service = FileService::new(config).await?;
+
+        for (call_id, url) in &snapshots {
+            if let Some(key) = file_service.get_key_from_url(url) {
+                // Файл могли удалить вручную, ссылку всё равно убираем
+                if let Err(e) = file_service.delete_file(&key).await {
+                    tracing::warn!("Failed to delete intercom snapshot {}: {}", call_id, e);
+                }
+            }
+        }
+
+        let ids: Vec<Uuid> = snapshots.iter().map(|(id, _)| *id).collect();
+        sqlx::query(
+            "UPDATE intercom_calls SET snapshot_url = NULL, snapshot_purged_at = NOW() WHERE id = ANY($1)",
+        )
+        .bind(&ids)
+        .execute(pool)
+        .await?;
+
+        Ok(snapshots.len())
+    }
+}
+
+/// Хэш кода домофона; квартира служит солью, чтобы одинаковые коды разных квартир не совпадали
+fn code_hash(apartment_id: Uuid, code: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", apartment_id, code).as_bytes()))
+}
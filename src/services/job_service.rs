@@ -0,0 +1,393 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    AddressRegistryImportPayload, CommunicationExportPayload, EmailNotificationPayload,
+    GuestEntryNotificationPayload, Job, JobType,
+    NotificationFanoutPayload, SharedChargeBillingPayload, SmsDeliveryPayload,
+    StorageUploadPayload, TelegramNotificationPayload, WarehouseExportPayload,
+};
+use crate::services::{
+    AddressRegistryService, BarrierService, CommunicationExportService, EmailService, FileService,
+    SharedChargeService,
+    SmsService, TelegramService, WarehouseExportService,
+};
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::{PgExecutor, PgPool};
+use std::sync::Arc;
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Через сколько задача в статусе running считается зависшей
+const STALE_LOCK_MINUTES: i64 = 10;
+
+/// Максимальная задержка между повторами
+const MAX_RETRY_DELAY_SECS: i64 = 3600;
+
+/// Будит воркеры сразу после постановки задачи, не дожидаясь опроса
+static JOB_NOTIFY: Lazy<Notify> = Lazy::new(Notify::new);
+
+#[derive(Clone)]
+pub struct JobService {
+    pool: PgPool,
+    config: Config,
+}
+
+impl JobService {
+    pub fn new(pool: PgPool, config: Config) -> Self {
+        Self { pool, config }
+    }
+
+    /// Поставить задачу в очередь на немедленное выполнение. В транзакции задача
+    /// появится в очереди только вместе с её фиксацией
+    pub async fn enqueue<'e, T: Serialize>(
+        db: impl PgExecutor<'e>,
+        job_type: JobType,
+        payload: &T,
+    ) -> AppResult<Uuid> {
+        Self::enqueue_at(db, job_type, payload, Utc::now()).await
+    }
+
+    /// Поставить задачу в очередь на указанное время
+    pub async fn enqueue_at<'e, T: Serialize>(
+        db: impl PgExecutor<'e>,
+        job_type: JobType,
+        payload: &T,
+        run_at: DateTime<Utc>,
+    ) -> AppResult<Uuid> {
+        let payload = serde_json::to_value(payload)
+            .map_err(|e| AppError::Internal(format!("Ошибка сериализации задачи: {}", e)))?;
+
+        let (id,): (Uuid,) = sqlx::query_as(
+            "INSERT INTO jobs (job_type, payload, run_at) VALUES ($1, $2, $3) RETURNING id",
+        )
+        .bind(job_type)
+        .bind(payload)
+        .bind(run_at)
+        .fetch_one(db)
+        .await?;
+
+        JOB_NOTIFY.notify_one();
+
+        Ok(id)
+    }
+
+    /// Вернуть задачу из dead-letter обратно в очередь
+    pub async fn retry(pool: &PgPool, job_id: Uuid) -> AppResult<Job> {
+        let job = sqlx::query_as::<_, Job>(
+            r#"
+            UPDATE jobs
+            SET status = 'pending', attempts = 0, run_at = NOW(), locked_at = NULL, updated_at = NOW()
+            WHERE id = $1 AND status = 'dead'
+            RETURNING *
+            "#,
+        )
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Задача не найдена в dead-letter".to_string()))?;
+
+        JOB_NOTIFY.notify_one();
+
+        Ok(job)
+    }
+
+    /// Запустить воркеры очереди
+    pub fn start(self) {
+        let concurrency = self.config.job_worker_concurrency.max(1);
+        let service = Arc::new(self);
+
+        for worker in 0..concurrency {
+            let service = service.clone();
+            tokio::spawn(async move { service.run_worker(worker).await });
+        }
+
+        tracing::info!("Started {} job workers", concurrency);
+    }
+
+    async fn run_worker(&self, worker: usize) {
+        let poll_interval = std::time::Duration::from_secs(self.config.job_poll_interval_secs.max(1));
+
+        loop {
+            if worker == 0 {
+                if let Err(e) = self.release_stale_locks().await {
+                    tracing::error!("Failed to release stale jobs: {}", e);
+                }
+            }
+
+            match self.claim().await {
+                Ok(Some(job)) => {
+                    self.process(job).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Job worker {} failed to claim job: {}", worker, e),
+            }
+
+            tokio::select! {
+                _ = JOB_NOTIFY.notified() => {}
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+        }
+    }
+
+    /// Взять следующую задачу из очереди
+    async fn claim(&self) -> AppResult<Option<Job>> {
+        let job = sqlx::query_as::<_, Job>(
+            r#"
+            UPDATE jobs
+            SET status = 'running', attempts = attempts + 1, locked_at = NOW(), updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM jobs
+                WHERE status = 'pending' AND run_at <= NOW()
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    async fn process(&self, job: Job) {
+        match self.execute(&job).await {
+            Ok(()) => {
+                let result = sqlx::query(
+                    r#"
+                    UPDATE jobs
+                    SET status = 'completed', completed_at = NOW(), locked_at = NULL,
+                        last_error = NULL, updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(job.id)
+                .execute(&self.pool)
+                .await;
+
+                if let Err(e) = result {
+                    tracing::error!("Failed to mark job {} completed: {}", job.id, e);
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Job {} ({:?}) failed on attempt {}/{}: {}",
+                    job.id,
+                    job.job_type,
+                    job.attempts,
+                    job.max_attempts,
+                    e
+                );
+
+                if let Err(e) = self.fail(&job, &e.to_string()).await {
+                    tracing::error!("Failed to reschedule job {}: {}", job.id, e);
+                }
+            }
+        }
+    }
+
+    /// Перенести задачу на повтор или отправить в dead-letter
+    async fn fail(&self, job: &Job, error: &str) -> AppResult<()> {
+        if job.attempts >= job.max_attempts {
+            sqlx::query(
+                r#"
+                UPDATE jobs
+                SET status = 'dead', last_error = $2, locked_at = NULL, updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(job.id)
+            .bind(error)
+            .execute(&self.pool)
+            .await?;
+
+            tracing::error!("Job {} moved to dead-letter after {} attempts", job.id, job.attempts);
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'pending', last_error = $2, run_at = $3, locked_at = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(job.id)
+        .bind(error)
+        .bind(Utc::now() + retry_delay(job.attempts))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Вернуть в очередь задачи, воркер которых упал во время выполнения
+    async fn release_stale_locks(&self) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = 'pending', locked_at = NULL, updated_at = NOW()
+            WHERE status = 'running' AND locked_at < NOW() - make_interval(mins => $1)
+            "#,
+        )
+        .bind(STALE_LOCK_MINUTES as i32)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            tracing::warn!("Released {} stale jobs", result.rows_affected());
+        }
+
+        Ok(())
+    }
+
+    async fn execute(&self, job: &Job) -> AppResult<()> {
+        match job.job_type {
+            JobType::NotificationFanout => {
+                let payload: NotificationFanoutPayload = parse_payload(job)?;
+                self.notification_fanout(payload).await
+            }
+            JobType::SharedChargeBilling => {
+                let payload: SharedChargeBillingPayload = parse_payload(job)?;
+                SharedChargeService::bill(&self.pool, payload.charge_id).await
+            }
+            JobType::SmsDelivery => {
+                let payload: SmsDeliveryPayload = parse_payload(job)?;
+                SmsService::new(self.config.clone())
+                    .deliver_queued(&self.pool, &payload)
+                    .await
+            }
+            JobType::StorageUpload => {
+                let payload: StorageUploadPayload = parse_payload(job)?;
+                FileService::new(&self.config)
+                    .await?
+                    .flush_spooled(&payload)
+                    .await
+            }
+            JobType::AddressRegistryImport => {
+                let payload: AddressRegistryImportPayload = parse_payload(job)?;
+                AddressRegistryService::import(&self.pool, &payload.source_url)
+                    .await
+                    .map(|_| ())
+            }
+            JobType::CommunicationExport => {
+                let payload: CommunicationExportPayload = parse_payload(job)?;
+                let file_service = FileService::new(&self.config).await?;
+                CommunicationExportService::generate(&self.pool, &file_service, payload.export_id)
+                    .await
+            }
+            JobType::WarehouseExport => {
+                let payload: WarehouseExportPayload = parse_payload(job)?;
+                let file_service = FileService::new(&self.config).await?;
+                WarehouseExportService::generate(&self.pool, &file_service, payload.run_id).await
+            }
+            JobType::GuestEntryNotification => {
+                let payload: GuestEntryNotificationPayload = parse_payload(job)?;
+                BarrierService::new(SmsService::new(self.config.clone()))
+                    .retry_entry_notification(&self.pool, payload.guest_access_id)
+                    .await
+            }
+            JobType::EmailNotification => {
+                let payload: EmailNotificationPayload = parse_payload(job)?;
+                EmailService::new(self.config.clone())
+                    .deliver_queued(&self.pool, &payload)
+                    .await
+            }
+            JobType::TelegramNotification => {
+                let payload: TelegramNotificationPayload = parse_payload(job)?;
+                TelegramService::new(self.config.clone())
+                    .deliver_queued(&self.pool, &payload)
+                    .await
+            }
+        }
+    }
+
+    async fn notification_fanout(&self, payload: NotificationFanoutPayload) -> AppResult<()> {
+        let mut tx = self.pool.begin().await?;
+
+        // Рассылка запоминается целиком, чтобы потом показать охват и прочтения
+        let (broadcast_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO notification_broadcasts (complex_id, notification_type, title, body, data)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(payload.complex_id)
+        .bind(&payload.notification_type)
+        .bind(&payload.title)
+        .bind(&payload.body)
+        .bind(&payload.data)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let recipients: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO notifications (user_id, notification_type, title, body, data, broadcast_id)
+            SELECT DISTINCT u.user_id, $2, $3, $4, $5, $7
+            FROM (
+                SELECT owner_id AS user_id FROM apartments WHERE complex_id = $1
+                UNION
+                SELECT resident_id AS user_id FROM apartments WHERE complex_id = $1
+            ) u
+            WHERE u.user_id IS NOT NULL
+              AND ($6::uuid IS NULL OR u.user_id <> $6)
+              AND NOT EXISTS (
+                  SELECT 1 FROM notification_settings s
+                  WHERE s.user_id = u.user_id AND s.notification_type = $2 AND NOT s.in_app
+              )
+            RETURNING user_id
+            "#,
+        )
+        .bind(payload.complex_id)
+        .bind(&payload.notification_type)
+        .bind(&payload.title)
+        .bind(&payload.body)
+        .bind(&payload.data)
+        .bind(payload.exclude_user_id)
+        .bind(broadcast_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let recipients: Vec<Uuid> = recipients.into_iter().map(|(id,)| id).collect();
+
+        sqlx::query("UPDATE notification_broadcasts SET recipients_count = $2 WHERE id = $1")
+            .bind(broadcast_id)
+            .bind(recipients.len() as i32)
+            .execute(&mut *tx)
+            .await?;
+
+        // В той же транзакции: при повторе задачи рассылка не задвоится
+        let text = match &payload.body {
+            Some(body) => format!("{}\n\n{}", payload.title, body),
+            None => payload.title.clone(),
+        };
+        TelegramService::enqueue_with(&mut tx, &recipients, payload.notification_type.clone(), text)
+            .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            "Fanned out notification to {} users of complex {}",
+            recipients.len(),
+            payload.complex_id
+        );
+
+        Ok(())
+    }
+}
+
+fn parse_payload<T: serde::de::DeserializeOwned>(job: &Job) -> AppResult<T> {
+    serde_json::from_value(job.payload.clone())
+        .map_err(|e| AppError::Internal(format!("Некорректные данные задачи: {}", e)))
+}
+
+/// Экспоненциальная задержка перед повтором: 30с, 1м, 2м, ... но не больше часа
+pub fn retry_delay(attempts: i32) -> Duration {
+    let exp = attempts.clamp(1, 20) as u32 - 1;
+    let secs = 30i64.saturating_mul(1i64 << exp).min(MAX_RETRY_DELAY_SECS);
+    Duration::seconds(secs)
+}
@@ -0,0 +1,234 @@
+use rust_decimal::Decimal;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    MaintenanceCostItem, MaintenanceCostItemRequest, MaintenanceCostKind, MaintenanceCostsResponse,
+    MaintenanceRequest, MaintenanceStatus,
+};
+
+/// Материалы и работы по заявкам на обслуживание
+pub struct MaintenanceCostService;
+
+impl MaintenanceCostService {
+    pub async fn costs(pool: &PgPool, request: &MaintenanceRequest) -> AppResult<MaintenanceCostsResponse> {
+        let items = sqlx::query_as::<_, MaintenanceCostItem>(
+            "SELECT * FROM maintenance_cost_items WHERE request_id = $1 ORDER BY created_at",
+        )
+        .bind(request.id)
+        .fetch_all(pool)
+        .await?;
+
+        let sum = |kind: MaintenanceCostKind| -> Decimal {
+            items.iter().filter(|i| i.kind == kind).map(|i| i.amount).sum()
+        };
+        let labor_hours = items
+            .iter()
+            .filter(|i| i.kind == MaintenanceCostKind::Labor)
+            .map(|i| i.quantity)
+            .sum();
+
+        Ok(MaintenanceCostsResponse {
+            request_id: request.id,
+            estimated_cost: request.estimated_cost,
+            total_cost: request.total_cost,
+            materials: sum(MaintenanceCostKind::Material),
+            labor: sum(MaintenanceCostKind::Labor),
+            labor_hours,
+            other: sum(MaintenanceCostKind::Other),
+            items,
+        })
+    }
+
+    pub async fn set_estimate(
+        pool: &PgPool,
+        request: &MaintenanceRequest,
+        estimated_cost: Option<Decimal>,
+    ) -> AppResult<MaintenanceRequest> {
+        Self::check_editable(request)?;
+        if estimated_cost.is_some_and(|cost| cost < Decimal::ZERO) {
+            return Err(AppError::Validation(
+                "Оценка стоимости не может быть отрицательной".to_string(),
+            ));
+        }
+
+        Ok(sqlx::query_as::<_, MaintenanceRequest>(
+            r#"
+            UPDATE maintenance_requests SET estimated_cost = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(request.id)
+        .bind(estimated_cost.map(|cost| cost.round_dp(2)))
+        .fetch_one(pool)
+        .await?)
+    }
+
+    pub async fn add_item(
+        pool: &PgPool,
+        request: &MaintenanceRequest,
+        created_by: Uuid,
+        payload: &MaintenanceCostItemRequest,
+    ) -> AppResult<MaintenanceCostItem> {
+        Self::check_editable(request)?;
+        let (description, unit, amount) = Self::validate(payload)?;
+
+        let mut tx = pool.begin().await?;
+
+        let item = sqlx::query_as::<_, MaintenanceCostItem>(
+            r#"
+            INSERT INTO maintenance_cost_items (
+                request_id, kind, description, quantity, unit, unit_price, amount, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING *
+            "#,
+        )
+        .bind(request.id)
+        .bind(payload.kind)
+        .bind(description)
+        .bind(payload.quantity)
+        .bind(unit)
+        .bind(payload.unit_price)
+        .bind(amount)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::recalculate(&mut tx, request.id).await?;
+        tx.commit().await?;
+
+        Ok(item)
+    }
+
+    pub async fn update_item(
+        pool: &PgPool,
+        request: &MaintenanceRequest,
+        item_id: Uuid,
+        payload: &MaintenanceCostItemRequest,
+    ) -> AppResult<MaintenanceCostItem> {
+        Self::check_editable(request)?;
+        let (description, unit, amount) = Self::validate(payload)?;
+
+        let mut tx = pool.begin().await?;
+
+        let item = sqlx::query_as::<_, MaintenanceCostItem>(
+            r#"
+            UPDATE maintenance_cost_items SET
+                kind = $3,
+                description = $4,
+                quantity = $5,
+                unit = $6,
+                unit_price = $7,
+                amount = $8,
+                updated_at = NOW()
+            WHERE id = $1 AND request_id = $2
+            RETURNING *
+            "#,
+        )
+        .bind(item_id)
+        .bind(request.id)
+        .bind(payload.kind)
+        .bind(description)
+        .bind(payload.quantity)
+        .bind(unit)
+        .bind(payload.unit_price)
+        .bind(amount)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Позиция не найдена".to_string()))?;
+
+        Self::recalculate(&mut tx, request.id).await?;
+        tx.commit().await?;
+
+        Ok(item)
+    }
+
+    pub async fn delete_item(
+        pool: &PgPool,
+        request: &MaintenanceRequest,
+        item_id: Uuid,
+    ) -> AppResult<MaintenanceCostItem> {
+        Self::check_editable(request)?;
+
+        let mut tx = pool.begin().await?;
+
+        let item = sqlx::query_as::<_, MaintenanceCostItem>(
+            "DELETE FROM maintenance_cost_items WHERE id = $1 AND request_id = $2 RETURNING *",
+        )
+        .bind(item_id)
+        .bind(request.id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Позиция не найдена".to_string()))?;
+
+        Self::recalculate(&mut tx, request.id).await?;
+        tx.commit().await?;
+
+        Ok(item)
+    }
+
+    /// Стоимость ведётся и после завершения, но не по отменённым и отклонённым заявкам
+    fn check_editable(request: &MaintenanceRequest) -> AppResult<()> {
+        if matches!(
+            request.status,
+            MaintenanceStatus::Cancelled | MaintenanceStatus::Rejected
+        ) {
+            return Err(AppError::BadRequest(
+                "Стоимость закрытой заявки не меняется".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Описание, единица измерения и сумма позиции
+    fn validate(payload: &MaintenanceCostItemRequest) -> AppResult<(&str, Option<&str>, Decimal)> {
+        let description = payload.description.trim();
+        if description.is_empty() || description.chars().count() > 200 {
+            return Err(AppError::Validation(
+                "Описание позиции — от 1 до 200 символов".to_string(),
+            ));
+        }
+        if payload.quantity <= Decimal::ZERO {
+            return Err(AppError::Validation(
+                "Количество должно быть больше нуля".to_string(),
+            ));
+        }
+        if payload.unit_price < Decimal::ZERO {
+            return Err(AppError::Validation(
+                "Цена не может быть отрицательной".to_string(),
+            ));
+        }
+
+        let unit = payload
+            .unit
+            .as_deref()
+            .map(str::trim)
+            .filter(|unit| !unit.is_empty());
+        if unit.is_some_and(|unit| unit.chars().count() > 20) {
+            return Err(AppError::Validation(
+                "Единица измерения — не длиннее 20 символов".to_string(),
+            ));
+        }
+
+        Ok((description, unit, (payload.quantity * payload.unit_price).round_dp(2)))
+    }
+
+    async fn recalculate(tx: &mut Transaction<'_, Postgres>, request_id: Uuid) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE maintenance_requests SET
+                total_cost = (SELECT COALESCE(SUM(amount), 0) FROM maintenance_cost_items WHERE request_id = $1),
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(request_id)
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+}
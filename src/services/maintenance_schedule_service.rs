@@ -0,0 +1,379 @@
+use chrono::{Duration, NaiveDate, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateMaintenanceScheduleRequest, MaintenanceFrequency, MaintenancePriority,
+    MaintenanceSchedule, PlannedMaintenanceResponse, UpdateMaintenanceScheduleRequest,
+};
+use crate::services::SchedulerService;
+use crate::utils::recurrence::next_occurrence;
+
+/// Как часто проверять графики, у которых наступила дата работ
+const MAINTENANCE_SCHEDULE_INTERVAL_SECS: u64 = 3600;
+
+/// Сколько графиков обрабатывать за один проход
+const MAINTENANCE_SCHEDULE_BATCH: i64 = 100;
+
+/// Насколько вперёд можно смотреть плановые работы, дней
+pub const MAX_PLANNED_DAYS: i64 = 365;
+
+/// Сколько ближайших дат показывать по одному графику
+const MAX_PLANNED_PER_SCHEDULE: usize = 12;
+
+/// Повторяющиеся плановые работы и заявки по ним
+pub struct MaintenanceScheduleService;
+
+impl MaintenanceScheduleService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "maintenance_schedules",
+            std::time::Duration::from_secs(MAINTENANCE_SCHEDULE_INTERVAL_SECS),
+            |pool, _config| async move {
+                let created =
+                    MaintenanceScheduleService::run_due(&pool, Utc::now().date_naive()).await?;
+                if created > 0 {
+                    tracing::info!("Created {} planned maintenance requests", created);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    pub async fn list(pool: &PgPool, complex_id: Uuid) -> AppResult<Vec<MaintenanceSchedule>> {
+        Ok(sqlx::query_as::<_, MaintenanceSchedule>(
+            r#"
+            SELECT * FROM maintenance_schedules
+            WHERE complex_id = $1
+            ORDER BY is_active DESC, next_run_on NULLS LAST, created_at
+            "#,
+        )
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn get(
+        pool: &PgPool,
+        id: Uuid,
+        complex_ids: &[Uuid],
+    ) -> AppResult<MaintenanceSchedule> {
+        sqlx::query_as::<_, MaintenanceSchedule>(
+            "SELECT * FROM maintenance_schedules WHERE id = $1 AND complex_id = ANY($2)",
+        )
+        .bind(id)
+        .bind(complex_ids)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("График работ не найден".to_string()))
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        complex_id: Uuid,
+        created_by: Uuid,
+        payload: &CreateMaintenanceScheduleRequest,
+    ) -> AppResult<MaintenanceSchedule> {
+        let title = validate_title(&payload.title)?;
+        let interval = payload.interval_count.unwrap_or(1);
+        validate_recurrence(interval, payload.starts_on, payload.ends_on)?;
+        if let Some(worker_id) = payload.assigned_to {
+            Self::check_worker(pool, complex_id, worker_id).await?;
+        }
+
+        let next_run_on = next_run(
+            payload.frequency,
+            interval,
+            payload.starts_on,
+            payload.ends_on,
+            Utc::now().date_naive(),
+        );
+
+        Ok(sqlx::query_as::<_, MaintenanceSchedule>(
+            r#"
+            INSERT INTO maintenance_schedules (
+                complex_id, created_by, category, title, description, location, priority,
+                assigned_to, frequency, interval_count, starts_on, ends_on, next_run_on
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING *
+            "#,
+        )
+        .bind(complex_id)
+        .bind(created_by)
+        .bind(&payload.category)
+        .bind(title)
+        .bind(&payload.description)
+        .bind(&payload.location)
+        .bind(payload.priority.clone().unwrap_or(MaintenancePriority::Normal))
+        .bind(payload.assigned_to)
+        .bind(payload.frequency)
+        .bind(interval)
+        .bind(payload.starts_on)
+        .bind(payload.ends_on)
+        .bind(next_run_on)
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Изменить график; ближайшая дата пересчитывается от сегодняшнего дня
+    pub async fn update(
+        pool: &PgPool,
+        schedule: &MaintenanceSchedule,
+        payload: UpdateMaintenanceScheduleRequest,
+    ) -> AppResult<MaintenanceSchedule> {
+        let title = match &payload.title {
+            Some(title) => validate_title(title)?,
+            None => schedule.title.clone(),
+        };
+        let frequency = payload.frequency.unwrap_or(schedule.frequency);
+        let interval = payload.interval_count.unwrap_or(schedule.interval_count);
+        let starts_on = payload.starts_on.unwrap_or(schedule.starts_on);
+        let ends_on = payload.ends_on.apply(schedule.ends_on);
+        validate_recurrence(interval, starts_on, ends_on)?;
+
+        let assigned_to = payload.assigned_to.apply(schedule.assigned_to);
+        if let Some(worker_id) = assigned_to.filter(|id| Some(*id) != schedule.assigned_to) {
+            Self::check_worker(pool, schedule.complex_id, worker_id).await?;
+        }
+
+        let next_run_on = next_run(frequency, interval, starts_on, ends_on, Utc::now().date_naive());
+
+        Ok(sqlx::query_as::<_, MaintenanceSchedule>(
+            r#"
+            UPDATE maintenance_schedules SET
+                category = $2,
+                title = $3,
+                description = $4,
+                location = $5,
+                priority = $6,
+                assigned_to = $7,
+                frequency = $8,
+                interval_count = $9,
+                starts_on = $10,
+                ends_on = $11,
+                next_run_on = $12,
+                is_active = $13,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(schedule.id)
+        .bind(payload.category.unwrap_or_else(|| schedule.category.clone()))
+        .bind(title)
+        .bind(payload.description.apply(schedule.description.clone()))
+        .bind(payload.location.apply(schedule.location.clone()))
+        .bind(payload.priority.unwrap_or_else(|| schedule.priority.clone()))
+        .bind(assigned_to)
+        .bind(frequency)
+        .bind(interval)
+        .bind(starts_on)
+        .bind(ends_on)
+        .bind(next_run_on)
+        .bind(payload.is_active.unwrap_or(schedule.is_active))
+        .fetch_one(pool)
+        .await?)
+    }
+
+    /// Удалить график; созданные по нему заявки остаются
+    pub async fn delete(pool: &PgPool, id: Uuid) -> AppResult<()> {
+        sqlx::query("DELETE FROM maintenance_schedules WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Ближайшие плановые работы ЖК на `days` дней вперёд
+    pub async fn planned(
+        pool: &PgPool,
+        complex_id: Uuid,
+        today: NaiveDate,
+        days: i64,
+    ) -> AppResult<Vec<PlannedMaintenanceResponse>> {
+        let until = today + Duration::days(days.clamp(1, MAX_PLANNED_DAYS));
+
+        let schedules = sqlx::query_as::<_, MaintenanceSchedule>(
+            r#"
+            SELECT * FROM maintenance_schedules
+            WHERE complex_id = $1 AND is_active AND next_run_on <= $2
+            "#,
+        )
+        .bind(complex_id)
+        .bind(until)
+        .fetch_all(pool)
+        .await?;
+
+        let mut planned = Vec::new();
+        for schedule in schedules {
+            let mut date = schedule.next_run_on;
+            let mut count = 0;
+            while let Some(planned_for) = date.filter(|date| *date <= until) {
+                if count == MAX_PLANNED_PER_SCHEDULE {
+                    break;
+                }
+                planned.push(PlannedMaintenanceResponse {
+                    schedule_id: schedule.id,
+                    category: schedule.category.clone(),
+                    title: schedule.title.clone(),
+                    description: schedule.description.clone(),
+                    location: schedule.location.clone(),
+                    planned_for,
+                });
+                count += 1;
+                date = next_run(
+                    schedule.frequency,
+                    schedule.interval_count,
+                    schedule.starts_on,
+                    schedule.ends_on,
+                    planned_for + Duration::days(1),
+                );
+            }
+        }
+
+        planned.sort_by(|a, b| a.planned_for.cmp(&b.planned_for).then_with(|| a.title.cmp(&b.title)));
+        Ok(planned)
+    }
+
+    /// Создать заявки по графикам, у которых наступила дата работ, и перенести их на следующую.
+    /// Если планировщик простаивал, пропущенные даты не догоняются: заявка создаётся одна
+    pub async fn run_due(pool: &PgPool, today: NaiveDate) -> AppResult<u64> {
+        let mut tx = pool.begin().await?;
+
+        let due = sqlx::query_as::<_, MaintenanceSchedule>(
+            r#"
+            SELECT * FROM maintenance_schedules
+            WHERE is_active AND next_run_on <= $1
+            ORDER BY next_run_on
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(today)
+        .bind(MAINTENANCE_SCHEDULE_BATCH)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut created = 0;
+        for schedule in due {
+            let request: Option<(Uuid,)> = sqlx::query_as(
+                r#"
+                INSERT INTO maintenance_requests (
+                    complex_id, requester_id, category, title, description, location, priority,
+                    status, assigned_to, assigned_at, schedule_id, planned_for
+                )
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, 'new', $8,
+                    CASE WHEN $8::uuid IS NULL THEN NULL ELSE NOW() END, $9, $10
+                )
+                ON CONFLICT (schedule_id, planned_for) WHERE schedule_id IS NOT NULL DO NOTHING
+                RETURNING id
+                "#,
+            )
+            .bind(schedule.complex_id)
+            .bind(schedule.created_by)
+            .bind(&schedule.category)
+            .bind(&schedule.title)
+            .bind(&schedule.description)
+            .bind(&schedule.location)
+            .bind(&schedule.priority)
+            .bind(schedule.assigned_to)
+            .bind(schedule.id)
+            .bind(schedule.next_run_on)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if request.is_some() {
+                created += 1;
+            }
+
+            let next_run_on = next_run(
+                schedule.frequency,
+                schedule.interval_count,
+                schedule.starts_on,
+                schedule.ends_on,
+                today + Duration::days(1),
+            );
+
+            sqlx::query(
+                r#"
+                UPDATE maintenance_schedules
+                SET next_run_on = $2, last_request_id = COALESCE($3, last_request_id), updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(schedule.id)
+            .bind(next_run_on)
+            .bind(request.map(|(id,)| id))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    /// Исполнитель — действующий сотрудник ОСИ этого ЖК
+    pub async fn check_worker(pool: &PgPool, complex_id: Uuid, worker_id: Uuid) -> AppResult<()> {
+        let worker: Option<(i32,)> = sqlx::query_as(
+            r#"
+            SELECT 1 FROM osi_workers w
+            JOIN osi o ON o.id = w.osi_id
+            WHERE w.id = $1 AND o.complex_id = $2 AND w.is_active
+            "#,
+        )
+        .bind(worker_id)
+        .bind(complex_id)
+        .fetch_optional(pool)
+        .await?;
+
+        if worker.is_none() {
+            return Err(AppError::Validation(
+                "Исполнитель не найден среди сотрудников ОСИ".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Ближайшая дата работ не раньше `from` с учётом даты окончания графика
+fn next_run(
+    frequency: MaintenanceFrequency,
+    interval: i32,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+    from: NaiveDate,
+) -> Option<NaiveDate> {
+    next_occurrence(starts_on, frequency.step(interval as u32), from)
+        .filter(|date| ends_on.is_none_or(|end| *date <= end))
+}
+
+fn validate_title(title: &str) -> AppResult<String> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > 200 {
+        return Err(AppError::Validation(
+            "Название работ — от 1 до 200 символов".to_string(),
+        ));
+    }
+    Ok(title.to_string())
+}
+
+fn validate_recurrence(
+    interval: i32,
+    starts_on: NaiveDate,
+    ends_on: Option<NaiveDate>,
+) -> AppResult<()> {
+    if !(1..=365).contains(&interval) {
+        return Err(AppError::Validation(
+            "Интервал повторения — от 1 до 365".to_string(),
+        ));
+    }
+    if ends_on.is_some_and(|end| end < starts_on) {
+        return Err(AppError::Validation(
+            "Дата окончания раньше даты начала".to_string(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,291 @@
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    ListingReport, ListingReportEntry, ListingReportStatus, ListingReportsQuery, ListingStatus,
+    MarketplaceListing, ModerationAction, NotificationType, Paginated, ReportListingRequest,
+    ResolveListingReportRequest,
+};
+use crate::services::NotificationService;
+
+/// Максимальный срок блокировки на маркетплейсе, дней
+const MAX_BAN_DAYS: i64 = 3650;
+
+/// Жалобы на объявления и меры против продавцов
+pub struct MarketplaceModerationService;
+
+impl MarketplaceModerationService {
+    /// Ошибка, если у пользователя действующий запрет на маркетплейс
+    pub async fn ensure_not_banned(pool: &PgPool, user_id: Uuid) -> AppResult<()> {
+        let (banned,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM marketplace_sanctions
+                WHERE user_id = $1 AND kind = 'ban' AND (expires_at IS NULL OR expires_at > NOW())
+            )
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+        if banned {
+            return Err(AppError::Forbidden);
+        }
+        Ok(())
+    }
+
+    /// Пожаловаться на объявление; повторная жалоба до рассмотрения первой — конфликт
+    pub async fn report(
+        pool: &PgPool,
+        listing: &MarketplaceListing,
+        reporter_id: Uuid,
+        payload: &ReportListingRequest,
+    ) -> AppResult<ListingReport> {
+        if listing.seller_id == reporter_id {
+            return Err(AppError::BadRequest(
+                "Нельзя пожаловаться на своё объявление".to_string(),
+            ));
+        }
+        let comment = payload
+            .comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|comment| !comment.is_empty());
+        if comment.is_some_and(|comment| comment.chars().count() > 1000) {
+            return Err(AppError::Validation(
+                "Комментарий не длиннее 1000 символов".to_string(),
+            ));
+        }
+
+        let report = sqlx::query_as::<_, ListingReport>(
+            r#"
+            INSERT INTO listing_reports (listing_id, complex_id, reporter_id, reason, comment)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (listing_id, reporter_id) WHERE status = 'pending' DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(listing.id)
+        .bind(listing.complex_id)
+        .bind(reporter_id)
+        .bind(payload.reason)
+        .bind(comment)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| {
+            AppError::Conflict("Ваша жалоба на это объявление уже на рассмотрении".to_string())
+        })?;
+
+        Ok(report)
+    }
+
+    /// Очередь модерации: старые жалобы сверху
+    pub async fn queue(
+        pool: &PgPool,
+        query: &ListingReportsQuery,
+    ) -> AppResult<Paginated<ListingReportEntry>> {
+        let status = query.status.unwrap_or(ListingReportStatus::Pending);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+        let page = query.page.unwrap_or(0).max(0);
+
+        let (total,): (i64,) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FROM listing_reports
+            WHERE status = $1 AND ($2::uuid IS NULL OR complex_id = $2)
+            "#,
+        )
+        .bind(status)
+        .bind(query.complex_id)
+        .fetch_one(pool)
+        .await?;
+
+        let items = sqlx::query_as::<_, ListingReportEntry>(
+            r#"
+            SELECT r.id, r.listing_id, r.complex_id, r.reporter_id,
+                   reporter.display_name AS reporter_name,
+                   r.reason, r.comment, r.status, r.resolution, r.moderator_note,
+                   r.reviewed_at, r.created_at,
+                   l.title AS listing_title, l.status AS listing_status, l.seller_id,
+                   seller.display_name AS seller_name,
+                   (SELECT COUNT(*) FROM listing_reports p
+                    WHERE p.listing_id = r.listing_id AND p.status = 'pending') AS pending_reports,
+                   (SELECT COUNT(*) FROM marketplace_sanctions s
+                    WHERE s.user_id = l.seller_id AND s.kind = 'warning') AS seller_warnings
+            FROM listing_reports r
+            JOIN marketplace_listings l ON l.id = r.listing_id
+            JOIN users reporter ON reporter.id = r.reporter_id
+            JOIN users seller ON seller.id = l.seller_id
+            WHERE r.status = $1 AND ($2::uuid IS NULL OR r.complex_id = $2)
+            ORDER BY r.created_at
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(status)
+        .bind(query.complex_id)
+        .bind(limit)
+        .bind(page * limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(Paginated::new(items, page, limit, total))
+    }
+
+    /// Рассмотреть жалобу. Любая мера, кроме отклонения, закрывает все открытые
+    /// жалобы на объявление; продавец получает уведомление
+    pub async fn resolve(
+        pool: &PgPool,
+        report_id: Uuid,
+        moderator_id: Uuid,
+        payload: &ResolveListingReportRequest,
+    ) -> AppResult<(ListingReport, MarketplaceListing)> {
+        let ban_until = match (payload.action, payload.ban_days) {
+            (ModerationAction::BanUser, Some(days)) if !(1..=MAX_BAN_DAYS).contains(&days) => {
+                return Err(AppError::Validation(format!(
+                    "Срок блокировки — от 1 до {} дней",
+                    MAX_BAN_DAYS
+                )));
+            }
+            (ModerationAction::BanUser, days) => days.map(|days| Utc::now() + Duration::days(days)),
+            (_, Some(_)) => {
+                return Err(AppError::Validation(
+                    "Срок указывается только для блокировки".to_string(),
+                ));
+            }
+            (_, None) => None,
+        };
+        let note = payload
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|note| !note.is_empty());
+
+        let mut tx = pool.begin().await?;
+
+        let report = sqlx::query_as::<_, ListingReport>(
+            "SELECT * FROM listing_reports WHERE id = $1 FOR UPDATE",
+        )
+        .bind(report_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Жалоба не найдена".to_string()))?;
+
+        if report.status != ListingReportStatus::Pending {
+            return Err(AppError::BadRequest("Жалоба уже рассмотрена".to_string()));
+        }
+
+        let mut listing = sqlx::query_as::<_, MarketplaceListing>(
+            "SELECT * FROM marketplace_listings WHERE id = $1 FOR UPDATE",
+        )
+        .bind(report.listing_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (status, scope_sql) = match payload.action {
+            ModerationAction::Dismiss => (ListingReportStatus::Dismissed, "id = $1"),
+            _ => (
+                ListingReportStatus::Resolved,
+                "listing_id = (SELECT listing_id FROM listing_reports WHERE id = $1) AND status = 'pending'",
+            ),
+        };
+        sqlx::query(&format!(
+            r#"
+            UPDATE listing_reports
+            SET status = $2, resolution = $3, moderator_note = $4, reviewed_by = $5, reviewed_at = NOW()
+            WHERE {}
+            "#,
+            scope_sql
+        ))
+        .bind(report.id)
+        .bind(status)
+        .bind(payload.action.as_str())
+        .bind(note)
+        .bind(moderator_id)
+        .execute(&mut *tx)
+        .await?;
+
+        match payload.action {
+            ModerationAction::Dismiss => {}
+            ModerationAction::HideListing => {
+                listing = sqlx::query_as::<_, MarketplaceListing>(
+                    "UPDATE marketplace_listings SET status = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+                )
+                .bind(listing.id)
+                .bind(ListingStatus::Hidden)
+                .fetch_one(&mut *tx)
+                .await?;
+            }
+            ModerationAction::WarnUser | ModerationAction::BanUser => {
+                let kind = if payload.action == ModerationAction::BanUser { "ban" } else { "warning" };
+                sqlx::query(
+                    r#"
+                    INSERT INTO marketplace_sanctions (user_id, kind, report_id, reason, issued_by, expires_at)
+                    VALUES ($1, $2::marketplace_sanction_kind, $3, $4, $5, $6)
+                    "#,
+                )
+                .bind(listing.seller_id)
+                .bind(kind)
+                .bind(report.id)
+                .bind(note)
+                .bind(moderator_id)
+                .bind(ban_until)
+                .execute(&mut *tx)
+                .await?;
+
+                // Заблокированный продавец пропадает из ленты целиком
+                if payload.action == ModerationAction::BanUser {
+                    sqlx::query(
+                        r#"
+                        UPDATE marketplace_listings SET status = 'hidden', updated_at = NOW()
+                        WHERE seller_id = $1 AND status IN ('draft', 'active', 'reserved')
+                        "#,
+                    )
+                    .bind(listing.seller_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    listing = sqlx::query_as::<_, MarketplaceListing>(
+                        "SELECT * FROM marketplace_listings WHERE id = $1",
+                    )
+                    .bind(listing.id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+
+        let report = sqlx::query_as::<_, ListingReport>("SELECT * FROM listing_reports WHERE id = $1")
+            .bind(report.id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let title = match payload.action {
+            ModerationAction::Dismiss => None,
+            ModerationAction::HideListing => Some("Объявление скрыто модератором"),
+            ModerationAction::WarnUser => Some("Предупреждение от модератора маркетплейса"),
+            ModerationAction::BanUser => Some("Размещение объявлений заблокировано"),
+        };
+        if let Some(title) = title {
+            let body = match (note, ban_until) {
+                (Some(note), _) => format!("«{}»: {}", listing.title, note),
+                (None, Some(until)) => format!("«{}». До {}", listing.title, until.format("%d.%m.%Y")),
+                (None, None) => format!("«{}»", listing.title),
+            };
+            NotificationService::notify_users(
+                pool,
+                &[listing.seller_id],
+                NotificationType::Marketplace,
+                title,
+                Some(&body),
+                Some(json!({"listing_id": listing.id, "action": payload.action.as_str()})),
+            )
+            .await?;
+        }
+
+        Ok((report, listing))
+    }
+}
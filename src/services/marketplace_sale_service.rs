@@ -0,0 +1,248 @@
+use serde_json::json;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::middleware::get_user_complexes;
+use crate::models::{ConfirmReceiptRequest, ListingStatus, MarketplaceListing, NotificationType};
+use crate::services::NotificationService;
+
+/// Бронь, продажа и подтверждение получения товара
+pub struct MarketplaceSaleService;
+
+impl MarketplaceSaleService {
+    /// Придержать товар для соседа; повторная бронь переносит её на другого покупателя
+    pub async fn reserve(
+        pool: &PgPool,
+        listing_id: Uuid,
+        seller_id: Uuid,
+        buyer_id: Uuid,
+    ) -> AppResult<MarketplaceListing> {
+        let mut tx = pool.begin().await?;
+        let listing = Self::seller_listing(&mut tx, listing_id, seller_id).await?;
+        if !matches!(listing.status, ListingStatus::Active | ListingStatus::Reserved) {
+            return Err(AppError::Conflict(
+                "Забронировать можно только активное объявление".to_string(),
+            ));
+        }
+        Self::check_buyer(pool, &listing, buyer_id).await?;
+
+        let updated = sqlx::query_as::<_, MarketplaceListing>(
+            r#"
+            UPDATE marketplace_listings
+            SET status = 'reserved', reserved_for = $2, reserved_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(listing.id)
+        .bind(buyer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Self::notify(pool, buyer_id, "Товар забронирован для вас", &updated).await?;
+        if let Some(previous) = listing.reserved_for.filter(|id| *id != buyer_id) {
+            Self::notify(pool, previous, "Бронь товара снята", &updated).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Снять бронь и вернуть объявление в ленту
+    pub async fn cancel_reservation(
+        pool: &PgPool,
+        listing_id: Uuid,
+        seller_id: Uuid,
+    ) -> AppResult<MarketplaceListing> {
+        let mut tx = pool.begin().await?;
+        let listing = Self::seller_listing(&mut tx, listing_id, seller_id).await?;
+        if listing.status != ListingStatus::Reserved {
+            return Err(AppError::Conflict("Объявление не забронировано".to_string()));
+        }
+
+        let updated = sqlx::query_as::<_, MarketplaceListing>(
+            r#"
+            UPDATE marketplace_listings
+            SET status = 'active', reserved_for = NULL, reserved_at = NULL, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(listing.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        if let Some(previous) = listing.reserved_for {
+            Self::notify(pool, previous, "Бронь товара снята", &updated).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Отметить продажу. Без покупателя в запросе товар уходит тому, для кого забронирован
+    pub async fn sell(
+        pool: &PgPool,
+        listing_id: Uuid,
+        seller_id: Uuid,
+        buyer_id: Option<Uuid>,
+    ) -> AppResult<MarketplaceListing> {
+        let mut tx = pool.begin().await?;
+        let listing = Self::seller_listing(&mut tx, listing_id, seller_id).await?;
+        if !matches!(listing.status, ListingStatus::Active | ListingStatus::Reserved) {
+            return Err(AppError::Conflict(
+                "Продать можно только активное или забронированное объявление".to_string(),
+            ));
+        }
+        let buyer_id = buyer_id
+            .or(listing.reserved_for)
+            .ok_or_else(|| AppError::Validation("Укажите покупателя".to_string()))?;
+        Self::check_buyer(pool, &listing, buyer_id).await?;
+
+        let updated = sqlx::query_as::<_, MarketplaceListing>(
+            r#"
+            UPDATE marketplace_listings
+            SET status = 'sold', buyer_id = $2, sold_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(listing.id)
+        .bind(buyer_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Self::notify(
+            pool,
+            buyer_id,
+            "Продавец отметил покупку — подтвердите получение",
+            &updated,
+        )
+        .await?;
+        if let Some(previous) = listing.reserved_for.filter(|id| *id != buyer_id) {
+            Self::notify(pool, previous, "Бронь товара снята", &updated).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Покупатель подтверждает получение и может оценить продавца
+    pub async fn confirm_receipt(
+        pool: &PgPool,
+        listing_id: Uuid,
+        buyer_id: Uuid,
+        payload: &ConfirmReceiptRequest,
+    ) -> AppResult<MarketplaceListing> {
+        if payload.rating.is_some_and(|rating| !(1..=5).contains(&rating)) {
+            return Err(AppError::Validation("Оценка — от 1 до 5".to_string()));
+        }
+        let review = payload
+            .review
+            .as_deref()
+            .map(str::trim)
+            .filter(|review| !review.is_empty());
+        if review.is_some_and(|review| review.chars().count() > 1000) {
+            return Err(AppError::Validation(
+                "Отзыв не длиннее 1000 символов".to_string(),
+            ));
+        }
+        if review.is_some() && payload.rating.is_none() {
+            return Err(AppError::Validation("К отзыву нужна оценка".to_string()));
+        }
+
+        let mut tx = pool.begin().await?;
+        let listing = sqlx::query_as::<_, MarketplaceListing>(
+            "SELECT * FROM marketplace_listings WHERE id = $1 AND buyer_id = $2 FOR UPDATE",
+        )
+        .bind(listing_id)
+        .bind(buyer_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Покупка не найдена".to_string()))?;
+
+        if listing.received_at.is_some() {
+            return Err(AppError::Conflict("Получение уже подтверждено".to_string()));
+        }
+
+        let updated = sqlx::query_as::<_, MarketplaceListing>(
+            r#"
+            UPDATE marketplace_listings
+            SET received_at = NOW(), buyer_rating = $2, buyer_review = $3, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(listing.id)
+        .bind(payload.rating)
+        .bind(review)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Self::notify(
+            pool,
+            updated.seller_id,
+            "Покупатель подтвердил получение",
+            &updated,
+        )
+        .await?;
+        Ok(updated)
+    }
+
+    async fn seller_listing(
+        tx: &mut Transaction<'_, Postgres>,
+        listing_id: Uuid,
+        seller_id: Uuid,
+    ) -> AppResult<MarketplaceListing> {
+        let listing = sqlx::query_as::<_, MarketplaceListing>(
+            "SELECT * FROM marketplace_listings WHERE id = $1 FOR UPDATE",
+        )
+        .bind(listing_id)
+        .fetch_optional(&mut **tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Объявление не найдено".to_string()))?;
+
+        if listing.seller_id != seller_id {
+            return Err(AppError::Forbidden);
+        }
+        Ok(listing)
+    }
+
+    /// Покупатель — другой житель того же ЖК
+    async fn check_buyer(
+        pool: &PgPool,
+        listing: &MarketplaceListing,
+        buyer_id: Uuid,
+    ) -> AppResult<()> {
+        if buyer_id == listing.seller_id {
+            return Err(AppError::BadRequest(
+                "Нельзя продать товар самому себе".to_string(),
+            ));
+        }
+        if !get_user_complexes(pool, buyer_id)
+            .await?
+            .contains(&listing.complex_id)
+        {
+            return Err(AppError::Validation(
+                "Покупатель не живёт в этом ЖК".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn notify(
+        pool: &PgPool,
+        user_id: Uuid,
+        title: &str,
+        listing: &MarketplaceListing,
+    ) -> AppResult<()> {
+        NotificationService::notify_users(
+            pool,
+            &[user_id],
+            NotificationType::Marketplace,
+            title,
+            Some(&format!("«{}»", listing.title)),
+            Some(json!({"listing_id": listing.id})),
+        )
+        .await
+    }
+}
@@ -0,0 +1,106 @@
+use crate::build_info::MIGRATOR;
+use crate::error::AppResult;
+use crate::models::{MigrationReport, MigrationState, MigrationStatus};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+
+/// Запись из служебной таблицы sqlx
+#[derive(sqlx::FromRow)]
+struct AppliedMigration {
+    version: i64,
+    description: String,
+    installed_on: DateTime<Utc>,
+    success: bool,
+    checksum: Vec<u8>,
+}
+
+pub struct MigrationService;
+
+impl MigrationService {
+    /// Сверить миграции в базе со встроенными в бинарник
+    pub async fn status(pool: &PgPool) -> AppResult<MigrationReport> {
+        let applied = Self::applied(pool).await?;
+        let mut applied: BTreeMap<i64, AppliedMigration> =
+            applied.into_iter().map(|m| (m.version, m)).collect();
+
+        let mut migrations = Vec::new();
+        for embedded in MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()) {
+            let status = match applied.remove(&embedded.version) {
+                None => MigrationStatus {
+                    version: embedded.version,
+                    description: embedded.description.to_string(),
+                    state: MigrationState::Pending,
+                    installed_on: None,
+                },
+                Some(row) => MigrationStatus {
+                    version: row.version,
+                    description: row.description,
+                    state: if !row.success {
+                        MigrationState::Failed
+                    } else if row.checksum != *embedded.checksum {
+                        MigrationState::ChecksumMismatch
+                    } else {
+                        MigrationState::Applied
+                    },
+                    installed_on: Some(row.installed_on),
+                },
+            };
+            migrations.push(status);
+        }
+
+        // Всё, что осталось, применено более новой сборкой или удалено из репозитория
+        migrations.extend(applied.into_values().map(|row| MigrationStatus {
+            version: row.version,
+            description: row.description,
+            state: if row.success {
+                MigrationState::Unknown
+            } else {
+                MigrationState::Failed
+            },
+            installed_on: Some(row.installed_on),
+        }));
+        migrations.sort_by_key(|m| m.version);
+
+        let pending = migrations
+            .iter()
+            .filter(|m| m.state == MigrationState::Pending)
+            .count();
+
+        Ok(MigrationReport {
+            embedded_version: crate::build_info::latest_migration(),
+            applied_version: migrations
+                .iter()
+                .filter(|m| matches!(m.state, MigrationState::Applied | MigrationState::ChecksumMismatch))
+                .map(|m| m.version)
+                .max(),
+            pending,
+            drift: migrations.iter().any(|m| m.state != MigrationState::Applied),
+            migrations,
+        })
+    }
+
+    /// Применённые миграции; на пустой базе служебной таблицы ещё нет
+    async fn applied(pool: &PgPool) -> AppResult<Vec<AppliedMigration>> {
+        let (exists,): (bool,) =
+            sqlx::query_as("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .fetch_one(pool)
+                .await?;
+
+        if !exists {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query_as::<_, AppliedMigration>(
+            r#"
+            SELECT version, description, installed_on, success, checksum
+            FROM _sqlx_migrations
+            ORDER BY version
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows)
+    }
+}
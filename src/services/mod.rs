@@ -1,9 +1,102 @@
+pub mod address_registry_service;
+pub mod admin_guard_service;
+pub mod announcement_service;
+pub mod anomaly_service;
 pub mod auth_service;
+pub mod audit_service;
+pub mod banner_service;
+pub mod barrier_driver;
 pub mod barrier_service;
+pub mod billing_service;
+pub mod budget_service;
+pub mod chat_service;
+pub mod communication_export_service;
+pub mod complex_restructure_service;
+pub mod complex_verification_service;
+pub mod consent_service;
+pub mod document_service;
+pub mod election_service;
+pub mod email_service;
+pub mod event_service;
+pub mod field_history_service;
+pub mod financial_audit_service;
 pub mod file_service;
+pub mod intercom_service;
+pub mod job_service;
+pub mod maintenance_cost_service;
+pub mod maintenance_schedule_service;
+pub mod marketplace_moderation_service;
+pub mod marketplace_sale_service;
+pub mod migration_service;
+pub mod move_out_service;
+pub mod notification_service;
+pub mod notification_stream_service;
+pub mod ocr_service;
+pub mod payment_service;
+pub mod pending_expiry_service;
+pub mod permission_service;
+pub mod query_metrics;
+pub mod resilience;
+pub mod sandbox_service;
+pub mod scheduler_service;
+pub mod settings_service;
+pub mod shared_charge_service;
+pub mod sms_provider;
 pub mod sms_service;
+pub mod storage;
+pub mod stream_service;
+pub mod survey_service;
+pub mod telegram_service;
+pub mod view_service;
+pub mod voting_service;
+pub mod warehouse_export_service;
+pub mod whatsapp_service;
 
+pub use address_registry_service::AddressRegistryService;
+pub use admin_guard_service::AdminGuardService;
+pub use announcement_service::AnnouncementService;
+pub use anomaly_service::AnomalyService;
 pub use auth_service::AuthService;
+pub use audit_service::AuditService;
+pub use banner_service::BannerService;
 pub use barrier_service::BarrierService;
+pub use billing_service::BillingService;
+pub use budget_service::BudgetService;
+pub use chat_service::ChatService;
+pub use communication_export_service::CommunicationExportService;
+pub use complex_restructure_service::ComplexRestructureService;
+pub use complex_verification_service::ComplexVerificationService;
+pub use consent_service::ConsentService;
+pub use document_service::DocumentService;
+pub use election_service::ElectionService;
+pub use email_service::EmailService;
+pub use event_service::EventService;
+pub use field_history_service::FieldHistoryService;
 pub use file_service::FileService;
+pub use financial_audit_service::{FinancialAuditFilter, FinancialAuditService};
+pub use intercom_service::IntercomService;
+pub use job_service::JobService;
+pub use maintenance_cost_service::MaintenanceCostService;
+pub use maintenance_schedule_service::MaintenanceScheduleService;
+pub use marketplace_moderation_service::MarketplaceModerationService;
+pub use marketplace_sale_service::MarketplaceSaleService;
+pub use migration_service::MigrationService;
+pub use move_out_service::MoveOutService;
+pub use notification_service::NotificationService;
+pub use notification_stream_service::NotificationStreamService;
+pub use ocr_service::OcrService;
+pub use payment_service::PaymentService;
+pub use pending_expiry_service::PendingExpiryService;
+pub use permission_service::PermissionService;
+pub use sandbox_service::SandboxService;
+pub use scheduler_service::SchedulerService;
+pub use settings_service::SettingsService;
+pub use shared_charge_service::SharedChargeService;
 pub use sms_service::SmsService;
+pub use stream_service::StreamService;
+pub use survey_service::SurveyService;
+pub use telegram_service::TelegramService;
+pub use view_service::ViewService;
+pub use voting_service::VotingService;
+pub use warehouse_export_service::WarehouseExportService;
+pub use whatsapp_service::WhatsAppService;
@@ -0,0 +1,282 @@
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    Apartment, CreateMoveOutRequest, MoveOut, MoveOutChecklist, MoveOutMeter, NotificationType,
+};
+use crate::services::{NotificationService, SchedulerService};
+
+/// Как часто проверять выезды, у которых наступил день выезда
+const MOVE_OUT_INTERVAL_SECS: u64 = 3600;
+
+/// За сколько дней до выезда показание считается итоговым
+const MOVE_OUT_READING_WINDOW_DAYS: i64 = 3;
+
+/// Выезд жильцов: уведомление ОСИ, итоговые показания и снятие доступов
+pub struct MoveOutService;
+
+impl MoveOutService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "move_outs",
+            std::time::Duration::from_secs(MOVE_OUT_INTERVAL_SECS),
+            |pool, _config| async move {
+                let completed = MoveOutService::complete_due(&pool, Utc::now().date_naive()).await?;
+                if completed > 0 {
+                    tracing::info!("Completed {} move-outs", completed);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Назначить выезд жильца квартиры и сообщить председателю
+    pub async fn schedule(
+        pool: &PgPool,
+        apartment: &Apartment,
+        initiated_by: Uuid,
+        payload: &CreateMoveOutRequest,
+    ) -> AppResult<MoveOut> {
+        let resident_id = apartment
+            .resident_id
+            .ok_or_else(|| AppError::BadRequest("В квартире не указан жилец".to_string()))?;
+
+        if payload.effective_date < Utc::now().date_naive() {
+            return Err(AppError::Validation("Дата выезда уже прошла".to_string()));
+        }
+
+        let scheduled: Option<(Uuid,)> = sqlx::query_as(
+            "SELECT id FROM move_outs WHERE apartment_id = $1 AND status = 'scheduled'",
+        )
+        .bind(apartment.id)
+        .fetch_optional(pool)
+        .await?;
+
+        if scheduled.is_some() {
+            return Err(AppError::Conflict("Выезд уже назначен".to_string()));
+        }
+
+        let move_out = sqlx::query_as::<_, MoveOut>(
+            r#"
+            INSERT INTO move_outs (apartment_id, complex_id, user_id, initiated_by, effective_date, note)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING *
+            "#,
+        )
+        .bind(apartment.id)
+        .bind(apartment.complex_id)
+        .bind(resident_id)
+        .bind(initiated_by)
+        .bind(payload.effective_date)
+        .bind(&payload.note)
+        .fetch_one(pool)
+        .await?;
+
+        Self::notify(
+            pool,
+            &move_out,
+            "Выезд жильца",
+            &format!(
+                "Кв. {}: жилец выезжает {}. Доступы будут сняты в этот день, нужны итоговые показания счётчиков",
+                apartment.number,
+                move_out.effective_date.format("%d.%m.%Y")
+            ),
+        )
+        .await?;
+
+        Ok(move_out)
+    }
+
+    /// Назначенный или последний выезд квартиры
+    pub async fn latest(pool: &PgPool, apartment_id: Uuid) -> AppResult<Option<MoveOut>> {
+        let move_out = sqlx::query_as::<_, MoveOut>(
+            r#"
+            SELECT * FROM move_outs WHERE apartment_id = $1
+            ORDER BY status = 'scheduled' DESC, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(apartment_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(move_out)
+    }
+
+    /// Счётчики квартиры и итоговые показания, сданные не раньше чем за несколько дней до выезда
+    pub async fn checklist(pool: &PgPool, move_out: MoveOut) -> AppResult<MoveOutChecklist> {
+        let meters = sqlx::query_as::<_, MoveOutMeter>(
+            r#"
+            SELECT m.id AS meter_id, m.utility_type, m.serial_number,
+                   r.id AS reading_id, r.value, r.reading_date
+            FROM meters m
+            LEFT JOIN LATERAL (
+                SELECT id, value, reading_date FROM meter_readings
+                WHERE meter_id = m.id AND reading_date >= $2
+                ORDER BY reading_date DESC, created_at DESC
+                LIMIT 1
+            ) r ON true
+            WHERE m.apartment_id = $1 AND m.is_active
+            ORDER BY m.utility_type, m.serial_number
+            "#,
+        )
+        .bind(move_out.apartment_id)
+        .bind(reading_window_start(move_out.effective_date))
+        .fetch_all(pool)
+        .await?;
+
+        let readings_complete = meters.iter().all(|meter| meter.reading_id.is_some());
+
+        Ok(MoveOutChecklist {
+            move_out,
+            meters,
+            readings_complete,
+        })
+    }
+
+    /// Отменить назначенный выезд
+    pub async fn cancel(pool: &PgPool, move_out_id: Uuid, cancelled_by: Uuid) -> AppResult<MoveOut> {
+        sqlx::query_as::<_, MoveOut>(
+            r#"
+            UPDATE move_outs SET
+                status = 'cancelled',
+                cancelled_by = $2,
+                cancelled_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'scheduled'
+            RETURNING *
+            "#,
+        )
+        .bind(move_out_id)
+        .bind(cancelled_by)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Назначенный выезд не найден".to_string()))
+    }
+
+    /// Завершить выезды, день которых наступил: снять жильца с квартиры,
+    /// отозвать пропуска и номера авто, удалить заведённых им членов семьи
+    pub async fn complete_due(pool: &PgPool, today: NaiveDate) -> AppResult<u64> {
+        let due = sqlx::query_as::<_, MoveOut>(
+            "SELECT * FROM move_outs WHERE status = 'scheduled' AND effective_date <= $1",
+        )
+        .bind(today)
+        .fetch_all(pool)
+        .await?;
+
+        let mut completed = 0;
+        for move_out in due {
+            let checklist = Self::checklist(pool, move_out).await?;
+            let move_out = &checklist.move_out;
+
+            let mut tx = pool.begin().await?;
+
+            let updated = sqlx::query_as::<_, MoveOut>(
+                r#"
+                UPDATE move_outs SET
+                    status = 'completed',
+                    readings_complete = $2,
+                    completed_at = NOW(),
+                    updated_at = NOW()
+                WHERE id = $1 AND status = 'scheduled'
+                RETURNING *
+                "#,
+            )
+            .bind(move_out.id)
+            .bind(checklist.readings_complete)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(updated) = updated else {
+                continue;
+            };
+
+            sqlx::query(
+                "UPDATE apartments SET resident_id = NULL, updated_at = NOW() WHERE id = $1 AND resident_id = $2",
+            )
+            .bind(move_out.apartment_id)
+            .bind(move_out.user_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE guest_access SET status = 'cancelled'
+                WHERE complex_id = $1 AND created_by = $2
+                  AND status IN ('awaiting_approval', 'pending', 'active')
+                "#,
+            )
+            .bind(move_out.complex_id)
+            .bind(move_out.user_id)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("DELETE FROM resident_vehicles WHERE complex_id = $1 AND user_id = $2")
+                .bind(move_out.complex_id)
+                .bind(move_out.user_id)
+                .execute(&mut *tx)
+                .await?;
+
+            sqlx::query("DELETE FROM family_members WHERE apartment_id = $1 AND created_by = $2")
+                .bind(move_out.apartment_id)
+                .bind(move_out.user_id)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            completed += 1;
+
+            let (number,): (String,) = sqlx::query_as("SELECT number FROM apartments WHERE id = $1")
+                .bind(updated.apartment_id)
+                .fetch_one(pool)
+                .await?;
+            let body = if checklist.readings_complete {
+                format!("Кв. {}: жилец выехал, доступы сняты, итоговые показания сданы", number)
+            } else {
+                format!(
+                    "Кв. {}: жилец выехал, доступы сняты. Итоговые показания сданы не по всем счётчикам",
+                    number
+                )
+            };
+            Self::notify(pool, &updated, "Жилец выехал", &body).await?;
+        }
+
+        Ok(completed)
+    }
+
+    /// Председателю ОСИ и владельцу квартиры
+    async fn notify(pool: &PgPool, move_out: &MoveOut, title: &str, body: &str) -> AppResult<()> {
+        let recipients: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT chairman_id FROM osi WHERE complex_id = $1 AND chairman_id IS NOT NULL
+            UNION SELECT owner_id FROM apartments WHERE id = $2 AND owner_id IS NOT NULL
+            "#,
+        )
+        .bind(move_out.complex_id)
+        .bind(move_out.apartment_id)
+        .fetch_all(pool)
+        .await?;
+        let user_ids: Vec<Uuid> = recipients.into_iter().map(|(id,)| id).collect();
+
+        NotificationService::notify_users(
+            pool,
+            &user_ids,
+            NotificationType::System,
+            title,
+            Some(body),
+            Some(json!({
+                "move_out_id": move_out.id,
+                "apartment_id": move_out.apartment_id,
+                "effective_date": move_out.effective_date,
+            })),
+        )
+        .await
+    }
+}
+
+fn reading_window_start(effective_date: NaiveDate) -> NaiveDate {
+    effective_date - Duration::days(MOVE_OUT_READING_WINDOW_DAYS)
+}
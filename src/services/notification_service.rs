@@ -0,0 +1,154 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    NotificationChannel, NotificationSetting, NotificationSettingUpdate, NotificationType,
+};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+pub struct NotificationService;
+
+impl NotificationService {
+    /// Создать уведомление для списка пользователей
+    pub async fn notify_users(
+        pool: &PgPool,
+        user_ids: &[Uuid],
+        notification_type: NotificationType,
+        title: &str,
+        body: Option<&str>,
+        data: Option<serde_json::Value>,
+    ) -> AppResult<()> {
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO notifications (user_id, notification_type, title, body, data)
+            SELECT DISTINCT u, $2, $3, $4, $5 FROM UNNEST($1::uuid[]) AS u
+            WHERE NOT EXISTS (
+                SELECT 1 FROM notification_settings s
+                WHERE s.user_id = u AND s.notification_type = $2 AND NOT s.in_app
+            )
+            "#,
+        )
+        .bind(user_ids)
+        .bind(notification_type)
+        .bind(title)
+        .bind(body)
+        .bind(data)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Получатели, у которых этот тип уведомлений включён в канале
+    pub async fn recipients<'e>(
+        db: impl PgExecutor<'e>,
+        user_ids: &[Uuid],
+        notification_type: NotificationType,
+        channel: NotificationChannel,
+    ) -> AppResult<Vec<Uuid>> {
+        if channel == NotificationChannel::InApp && notification_type.is_mandatory() {
+            return Ok(user_ids.to_vec());
+        }
+
+        let allowed: Vec<(Uuid,)> = sqlx::query_as(&format!(
+            r#"
+            SELECT DISTINCT u FROM UNNEST($1::uuid[]) AS u
+            WHERE COALESCE((
+                SELECT s.{} FROM notification_settings s
+                WHERE s.user_id = u AND s.notification_type = $2
+            ), $3)
+            "#,
+            channel.column()
+        ))
+        .bind(user_ids)
+        .bind(notification_type)
+        .bind(channel.enabled_by_default())
+        .fetch_all(db)
+        .await?;
+
+        Ok(allowed.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Настройки пользователя по всем типам уведомлений
+    pub async fn settings(pool: &PgPool, user_id: Uuid) -> AppResult<Vec<NotificationSetting>> {
+        let saved = sqlx::query_as::<_, NotificationSetting>(
+            "SELECT notification_type, push, sms, in_app, email, telegram FROM notification_settings WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(NotificationType::ALL
+            .into_iter()
+            .map(|notification_type| {
+                saved
+                    .iter()
+                    .find(|s| s.notification_type == notification_type)
+                    .cloned()
+                    .unwrap_or(NotificationSetting {
+                        notification_type,
+                        push: true,
+                        sms: true,
+                        in_app: true,
+                        email: false,
+                        telegram: true,
+                    })
+            })
+            .collect())
+    }
+
+    pub async fn update_settings(
+        pool: &PgPool,
+        user_id: Uuid,
+        updates: &[NotificationSettingUpdate],
+    ) -> AppResult<Vec<NotificationSetting>> {
+        if let Some(update) = updates
+            .iter()
+            .find(|u| u.notification_type.is_mandatory() && u.in_app == Some(false))
+        {
+            return Err(AppError::Validation(format!(
+                "Уведомления типа {:?} нельзя отключить в приложении",
+                update.notification_type
+            )));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        for update in updates {
+            sqlx::query(
+                r#"
+                INSERT INTO notification_settings (
+                    user_id, notification_type, push, sms, in_app, email, telegram
+                )
+                VALUES (
+                    $1, $2, COALESCE($3, true), COALESCE($4, true), COALESCE($5, true),
+                    COALESCE($6, false), COALESCE($7, true)
+                )
+                ON CONFLICT (user_id, notification_type) DO UPDATE SET
+                    push = COALESCE($3, notification_settings.push),
+                    sms = COALESCE($4, notification_settings.sms),
+                    in_app = COALESCE($5, notification_settings.in_app),
+                    email = COALESCE($6, notification_settings.email),
+                    telegram = COALESCE($7, notification_settings.telegram),
+                    updated_at = NOW()
+                "#,
+            )
+            .bind(user_id)
+            .bind(&update.notification_type)
+            .bind(update.push)
+            .bind(update.sms)
+            .bind(update.in_app)
+            .bind(update.email)
+            .bind(update.telegram)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Self::settings(pool, user_id).await
+    }
+}
@@ -0,0 +1,75 @@
+use once_cell::sync::OnceCell;
+use serde::Deserialize;
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+
+/// Канал Postgres, в который триггеры публикуют изменения уведомлений
+const NOTIFICATION_CHANNEL: &str = "notification_events";
+
+/// Сколько событий держать для медленных подписчиков; отставшие получают только счётчик
+const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// Пауза перед переподключением слушателя после обрыва соединения
+const RECONNECT_DELAY_SECS: u64 = 5;
+
+static EVENT_SENDER: OnceCell<broadcast::Sender<NotificationEvent>> = OnceCell::new();
+
+/// Изменение уведомлений пользователя: новое уведомление или прочтение
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct NotificationEvent {
+    pub user_id: Uuid,
+    pub notification_id: Option<Uuid>,
+}
+
+/// Рассылка событий уведомлений открытым потокам клиентов
+pub struct NotificationStreamService;
+
+impl NotificationStreamService {
+    /// Запустить слушателя канала уведомлений
+    pub fn start(pool: PgPool) {
+        let (tx, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        if EVENT_SENDER.set(tx.clone()).is_err() {
+            return;
+        }
+
+        tokio::spawn(run_listener(pool, tx));
+    }
+
+    /// Подписаться на события всех пользователей; фильтрует подписчик
+    pub fn subscribe() -> AppResult<broadcast::Receiver<NotificationEvent>> {
+        EVENT_SENDER
+            .get()
+            .map(broadcast::Sender::subscribe)
+            .ok_or_else(|| AppError::ServiceUnavailable("Поток уведомлений не запущен".to_string()))
+    }
+}
+
+async fn run_listener(pool: PgPool, tx: broadcast::Sender<NotificationEvent>) {
+    loop {
+        if let Err(e) = listen(&pool, &tx).await {
+            tracing::warn!("Notification listener failed, reconnecting: {}", e);
+        }
+        tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+    }
+}
+
+async fn listen(pool: &PgPool, tx: &broadcast::Sender<NotificationEvent>) -> AppResult<()> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(NOTIFICATION_CHANNEL).await?;
+
+    loop {
+        let notification = listener.recv().await?;
+        match serde_json::from_str::<NotificationEvent>(notification.payload()) {
+            // Ошибка отправки значит лишь, что сейчас никто не подписан
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => tracing::warn!("Malformed notification event: {}", e),
+        }
+    }
+}
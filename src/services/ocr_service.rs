@@ -0,0 +1,170 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use chrono::NaiveDate;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+
+pub type OcrFuture<'a> = Pin<Box<dyn Future<Output = AppResult<String>> + Send + 'a>>;
+
+/// Провайдер распознавания текста на изображениях
+pub trait OcrProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Распознать текст на изображении
+    fn recognize<'a>(&'a self, image: &'a [u8], content_type: &'a str) -> OcrFuture<'a>;
+}
+
+/// Распознавание отключено — возвращаем пустой текст, поля заполняются вручную
+pub struct DisabledOcrProvider;
+
+impl OcrProvider for DisabledOcrProvider {
+    fn name(&self) -> &'static str {
+        "disabled"
+    }
+
+    fn recognize<'a>(&'a self, _image: &'a [u8], _content_type: &'a str) -> OcrFuture<'a> {
+        Box::pin(async { Ok(String::new()) })
+    }
+}
+
+/// HTTP-сервис распознавания: принимает изображение, возвращает `{"text": "..."}`
+pub struct HttpOcrProvider {
+    client: reqwest::Client,
+    url: String,
+    api_key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpOcrResponse {
+    text: String,
+}
+
+impl HttpOcrProvider {
+    pub fn new(url: String, api_key: String) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            api_key,
+        }
+    }
+}
+
+impl OcrProvider for HttpOcrProvider {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn recognize<'a>(&'a self, image: &'a [u8], content_type: &'a str) -> OcrFuture<'a> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .post(&self.url)
+                .bearer_auth(&self.api_key)
+                .header(reqwest::header::CONTENT_TYPE, content_type)
+                .body(image.to_vec())
+                .send()
+                .await
+                .map_err(|e| AppError::Ocr(format!("OCR недоступен: {}", e)))?;
+
+            if !response.status().is_success() {
+                return Err(AppError::Ocr(format!(
+                    "OCR вернул ошибку: {}",
+                    response.status()
+                )));
+            }
+
+            let result: HttpOcrResponse = response
+                .json()
+                .await
+                .map_err(|e| AppError::Ocr(format!("Некорректный ответ OCR: {}", e)))?;
+
+            Ok(result.text)
+        })
+    }
+}
+
+pub struct OcrService;
+
+impl OcrService {
+    /// Провайдер по настройкам окружения
+    pub fn provider(config: &Config) -> Arc<dyn OcrProvider> {
+        match &config.ocr_api_url {
+            Some(url) => Arc::new(HttpOcrProvider::new(url.clone(), config.ocr_api_key.clone())),
+            None => Arc::new(DisabledOcrProvider),
+        }
+    }
+}
+
+/// Поля, извлечённые из счёта поставщика
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InvoiceFields {
+    pub amount: Option<Decimal>,
+    pub date: Option<NaiveDate>,
+    pub vendor: Option<String>,
+}
+
+static AMOUNT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(\d{1,3}(?:[ \u{00A0}]\d{3})+|\d+)(?:[.,](\d{1,2}))?").unwrap());
+
+static DATE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d{2})[./-](\d{2})[./-](\d{4})\b").unwrap());
+
+static VENDOR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)\b(ТОО|ИП|АО|LLP|TOO)\s*[«"']?([^«»"'\n]{2,100})"#).unwrap());
+
+const TOTAL_MARKERS: &[&str] = &["итого", "к оплате", "всего", "сумма", "total"];
+
+/// Разобрать распознанный текст счёта
+pub fn parse_invoice(text: &str) -> InvoiceFields {
+    InvoiceFields {
+        amount: parse_amount(text),
+        date: DATE_RE.captures_iter(text).find_map(|c| {
+            NaiveDate::from_ymd_opt(c[3].parse().ok()?, c[2].parse().ok()?, c[1].parse().ok()?)
+        }),
+        vendor: VENDOR_RE.captures(text).map(|c| {
+            format!("{} {}", c[1].to_uppercase(), c[2].trim().trim_end_matches(['»', '"', '\'']))
+        }),
+    }
+}
+
+/// Сумма берётся из строки с «Итого»/«К оплате», иначе — наибольшее число с копейками
+fn parse_amount(text: &str) -> Option<Decimal> {
+    let amounts_in = |line: &str| -> Vec<Decimal> {
+        let line = DATE_RE.replace_all(line, "");
+        AMOUNT_RE
+            .captures_iter(&line)
+            .filter_map(|c| {
+                let whole: String = c[1].chars().filter(|ch| ch.is_ascii_digit()).collect();
+                let value = match c.get(2) {
+                    Some(frac) => format!("{}.{}", whole, frac.as_str()),
+                    None => whole,
+                };
+                Decimal::from_str(&value).ok()
+            })
+            .collect()
+    };
+
+    let total = text
+        .lines()
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            TOTAL_MARKERS.iter().any(|m| lower.contains(m))
+        })
+        .flat_map(amounts_in)
+        .max();
+
+    total
+        .or_else(|| {
+            text.lines()
+                .filter(|line| line.contains(',') || line.contains('.'))
+                .flat_map(amounts_in)
+                .max()
+        })
+        .filter(|amount| *amount > Decimal::ZERO)
+}
@@ -0,0 +1,335 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    FinancialContext, FinancialEvent, NewFinancialAuditEntry, Payment, PaymentAllocation,
+    PaymentStatus,
+};
+use crate::services::resilience::{self, KASPI};
+use crate::services::FinancialAuditService;
+use chrono::{Datelike, Utc};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+/// Заголовок с подписью уведомления Kaspi
+pub const KASPI_SIGNATURE_HEADER: &str = "x-kaspi-signature";
+
+pub struct PaymentService;
+
+/// Клиент Kaspi Pay: создание счёта на оплату и проверка уведомлений
+pub struct KaspiProvider {
+    config: Config,
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Serialize)]
+struct KaspiCreatePaymentRequest<'a> {
+    merchant_id: &'a str,
+    order_id: Uuid,
+    amount: Decimal,
+    currency: &'static str,
+    description: &'a str,
+    callback_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KaspiCreatePaymentResponse {
+    id: String,
+    payment_url: String,
+}
+
+/// Уведомление Kaspi об изменении статуса платежа
+#[derive(Debug, Deserialize)]
+pub struct KaspiWebhookPayload {
+    /// Идентификатор платежа в Kaspi
+    pub id: String,
+    /// Наш ID платежа, переданный при создании
+    pub order_id: Uuid,
+    pub status: String,
+    pub amount: Decimal,
+}
+
+/// Созданный у провайдера платёж
+pub struct ExternalPayment {
+    pub external_id: String,
+    pub payment_url: String,
+}
+
+impl KaspiProvider {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.kaspi_enabled
+    }
+
+    /// Создать платёж в Kaspi и получить ссылку на оплату
+    pub async fn create_payment(&self, payment: &Payment, description: &str) -> AppResult<ExternalPayment> {
+        let request = KaspiCreatePaymentRequest {
+            merchant_id: &self.config.kaspi_merchant_id,
+            order_id: payment.id,
+            amount: payment.amount,
+            currency: "KZT",
+            description,
+            callback_url: format!(
+                "{}/api/v1/communal/payments/webhook",
+                self.config.public_url.trim_end_matches('/')
+            ),
+        };
+
+        let result = resilience::call(&KASPI, || self.send_create_payment(&request)).await?;
+
+        Ok(ExternalPayment {
+            external_id: result.id,
+            payment_url: result.payment_url,
+        })
+    }
+
+    /// Проверить подпись уведомления: hex(HMAC-SHA256(secret, body))
+    pub fn verify_signature(&self, body: &[u8], signature: &str) -> bool {
+        verify_hmac_signature(&self.config.kaspi_webhook_secret, body, signature)
+    }
+
+    async fn send_create_payment(
+        &self,
+        request: &KaspiCreatePaymentRequest<'_>,
+    ) -> AppResult<KaspiCreatePaymentResponse> {
+        let response = self
+            .client
+            .post(format!("{}/payments", self.config.kaspi_api_url.trim_end_matches('/')))
+            .bearer_auth(&self.config.kaspi_api_key)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| AppError::Payment(format!("Kaspi недоступен: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::Payment(format!(
+                "Kaspi вернул ошибку: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| AppError::Payment(format!("Некорректный ответ Kaspi: {}", e)))
+    }
+}
+
+pub fn verify_hmac_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    if secret.is_empty() {
+        return false;
+    }
+
+    let Ok(expected) = hex::decode(signature.trim()) else {
+        return false;
+    };
+
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+impl PaymentService {
+    /// Провести успешный платёж: отметить завершённым и погасить счета.
+    /// Повторное уведомление по уже проведённому платежу ничего не меняет.
+    pub async fn complete(
+        pool: &PgPool,
+        context: &FinancialContext,
+        payment_id: Uuid,
+        external_id: &str,
+    ) -> AppResult<Option<Payment>> {
+        let mut tx = pool.begin().await?;
+
+        let payment = sqlx::query_as::<_, Payment>(
+            r#"
+            UPDATE payments
+            SET status = 'completed', completed_at = NOW(), external_id = COALESCE(external_id, $2)
+            WHERE id = $1 AND status IN ('pending', 'processing')
+            RETURNING *
+            "#,
+        )
+        .bind(payment_id)
+        .bind(external_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(payment) = payment else {
+            return Ok(None);
+        };
+
+        let (allocations, advance) =
+            Self::allocate(&mut tx, payment.id, payment.apartment_id, payment.bill_id, payment.amount)
+                .await?;
+
+        Self::audit(
+            &mut tx,
+            context,
+            FinancialEvent::PaymentCompleted,
+            &payment,
+            Some(json!({"allocations": allocations, "advance": advance})),
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(payment))
+    }
+
+    /// Отметить платёж неуспешным
+    pub async fn fail(pool: &PgPool, context: &FinancialContext, payment_id: Uuid) -> AppResult<()> {
+        let mut tx = pool.begin().await?;
+
+        let payment = sqlx::query_as::<_, Payment>(
+            r#"
+            UPDATE payments SET status = $2
+            WHERE id = $1 AND status IN ('pending', 'processing')
+            RETURNING *
+            "#,
+        )
+        .bind(payment_id)
+        .bind(PaymentStatus::Failed)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(payment) = payment {
+            Self::audit(&mut tx, context, FinancialEvent::PaymentFailed, &payment, None).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Записать изменение платежа в финансовый журнал
+    pub async fn audit(
+        tx: &mut Transaction<'_, Postgres>,
+        context: &FinancialContext,
+        event: FinancialEvent,
+        payment: &Payment,
+        details: Option<Value>,
+    ) -> AppResult<()> {
+        let complex_id = FinancialAuditService::apartment_complex(tx, payment.apartment_id).await?;
+
+        FinancialAuditService::record(
+            tx,
+            context,
+            NewFinancialAuditEntry {
+                event,
+                complex_id: Some(complex_id),
+                entity_type: "payment",
+                entity_id: payment.id,
+                amount: Some(payment.amount),
+                provider: Some(payment.method.as_str().to_string()),
+                provider_reference: payment.external_id.clone(),
+                details,
+            },
+        )
+        .await
+    }
+
+    /// Распределить платёж по неоплаченным счетам квартиры: сначала указанный счёт,
+    /// затем остальные от старых к новым. Возвращает распределение и остаток (аванс).
+    pub async fn allocate(
+        conn: &mut PgConnection,
+        payment_id: Uuid,
+        apartment_id: Uuid,
+        preferred_bill_id: Option<Uuid>,
+        amount: Decimal,
+    ) -> AppResult<(Vec<PaymentAllocation>, Decimal)> {
+        let bills: Vec<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT id, total_amount, COALESCE(paid_amount, 0)
+            FROM bills
+            WHERE apartment_id = $1 AND status IN ('pending', 'overdue')
+            ORDER BY (id = $2) DESC NULLS LAST, period_start, created_at
+            FOR UPDATE
+            "#,
+        )
+        .bind(apartment_id)
+        .bind(preferred_bill_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut remaining = amount;
+        let mut allocations = Vec::new();
+
+        for (bill_id, total, paid) in bills {
+            if remaining <= Decimal::ZERO {
+                break;
+            }
+
+            let due = total - paid;
+            if due <= Decimal::ZERO {
+                continue;
+            }
+
+            let part = remaining.min(due);
+            remaining -= part;
+
+            sqlx::query("INSERT INTO payment_allocations (payment_id, bill_id, amount) VALUES ($1, $2, $3)")
+                .bind(payment_id)
+                .bind(bill_id)
+                .bind(part)
+                .execute(&mut *conn)
+                .await?;
+
+            sqlx::query(
+                r#"
+                UPDATE bills SET
+                    paid_amount = COALESCE(paid_amount, 0) + $2,
+                    status = CASE WHEN COALESCE(paid_amount, 0) + $2 >= total_amount THEN 'paid'::bill_status ELSE status END,
+                    paid_at = CASE WHEN COALESCE(paid_amount, 0) + $2 >= total_amount THEN NOW() ELSE paid_at END
+                WHERE id = $1
+                "#,
+            )
+            .bind(bill_id)
+            .bind(part)
+            .execute(&mut *conn)
+            .await?;
+
+            allocations.push(PaymentAllocation {
+                bill_id,
+                amount: part,
+            });
+        }
+
+        Ok((allocations, remaining))
+    }
+
+    /// Следующий номер квитанции ОСИ, например `2026-000042`
+    pub async fn next_receipt_number(conn: &mut PgConnection, osi_id: Uuid) -> AppResult<String> {
+        let year = Utc::now().year();
+
+        let (number,): (i32,) = sqlx::query_as(
+            r#"
+            INSERT INTO receipt_counters (osi_id, year, last_number)
+            VALUES ($1, $2, 1)
+            ON CONFLICT (osi_id, year) DO UPDATE SET last_number = receipt_counters.last_number + 1
+            RETURNING last_number
+            "#,
+        )
+        .bind(osi_id)
+        .bind(year)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(format!("{}-{:06}", year, number))
+    }
+
+    /// Токен для проверки подлинности квитанции по QR-коду
+    pub fn generate_verification_token() -> String {
+        Uuid::new_v4().simple().to_string()
+    }
+}
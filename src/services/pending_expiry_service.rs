@@ -0,0 +1,250 @@
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::AppResult;
+use crate::models::{ComplexStatus, NewFieldChange, NotificationType};
+use crate::services::{
+    ComplexVerificationService, FieldHistoryService, NotificationService, SchedulerService,
+};
+
+/// Как часто проверять заявки без движения
+const PENDING_EXPIRY_INTERVAL_SECS: u64 = 3600;
+
+/// Последнее движение по заявке на ЖК: подача, действие администратора или новый документ
+const COMPLEX_LAST_ACTIVITY: &str = r#"
+    GREATEST(v.submitted_at, v.updated_at, COALESCE(
+        (SELECT MAX(e.created_at) FROM complex_verification_evidence e
+         WHERE e.complex_id = v.complex_id),
+        v.submitted_at
+    ))
+"#;
+
+/// Закрытие заявок на ЖК и на вступление, по которым давно ничего не происходит.
+/// Заявка закрывается не раньше, чем через срок напоминания после самого напоминания
+pub struct PendingExpiryService;
+
+impl PendingExpiryService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "pending_expiry",
+            std::time::Duration::from_secs(PENDING_EXPIRY_INTERVAL_SECS),
+            |pool, config| async move { PendingExpiryService::run(&pool, &config).await },
+        );
+    }
+
+    pub async fn run(pool: &PgPool, config: &Config) -> AppResult<()> {
+        let days = config.pending_expiry_days.max(1);
+        let reminder_days = config.pending_expiry_reminder_days.clamp(0, days);
+
+        let reminded = Self::remind_complexes(pool, days, reminder_days).await?
+            + Self::remind_join_requests(pool, days, reminder_days).await?;
+        let expired = Self::expire_complexes(pool, days, reminder_days).await?
+            + Self::expire_join_requests(pool, days, reminder_days).await?;
+
+        if reminded > 0 || expired > 0 {
+            tracing::info!(
+                "Pending applications: {} reminded, {} expired",
+                reminded,
+                expired
+            );
+        }
+        Ok(())
+    }
+
+    async fn remind_complexes(pool: &PgPool, days: i64, reminder_days: i64) -> AppResult<usize> {
+        let complex_ids: Vec<(Uuid,)> = sqlx::query_as(&format!(
+            r#"
+            UPDATE complex_verifications v SET expiry_reminded_at = NOW()
+            WHERE v.status IN ('submitted', 'needs_info')
+              AND {activity} < NOW() - make_interval(days => $1)
+              AND (v.expiry_reminded_at IS NULL OR v.expiry_reminded_at < {activity})
+            RETURNING v.complex_id
+            "#,
+            activity = COMPLEX_LAST_ACTIVITY
+        ))
+        .bind((days - reminder_days) as i32)
+        .fetch_all(pool)
+        .await?;
+
+        let body = format!(
+            "Если по заявке ничего не изменится, через {} дн. она будет закрыта. \
+             Дополните сведения или загрузите документы.",
+            reminder_days
+        );
+        for (complex_id,) in &complex_ids {
+            ComplexVerificationService::notify_creator(
+                pool,
+                *complex_id,
+                "Заявка на ЖК скоро закроется",
+                Some(&body),
+            )
+            .await?;
+        }
+
+        Ok(complex_ids.len())
+    }
+
+    async fn expire_complexes(pool: &PgPool, days: i64, reminder_days: i64) -> AppResult<usize> {
+        let reason = expiry_reason(days);
+        let mut tx = pool.begin().await?;
+
+        let complex_ids: Vec<(Uuid,)> = sqlx::query_as(&format!(
+            r#"
+            UPDATE complex_verifications v
+            SET status = 'rejected', rejection_reasons = ARRAY[$3], expired_at = NOW(),
+                reviewed_by = NULL, reviewed_at = NOW(), updated_at = NOW()
+            WHERE v.status IN ('submitted', 'needs_info')
+              AND {activity} < NOW() - make_interval(days => $1)
+              AND v.expiry_reminded_at >= {activity}
+              AND v.expiry_reminded_at < NOW() - make_interval(days => $2)
+            RETURNING v.complex_id
+            "#,
+            activity = COMPLEX_LAST_ACTIVITY
+        ))
+        .bind(days as i32)
+        .bind(reminder_days as i32)
+        .bind(&reason)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let ids: Vec<Uuid> = complex_ids.iter().map(|(id,)| *id).collect();
+        let closed: Vec<(Uuid,)> = sqlx::query_as(
+            "UPDATE complexes SET status = 'inactive', updated_at = NOW() WHERE id = ANY($1) AND status = 'pending' RETURNING id",
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (complex_id,) in closed {
+            FieldHistoryService::record(
+                &mut *tx,
+                NewFieldChange {
+                    complex_id,
+                    entity_type: "complex",
+                    entity_id: complex_id,
+                    changed_by: None,
+                    old_value: json!({"status": ComplexStatus::Pending}),
+                    new_value: json!({"status": ComplexStatus::Inactive}),
+                    request_id: None,
+                },
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        for complex_id in &ids {
+            ComplexVerificationService::notify_creator(
+                pool,
+                *complex_id,
+                "Заявка на ЖК закрыта",
+                Some(&format!("{} Её можно подать повторно, данные сохранены.", reason)),
+            )
+            .await?;
+        }
+
+        Ok(ids.len())
+    }
+
+    async fn remind_join_requests(
+        pool: &PgPool,
+        days: i64,
+        reminder_days: i64,
+    ) -> AppResult<usize> {
+        let requests: Vec<(Uuid, Uuid, Uuid, Option<Uuid>)> = sqlx::query_as(
+            r#"
+            UPDATE join_requests r SET expiry_reminded_at = NOW()
+            FROM complexes c
+            LEFT JOIN osi o ON o.complex_id = c.id
+            WHERE c.id = r.complex_id
+              AND r.status = 'pending'
+              AND r.submitted_at < NOW() - make_interval(days => $1)
+              AND (r.expiry_reminded_at IS NULL OR r.expiry_reminded_at < r.submitted_at)
+            RETURNING r.id, r.user_id, r.complex_id, COALESCE(o.chairman_id, c.created_by)
+            "#,
+        )
+        .bind((days - reminder_days) as i32)
+        .fetch_all(pool)
+        .await?;
+
+        let body = format!(
+            "Если заявку не рассмотрят, через {} дн. она будет закрыта автоматически.",
+            reminder_days
+        );
+        for (request_id, user_id, complex_id, reviewer_id) in &requests {
+            let data = json!({"join_request_id": request_id, "complex_id": complex_id});
+            NotificationService::notify_users(
+                pool,
+                &[*user_id],
+                NotificationType::System,
+                "Заявка на вступление ждёт рассмотрения",
+                Some(&body),
+                Some(data.clone()),
+            )
+            .await?;
+            if let Some(reviewer_id) = reviewer_id {
+                NotificationService::notify_users(
+                    pool,
+                    &[*reviewer_id],
+                    NotificationType::System,
+                    "Рассмотрите заявку на вступление в ЖК",
+                    Some(&body),
+                    Some(data),
+                )
+                .await?;
+            }
+        }
+
+        Ok(requests.len())
+    }
+
+    async fn expire_join_requests(
+        pool: &PgPool,
+        days: i64,
+        reminder_days: i64,
+    ) -> AppResult<usize> {
+        let reason = expiry_reason(days);
+
+        let requests: Vec<(Uuid, Uuid, Uuid)> = sqlx::query_as(
+            r#"
+            UPDATE join_requests
+            SET status = 'rejected', rejection_reason = $3, expired_at = NOW(),
+                reviewed_by = NULL, reviewed_at = NOW()
+            WHERE status = 'pending'
+              AND submitted_at < NOW() - make_interval(days => $1)
+              AND expiry_reminded_at >= submitted_at
+              AND expiry_reminded_at < NOW() - make_interval(days => $2)
+            RETURNING id, user_id, complex_id
+            "#,
+        )
+        .bind(days as i32)
+        .bind(reminder_days as i32)
+        .bind(&reason)
+        .fetch_all(pool)
+        .await?;
+
+        let body = format!("{} Её можно подать повторно.", reason);
+        for (request_id, user_id, complex_id) in &requests {
+            NotificationService::notify_users(
+                pool,
+                &[*user_id],
+                NotificationType::System,
+                "Заявка на вступление закрыта",
+                Some(&body),
+                Some(json!({"join_request_id": request_id, "complex_id": complex_id})),
+            )
+            .await?;
+        }
+
+        Ok(requests.len())
+    }
+}
+
+fn expiry_reason(days: i64) -> String {
+    format!(
+        "Заявка закрыта автоматически: по ней не было движения {} дн.",
+        days
+    )
+}
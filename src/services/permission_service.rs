@@ -0,0 +1,107 @@
+use crate::error::{AppError, AppResult};
+use crate::middleware::{is_admin_or_higher, AuthUser};
+use crate::models::Permission;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct PermissionService;
+
+impl PermissionService {
+    /// Права пользователя в ЖК: по ролям в этом ЖК, председательству в ОСИ и отдельным выдачам
+    pub async fn permissions(pool: &PgPool, auth_user: &AuthUser, complex_id: Uuid) -> AppResult<Vec<Permission>> {
+        if is_admin_or_higher(&auth_user.role) {
+            return Ok(sqlx::query_as::<_, (Permission,)>(
+                "SELECT unnest(enum_range(NULL::app_permission))",
+            )
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .map(|(p,)| p)
+            .collect());
+        }
+
+        let permissions: Vec<(Permission,)> = sqlx::query_as(
+            r#"
+            SELECT rp.permission
+            FROM role_permissions rp
+            WHERE rp.role IN (
+                SELECT role FROM complex_roles WHERE user_id = $1 AND complex_id = $2
+                UNION
+                SELECT 'chairman'::user_role FROM osi WHERE complex_id = $2 AND chairman_id = $1
+            )
+            UNION
+            SELECT permission FROM permission_grants WHERE user_id = $1 AND complex_id = $2
+            "#,
+        )
+        .bind(auth_user.user_id)
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(permissions.into_iter().map(|(p,)| p).collect())
+    }
+
+    /// Есть ли у пользователя право в ЖК
+    pub async fn has(pool: &PgPool, auth_user: &AuthUser, complex_id: Uuid, permission: Permission) -> AppResult<bool> {
+        if is_admin_or_higher(&auth_user.role) {
+            return Ok(true);
+        }
+
+        let (allowed,): (bool,) = sqlx::query_as(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM role_permissions rp
+                WHERE rp.permission = $3 AND rp.role IN (
+                    SELECT role FROM complex_roles WHERE user_id = $1 AND complex_id = $2
+                    UNION
+                    SELECT 'chairman'::user_role FROM osi WHERE complex_id = $2 AND chairman_id = $1
+                )
+                UNION ALL
+                SELECT 1 FROM permission_grants
+                WHERE user_id = $1 AND complex_id = $2 AND permission = $3
+            )
+            "#,
+        )
+        .bind(auth_user.user_id)
+        .bind(complex_id)
+        .bind(permission)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(allowed)
+    }
+
+    /// ЖК, в которых у пользователя есть право по ролям или выдачам, без учёта прав администратора
+    pub async fn complexes_with(pool: &PgPool, user_id: Uuid, permission: Permission) -> AppResult<Vec<Uuid>> {
+        let complexes: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT cr.complex_id
+            FROM complex_roles cr
+            JOIN role_permissions rp ON rp.role = cr.role AND rp.permission = $2
+            WHERE cr.user_id = $1
+            UNION
+            SELECT o.complex_id
+            FROM osi o
+            JOIN role_permissions rp ON rp.role = 'chairman' AND rp.permission = $2
+            WHERE o.chairman_id = $1
+            UNION
+            SELECT complex_id FROM permission_grants WHERE user_id = $1 AND permission = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(permission)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(complexes.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Проверить право, иначе `Forbidden`
+    pub async fn require(pool: &PgPool, auth_user: &AuthUser, complex_id: Uuid, permission: Permission) -> AppResult<()> {
+        if Self::has(pool, auth_user, complex_id, permission).await? {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+}
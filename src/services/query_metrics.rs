@@ -0,0 +1,170 @@
+use crate::middleware::RequestId;
+use axum::{body::Body, extract::MatchedPath, http::Request};
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::Mutex;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Metadata, Span, Subscriber};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Имя span'а HTTP-запроса, к которому относятся запросы к базе
+const REQUEST_SPAN: &str = "request";
+
+/// Цель событий, которыми sqlx сообщает о выполненных запросах
+const QUERY_TARGET: &str = "sqlx::query";
+
+/// Сколько последних замеров на маршрут хранится для расчёта перцентиля
+const WINDOW: usize = 1024;
+
+#[derive(Default)]
+struct RouteStats {
+    samples: VecDeque<f64>,
+    count: u64,
+    sum: f64,
+}
+
+static ROUTES: Lazy<Mutex<HashMap<String, RouteStats>>> = Lazy::new(Default::default);
+
+/// Span запроса: маршрут и идентификатор попадают в логи медленных запросов к базе
+pub fn request_span(request: &Request<Body>) -> Span {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str())
+        .unwrap_or("unmatched");
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|id| id.0.as_str())
+        .unwrap_or_default();
+
+    tracing::info_span!("request", method = %request.method(), route, request_id)
+}
+
+/// Слой tracing, собирающий время запросов к базе по маршрутам
+pub fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    QueryMetricsLayer.with_filter(filter_fn(is_relevant))
+}
+
+fn is_relevant(meta: &Metadata<'_>) -> bool {
+    if meta.is_span() {
+        meta.name() == REQUEST_SPAN && meta.target() == module_path!()
+    } else {
+        meta.target() == QUERY_TARGET
+    }
+}
+
+/// Маршрут, сохранённый в расширениях span'а запроса
+struct Route(String);
+
+struct QueryMetricsLayer;
+
+impl<S> Layer<S> for QueryMetricsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = RouteVisitor(None);
+        attrs.record(&mut visitor);
+
+        if let (Some(route), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(Route(route));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = ElapsedVisitor(None);
+        event.record(&mut visitor);
+        let Some(elapsed) = visitor.0 else {
+            return;
+        };
+
+        let Some(route) = ctx.event_scope(event).and_then(|scope| {
+            scope
+                .into_iter()
+                .find_map(|span| span.extensions().get::<Route>().map(|r| r.0.clone()))
+        }) else {
+            return;
+        };
+
+        let mut routes = ROUTES.lock().unwrap_or_else(|e| e.into_inner());
+        let stats = routes.entry(route).or_default();
+        if stats.samples.len() == WINDOW {
+            stats.samples.pop_front();
+        }
+        stats.samples.push_back(elapsed);
+        stats.count += 1;
+        stats.sum += elapsed;
+    }
+}
+
+struct RouteVisitor(Option<String>);
+
+impl Visit for RouteVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "route" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "route" {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+struct ElapsedVisitor(Option<f64>);
+
+impl Visit for ElapsedVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if field.name() == "elapsed_secs" {
+            self.0 = Some(value);
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+/// 95-й перцентиль по последним замерам
+fn p95(samples: &VecDeque<f64>) -> f64 {
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (sorted.len() as f64 * 0.95).ceil() as usize;
+    sorted.get(rank.saturating_sub(1)).copied().unwrap_or_default()
+}
+
+/// Время запросов к базе по маршрутам в формате Prometheus
+pub fn render_metrics() -> String {
+    let routes = ROUTES.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names: Vec<_> = routes.keys().collect();
+    names.sort_unstable();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP db_query_duration_seconds Database query time per route, p95 over the last {} queries",
+        WINDOW
+    );
+    let _ = writeln!(out, "# TYPE db_query_duration_seconds summary");
+    for name in names {
+        let stats = &routes[name];
+        let _ = writeln!(
+            out,
+            "db_query_duration_seconds{{route=\"{}\",quantile=\"0.95\"}} {}",
+            name,
+            p95(&stats.samples)
+        );
+        let _ = writeln!(out, "db_query_duration_seconds_sum{{route=\"{}\"}} {}", name, stats.sum);
+        let _ = writeln!(out, "db_query_duration_seconds_count{{route=\"{}\"}} {}", name, stats.count);
+    }
+
+    out
+}
@@ -0,0 +1,310 @@
+use crate::error::{AppError, AppResult};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Внешний сервис: политика повторов и параметры автомата защиты
+pub struct ExternalService {
+    pub name: &'static str,
+    /// Сколько всего попыток делаем за один вызов
+    pub max_attempts: u32,
+    /// Базовая задержка перед повтором, удваивается с каждой попыткой
+    pub base_delay: Duration,
+    /// Сколько неудачных вызовов подряд размыкают автомат
+    pub failure_threshold: u32,
+    /// Сколько автомат остаётся разомкнутым до пробного вызова
+    pub open_for: Duration,
+}
+
+/// У каждого SMS-провайдера свой автомат: пока один разомкнут, SMS идут через следующего
+pub const SMS_MOBIZON: ExternalService = ExternalService {
+    name: "sms_mobizon",
+    max_attempts: 3,
+    base_delay: Duration::from_millis(200),
+    failure_threshold: 5,
+    open_for: Duration::from_secs(30),
+};
+
+pub const SMS_SMSC: ExternalService = ExternalService {
+    name: "sms_smsc",
+    max_attempts: 3,
+    base_delay: Duration::from_millis(200),
+    failure_threshold: 5,
+    open_for: Duration::from_secs(30),
+};
+
+/// Без повторов: при сбое код сразу уходит по SMS
+pub const WHATSAPP: ExternalService = ExternalService {
+    name: "whatsapp",
+    max_attempts: 1,
+    base_delay: Duration::from_millis(200),
+    failure_threshold: 5,
+    open_for: Duration::from_secs(60),
+};
+
+pub const KASPI: ExternalService = ExternalService {
+    name: "kaspi",
+    max_attempts: 2,
+    base_delay: Duration::from_millis(300),
+    failure_threshold: 5,
+    open_for: Duration::from_secs(60),
+};
+
+/// Состояние автомата защиты
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    Closed,
+    Open,
+    /// Время размыкания прошло, пропускаем пробный вызов
+    HalfOpen,
+}
+
+impl BreakerState {
+    fn as_metric(self) -> u8 {
+        match self {
+            BreakerState::Closed => 0,
+            BreakerState::HalfOpen => 1,
+            BreakerState::Open => 2,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    open_for: Duration,
+    /// Когда начат пробный вызов в полуоткрытом состоянии; брошенная проба
+    /// перестаёт занимать автомат через `open_for`
+    probe_started: Option<Instant>,
+    calls: u64,
+    failures: u64,
+    rejected: u64,
+}
+
+impl Breaker {
+    fn state(&self) -> BreakerState {
+        match self.opened_at {
+            None => BreakerState::Closed,
+            Some(at) if at.elapsed() < self.open_for => BreakerState::Open,
+            Some(_) => BreakerState::HalfOpen,
+        }
+    }
+
+    /// Пропустить ли вызов: при разомкнутом автомате — нет, в полуоткрытом — только одну пробу
+    fn admit(&mut self) -> bool {
+        let allowed = match self.state() {
+            BreakerState::Closed => true,
+            BreakerState::Open => false,
+            BreakerState::HalfOpen => {
+                let probing = self.probe_started.is_some_and(|at| at.elapsed() < self.open_for);
+                if !probing {
+                    self.probe_started = Some(Instant::now());
+                }
+                !probing
+            }
+        };
+        if allowed {
+            self.calls += 1;
+        } else {
+            self.rejected += 1;
+        }
+        allowed
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probe_started = None;
+    }
+
+    /// Учесть вызов, не удавшийся после всех повторов; возвращает `true`, если автомат разомкнулся
+    fn record_failure(&mut self, failure_threshold: u32) -> bool {
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        let half_open = self.state() == BreakerState::HalfOpen;
+        self.probe_started = None;
+        if half_open || self.consecutive_failures >= failure_threshold {
+            let opened = self.opened_at.is_none() || half_open;
+            self.opened_at = Some(Instant::now());
+            return opened;
+        }
+        false
+    }
+}
+
+/// Имя метрики, её описание и значение для автомата
+type MetricCounter = (&'static str, &'static str, fn(&Breaker) -> u64);
+
+static BREAKERS: Lazy<Mutex<HashMap<&'static str, Breaker>>> = Lazy::new(Default::default);
+
+fn with_breaker<R>(service: &ExternalService, f: impl FnOnce(&mut Breaker) -> R) -> R {
+    let mut breakers = BREAKERS.lock().unwrap_or_else(|e| e.into_inner());
+    let breaker = breakers.entry(service.name).or_insert_with(|| Breaker {
+        open_for: service.open_for,
+        ..Default::default()
+    });
+    f(breaker)
+}
+
+/// Вызвать внешний сервис с повторами и автоматом защиты.
+/// При разомкнутом автомате сразу возвращает `ServiceUnavailable`.
+pub async fn call<T, F, Fut>(service: &ExternalService, mut op: F) -> AppResult<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = AppResult<T>>,
+{
+    let allowed = with_breaker(service, Breaker::admit);
+
+    if !allowed {
+        return Err(AppError::ServiceUnavailable(service.name.to_string()));
+    }
+
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Ok(value) => {
+                with_breaker(service, Breaker::record_success);
+                return Ok(value);
+            }
+            Err(e) if attempt < service.max_attempts => {
+                tracing::warn!(
+                    "{} call failed (attempt {}/{}): {}",
+                    service.name,
+                    attempt,
+                    service.max_attempts,
+                    e
+                );
+                tokio::time::sleep(backoff(service.base_delay, attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => {
+                if with_breaker(service, |b| b.record_failure(service.failure_threshold)) {
+                    tracing::error!("Circuit breaker for {} opened", service.name);
+                }
+                return Err(e);
+            }
+        }
+    }
+}
+
+/// Экспоненциальная задержка со случайным разбросом ±50%
+fn backoff(base: Duration, attempt: u32) -> Duration {
+    let delay = base.saturating_mul(1 << (attempt - 1).min(10));
+    delay.mul_f64(rand::thread_rng().gen_range(0.5..1.5))
+}
+
+/// Состояние автоматов защиты в формате Prometheus
+pub fn render_metrics() -> String {
+    let breakers = BREAKERS.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names: Vec<_> = breakers.keys().copied().collect();
+    names.sort_unstable();
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "# HELP external_circuit_state Circuit breaker state: 0 closed, 1 half-open, 2 open"
+    );
+    let _ = writeln!(out, "# TYPE external_circuit_state gauge");
+    for name in &names {
+        let _ = writeln!(
+            out,
+            "external_circuit_state{{service=\"{}\"}} {}",
+            name,
+            breakers[name].state().as_metric()
+        );
+    }
+
+    let counters: [MetricCounter; 3] = [
+        ("external_calls_total", "Calls made to the external service", |b| b.calls),
+        ("external_failures_total", "Calls that failed after all retries", |b| b.failures),
+        ("external_rejected_total", "Calls rejected by an open circuit breaker", |b| b.rejected),
+    ];
+    for (metric, help, value) in counters {
+        let _ = writeln!(out, "# HELP {} {}", metric, help);
+        let _ = writeln!(out, "# TYPE {} counter", metric);
+        for name in &names {
+            let _ = writeln!(out, "{}{{service=\"{}\"}} {}", metric, name, value(&breakers[name]));
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(open_for: Duration) -> Breaker {
+        Breaker {
+            open_for,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn opens_after_threshold_failures() {
+        let mut b = breaker(Duration::from_secs(60));
+        assert!(b.admit());
+        assert!(!b.record_failure(3));
+        assert!(!b.record_failure(3));
+        assert_eq!(b.state(), BreakerState::Closed);
+        assert!(b.record_failure(3));
+        assert_eq!(b.state(), BreakerState::Open);
+        assert!(!b.admit());
+        assert_eq!(b.rejected, 1);
+    }
+
+    #[test]
+    fn success_resets_failure_streak() {
+        let mut b = breaker(Duration::from_secs(60));
+        b.record_failure(3);
+        b.record_failure(3);
+        b.record_success();
+        assert!(!b.record_failure(3));
+        assert_eq!(b.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn half_open_admits_single_probe() {
+        let mut b = breaker(Duration::from_millis(20));
+        b.record_failure(1);
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(b.state(), BreakerState::HalfOpen);
+        assert!(b.admit());
+        assert!(!b.admit());
+        assert!(!b.admit());
+        b.record_success();
+        assert_eq!(b.state(), BreakerState::Closed);
+        assert!(b.admit());
+    }
+
+    #[test]
+    fn failed_probe_reopens() {
+        let mut b = breaker(Duration::from_millis(20));
+        b.record_failure(5);
+        b.record_failure(5);
+        b.record_failure(5);
+        b.record_failure(5);
+        assert!(b.record_failure(5));
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(b.admit());
+        assert!(b.record_failure(5));
+        assert_eq!(b.state(), BreakerState::Open);
+        assert!(!b.admit());
+    }
+
+    #[test]
+    fn abandoned_probe_frees_breaker() {
+        let mut b = breaker(Duration::from_millis(20));
+        b.record_failure(1);
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(b.admit());
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(b.admit());
+    }
+}
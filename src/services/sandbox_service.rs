@@ -0,0 +1,426 @@
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{Complex, CreateSandboxComplexRequest, SandboxComplexCreated};
+use crate::services::AuthService;
+use crate::utils::parse_phone;
+
+/// Таблицы с полем ЖК, которые сброс не трогает: сам ЖК, его проверка, права партнёра и журналы
+const SANDBOX_KEPT_TABLES: &[&str] = &[
+    "complexes",
+    "complex_photos",
+    "complex_verifications",
+    "complex_verification_evidence",
+    "chairman_applications",
+    "complex_roles",
+    "permission_grants",
+    "audit_logs",
+    "sms_messages",
+    // Журнал только на добавление: триггер не даёт удалять записи
+    "financial_audit_log",
+];
+
+/// Эталонные квартиры демо-ЖК: корпус, номер, этаж, площадь в сотых м², комнаты
+const SANDBOX_APARTMENTS: &[(&str, &str, i32, i64, i32)] = &[
+    ("1", "1", 1, 4250, 1),
+    ("1", "2", 1, 6480, 2),
+    ("1", "3", 2, 4250, 1),
+    ("1", "4", 2, 8910, 3),
+    ("2", "1", 1, 5530, 2),
+    ("2", "2", 1, 7120, 2),
+    ("2", "3", 2, 5530, 2),
+    ("2", "4", 2, 10240, 4),
+];
+
+/// Демо-ЖК для партнёров: создание и сброс к эталонным данным
+pub struct SandboxService;
+
+impl SandboxService {
+    /// Демо-ЖК ли это: SMS и платежи в нём не уходят провайдерам
+    pub async fn is_sandbox(pool: &PgPool, complex_id: Uuid) -> AppResult<bool> {
+        let is_sandbox: Option<(bool,)> =
+            sqlx::query_as("SELECT is_sandbox FROM complexes WHERE id = $1")
+                .bind(complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(is_sandbox.is_some_and(|(is_sandbox,)| is_sandbox))
+    }
+
+    /// Код входа для номера, состоящего только в демо-ЖК: код последнего демо-ЖК,
+    /// где он председатель. `None` — обычный вход по SMS
+    pub async fn login_code(pool: &PgPool, phone: &str) -> AppResult<Option<String>> {
+        if !Self::is_sandbox_phone(pool, phone).await? {
+            return Ok(None);
+        }
+
+        let code: Option<(Option<String>,)> = sqlx::query_as(
+            r#"
+            SELECT c.sandbox_login_code FROM users u
+            JOIN osi o ON o.chairman_id = u.id
+            JOIN complexes c ON c.id = o.complex_id
+            WHERE u.phone = $1 AND c.is_sandbox
+            ORDER BY c.created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(phone)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(code.and_then(|(code,)| code))
+    }
+
+    /// Номер пользователя без особой глобальной роли, состоящего только в демо-ЖК:
+    /// квартиры, семьи, ОСИ, роли в ЖК и работа в ОСИ учитываются все
+    async fn is_sandbox_phone(pool: &PgPool, phone: &str) -> AppResult<bool> {
+        let (sandbox_only,): (bool,) = sqlx::query_as(
+            r#"
+            WITH memberships AS (
+                SELECT c.is_sandbox FROM users u
+                JOIN apartments a ON a.owner_id = u.id OR a.resident_id = u.id
+                JOIN complexes c ON c.id = a.complex_id
+                WHERE u.phone = $1
+                UNION ALL
+                SELECT c.is_sandbox FROM family_members f
+                JOIN apartments a ON a.id = f.apartment_id
+                JOIN complexes c ON c.id = a.complex_id
+                WHERE f.phone = $1 OR f.user_id = (SELECT id FROM users WHERE phone = $1)
+                UNION ALL
+                SELECT c.is_sandbox FROM users u
+                JOIN osi o ON o.chairman_id = u.id
+                JOIN complexes c ON c.id = o.complex_id
+                WHERE u.phone = $1
+                UNION ALL
+                SELECT c.is_sandbox FROM users u
+                JOIN complex_roles r ON r.user_id = u.id
+                JOIN complexes c ON c.id = r.complex_id
+                WHERE u.phone = $1
+                UNION ALL
+                SELECT c.is_sandbox FROM osi_workers w
+                JOIN osi o ON o.id = w.osi_id
+                JOIN complexes c ON c.id = o.complex_id
+                WHERE w.phone = $1 OR w.user_id = (SELECT id FROM users WHERE phone = $1)
+            )
+            SELECT COALESCE(bool_and(is_sandbox), false)
+                   AND NOT EXISTS (
+                       SELECT 1 FROM users WHERE phone = $1 AND role NOT IN ('user', 'owner')
+                   )
+            FROM memberships
+            "#,
+        )
+        .bind(phone)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(sandbox_only)
+    }
+
+    /// Создать демо-ЖК с эталонными данными; партнёр с указанным номером становится председателем
+    /// ОСИ этого ЖК (глобальная роль не меняется) и входит по выданному коду
+    pub async fn create(
+        pool: &PgPool,
+        created_by: Uuid,
+        payload: &CreateSandboxComplexRequest,
+    ) -> AppResult<SandboxComplexCreated> {
+        let name = payload.name.trim();
+        if name.is_empty() {
+            return Err(AppError::Validation("Укажите название ЖК".to_string()));
+        }
+        let phone = parse_phone(&payload.chairman_phone).ok_or_else(|| {
+            AppError::Validation("Неверный формат номера телефона".to_string())
+        })?;
+
+        // Существующий аккаунт подходит, только если это уже партнёр демо-ЖК: иначе
+        // создатель демо-ЖК получил бы код входа в чужой аккаунт вместе с его ролью
+        let existing: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM users WHERE phone = $1")
+            .bind(&phone.e164)
+            .fetch_optional(pool)
+            .await?;
+
+        if existing.is_some() && !Self::is_sandbox_phone(pool, &phone.e164).await? {
+            return Err(AppError::Conflict(
+                "Номер уже зарегистрирован, для демо-ЖК нужен новый номер".to_string(),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let chairman_id = match existing {
+            Some((id,)) => id,
+            None => {
+                let created: Option<(Uuid,)> = sqlx::query_as(
+                    r#"
+                    INSERT INTO users (phone, phone_country_code, first_name, role, is_verified)
+                    VALUES ($1, $2, 'Партнёр', 'owner', true)
+                    ON CONFLICT (phone) DO NOTHING
+                    RETURNING id
+                    "#,
+                )
+                .bind(&phone.e164)
+                .bind(&phone.country_code)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+                let (id,) = created.ok_or_else(|| {
+                    AppError::Conflict(
+                        "Номер уже зарегистрирован, для демо-ЖК нужен новый номер".to_string(),
+                    )
+                })?;
+                id
+            }
+        };
+
+        let login_code = AuthService::generate_sms_code();
+
+        let complex = sqlx::query_as::<_, Complex>(
+            r#"
+            INSERT INTO complexes (
+                city_id, name, description, buildings_count, apartments_count,
+                status, is_sandbox, verified_at, verified_by, created_by, sandbox_login_code
+            )
+            VALUES ($1, $2, 'Демо-ЖК для интеграции с API', 2, $3, 'active', true, NOW(), $4, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(&payload.city_id)
+        .bind(name)
+        .bind(SANDBOX_APARTMENTS.len() as i32)
+        .bind(created_by)
+        .bind(&login_code)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        Self::seed(&mut tx, &complex, chairman_id).await?;
+
+        tx.commit().await?;
+        Ok(SandboxComplexCreated { complex, login_code })
+    }
+
+    /// Удалить всё, что накопилось в демо-ЖК, и заново заполнить его эталонными данными
+    pub async fn reset(pool: &PgPool, complex: &Complex) -> AppResult<()> {
+        if !complex.is_sandbox {
+            return Err(AppError::BadRequest(
+                "Сбросить можно только демо-ЖК".to_string(),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let chairman: Option<(Option<Uuid>,)> =
+            sqlx::query_as("SELECT chairman_id FROM osi WHERE complex_id = $1")
+                .bind(complex.id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let chairman_id = chairman
+            .and_then(|(chairman_id,)| chairman_id)
+            .or(complex.created_by)
+            .ok_or_else(|| AppError::BadRequest("У демо-ЖК нет председателя".to_string()))?;
+
+        Self::purge(&mut tx, complex.id).await?;
+        Self::seed(&mut tx, complex, chairman_id).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Удалить данные ЖК из всех таблиц, ссылающихся на ЖК, его квартиры или ОСИ.
+    /// Порядок не задаётся вручную: таблица, на которую ещё ссылаются, удаляется следующим проходом
+    async fn purge(conn: &mut PgConnection, complex_id: Uuid) -> AppResult<()> {
+        let tables: Vec<(String, bool, bool, bool)> = sqlx::query_as(
+            r#"
+            SELECT c.table_name::text,
+                   bool_or(c.column_name = 'complex_id'),
+                   bool_or(c.column_name = 'apartment_id'),
+                   bool_or(c.column_name = 'osi_id')
+            FROM information_schema.columns c
+            JOIN information_schema.tables t
+              ON t.table_schema = c.table_schema AND t.table_name = c.table_name
+            WHERE c.table_schema = current_schema()
+              AND t.table_type = 'BASE TABLE'
+              AND c.column_name IN ('complex_id', 'apartment_id', 'osi_id')
+            GROUP BY c.table_name
+            ORDER BY c.table_name
+            "#,
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let mut pending: Vec<String> = tables
+            .into_iter()
+            .filter(|(table, ..)| !SANDBOX_KEPT_TABLES.contains(&table.as_str()))
+            .map(|(table, by_complex, by_apartment, by_osi)| {
+                let mut conditions = Vec::new();
+                if by_complex {
+                    conditions.push("complex_id = $1".to_string());
+                }
+                if by_apartment {
+                    conditions.push("apartment_id IN (SELECT id FROM apartments WHERE complex_id = $1)".to_string());
+                }
+                if by_osi {
+                    conditions.push("osi_id IN (SELECT id FROM osi WHERE complex_id = $1)".to_string());
+                }
+                format!("DELETE FROM \"{}\" WHERE {}", table, conditions.join(" OR "))
+            })
+            .collect();
+
+        while !pending.is_empty() {
+            let mut blocked = Vec::new();
+
+            for statement in &pending {
+                sqlx::query("SAVEPOINT sandbox_purge").execute(&mut *conn).await?;
+                match sqlx::query(statement).bind(complex_id).execute(&mut *conn).await {
+                    Ok(_) => {
+                        sqlx::query("RELEASE SAVEPOINT sandbox_purge")
+                            .execute(&mut *conn)
+                            .await?;
+                    }
+                    // На строки ещё ссылается другая таблица ЖК
+                    Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23503") => {
+                        sqlx::query("ROLLBACK TO SAVEPOINT sandbox_purge")
+                            .execute(&mut *conn)
+                            .await?;
+                        blocked.push(statement.clone());
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            if blocked.len() == pending.len() {
+                return Err(AppError::Internal(format!(
+                    "Не удалось очистить демо-ЖК {}: {}",
+                    complex_id,
+                    blocked.join("; ")
+                )));
+            }
+            pending = blocked;
+        }
+
+        Ok(())
+    }
+
+    /// Эталонные данные: ОСИ, квартиры, объявление, голосование, счёт, заявка и чат ЖК
+    async fn seed(conn: &mut PgConnection, complex: &Complex, chairman_id: Uuid) -> AppResult<()> {
+        let (osi_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO osi (complex_id, name, chairman_id, phone, bank_name)
+            SELECT $1, 'ОСИ «' || $2 || '»', id, phone, 'Демо-банк' FROM users WHERE id = $3
+            RETURNING id
+            "#,
+        )
+        .bind(complex.id)
+        .bind(&complex.name)
+        .bind(chairman_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        let mut chairman_apartment_id = None;
+        for (index, (building, number, floor, area, rooms)) in SANDBOX_APARTMENTS.iter().enumerate() {
+            // Первая квартира принадлежит председателю, остальные свободны для тестовых жителей
+            let owner_id = (index == 0).then_some(chairman_id);
+            let (apartment_id,): (Uuid,) = sqlx::query_as(
+                r#"
+                INSERT INTO apartments (complex_id, building, number, floor, area, rooms_count, owner_id, is_ownership_verified)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $7 IS NOT NULL)
+                RETURNING id
+                "#,
+            )
+            .bind(complex.id)
+            .bind(building)
+            .bind(number)
+            .bind(floor)
+            .bind(Decimal::new(*area, 2))
+            .bind(rooms)
+            .bind(owner_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            chairman_apartment_id.get_or_insert(apartment_id);
+        }
+        let chairman_apartment_id = chairman_apartment_id.expect("sandbox fixture has apartments");
+
+        sqlx::query(
+            r#"
+            INSERT INTO announcements (complex_id, title, content, author_id)
+            VALUES ($1, 'Добро пожаловать в демо-ЖК', 'Данные этого ЖК можно свободно менять и сбрасывать.', $2)
+            "#,
+        )
+        .bind(complex.id)
+        .bind(chairman_id)
+        .execute(&mut *conn)
+        .await?;
+
+        let now = Utc::now();
+        let (voting_id,): (Uuid,) = sqlx::query_as(
+            r#"
+            INSERT INTO votings (complex_id, osi_id, title, voting_type, status, starts_at, ends_at, created_by)
+            VALUES ($1, $2, 'Установить шлагбаум у второго корпуса', 'yes_no', 'active', $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(complex.id)
+        .bind(osi_id)
+        .bind(now)
+        .bind(now + Duration::days(14))
+        .bind(chairman_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO voting_options (voting_id, text, sort_order) VALUES ($1, 'За', 0), ($1, 'Против', 1)",
+        )
+        .bind(voting_id)
+        .execute(&mut *conn)
+        .await?;
+
+        // Неоплаченный счёт председателя за прошлый месяц — чтобы проверить оплату
+        let month_start = NaiveDate::from_ymd_opt(now.year(), now.month(), 1).expect("valid date");
+        let period_start = month_start - chrono::Months::new(1);
+        let amount = Decimal::new(1850000, 2);
+        sqlx::query(
+            r#"
+            INSERT INTO bills (apartment_id, complex_id, period_start, period_end, amount, total_amount, due_date)
+            VALUES ($1, $2, $3, $4, $5, $5, $6)
+            "#,
+        )
+        .bind(chairman_apartment_id)
+        .bind(complex.id)
+        .bind(period_start)
+        .bind(month_start - Duration::days(1))
+        .bind(amount)
+        .bind(month_start + Duration::days(24))
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO maintenance_requests (complex_id, apartment_id, requester_id, category, title)
+            VALUES ($1, $2, $3, 'plumbing', 'Течёт кран на кухне')
+            "#,
+        )
+        .bind(complex.id)
+        .bind(chairman_apartment_id)
+        .bind(chairman_id)
+        .execute(&mut *conn)
+        .await?;
+
+        sqlx::query(
+            r#"
+            WITH chat AS (
+                INSERT INTO chats (complex_id, chat_type, name, created_by)
+                VALUES ($1, 'complex', 'Чат ЖК', $2)
+                RETURNING id
+            )
+            INSERT INTO chat_members (chat_id, user_id) SELECT id, $2 FROM chat
+            "#,
+        )
+        .bind(complex.id)
+        .bind(chairman_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
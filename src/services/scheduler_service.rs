@@ -0,0 +1,107 @@
+use crate::config::Config;
+use crate::error::AppResult;
+use sqlx::PgPool;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+pub type TaskFuture = Pin<Box<dyn Future<Output = AppResult<()>> + Send>>;
+
+type TaskFn = Arc<dyn Fn(PgPool, Config) -> TaskFuture + Send + Sync>;
+
+/// Периодическая задача обслуживания
+struct RecurringTask {
+    name: &'static str,
+    interval: Duration,
+    run: TaskFn,
+}
+
+/// Планировщик периодических задач. Модули регистрируют свои задачи
+/// через `register`, после чего `start` запускает их по интервалам.
+pub struct SchedulerService {
+    pool: PgPool,
+    config: Config,
+    tasks: Vec<RecurringTask>,
+}
+
+impl SchedulerService {
+    pub fn new(pool: PgPool, config: Config) -> Self {
+        Self {
+            pool,
+            config,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Зарегистрировать задачу, выполняемую каждые `interval`
+    pub fn register<F, Fut>(&mut self, name: &'static str, interval: Duration, task: F)
+    where
+        F: Fn(PgPool, Config) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = AppResult<()>> + Send + 'static,
+    {
+        self.tasks.push(RecurringTask {
+            name,
+            interval,
+            run: Arc::new(move |pool, config| Box::pin(task(pool, config))),
+        });
+    }
+
+    /// Запустить все зарегистрированные задачи
+    pub fn start(self) {
+        if !self.config.scheduler_enabled {
+            tracing::info!("Scheduler is disabled");
+            return;
+        }
+
+        let count = self.tasks.len();
+
+        for task in self.tasks {
+            let pool = self.pool.clone();
+            let config = self.config.clone();
+            tokio::spawn(async move { run_task(task, pool, config).await });
+        }
+
+        tracing::info!("Started {} scheduled tasks", count);
+    }
+}
+
+async fn run_task(task: RecurringTask, pool: PgPool, config: Config) {
+    let mut interval = tokio::time::interval(task.interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        match run_exclusive(&task, &pool, &config).await {
+            Ok(true) => {}
+            Ok(false) => tracing::debug!("Scheduled task {} is running elsewhere", task.name),
+            Err(e) => tracing::error!("Scheduled task {} failed: {}", task.name, e),
+        }
+    }
+}
+
+/// Выполнить задачу под advisory-блокировкой, чтобы при нескольких
+/// экземплярах сервиса она не запускалась параллельно
+async fn run_exclusive(task: &RecurringTask, pool: &PgPool, config: &Config) -> AppResult<bool> {
+    let mut conn = pool.acquire().await?;
+
+    let (locked,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_lock(hashtext($1))")
+        .bind(task.name)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    if !locked {
+        return Ok(false);
+    }
+
+    let result = (task.run)(pool.clone(), config.clone()).await;
+
+    sqlx::query("SELECT pg_advisory_unlock(hashtext($1))")
+        .bind(task.name)
+        .execute(&mut *conn)
+        .await?;
+
+    result.map(|_| true)
+}
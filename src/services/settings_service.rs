@@ -0,0 +1,76 @@
+use crate::error::{AppError, AppResult};
+use crate::models::MaintenanceMode;
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Как долго держим настройки в памяти, прежде чем перечитать из базы
+const SETTINGS_CACHE_TTL: Duration = Duration::from_secs(10);
+
+const MAINTENANCE_MODE_KEY: &str = "maintenance_mode";
+
+static MAINTENANCE_CACHE: Lazy<RwLock<Option<(Instant, MaintenanceMode)>>> =
+    Lazy::new(|| RwLock::new(None));
+
+pub struct SettingsService;
+
+impl SettingsService {
+    /// Текущий режим обслуживания (с кэшированием, вызывается на каждый запрос)
+    pub async fn maintenance_mode(pool: &PgPool) -> AppResult<MaintenanceMode> {
+        if let Ok(cache) = MAINTENANCE_CACHE.read() {
+            if let Some((loaded_at, mode)) = cache.as_ref() {
+                if loaded_at.elapsed() < SETTINGS_CACHE_TTL {
+                    return Ok(mode.clone());
+                }
+            }
+        }
+
+        let value: Option<(serde_json::Value,)> =
+            sqlx::query_as("SELECT value FROM system_settings WHERE key = $1")
+                .bind(MAINTENANCE_MODE_KEY)
+                .fetch_optional(pool)
+                .await?;
+
+        let mode: MaintenanceMode = value
+            .and_then(|(v,)| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+
+        if let Ok(mut cache) = MAINTENANCE_CACHE.write() {
+            *cache = Some((Instant::now(), mode.clone()));
+        }
+
+        Ok(mode)
+    }
+
+    /// Сохранить режим обслуживания
+    pub async fn set_maintenance_mode(
+        pool: &PgPool,
+        mode: &MaintenanceMode,
+        updated_by: Uuid,
+    ) -> AppResult<()> {
+        let value = serde_json::to_value(mode)
+            .map_err(|e| AppError::Internal(format!("Ошибка сериализации настроек: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO system_settings (key, value, updated_by, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (key) DO UPDATE
+            SET value = EXCLUDED.value, updated_by = EXCLUDED.updated_by, updated_at = NOW()
+            "#,
+        )
+        .bind(MAINTENANCE_MODE_KEY)
+        .bind(value)
+        .bind(updated_by)
+        .execute(pool)
+        .await?;
+
+        if let Ok(mut cache) = MAINTENANCE_CACHE.write() {
+            *cache = Some((Instant::now(), mode.clone()));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::error::AppResult;
+use crate::models::{AllocationRule, SharedCharge, SharedChargeStatus};
+use crate::services::BillingService;
+use chrono::{Datelike, Duration, NaiveDate};
+use rust_decimal::{Decimal, RoundingStrategy};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct SharedChargeService;
+
+impl SharedChargeService {
+    /// Добавить доли общего начисления в счета квартир за расчётный период
+    pub async fn bill(pool: &PgPool, charge_id: Uuid) -> AppResult<()> {
+        let mut tx = pool.begin().await?;
+
+        let Some(charge) =
+            sqlx::query_as::<_, SharedCharge>("SELECT * FROM shared_charges WHERE id = $1 FOR UPDATE")
+                .bind(charge_id)
+                .fetch_optional(&mut *tx)
+                .await?
+        else {
+            return Ok(());
+        };
+
+        if charge.status != SharedChargeStatus::Scheduled {
+            return Ok(());
+        }
+
+        let (complex_id,): (Uuid,) = sqlx::query_as("SELECT complex_id FROM osi WHERE id = $1")
+            .bind(charge.osi_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        let period_start = charge.billing_period;
+
+        let rate = (charge.total_amount / charge.total_basis)
+            .round_dp_with_strategy(4, RoundingStrategy::MidpointAwayFromZero);
+        let unit = match charge.rule {
+            AllocationRule::ByArea => "м²",
+            AllocationRule::PerUnit => "кв.",
+        };
+
+        let allocations: Vec<(Uuid, Uuid, Decimal, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT id, apartment_id, basis, amount FROM shared_charge_allocations
+            WHERE charge_id = $1 AND bill_item_id IS NULL AND amount > 0
+            "#,
+        )
+        .bind(charge.id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (allocation_id, apartment_id, basis, amount) in allocations {
+            let bill_id =
+                BillingService::pending_bill(&mut tx, apartment_id, complex_id, period_start).await?;
+
+            let (item_id,): (Uuid,) = sqlx::query_as(
+                r#"
+                INSERT INTO bill_items (bill_id, utility_type, description, quantity, unit, rate, amount)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING id
+                "#,
+            )
+            .bind(bill_id)
+            .bind(&charge.utility_type)
+            .bind(&charge.title)
+            .bind(basis)
+            .bind(unit)
+            .bind(rate)
+            .bind(amount)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                "UPDATE bills SET amount = amount + $2, total_amount = total_amount + $2 WHERE id = $1",
+            )
+            .bind(bill_id)
+            .bind(amount)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("UPDATE shared_charge_allocations SET bill_item_id = $2 WHERE id = $1")
+                .bind(allocation_id)
+                .bind(item_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("UPDATE shared_charges SET status = 'billed', billed_at = NOW() WHERE id = $1")
+            .bind(charge.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        tracing::info!("Shared charge {} billed for period {}", charge.id, period_start);
+
+        Ok(())
+    }
+}
+
+/// Разделить сумму пропорционально базе с точностью до тиына.
+/// Остаток от округления достаётся доле с наибольшей базой, чтобы сумма сошлась.
+pub fn split_amount(total: Decimal, bases: &[Decimal]) -> Vec<Decimal> {
+    let total_basis: Decimal = bases.iter().sum();
+    if total_basis <= Decimal::ZERO {
+        return vec![Decimal::ZERO; bases.len()];
+    }
+
+    let mut shares: Vec<Decimal> = bases
+        .iter()
+        .map(|basis| {
+            (total * basis / total_basis).round_dp_with_strategy(2, RoundingStrategy::ToZero)
+        })
+        .collect();
+
+    let remainder = total - shares.iter().sum::<Decimal>();
+    if let Some((largest, _)) = bases.iter().enumerate().max_by_key(|(_, basis)| **basis) {
+        shares[largest] += remainder;
+    }
+
+    shares
+}
+
+/// Первый день следующего месяца — начало ближайшего расчётного периода
+pub fn next_month_start(date: NaiveDate) -> NaiveDate {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(date)
+}
+
+/// Последний день месяца
+pub fn month_end(date: NaiveDate) -> NaiveDate {
+    next_month_start(date) - Duration::days(1)
+}
@@ -0,0 +1,353 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::SmsDeliveryStatus;
+use crate::services::resilience::{ExternalService, SMS_MOBIZON, SMS_SMSC};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Отправленное SMS по данным провайдера
+pub struct SentSms {
+    pub message_id: Option<String>,
+    /// Стоимость, если провайдер её вернул
+    pub cost: Option<Decimal>,
+}
+
+/// Отчёт провайдера о доставке SMS
+pub struct SmsStatusReport {
+    pub message_id: String,
+    pub status: SmsDeliveryStatus,
+    /// Код или описание причины недоставки
+    pub error: Option<String>,
+}
+
+/// Шлюз отправки SMS
+#[axum::async_trait]
+pub trait SmsProvider: Send + Sync {
+    /// Имя провайдера в конфигурации и журнале SMS
+    fn name(&self) -> &'static str;
+
+    /// Повторы и автомат защиты для этого провайдера
+    fn service(&self) -> &'static ExternalService;
+
+    async fn send(&self, phone: &str, text: &str, sender: &str) -> AppResult<SentSms>;
+
+    /// Разобрать параметры обратного вызова о статусе. `None` — промежуточный
+    /// статус или запрос без идентификатора сообщения
+    fn parse_status(&self, params: &HashMap<String, String>) -> Option<SmsStatusReport>;
+}
+
+/// Провайдер по имени из конфигурации или журнала SMS
+pub fn by_name(config: &Config, name: &str) -> Option<Box<dyn SmsProvider>> {
+    match name {
+        "mobizon" => Some(Box::new(MobizonProvider::new(config))),
+        "smsc" => Some(Box::new(SmscProvider::new(config))),
+        _ => None,
+    }
+}
+
+/// Провайдеры в порядке из `SMS_PROVIDERS`; неизвестные имена пропускаются
+pub fn from_config(config: &Config) -> Vec<Box<dyn SmsProvider>> {
+    config
+        .sms_providers
+        .iter()
+        .filter_map(|name| {
+            let provider = by_name(config, name);
+            if provider.is_none() {
+                tracing::warn!("Unknown SMS provider '{}' in configuration", name);
+            }
+            provider
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct MobizonResponse {
+    code: i32,
+    message: String,
+    #[serde(default)]
+    data: Option<MobizonMessageData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MobizonMessageData {
+    message_id: Option<serde_json::Value>,
+    cost: Option<Decimal>,
+}
+
+/// Mobizon: `sendsmsmessage` с ключом API в строке запроса
+pub struct MobizonProvider {
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl MobizonProvider {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            api_key: config.sms_api_key.clone(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[axum::async_trait]
+impl SmsProvider for MobizonProvider {
+    fn name(&self) -> &'static str {
+        "mobizon"
+    }
+
+    fn service(&self) -> &'static ExternalService {
+        &SMS_MOBIZON
+    }
+
+    async fn send(&self, phone: &str, text: &str, sender: &str) -> AppResult<SentSms> {
+        let url = format!(
+            "https://api.mobizon.kz/service/message/sendsmsmessage?apiKey={}",
+            self.api_key
+        );
+        let params = [("recipient", phone), ("text", text), ("from", sender)];
+
+        let response = self
+            .client
+            .post(&url)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| AppError::Sms(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::Sms(e.to_string()))?;
+
+        if !status.is_success() {
+            tracing::error!("Mobizon API error: {} - {}", status, body);
+            return Err(AppError::Sms(format!("Mobizon API error: {}", status)));
+        }
+
+        let result: MobizonResponse =
+            serde_json::from_str(&body).map_err(|e| AppError::Sms(e.to_string()))?;
+
+        if result.code != 0 {
+            tracing::error!("Mobizon send failed: {}", result.message);
+            return Err(AppError::Sms(result.message));
+        }
+
+        let data = result.data;
+        Ok(SentSms {
+            message_id: data
+                .as_ref()
+                .and_then(|d| d.message_id.as_ref())
+                .map(|id| id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string())),
+            cost: data.and_then(|d| d.cost),
+        })
+    }
+
+    /// `messageId` и `status`: DELIVRD — доставлено, UNDELIV, REJECTD, EXPIRED,
+    /// DELETED — нет; остальные статусы промежуточные
+    fn parse_status(&self, params: &HashMap<String, String>) -> Option<SmsStatusReport> {
+        let message_id = params.get("messageId")?;
+        let code = params.get("status")?;
+        let status = match code.as_str() {
+            "DELIVRD" => SmsDeliveryStatus::Delivered,
+            "UNDELIV" | "REJECTD" | "EXPIRED" | "DELETED" => SmsDeliveryStatus::Undelivered,
+            _ => return None,
+        };
+
+        Some(SmsStatusReport {
+            message_id: message_id.clone(),
+            status,
+            error: (status == SmsDeliveryStatus::Undelivered).then(|| code.clone()),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SmscResponse {
+    id: Option<serde_json::Value>,
+    /// Стоимость приходит строкой
+    cost: Option<String>,
+    error: Option<String>,
+    error_code: Option<i32>,
+}
+
+/// SMSC: `send.php` с логином и паролем, ответ в JSON (`fmt=3`) со стоимостью (`cost=3`)
+pub struct SmscProvider {
+    login: String,
+    password: String,
+    client: reqwest::Client,
+}
+
+impl SmscProvider {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            login: config.smsc_login.clone(),
+            password: config.smsc_password.clone(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[axum::async_trait]
+impl SmsProvider for SmscProvider {
+    fn name(&self) -> &'static str {
+        "smsc"
+    }
+
+    fn service(&self) -> &'static ExternalService {
+        &SMS_SMSC
+    }
+
+    async fn send(&self, phone: &str, text: &str, sender: &str) -> AppResult<SentSms> {
+        let params = [
+            ("login", self.login.as_str()),
+            ("psw", self.password.as_str()),
+            ("phones", phone),
+            ("mes", text),
+            ("sender", sender),
+            ("charset", "utf-8"),
+            ("fmt", "3"),
+            ("cost", "3"),
+        ];
+
+        let response = self
+            .client
+            .post("https://smsc.kz/sys/send.php")
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| AppError::Sms(e.to_string()))?;
+
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::Sms(e.to_string()))?;
+
+        if !status.is_success() {
+            tracing::error!("SMSC API error: {} - {}", status, body);
+            return Err(AppError::Sms(format!("SMSC API error: {}", status)));
+        }
+
+        let result: SmscResponse =
+            serde_json::from_str(&body).map_err(|e| AppError::Sms(e.to_string()))?;
+
+        if let Some(error) = result.error {
+            tracing::error!("SMSC send failed ({:?}): {}", result.error_code, error);
+            return Err(AppError::Sms(error));
+        }
+
+        Ok(SentSms {
+            message_id: result
+                .id
+                .map(|id| id.as_str().map(str::to_string).unwrap_or_else(|| id.to_string())),
+            cost: result.cost.and_then(|cost| Decimal::from_str(&cost).ok()),
+        })
+    }
+
+    /// `id`, `status` и `err`: 1 — доставлено, 3 и 20–25 — не доставлено,
+    /// остальные статусы промежуточные
+    fn parse_status(&self, params: &HashMap<String, String>) -> Option<SmsStatusReport> {
+        let message_id = params.get("id")?;
+        let code: i32 = params.get("status")?.parse().ok()?;
+        let status = match code {
+            1 => SmsDeliveryStatus::Delivered,
+            3 | 20..=25 => SmsDeliveryStatus::Undelivered,
+            _ => return None,
+        };
+        let error = (status == SmsDeliveryStatus::Undelivered).then(|| match params.get("err") {
+            Some(err) => format!("status {}, err {}", code, err),
+            None => format!("status {}", code),
+        });
+
+        Some(SmsStatusReport {
+            message_id: message_id.clone(),
+            status,
+            error,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn mobizon() -> MobizonProvider {
+        MobizonProvider {
+            api_key: String::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn smsc() -> SmscProvider {
+        SmscProvider {
+            login: String::new(),
+            password: String::new(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    #[test]
+    fn mobizon_delivered() {
+        let report = mobizon()
+            .parse_status(&params(&[("messageId", "42"), ("status", "DELIVRD")]))
+            .unwrap();
+        assert_eq!(report.message_id, "42");
+        assert_eq!(report.status, SmsDeliveryStatus::Delivered);
+        assert_eq!(report.error, None);
+    }
+
+    #[test]
+    fn mobizon_undelivered_keeps_code() {
+        for code in ["UNDELIV", "REJECTD", "EXPIRED", "DELETED"] {
+            let report = mobizon()
+                .parse_status(&params(&[("messageId", "42"), ("status", code)]))
+                .unwrap();
+            assert_eq!(report.status, SmsDeliveryStatus::Undelivered);
+            assert_eq!(report.error.as_deref(), Some(code));
+        }
+    }
+
+    #[test]
+    fn mobizon_intermediate_or_incomplete_ignored() {
+        assert!(mobizon().parse_status(&params(&[("messageId", "42"), ("status", "ACCEPTD")])).is_none());
+        assert!(mobizon().parse_status(&params(&[("status", "DELIVRD")])).is_none());
+        assert!(mobizon().parse_status(&params(&[("messageId", "42")])).is_none());
+    }
+
+    #[test]
+    fn smsc_delivered() {
+        let report = smsc().parse_status(&params(&[("id", "7"), ("status", "1")])).unwrap();
+        assert_eq!(report.message_id, "7");
+        assert_eq!(report.status, SmsDeliveryStatus::Delivered);
+        assert_eq!(report.error, None);
+    }
+
+    #[test]
+    fn smsc_undelivered_with_and_without_err() {
+        let report = smsc()
+            .parse_status(&params(&[("id", "7"), ("status", "20"), ("err", "1")]))
+            .unwrap();
+        assert_eq!(report.status, SmsDeliveryStatus::Undelivered);
+        assert_eq!(report.error.as_deref(), Some("status 20, err 1"));
+
+        let report = smsc().parse_status(&params(&[("id", "7"), ("status", "3")])).unwrap();
+        assert_eq!(report.status, SmsDeliveryStatus::Undelivered);
+        assert_eq!(report.error.as_deref(), Some("status 3"));
+    }
+
+    #[test]
+    fn smsc_intermediate_or_malformed_ignored() {
+        for status in ["0", "2", "26", "-1", "delivered"] {
+            assert!(smsc().parse_status(&params(&[("id", "7"), ("status", status)])).is_none());
+        }
+        assert!(smsc().parse_status(&params(&[("status", "1")])).is_none());
+    }
+}
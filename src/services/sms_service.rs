@@ -1,116 +1,482 @@
 use crate::config::Config;
 use crate::error::{AppError, AppResult};
-use serde::{Deserialize, Serialize};
+use crate::models::{JobType, SmsDeliveryPayload, SmsDeliveryStatus, SmsMessage, SmsStatus};
+use crate::services::resilience;
+use crate::services::sms_provider::{self, SentSms, SmsProvider, SmsStatusReport};
+use crate::services::{JobService, SandboxService};
+use crate::utils::parse_phone;
+use rust_decimal::Decimal;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// SMS с кодом входа: текст в журнал не пишется и повторно не отправляется
+const AUTH_CODE_KIND: &str = "auth_code";
+
+/// Условие на строку `m` журнала, при котором SMS можно переслать вручную: отправка
+/// не удалась или провайдер сообщил о недоставке, и с тех пор тот же текст на тот же
+/// номер не уходил и не ждёт повтора в очереди или в гостевом пропуске
+pub const RESENDABLE_SQL: &str = r#"
+    m.text IS NOT NULL AND m.resent_at IS NULL
+    AND (m.status = 'failed' OR m.delivery_status = 'undelivered')
+    AND NOT EXISTS (
+        SELECT 1 FROM sms_messages later
+        WHERE later.phone = m.phone AND later.kind = m.kind AND later.text = m.text
+          AND later.created_at > m.created_at AND later.status IN ('sent', 'failed'))
+    AND NOT EXISTS (
+        SELECT 1 FROM jobs j
+        WHERE j.job_type = 'sms_delivery' AND j.status IN ('pending', 'running')
+          AND j.payload->>'phone' = m.phone AND j.payload->>'kind' = m.kind
+          AND j.payload->>'text' = m.text)
+    AND NOT (m.kind = 'guest_entry' AND EXISTS (
+        SELECT 1 FROM guest_access g JOIN users u ON u.id = g.created_by
+        WHERE u.phone = m.phone AND g.owner_notification_status = 'retrying'))
+"#;
 
 pub struct SmsService {
     config: Config,
-    client: reqwest::Client,
+    /// Провайдеры в порядке перебора при сбоях
+    providers: Vec<Box<dyn SmsProvider>>,
 }
 
-#[derive(Debug, Serialize)]
-struct MobizonRequest {
-    recipient: String,
-    text: String,
-    from: String,
-}
-
-#[derive(Debug, Deserialize)]
-struct MobizonResponse {
-    code: i32,
-    message: String,
+/// Чем закончилась отправка SMS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsDelivery {
+    Sent,
+    /// SMS выключены в конфигурации
+    Disabled,
+    /// Месячный лимит ОСИ на SMS исчерпан — уведомлять нужно только push
+    OverBudget,
+    /// Шлюз недоступен, SMS поставлено в очередь на повторную отправку
+    Queued,
 }
 
 impl SmsService {
     pub fn new(config: Config) -> Self {
         Self {
+            providers: sms_provider::from_config(&config),
             config,
-            client: reqwest::Client::new(),
         }
     }
 
-    pub async fn send_code(&self, phone: &str, code: &str) -> AppResult<()> {
+    pub async fn send_code(&self, pool: &PgPool, phone: &str, code: &str) -> AppResult<()> {
         if !self.config.sms_enabled {
             tracing::info!("SMS disabled. Code for {}: {}", phone, code);
             return Ok(());
         }
 
         let text = format!("Ваш код подтверждения LocalHood: {}. Никому не сообщайте этот код.", code);
-        self.send_sms(phone, &text).await
+        // Коды входа не ограничиваются лимитом ОСИ и относятся на платформу
+        self.deliver(pool, None, AUTH_CODE_KIND, phone, &text).await?;
+        Ok(())
     }
 
     pub async fn send_guest_entry_notification(
         &self,
+        pool: &PgPool,
+        complex_id: Uuid,
         phone: &str,
         guest_name: &str,
         time: &str,
-    ) -> AppResult<()> {
+    ) -> AppResult<SmsDelivery> {
         if !self.config.sms_enabled {
             tracing::info!("SMS disabled. Guest entry notification for {}", phone);
-            return Ok(());
+            return Ok(SmsDelivery::Disabled);
         }
 
         let text = format!("LocalHood: Гость {} въехал в {}.", guest_name, time);
-        self.send_sms(phone, &text).await
+        // Без общей очереди SMS: повторы ведёт BarrierService и отмечает их в пропуске
+        self.send_and_record(pool, Some(complex_id), "guest_entry", phone, &text)
+            .await
     }
 
     pub async fn send_overstay_notification(
         &self,
+        pool: &PgPool,
+        complex_id: Uuid,
         phone: &str,
         guest_name: &str,
         minutes: i32,
-    ) -> AppResult<()> {
+    ) -> AppResult<SmsDelivery> {
         if !self.config.sms_enabled {
             tracing::info!("SMS disabled. Overstay notification for {}", phone);
-            return Ok(());
+            return Ok(SmsDelivery::Disabled);
         }
 
         let text = format!(
             "LocalHood: Гость {} не выехал. Прошло {} мин.",
             guest_name, minutes
         );
-        self.send_sms(phone, &text).await
+        self.deliver(pool, Some(complex_id), "guest_overstay", phone, &text).await
     }
 
-    async fn send_sms(&self, phone: &str, text: &str) -> AppResult<()> {
-        let url = format!(
-            "https://api.mobizon.kz/service/message/sendsmsmessage?apiKey={}",
-            self.config.sms_api_key
-        );
+    pub async fn send_family_invitation(
+        &self,
+        pool: &PgPool,
+        complex_id: Uuid,
+        phone: &str,
+        link: &str,
+    ) -> AppResult<SmsDelivery> {
+        if !self.config.sms_enabled {
+            tracing::info!("SMS disabled. Family invitation for {}: {}", phone, link);
+            return Ok(SmsDelivery::Disabled);
+        }
 
-        let params = [
-            ("recipient", phone),
-            ("text", text),
-            ("from", &self.config.sms_sender),
-        ];
-
-        let response = self
-            .client
-            .post(&url)
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Sms(e.to_string()))?;
+        let text = format!("LocalHood: вас добавили в семью жителя. Завести аккаунт: {}", link);
+        self.deliver(pool, Some(complex_id), "family_invitation", phone, &text).await
+    }
+
+    /// Повторно отправить неотправленное или недоставленное SMS из журнала.
+    /// Каждое SMS пересылается один раз; новая попытка пишется в журнал отдельно,
+    /// а при неудаче пересылку можно повторить
+    pub async fn resend(&self, pool: &PgPool, id: Uuid) -> AppResult<()> {
+        if !self.config.sms_enabled {
+            return Err(AppError::ServiceUnavailable("SMS".to_string()));
+        }
 
-        let status = response.status();
-        let body = response
-            .text()
+        let message = sqlx::query_as::<_, SmsMessage>("SELECT * FROM sms_messages WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("SMS не найдено".to_string()))?;
+
+        if message.status != SmsStatus::Failed
+            && message.delivery_status != Some(SmsDeliveryStatus::Undelivered)
+        {
+            return Err(AppError::Conflict(
+                "Повторно отправляются только неотправленные и недоставленные SMS".to_string(),
+            ));
+        }
+        let Some(text) = message.text else {
+            return Err(AppError::BadRequest(
+                "Текст SMS не сохранён: коды входа повторно не отправляются".to_string(),
+            ));
+        };
+
+        let claimed = sqlx::query(&format!(
+            "UPDATE sms_messages m SET resent_at = NOW() WHERE m.id = $1 AND {}",
+            RESENDABLE_SQL
+        ))
+        .bind(id)
+        .execute(pool)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Err(AppError::Conflict(
+                "SMS уже отправлено повторно или ждёт повтора в очереди".to_string(),
+            ));
+        }
+
+        let result = match self
+            .send_and_record(pool, message.complex_id, &message.kind, &message.phone, &text)
             .await
-            .map_err(|e| AppError::Sms(e.to_string()))?;
+        {
+            Ok(SmsDelivery::OverBudget) => Err(AppError::Conflict(
+                "Месячный лимит ОСИ на SMS исчерпан".to_string(),
+            )),
+            Ok(_) => return Ok(()),
+            Err(e) => Err(e),
+        };
+
+        // Пересылка не состоялась — SMS снова доступно для повтора
+        sqlx::query("UPDATE sms_messages SET resent_at = NULL WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        result
+    }
+
+    /// Токен из адреса обратного вызова совпадает с `SMS_CALLBACK_SECRET`
+    pub fn verify_callback_secret(&self, token: &str) -> bool {
+        !self.config.sms_callback_secret.is_empty()
+            && Sha256::digest(token.as_bytes()) == Sha256::digest(self.config.sms_callback_secret.as_bytes())
+    }
+
+    /// Записать отчёт провайдера о доставке; `false`, если сообщение не найдено
+    pub async fn record_delivery(pool: &PgPool, provider: &str, report: &SmsStatusReport) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sms_messages
+            SET delivery_status = $3, delivery_error = $4, delivery_updated_at = NOW()
+            WHERE provider = $1 AND provider_message_id = $2
+            "#,
+        )
+        .bind(provider)
+        .bind(&report.message_id)
+        .bind(report.status)
+        .bind(&report.error)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Расходы ОСИ на SMS за текущий месяц
+    pub async fn monthly_spend(pool: &PgPool, complex_id: Uuid) -> AppResult<Decimal> {
+        let (spent,): (Decimal,) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(cost), 0) FROM sms_messages
+            WHERE complex_id = $1 AND status = 'sent' AND created_at >= date_trunc('month', NOW())
+            "#,
+        )
+        .bind(complex_id)
+        .fetch_one(pool)
+        .await?;
+
+        Ok(spent)
+    }
+
+    /// Исчерпан ли месячный лимит ОСИ на SMS
+    async fn over_budget(pool: &PgPool, complex_id: Uuid) -> AppResult<bool> {
+        let cap: Option<(Option<Decimal>,)> =
+            sqlx::query_as("SELECT sms_monthly_cap FROM osi WHERE complex_id = $1")
+                .bind(complex_id)
+                .fetch_optional(pool)
+                .await?;
+
+        let Some(cap) = cap.and_then(|(cap,)| cap) else {
+            return Ok(false);
+        };
+
+        Ok(Self::monthly_spend(pool, complex_id).await? >= cap)
+    }
+
+    /// Отправить SMS из очереди; ошибка возвращается, чтобы задача повторилась позже
+    pub async fn deliver_queued(&self, pool: &PgPool, payload: &SmsDeliveryPayload) -> AppResult<()> {
+        if !self.config.sms_enabled {
+            return Ok(());
+        }
+
+        let delivery = self
+            .send_and_record(pool, payload.complex_id, &payload.kind, &payload.phone, &payload.text)
+            .await?;
+        if delivery == SmsDelivery::OverBudget {
+            tracing::info!("Queued {} SMS dropped: budget exhausted", payload.kind);
+        }
+
+        Ok(())
+    }
 
-        if !status.is_success() {
-            tracing::error!("SMS API error: {} - {}", status, body);
-            return Err(AppError::Sms(format!("SMS API error: {}", status)));
+    /// Отправить SMS; если шлюз недоступен — поставить в очередь вместо ошибки
+    async fn deliver(
+        &self,
+        pool: &PgPool,
+        complex_id: Option<Uuid>,
+        kind: &'static str,
+        phone: &str,
+        text: &str,
+    ) -> AppResult<SmsDelivery> {
+        match self.send_and_record(pool, complex_id, kind, phone, text).await {
+            Err(AppError::Sms(_) | AppError::ServiceUnavailable(_)) => {
+                let payload = SmsDeliveryPayload {
+                    complex_id,
+                    phone: phone.to_string(),
+                    kind: kind.to_string(),
+                    text: text.to_string(),
+                };
+                JobService::enqueue(pool, JobType::SmsDelivery, &payload).await?;
+                tracing::warn!("SMS gateway unavailable, {} queued for retry", kind);
+                Ok(SmsDelivery::Queued)
+            }
+            result => result,
         }
+    }
+
+    /// Отправить SMS с учётом лимита ОСИ и записать его стоимость
+    async fn send_and_record(
+        &self,
+        pool: &PgPool,
+        complex_id: Option<Uuid>,
+        kind: &str,
+        phone: &str,
+        text: &str,
+    ) -> AppResult<SmsDelivery> {
+        let segments = sms_segments(text);
+
+        if let Some(complex_id) = complex_id {
+            // SMS демо-ЖК только попадают в журнал
+            if SandboxService::is_sandbox(pool, complex_id).await? {
+                tracing::info!("Sandbox complex {}: {} SMS to {} not sent", complex_id, kind, phone);
+                self.record(
+                    pool,
+                    Some(complex_id),
+                    kind,
+                    phone,
+                    text,
+                    segments,
+                    Decimal::ZERO,
+                    "sandbox",
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+                return Ok(SmsDelivery::Sent);
+            }
 
-        let result: MobizonResponse =
-            serde_json::from_str(&body).map_err(|e| AppError::Sms(e.to_string()))?;
+            if Self::over_budget(pool, complex_id).await? {
+                tracing::info!("SMS budget exhausted for complex {}, {} not sent", complex_id, kind);
+                self.record(
+                    pool,
+                    Some(complex_id),
+                    kind,
+                    phone,
+                    text,
+                    segments,
+                    Decimal::ZERO,
+                    "suppressed",
+                    None,
+                    None,
+                    None,
+                )
+                .await?;
+                return Ok(SmsDelivery::OverBudget);
+            }
+        }
 
-        if result.code != 0 {
-            tracing::error!("SMS send failed: {}", result.message);
-            return Err(AppError::Sms(result.message));
+        let international = is_international(phone);
+        let segment_price = if international {
+            self.config.sms_international_segment_price
+        } else {
+            self.config.sms_segment_price
+        };
+        if international {
+            tracing::warn!(
+                "International {} SMS to {}: {} segment(s), estimated cost {}",
+                kind,
+                phone,
+                segments,
+                segment_price * Decimal::from(segments)
+            );
         }
 
-        tracing::info!("SMS sent to {}", phone);
+        match self.send_with_failover(phone, text).await {
+            Ok((provider, sent)) => {
+                let cost = sent.cost.unwrap_or(segment_price * Decimal::from(segments));
+                self.record(
+                    pool,
+                    complex_id,
+                    kind,
+                    phone,
+                    text,
+                    segments,
+                    cost,
+                    "sent",
+                    Some(provider),
+                    sent.message_id,
+                    None,
+                )
+                .await?;
+                Ok(SmsDelivery::Sent)
+            }
+            Err((provider, e)) => {
+                self.record(
+                    pool,
+                    complex_id,
+                    kind,
+                    phone,
+                    text,
+                    segments,
+                    Decimal::ZERO,
+                    "failed",
+                    provider,
+                    None,
+                    Some(e.to_string()),
+                )
+                .await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Отправить через первого провайдера, который справился. При неудаче всех —
+    /// имя последнего опробованного и его ошибка
+    async fn send_with_failover(
+        &self,
+        phone: &str,
+        text: &str,
+    ) -> Result<(&'static str, SentSms), (Option<&'static str>, AppError)> {
+        // За пределами +7 буквенное имя отправителя регистрируется отдельно
+        let sender = if is_international(phone) {
+            &self.config.sms_international_sender
+        } else {
+            &self.config.sms_sender
+        };
+
+        let mut failure = (
+            None,
+            AppError::Sms("Не настроен ни один SMS-провайдер".to_string()),
+        );
+        for provider in &self.providers {
+            match resilience::call(provider.service(), || provider.send(phone, text, sender)).await {
+                Ok(sent) => {
+                    tracing::info!("SMS sent to {} via {}", phone, provider.name());
+                    return Ok((provider.name(), sent));
+                }
+                Err(e) => {
+                    tracing::warn!("SMS provider {} failed: {}", provider.name(), e);
+                    failure = (Some(provider.name()), e);
+                }
+            }
+        }
+
+        Err(failure)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn record(
+        &self,
+        pool: &PgPool,
+        complex_id: Option<Uuid>,
+        kind: &str,
+        phone: &str,
+        text: &str,
+        segments: i32,
+        cost: Decimal,
+        status: &'static str,
+        provider: Option<&str>,
+        provider_message_id: Option<String>,
+        error: Option<String>,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO sms_messages
+                (complex_id, phone, kind, text, segments, cost, status, provider, provider_message_id,
+                 error, is_international)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::sms_status, $8, $9, $10, $11)
+            "#,
+        )
+        .bind(complex_id)
+        .bind(phone)
+        .bind(kind)
+        .bind((kind != AUTH_CODE_KIND).then_some(text))
+        .bind(segments)
+        .bind(cost)
+        .bind(status)
+        .bind(provider)
+        .bind(provider_message_id)
+        .bind(error)
+        .bind(is_international(phone))
+        .execute(pool)
+        .await?;
+
         Ok(())
     }
 }
+
+/// Номер вне зоны +7 тарифицируется провайдером как международный
+fn is_international(phone: &str) -> bool {
+    parse_phone(phone).is_some_and(|parsed| !parsed.is_domestic())
+}
+
+/// Количество тарифицируемых сегментов: латиница — 160/153 символа, кириллица — 70/67
+fn sms_segments(text: &str) -> i32 {
+    let chars = text.chars().count();
+    let (single, multi) = if text.is_ascii() { (160, 153) } else { (70, 67) };
+
+    if chars <= single {
+        1
+    } else {
+        chars.div_ceil(multi) as i32
+    }
+}
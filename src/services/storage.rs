@@ -0,0 +1,381 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::config::http::HttpResponse;
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::error::SdkError;
+use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+/// Путь API, по которому локальное хранилище отдаёт и принимает файлы
+pub const LOCAL_FILES_PATH: &str = "/api/v1/files/local";
+
+/// Хранилище объектов, с которым работает `FileService`
+#[axum::async_trait]
+pub trait StorageBackend: Send + Sync {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<()>;
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>>;
+
+    async fn delete(&self, key: &str) -> AppResult<()>;
+
+    /// Размер объекта; `None`, если объекта нет
+    async fn size(&self, key: &str) -> AppResult<Option<i64>>;
+
+    /// Ссылка для загрузки объекта напрямую методом PUT
+    async fn presigned_put_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> AppResult<String>;
+
+    fn public_url(&self, key: &str) -> String;
+
+    fn key_from_url(&self, url: &str) -> Option<String>;
+
+    /// Проверить, что хранилище доступно для записи
+    async fn check(&self) -> AppResult<()>;
+
+    /// Хранилище на диске этого сервера; откладывать загрузки в него бессмысленно
+    fn is_local(&self) -> bool {
+        false
+    }
+}
+
+/// Хранилище из конфигурации: `STORAGE_BACKEND=local` или S3/MinIO по умолчанию
+pub fn from_config(config: &Config) -> Box<dyn StorageBackend> {
+    match config.storage_backend.as_str() {
+        "local" => Box::new(LocalStorage::new(config)),
+        _ => Box::new(S3Storage::new(config)),
+    }
+}
+
+/// S3-совместимое хранилище (MinIO)
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+    public_url: Option<String>,
+}
+
+impl S3Storage {
+    pub fn new(config: &Config) -> Self {
+        let credentials = Credentials::new(
+            &config.minio_access_key,
+            &config.minio_secret_key,
+            None,
+            None,
+            "localhood",
+        );
+
+        let s3_config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .credentials_provider(credentials)
+            .region(Region::new("us-east-1"))
+            .endpoint_url(&config.minio_endpoint)
+            .force_path_style(true)
+            .build();
+
+        Self {
+            client: Client::from_conf(s3_config),
+            bucket: config.minio_bucket.clone(),
+            public_url: config.minio_public_url.clone(),
+        }
+    }
+}
+
+#[axum::async_trait]
+impl StorageBackend for S3Storage {
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(s3_error)?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        let object = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+
+        Ok(object
+            .body
+            .collect()
+            .await
+            .map_err(|e| AppError::File(e.to_string()))?
+            .into_bytes()
+            .to_vec())
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(s3_error)?;
+
+        Ok(())
+    }
+
+    async fn size(&self, key: &str) -> AppResult<Option<i64>> {
+        match self.client.head_object().bucket(&self.bucket).key(key).send().await {
+            Ok(output) => Ok(Some(output.content_length().unwrap_or(0))),
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_not_found()) => Ok(None),
+            Err(e) => Err(s3_error(e)),
+        }
+    }
+
+    async fn presigned_put_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> AppResult<String> {
+        let presigning = PresigningConfig::expires_in(expires_in)
+            .map_err(|e| AppError::File(e.to_string()))?;
+
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .presigned(presigning)
+            .await
+            .map_err(|e| AppError::File(e.to_string()))?;
+
+        Ok(request.uri().to_string())
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        match &self.public_url {
+            Some(base_url) => format!("{}/{}/{}", base_url, self.bucket, key),
+            None => format!("/{}/{}", self.bucket, key),
+        }
+    }
+
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        let prefix = format!("/{}/", self.bucket);
+        url.find(&prefix).map(|pos| url[pos + prefix.len()..].to_string())
+    }
+
+    async fn check(&self) -> AppResult<()> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket)
+            .send()
+            .await
+            .map_err(|e| AppError::StorageUnavailable(format!("бакет {}: {}", self.bucket, e)))?;
+
+        Ok(())
+    }
+}
+
+/// Сетевые сбои и 5xx от S3 — хранилище недоступно; остальное — ошибка конкретного файла
+fn s3_error<E>(e: SdkError<E, HttpResponse>) -> AppError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let unavailable = match &e {
+        SdkError::DispatchFailure(_) | SdkError::TimeoutError(_) => true,
+        SdkError::ResponseError(r) => r.raw().status().is_server_error(),
+        SdkError::ServiceError(r) => r.raw().status().is_server_error(),
+        _ => false,
+    };
+
+    if unavailable {
+        AppError::StorageUnavailable(e.to_string())
+    } else {
+        AppError::File(e.to_string())
+    }
+}
+
+/// Файлы на диске сервера для установок без S3.
+/// Раздаются и принимаются через API по пути `LOCAL_FILES_PATH`.
+pub struct LocalStorage {
+    root: PathBuf,
+    base_url: String,
+    signing_secret: String,
+}
+
+impl LocalStorage {
+    pub fn new(config: &Config) -> Self {
+        Self::with_root(config, &config.local_storage_path)
+    }
+
+    /// Локальное хранилище в другом каталоге, например для отложенных загрузок
+    pub fn with_root(config: &Config, root: &str) -> Self {
+        Self {
+            root: PathBuf::from(root),
+            base_url: format!("{}{}", config.public_url.trim_end_matches('/'), LOCAL_FILES_PATH),
+            signing_secret: config.jwt_secret.clone(),
+        }
+    }
+
+    /// Путь к файлу; ключи с `..` и абсолютные пути отклоняются
+    fn path(&self, key: &str) -> AppResult<PathBuf> {
+        let relative = Path::new(key);
+        let safe = !key.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)));
+
+        if !safe {
+            return Err(AppError::BadRequest("Недопустимый путь файла".to_string()));
+        }
+
+        Ok(self.root.join(relative))
+    }
+
+    /// HMAC-SHA256 от `key \n content_type \n expires` на секрете сервера
+    fn upload_mac(&self, key: &str, content_type: &str, expires: i64) -> Option<Hmac<Sha256>> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_secret.as_bytes()).ok()?;
+        mac.update(format!("{}\n{}\n{}", key, content_type, expires).as_bytes());
+        Some(mac)
+    }
+
+    /// Проверить подпись и срок действия ссылки на загрузку
+    pub fn verify_upload(&self, key: &str, content_type: &str, expires: i64, signature: &str) -> bool {
+        if expires < chrono::Utc::now().timestamp() {
+            return false;
+        }
+
+        let Ok(expected) = hex::decode(signature.trim()) else {
+            return false;
+        };
+
+        self.upload_mac(key, content_type, expires)
+            .is_some_and(|mac| mac.verify_slice(&expected).is_ok())
+    }
+}
+
+#[axum::async_trait]
+impl StorageBackend for LocalStorage {
+    async fn put(&self, key: &str, _content_type: &str, data: Vec<u8>) -> AppResult<()> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::File(e.to_string()))?;
+        }
+
+        tokio::fs::write(&path, data)
+            .await
+            .map_err(|e| AppError::File(e.to_string()))
+    }
+
+    async fn get(&self, key: &str) -> AppResult<Vec<u8>> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(AppError::NotFound("Файл не найден".to_string()))
+            }
+            Err(e) => Err(AppError::File(e.to_string())),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> AppResult<()> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::File(e.to_string())),
+        }
+    }
+
+    async fn size(&self, key: &str) -> AppResult<Option<i64>> {
+        match tokio::fs::metadata(self.path(key)?).await {
+            Ok(meta) => Ok(Some(meta.len() as i64)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(AppError::File(e.to_string())),
+        }
+    }
+
+    async fn presigned_put_url(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires_in: Duration,
+    ) -> AppResult<String> {
+        self.path(key)?;
+
+        let expires = chrono::Utc::now().timestamp() + expires_in.as_secs() as i64;
+        let signature = self
+            .upload_mac(key, content_type, expires)
+            .map(|mac| hex::encode(mac.finalize().into_bytes()))
+            .ok_or_else(|| AppError::Internal("Не удалось подписать ссылку".to_string()))?;
+
+        Ok(format!(
+            "{}?expires={}&signature={}",
+            self.public_url(key),
+            expires,
+            signature
+        ))
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+
+    fn key_from_url(&self, url: &str) -> Option<String> {
+        let prefix = format!("{}/", LOCAL_FILES_PATH);
+        url.find(&prefix)
+            .map(|pos| url[pos + prefix.len()..].split('?').next().unwrap_or_default().to_string())
+    }
+
+    async fn check(&self) -> AppResult<()> {
+        tokio::fs::create_dir_all(&self.root)
+            .await
+            .map_err(|e| AppError::StorageUnavailable(format!("{}: {}", self.root.display(), e)))?;
+
+        let metadata = tokio::fs::metadata(&self.root)
+            .await
+            .map_err(|e| AppError::StorageUnavailable(format!("{}: {}", self.root.display(), e)))?;
+
+        if metadata.permissions().readonly() {
+            return Err(AppError::StorageUnavailable(format!(
+                "{}: только для чтения",
+                self.root.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
+}
+
+/// Content-Type по расширению для раздачи локальных файлов
+pub fn content_type_for_key(key: &str) -> &'static str {
+    let extension = key.rsplit('.').next().unwrap_or_default().to_ascii_lowercase();
+    match extension.as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,235 @@
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::Sha256;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Путь API, через который бэкенд ретранслирует HLS камер
+pub const STREAMS_PATH: &str = "/api/v1/security/streams";
+
+/// Имя плейлиста, под которым клиенту отдаётся основной поток камеры
+pub const PLAYLIST_NAME: &str = "index.m3u8";
+
+/// Время жизни токена воспроизведения; по истечении клиент запрашивает новый
+pub const PLAYBACK_TOKEN_TTL_SECS: i64 = 600;
+
+/// Таймаут запроса к камере
+const UPSTREAM_TIMEOUT_SECS: u64 = 15;
+
+/// Ответ камеры, который отдаётся клиенту
+pub struct StreamChunk {
+    pub content_type: String,
+    pub body: Vec<u8>,
+}
+
+/// Прокси HLS-потоков камер: клиент видит только адреса бэкенда с подписанным токеном
+pub struct StreamService {
+    client: reqwest::Client,
+    signing_secret: String,
+    base_url: String,
+}
+
+impl StreamService {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(UPSTREAM_TIMEOUT_SECS))
+                .build()
+                .unwrap_or_default(),
+            signing_secret: config.jwt_secret.clone(),
+            base_url: format!("{}{}", config.public_url.trim_end_matches('/'), STREAMS_PATH),
+        }
+    }
+
+    /// Выдать токен воспроизведения камеры: `<expires>.<подпись>`
+    pub fn issue_token(&self, camera_id: Uuid) -> AppResult<(String, i64)> {
+        let expires = chrono::Utc::now().timestamp() + PLAYBACK_TOKEN_TTL_SECS;
+        let signature = self
+            .token_mac(camera_id, expires)
+            .map(|mac| hex::encode(mac.finalize().into_bytes()))
+            .ok_or_else(|| AppError::Internal("Не удалось подписать токен".to_string()))?;
+
+        Ok((format!("{}.{}", expires, signature), expires))
+    }
+
+    /// Проверить токен воспроизведения для камеры
+    pub fn verify_token(&self, camera_id: Uuid, token: &str) -> AppResult<()> {
+        let (expires, signature) = token.split_once('.').ok_or(AppError::Unauthorized)?;
+        let expires: i64 = expires.parse().map_err(|_| AppError::Unauthorized)?;
+        if expires < chrono::Utc::now().timestamp() {
+            return Err(AppError::Unauthorized);
+        }
+
+        let expected = hex::decode(signature).map_err(|_| AppError::Unauthorized)?;
+        let valid = self
+            .token_mac(camera_id, expires)
+            .is_some_and(|mac| mac.verify_slice(&expected).is_ok());
+
+        if !valid {
+            return Err(AppError::Unauthorized);
+        }
+
+        Ok(())
+    }
+
+    /// Адрес плейлиста камеры на бэкенде
+    pub fn playlist_url(&self, camera_id: Uuid, token: &str) -> String {
+        format!("{}/{}/{}?token={}", self.base_url, camera_id, PLAYLIST_NAME, token)
+    }
+
+    /// Получить плейлист или сегмент камеры. `path` — путь относительно плейлиста камеры.
+    pub async fn fetch(
+        &self,
+        camera_id: Uuid,
+        stream_url: &str,
+        path: &str,
+        token: &str,
+    ) -> AppResult<StreamChunk> {
+        let playlist = hls_source(stream_url)
+            .ok_or_else(|| AppError::BadRequest("Камера не отдаёт HLS-поток".to_string()))?;
+
+        let upstream = if path == PLAYLIST_NAME {
+            playlist.clone()
+        } else {
+            resolve_within(&playlist, path)
+                .ok_or_else(|| AppError::NotFound("Сегмент не найден".to_string()))?
+        };
+
+        let response = self
+            .client
+            .get(upstream.clone())
+            .send()
+            .await
+            .map_err(|e| camera_unavailable(camera_id, e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(AppError::NotFound("Сегмент не найден".to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(AppError::Stream(format!(
+                "Камера вернула {}",
+                response.status()
+            )));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| camera_unavailable(camera_id, e))?
+            .to_vec();
+
+        let is_playlist = upstream.path().ends_with(".m3u8") || content_type.contains("mpegurl");
+        if !is_playlist {
+            return Ok(StreamChunk { content_type, body });
+        }
+
+        let text = String::from_utf8_lossy(&body);
+        let proxy_base = format!("{}/{}/", self.base_url, camera_id);
+        Ok(StreamChunk {
+            content_type: "application/vnd.apple.mpegurl".to_string(),
+            body: rewrite_playlist(&text, &playlist, &upstream, &proxy_base, token).into_bytes(),
+        })
+    }
+
+    /// HMAC-SHA256 от `camera_id \n expires` на секрете сервера
+    fn token_mac(&self, camera_id: Uuid, expires: i64) -> Option<Hmac<Sha256>> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_secret.as_bytes()).ok()?;
+        mac.update(format!("{}\n{}", camera_id, expires).as_bytes());
+        Some(mac)
+    }
+}
+
+/// Ошибка запроса к камере; адрес может содержать учётные данные и не попадает ни в ответ, ни в лог
+fn camera_unavailable(camera_id: Uuid, e: reqwest::Error) -> AppError {
+    tracing::warn!("Camera {} stream request failed: {}", camera_id, e.without_url());
+    AppError::Stream("Камера недоступна".to_string())
+}
+
+/// Адрес HLS-плейлиста камеры; RTSP и прочие протоколы через прокси не отдаются
+pub fn hls_source(stream_url: &str) -> Option<Url> {
+    Url::parse(stream_url)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Каталог, в котором лежит плейлист камеры
+fn base_dir(playlist: &Url) -> Option<Url> {
+    playlist.join("./").ok()
+}
+
+/// Разрешить путь относительно плейлиста, не выходя за его каталог и хост
+fn resolve_within(playlist: &Url, path: &str) -> Option<Url> {
+    let base = base_dir(playlist)?;
+    let resolved = base.join(path).ok()?;
+    resolved.as_str().starts_with(base.as_str()).then_some(resolved)
+}
+
+/// Путь ресурса относительно каталога плейлиста камеры, без параметров запроса
+fn relative_path(playlist: &Url, resource: &Url) -> Option<String> {
+    let base = base_dir(playlist)?;
+    let mut resource = resource.clone();
+    resource.set_query(None);
+    resource.set_fragment(None);
+    resource.as_str().strip_prefix(base.as_str()).map(str::to_string)
+}
+
+/// Заменить ссылки в плейлисте на абсолютные адреса прокси.
+/// Ссылки за пределами каталога камеры удаляются.
+fn rewrite_playlist(
+    text: &str,
+    playlist: &Url,
+    current: &Url,
+    proxy_base: &str,
+    token: &str,
+) -> String {
+    let proxied = |uri: &str| -> Option<String> {
+        let resource = current.join(uri).ok()?;
+        let path = relative_path(playlist, &resource)?;
+        Some(format!("{}{}?token={}", proxy_base, path, token))
+    };
+
+    let mut lines = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            lines.push(line.to_string());
+        } else if trimmed.starts_with('#') {
+            lines.push(rewrite_uri_attribute(trimmed, &proxied));
+        } else if let Some(uri) = proxied(trimmed) {
+            lines.push(uri);
+        } else {
+            // Сегмент с чужого хоста: отдавать ссылку нельзя, убираем вместе с его EXTINF
+            if lines.last().is_some_and(|l: &String| l.starts_with("#EXTINF")) {
+                lines.pop();
+            }
+        }
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Переписать атрибут `URI="..."` в тегах вроде `#EXT-X-KEY` и `#EXT-X-MAP`
+fn rewrite_uri_attribute(line: &str, proxied: &dyn Fn(&str) -> Option<String>) -> String {
+    const ATTRIBUTE: &str = "URI=\"";
+
+    let Some(start) = line.find(ATTRIBUTE).map(|pos| pos + ATTRIBUTE.len()) else {
+        return line.to_string();
+    };
+    let Some(len) = line[start..].find('"') else {
+        return line.to_string();
+    };
+
+    let uri = proxied(&line[start..start + len]).unwrap_or_default();
+    format!("{}{}{}", &line[..start], uri, &line[start + len..])
+}
@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateSurveyRequest, SubmitSurveyRequest, Survey, SurveyBuildingResult, SurveyOptionResult,
+    SurveyQuestion, SurveyQuestionResult, SurveyQuestionType, SurveyResults, SurveyScaleResult,
+};
+use crate::utils::csv_row;
+
+/// Сколько вопросов может быть в опросе
+const SURVEY_MAX_QUESTIONS: usize = 50;
+
+/// Сколько шагов может быть у шкалы
+const SURVEY_MAX_SCALE_STEPS: i32 = 10;
+
+/// Сколько свободных ответов возвращать в итогах; остальные — в выгрузке
+const SURVEY_TEXT_ANSWERS_LIMIT: usize = 100;
+
+const SURVEY_TEXT_MAX_LEN: usize = 2000;
+
+/// Ответ из базы вместе с корпусом квартиры ответившего
+#[derive(sqlx::FromRow)]
+struct AnswerRow {
+    response_id: Uuid,
+    question_id: Uuid,
+    option_indexes: Vec<i32>,
+    text_value: Option<String>,
+    scale_value: Option<i32>,
+    building: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Опросы жителей: вопросы, ответы и сводные итоги
+pub struct SurveyService;
+
+impl SurveyService {
+    /// Создать опрос с вопросами; он сразу открыт для ответов
+    pub async fn create(
+        pool: &PgPool,
+        created_by: Uuid,
+        payload: &CreateSurveyRequest,
+    ) -> AppResult<(Survey, Vec<SurveyQuestion>)> {
+        validate_survey(payload)?;
+
+        let mut tx = pool.begin().await?;
+
+        let survey = sqlx::query_as::<_, Survey>(
+            r#"
+            INSERT INTO surveys (complex_id, title, description, ends_at, created_by)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(payload.complex_id)
+        .bind(payload.title.trim())
+        .bind(&payload.description)
+        .bind(payload.ends_at)
+        .bind(created_by)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let mut questions = Vec::with_capacity(payload.questions.len());
+        for (position, question) in payload.questions.iter().enumerate() {
+            let options: Vec<String> = question.options.iter().map(|option| option.trim().to_string()).collect();
+            let created = sqlx::query_as::<_, SurveyQuestion>(
+                r#"
+                INSERT INTO survey_questions (
+                    survey_id, position, question_type, text, options,
+                    allow_multiple, scale_min, scale_max, is_required
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING *
+                "#,
+            )
+            .bind(survey.id)
+            .bind(position as i32)
+            .bind(question.question_type)
+            .bind(question.text.trim())
+            .bind(&options)
+            .bind(question.allow_multiple && question.question_type == SurveyQuestionType::Choice)
+            .bind(question.scale_min.unwrap_or(1))
+            .bind(question.scale_max.unwrap_or(5))
+            .bind(question.is_required.unwrap_or(true))
+            .fetch_one(&mut *tx)
+            .await?;
+            questions.push(created);
+        }
+
+        tx.commit().await?;
+
+        Ok((survey, questions))
+    }
+
+    pub async fn questions(pool: &PgPool, survey_id: Uuid) -> AppResult<Vec<SurveyQuestion>> {
+        let questions = sqlx::query_as::<_, SurveyQuestion>(
+            "SELECT * FROM survey_questions WHERE survey_id = $1 ORDER BY position",
+        )
+        .bind(survey_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(questions)
+    }
+
+    /// Записать ответы жителя; повторно отвечать нельзя
+    pub async fn submit(
+        pool: &PgPool,
+        survey: &Survey,
+        user_id: Uuid,
+        payload: &SubmitSurveyRequest,
+    ) -> AppResult<()> {
+        if !survey.is_open() {
+            return Err(AppError::BadRequest("Опрос завершён".to_string()));
+        }
+
+        let questions = Self::questions(pool, survey.id).await?;
+        validate_answers(&questions, payload)?;
+
+        let apartment: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT a.id FROM apartments a
+            WHERE a.complex_id = $1
+              AND (a.owner_id = $2 OR a.resident_id = $2 OR EXISTS (
+                  SELECT 1 FROM family_members f WHERE f.apartment_id = a.id AND f.user_id = $2
+              ))
+            ORDER BY a.owner_id = $2 DESC, a.created_at
+            LIMIT 1
+            "#,
+        )
+        .bind(survey.complex_id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let mut tx = pool.begin().await?;
+
+        let response: Option<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO survey_responses (survey_id, user_id, apartment_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (survey_id, user_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(survey.id)
+        .bind(user_id)
+        .bind(apartment.map(|(id,)| id))
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (response_id,) = response.ok_or_else(|| AppError::Conflict("Вы уже ответили на опрос".to_string()))?;
+
+        for answer in &payload.answers {
+            sqlx::query(
+                r#"
+                INSERT INTO survey_answers (response_id, question_id, option_indexes, text_value, scale_value)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+            )
+            .bind(response_id)
+            .bind(answer.question_id)
+            .bind(&answer.option_indexes)
+            .bind(answer.text.as_deref().map(str::trim))
+            .bind(answer.scale_value)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("UPDATE surveys SET responses_count = responses_count + 1 WHERE id = $1")
+            .bind(survey.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Закрыть опрос досрочно
+    pub async fn close(pool: &PgPool, survey_id: Uuid) -> AppResult<Survey> {
+        sqlx::query_as::<_, Survey>(
+            r#"
+            UPDATE surveys SET status = 'closed', closed_at = NOW(), updated_at = NOW()
+            WHERE id = $1 AND status = 'active'
+            RETURNING *
+            "#,
+        )
+        .bind(survey_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Опрос уже закрыт".to_string()))
+    }
+
+    /// Итоги по каждому вопросу с разбивкой по корпусам
+    pub async fn results(pool: &PgPool, survey: &Survey) -> AppResult<SurveyResults> {
+        let questions = Self::questions(pool, survey.id).await?;
+        let answers = Self::answers(pool, survey.id).await?;
+
+        let questions = questions
+            .iter()
+            .map(|question| {
+                let answers: Vec<&AnswerRow> = answers.iter().filter(|a| a.question_id == question.id).collect();
+                question_result(question, &answers)
+            })
+            .collect();
+
+        Ok(SurveyResults {
+            survey_id: survey.id,
+            responses_count: survey.responses_count,
+            questions,
+        })
+    }
+
+    /// Все ответы в CSV: строка на ответившего, столбец на вопрос
+    pub async fn export_csv(pool: &PgPool, survey: &Survey) -> AppResult<String> {
+        let questions = Self::questions(pool, survey.id).await?;
+        let answers = Self::answers(pool, survey.id).await?;
+
+        let mut header = vec!["Дата".to_string(), "Корпус".to_string()];
+        header.extend(questions.iter().map(|question| question.text.clone()));
+        let mut csv = csv_row(&header);
+
+        // Ответы уже упорядочены по времени ответа, порядок строк сохраняем
+        let mut rows: Vec<(Uuid, Vec<String>)> = Vec::new();
+        for answer in &answers {
+            let index = match rows.iter().position(|(id, _)| *id == answer.response_id) {
+                Some(index) => index,
+                None => {
+                    let mut row = vec![
+                        answer.created_at.format("%d.%m.%Y %H:%M").to_string(),
+                        answer.building.clone().unwrap_or_default(),
+                    ];
+                    row.resize(questions.len() + 2, String::new());
+                    rows.push((answer.response_id, row));
+                    rows.len() - 1
+                }
+            };
+            if let Some(position) = questions.iter().position(|q| q.id == answer.question_id) {
+                rows[index].1[position + 2] = answer_text(&questions[position], answer);
+            }
+        }
+
+        for (_, row) in rows {
+            csv.push_str(&csv_row(&row));
+        }
+
+        Ok(csv)
+    }
+
+    async fn answers(pool: &PgPool, survey_id: Uuid) -> AppResult<Vec<AnswerRow>> {
+        let answers = sqlx::query_as::<_, AnswerRow>(
+            r#"
+            SELECT a.response_id, a.question_id, a.option_indexes, a.text_value, a.scale_value,
+                   ap.building, r.created_at
+            FROM survey_answers a
+            JOIN survey_responses r ON r.id = a.response_id
+            LEFT JOIN apartments ap ON ap.id = r.apartment_id
+            WHERE r.survey_id = $1
+            ORDER BY r.created_at, r.id
+            "#,
+        )
+        .bind(survey_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(answers)
+    }
+}
+
+fn validate_survey(payload: &CreateSurveyRequest) -> AppResult<()> {
+    if payload.title.trim().is_empty() {
+        return Err(AppError::Validation("Укажите название опроса".to_string()));
+    }
+    if payload.questions.is_empty() || payload.questions.len() > SURVEY_MAX_QUESTIONS {
+        return Err(AppError::Validation(format!(
+            "В опросе должно быть от 1 до {} вопросов",
+            SURVEY_MAX_QUESTIONS
+        )));
+    }
+    if payload.ends_at.is_some_and(|ends_at| ends_at <= Utc::now()) {
+        return Err(AppError::Validation("Дата окончания уже прошла".to_string()));
+    }
+
+    for (index, question) in payload.questions.iter().enumerate() {
+        let number = index + 1;
+        if question.text.trim().is_empty() {
+            return Err(AppError::Validation(format!("Вопрос {}: укажите текст", number)));
+        }
+        match question.question_type {
+            SurveyQuestionType::Choice => {
+                if question.options.len() < 2 || question.options.iter().any(|o| o.trim().is_empty()) {
+                    return Err(AppError::Validation(format!(
+                        "Вопрос {}: нужно минимум 2 непустых варианта",
+                        number
+                    )));
+                }
+            }
+            SurveyQuestionType::Scale => {
+                let (min, max) = (question.scale_min.unwrap_or(1), question.scale_max.unwrap_or(5));
+                if min >= max || max - min > SURVEY_MAX_SCALE_STEPS {
+                    return Err(AppError::Validation(format!("Вопрос {}: неверные границы шкалы", number)));
+                }
+            }
+            SurveyQuestionType::Text => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_answers(questions: &[SurveyQuestion], payload: &SubmitSurveyRequest) -> AppResult<()> {
+    let mut answered: HashSet<Uuid> = HashSet::new();
+
+    for answer in &payload.answers {
+        let question = questions
+            .iter()
+            .find(|question| question.id == answer.question_id)
+            .ok_or_else(|| AppError::Validation("Вопрос не относится к опросу".to_string()))?;
+        if !answered.insert(question.id) {
+            return Err(AppError::Validation("Ответ на вопрос указан дважды".to_string()));
+        }
+
+        let number = question.position + 1;
+        let valid = match question.question_type {
+            SurveyQuestionType::Choice => {
+                let mut indexes = answer.option_indexes.clone();
+                indexes.sort_unstable();
+                indexes.dedup();
+                !indexes.is_empty()
+                    && indexes.len() == answer.option_indexes.len()
+                    && (question.allow_multiple || indexes.len() == 1)
+                    && indexes.iter().all(|&i| i >= 0 && (i as usize) < question.options.len())
+            }
+            SurveyQuestionType::Text => answer
+                .text
+                .as_deref()
+                .is_some_and(|text| !text.trim().is_empty() && text.chars().count() <= SURVEY_TEXT_MAX_LEN),
+            SurveyQuestionType::Scale => answer
+                .scale_value
+                .is_some_and(|value| (question.scale_min..=question.scale_max).contains(&value)),
+        };
+        if !valid {
+            return Err(AppError::Validation(format!("Вопрос {}: неверный ответ", number)));
+        }
+    }
+
+    if let Some(missing) = questions
+        .iter()
+        .find(|question| question.is_required && !answered.contains(&question.id))
+    {
+        return Err(AppError::Validation(format!(
+            "Вопрос {}: ответ обязателен",
+            missing.position + 1
+        )));
+    }
+
+    Ok(())
+}
+
+fn question_result(question: &SurveyQuestion, answers: &[&AnswerRow]) -> SurveyQuestionResult {
+    let answers_count = answers.len() as i64;
+
+    let options = question
+        .options
+        .iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let count = answers
+                .iter()
+                .filter(|a| a.option_indexes.contains(&(index as i32)))
+                .count() as i64;
+            SurveyOptionResult {
+                index: index as i32,
+                text: text.clone(),
+                count,
+                percentage: percentage(count, answers_count),
+            }
+        })
+        .collect();
+
+    let scale = if question.question_type == SurveyQuestionType::Scale {
+        (question.scale_min..=question.scale_max)
+            .map(|value| SurveyScaleResult {
+                value,
+                count: answers.iter().filter(|a| a.scale_value == Some(value)).count() as i64,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let text_answers = answers
+        .iter()
+        .rev()
+        .filter_map(|a| a.text_value.clone())
+        .take(SURVEY_TEXT_ANSWERS_LIMIT)
+        .collect();
+
+    // BTreeMap даёт стабильный порядок корпусов; ответившие без корпуса идут первыми
+    let mut by_building: BTreeMap<Option<String>, Vec<&AnswerRow>> = BTreeMap::new();
+    for answer in answers {
+        by_building.entry(answer.building.clone()).or_default().push(answer);
+    }
+    let buildings = by_building
+        .into_iter()
+        .map(|(building, answers)| SurveyBuildingResult {
+            building,
+            answers_count: answers.len() as i64,
+            option_counts: (0..question.options.len() as i32)
+                .map(|index| answers.iter().filter(|a| a.option_indexes.contains(&index)).count() as i64)
+                .collect(),
+            scale_average: scale_average(&answers),
+        })
+        .collect();
+
+    SurveyQuestionResult {
+        question_id: question.id,
+        text: question.text.clone(),
+        question_type: question.question_type,
+        answers_count,
+        options,
+        scale,
+        scale_average: scale_average(answers),
+        text_answers,
+        buildings,
+    }
+}
+
+fn scale_average(answers: &[&AnswerRow]) -> Option<f64> {
+    let values: Vec<i32> = answers.iter().filter_map(|a| a.scale_value).collect();
+    if values.is_empty() {
+        return None;
+    }
+    let average = values.iter().map(|&v| f64::from(v)).sum::<f64>() / values.len() as f64;
+    Some((average * 100.0).round() / 100.0)
+}
+
+fn percentage(count: i64, total: i64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (count as f64 * 10000.0 / total as f64).round() / 100.0
+}
+
+fn answer_text(question: &SurveyQuestion, answer: &AnswerRow) -> String {
+    match question.question_type {
+        SurveyQuestionType::Choice => answer
+            .option_indexes
+            .iter()
+            .filter_map(|&index| question.options.get(index as usize).cloned())
+            .collect::<Vec<_>>()
+            .join("; "),
+        SurveyQuestionType::Text => answer.text_value.clone().unwrap_or_default(),
+        SurveyQuestionType::Scale => answer.scale_value.map(|v| v.to_string()).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,301 @@
+use chrono::{Duration, Utc};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    JobType, NotificationChannel, NotificationType, TelegramLinkCode, TelegramLinkStatus,
+    TelegramNotificationPayload, TelegramUpdate,
+};
+use crate::services::{JobService, NotificationService};
+
+/// Сколько действует код привязки из deep-link
+const LINK_CODE_TTL_MINUTES: i64 = 15;
+
+pub struct TelegramService {
+    config: Config,
+    client: reqwest::Client,
+}
+
+impl TelegramService {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Выпустить код привязки; прежние коды пользователя перестают действовать
+    pub async fn create_link_code(&self, pool: &PgPool, user_id: Uuid) -> AppResult<TelegramLinkCode> {
+        if !self.config.telegram_enabled {
+            return Err(AppError::ServiceUnavailable("Telegram-бот".to_string()));
+        }
+
+        let code = Uuid::new_v4().simple().to_string();
+        let expires_at = Utc::now() + Duration::minutes(LINK_CODE_TTL_MINUTES);
+
+        let mut tx = pool.begin().await?;
+
+        sqlx::query("DELETE FROM telegram_link_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO telegram_link_codes (code_hash, user_id, expires_at) VALUES ($1, $2, $3)",
+        )
+        .bind(code_hash(&code))
+        .bind(user_id)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(TelegramLinkCode {
+            url: format!(
+                "https://t.me/{}?start={}",
+                self.config.telegram_bot_username, code
+            ),
+            code,
+            expires_at,
+        })
+    }
+
+    pub async fn status(pool: &PgPool, user_id: Uuid) -> AppResult<TelegramLinkStatus> {
+        let link: Option<(Option<String>, chrono::DateTime<Utc>)> =
+            sqlx::query_as("SELECT username, linked_at FROM telegram_links WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(pool)
+                .await?;
+
+        Ok(match link {
+            Some((username, linked_at)) => TelegramLinkStatus {
+                linked: true,
+                username,
+                linked_at: Some(linked_at),
+            },
+            None => TelegramLinkStatus {
+                linked: false,
+                username: None,
+                linked_at: None,
+            },
+        })
+    }
+
+    pub async fn unlink(pool: &PgPool, user_id: Uuid) -> AppResult<bool> {
+        let result = sqlx::query("DELETE FROM telegram_links WHERE user_id = $1")
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Секрет из заголовка вебхука совпадает с заданным в `setWebhook`
+    pub fn verify_webhook_secret(&self, secret: &str) -> bool {
+        !self.config.telegram_webhook_secret.is_empty()
+            && Sha256::digest(secret.as_bytes())
+                == Sha256::digest(self.config.telegram_webhook_secret.as_bytes())
+    }
+
+    /// Обработать сообщение боту: `/start <код>` привязывает чат, `/stop` отвязывает
+    pub async fn handle_update(&self, pool: &PgPool, update: &TelegramUpdate) -> AppResult<()> {
+        let Some(message) = &update.message else {
+            return Ok(());
+        };
+        let chat_id = message.chat.id;
+        let text = message.text.as_deref().unwrap_or_default().trim();
+
+        let reply = if let Some(code) = text.strip_prefix("/start") {
+            let username = message.from.as_ref().and_then(|u| u.username.as_deref());
+            if Self::link(pool, code.trim(), chat_id, username).await? {
+                "Готово! Уведомления LocalHood будут приходить в этот чат. Отключить — /stop"
+            } else {
+                "Ссылка устарела. Откройте настройки уведомлений в приложении и подключите Telegram заново"
+            }
+        } else if text == "/stop" {
+            sqlx::query("DELETE FROM telegram_links WHERE chat_id = $1")
+                .bind(chat_id)
+                .execute(pool)
+                .await?;
+            "Уведомления отключены. Подключить снова можно в настройках приложения"
+        } else {
+            "Бот присылает уведомления LocalHood. Подключить его можно в настройках уведомлений приложения"
+        };
+
+        // Ответ в чат не обязателен: ошибку отправки только пишем в лог
+        if let Err(e) = self.send_message(chat_id, reply).await {
+            tracing::warn!("Telegram reply to update {} failed: {}", update.update_id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Погасить код и привязать чат; чат, привязанный к другому аккаунту, переходит к новому
+    async fn link(pool: &PgPool, code: &str, chat_id: i64, username: Option<&str>) -> AppResult<bool> {
+        let mut tx = pool.begin().await?;
+
+        let user_id: Option<(Uuid,)> = sqlx::query_as(
+            "DELETE FROM telegram_link_codes WHERE code_hash = $1 AND expires_at > NOW() RETURNING user_id",
+        )
+        .bind(code_hash(code))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id,)) = user_id else {
+            return Ok(false);
+        };
+
+        sqlx::query("DELETE FROM telegram_links WHERE chat_id = $1 AND user_id <> $2")
+            .bind(chat_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO telegram_links (user_id, chat_id, username)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE SET
+                chat_id = EXCLUDED.chat_id,
+                username = EXCLUDED.username,
+                linked_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(chat_id)
+        .bind(username)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!("Telegram chat linked for user {}", user_id);
+        Ok(true)
+    }
+
+    /// Поставить сообщение в очередь для привязавших Telegram и не отключивших этот тип
+    pub async fn enqueue(
+        pool: &PgPool,
+        user_ids: &[Uuid],
+        notification_type: NotificationType,
+        text: String,
+    ) -> AppResult<()> {
+        Self::enqueue_with(&mut *pool.acquire().await?, user_ids, notification_type, text).await
+    }
+
+    /// То же на заданном соединении — например, в транзакции, где создаётся само событие
+    pub async fn enqueue_with(
+        conn: &mut PgConnection,
+        user_ids: &[Uuid],
+        notification_type: NotificationType,
+        text: String,
+    ) -> AppResult<()> {
+        let user_ids = NotificationService::recipients(
+            &mut *conn,
+            user_ids,
+            notification_type,
+            NotificationChannel::Telegram,
+        )
+        .await?;
+
+        let linked: Vec<(Uuid,)> =
+            sqlx::query_as("SELECT user_id FROM telegram_links WHERE user_id = ANY($1)")
+                .bind(&user_ids)
+                .fetch_all(&mut *conn)
+                .await?;
+        if linked.is_empty() {
+            return Ok(());
+        }
+
+        JobService::enqueue(
+            &mut *conn,
+            JobType::TelegramNotification,
+            &TelegramNotificationPayload {
+                user_ids: linked.into_iter().map(|(id,)| id).collect(),
+                text,
+            },
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// Отправить сообщение из очереди. Ошибка возвращается, только если не ушло
+    /// ни одно сообщение; чат, заблокировавший бота, отвязывается
+    pub async fn deliver_queued(&self, pool: &PgPool, payload: &TelegramNotificationPayload) -> AppResult<()> {
+        if !self.config.telegram_enabled {
+            return Ok(());
+        }
+
+        let chats: Vec<(Uuid, i64)> =
+            sqlx::query_as("SELECT user_id, chat_id FROM telegram_links WHERE user_id = ANY($1)")
+                .bind(&payload.user_ids)
+                .fetch_all(pool)
+                .await?;
+
+        let mut last_error = None;
+        let mut sent = 0;
+
+        for (user_id, chat_id) in chats {
+            match self.send_message(chat_id, &payload.text).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {
+                    tracing::info!("Telegram chat of user {} blocked the bot, unlinking", user_id);
+                    sqlx::query("DELETE FROM telegram_links WHERE user_id = $1")
+                        .bind(user_id)
+                        .execute(pool)
+                        .await?;
+                }
+                Err(e) => {
+                    tracing::warn!("Telegram message to user {} failed: {}", user_id, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if sent == 0 => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// `false`, если бот заблокирован или чат удалён
+    async fn send_message(&self, chat_id: i64, text: &str) -> AppResult<bool> {
+        if !self.config.telegram_enabled {
+            tracing::info!("Telegram disabled. Message to chat {}: {}", chat_id, text);
+            return Ok(true);
+        }
+
+        let response = self
+            .client
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                self.config.telegram_api_url, self.config.telegram_bot_token
+            ))
+            .json(&json!({"chat_id": chat_id, "text": text}))
+            .send()
+            .await
+            // В адресе запроса токен бота: в журнал и ошибку задачи он попасть не должен
+            .map_err(|e| AppError::ServiceUnavailable(format!("Telegram ({})", e.without_url())))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::FORBIDDEN {
+            return Ok(false);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!("Telegram API error: {} - {}", status, body);
+            return Err(AppError::ServiceUnavailable(format!("Telegram ({})", status)));
+        }
+
+        Ok(true)
+    }
+}
+
+fn code_hash(code: &str) -> String {
+    hex::encode(Sha256::digest(code.as_bytes()))
+}
@@ -0,0 +1,140 @@
+use crate::error::AppResult;
+use crate::models::ViewEntity;
+use once_cell::sync::OnceCell;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+/// Размер буфера событий просмотров
+const VIEW_QUEUE_SIZE: usize = 10_000;
+
+/// Сбрасываем накопленные просмотры при достижении этого размера
+const VIEW_BATCH_SIZE: usize = 500;
+
+/// Интервал сброса просмотров в базу
+const VIEW_FLUSH_INTERVAL_SECS: u64 = 5;
+
+static VIEW_SENDER: OnceCell<mpsc::Sender<ViewEvent>> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ViewEvent {
+    entity: ViewEntity,
+    entity_id: Uuid,
+    user_id: Uuid,
+}
+
+pub struct ViewService;
+
+impl ViewService {
+    /// Запустить фоновый батчер просмотров
+    pub fn start(pool: PgPool) {
+        let (tx, rx) = mpsc::channel(VIEW_QUEUE_SIZE);
+        if VIEW_SENDER.set(tx).is_err() {
+            return;
+        }
+
+        tokio::spawn(run_batcher(pool, rx));
+    }
+
+    /// Зафиксировать просмотр, не блокируя обработчик запроса
+    pub fn record(entity: ViewEntity, entity_id: Uuid, user_id: Uuid) {
+        let Some(tx) = VIEW_SENDER.get() else {
+            return;
+        };
+
+        let event = ViewEvent {
+            entity,
+            entity_id,
+            user_id,
+        };
+
+        if tx.try_send(event).is_err() {
+            tracing::warn!("View queue is full, dropping view of {}", entity_id);
+        }
+    }
+
+    /// Записать пачку просмотров и обновить счётчики
+    async fn flush(pool: &PgPool, batch: HashMap<ViewEvent, i32>) -> AppResult<()> {
+        let mut counters: HashMap<(ViewEntity, Uuid), (i32, i32)> = HashMap::new();
+        let mut tx = pool.begin().await?;
+
+        for (event, views) in batch {
+            let (inserted,): (bool,) = sqlx::query_as(
+                r#"
+                INSERT INTO content_views (entity_type, entity_id, user_id, views)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (entity_type, entity_id, user_id, view_date)
+                DO UPDATE SET views = content_views.views + EXCLUDED.views
+                RETURNING (xmax = 0)
+                "#,
+            )
+            .bind(event.entity.as_str())
+            .bind(event.entity_id)
+            .bind(event.user_id)
+            .bind(views)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            let counter = counters.entry((event.entity, event.entity_id)).or_default();
+            if inserted {
+                counter.0 += 1;
+            }
+            counter.1 += views;
+        }
+
+        for ((entity, entity_id), (unique, total)) in counters {
+            let sql = match entity {
+                ViewEntity::Announcement => {
+                    "UPDATE announcements SET views_count = views_count + $2, total_views_count = total_views_count + $3 WHERE id = $1"
+                }
+                ViewEntity::Listing => {
+                    "UPDATE marketplace_listings SET views_count = views_count + $2, total_views_count = total_views_count + $3 WHERE id = $1"
+                }
+            };
+
+            sqlx::query(sql)
+                .bind(entity_id)
+                .bind(unique)
+                .bind(total)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
+
+async fn run_batcher(pool: PgPool, mut rx: mpsc::Receiver<ViewEvent>) {
+    let mut interval =
+        tokio::time::interval(std::time::Duration::from_secs(VIEW_FLUSH_INTERVAL_SECS));
+    let mut batch: HashMap<ViewEvent, i32> = HashMap::new();
+
+    loop {
+        let closed = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => {
+                    *batch.entry(event).or_default() += 1;
+                    if batch.len() < VIEW_BATCH_SIZE {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = interval.tick() => false,
+        };
+
+        if !batch.is_empty() {
+            if let Err(e) = ViewService::flush(&pool, std::mem::take(&mut batch)).await {
+                tracing::error!("Failed to flush views: {}", e);
+            }
+        }
+
+        if closed {
+            break;
+        }
+    }
+}
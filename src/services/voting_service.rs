@@ -0,0 +1,597 @@
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    EmailTemplate, NotificationType, Osi, VoteDelegation, Voting, VotingApartment, VotingType,
+};
+use crate::services::document_service::{ProtocolOption, ProtocolParticipant, ProtocolResults};
+use crate::services::{
+    BudgetService, ElectionService, EmailService, NotificationService, SchedulerService,
+};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sha2::Sha256;
+use sqlx::{PgConnection, PgPool};
+use std::fmt::Write as _;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Как часто проверять голосования, которым пора начаться или завершиться
+const VOTING_TRANSITIONS_INTERVAL_SECS: u64 = 60;
+
+pub struct VotingService;
+
+/// Явка голосования в весах (площадях)
+#[derive(Debug, Clone, Copy)]
+pub struct Turnout {
+    pub voted_weight: Decimal,
+    pub total_weight: Decimal,
+}
+
+impl Turnout {
+    /// Набран ли кворум при заданном проценте
+    pub fn quorum_reached(&self, quorum_percent: i32) -> bool {
+        if self.total_weight <= Decimal::ZERO {
+            return false;
+        }
+        self.voted_weight * Decimal::from(100) >= self.total_weight * Decimal::from(quorum_percent)
+    }
+}
+
+/// Итог варианта после мгновенного второго тура
+#[derive(Debug, Clone)]
+pub struct RankedOptionResult {
+    pub option_id: Uuid,
+    pub sort_order: i32,
+    /// Бюллетени и вес, доставшиеся варианту в последнем туре, где он участвовал
+    pub votes_count: i32,
+    pub votes_weight: Decimal,
+    pub eliminated_in_round: Option<i32>,
+}
+
+/// Итоги рейтингового голосования
+#[derive(Debug, Clone)]
+pub struct RankedTally {
+    pub options: Vec<RankedOptionResult>,
+    /// Вес бюллетеней, не исчерпанных к последнему туру
+    pub continuing_weight: Decimal,
+}
+
+impl RankedTally {
+    /// Оставшиеся в последнем туре варианты по убыванию веса
+    pub fn final_round(&self) -> Vec<(Uuid, i32, Decimal)> {
+        let mut options: Vec<(Uuid, i32, Decimal)> = self
+            .options
+            .iter()
+            .filter(|option| option.eliminated_in_round.is_none())
+            .map(|option| (option.option_id, option.sort_order, option.votes_weight))
+            .collect();
+        options.sort_by(|a, b| b.2.cmp(&a.2).then(a.1.cmp(&b.1)));
+        options
+    }
+}
+
+impl VotingService {
+    /// Зарегистрировать запуск и закрытие голосований по расписанию
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "voting_transitions",
+            Duration::from_secs(VOTING_TRANSITIONS_INTERVAL_SECS),
+            |pool, _config| async move {
+                let (activated, closed) = VotingService::run_transitions(&pool).await?;
+                if activated > 0 || closed > 0 {
+                    tracing::info!("Activated {} votings, closed {}", activated, closed);
+                }
+                Ok(())
+            },
+        );
+    }
+
+    /// Черновики с наступившим началом — в активные, активные с истёкшим сроком — закрыть
+    pub async fn run_transitions(pool: &PgPool) -> AppResult<(usize, usize)> {
+        // Выборы не начинаются, пока идёт выдвижение кандидатов
+        let due = sqlx::query_as::<_, Voting>(
+            r#"
+            SELECT * FROM votings
+            WHERE status = 'draft' AND starts_at <= NOW() AND ends_at > NOW()
+              AND (nomination_ends_at IS NULL OR nomination_ends_at <= NOW())
+            "#,
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let mut activated = 0;
+        for voting in &due {
+            if Self::activate(pool, voting).await?.is_some() {
+                activated += 1;
+            }
+        }
+
+        let expired = sqlx::query_as::<_, Voting>(
+            "SELECT * FROM votings WHERE status = 'active' AND ends_at <= NOW()",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        // Сбой одного голосования не мешает закрыть остальные
+        let mut closed = 0;
+        for voting in &expired {
+            match Self::finish(pool, voting).await {
+                Ok(_) => closed += 1,
+                Err(e) => tracing::error!("Failed to close voting {}: {}", voting.id, e),
+            }
+        }
+
+        Ok((activated, closed))
+    }
+
+    /// Открыть черновик для голосования и разослать собственникам приглашение.
+    /// При досрочном запуске начало переносится на текущий момент.
+    /// `None`, если голосование уже не черновик.
+    pub async fn activate(pool: &PgPool, voting: &Voting) -> AppResult<Option<Voting>> {
+        let activated = sqlx::query_as::<_, Voting>(
+            r#"
+            UPDATE votings SET
+                status = 'active',
+                starts_at = LEAST(starts_at, NOW()),
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'draft'
+            RETURNING *
+            "#,
+        )
+        .bind(voting.id)
+        .fetch_optional(pool)
+        .await?;
+
+        let Some(activated) = activated else {
+            return Ok(None);
+        };
+
+        let body = format!(
+            "Голосование открыто до {}. Проголосуйте в приложении",
+            activated.ends_at.format("%d.%m.%Y %H:%M")
+        );
+        let owner_ids = Self::owner_ids(pool, activated.complex_id).await?;
+        NotificationService::notify_users(
+            pool,
+            &owner_ids,
+            NotificationType::Voting,
+            &activated.title,
+            Some(&body),
+            Some(json!({"voting_id": activated.id, "status": activated.status})),
+        )
+        .await?;
+        EmailService::enqueue(
+            pool,
+            &owner_ids,
+            EmailTemplate::VotingOpened {
+                voting_id: activated.id,
+                title: activated.title.clone(),
+                ends_at: activated.ends_at,
+            },
+        )
+        .await?;
+
+        tracing::info!("Voting {} activated", activated.id);
+
+        Ok(Some(activated))
+    }
+
+    /// Закрыть голосование, применить решение к бюджету и выборам и сообщить собственникам итог
+    pub async fn finish(pool: &PgPool, voting: &Voting) -> AppResult<Voting> {
+        // Итоги, бюджет и передача полномочий фиксируются вместе: сбой откатывает
+        // закрытие, и планировщик повторит его на следующем проходе
+        let mut tx = pool.begin().await?;
+        let closed = Self::close(&mut tx, voting).await?;
+        BudgetService::resolve_voting(&mut tx, voting.id).await?;
+        let election = ElectionService::resolve_voting(&mut tx, voting.id).await?;
+        tx.commit().await?;
+
+        // Итог выборов собственникам сообщает ElectionService
+        if let Some(outcome) = election {
+            ElectionService::announce_result(pool, &closed, outcome).await?;
+            return Ok(closed);
+        }
+
+        let body = match (closed.quorum_reached, closed.passed) {
+            (Some(false), _) => "Голосование завершено: кворум не набран",
+            (_, Some(true)) => "Голосование завершено: решение принято",
+            _ => "Голосование завершено: решение не принято",
+        };
+        NotificationService::notify_users(
+            pool,
+            &Self::owner_ids(pool, closed.complex_id).await?,
+            NotificationType::Voting,
+            &closed.title,
+            Some(body),
+            Some(json!({"voting_id": closed.id, "status": closed.status, "passed": closed.passed})),
+        )
+        .await?;
+
+        Ok(closed)
+    }
+
+    async fn owner_ids(pool: &PgPool, complex_id: Uuid) -> AppResult<Vec<Uuid>> {
+        let owner_ids: Vec<(Uuid,)> = sqlx::query_as(
+            "SELECT DISTINCT owner_id FROM apartments WHERE complex_id = $1 AND owner_id IS NOT NULL",
+        )
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(owner_ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Действующая доверенность собственника на это голосование; доверенность
+    /// на конкретное голосование важнее доверенности на период
+    pub async fn active_delegation(
+        pool: &PgPool,
+        voting: &Voting,
+        principal_id: Uuid,
+        proxy_id: Uuid,
+    ) -> AppResult<Option<VoteDelegation>> {
+        let delegation = sqlx::query_as::<_, VoteDelegation>(
+            r#"
+            SELECT * FROM vote_delegations
+            WHERE principal_id = $1 AND proxy_id = $2 AND complex_id = $3
+              AND revoked_at IS NULL
+              AND (
+                  voting_id = $4
+                  OR (voting_id IS NULL AND valid_from <= NOW() AND (valid_until IS NULL OR valid_until > NOW()))
+              )
+            ORDER BY voting_id IS NULL, created_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(principal_id)
+        .bind(proxy_id)
+        .bind(voting.complex_id)
+        .bind(voting.id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(delegation)
+    }
+
+    /// Квартиры собственника в ЖК голосования и отметка, подан ли за них бюллетень
+    pub async fn voter_apartments(
+        pool: &PgPool,
+        voting: &Voting,
+        owner_id: Uuid,
+    ) -> AppResult<Vec<VotingApartment>> {
+        let apartments = sqlx::query_as::<_, VotingApartment>(
+            r#"
+            SELECT
+                a.id AS apartment_id, a.number, a.building,
+                COALESCE(a.area, 1) AS vote_weight,
+                EXISTS(SELECT 1 FROM votes v WHERE v.voting_id = $1 AND v.apartment_id = a.id) AS voted
+            FROM apartments a
+            WHERE a.complex_id = $2 AND a.owner_id = $3
+            ORDER BY a.building NULLS FIRST, a.number
+            "#,
+        )
+        .bind(voting.id)
+        .bind(voting.complex_id)
+        .bind(owner_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(apartments)
+    }
+
+    /// Проголосовавший вес против суммарной площади квартир собственников ЖК
+    pub async fn turnout(conn: &mut PgConnection, voting: &Voting) -> AppResult<Turnout> {
+        let (voted_weight,): (Decimal,) =
+            sqlx::query_as("SELECT votes_weight FROM votings WHERE id = $1")
+                .bind(voting.id)
+                .fetch_one(&mut *conn)
+                .await?;
+
+        let (total_weight,): (Decimal,) = sqlx::query_as(
+            "SELECT COALESCE(SUM(COALESCE(area, 1)), 0) FROM apartments WHERE complex_id = $1 AND owner_id IS NOT NULL",
+        )
+        .bind(voting.complex_id)
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(Turnout {
+            voted_weight,
+            total_weight,
+        })
+    }
+
+    /// Закрыть голосование и зафиксировать итоги: кворум от площади собственников,
+    /// вариант-победитель и принято ли решение. `Conflict`, если голосование уже не активно
+    pub async fn close(conn: &mut PgConnection, voting: &Voting) -> AppResult<Voting> {
+        let turnout = Self::turnout(&mut *conn, voting).await?;
+        let quorum_reached = turnout.quorum_reached(voting.quorum_percent);
+
+        // В рейтинговом голосовании большинство считается от бюллетеней последнего тура
+        let (options, decisive_weight) = if voting.voting_type == VotingType::Ranked {
+            let tally = Self::ranked_tally(&mut *conn, voting).await?;
+            (tally.final_round(), tally.continuing_weight)
+        } else {
+            let options: Vec<(Uuid, i32, Decimal)> = sqlx::query_as(
+                "SELECT id, sort_order, votes_weight FROM voting_options WHERE voting_id = $1 ORDER BY votes_weight DESC, sort_order",
+            )
+            .bind(voting.id)
+            .fetch_all(&mut *conn)
+            .await?;
+            (options, turnout.voted_weight)
+        };
+
+        // Без кворума решение не принимается и победителя нет
+        let winner = leading_option(&options).filter(|_| quorum_reached);
+        let passed = winner.is_some_and(|(_, sort_order, weight)| match voting.voting_type {
+            // Председатель избирается относительным большинством
+            VotingType::Election => true,
+            // Первый вариант — «за»
+            VotingType::YesNo => sort_order == 0 && weight * Decimal::from(2) > decisive_weight,
+            VotingType::SingleChoice | VotingType::MultipleChoice | VotingType::Ranked => {
+                weight * Decimal::from(2) > decisive_weight
+            }
+        });
+
+        // Победителя выборов определяет ElectionService вместе с передачей полномочий
+        let voting = sqlx::query_as::<_, Voting>(
+            r#"
+            UPDATE votings SET
+                status = 'closed',
+                total_weight = $2,
+                quorum_reached = $3,
+                passed = $4,
+                winner_option_id = CASE WHEN voting_type = 'election' THEN winner_option_id ELSE $5 END,
+                closed_at = NOW(),
+                updated_at = NOW()
+            WHERE id = $1 AND status = 'active'
+            RETURNING *
+            "#,
+        )
+        .bind(voting.id)
+        .bind(turnout.total_weight)
+        .bind(quorum_reached)
+        .bind(passed)
+        .bind(winner.map(|(id, _, _)| id))
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Голосование уже не активно".to_string()))?;
+
+        tracing::info!(
+            "Voting {} closed: quorum {}, passed {}",
+            voting.id,
+            quorum_reached,
+            passed
+        );
+
+        Ok(voting)
+    }
+
+    /// Итоги рейтингового голосования: бюллетени выбывших вариантов переходят
+    /// к следующему предпочтению, пока лидер не наберёт большинство
+    pub async fn ranked_tally(conn: &mut PgConnection, voting: &Voting) -> AppResult<RankedTally> {
+        let options: Vec<(Uuid, i32)> = sqlx::query_as(
+            "SELECT id, sort_order FROM voting_options WHERE voting_id = $1 ORDER BY sort_order",
+        )
+        .bind(voting.id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        let ballots: Vec<(Decimal, Vec<Uuid>)> = sqlx::query_as(
+            r#"
+            SELECT MAX(c.vote_weight), array_agg(c.option_id ORDER BY c.rank)
+            FROM vote_choices c
+            JOIN votes v ON v.id = c.vote_id
+            WHERE v.voting_id = $1
+            GROUP BY c.vote_id
+            "#,
+        )
+        .bind(voting.id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+        Ok(instant_runoff(&options, &ballots))
+    }
+
+    /// Итоги закрытого голосования для протокола: варианты, кворум и проголосовавшие квартиры
+    pub async fn protocol_results(pool: &PgPool, voting: &Voting) -> AppResult<ProtocolResults> {
+        let (complex_name,): (String,) = sqlx::query_as("SELECT name FROM complexes WHERE id = $1")
+            .bind(voting.complex_id)
+            .fetch_one(pool)
+            .await?;
+
+        let osi = sqlx::query_as::<_, Osi>(
+            "SELECT * FROM osi WHERE id = $1 OR ($1 IS NULL AND complex_id = $2)",
+        )
+        .bind(voting.osi_id)
+        .bind(voting.complex_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let options: Vec<(String, i32, Decimal)> = sqlx::query_as(
+            "SELECT text, votes_count, votes_weight FROM voting_options WHERE voting_id = $1 ORDER BY sort_order",
+        )
+        .bind(voting.id)
+        .fetch_all(pool)
+        .await?;
+
+        // Вес бюллетеня — площадь квартиры, за которую он подан
+        let participants: Vec<(String, Option<Decimal>, String, String, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT
+                COALESCE(string_agg(a.number || COALESCE(', корп. ' || a.building, ''), '; ' ORDER BY a.number), '—'),
+                SUM(a.area),
+                u.display_name || COALESCE(' (по доверенности — ' || p.display_name || ')', ''),
+                (
+                    SELECT string_agg(
+                        CASE WHEN $2 THEN c.rank || '. ' ELSE '' END || o.text,
+                        ', ' ORDER BY c.rank, o.sort_order
+                    )
+                    FROM vote_choices c
+                    JOIN voting_options o ON o.id = c.option_id
+                    WHERE c.vote_id = v.id
+                ),
+                v.vote_weight
+            FROM votes v
+            JOIN users u ON u.id = v.user_id
+            LEFT JOIN users p ON p.id = v.proxy_id
+            LEFT JOIN apartments a ON a.id = v.apartment_id
+            WHERE v.voting_id = $1
+            GROUP BY v.id, u.display_name, p.display_name, v.vote_weight
+            ORDER BY MIN(a.number), u.display_name
+            "#,
+        )
+        .bind(voting.id)
+        .bind(voting.voting_type == VotingType::Ranked)
+        .fetch_all(pool)
+        .await?;
+
+        // Площадь и кворум берутся зафиксированные при закрытии, если они есть
+        let turnout = Self::turnout(&mut *pool.acquire().await?, voting).await?;
+        let total_weight = voting.total_weight.unwrap_or(turnout.total_weight);
+        let quorum_reached = voting
+            .quorum_reached
+            .unwrap_or_else(|| turnout.quorum_reached(voting.quorum_percent));
+
+        Ok(ProtocolResults {
+            osi_name: osi.as_ref().map(|osi| osi.name.clone()),
+            osi_bin: osi.as_ref().and_then(|osi| osi.bin.clone()),
+            osi_address: osi.and_then(|osi| osi.address),
+            complex_name,
+            title: voting.title.clone(),
+            description: voting.description.clone(),
+            voting_type: voting.voting_type.title().to_string(),
+            starts_at: voting.starts_at,
+            ends_at: voting.ends_at,
+            options: options
+                .into_iter()
+                .map(|(text, votes_count, votes_weight)| ProtocolOption {
+                    text,
+                    votes_count,
+                    votes_weight,
+                })
+                .collect(),
+            voted_weight: turnout.voted_weight,
+            total_weight,
+            quorum_percent: voting.quorum_percent,
+            quorum_reached,
+            participants: participants
+                .into_iter()
+                .map(|(apartments, area, owner, choice, weight)| ProtocolParticipant {
+                    apartments,
+                    area,
+                    owner,
+                    choice,
+                    weight,
+                })
+                .collect(),
+        })
+    }
+
+    /// Подпись итогов: HMAC-SHA256 от голосования, вариантов, кворума и участников
+    pub fn sign_protocol(secret: &str, voting_id: Uuid, results: &ProtocolResults) -> String {
+        let mut payload = format!(
+            "{}|{}|{}|{}|{}",
+            voting_id,
+            results.title,
+            results.voted_weight.normalize(),
+            results.total_weight.normalize(),
+            results.quorum_percent
+        );
+        for option in &results.options {
+            let _ = write!(
+                payload,
+                "|{}:{}:{}",
+                option.text,
+                option.votes_count,
+                option.votes_weight.normalize()
+            );
+        }
+        for participant in &results.participants {
+            let _ = write!(
+                payload,
+                "|{}:{}:{}",
+                participant.apartments,
+                participant.choice,
+                participant.weight.normalize()
+            );
+        }
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+/// Единственный вариант с наибольшим ненулевым весом.
+/// Варианты должны быть отсортированы по весу по убыванию.
+fn leading_option(options: &[(Uuid, i32, Decimal)]) -> Option<(Uuid, i32, Decimal)> {
+    let &(id, sort_order, top) = options.first()?;
+    if top <= Decimal::ZERO || options.get(1).is_some_and(|(_, _, weight)| *weight == top) {
+        return None;
+    }
+    Some((id, sort_order, top))
+}
+
+/// Мгновенный второй тур: в каждом туре бюллетень отдаётся первому оставшемуся
+/// варианту, слабейший выбывает, пока лидер не наберёт больше половины.
+/// При равенстве слабейших выбывает последний по порядку; если равны все — туры
+/// прекращаются без победителя.
+fn instant_runoff(options: &[(Uuid, i32)], ballots: &[(Decimal, Vec<Uuid>)]) -> RankedTally {
+    let mut results: Vec<RankedOptionResult> = options
+        .iter()
+        .map(|&(option_id, sort_order)| RankedOptionResult {
+            option_id,
+            sort_order,
+            votes_count: 0,
+            votes_weight: Decimal::ZERO,
+            eliminated_in_round: None,
+        })
+        .collect();
+    let mut continuing_weight = Decimal::ZERO;
+
+    for round in 1.. {
+        let mut tally: Vec<(i32, Decimal)> = vec![(0, Decimal::ZERO); results.len()];
+        for (weight, ranking) in ballots {
+            let choice = ranking.iter().find_map(|option_id| {
+                results.iter().position(|result| {
+                    result.option_id == *option_id && result.eliminated_in_round.is_none()
+                })
+            });
+            if let Some(index) = choice {
+                tally[index].0 += 1;
+                tally[index].1 += *weight;
+            }
+        }
+
+        let mut active: Vec<usize> = Vec::new();
+        continuing_weight = Decimal::ZERO;
+        for (index, result) in results.iter_mut().enumerate() {
+            if result.eliminated_in_round.is_none() {
+                (result.votes_count, result.votes_weight) = tally[index];
+                continuing_weight += result.votes_weight;
+                active.push(index);
+            }
+        }
+
+        let weights = active.iter().map(|&index| results[index].votes_weight);
+        let (Some(top), Some(bottom)) = (weights.clone().max(), weights.min()) else {
+            break;
+        };
+        if active.len() <= 1 || top * Decimal::from(2) > continuing_weight || top == bottom {
+            break;
+        }
+
+        if let Some(&weakest) = active
+            .iter()
+            .rev()
+            .find(|&&index| results[index].votes_weight == bottom)
+        {
+            results[weakest].eliminated_in_round = Some(round);
+        }
+    }
+
+    RankedTally {
+        options: results,
+        continuing_weight,
+    }
+}
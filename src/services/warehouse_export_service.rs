@@ -0,0 +1,731 @@
+use std::io::Write;
+use std::sync::Arc;
+
+use chrono::{Duration, NaiveDate, Utc};
+use flate2::{write::GzEncoder, Compression};
+use parquet::basic::{
+    Compression as ParquetCompression, GzipLevel, LogicalType, Repetition, Type as PhysicalType,
+};
+use parquet::data_type::{ByteArray, ByteArrayType};
+use parquet::errors::Result as ParquetResult;
+use parquet::file::{properties::WriterProperties, writer::SerializedFileWriter};
+use parquet::schema::types::Type as SchemaType;
+use serde_json::{json, Value};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{AppError, AppResult};
+use crate::models::{
+    CreateWarehouseExportRequest, ExportStatus, JobType, NewAuditLog, UpdateWarehouseExportRequest,
+    WarehouseDataset, WarehouseExportConfig, WarehouseExportPayload, WarehouseExportRun,
+    WarehouseFormat,
+};
+use crate::services::{AuditService, FileService, JobService, SchedulerService};
+use crate::utils::csv_row;
+
+/// Текущая версия набора колонок; старые версии остаются, пока на них есть настройки
+pub const WAREHOUSE_SCHEMA_VERSION: i32 = 1;
+
+/// Как часто проверять, не пора ли выгружать прошедшие сутки
+const WAREHOUSE_EXPORT_INTERVAL_SECS: u64 = 3600;
+
+/// На сколько суток назад планировщик догоняет пропущенные выгрузки
+const WAREHOUSE_CATCH_UP_DAYS: i32 = 7;
+
+/// Сколько последних выгрузок показывать по настройке
+const WAREHOUSE_RUNS_LIMIT: i64 = 100;
+
+enum Column {
+    Text(&'static str),
+    /// Момент времени, выгружается в UTC в формате ISO 8601
+    Time(&'static str),
+}
+
+/// Колонки набора данных в одной версии схемы. В запросе `$1` — ЖК,
+/// `$2` и `$3` — границы суток
+struct DatasetSchema {
+    columns: &'static [(&'static str, Column)],
+    source: &'static str,
+    order_by: &'static str,
+}
+
+impl DatasetSchema {
+    fn query(&self) -> String {
+        let values = self
+            .columns
+            .iter()
+            .map(|(_, column)| match column {
+                Column::Text(expr) => format!("({})::text", expr),
+                Column::Time(expr) => format!(
+                    r#"to_char({} AT TIME ZONE 'UTC', 'YYYY-MM-DD"T"HH24:MI:SS"Z"')"#,
+                    expr
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "SELECT ARRAY[{}]::text[] {} ORDER BY {}",
+            values, self.source, self.order_by
+        )
+    }
+
+    fn header(&self) -> Vec<&'static str> {
+        self.columns.iter().map(|(name, _)| *name).collect()
+    }
+}
+
+/// Счета, выставленные или оплаченные за сутки
+const BILLS_V1: DatasetSchema = DatasetSchema {
+    columns: &[
+        ("id", Column::Text("b.id")),
+        ("apartment_id", Column::Text("b.apartment_id")),
+        ("period_start", Column::Text("b.period_start")),
+        ("period_end", Column::Text("b.period_end")),
+        ("amount", Column::Text("b.amount")),
+        ("debt", Column::Text("b.debt")),
+        ("penalty", Column::Text("b.penalty")),
+        ("total_amount", Column::Text("b.total_amount")),
+        ("paid_amount", Column::Text("b.paid_amount")),
+        ("status", Column::Text("b.status")),
+        ("due_date", Column::Text("b.due_date")),
+        ("paid_at", Column::Time("b.paid_at")),
+        ("created_at", Column::Time("b.created_at")),
+    ],
+    source: r#"
+        FROM bills b
+        WHERE b.complex_id = $1
+          AND ((b.created_at >= $2 AND b.created_at < $3)
+               OR (b.paid_at >= $2 AND b.paid_at < $3))
+    "#,
+    order_by: "b.created_at, b.id",
+};
+
+/// Платежи, созданные или проведённые за сутки
+const PAYMENTS_V1: DatasetSchema = DatasetSchema {
+    columns: &[
+        ("id", Column::Text("p.id")),
+        ("bill_id", Column::Text("p.bill_id")),
+        ("apartment_id", Column::Text("p.apartment_id")),
+        ("amount", Column::Text("p.amount")),
+        ("method", Column::Text("p.method")),
+        ("status", Column::Text("p.status")),
+        ("receipt_number", Column::Text("p.receipt_number")),
+        ("completed_at", Column::Time("p.completed_at")),
+        ("created_at", Column::Time("p.created_at")),
+    ],
+    source: r#"
+        FROM payments p
+        JOIN apartments a ON a.id = p.apartment_id
+        WHERE a.complex_id = $1
+          AND ((p.created_at >= $2 AND p.created_at < $3)
+               OR (p.completed_at >= $2 AND p.completed_at < $3))
+    "#,
+    order_by: "p.created_at, p.id",
+};
+
+/// Заявки на обслуживание, изменившиеся за сутки; текст заявок не выгружается
+const MAINTENANCE_V1: DatasetSchema = DatasetSchema {
+    columns: &[
+        ("id", Column::Text("m.id")),
+        ("apartment_id", Column::Text("m.apartment_id")),
+        ("category", Column::Text("m.category")),
+        ("priority", Column::Text("m.priority")),
+        ("status", Column::Text("m.status")),
+        ("assigned_to", Column::Text("m.assigned_to")),
+        ("rating", Column::Text("m.rating")),
+        ("created_at", Column::Time("m.created_at")),
+        ("assigned_at", Column::Time("m.assigned_at")),
+        ("completed_at", Column::Time("m.completed_at")),
+        ("updated_at", Column::Time("m.updated_at")),
+    ],
+    source: r#"
+        FROM maintenance_requests m
+        WHERE m.complex_id = $1 AND m.updated_at >= $2 AND m.updated_at < $3
+    "#,
+    order_by: "m.updated_at, m.id",
+};
+
+/// Проезды через шлагбаумы за сутки
+const ACCESS_LOGS_V1: DatasetSchema = DatasetSchema {
+    columns: &[
+        ("id", Column::Text("l.id")),
+        ("barrier_id", Column::Text("l.barrier_id")),
+        ("user_id", Column::Text("l.user_id")),
+        ("guest_access_id", Column::Text("l.guest_access_id")),
+        ("action", Column::Text("l.action")),
+        ("geo_mismatch", Column::Text("l.geo_mismatch")),
+        ("created_at", Column::Time("l.created_at")),
+    ],
+    source: r#"
+        FROM barrier_access_logs l
+        WHERE l.complex_id = $1 AND l.created_at >= $2 AND l.created_at < $3
+    "#,
+    order_by: "l.created_at, l.id",
+};
+
+fn dataset_schema(dataset: WarehouseDataset, version: i32) -> Option<&'static DatasetSchema> {
+    match (dataset, version) {
+        (WarehouseDataset::Bills, 1) => Some(&BILLS_V1),
+        (WarehouseDataset::Payments, 1) => Some(&PAYMENTS_V1),
+        (WarehouseDataset::Maintenance, 1) => Some(&MAINTENANCE_V1),
+        (WarehouseDataset::AccessLogs, 1) => Some(&ACCESS_LOGS_V1),
+        _ => None,
+    }
+}
+
+/// Ежедневные выгрузки счетов, платежей, заявок и проездов ЖК в хранилище.
+/// Файлы разложены по Hive-разделам:
+/// `<префикс>/<набор>/v<версия>/complex_id=<ЖК>/date=<сутки>/part-0000.<формат>`,
+/// рядом в `_manifests` лежит описание выгрузки с колонками и числом строк
+pub struct WarehouseExportService;
+
+impl WarehouseExportService {
+    pub fn register_jobs(scheduler: &mut SchedulerService) {
+        scheduler.register(
+            "warehouse_export",
+            std::time::Duration::from_secs(WAREHOUSE_EXPORT_INTERVAL_SECS),
+            |pool, _config| async move { WarehouseExportService::schedule(&pool).await },
+        );
+    }
+
+    pub async fn list(
+        pool: &PgPool,
+        complex_id: Option<Uuid>,
+    ) -> AppResult<Vec<WarehouseExportConfig>> {
+        Ok(sqlx::query_as::<_, WarehouseExportConfig>(
+            r#"
+            SELECT * FROM warehouse_export_configs
+            WHERE $1::uuid IS NULL OR complex_id = $1
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(complex_id)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    pub async fn create(
+        pool: &PgPool,
+        created_by: Uuid,
+        payload: &CreateWarehouseExportRequest,
+    ) -> AppResult<WarehouseExportConfig> {
+        let s3_prefix = normalize_prefix(&payload.s3_prefix)?;
+        let datasets = normalize_datasets(&payload.datasets)?;
+        let schema_version = payload.schema_version.unwrap_or(WAREHOUSE_SCHEMA_VERSION);
+        validate_schema_version(schema_version)?;
+        let run_hour = payload.run_hour.unwrap_or(2);
+        validate_run_hour(run_hour)?;
+
+        let exists: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM complexes WHERE id = $1")
+            .bind(payload.complex_id)
+            .fetch_optional(pool)
+            .await?;
+        if exists.is_none() {
+            return Err(AppError::NotFound("ЖК не найден".to_string()));
+        }
+
+        sqlx::query_as::<_, WarehouseExportConfig>(
+            r#"
+            INSERT INTO warehouse_export_configs (
+                complex_id, s3_prefix, datasets, format, schema_version, run_hour, created_by
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *
+            "#,
+        )
+        .bind(payload.complex_id)
+        .bind(&s3_prefix)
+        .bind(&datasets)
+        .bind(payload.format.unwrap_or(WarehouseFormat::CsvGzip))
+        .bind(schema_version)
+        .bind(run_hour)
+        .bind(created_by)
+        .fetch_one(pool)
+        .await
+        .map_err(prefix_conflict)
+    }
+
+    /// Изменить настройку; возвращает прежнюю и новую версии
+    pub async fn update(
+        pool: &PgPool,
+        id: Uuid,
+        payload: &UpdateWarehouseExportRequest,
+    ) -> AppResult<(WarehouseExportConfig, WarehouseExportConfig)> {
+        let old = Self::get(pool, id).await?;
+
+        let s3_prefix = payload
+            .s3_prefix
+            .as_deref()
+            .map(normalize_prefix)
+            .transpose()?;
+        let datasets = payload
+            .datasets
+            .as_deref()
+            .map(normalize_datasets)
+            .transpose()?;
+        if let Some(version) = payload.schema_version {
+            validate_schema_version(version)?;
+        }
+        if let Some(hour) = payload.run_hour {
+            validate_run_hour(hour)?;
+        }
+
+        let config = sqlx::query_as::<_, WarehouseExportConfig>(
+            r#"
+            UPDATE warehouse_export_configs SET
+                s3_prefix = COALESCE($2, s3_prefix),
+                datasets = COALESCE($3, datasets),
+                format = COALESCE($4, format),
+                schema_version = COALESCE($5, schema_version),
+                run_hour = COALESCE($6, run_hour),
+                is_enabled = COALESCE($7, is_enabled),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(s3_prefix)
+        .bind(datasets)
+        .bind(payload.format)
+        .bind(payload.schema_version)
+        .bind(payload.run_hour)
+        .bind(payload.is_enabled)
+        .fetch_one(pool)
+        .await
+        .map_err(prefix_conflict)?;
+
+        Ok((old, config))
+    }
+
+    /// Удалить настройку вместе с историей выгрузок; файлы в хранилище остаются
+    pub async fn delete(pool: &PgPool, id: Uuid) -> AppResult<WarehouseExportConfig> {
+        sqlx::query_as::<_, WarehouseExportConfig>(
+            "DELETE FROM warehouse_export_configs WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Настройка выгрузки не найдена".to_string()))
+    }
+
+    pub async fn runs(pool: &PgPool, config_id: Uuid) -> AppResult<Vec<WarehouseExportRun>> {
+        Self::get(pool, config_id).await?;
+
+        Ok(sqlx::query_as::<_, WarehouseExportRun>(
+            r#"
+            SELECT * FROM warehouse_export_runs
+            WHERE config_id = $1
+            ORDER BY export_date DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(config_id)
+        .bind(WAREHOUSE_RUNS_LIMIT)
+        .fetch_all(pool)
+        .await?)
+    }
+
+    /// Выгрузить сутки вручную, например после исправления данных.
+    /// Уже выгруженные файлы за эти сутки перезаписываются
+    pub async fn trigger(
+        pool: &PgPool,
+        config_id: Uuid,
+        date: NaiveDate,
+        actor_id: Uuid,
+        request_id: &str,
+    ) -> AppResult<WarehouseExportRun> {
+        let config = Self::get(pool, config_id).await?;
+        if date >= Utc::now().date_naive() {
+            return Err(AppError::Validation(
+                "Выгрузить можно только завершившиеся сутки (UTC)".to_string(),
+            ));
+        }
+
+        let mut tx = pool.begin().await?;
+
+        let run = sqlx::query_as::<_, WarehouseExportRun>(
+            r#"
+            INSERT INTO warehouse_export_runs (config_id, export_date, schema_version)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (config_id, export_date) DO UPDATE SET
+                schema_version = EXCLUDED.schema_version,
+                status = 'pending', files = '[]', rows_count = NULL, error = NULL,
+                created_at = NOW(), completed_at = NULL
+            RETURNING *
+            "#,
+        )
+        .bind(config.id)
+        .bind(date)
+        .bind(config.schema_version)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        JobService::enqueue(&mut *tx, JobType::WarehouseExport, &WarehouseExportPayload { run_id: run.id })
+            .await?;
+
+        AuditService::record(
+            &mut *tx,
+            NewAuditLog {
+                actor_id,
+                complex_id: Some(config.complex_id),
+                action: "run_warehouse_export",
+                entity_type: "warehouse_export",
+                entity_id: Some(config.id),
+                old_value: None,
+                new_value: Some(json!({"run_id": run.id, "date": date})),
+                request_id: Some(request_id.to_string()),
+            },
+        )
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(run)
+    }
+
+    /// Завести выгрузки за прошедшие сутки для настроек, у которых наступил час выгрузки.
+    /// Пропущенные сутки догоняются, но не раньше дня создания настройки
+    pub async fn schedule(pool: &PgPool) -> AppResult<()> {
+        let mut tx = pool.begin().await?;
+
+        let run_ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            INSERT INTO warehouse_export_runs (config_id, export_date, schema_version)
+            SELECT c.id, d::date, c.schema_version
+            FROM warehouse_export_configs c
+            CROSS JOIN LATERAL generate_series(
+                GREATEST(
+                    (c.created_at AT TIME ZONE 'UTC')::date - 1,
+                    (NOW() AT TIME ZONE 'UTC')::date - $1
+                ),
+                (NOW() AT TIME ZONE 'UTC')::date - 1,
+                INTERVAL '1 day'
+            ) d
+            WHERE c.is_enabled
+              AND EXTRACT(HOUR FROM NOW() AT TIME ZONE 'UTC') >= c.run_hour
+            ON CONFLICT (config_id, export_date) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(WAREHOUSE_CATCH_UP_DAYS)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (run_id,) in &run_ids {
+            JobService::enqueue(&mut *tx, JobType::WarehouseExport, &WarehouseExportPayload { run_id: *run_id })
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        if !run_ids.is_empty() {
+            tracing::info!("Warehouse exports scheduled: {}", run_ids.len());
+        }
+        Ok(())
+    }
+
+    /// Сформировать и записать файлы выгрузки. Ошибка записывается в выгрузку,
+    /// а задача уходит на повтор
+    pub async fn generate(
+        pool: &PgPool,
+        file_service: &FileService,
+        run_id: Uuid,
+    ) -> AppResult<()> {
+        let run = sqlx::query_as::<_, WarehouseExportRun>(
+            "SELECT * FROM warehouse_export_runs WHERE id = $1",
+        )
+        .bind(run_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Выгрузка не найдена".to_string()))?;
+
+        if run.status == ExportStatus::Completed {
+            return Ok(());
+        }
+        let config = Self::get(pool, run.config_id).await?;
+
+        match Self::build(pool, file_service, &config, &run).await {
+            Ok((files, rows_count)) => {
+                sqlx::query(
+                    r#"
+                    UPDATE warehouse_export_runs
+                    SET status = 'completed', files = $2, rows_count = $3, error = NULL,
+                        completed_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(run.id)
+                .bind(files)
+                .bind(rows_count)
+                .execute(pool)
+                .await?;
+                Ok(())
+            }
+            Err(e) => {
+                sqlx::query(
+                    "UPDATE warehouse_export_runs SET status = 'failed', error = $2 WHERE id = $1",
+                )
+                .bind(run.id)
+                .bind(e.to_string())
+                .execute(pool)
+                .await?;
+                Err(e)
+            }
+        }
+    }
+
+    async fn build(
+        pool: &PgPool,
+        file_service: &FileService,
+        config: &WarehouseExportConfig,
+        run: &WarehouseExportRun,
+    ) -> AppResult<(Value, i32)> {
+        let from = run.export_date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let to = from + Duration::days(1);
+        let partition = format!("complex_id={}/date={}", config.complex_id, run.export_date);
+
+        let mut files = Vec::new();
+        let mut manifest = Vec::new();
+        let mut rows_count = 0;
+        for dataset in &config.datasets {
+            let schema = dataset_schema(*dataset, run.schema_version).ok_or_else(|| {
+                AppError::Internal(format!(
+                    "Нет схемы v{} для набора {}",
+                    run.schema_version,
+                    dataset.as_str()
+                ))
+            })?;
+
+            let rows: Vec<(Vec<Option<String>>,)> = sqlx::query_as(&schema.query())
+                .bind(config.complex_id)
+                .bind(from)
+                .bind(to)
+                .fetch_all(pool)
+                .await?;
+
+            let rows: Vec<Vec<Option<String>>> = rows.into_iter().map(|(row,)| row).collect();
+
+            let key = format!(
+                "{}/{}/v{}/{}/part-0000.{}",
+                config.s3_prefix,
+                dataset.as_str(),
+                run.schema_version,
+                partition,
+                config.format.extension()
+            );
+            file_service
+                .put_file(
+                    &key,
+                    config.format.content_type(),
+                    encode(config.format, &schema.header(), &rows)?,
+                )
+                .await?;
+
+            rows_count += rows.len() as i32;
+            files.push(json!({"dataset": dataset, "key": key, "rows": rows.len()}));
+            manifest.push(json!({
+                "dataset": dataset,
+                "key": key,
+                "rows": rows.len(),
+                "columns": schema.header(),
+            }));
+        }
+
+        let manifest = json!({
+            "schema_version": run.schema_version,
+            "complex_id": config.complex_id,
+            "date": run.export_date,
+            "format": config.format,
+            "generated_at": Utc::now(),
+            "datasets": manifest,
+        });
+        file_service
+            .put_file(
+                &format!("{}/_manifests/{}/manifest.json", config.s3_prefix, partition),
+                "application/json",
+                manifest.to_string().into_bytes(),
+            )
+            .await?;
+
+        Ok((Value::Array(files), rows_count))
+    }
+
+    async fn get(pool: &PgPool, id: Uuid) -> AppResult<WarehouseExportConfig> {
+        sqlx::query_as::<_, WarehouseExportConfig>(
+            "SELECT * FROM warehouse_export_configs WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Настройка выгрузки не найдена".to_string()))
+    }
+}
+
+fn encode(
+    format: WarehouseFormat,
+    header: &[&str],
+    rows: &[Vec<Option<String>>],
+) -> AppResult<Vec<u8>> {
+    match format {
+        WarehouseFormat::Csv => Ok(csv(header, rows).into_bytes()),
+        WarehouseFormat::CsvGzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(csv(header, rows).as_bytes())
+                .and_then(|_| encoder.finish())
+                .map_err(|e| AppError::Internal(format!("Не удалось сжать выгрузку: {}", e)))
+        }
+        WarehouseFormat::Parquet => parquet(header, rows)
+            .map_err(|e| AppError::Internal(format!("Не удалось записать Parquet: {}", e))),
+    }
+}
+
+fn csv(header: &[&str], rows: &[Vec<Option<String>>]) -> String {
+    let mut csv = csv_row(header);
+    for row in rows {
+        let fields: Vec<&str> = row.iter().map(|v| v.as_deref().unwrap_or("")).collect();
+        csv.push_str(&csv_row(&fields));
+    }
+    csv
+}
+
+/// Один row group на файл: суточная выгрузка ЖК помещается в память целиком
+fn parquet(header: &[&str], rows: &[Vec<Option<String>>]) -> ParquetResult<Vec<u8>> {
+    let fields = header
+        .iter()
+        .map(|name| {
+            SchemaType::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(Some(LogicalType::String))
+                .build()
+                .map(Arc::new)
+        })
+        .collect::<ParquetResult<Vec<_>>>()?;
+    let schema = SchemaType::group_type_builder("schema")
+        .with_fields(fields)
+        .build()?;
+    let properties = WriterProperties::builder()
+        .set_compression(ParquetCompression::GZIP(GzipLevel::default()))
+        .build();
+
+    let mut buffer = Vec::new();
+    let mut writer = SerializedFileWriter::new(&mut buffer, Arc::new(schema), Arc::new(properties))?;
+    let mut row_group = writer.next_row_group()?;
+    let mut index = 0;
+    while let Some(mut column) = row_group.next_column()? {
+        let mut values = Vec::new();
+        let mut definition_levels = Vec::with_capacity(rows.len());
+        for row in rows {
+            match &row[index] {
+                Some(value) => {
+                    values.push(ByteArray::from(value.as_str()));
+                    definition_levels.push(1);
+                }
+                None => definition_levels.push(0),
+            }
+        }
+        column
+            .typed::<ByteArrayType>()
+            .write_batch(&values, Some(&definition_levels), None)?;
+        column.close()?;
+        index += 1;
+    }
+    row_group.close()?;
+    writer.close()?;
+
+    Ok(buffer)
+}
+
+/// Префикс без «/» по краям, из латиницы, цифр и `_-.=/`, без пустых сегментов и `..`
+fn normalize_prefix(prefix: &str) -> AppResult<String> {
+    let prefix = prefix.trim().trim_matches('/');
+    let valid = !prefix.is_empty()
+        && prefix.len() <= 200
+        && prefix
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | '=' | '/'))
+        && prefix
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != "..");
+    if !valid {
+        return Err(AppError::Validation(
+            "Некорректный префикс: латиница, цифры и символы _-.=/".to_string(),
+        ));
+    }
+    Ok(prefix.to_string())
+}
+
+fn normalize_datasets(datasets: &[WarehouseDataset]) -> AppResult<Vec<WarehouseDataset>> {
+    let mut unique = Vec::new();
+    for dataset in datasets {
+        if !unique.contains(dataset) {
+            unique.push(*dataset);
+        }
+    }
+    if unique.is_empty() {
+        return Err(AppError::Validation(
+            "Выберите хотя бы один набор данных".to_string(),
+        ));
+    }
+    Ok(unique)
+}
+
+fn validate_schema_version(version: i32) -> AppResult<()> {
+    if !(1..=WAREHOUSE_SCHEMA_VERSION).contains(&version) {
+        return Err(AppError::Validation(format!(
+            "Версия схемы — от 1 до {}",
+            WAREHOUSE_SCHEMA_VERSION
+        )));
+    }
+    Ok(())
+}
+
+fn validate_run_hour(hour: i32) -> AppResult<()> {
+    if !(0..=23).contains(&hour) {
+        return Err(AppError::Validation("Час выгрузки — от 0 до 23".to_string()));
+    }
+    Ok(())
+}
+
+fn prefix_conflict(e: sqlx::Error) -> AppError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => AppError::Conflict(
+            "У ЖК уже есть выгрузка с таким префиксом".to_string(),
+        ),
+        _ => AppError::Database(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    #[test]
+    fn parquet_keeps_rows_and_nulls() {
+        let rows = vec![
+            vec![Some("a".to_string()), None],
+            vec![Some("b".to_string()), Some("2026-01-01T00:00:00Z".to_string())],
+        ];
+        let file = parquet(&["id", "paid_at"], &rows).unwrap();
+
+        let reader = SerializedFileReader::new(axum::body::Bytes::from(file)).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 2);
+
+        let values: Vec<String> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_string())
+            .collect();
+        assert_eq!(
+            values,
+            [
+                r#"{id: "a", paid_at: null}"#,
+                r#"{id: "b", paid_at: "2026-01-01T00:00:00Z"}"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use serde_json::json;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::services::resilience::{self, WHATSAPP};
+
+/// Отправка кодов входа через WhatsApp Business Cloud API
+pub struct WhatsAppService {
+    config: Config,
+    client: reqwest::Client,
+}
+
+impl WhatsAppService {
+    pub fn new(config: Config) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Отправить код шаблоном authentication; ошибка означает, что код нужно слать по SMS
+    pub async fn send_code(&self, phone: &str, code: &str) -> AppResult<()> {
+        if !self.config.whatsapp_enabled {
+            return Err(AppError::ServiceUnavailable("WhatsApp".to_string()));
+        }
+
+        resilience::call(&WHATSAPP, || self.send_template(phone, code)).await
+    }
+
+    async fn send_template(&self, phone: &str, code: &str) -> AppResult<()> {
+        let url = format!(
+            "{}/{}/messages",
+            self.config.whatsapp_api_url, self.config.whatsapp_phone_number_id
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.config.whatsapp_access_token)
+            .json(&json!({
+                "messaging_product": "whatsapp",
+                "to": phone.trim_start_matches('+'),
+                "type": "template",
+                "template": {
+                    "name": self.config.whatsapp_code_template,
+                    "language": {"code": self.config.whatsapp_template_language},
+                    "components": [
+                        {
+                            "type": "body",
+                            "parameters": [{"type": "text", "text": code}]
+                        },
+                        {
+                            "type": "button",
+                            "sub_type": "url",
+                            "index": "0",
+                            "parameters": [{"type": "text", "text": code}]
+                        }
+                    ]
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| AppError::ServiceUnavailable(format!("WhatsApp ({})", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp API error: {} - {}", status, body);
+            return Err(AppError::ServiceUnavailable(format!("WhatsApp ({})", status)));
+        }
+
+        tracing::info!("WhatsApp code sent to {}", phone);
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+/// Строка CSV: поля с запятой, кавычкой или переводом строки берутся в кавычки,
+/// кавычки внутри удваиваются. Завершается `\r\n`, как требует RFC 4180.
+pub fn csv_row<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut row = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push_str("\r\n");
+    row
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_row() {
+        assert_eq!(csv_row(&["Корпус 1", "5"]), "Корпус 1,5\r\n");
+        assert_eq!(
+            csv_row(&["Детская, площадка", "Сказал \"да\"", "две\nстроки"]),
+            "\"Детская, площадка\",\"Сказал \"\"да\"\"\",\"две\nстроки\"\r\n"
+        );
+        assert_eq!(csv_row::<&str>(&[]), "\r\n");
+    }
+}
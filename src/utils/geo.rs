@@ -0,0 +1,41 @@
+/// Средний радиус Земли, метров
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+/// Расстояние между двумя точками по поверхности Земли (формула гаверсинусов), метров
+pub fn distance_m(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Широта и долгота в допустимых пределах
+pub fn is_valid_coordinate(lat: f64, lon: f64) -> bool {
+    (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_m() {
+        assert_eq!(distance_m(43.238, 76.945, 43.238, 76.945), 0.0);
+        // Алматы — Астана, около 970 км
+        let km = distance_m(43.2389, 76.8897, 51.1694, 71.4491) / 1000.0;
+        assert!((960.0..980.0).contains(&km), "{}", km);
+        // Сотая доля градуса широты — около 1,1 км
+        let m = distance_m(43.0, 76.0, 43.01, 76.0);
+        assert!((1100.0..1125.0).contains(&m), "{}", m);
+    }
+
+    #[test]
+    fn test_is_valid_coordinate() {
+        assert!(is_valid_coordinate(43.238, 76.945));
+        assert!(!is_valid_coordinate(91.0, 76.0));
+        assert!(!is_valid_coordinate(43.0, -181.0));
+        assert!(!is_valid_coordinate(f64::NAN, 0.0));
+    }
+}
@@ -0,0 +1,53 @@
+use serde_json::{json, Map, Value};
+
+/// Изменения между двумя JSON-объектами по полям верхнего уровня:
+/// `{"поле": {"old": ..., "new": ...}}`. Для не-объектов — замена целиком.
+pub fn json_diff(old: &Value, new: &Value) -> Value {
+    let (Value::Object(old_map), Value::Object(new_map)) = (old, new) else {
+        if old == new {
+            return Value::Object(Map::new());
+        }
+        return json!({"old": old, "new": new});
+    };
+
+    let mut diff = Map::new();
+
+    for (key, old_value) in old_map {
+        let new_value = new_map.get(key).unwrap_or(&Value::Null);
+        if old_value != new_value {
+            diff.insert(key.clone(), json!({"old": old_value, "new": new_value}));
+        }
+    }
+
+    for (key, new_value) in new_map {
+        if !old_map.contains_key(key) && !new_value.is_null() {
+            diff.insert(key.clone(), json!({"old": Value::Null, "new": new_value}));
+        }
+    }
+
+    Value::Object(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_diff() {
+        let old = json!({"name": "ОСИ", "phone": null, "status": "pending", "removed": 1});
+        let new = json!({"name": "ОСИ", "phone": "+77001234567", "status": "cancelled", "added": true});
+
+        assert_eq!(
+            json_diff(&old, &new),
+            json!({
+                "phone": {"old": null, "new": "+77001234567"},
+                "status": {"old": "pending", "new": "cancelled"},
+                "removed": {"old": 1, "new": null},
+                "added": {"old": null, "new": true}
+            })
+        );
+
+        assert_eq!(json_diff(&old, &old), json!({}));
+        assert_eq!(json_diff(&json!("a"), &json!("b")), json!({"old": "a", "new": "b"}));
+    }
+}
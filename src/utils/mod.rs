@@ -1,3 +1,15 @@
+pub mod csv;
+pub mod geo;
+pub mod json_diff;
+pub mod patch;
+pub mod phone;
+pub mod recurrence;
+pub mod templates;
 pub mod validators;
 
+pub use csv::*;
+pub use json_diff::*;
+pub use patch::*;
+pub use phone::*;
+pub use templates::*;
 pub use validators::*;
@@ -0,0 +1,84 @@
+use serde::{Deserialize, Deserializer};
+
+/// Поле частичного обновления: не передано, передано `null` (очистить) или передано значение.
+/// В структуре запроса помечается `#[serde(default)]`, иначе отсутствие поля — ошибка.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Patch<T> {
+    #[default]
+    Absent,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_absent(&self) -> bool {
+        matches!(self, Patch::Absent)
+    }
+
+    /// Переданное значение; `None` и для `null`, и для отсутствующего поля
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            Patch::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Patch<U> {
+        match self {
+            Patch::Absent => Patch::Absent,
+            Patch::Null => Patch::Null,
+            Patch::Value(value) => Patch::Value(f(value)),
+        }
+    }
+
+    /// Значение после обновления: текущее, если поле не передано
+    pub fn apply(self, current: Option<T>) -> Option<T> {
+        match self {
+            Patch::Absent => current,
+            Patch::Null => None,
+            Patch::Value(value) => Some(value),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Option::<T>::deserialize(deserializer)? {
+            Some(value) => Patch::Value(value),
+            None => Patch::Null,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Request {
+        #[serde(default)]
+        email: Patch<String>,
+    }
+
+    fn parse(json: &str) -> Patch<String> {
+        serde_json::from_str::<Request>(json).unwrap().email
+    }
+
+    #[test]
+    fn test_patch_deserialize() {
+        assert_eq!(parse("{}"), Patch::Absent);
+        assert_eq!(parse(r#"{"email": null}"#), Patch::Null);
+        assert_eq!(parse(r#"{"email": "a@b.kz"}"#), Patch::Value("a@b.kz".to_string()));
+    }
+
+    #[test]
+    fn test_patch_apply() {
+        let current = Some("old".to_string());
+        assert_eq!(parse("{}").apply(current.clone()), current);
+        assert_eq!(parse(r#"{"email": null}"#).apply(current.clone()), None);
+        assert_eq!(
+            parse(r#"{"email": "new"}"#).apply(current),
+            Some("new".to_string())
+        );
+    }
+}
@@ -0,0 +1,100 @@
+/// Двузначные телефонные коды стран (ITU-T E.164). Однозначные — только 1 и 7,
+/// все остальные коды трёхзначные
+const TWO_DIGIT_COUNTRY_CODES: &[&str] = &[
+    "20", "27", "30", "31", "32", "33", "34", "36", "39", "40", "41", "43", "44", "45", "46", "47",
+    "48", "49", "51", "52", "53", "54", "55", "56", "57", "58", "60", "61", "62", "63", "64", "65",
+    "66", "81", "82", "84", "86", "90", "91", "92", "93", "94", "95", "98",
+];
+
+/// Номер телефона в формате E.164 с выделенным кодом страны
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoneNumber {
+    /// `+77001234567`
+    pub e164: String,
+    /// Код страны без `+`: `7`, `998`, `49`
+    pub country_code: String,
+}
+
+impl PhoneNumber {
+    /// Номер из Казахстана или России: зона `+7`
+    pub fn is_domestic(&self) -> bool {
+        self.country_code == "7"
+    }
+}
+
+/// Разобрать номер телефона. Номер с `+` или `00` считается международным,
+/// без них — казахстанским в привычных формах: `8 700 …`, `7 700 …`, `700 …`
+pub fn parse_phone(input: &str) -> Option<PhoneNumber> {
+    let input = input.trim();
+    let digits: String = input.chars().filter(|c| c.is_ascii_digit()).collect();
+
+    let digits = if input.starts_with('+') {
+        digits
+    } else if let Some(rest) = digits.strip_prefix("00") {
+        rest.to_string()
+    } else if digits.len() == 11 && (digits.starts_with('8') || digits.starts_with('7')) {
+        format!("7{}", &digits[1..])
+    } else if digits.len() == 10 {
+        format!("7{}", digits)
+    } else {
+        return None;
+    };
+
+    if !(8..=15).contains(&digits.len()) || digits.starts_with('0') {
+        return None;
+    }
+
+    let code_len = if digits.starts_with('1') || digits.starts_with('7') {
+        1
+    } else if TWO_DIGIT_COUNTRY_CODES.contains(&&digits[..2]) {
+        2
+    } else {
+        3
+    };
+    let (country_code, national) = digits.split_at(code_len);
+
+    // В зоне +7 номер всегда из десяти цифр
+    if country_code == "7" && national.len() != 10 {
+        return None;
+    }
+
+    Some(PhoneNumber {
+        e164: format!("+{}", digits),
+        country_code: country_code.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn e164(input: &str) -> Option<String> {
+        parse_phone(input).map(|phone| phone.e164)
+    }
+
+    #[test]
+    fn test_parse_kz_phone() {
+        assert_eq!(e164("+7 (700) 123-45-67"), Some("+77001234567".to_string()));
+        assert_eq!(e164("87001234567"), Some("+77001234567".to_string()));
+        assert_eq!(e164("77001234567"), Some("+77001234567".to_string()));
+        assert_eq!(e164("7001234567"), Some("+77001234567".to_string()));
+        assert_eq!(e164("+7700123456"), None);
+        assert_eq!(e164("700123"), None);
+        assert!(parse_phone("+77001234567").unwrap().is_domestic());
+    }
+
+    #[test]
+    fn test_parse_international_phone() {
+        let uz = parse_phone("+998 90 123 45 67").unwrap();
+        assert_eq!(uz.e164, "+998901234567");
+        assert_eq!(uz.country_code, "998");
+        assert!(!uz.is_domestic());
+
+        assert_eq!(parse_phone("0049 30 1234567").unwrap().country_code, "49");
+        assert_eq!(parse_phone("+1 415 555 0100").unwrap().country_code, "1");
+        // Японский номер не путается с казахстанским «8 …»
+        assert_eq!(e164("+81 3 1234 5678"), Some("+81312345678".to_string()));
+        assert_eq!(e164("+0123456789"), None);
+        assert_eq!(e164("+1234567890123456"), None);
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+
+/// Шаг повторения: дни или календарные месяцы
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceStep {
+    Days(u32),
+    Months(u32),
+}
+
+/// N-е повторение от даты начала (нулевое — сама дата начала).
+/// Месяцы считаются от начала, а не от прошлого повторения: 31 января → 28/29 февраля → 31 марта
+pub fn nth_occurrence(start: NaiveDate, step: RecurrenceStep, n: u32) -> Option<NaiveDate> {
+    match step {
+        RecurrenceStep::Days(days) => start.checked_add_days(Days::new(u64::from(days) * u64::from(n))),
+        RecurrenceStep::Months(months) => start.checked_add_months(Months::new(months.checked_mul(n)?)),
+    }
+}
+
+/// Первое повторение не раньше `from`
+pub fn next_occurrence(start: NaiveDate, step: RecurrenceStep, from: NaiveDate) -> Option<NaiveDate> {
+    if from <= start {
+        return Some(start);
+    }
+
+    let mut n = match step {
+        RecurrenceStep::Days(0) | RecurrenceStep::Months(0) => return None,
+        RecurrenceStep::Days(days) => {
+            let elapsed = (from - start).num_days() as u64;
+            u32::try_from(elapsed.div_ceil(u64::from(days))).ok()?
+        }
+        // Повторения раньше этого номера приходятся на более ранние месяцы
+        RecurrenceStep::Months(months) => {
+            let elapsed = (from.year() - start.year()) * 12 + from.month() as i32 - start.month() as i32;
+            elapsed as u32 / months
+        }
+    };
+
+    loop {
+        let date = nth_occurrence(start, step, n)?;
+        if date >= from {
+            return Some(date);
+        }
+        n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_nth_occurrence() {
+        let start = date(2024, 1, 31);
+        assert_eq!(nth_occurrence(start, RecurrenceStep::Months(1), 0), Some(start));
+        assert_eq!(nth_occurrence(start, RecurrenceStep::Months(1), 1), Some(date(2024, 2, 29)));
+        assert_eq!(nth_occurrence(start, RecurrenceStep::Months(1), 2), Some(date(2024, 3, 31)));
+        assert_eq!(nth_occurrence(start, RecurrenceStep::Days(14), 2), Some(date(2024, 2, 28)));
+    }
+
+    #[test]
+    fn test_next_occurrence() {
+        let start = date(2024, 3, 15);
+        let every_six_months = RecurrenceStep::Months(6);
+        assert_eq!(next_occurrence(start, every_six_months, date(2024, 1, 1)), Some(start));
+        assert_eq!(next_occurrence(start, every_six_months, start), Some(start));
+        assert_eq!(next_occurrence(start, every_six_months, date(2024, 3, 16)), Some(date(2024, 9, 15)));
+        assert_eq!(next_occurrence(start, every_six_months, date(2025, 9, 15)), Some(date(2025, 9, 15)));
+        assert_eq!(next_occurrence(start, RecurrenceStep::Days(7), date(2024, 3, 23)), Some(date(2024, 3, 29)));
+        assert_eq!(next_occurrence(start, RecurrenceStep::Days(0), date(2024, 3, 23)), None);
+    }
+}
@@ -0,0 +1,45 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+static PLACEHOLDER_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([a-zA-Z0-9_]+)\s*\}\}").unwrap());
+
+/// Подставить значения в плейсхолдеры вида `{{name}}`.
+/// Незаполненные плейсхолдеры остаются в тексте и возвращаются списком.
+pub fn render_template(text: &str, values: &HashMap<String, String>) -> (String, Vec<String>) {
+    let mut missing = Vec::new();
+
+    let rendered = PLACEHOLDER_REGEX.replace_all(text, |caps: &regex::Captures| {
+        let name = &caps[1];
+        match values.get(name) {
+            Some(value) => value.clone(),
+            None => {
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+                caps[0].to_string()
+            }
+        }
+    });
+
+    (rendered.into_owned(), missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let values = HashMap::from([("name".to_string(), "Алатау".to_string())]);
+
+        let (text, missing) = render_template("ЖК {{name}}, {{ date }} и {{date}}", &values);
+        assert_eq!(text, "ЖК Алатау, {{ date }} и {{date}}");
+        assert_eq!(missing, vec!["date".to_string()]);
+
+        let (text, missing) = render_template("Без плейсхолдеров", &values);
+        assert_eq!(text, "Без плейсхолдеров");
+        assert!(missing.is_empty());
+    }
+}
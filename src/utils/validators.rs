@@ -1,15 +1,16 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-static PHONE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\+7[0-9]{10}$").unwrap());
+use super::parse_phone;
 
 static EMAIL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}$").unwrap());
 
 static BIN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[0-9]{12}$").unwrap());
 
+/// Номер уже приведён к E.164
 pub fn validate_phone(phone: &str) -> bool {
-    PHONE_REGEX.is_match(phone)
+    parse_phone(phone).is_some_and(|parsed| parsed.e164 == phone)
 }
 
 pub fn validate_email(email: &str) -> bool {
@@ -24,6 +25,31 @@ pub fn sanitize_string(input: &str) -> String {
     input.trim().to_string()
 }
 
+/// Привести госномер к виду для сравнения: без пробелов и дефисов, латиница в верхнем регистре.
+/// Кириллические буквы, совпадающие по начертанию с латинскими, заменяются на латинские.
+pub fn normalize_vehicle_number(number: &str) -> String {
+    number
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .flat_map(char::to_uppercase)
+        .map(|c| match c {
+            'А' => 'A',
+            'В' => 'B',
+            'Е' => 'E',
+            'К' => 'K',
+            'М' => 'M',
+            'Н' => 'H',
+            'О' => 'O',
+            'Р' => 'P',
+            'С' => 'C',
+            'Т' => 'T',
+            'У' => 'Y',
+            'Х' => 'X',
+            c => c,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,6 +60,7 @@ mod tests {
         assert!(!validate_phone("87771234567"));
         assert!(!validate_phone("+7777123456"));
         assert!(!validate_phone("+777712345678"));
+        assert!(validate_phone("+998901234567"));
     }
 
     #[test]
@@ -50,4 +77,11 @@ mod tests {
         assert!(!validate_bin("12345678901"));
         assert!(!validate_bin("1234567890123"));
     }
+
+    #[test]
+    fn test_normalize_vehicle_number() {
+        assert_eq!(normalize_vehicle_number("123 abc-02"), "123ABC02");
+        assert_eq!(normalize_vehicle_number("777 ках 01"), "777KAX01");
+        assert_eq!(normalize_vehicle_number("777KAX01"), "777KAX01");
+    }
 }
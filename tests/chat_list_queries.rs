@@ -0,0 +1,193 @@
+//! Число запросов к базе при загрузке списка чатов не зависит от числа чатов.
+//!
+//! Нужна отдельная база в `TEST_DATABASE_URL`: тест применяет к ней миграции и
+//! считает запросы через метрики `query_metrics`. Без переменной тест пропускается.
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    middleware as axum_middleware, Router,
+};
+use localhood_backend::{
+    api,
+    build_info::MIGRATOR,
+    middleware::{auth_middleware, AppState},
+    models::User,
+    services::{query_metrics, AuthService},
+    Config,
+};
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use tower::ServiceExt;
+use tower_http::trace::TraceLayer;
+use tracing_subscriber::prelude::*;
+use uuid::Uuid;
+
+const ROUTE: &str = "/api/v1/chat";
+
+/// Сколько запросов к базе выполнено в рамках маршрута с момента запуска
+fn route_query_count() -> u64 {
+    query_metrics::render_metrics()
+        .lines()
+        .filter_map(|line| line.strip_prefix("db_query_duration_seconds_count{route=\""))
+        .filter_map(|rest| rest.split_once("\"} "))
+        .filter(|(route, _)| route.trim_end_matches('/') == ROUTE)
+        .map(|(_, count)| count.parse::<u64>().expect("Invalid metric value"))
+        .sum()
+}
+
+/// Чаты ЖК с сообщениями соседа и членством жителя
+async fn seed_chats(
+    pool: &PgPool,
+    complex_id: Uuid,
+    user_id: Uuid,
+    neighbour_id: Uuid,
+    count: usize,
+) {
+    for i in 0..count {
+        let (chat_id,): (Uuid,) = sqlx::query_as(
+            "INSERT INTO chats (complex_id, chat_type, name) VALUES ($1, 'complex', $2) RETURNING id",
+        )
+        .bind(complex_id)
+        .bind(format!("Чат {}", i))
+        .fetch_one(pool)
+        .await
+        .expect("Failed to seed chat");
+
+        sqlx::query("INSERT INTO chat_members (chat_id, user_id) VALUES ($1, $2), ($1, $3)")
+            .bind(chat_id)
+            .bind(user_id)
+            .bind(neighbour_id)
+            .execute(pool)
+            .await
+            .expect("Failed to seed chat members");
+
+        sqlx::query(
+            r#"
+            INSERT INTO chat_messages (chat_id, sender_id, content)
+            SELECT $1, $2, 'Сообщение ' || n FROM generate_series(1, 3) n
+            "#,
+        )
+        .bind(chat_id)
+        .bind(neighbour_id)
+        .execute(pool)
+        .await
+        .expect("Failed to seed chat messages");
+    }
+}
+
+/// Загрузить список чатов и вернуть число выполненных при этом запросов
+async fn list_chats(app: &Router, token: &str, expected: usize) -> u64 {
+    let before = route_query_count();
+
+    let request = Request::builder()
+        .uri(ROUTE)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .body(Body::empty())
+        .expect("Failed to build request");
+    let response = app.clone().oneshot(request).await.expect("Router failed");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("Failed to read body");
+    assert_eq!(status, StatusCode::OK, "{}", String::from_utf8_lossy(&body));
+
+    let chats: Vec<serde_json::Value> =
+        serde_json::from_slice(&body).expect("Invalid chat list response");
+    assert_eq!(chats.len(), expected);
+    assert!(chats.iter().all(|chat| chat["unread_count"] == 3));
+
+    route_query_count() - before
+}
+
+#[tokio::test]
+async fn chat_list_query_count_does_not_grow_with_chats() {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping chat list query count test");
+        return;
+    };
+
+    tracing_subscriber::registry()
+        .with(query_metrics::layer())
+        .init();
+
+    std::env::set_var("DATABASE_URL", &url);
+    std::env::set_var("JWT_SECRET", "chat-list-queries-test");
+    let config = Config::from_env().expect("Failed to load configuration");
+
+    // Одно соединение: sqlx догружает описания типов на каждом новом соединении,
+    // и эти запросы не должны попадать в сравнение
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    MIGRATOR.run(&pool).await.expect("Failed to run migrations");
+
+    let mut users = Vec::new();
+    for name in ["Житель", "Сосед"] {
+        let user: User = sqlx::query_as(
+            "INSERT INTO users (phone, first_name, role) VALUES ($1, $2, 'owner') RETURNING *",
+        )
+        .bind(format!(
+            "+7702{}",
+            &Uuid::new_v4().simple().to_string()[..7]
+        ))
+        .bind(name)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to seed user");
+        users.push(user);
+    }
+    let (user, neighbour) = (&users[0], &users[1]);
+
+    let (complex_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO complexes (city_id, name, created_by) VALUES ('almaty', $1, $2) RETURNING id",
+    )
+    .bind(format!("ЖК {}", user.phone))
+    .bind(user.id)
+    .fetch_one(&pool)
+    .await
+    .expect("Failed to seed complex");
+
+    sqlx::query(
+        "INSERT INTO apartments (complex_id, number, owner_id) VALUES ($1, '1', $2), ($1, '2', $3)",
+    )
+    .bind(complex_id)
+    .bind(user.id)
+    .bind(neighbour.id)
+    .execute(&pool)
+    .await
+    .expect("Failed to seed apartments");
+
+    let token = AuthService::new(config.clone())
+        .generate_access_token(user, None)
+        .expect("Failed to issue token");
+
+    let state = AppState {
+        pool: pool.clone(),
+        config,
+    };
+    let app = Router::new()
+        .nest("/api/v1", api::routes())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .layer(TraceLayer::new_for_http().make_span_with(query_metrics::request_span))
+        .with_state(state);
+
+    seed_chats(&pool, complex_id, user.id, neighbour.id, 1).await;
+    // Первый запрос прогревает кэши авторизации и не участвует в сравнении
+    list_chats(&app, &token, 1).await;
+    let single = list_chats(&app, &token, 1).await;
+
+    seed_chats(&pool, complex_id, user.id, neighbour.id, 24).await;
+    let many = list_chats(&app, &token, 25).await;
+
+    assert!(single > 0, "No queries recorded for {}", ROUTE);
+    assert_eq!(
+        single, many,
+        "Listing 25 chats took {} queries, listing one took {}",
+        many, single
+    );
+}
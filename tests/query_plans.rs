@@ -0,0 +1,154 @@
+//! Регрессионные тесты планов ключевых запросов.
+//!
+//! Нужна отдельная база в `TEST_DATABASE_URL`: тест применяет к ней миграции.
+//! Без переменной тесты пропускаются.
+
+use localhood_backend::build_info::MIGRATOR;
+use sqlx::{Connection, PgConnection};
+
+/// Поиск по подстроке: должен идти через триграммные индексы
+const SEARCH_QUERIES: &[(&str, &str)] = &[
+    (
+        "admin complexes search",
+        "SELECT * FROM complexes WHERE name ILIKE '%парк%'",
+    ),
+    (
+        "admin users search",
+        "SELECT * FROM users WHERE phone ILIKE '%7701%' OR first_name ILIKE '%7701%' OR last_name ILIKE '%7701%'",
+    ),
+    (
+        "address search",
+        "SELECT * FROM addresses WHERE street ILIKE '%абая%' OR building ILIKE '%абая%'",
+    ),
+    (
+        "marketplace search",
+        "SELECT * FROM marketplace_listings WHERE title ILIKE '%диван%' OR description ILIKE '%диван%'",
+    ),
+    (
+        "maintenance requests of complex",
+        "SELECT * FROM maintenance_requests WHERE complex_id = '00000000-0000-0000-0000-000000000001'",
+    ),
+];
+
+/// Истории и ленты: индекс должен отдавать строки уже в нужном порядке, без сортировки
+const HISTORY_QUERIES: &[(&str, &str)] = &[
+    (
+        "admin users list",
+        "SELECT * FROM users ORDER BY created_at DESC LIMIT 20",
+    ),
+    (
+        "admin complexes list",
+        "SELECT * FROM complexes ORDER BY created_at DESC LIMIT 20",
+    ),
+    (
+        "barrier history",
+        "SELECT * FROM barrier_access_logs WHERE complex_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY created_at DESC, id DESC LIMIT 20",
+    ),
+    (
+        "security events",
+        "SELECT * FROM security_events WHERE complex_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY created_at DESC, id DESC LIMIT 20",
+    ),
+    (
+        "marketplace feed",
+        "SELECT * FROM marketplace_listings
+         WHERE complex_id = '00000000-0000-0000-0000-000000000001' AND status = 'active'
+         ORDER BY created_at DESC LIMIT 20",
+    ),
+    (
+        "announcements feed",
+        "SELECT * FROM announcements
+         WHERE complex_id = '00000000-0000-0000-0000-000000000001' AND is_published = true
+         ORDER BY priority DESC, published_at DESC LIMIT 20",
+    ),
+    (
+        "cash payments",
+        "SELECT * FROM payments
+         WHERE osi_id = '00000000-0000-0000-0000-000000000001' AND method = 'cash'
+         ORDER BY created_at DESC LIMIT 20",
+    ),
+    (
+        "osi expenses",
+        "SELECT * FROM osi_expenses WHERE osi_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY expense_date DESC, created_at DESC LIMIT 20",
+    ),
+    (
+        "domain events",
+        "SELECT * FROM domain_events WHERE osi_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY created_at DESC LIMIT 20",
+    ),
+    (
+        "meter readings",
+        "SELECT * FROM meter_readings WHERE meter_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY reading_date DESC LIMIT 12",
+    ),
+    (
+        "notifications",
+        "SELECT * FROM notifications WHERE user_id = '00000000-0000-0000-0000-000000000001'
+         ORDER BY created_at DESC, id DESC LIMIT 20",
+    ),
+];
+
+async fn connect() -> Option<PgConnection> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping query plan tests");
+        return None;
+    };
+
+    let mut connection = PgConnection::connect(&url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    MIGRATOR
+        .run(&mut connection)
+        .await
+        .expect("Failed to run migrations");
+
+    // На пустых таблицах планировщику дешевле прочитать всё и отсортировать;
+    // с запретом seq scan и сортировка останутся в плане только там, где нет подходящего индекса
+    for setting in ["SET enable_seqscan = off", "SET enable_sort = off"] {
+        sqlx::query(setting)
+            .execute(&mut connection)
+            .await
+            .expect("Failed to adjust planner settings");
+    }
+
+    Some(connection)
+}
+
+async fn explain(connection: &mut PgConnection, sql: &str) -> String {
+    let rows: Vec<(String,)> = sqlx::query_as(&format!("EXPLAIN {}", sql))
+        .fetch_all(connection)
+        .await
+        .unwrap_or_else(|e| panic!("EXPLAIN failed for {}: {}", sql, e));
+
+    rows.into_iter()
+        .map(|(line,)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn search_queries_use_indexes() {
+    let Some(mut connection) = connect().await else {
+        return;
+    };
+
+    for (name, sql) in SEARCH_QUERIES {
+        let plan = explain(&mut connection, sql).await;
+        assert!(!plan.contains("Seq Scan"), "{} falls back to a seq scan:\n{}", name, plan);
+    }
+}
+
+#[tokio::test]
+async fn history_queries_read_index_order() {
+    let Some(mut connection) = connect().await else {
+        return;
+    };
+
+    for (name, sql) in HISTORY_QUERIES {
+        let plan = explain(&mut connection, sql).await;
+        assert!(!plan.contains("Seq Scan"), "{} falls back to a seq scan:\n{}", name, plan);
+        assert!(!plan.contains("Sort"), "{} sorts instead of reading an index:\n{}", name, plan);
+    }
+}
@@ -0,0 +1,623 @@
+//! Попытки доступа к данным чужого ЖК по всем модулям.
+//!
+//! Нужна отдельная база в `TEST_DATABASE_URL`: тест применяет к ней миграции
+//! и поднимает роутер с аудитом изоляции в режиме `enforce`, так что любая утечка
+//! в ответе превращается в 500. Без переменной тесты пропускаются.
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Method, Request, StatusCode},
+    middleware as axum_middleware,
+    Router,
+};
+use localhood_backend::{
+    api,
+    build_info::MIGRATOR,
+    middleware::{auth_middleware, tenant_audit_middleware, AppState},
+    models::User,
+    services::AuthService,
+    Config,
+};
+use sqlx::PgPool;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// ЖК со своими данными, жителем-владельцем квартиры и председателем ОСИ без квартиры
+struct Tenant {
+    complex_id: Uuid,
+    osi_id: Uuid,
+    announcement_id: Uuid,
+    voting_id: Uuid,
+    listing_id: Uuid,
+    bill_id: Uuid,
+    maintenance_id: Uuid,
+    chat_id: Uuid,
+    security_event_id: Uuid,
+    blacklist_entry_id: Uuid,
+    token: String,
+    chairman_token: String,
+}
+
+struct Harness {
+    app: Router,
+    own: Tenant,
+    foreign: Tenant,
+}
+
+async fn setup() -> Option<Harness> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping tenant isolation tests");
+        return None;
+    };
+
+    std::env::set_var("DATABASE_URL", &url);
+    std::env::set_var("JWT_SECRET", "tenant-isolation-test");
+    std::env::set_var("TENANT_AUDIT_MODE", "enforce");
+    let config = Config::from_env().expect("Failed to load configuration");
+
+    let pool = PgPool::connect(&url)
+        .await
+        .expect("Failed to connect to TEST_DATABASE_URL");
+    MIGRATOR.run(&pool).await.expect("Failed to run migrations");
+
+    let own = seed_tenant(&pool, &config).await;
+    let foreign = seed_tenant(&pool, &config).await;
+
+    let state = AppState { pool, config };
+    let app = Router::new()
+        .nest("/api/v1", api::routes())
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            tenant_audit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .with_state(state);
+
+    Some(Harness { app, own, foreign })
+}
+
+async fn seed_tenant(pool: &PgPool, config: &Config) -> Tenant {
+    let phone = format!("+7700{}", &Uuid::new_v4().simple().to_string()[..7]);
+
+    let user: User = sqlx::query_as(
+        "INSERT INTO users (phone, first_name, role) VALUES ($1, 'Житель', 'owner') RETURNING *",
+    )
+    .bind(&phone)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed user");
+
+    let (complex_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO complexes (city_id, name, created_by) VALUES ('almaty', $1, $2) RETURNING id",
+    )
+    .bind(format!("ЖК {}", phone))
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed complex");
+
+    // Глобальная роль председателя не должна давать прав в других ЖК
+    let chairman: User = sqlx::query_as(
+        "INSERT INTO users (phone, first_name, role) VALUES ($1, 'Председатель', 'chairman') RETURNING *",
+    )
+    .bind(format!("+7701{}", &Uuid::new_v4().simple().to_string()[..7]))
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed chairman");
+
+    let (osi_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO osi (complex_id, name, chairman_id) VALUES ($1, 'ОСИ', $2) RETURNING id",
+    )
+    .bind(complex_id)
+    .bind(chairman.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed OSI");
+
+    let (apartment_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO apartments (complex_id, number, owner_id) VALUES ($1, '1', $2) RETURNING id",
+    )
+    .bind(complex_id)
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed apartment");
+
+    let (announcement_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO announcements (complex_id, title, content, author_id)
+        VALUES ($1, 'Объявление', 'Текст', $2)
+        RETURNING id
+        "#,
+    )
+    .bind(complex_id)
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed announcement");
+
+    let (voting_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO votings (complex_id, osi_id, title, status, starts_at, ends_at, created_by)
+        VALUES ($1, $2, 'Голосование', 'active', NOW(), NOW() + INTERVAL '7 days', $3)
+        RETURNING id
+        "#,
+    )
+    .bind(complex_id)
+    .bind(osi_id)
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed voting");
+
+    let (listing_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO marketplace_listings (complex_id, seller_id, category_id, title, price)
+        SELECT $1, $2, id, 'Диван', 1000 FROM marketplace_categories LIMIT 1
+        RETURNING id
+        "#,
+    )
+    .bind(complex_id)
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed listing");
+
+    let (bill_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO bills (apartment_id, complex_id, period_start, period_end, amount, total_amount, due_date)
+        VALUES ($1, $2, '2026-01-01', '2026-01-31', 5000, 5000, '2026-02-10')
+        RETURNING id
+        "#,
+    )
+    .bind(apartment_id)
+    .bind(complex_id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed bill");
+
+    let (maintenance_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO maintenance_requests (complex_id, apartment_id, requester_id, category, title)
+        VALUES ($1, $2, $3, 'plumbing', 'Течёт кран')
+        RETURNING id
+        "#,
+    )
+    .bind(complex_id)
+    .bind(apartment_id)
+    .bind(user.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed maintenance request");
+
+    let (chat_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO chats (complex_id, chat_type, name) VALUES ($1, 'complex', 'Чат ЖК') RETURNING id",
+    )
+    .bind(complex_id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed chat");
+
+    sqlx::query("INSERT INTO chat_members (chat_id, user_id) VALUES ($1, $2)")
+        .bind(chat_id)
+        .bind(user.id)
+        .execute(pool)
+        .await
+        .expect("Failed to seed chat member");
+
+    let (security_event_id,): (Uuid,) = sqlx::query_as(
+        "INSERT INTO security_events (complex_id, event_type) VALUES ($1, 'pass_overuse') RETURNING id",
+    )
+    .bind(complex_id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed security event");
+
+    let (blacklist_entry_id,): (Uuid,) = sqlx::query_as(
+        r#"
+        INSERT INTO barrier_blacklist (complex_id, vehicle_number, reason, created_by)
+        VALUES ($1, 'A123BC', 'Нарушитель', $2)
+        RETURNING id
+        "#,
+    )
+    .bind(complex_id)
+    .bind(chairman.id)
+    .fetch_one(pool)
+    .await
+    .expect("Failed to seed blacklist entry");
+
+    let auth_service = AuthService::new(config.clone());
+    let token = auth_service
+        .generate_access_token(&user, None)
+        .expect("Failed to issue token");
+    let chairman_token = auth_service
+        .generate_access_token(&chairman, None)
+        .expect("Failed to issue chairman token");
+
+    Tenant {
+        complex_id,
+        osi_id,
+        announcement_id,
+        voting_id,
+        listing_id,
+        bill_id,
+        maintenance_id,
+        chat_id,
+        security_event_id,
+        blacklist_entry_id,
+        token,
+        chairman_token,
+    }
+}
+
+/// Статус и тело ответа на запрос от имени жителя
+async fn call(app: &Router, method: Method, uri: &str, token: &str) -> (StatusCode, String) {
+    call_with_body(app, method, uri, token, "{}").await
+}
+
+async fn call_with_body(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: &str,
+    body: &str,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", token))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("Failed to build request");
+
+    let response = app.clone().oneshot(request).await.expect("Router failed");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("Failed to read body");
+
+    (status, String::from_utf8_lossy(&body).into_owned())
+}
+
+/// Запрос к чужому ЖК должен быть отклонён, а не обслужен
+async fn assert_denied(harness: &Harness, method: Method, uri: &str) {
+    let (status, body) = call(&harness.app, method, uri, &harness.own.token).await;
+    assert!(
+        matches!(
+            status,
+            StatusCode::FORBIDDEN | StatusCode::NOT_FOUND | StatusCode::BAD_REQUEST
+        ),
+        "{} answered {} to a foreign tenant: {}",
+        uri,
+        status,
+        body
+    );
+}
+
+/// Председатель своего ЖК получает отказ в чужом, а не ошибку валидации после проверки прав
+async fn assert_chairman_denied(harness: &Harness, method: Method, uri: &str, body: &str) {
+    let (status, response) =
+        call_with_body(&harness.app, method, uri, &harness.own.chairman_token, body).await;
+    assert!(
+        matches!(status, StatusCode::FORBIDDEN | StatusCode::NOT_FOUND),
+        "{} answered {} to a foreign chairman: {}",
+        uri,
+        status,
+        response
+    );
+}
+
+/// Свой список доступен и не содержит ничего из чужого ЖК
+async fn assert_own_list_clean(harness: &Harness, uri: &str, foreign_id: Uuid) {
+    let (status, body) = call(&harness.app, Method::GET, uri, &harness.own.token).await;
+    assert_eq!(status, StatusCode::OK, "{} failed: {}", uri, body);
+    assert!(
+        !body.contains(&foreign_id.to_string()),
+        "{} leaked {} of a foreign complex: {}",
+        uri,
+        foreign_id,
+        body
+    );
+}
+
+#[tokio::test]
+async fn announcements_are_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/announcements", foreign.announcement_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/announcements?complex_id={}", foreign.complex_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/announcements/{}", foreign.announcement_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::DELETE,
+        &format!("/api/v1/announcements/{}", foreign.announcement_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn votings_are_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/votings", foreign.voting_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/votings?complex_id={}", foreign.complex_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/votings/{}", foreign.voting_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::POST,
+        &format!("/api/v1/votings/{}/close", foreign.voting_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn marketplace_is_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/marketplace/listings", foreign.listing_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/marketplace/listings?complex_id={}", foreign.complex_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/marketplace/listings/{}", foreign.listing_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn communal_bills_are_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/communal/bills", foreign.bill_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/communal/bills/{}", foreign.bill_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn maintenance_is_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/maintenance", foreign.maintenance_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/maintenance/{}", foreign.maintenance_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/maintenance/{}/comments", foreign.maintenance_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn chats_are_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    assert_own_list_clean(&harness, "/api/v1/chat", foreign.chat_id).await;
+    assert_denied(
+        &harness,
+        Method::GET,
+        &format!("/api/v1/chat/{}/messages", foreign.chat_id),
+    )
+    .await;
+    assert_denied(
+        &harness,
+        Method::POST,
+        &format!("/api/v1/chat/{}/read", foreign.chat_id),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn osi_finance_is_isolated() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let foreign = &harness.foreign;
+
+    for path in [
+        "workers",
+        "documents",
+        "expenses",
+        "budgets",
+        "shared-charges",
+        "finance-summary",
+        "reports/monthly?period=2026-01",
+    ] {
+        assert_denied(
+            &harness,
+            Method::GET,
+            &format!("/api/v1/osi/{}/{}", foreign.osi_id, path),
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn own_data_passes_audit() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let own = &harness.own;
+
+    for uri in [
+        format!("/api/v1/announcements/{}", own.announcement_id),
+        format!("/api/v1/votings/{}", own.voting_id),
+        format!("/api/v1/marketplace/listings/{}", own.listing_id),
+        format!("/api/v1/communal/bills/{}", own.bill_id),
+        format!("/api/v1/maintenance/{}", own.maintenance_id),
+    ] {
+        let (status, body) = call(&harness.app, Method::GET, &uri, &own.token).await;
+        assert_eq!(status, StatusCode::OK, "{} failed: {}", uri, body);
+    }
+}
+
+#[tokio::test]
+async fn chairman_is_limited_to_own_complex() {
+    let Some(harness) = setup().await else {
+        return;
+    };
+    let own = &harness.own;
+    let foreign = &harness.foreign;
+
+    let (status, body) = call(
+        &harness.app,
+        Method::GET,
+        &format!("/api/v1/complexes/{}/templates", own.complex_id),
+        &own.chairman_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "own templates failed: {}", body);
+
+    // Председатель без квартиры выбирает свой ЖК через ComplexScope
+    let (status, body) = call(
+        &harness.app,
+        Method::GET,
+        "/api/v1/security/blacklist",
+        &own.chairman_token,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "own blacklist failed: {}", body);
+    assert!(
+        !body.contains(&foreign.blacklist_entry_id.to_string()),
+        "own blacklist leaked a foreign entry: {}",
+        body
+    );
+
+    // Единственный ЖК председателя подставляется сам
+    let (status, body) = call_with_body(
+        &harness.app,
+        Method::POST,
+        "/api/v1/announcements",
+        &own.chairman_token,
+        r#"{"title": "Собрание", "content": "В субботу"}"#,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "own announcement failed: {}", body);
+
+    let foreign_announcement = format!(
+        r#"{{"complex_id": "{}", "title": "Собрание", "content": "В субботу"}}"#,
+        foreign.complex_id
+    );
+    let foreign_voting = format!(
+        r#"{{"complex_id": "{}", "title": "Ремонт", "options": ["За", "Против"], "starts_at": "2026-03-01T00:00:00Z", "ends_at": "2026-03-08T00:00:00Z"}}"#,
+        foreign.complex_id
+    );
+
+    for (method, uri, body) in [
+        (
+            Method::POST,
+            "/api/v1/announcements".to_string(),
+            foreign_announcement.as_str(),
+        ),
+        (
+            Method::POST,
+            "/api/v1/votings".to_string(),
+            foreign_voting.as_str(),
+        ),
+        (
+            Method::GET,
+            format!("/api/v1/complexes/{}/templates", foreign.complex_id),
+            "{}",
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/security/events/{}/review", foreign.security_event_id),
+            r#"{"lift_suspension": true}"#,
+        ),
+        (
+            Method::DELETE,
+            format!("/api/v1/security/blacklist/{}", foreign.blacklist_entry_id),
+            "{}",
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/votings/{}/activate", foreign.voting_id),
+            "{}",
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/votings/{}/close", foreign.voting_id),
+            "{}",
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/votings/{}/repeat", foreign.voting_id),
+            r#"{"quorum_percent": 30, "starts_at": "2026-03-01T00:00:00Z", "ends_at": "2026-03-08T00:00:00Z"}"#,
+        ),
+        (
+            Method::DELETE,
+            format!(
+                "/api/v1/announcements/{}/attachments/{}",
+                foreign.announcement_id,
+                Uuid::new_v4()
+            ),
+            "{}",
+        ),
+        (
+            Method::GET,
+            format!("/api/v1/maintenance/{}", foreign.maintenance_id),
+            "{}",
+        ),
+        (
+            Method::POST,
+            format!("/api/v1/maintenance/{}/comments", foreign.maintenance_id),
+            r#"{"content": "Комментарий"}"#,
+        ),
+    ] {
+        assert_chairman_denied(&harness, method, &uri, body).await;
+    }
+}